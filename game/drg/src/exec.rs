@@ -37,6 +37,7 @@ impl MainLoop {
       println!("all the currently available features:");
       println!("");
       println!("Exit and unload  - Delete");
+      println!("Take screenshot  - F12");
       println!("");
       println!("-------------------------------------------------");
       println!("");
@@ -55,8 +56,16 @@ impl MainLoop {
       // Poll input devices
       self.input.poll();
 
+      // Save screenshots next to the game
+      if self.input.screenshot.is_changed() == true && self.input.screenshot.is_active() == true {
+         match nusion_core::screenshot::save_to_directory("nusion-screenshots") {
+            Ok(path) => println!("Saved screenshot to {}", path.display()),
+            Err(err) => println!("Failed to save screenshot: {err}"),
+         }
+      }
+
       // Exit if we are supposed to
-      return Ok(self.input.key_press_exit == false);
+      return Ok(self.input.exit.is_active() == false);
    }
 }
 
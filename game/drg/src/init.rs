@@ -38,71 +38,70 @@ static ref LOOP_STATUS
 // Hook which executes the main loop.
 // We have to be careful to minimize
 // the chance of a race condition since
-// this is applied asynchronously.
-const LOOP_HOOK
-   : nusion_core::patch::writer::Hook<std::ops::Range<usize>>
-   = nusion_core::patch::writer::Hook{
-      memory_offset_range  : 0x8241BC..0x8241CD,
-      checksum             : nusion_core::patch::Checksum::from(0xF7946268),
-      hook                 : nusion_core::hook!("
-         // Store volatiles and align stack
-         push  rax
-
-         // Call the hook
-         call  {target}
-
-         // Restore stack and volatiles
-         pop   rax
-
-         // Execute stolen bytes and return
-         mov   [rsp+0x60],rax
-         mov   rcx,[rax]
-         mov   [rsp+0x68],rcx
-         mov   rcx,[rax+0x10]
-         ret
-         ", || {
-            // Don't block while waiting for the
-            // lock, this increases the chance of
-            // a race condition
-            let mut lock = match LOOP_STATUS.try_lock() {
-               Ok(lock) => lock,
-               Err(_)   => return,
-            };
-
-            // If we aren't supposed to execute,
-            // return early to prevent execution
-            // after we are supposed to exit
-            if lock.should_execute == false {
-               return;
-            }
-
-            // Execute the main loop, unwraping the
-            // return code for the loop status struct
-            let should_execute   : bool;
-            let err_code         : Option<Box<dyn std::error::Error + Send>>;
-            match lock.main_loop.as_mut().expect(
-               "Attempted to execute main loop before initialization, this is a bug!",
-            ).execute() {
-               Ok(state) => {
-                  should_execute = state;
-                  err_code       = None;
-               },
-               Err(err) => {
-                  should_execute = false;
-                  err_code       = Some(err);
-               },
-            }
-         
-            // Store the unwrapped error code
-            // in the mutex
-            lock.should_execute  = should_execute;
-            lock.err_code        = err_code;
-
-            // Return from the hook
+// this is applied asynchronously.  The
+// location comes from the offset database.
+const LOOP_HOOK_TARGET
+   : unsafe extern "C" fn()
+   = nusion_core::hook!("
+      // Store volatiles and align stack
+      push  rax
+
+      // Call the hook
+      call  {target}
+
+      // Restore stack and volatiles
+      pop   rax
+
+      // Execute stolen bytes and return
+      mov   [rsp+0x60],rax
+      mov   rcx,[rax]
+      mov   [rsp+0x68],rcx
+      mov   rcx,[rax+0x10]
+      ret
+      ", || {
+         nusion_core::log_once!("Main loop hook is running");
+
+         // Don't block while waiting for the
+         // lock, this increases the chance of
+         // a race condition
+         let mut lock = match LOOP_STATUS.try_lock() {
+            Ok(lock) => lock,
+            Err(_)   => return,
+         };
+
+         // If we aren't supposed to execute,
+         // return early to prevent execution
+         // after we are supposed to exit
+         if lock.should_execute == false {
             return;
          }
-      )
-   };
+
+         // Execute the main loop, unwraping the
+         // return code for the loop status struct
+         let should_execute   : bool;
+         let err_code         : Option<Box<dyn std::error::Error + Send>>;
+         match lock.main_loop.as_mut().expect(
+            "Attempted to execute main loop before initialization, this is a bug!",
+         ).execute() {
+            Ok(state) => {
+               should_execute = state;
+               err_code       = None;
+            },
+            Err(err) => {
+               should_execute = false;
+               err_code       = Some(err);
+            },
+         }
+      
+         // Store the unwrapped error code
+         // in the mutex
+         lock.should_execute  = should_execute;
+         lock.err_code        = err_code;
+
+         // Return from the hook
+         return;
+      }
+   );
 
 ///////////////////////
 // NUSION ENTRYPOINT //
@@ -112,18 +111,35 @@ const LOOP_HOOK
 /// on a separate thread to the main game thread, so
 /// we need synchronization in order to safely read
 /// and write between the main loop hook and this.
-#[nusion_core::main("FSD-Win64-Shipping.exe")]
+#[nusion_core::main(
+   processes("FSD-Win64-Shipping.exe"),
+   title = "Nusion for Deep Rock Galactic by Sinsig",
+   manual_ready = true,
+)]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-   // Initialization
-   nusion_core::env_mut!().console_mut().set_title(
-      "Nusion for Deep Rock Galactic by Sinsig",
-   )?;
+   // Load the offsets for the current game version
+   let offsets       = crate::offsets::load()?;
+   let game_offsets  = offsets.module(crate::offsets::GAME_MODULE)?;
 
    // Hooks the game's main loop to execute our
-   // main loop.  This currently has a race condition
-   // because we might be executing this bit of code
-   // while writing, but don't worry about it!
-   let hook_loop = unsafe{crate::game_mut!().patch_create(&LOOP_HOOK)}?;
+   // main loop.  The game thread is suspended
+   // while the hook is written so it can't
+   // execute a half-written instruction.
+   // Suspending threads is only supported
+   // on Windows.
+   let hook_loop_writer = nusion_core::patch::writer::Hook{
+      memory_offset_range  : game_offsets.offset_range("main_loop")?,
+      checksum             : game_offsets.checksum("main_loop")?,
+      hook                 : LOOP_HOOK_TARGET,
+   };
+   #[cfg(windows)]
+   let hook_loop = unsafe{crate::game_mut!().patch_create_atomic(&hook_loop_writer)}?;
+   #[cfg(not(windows))]
+   let hook_loop = unsafe{crate::game_mut!().patch_create(&hook_loop_writer)}?;
+
+   // Let the game run if it was started
+   // suspended by the launcher
+   nusion_core::inject::signal_ready()?;
 
    // Wait for us to either receive an Ok(false) or Err(_)
    // from the main loop
@@ -138,6 +154,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
          Err(_)   => continue,
       };
 
+      // Treat an unload request like the exit key
+      if nusion_core::is_unload_requested() == true {
+         lock.should_execute = false;
+      }
+
       // Unpack loop state
       let should_execute   = lock.should_execute;
       let err_code         = lock.err_code.take();
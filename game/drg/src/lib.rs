@@ -9,6 +9,7 @@
 mod exec;
 mod init;
 mod input;
+mod offsets;
 
 ////////////////////////////////
 /// GENERALLY USED UTILITIES ///
@@ -21,7 +22,7 @@ macro_rules! game {
    () => {
       nusion_core::env!()
          .modules()
-         .find_by_executable_file_name("FSD-Win64-Shipping.exe")
+         .get("FSD-Win64-Shipping.exe")
          .expect("Failed to find game module")
    }
 }
@@ -33,7 +34,7 @@ macro_rules! game_mut {
    () => {
       nusion_core::env_mut!()
          .modules_mut()
-         .find_mut_by_executable_file_name("FSD-Win64-Shipping.exe")
+         .get_mut("FSD-Win64-Shipping.exe")
          .expect("Failed to find game module")
    }
 }
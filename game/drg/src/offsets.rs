@@ -0,0 +1,39 @@
+//! Loading of the offset database
+//! so offsets can be updated for new
+//! game versions without touching the
+//! mod logic.
+
+////////////////////////////////////
+// GLOBAL VARIABLES AND CONSTANTS //
+////////////////////////////////////
+
+/// The name of the game module in
+/// the offset database.
+pub const GAME_MODULE : &'static str = "FSD-Win64-Shipping.exe";
+
+/// Offset pack loaded instead of the
+/// built-in one when it exists in the
+/// working directory.
+const OFFSETS_OVERRIDE_PATH : &'static str = "nusion-drg-offsets.txt";
+
+/// Built-in offset pack for the game
+/// version the mod was written for.
+const OFFSETS_DEFAULT : &'static str = include_str!("../offsets.txt");
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Loads the offset database, preferring
+/// a community offset pack in the working
+/// directory over the built-in one.
+pub fn load() -> nusion_core::offsets::Result<nusion_core::offsets::OffsetsDb> {
+   let path = std::path::Path::new(OFFSETS_OVERRIDE_PATH);
+
+   if path.is_file() == true {
+      println!("Loading offsets from {OFFSETS_OVERRIDE_PATH}");
+      return nusion_core::offsets::OffsetsDb::load(path);
+   }
+
+   return nusion_core::offsets::OffsetsDb::parse(OFFSETS_DEFAULT);
+}
@@ -0,0 +1,508 @@
+//! Alignment of data within a
+//! section of memory.
+//!
+//! Nothing in here may panic, since it
+//! runs while patching memory from inside
+//! game threads.  Errors are returned
+//! instead, and the lints below keep
+//! indexing from sneaking back in.
+
+#![deny(
+   clippy::indexing_slicing,
+   clippy::panic,
+   clippy::unwrap_used,
+   clippy::expect_used,
+)]
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Enum for representing alignment
+/// of data within a section of memory.
+#[derive(Debug)]
+pub enum Alignment {
+   Left,
+   LeftOffset{
+      elements : usize,
+   },
+   LeftByteOffset{
+      bytes    : usize,
+   },
+   Right,
+   RightOffset{
+      elements : usize,
+   },
+   RightByteOffset{
+      bytes    : usize,
+   },
+   Center,
+   CenterByte,
+   /// Places the data at the first address
+   /// in the buffer which is a multiple of
+   /// <code>bytes</code>, such as the start
+   /// of a 16-byte instruction fetch block.
+   Stride{
+      bytes    : usize,
+   },
+}
+
+/// What to write into leftover padding
+/// bytes which are too few to hold a whole
+/// padding value.  These are the bytes at
+/// the very start and end of the buffer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TailFill {
+   /// Returns an error if there are
+   /// any leftover bytes.
+   #[default]
+   Error,
+   /// Fills leftover bytes with a
+   /// single byte value.
+   Byte{
+      value    : u8,
+   },
+   /// Leaves leftover bytes as they were.
+   Unchanged,
+}
+
+/// An error relating to aligning
+/// data within a section of memory.
+#[derive(Debug)]
+pub enum AlignmentError {
+   LengthMismatch{
+      found       : usize,
+      expected    : usize,
+   },
+   OutOfRange{
+      maximum     : usize,
+      provided    : usize,
+   },
+   ResidualBytesDouble{
+      left        : usize,
+      right       : usize,
+   },
+   ZeroLengthType,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>AlignmentError</code>
+pub type Result<T> = core::result::Result<T, AlignmentError>;
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - AlignmentError //
+////////////////////////////////////////////
+
+impl core::fmt::Display for AlignmentError {
+   fn fmt(
+      & self,
+      stream : & mut core::fmt::Formatter<'_>,
+   ) -> core::fmt::Result {
+      return match self {
+         Self::LengthMismatch             {found, expected, }
+            => write!(stream, "Length mismatch: Found {found}, expected {expected}",),
+         Self::OutOfRange                 {maximum, provided}
+            => write!(stream, "Out of range: Maximum of {maximum} bytes, provided {provided} bytes"),
+         Self::ResidualBytesDouble        {left, right,     }
+            => write!(stream, "Residual bytes: {left} on left, {right} on right"),
+         Self::ZeroLengthType
+            => write!(stream, "Zero-length type"),
+      };
+   }
+}
+
+/////////////////////////
+// METHODS - Alignment //
+/////////////////////////
+
+impl Alignment {
+   /// Returns the amount of left
+   /// and right padding to insert
+   /// given a buffer byte count
+   /// and insert data byte count.
+   /// The returned tuple is the
+   /// amount of <b>elements</b>
+   /// to be inserted before and
+   /// after the source respectively.
+   /// If there are an uneven number
+   /// of bytes on either side or
+   /// a byte offset count too large
+   /// is passed in, an error is
+   /// returned.  Stride alignment is
+   /// measured from the start of the
+   /// buffer.  An error is also returned
+   /// if the size of <code>T</code> is zero.
+   pub fn padding_count<T>(
+      & self,
+      buffer_byte_count : usize,
+      insert_byte_count : usize,
+   ) -> Result<(usize, usize)> {
+      let element_byte_size = core::mem::size_of::<T>();
+      if element_byte_size == 0 {
+         return Err(AlignmentError::ZeroLengthType);
+      }
+
+      let (
+         bytes_left,
+         bytes_right,
+      ) = self.padding_bytes::<T>(
+         0,
+         buffer_byte_count,
+         insert_byte_count,
+      )?;
+
+      let residual_bytes_left    = bytes_left   % element_byte_size;
+      let residual_bytes_right   = bytes_right  % element_byte_size;
+      if residual_bytes_left != 0 || residual_bytes_right != 0 {
+         return Err(AlignmentError::ResidualBytesDouble{
+            left  : residual_bytes_left,
+            right : residual_bytes_right,
+         });
+      }
+
+      let elements_left    = bytes_left   / element_byte_size;
+      let elements_right   = bytes_right  / element_byte_size;
+
+      return Ok((elements_left, elements_right));
+   }
+
+   /// Returns the amount of left and
+   /// right padding in <b>bytes</b> for
+   /// a buffer starting at an address.
+   /// Element offsets are counted in
+   /// elements of type <code>T</code>,
+   /// but the padding doesn't need to
+   /// be a multiple of its size.
+   pub fn padding_bytes<T>(
+      & self,
+      buffer_address    : usize,
+      buffer_byte_count : usize,
+      insert_byte_count : usize,
+   ) -> Result<(usize, usize)> {
+      if buffer_byte_count < insert_byte_count {
+         return Err(AlignmentError::LengthMismatch{
+            found    : insert_byte_count,
+            expected : buffer_byte_count,
+         });
+      }
+
+      let element_byte_size   = core::mem::size_of::<T>();
+      let padding_byte_count  = buffer_byte_count - insert_byte_count;
+
+      let mut bytes_from_left    : Option<usize> = None;
+      let mut bytes_from_right   : Option<usize> = None;
+      match self {
+         Self::Left
+            => bytes_from_left   = Some(0),
+         Self::LeftOffset     {elements}
+            => bytes_from_left   = Some(elements.saturating_mul(element_byte_size)),
+         Self::LeftByteOffset {bytes}
+            => bytes_from_left   = Some(*bytes),
+         Self::Right
+            => bytes_from_right  = Some(0),
+         Self::RightOffset    {elements}
+            => bytes_from_right  = Some(elements.saturating_mul(element_byte_size)),
+         Self::RightByteOffset{bytes}
+            => bytes_from_right  = Some(*bytes),
+         Self::Center
+            => bytes_from_left   = Some(0),
+         Self::CenterByte
+            => bytes_from_right  = Some(buffer_byte_count / 2),
+         Self::Stride         {bytes}
+            => bytes_from_left   = Some(stride_offset(buffer_address, *bytes)),
+      }
+
+      let mut bytes_left   : usize = 0;
+      let mut bytes_right  : usize = 0;
+      if let Some(bytes_from_left)  = bytes_from_left {
+         if bytes_from_left > padding_byte_count {
+            return Err(AlignmentError::OutOfRange{
+               maximum  : padding_byte_count,
+               provided : bytes_from_left,
+            });
+         }
+         bytes_left  = bytes_from_left;
+         bytes_right = padding_byte_count - bytes_from_left;
+      }
+      if let Some(bytes_from_right) = bytes_from_right {
+         if bytes_from_right > padding_byte_count {
+            return Err(AlignmentError::OutOfRange{
+               maximum  : padding_byte_count,
+               provided : bytes_from_right,
+            });
+         }
+         bytes_left  = padding_byte_count - bytes_from_right;
+         bytes_right = bytes_from_right;
+      }
+
+      return Ok((bytes_left, bytes_right));
+   }
+
+   /// Fills a byte array with an
+   /// item surrounded by padding
+   /// values using the given
+   /// alignment.  Stride alignment is
+   /// measured from <code>buffer_address</code>,
+   /// which is where the buffer will be
+   /// stored and may differ from the
+   /// address of the buffer itself.
+   pub fn clone_from_item_with_padding<T, U>(
+      & self,
+      buffer         : & mut [u8],
+      buffer_address : usize,
+      item           : T,
+      value          : U,
+   ) -> Result<& Self>
+   where U: Clone,
+   {
+      return self.clone_from_item_with_split_padding(
+         buffer,
+         buffer_address,
+         item,
+         value.clone(),
+         value,
+         TailFill::Error,
+      );
+   }
+
+   /// Fills a byte array with a
+   /// slice type surrounded by
+   /// padding values using the
+   /// given alignment.  Stride alignment
+   /// is measured from <code>buffer_address</code>
+   /// like <code>clone_from_item_with_padding</code>.
+   pub fn clone_from_slice_with_padding<T, U>(
+      & self,
+      buffer         : & mut [u8],
+      buffer_address : usize,
+      slice          : & [T],
+      value          : U,
+   ) -> Result<& Self>
+   where T: Clone,
+         U: Clone,
+   {
+      return self.clone_from_slice_with_split_padding(
+         buffer,
+         buffer_address,
+         slice,
+         value.clone(),
+         value,
+         TailFill::Error,
+      );
+   }
+
+   /// Fills a byte array with an item
+   /// preceded by one padding value and
+   /// followed by another.  Whole padding
+   /// values are placed next to the item,
+   /// and any leftover bytes at either end
+   /// of the buffer are handled according
+   /// to <code>tail</code>.  Stride alignment
+   /// is measured from <code>buffer_address</code>
+   /// like <code>clone_from_item_with_padding</code>.
+   pub fn clone_from_item_with_split_padding<T, U, V>(
+      & self,
+      buffer         : & mut [u8],
+      buffer_address : usize,
+      item           : T,
+      left           : U,
+      right          : V,
+      tail           : TailFill,
+   ) -> Result<& Self>
+   where U: Clone,
+         V: Clone,
+   {
+      let size_of_t = core::mem::size_of::<T>();
+
+      let byte_start_item = self.fill_split_padding(
+         buffer,
+         buffer_address,
+         size_of_t,
+         left,
+         right,
+         tail,
+      )?;
+
+      // Copy item
+      let dest = subslice_mut(buffer, byte_start_item, size_of_t)?;
+      unsafe{core::ptr::write_unaligned(
+         dest.as_mut_ptr() as * mut T,
+         item,
+      )};
+
+      return Ok(self);
+   }
+
+   /// Fills a byte array with a slice
+   /// preceded by one padding value and
+   /// followed by another.  Whole padding
+   /// values are placed next to the slice,
+   /// and any leftover bytes at either end
+   /// of the buffer are handled according
+   /// to <code>tail</code>.  Stride alignment
+   /// is measured from <code>buffer_address</code>
+   /// like <code>clone_from_item_with_padding</code>.
+   pub fn clone_from_slice_with_split_padding<T, U, V>(
+      & self,
+      buffer         : & mut [u8],
+      buffer_address : usize,
+      slice          : & [T],
+      left           : U,
+      right          : V,
+      tail           : TailFill,
+   ) -> Result<& Self>
+   where T: Clone,
+         U: Clone,
+         V: Clone,
+   {
+      let size_of_t        = core::mem::size_of::<T>();
+      let slice_byte_count = slice.len().saturating_mul(size_of_t);
+
+      let byte_start_slice = self.fill_split_padding(
+         buffer,
+         buffer_address,
+         slice_byte_count,
+         left,
+         right,
+         tail,
+      )?;
+
+      // Copy slice
+      let dest = subslice_mut(buffer, byte_start_slice, slice_byte_count)?;
+      let dest = dest.as_mut_ptr() as * mut T;
+      for (index, element) in slice.iter().enumerate() {
+         unsafe{core::ptr::write_unaligned(dest.add(index), element.clone())};
+      }
+
+      return Ok(self);
+   }
+}
+
+//////////////////////////////////
+// INTERNAL HELPERS - Alignment //
+//////////////////////////////////
+
+impl Alignment {
+   /// Writes the padding on both sides
+   /// of the insert data, returning the
+   /// byte offset the data starts at.
+   fn fill_split_padding<U, V>(
+      & self,
+      buffer            : & mut [u8],
+      buffer_address    : usize,
+      insert_byte_count : usize,
+      left              : U,
+      right             : V,
+      tail              : TailFill,
+   ) -> Result<usize>
+   where U: Clone,
+         V: Clone,
+   {
+      let size_of_u = core::mem::size_of::<U>();
+      let size_of_v = core::mem::size_of::<V>();
+      if size_of_u == 0 || size_of_v == 0 {
+         return Err(AlignmentError::ZeroLengthType);
+      }
+
+      let (
+         bytes_left,
+         bytes_right,
+      ) = self.padding_bytes::<U>(
+         buffer_address,
+         buffer.len(),
+         insert_byte_count,
+      )?;
+
+      let residual_bytes_left    = bytes_left   % size_of_u;
+      let residual_bytes_right   = bytes_right  % size_of_v;
+      if tail == TailFill::Error && (residual_bytes_left != 0 || residual_bytes_right != 0) {
+         return Err(AlignmentError::ResidualBytesDouble{
+            left  : residual_bytes_left,
+            right : residual_bytes_right,
+         });
+      }
+
+      // Padding plus insert data always
+      // adds up to the buffer length
+      let byte_end_left    = bytes_left;
+      let byte_start_right = bytes_left + insert_byte_count;
+      let byte_end_right   = byte_start_right + bytes_right - residual_bytes_right;
+
+      // Fill leftover bytes at both ends
+      if let TailFill::Byte{value} = tail {
+         subslice_mut(buffer, 0, residual_bytes_left)?.fill(value);
+         subslice_mut(buffer, byte_end_right, residual_bytes_right)?.fill(value);
+      }
+
+      // Fill left padding
+      fill_unaligned(
+         subslice_mut(buffer, residual_bytes_left, byte_end_left - residual_bytes_left)?,
+         left,
+      );
+
+      // Fill right padding
+      fill_unaligned(
+         subslice_mut(buffer, byte_start_right, byte_end_right - byte_start_right)?,
+         right,
+      );
+
+      return Ok(byte_end_left);
+   }
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Alignment //
+///////////////////////////////////////
+
+impl Default for Alignment {
+   fn default() -> Self {
+      return Self::Center;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Returns the distance from an address
+/// to the next multiple of a stride.
+fn stride_offset(
+   address  : usize,
+   stride   : usize,
+) -> usize {
+   let stride = usize::max(stride, 1);
+   return (stride - address % stride) % stride;
+}
+
+/// Gets a mutable sub-slice of a buffer,
+/// returning an error instead of panicking
+/// if it doesn't fit.
+fn subslice_mut(
+   buffer   : & mut [u8],
+   start    : usize,
+   length   : usize,
+) -> Result<& mut [u8]> {
+   let buffer_length = buffer.len();
+   let end           = start.saturating_add(length);
+   return buffer.get_mut(start..end).ok_or(AlignmentError::OutOfRange{
+      maximum  : buffer_length,
+      provided : end,
+   });
+}
+
+/// Fills a byte buffer with whole copies
+/// of a value, which may be unaligned.
+/// The buffer length must be a multiple
+/// of the value's size.
+fn fill_unaligned<U>(
+   buffer   : & mut [u8],
+   value    : U,
+) where U: Clone,
+{
+   let count   = buffer.len().checked_div(core::mem::size_of::<U>()).unwrap_or(0);
+   let dest    = buffer.as_mut_ptr() as * mut U;
+   for index in 0..count {
+      unsafe{core::ptr::write_unaligned(dest.add(index), value.clone())};
+   }
+   return;
+}
@@ -0,0 +1,358 @@
+//! Checksums for storing and verifying
+//! patched byte data.
+
+use alloc::vec::Vec;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Struct for storing and verifying
+/// stored byte data for a patch.  A
+/// checksum may also record the number
+/// of bytes it was calculated from, in
+/// which case data of any other length
+/// never matches it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checksum {
+   value    : ChecksumValue,
+   length   : Option<usize>,
+}
+
+/// Hash algorithm used to calculate
+/// a checksum.  CRC-32 is fast but
+/// collides easily on short ranges,
+/// so longer hashes are available for
+/// patches where that matters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChecksumAlgorithm {
+   Crc32,
+   Crc64,
+   Fnv1a,
+   Sha256,
+}
+
+/// The hash value stored in a checksum
+/// for each supported algorithm.
+/// <code>Crc64</code> is CRC-64/XZ and
+/// <code>Fnv1a</code> is 64-bit FNV-1a.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChecksumValue {
+   Crc32(u32),
+   Crc64(u64),
+   Fnv1a(u64),
+   Sha256([u8; 32]),
+}
+
+////////////////////////
+// METHODS - Checksum //
+////////////////////////
+
+impl Checksum {
+   /// Creates a new CRC-32 checksum from
+   /// the provided byte data, recording
+   /// its length.
+   pub const fn new(
+      data  : & [u8],
+   ) -> Self {
+      return Self::compute(ChecksumAlgorithm::Crc32, data);
+   }
+
+   /// Creates a new checksum from the
+   /// provided byte data with any of the
+   /// supported algorithms, recording its
+   /// length.  This is a <code>const</code>
+   /// function, so checksums of known bytes
+   /// can be calculated at build time with
+   /// the <code>checksum!</code> macro.
+   pub const fn compute(
+      algorithm   : ChecksumAlgorithm,
+      data        : & [u8],
+   ) -> Self {
+      let value = match algorithm {
+         ChecksumAlgorithm::Crc32
+            => ChecksumValue::Crc32(crc::Crc::<u32>::new(&crc::CRC_32_CKSUM).checksum(data)),
+         ChecksumAlgorithm::Crc64
+            => ChecksumValue::Crc64(crc::Crc::<u64>::new(&crc::CRC_64_XZ).checksum(data)),
+         ChecksumAlgorithm::Fnv1a
+            => ChecksumValue::Fnv1a(fnv1a_64(data)),
+         ChecksumAlgorithm::Sha256
+            => ChecksumValue::Sha256(sha256(data)),
+      };
+
+      return Self{
+         value    : value,
+         length   : Some(data.len()),
+      };
+   }
+
+   /// Creates a CRC-32 checksum from an
+   /// existing checksum value.
+   pub const fn from(
+      checksum : u32,
+   ) -> Self {
+      return Self::from_value(ChecksumValue::Crc32(checksum));
+   }
+
+   /// Creates a checksum from an existing
+   /// value of any algorithm.
+   pub const fn from_value(
+      value : ChecksumValue,
+   ) -> Self {
+      return Self{
+         value    : value,
+         length   : None,
+      };
+   }
+
+   /// Binds the checksum to an expected
+   /// number of bytes.
+   ///
+   /// ```
+   /// const CHECKSUM : nusion_core::patch::Checksum
+   ///    = nusion_core::patch::Checksum::from(0xFC204AFD).with_length(16);
+   /// ```
+   pub const fn with_length(
+      mut self,
+      length : usize,
+   ) -> Self {
+      self.length = Some(length);
+      return self;
+   }
+
+   /// Gets the stored hash value.
+   pub const fn value(
+      & self,
+   ) -> ChecksumValue {
+      return self.value;
+   }
+
+   /// Gets the algorithm used to
+   /// calculate the checksum.
+   pub const fn algorithm(
+      & self,
+   ) -> ChecksumAlgorithm {
+      return self.value.algorithm();
+   }
+
+   /// Gets the expected number of
+   /// bytes, if it is bound.
+   pub const fn length(
+      & self,
+   ) -> Option<usize> {
+      return self.length;
+   }
+
+   /// Checks whether byte data matches
+   /// the checksum, including its length
+   /// if the length is bound.
+   pub fn matches(
+      & self,
+      data  : & [u8],
+   ) -> bool {
+      if let Some(length) = self.length {
+         if length != data.len() {
+            return false;
+         }
+      }
+
+      return Self::compute(self.algorithm(), data).value == self.value;
+   }
+}
+
+/////////////////////////////
+// METHODS - ChecksumValue //
+/////////////////////////////
+
+impl ChecksumValue {
+   /// Gets the algorithm the value
+   /// was calculated with.
+   pub const fn algorithm(
+      & self,
+   ) -> ChecksumAlgorithm {
+      return match self {
+         Self::Crc32(_)    => ChecksumAlgorithm::Crc32,
+         Self::Crc64(_)    => ChecksumAlgorithm::Crc64,
+         Self::Fnv1a(_)    => ChecksumAlgorithm::Fnv1a,
+         Self::Sha256(_)   => ChecksumAlgorithm::Sha256,
+      };
+   }
+
+   /// Gets the value as little-endian
+   /// bytes.
+   pub fn to_le_bytes(
+      & self,
+   ) -> Vec<u8> {
+      return match self {
+         Self::Crc32(value)   => value.to_le_bytes().to_vec(),
+         Self::Crc64(value)   => value.to_le_bytes().to_vec(),
+         Self::Fnv1a(value)   => value.to_le_bytes().to_vec(),
+         Self::Sha256(value)  => value.to_vec(),
+      };
+   }
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Checksum //
+//////////////////////////////////////
+
+impl core::fmt::Display for Checksum {
+   fn fmt(
+      & self,
+      stream : & mut core::fmt::Formatter<'_>,
+   ) -> core::fmt::Result {
+      write!(stream, "{}", self.value)?;
+      if let Some(length) = self.length {
+         write!(stream, " ({length} bytes)")?;
+      }
+      return Ok(());
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ChecksumValue //
+///////////////////////////////////////////
+
+impl core::fmt::Display for ChecksumValue {
+   fn fmt(
+      & self,
+      stream : & mut core::fmt::Formatter<'_>,
+   ) -> core::fmt::Result {
+      return match self {
+         Self::Crc32(value)
+            => write!(stream, "CRC-32 {value:#010X}"),
+         Self::Crc64(value)
+            => write!(stream, "CRC-64 {value:#018X}"),
+         Self::Fnv1a(value)
+            => write!(stream, "FNV-1a {value:#018X}"),
+         Self::Sha256(value) => {
+            write!(stream, "SHA-256 ")?;
+            for byte in value {
+               write!(stream, "{byte:02x}")?;
+            }
+            Ok(())
+         },
+      };
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// 64-bit FNV-1a hash.
+const fn fnv1a_64(
+   data  : & [u8],
+) -> u64 {
+   const OFFSET_BASIS   : u64 = 0xCBF29CE484222325;
+   const PRIME          : u64 = 0x00000100000001B3;
+
+   let mut hash   = OFFSET_BASIS;
+   let mut index  = 0;
+   while index < data.len() {
+      hash ^= data[index] as u64;
+      hash = hash.wrapping_mul(PRIME);
+      index += 1;
+   }
+
+   return hash;
+}
+
+/// SHA-256 hash, written as a
+/// <code>const</code> function so it
+/// can run at build time.
+const fn sha256(
+   data  : & [u8],
+) -> [u8; 32] {
+   const K : [u32; 64] = [
+      0x428A2F98, 0x71374491, 0xB5C0FBCF, 0xE9B5DBA5, 0x3956C25B, 0x59F111F1, 0x923F82A4, 0xAB1C5ED5,
+      0xD807AA98, 0x12835B01, 0x243185BE, 0x550C7DC3, 0x72BE5D74, 0x80DEB1FE, 0x9BDC06A7, 0xC19BF174,
+      0xE49B69C1, 0xEFBE4786, 0x0FC19DC6, 0x240CA1CC, 0x2DE92C6F, 0x4A7484AA, 0x5CB0A9DC, 0x76F988DA,
+      0x983E5152, 0xA831C66D, 0xB00327C8, 0xBF597FC7, 0xC6E00BF3, 0xD5A79147, 0x06CA6351, 0x14292967,
+      0x27B70A85, 0x2E1B2138, 0x4D2C6DFC, 0x53380D13, 0x650A7354, 0x766A0ABB, 0x81C2C92E, 0x92722C85,
+      0xA2BFE8A1, 0xA81A664B, 0xC24B8B70, 0xC76C51A3, 0xD192E819, 0xD6990624, 0xF40E3585, 0x106AA070,
+      0x19A4C116, 0x1E376C08, 0x2748774C, 0x34B0BCB5, 0x391C0CB3, 0x4ED8AA4A, 0x5B9CCA4F, 0x682E6FF3,
+      0x748F82EE, 0x78A5636F, 0x84C87814, 0x8CC70208, 0x90BEFFFA, 0xA4506CEB, 0xBEF9A3F7, 0xC67178F2,
+   ];
+
+   let mut state : [u32; 8] = [
+      0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+   ];
+
+   // The message is padded with a single
+   // set bit, zeros, and the bit length so
+   // it fills a whole number of blocks
+   let bit_length    = (data.len() as u64).wrapping_mul(8);
+   let block_count   = (data.len() + 9).div_ceil(64);
+
+   let mut block_index = 0;
+   while block_index < block_count {
+      let mut w = [0u32; 64];
+
+      let mut i = 0;
+      while i < 64 {
+         let position = block_index * 64 + i;
+         let byte = if position < data.len() {
+            data[position]
+         } else if position == data.len() {
+            0x80
+         } else if position >= block_count * 64 - 8 {
+            (bit_length >> ((block_count * 64 - 1 - position) * 8)) as u8
+         } else {
+            0x00
+         };
+
+         w[i / 4] |= (byte as u32) << ((3 - i % 4) * 8);
+         i += 1;
+      }
+
+      let mut i = 16;
+      while i < 64 {
+         let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+         let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+         w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+         i += 1;
+      }
+
+      let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+      let mut i = 0;
+      while i < 64 {
+         let s1      = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+         let choice  = (e & f) ^ ((!e) & g);
+         let temp1   = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(K[i]).wrapping_add(w[i]);
+         let s0      = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+         let major   = (a & b) ^ (a & c) ^ (b & c);
+         let temp2   = s0.wrapping_add(major);
+
+         h = g;
+         g = f;
+         f = e;
+         e = d.wrapping_add(temp1);
+         d = c;
+         c = b;
+         b = a;
+         a = temp1.wrapping_add(temp2);
+         i += 1;
+      }
+
+      state[0] = state[0].wrapping_add(a);
+      state[1] = state[1].wrapping_add(b);
+      state[2] = state[2].wrapping_add(c);
+      state[3] = state[3].wrapping_add(d);
+      state[4] = state[4].wrapping_add(e);
+      state[5] = state[5].wrapping_add(f);
+      state[6] = state[6].wrapping_add(g);
+      state[7] = state[7].wrapping_add(h);
+      block_index += 1;
+   }
+
+   let mut digest = [0u8; 32];
+   let mut i = 0;
+   while i < 32 {
+      digest[i] = (state[i / 4] >> ((3 - i % 4) * 8)) as u8;
+      i += 1;
+   }
+
+   return digest;
+}
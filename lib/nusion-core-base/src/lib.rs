@@ -0,0 +1,19 @@
+//! Crate root for nusion-core-base, a collection
+//! of OS-independent data structures used by
+//! nusion-core.
+//!
+//! This crate is <code>no_std</code> and only
+//! requires an allocator, so its types can be
+//! reused outside of a running process, such
+//! as in UEFI tooling or emulator plugins.
+//! Mods should use the re-exports found in
+//! nusion-core instead.
+
+#![no_std]
+
+extern crate alloc;
+
+// Public modules
+pub mod alignment;
+pub mod checksum;
+pub mod offset;
@@ -0,0 +1,206 @@
+//! Overflow-checked arithmetic on
+//! memory offsets and addresses.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An offset or address which can only
+/// be combined through checked arithmetic,
+/// such as when computing the address of
+/// an array element from a base, index,
+/// and stride.  The arithmetic operators
+/// return a <code>Result</code> instead of
+/// wrapping or panicking.
+///
+/// ```
+/// use nusion_core::patch::Offset;
+///
+/// let element = (Offset::new(base) + (Offset::new(index) * stride))?;
+/// let range   = element.to_range(core::mem::size_of::<u32>())?;
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Offset(usize);
+
+/// An error relating to offset
+/// arithmetic overflowing.
+#[derive(Debug)]
+pub enum OffsetError {
+   OutOfRange{
+      maximum     : usize,
+      provided    : usize,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>OffsetError</code>
+pub type Result<T> = core::result::Result<T, OffsetError>;
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - OffsetError //
+/////////////////////////////////////////
+
+impl core::fmt::Display for OffsetError {
+   fn fmt(
+      & self,
+      stream : & mut core::fmt::Formatter<'_>,
+   ) -> core::fmt::Result {
+      return match self {
+         Self::OutOfRange                 {maximum, provided}
+            => write!(stream, "Out of range: Maximum of {maximum} bytes, provided {provided} bytes"),
+      };
+   }
+}
+
+//////////////////////
+// METHODS - Offset //
+//////////////////////
+
+impl Offset {
+   /// Creates an offset from a
+   /// raw integer.
+   pub const fn new(
+      offset : usize,
+   ) -> Self {
+      return Self(offset);
+   }
+
+   /// Gets the offset as a raw
+   /// integer.
+   pub const fn get(
+      & self,
+   ) -> usize {
+      return self.0;
+   }
+
+   /// Adds to the offset, failing if
+   /// the result overflows.
+   pub fn checked_add(
+      & self,
+      rhs : usize,
+   ) -> Result<Self> {
+      return self.0.checked_add(rhs).map(Self).ok_or(OffsetError::OutOfRange{
+         maximum  : usize::MAX - self.0,
+         provided : rhs,
+      });
+   }
+
+   /// Subtracts from the offset,
+   /// failing if the result would
+   /// be negative.
+   pub fn checked_sub(
+      & self,
+      rhs : usize,
+   ) -> Result<Self> {
+      return self.0.checked_sub(rhs).map(Self).ok_or(OffsetError::OutOfRange{
+         maximum  : self.0,
+         provided : rhs,
+      });
+   }
+
+   /// Multiplies the offset, failing
+   /// if the result overflows.
+   pub fn checked_mul(
+      & self,
+      rhs : usize,
+   ) -> Result<Self> {
+      return self.0.checked_mul(rhs).map(Self).ok_or(OffsetError::OutOfRange{
+         maximum  : usize::MAX / usize::max(rhs, 1),
+         provided : self.0,
+      });
+   }
+
+   /// Creates the range of a given
+   /// length starting at the offset.
+   pub fn to_range(
+      & self,
+      length : usize,
+   ) -> Result<core::ops::Range<usize>> {
+      return Ok(self.0..self.checked_add(length)?.0);
+   }
+
+   /// Creates the range from the
+   /// offset up to another offset,
+   /// failing if the end is before
+   /// the start.
+   pub fn range_to(
+      & self,
+      end : Self,
+   ) -> Result<core::ops::Range<usize>> {
+      end.checked_sub(self.0)?;
+      return Ok(self.0..end.0);
+   }
+}
+
+////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Offset //
+////////////////////////////////////
+
+impl From<usize> for Offset {
+   fn from(
+      item : usize,
+   ) -> Self {
+      return Self(item);
+   }
+}
+
+impl From<Offset> for usize {
+   fn from(
+      item : Offset,
+   ) -> Self {
+      return item.0;
+   }
+}
+
+impl core::fmt::Display for Offset {
+   fn fmt(
+      & self,
+      stream : & mut core::fmt::Formatter<'_>,
+   ) -> core::fmt::Result {
+      return write!(stream, "{:#X}", self.0);
+   }
+}
+
+impl core::ops::Add<usize> for Offset {
+   type Output = Result<Self>;
+
+   fn add(
+      self,
+      rhs : usize,
+   ) -> Self::Output {
+      return self.checked_add(rhs);
+   }
+}
+
+impl core::ops::Add<Result<Offset>> for Offset {
+   type Output = Result<Self>;
+
+   fn add(
+      self,
+      rhs : Result<Offset>,
+   ) -> Self::Output {
+      return self.checked_add(rhs?.0);
+   }
+}
+
+impl core::ops::Sub<usize> for Offset {
+   type Output = Result<Self>;
+
+   fn sub(
+      self,
+      rhs : usize,
+   ) -> Self::Output {
+      return self.checked_sub(rhs);
+   }
+}
+
+impl core::ops::Mul<usize> for Offset {
+   type Output = Result<Self>;
+
+   fn mul(
+      self,
+      rhs : usize,
+   ) -> Self::Output {
+      return self.checked_mul(rhs);
+   }
+}
@@ -8,33 +8,69 @@ pub fn main(
    let info = syn::parse_macro_input!(item as EntrypointInfo);
 
    // Parse the process filter list
-   let allow_list = syn::parse_macro_input!(
-      attr as EntrypointProcessAllowList
-   ).list;
+   // and console options
+   let options = syn::parse_macro_input!(
+      attr as EntrypointOptions
+   );
 
    // Miscellaneous variables used to construct
    // the code for main.
-   let func    = &info.func;
-   let ident   = &func.sig.ident;
+   let func       = &info.func;
+   let ident      = &func.sig.ident;
+   let allow_list = &options.processes;
+   let console    = match &options.console {
+      Some(expr)  => console_mode(expr),
+      None        => quote::quote! {nusion_core::environment::ConsoleMode::Window},
+   };
+   let title      = match &options.title {
+      Some(title) => quote::quote! {::core::option::Option::Some(#title)},
+      None        => quote::quote! {::core::option::Option::None},
+   };
+   let minidump   = match &options.minidump {
+      Some(expr)  => quote::quote! {#expr},
+      None        => quote::quote! {false},
+   };
+   let manual_ready = match &options.manual_ready {
+      Some(expr)  => quote::quote! {#expr},
+      None        => quote::quote! {false},
+   };
 
    // Construct the syntax for the call
    // to the entrypoint
    return proc_macro::TokenStream::from(match info.variant {
       EntrypointReturnType::Void    => quote::quote! {
-         nusion_core::__private::build_entry!(#ident, void, #(#allow_list),*);
+         nusion_core::__private::build_entry!(#ident, void, #console, #title, #minidump, #manual_ready, #(#allow_list),*);
          #func
       },
       EntrypointReturnType::Static  => quote::quote! {
-         nusion_core::__private::build_entry!(#ident, result_static, #(#allow_list),*);
+         nusion_core::__private::build_entry!(#ident, result_static, #console, #title, #minidump, #manual_ready, #(#allow_list),*);
          #func
       },
       EntrypointReturnType::Dynamic => quote::quote! {
-         nusion_core::__private::build_entry!(#ident, result_dynamic, #(#allow_list),*);
+         nusion_core::__private::build_entry!(#ident, result_dynamic, #console, #title, #minidump, #manual_ready, #(#allow_list),*);
          #func
       },
    });
 }
 
+// Bare console mode names are shorthand
+// for the ConsoleMode variant, anything
+// else is converted with From, so bool
+// expressions keep working
+fn console_mode(
+   expr : & syn::Expr,
+) -> proc_macro2::TokenStream {
+   if let syn::Expr::Path(path) = expr {
+      if let Some(name) = path.path.get_ident() {
+         if name == "None" || name == "Window" || name == "Attach" {
+            return quote::quote! {nusion_core::environment::ConsoleMode::#name};
+         }
+      }
+   }
+
+   return quote::quote! {#expr};
+}
+
 struct EntrypointInfo {
    func     : syn::ItemFn,
    variant  : EntrypointReturnType,
@@ -396,33 +432,82 @@ impl syn::parse::Parse for EntrypointInfo {
    }
 }
 
-struct EntrypointProcessAllowList {
-   list  : Vec<syn::LitStr>,
+struct EntrypointOptions {
+   processes   : Vec<syn::LitStr>,
+   console     : Option<syn::Expr>,
+   title       : Option<syn::LitStr>,
+   minidump    : Option<syn::Expr>,
+   manual_ready: Option<syn::Expr>,
 }
 
-impl syn::parse::Parse for EntrypointProcessAllowList {
+impl syn::parse::Parse for EntrypointOptions {
    fn parse(
       input : syn::parse::ParseStream<'_>,
    ) -> syn::parse::Result<Self> {
-      let mut output = Vec::new();
+      let mut output = Self{
+         processes   : Vec::new(),
+         console     : None,
+         title       : None,
+         minidump    : None,
+         manual_ready: None,
+      };
 
       while input.is_empty() == false {
-         // Required - String literal for the process name
-         let proc = input.parse::<syn::LitStr>()?;
+         // A bare string literal is a process
+         // name, otherwise a named option
+         if input.peek(syn::LitStr) == true {
+            output.processes.push(input.parse::<syn::LitStr>()?);
+         } else {
+            let name = input.parse::<syn::Ident>()?;
+
+            if name == "processes" {
+               let list;
+               syn::parenthesized!(list in input);
+               let list = list.parse_terminated::<syn::LitStr, syn::Token![,]>(
+                  <syn::LitStr as syn::parse::Parse>::parse,
+               )?;
+               output.processes.extend(list);
+            } else if name == "console" {
+               input.parse::<syn::Token![=]>()?;
+               let expr = input.parse::<syn::Expr>()?;
+               if output.console.replace(expr).is_some() == true {
+                  proc_macro_error::emit_error!(name.span(), "console is specified more than once");
+               }
+            } else if name == "title" {
+               input.parse::<syn::Token![=]>()?;
+               let title = input.parse::<syn::LitStr>()?;
+               if output.title.replace(title).is_some() == true {
+                  proc_macro_error::emit_error!(name.span(), "title is specified more than once");
+               }
+            } else if name == "minidump" {
+               input.parse::<syn::Token![=]>()?;
+               let expr = input.parse::<syn::Expr>()?;
+               if output.minidump.replace(expr).is_some() == true {
+                  proc_macro_error::emit_error!(name.span(), "minidump is specified more than once");
+               }
+            } else if name == "manual_ready" {
+               input.parse::<syn::Token![=]>()?;
+               let expr = input.parse::<syn::Expr>()?;
+               if output.manual_ready.replace(expr).is_some() == true {
+                  proc_macro_error::emit_error!(name.span(), "manual_ready is specified more than once");
+               }
+            } else {
+               proc_macro_error::abort!(
+                  name.span(),
+                  "unknown option '{}', expected 'processes', 'console', 'title', 'minidump', or 'manual_ready'",
+                  name,
+               );
+            }
+         }
 
          // Required if not last element - comma separator
          if let Err(e) = input.parse::<syn::Token![,]>() {
             if input.is_empty() == false {
                return Err(e);
             }
-         } 
-
-         output.push(proc);
+         }
       }
 
-      return Ok(Self{
-         list : output
-      });
+      return Ok(output);
    }
 }
-
@@ -0,0 +1,102 @@
+/// Implementation of the supported_versions
+/// attribute macro.
+pub fn supported_versions(
+   attr  : proc_macro::TokenStream,
+   item  : proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+   // Parse the gated function
+   let func = syn::parse_macro_input!(item as syn::ItemFn);
+
+   // Parse the version pattern list
+   let patterns = syn::parse_macro_input!(
+      attr as SupportedVersionsPatternList
+   ).list;
+
+   if patterns.is_empty() == true {
+      proc_macro_error::abort_call_site!(
+         "at least one supported version pattern is required",
+      );
+   }
+
+   // Invalid patterns would silently never
+   // match, so catch them while compiling
+   for pattern in patterns.iter() {
+      if is_valid_pattern(&pattern.value()) == false {
+         proc_macro_error::emit_error!(
+            pattern.span(),
+            "version patterns should be up to 4 numbers separated by '.', optionally ending in '*'",
+         );
+      }
+   }
+
+   // Unpack the function so the check can
+   // be placed in front of its body
+   let attrs   = &func.attrs;
+   let vis     = &func.vis;
+   let sig     = &func.sig;
+   let block   = &func.block;
+   let feature = sig.ident.to_string();
+
+   return proc_macro::TokenStream::from(quote::quote!{
+      #(#attrs)*
+      #vis #sig {
+         static __NUSION_CORE_VERSION_GATE : nusion_core::versions::VersionGate
+            = nusion_core::versions::VersionGate::new(#feature, &[#(#patterns),*]);
+
+         if __NUSION_CORE_VERSION_GATE.is_supported() == false {
+            return <_ as nusion_core::versions::Unsupported>::unsupported(
+               &__NUSION_CORE_VERSION_GATE,
+            );
+         }
+
+         #block
+      }
+   });
+}
+
+struct SupportedVersionsPatternList {
+   pub list : Vec<syn::LitStr>,
+}
+
+/// Checks a version pattern the same
+/// way as <code>VersionPattern</code>
+/// parses it.
+fn is_valid_pattern(
+   pattern : & str,
+) -> bool {
+   let parts = pattern.split('.').collect::<Vec<&str>>();
+   if parts.len() > 4 {
+      return false;
+   }
+
+   for (index, part) in parts.iter().enumerate() {
+      // Wildcards are only allowed last
+      if *part == "*" {
+         if index != parts.len() - 1 {
+            return false;
+         }
+         continue;
+      }
+
+      if part.parse::<u16>().is_err() == true {
+         return false;
+      }
+   }
+
+   return true;
+}
+
+impl syn::parse::Parse for SupportedVersionsPatternList {
+   fn parse(
+      input : syn::parse::ParseStream<'_>,
+   ) -> syn::parse::Result<Self> {
+      let list = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(
+         input,
+      )?;
+
+      return Ok(Self{
+         list : list.into_iter().collect(),
+      });
+   }
+}
+
@@ -1,73 +1,43 @@
 // Implementation of the asm_bytes
 // function-like macro.
+//
+// The assembly is parsed and assembled while
+// the macro expands with the same assembler
+// nusion-core uses at runtime, so the macro
+// expands to a plain byte array, which can
+// be used anywhere a constant can.  Nothing
+// outside the macro is run, so it works on
+// any host and in sandboxed builds.
+
+use nusion_core_sys::assembler::{
+   Address,
+   Assembler,
+   Condition,
+   Label,
+   Operand,
+   Register,
+   Size,
+   Target,
+};
+
 pub fn asm_bytes(
    item  : proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-   // Parse input and generate UUID
-   let input   = syn::parse_macro_input!(item as AsmBytesInput);
-   let uuid    = input.generate_uuid();
-
-   // Build identifiers based on UUID
-   const IDENT_PREFIX : &'static str = "__nusion_core_asm_bytes";
-   let ident   = AsmBytesIdentifier{
-      asm_label_start   : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_asm_start", uuid,
-      ),
-      asm_label_end     : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_asm_end",   uuid,
-      ),
-      module            : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_module",    uuid,
+   // Parse input
+   let input = syn::parse_macro_input!(item as AsmBytesInput);
+
+   // Assemble the template
+   let bytes = match input.assemble() {
+      Ok(bytes)   => bytes,
+      Err(err)    => proc_macro_error::abort!(
+         input.asm_template.span(), "{}", err,
       ),
    };
 
-   // Parse the assembly template
-   let asm_template = input.parse_asm_template(&ident);
-
-   // Unpack variables for use in quote block
-   let asm_ident_start  = &ident.asm_label_start;
-   let asm_ident_end    = &ident.asm_label_end;
-   let module_ident     = &ident.module;
-
    return proc_macro::TokenStream::from(quote::quote!{
-      // Create scope to define ASM
       {
-         // Use the same module trick from
-         // hook!() to define the ASM
-         mod #module_ident {
-            // Import items from environment
-            use super::*;
-
-            // Assembly bytes code gen
-            core::arch::global_asm!(#asm_template);
-
-            // Declarations of function pointers
-            #[allow(non_snake_case)]
-            extern "C" {
-               fn #asm_ident_start();
-               fn #asm_ident_end();
-            }
-
-            // Byte pointers to the function pointers.
-            // We have to do it this way to force the
-            // compiler to lay the labels next to each
-            // other in memory.  Otherwise, it can re-order
-            // them into memory if they were static variables
-            // and break everything.
-            pub const ASM_START  : * const u8 = #asm_ident_start  as * const u8;
-            pub const ASM_END    : * const u8 = #asm_ident_end    as * const u8;
-         }
-
-         // Construct the byte slice from the
-         // created pointers.  This is the part
-         // which breaks 'const' on older versions
-         // of the standard library.
-         unsafe{std::slice::from_raw_parts(
-            #module_ident::ASM_START,
-            usize::try_from(#module_ident::ASM_END.offset_from(
-               #module_ident::ASM_START,
-            )).expect("ASM end pointer is before start pointer! This is a bug in the macro!"),
-         )}
+         const ASM_BYTES : &[u8] = &[#(#bytes),*];
+         ASM_BYTES
       }
    });
 }
@@ -76,53 +46,52 @@ struct AsmBytesInput {
    pub asm_template  : syn::LitStr,
 }
 
-struct AsmBytesIdentifier {
-   pub asm_label_start  : syn::Ident,
-   pub asm_label_end    : syn::Ident,
-   pub module           : syn::Ident,
+// Labels seen so far while assembling.
+// Numeric labels may be defined more than
+// once, and are referenced as the next
+// definition with an "f" suffix or the
+// previous one with a "b" suffix.
+#[derive(Default)]
+struct AsmBytesLabels {
+   named    : std::collections::HashMap<String, Label>,
+   forward  : std::collections::HashMap<String, Label>,
+   backward : std::collections::HashMap<String, Label>,
+   bound    : std::collections::HashSet<String>,
 }
 
 impl AsmBytesInput {
-   pub fn generate_uuid(
+   pub fn assemble(
       & self,
-   ) -> u64 {
-      use core::hash::{Hash, Hasher};
-
-      let mut uuid_hasher = hashers::fnv::FNV1aHasher64::default();
+   ) -> Result<Vec<u8>, String> {
+      // The code may be copied anywhere, so
+      // only relative offsets within it
+      // are ever encoded
+      let mut asm    = Assembler::new(0);
+      let mut labels = AsmBytesLabels::default();
 
-      // Takes into account the literal
-      // string itself and the position
-      // in the file (span) to minimize
-      // chance of generating duplicate
-      // UUIDs.
-      self.asm_template                .hash(& mut uuid_hasher);
-      self.asm_template.span().start() .hash(& mut uuid_hasher);
-      self.asm_template.span().end()   .hash(& mut uuid_hasher);
+      for (index, line) in self.asm_template.value().lines().enumerate() {
+         for statement in strip_comment(line).split(';') {
+            assemble_statement(&mut asm, &mut labels, statement.trim()).map_err(|err| {
+               format!("line {}: {err}: {}", index + 1, statement.trim())
+            })?;
+         }
+      }
 
-      return uuid_hasher.finish();
-   }
+      // Numeric labels referenced forward
+      // are never defined if they remain
+      let unbound = labels.named.keys()
+         .filter(|name| labels.bound.contains(*name) == false)
+         .cloned()
+         .chain(labels.forward.keys().map(|number| format!("{number}f")))
+         .collect::<Vec<String>>();
+      if unbound.is_empty() == false {
+         return Err(format!(
+            "label {} is outside the assembly, which breaks once the bytes are copied",
+            unbound.join(", "),
+         ));
+      }
 
-   pub fn parse_asm_template(
-      & self,
-      identifiers : & AsmBytesIdentifier,
-   ) -> syn::LitStr {
-      // All this basically does it append
-      // labels and rodata section
-      let user_assembly = self.asm_template.value();
-      let label_start   = &identifiers.asm_label_start;
-      let label_end     = &identifiers.asm_label_end;
-      let span          = self.asm_template.span();
-      
-
-      return syn::LitStr::new(&format!("
-         .section .rodata        // Mark as non-executable
-
-         {label_start}:          // Start label
-         {user_assembly}         // User's assembly code
-         {label_end}:            // End label
-
-         .section .text          // Restore text section
-      "), span);
+      return asm.finish().map_err(|err| err.to_string());
    }
 }
 
@@ -143,3 +112,359 @@ impl syn::parse::Parse for AsmBytesInput {
    }
 }
 
+impl AsmBytesLabels {
+   fn bind(
+      & mut self,
+      asm   : & mut Assembler,
+      name  : & str,
+   ) -> Result<(), String> {
+      let label = match is_numeric_label(name) {
+         true  => {
+            let label = self.forward.remove(name).unwrap_or_else(|| asm.new_label());
+            self.backward.insert(String::from(name), label);
+            label
+         },
+         false => {
+            if self.bound.insert(String::from(name)) == false {
+               return Err(format!("label {name} is defined more than once"));
+            }
+            *self.named.entry(String::from(name)).or_insert_with(|| asm.new_label())
+         },
+      };
+
+      asm.bind(label).map_err(|err| err.to_string())?;
+      return Ok(());
+   }
+
+   fn reference(
+      & mut self,
+      asm   : & mut Assembler,
+      name  : & str,
+   ) -> Result<Label, String> {
+      if let Some(number) = name.strip_suffix('f').filter(|number| is_numeric_label(number)) {
+         return Ok(*self.forward.entry(String::from(number)).or_insert_with(|| asm.new_label()));
+      }
+      if let Some(number) = name.strip_suffix('b').filter(|number| is_numeric_label(number)) {
+         return self.backward.get(number).copied().ok_or(format!(
+            "label {number} isn't defined before {name}",
+         ));
+      }
+      if is_identifier(name) == false {
+         return Err(format!("expected a label, found {name}"));
+      }
+
+      return Ok(*self.named.entry(String::from(name)).or_insert_with(|| asm.new_label()));
+   }
+}
+
+fn assemble_statement(
+   asm      : & mut Assembler,
+   labels   : & mut AsmBytesLabels,
+   text     : & str,
+) -> Result<(), String> {
+   // Any number of labels may come
+   // before the instruction
+   let mut text = text;
+   while let Some((name, rest)) = text.split_once(':') {
+      let name = name.trim();
+      if is_identifier(name) == false && is_numeric_label(name) == false {
+         break;
+      }
+
+      labels.bind(asm, name)?;
+      text = rest.trim();
+   }
+
+   if text.is_empty() == true {
+      return Ok(());
+   }
+
+   let (mnemonic, operands) = match text.split_once(char::is_whitespace) {
+      Some((mnemonic, operands)) => (mnemonic, operands.trim()),
+      None                       => (text, ""),
+   };
+   let mnemonic = mnemonic.to_ascii_lowercase();
+   let operands = match operands.is_empty() {
+      true  => Vec::new(),
+      false => operands.split(',').map(str::trim).collect::<Vec<&str>>(),
+   };
+
+   if let (Some(condition), [target]) = (parse_condition(&mnemonic), operands.as_slice()) {
+      let label = labels.reference(asm, target)?;
+      asm.jcc(condition, label).map_err(|err| err.to_string())?;
+      return Ok(());
+   }
+
+   let result = match (mnemonic.as_str(), operands.as_slice()) {
+      ("nop",   [])                   => asm.nop(),
+      ("ret",   [])                   => asm.ret(),
+      ("int3",  [])                   => asm.int3(),
+      ("leave", [])                   => asm.leave(),
+      ("push",  [operand])            => asm.push(parse_operand(operand, Some(Size::Qword))?),
+      ("pop",   [operand])            => asm.pop(parse_operand(operand, Some(Size::Qword))?),
+      ("lea",   [register, address])  => {
+         let register = match parse_operand(register, None)? {
+            Operand::Register{register, size : Size::Qword} => register,
+            _ => return Err(String::from("lea only supports a 64-bit destination register")),
+         };
+         let address = match parse_operand(address, Some(Size::Qword))? {
+            Operand::Memory{address, ..} => address,
+            _ => return Err(String::from("lea needs a memory operand")),
+         };
+         asm.lea(register, address)
+      },
+      ("jmp",   [target])             => {
+         let target = parse_target(asm, labels, target)?;
+         asm.jmp(target)
+      },
+      ("call",  [target])             => {
+         let target = parse_target(asm, labels, target)?;
+         asm.call(target)
+      },
+      (_,       [left, right])
+         if ["mov", "add", "or", "and", "sub", "xor", "cmp"].contains(&mnemonic.as_str()) == true
+         => {
+            let (left, right) = parse_operand_pair(left, right)?;
+            match mnemonic.as_str() {
+               "mov" => asm.mov(left, right),
+               "add" => asm.add(left, right),
+               "or"  => asm.or(left, right),
+               "and" => asm.and(left, right),
+               "sub" => asm.sub(left, right),
+               "xor" => asm.xor(left, right),
+               _     => asm.cmp(left, right),
+            }
+         },
+      _  => return Err(format!(
+         "unsupported instruction, only nop, ret, int3, leave, push, pop, lea, mov, add, or, and, \
+         sub, xor, cmp, jmp, call and conditional jumps with {} operands are supported",
+         operands.len(),
+      )),
+   };
+
+   result.map_err(|err| err.to_string())?;
+   return Ok(());
+}
+
+// Jumps and calls may only target labels
+// within the assembly or a register, since
+// anything else is relative to where the
+// bytes were assembled
+fn parse_target(
+   asm      : & mut Assembler,
+   labels   : & mut AsmBytesLabels,
+   text     : & str,
+) -> Result<Target, String> {
+   if let Some((register, Size::Qword)) = parse_register(text) {
+      return Ok(Target::from(register));
+   }
+
+   return Ok(Target::from(labels.reference(asm, text)?));
+}
+
+// Parses both operands of an instruction,
+// sizing memory by the register operand
+// unless it has an explicit size
+fn parse_operand_pair(
+   left  : & str,
+   right : & str,
+) -> Result<(Operand, Operand), String> {
+   let size_left  = parse_register(left).map(|(_, size)| size);
+   let size_right = parse_register(right).map(|(_, size)| size);
+   return Ok((parse_operand(left, size_right)?, parse_operand(right, size_left)?));
+}
+
+fn parse_operand(
+   text        : & str,
+   memory_size : Option<Size>,
+) -> Result<Operand, String> {
+   if let Some((register, size)) = parse_register(text) {
+      return Ok(Operand::Register{register, size});
+   }
+   if let Some(value) = parse_integer(text) {
+      return Ok(Operand::Immediate{value});
+   }
+
+   // Memory with an optional size,
+   // such as "dword ptr [rcx+0x10]"
+   let lower = text.to_ascii_lowercase();
+   let (size, address) = match lower.split_once('[') {
+      Some((prefix, address)) => (prefix.trim().trim_end_matches("ptr").trim().to_string(), address),
+      None                    => return Err(format!("invalid operand {text}")),
+   };
+   let size = match size.as_str() {
+      "byte"   => Size::Byte,
+      "word"   => Size::Word,
+      "dword"  => Size::Dword,
+      "qword"  => Size::Qword,
+      ""       => memory_size.ok_or(format!("the size of {text} is ambiguous, add byte, word, dword or qword ptr"))?,
+      _        => return Err(format!("invalid operand size in {text}")),
+   };
+   let address = address.strip_suffix(']').ok_or(format!("missing ] in {text}"))?;
+
+   return Ok(Operand::Memory{
+      address  : parse_address(address)?,
+      size     : size,
+   });
+}
+
+// Parses the inside of a memory operand,
+// which is a sum of a base register, an
+// index register with an optional scale,
+// and a displacement
+fn parse_address(
+   text : & str,
+) -> Result<Address, String> {
+   let mut base         = None;
+   let mut index        = None;
+   let mut displacement = 0i64;
+
+   let text = text.replace(' ', "").replace('-', "+-");
+   for term in text.split('+').filter(|term| term.is_empty() == false) {
+      if let Some(value) = parse_integer(term) {
+         displacement += value;
+         continue;
+      }
+
+      let (register, scale) = match term.split_once('*') {
+         Some((left, right)) => match parse_integer(right) {
+            Some(scale) => (left, Some(scale)),
+            None        => (right, parse_integer(left)),
+         },
+         None                => (term, None),
+      };
+      let register = match parse_register(register) {
+         Some((register, Size::Qword)) => register,
+         _ => return Err(format!("invalid address term {term}, only 64-bit registers and integers can be used")),
+      };
+
+      match (scale, base, index) {
+         (None, None, _)         => base  = Some(register),
+         (None, Some(_), None)   => index = Some((register, 1)),
+         (Some(scale), _, None)  => index = Some((register, u8::try_from(scale).map_err(|_| format!("invalid scale {scale}"))?)),
+         _                       => return Err(String::from("an address has at most a base and an index register")),
+      }
+   }
+
+   let displacement = i32::try_from(displacement).map_err(|_| {
+      format!("displacement {displacement:#X} doesn't fit in 32 bits")
+   })?;
+
+   return match (base, index) {
+      (Some(base), None)
+         => Ok(Address::Base{base, displacement}),
+      (Some(base), Some((index, scale)))
+         => Ok(Address::Indexed{base, index, scale, displacement}),
+      _  => Err(String::from("an address needs a base register, since anything else is an absolute address")),
+   };
+}
+
+fn parse_integer(
+   text : & str,
+) -> Option<i64> {
+   let (negative, digits) = match text.strip_prefix('-') {
+      Some(digits)   => (true, digits.trim()),
+      None           => (false, text),
+   };
+
+   let value = if let Some(hex) = digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+      u64::from_str_radix(hex, 16).ok()?
+   } else if let Some(binary) = digits.strip_prefix("0b").or(digits.strip_prefix("0B")) {
+      u64::from_str_radix(binary, 2).ok()?
+   } else {
+      digits.parse::<u64>().ok()?
+   };
+   let value = value as i64;
+
+   return Some(match negative {
+      true  => value.wrapping_neg(),
+      false => value,
+   });
+}
+
+fn parse_condition(
+   mnemonic : & str,
+) -> Option<Condition> {
+   return Some(match mnemonic {
+      "jo"                    => Condition::Overflow,
+      "jno"                   => Condition::NotOverflow,
+      "jb" | "jc" | "jnae"    => Condition::Below,
+      "jae" | "jnc" | "jnb"   => Condition::AboveEqual,
+      "je" | "jz"             => Condition::Equal,
+      "jne" | "jnz"           => Condition::NotEqual,
+      "jbe" | "jna"           => Condition::BelowEqual,
+      "ja" | "jnbe"           => Condition::Above,
+      "js"                    => Condition::Sign,
+      "jns"                   => Condition::NotSign,
+      "jp" | "jpe"            => Condition::Parity,
+      "jnp" | "jpo"           => Condition::NotParity,
+      "jl" | "jnge"           => Condition::Less,
+      "jge" | "jnl"           => Condition::GreaterEqual,
+      "jle" | "jng"           => Condition::LessEqual,
+      "jg" | "jnle"           => Condition::Greater,
+      _                       => return None,
+   });
+}
+
+// Register names for each size, in the
+// same order as the register numbers
+const REGISTER_NAMES : [[&str; 4]; 16] = [
+   ["al",   "ax",   "eax",  "rax"],
+   ["cl",   "cx",   "ecx",  "rcx"],
+   ["dl",   "dx",   "edx",  "rdx"],
+   ["bl",   "bx",   "ebx",  "rbx"],
+   ["spl",  "sp",   "esp",  "rsp"],
+   ["bpl",  "bp",   "ebp",  "rbp"],
+   ["sil",  "si",   "esi",  "rsi"],
+   ["dil",  "di",   "edi",  "rdi"],
+   ["r8b",  "r8w",  "r8d",  "r8"],
+   ["r9b",  "r9w",  "r9d",  "r9"],
+   ["r10b", "r10w", "r10d", "r10"],
+   ["r11b", "r11w", "r11d", "r11"],
+   ["r12b", "r12w", "r12d", "r12"],
+   ["r13b", "r13w", "r13d", "r13"],
+   ["r14b", "r14w", "r14d", "r14"],
+   ["r15b", "r15w", "r15d", "r15"],
+];
+
+const REGISTERS : [Register; 16] = [
+   Register::Rax, Register::Rcx, Register::Rdx, Register::Rbx,
+   Register::Rsp, Register::Rbp, Register::Rsi, Register::Rdi,
+   Register::R8,  Register::R9,  Register::R10, Register::R11,
+   Register::R12, Register::R13, Register::R14, Register::R15,
+];
+
+const SIZES : [Size; 4] = [Size::Byte, Size::Word, Size::Dword, Size::Qword];
+
+fn parse_register(
+   text : & str,
+) -> Option<(Register, Size)> {
+   let text = text.trim().to_ascii_lowercase();
+   for (register, names) in REGISTERS.iter().zip(REGISTER_NAMES.iter()) {
+      if let Some(size) = names.iter().position(|name| *name == text) {
+         return Some((*register, SIZES[size]));
+      }
+   }
+
+   return None;
+}
+
+fn strip_comment(
+   line : & str,
+) -> & str {
+   let line = line.split("//").next().unwrap_or("");
+   return line.split('#').next().unwrap_or("");
+}
+
+fn is_identifier(
+   text : & str,
+) -> bool {
+   return text.starts_with(|c : char| c.is_ascii_alphabetic() == true || c == '_' || c == '.') == true
+      && text.chars().all(|c| c.is_ascii_alphanumeric() == true || c == '_' || c == '.') == true;
+}
+
+fn is_numeric_label(
+   text : & str,
+) -> bool {
+   return text.is_empty() == false && text.chars().all(|c| c.is_ascii_digit() == true);
+}
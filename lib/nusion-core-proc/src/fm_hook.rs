@@ -4,28 +4,75 @@ pub fn hook(
    item  : proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
    // Parse input item as a string literal and closure
-   let input = syn::parse_macro_input!(item as HookInput);
+   let source  = item.to_string();
+   let input   = syn::parse_macro_input!(item as HookInput);
+
+   // Verify there the move keyword wasn't used
+   if let Some(mv) = &input.closure.capture {
+      let span = mv.span;
+      proc_macro_error::abort!(span,
+         "closure may not take ownership of environment variables, use hook_with_context! instead",
+      );
+   }
+
+   return expand(input, &source, false);
+}
+
+/// Implementation of the hook_with_context
+/// function-like macro.
+pub fn hook_with_context(
+   item  : proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+   // Parse input item as a string literal and closure
+   let source  = item.to_string();
+   let input   = syn::parse_macro_input!(item as HookInput);
+
+   // The closure is called with the
+   // arguments by name
+   for pat in &input.closure.inputs {
+      if let syn::Pat::Type(ty) = pat {
+         if let syn::Pat::Ident(_) = &*ty.pat {
+         } else {
+            proc_macro_error::abort!(ty.colon_token.spans[0],
+               "closure arguments must be plain names for hook_with_context!",
+            );
+         }
+      }
+   }
 
-   // Generate input UUID
-   let uuid = input.generate_uuid(); 
+   return expand(input, &source, true);
+}
+
+fn expand(
+   input    : HookInput,
+   source   : & str,
+   context  : bool,
+) -> proc_macro::TokenStream {
+   // Generate the symbol for this expansion
+   let symbol = crate::symbol::generate("__nusion_core_hook", source);
+   let base   = &symbol.base;
 
    // Generate identifiers for the private
    // module, ASM trampoline, and closure
-   const IDENT_PREFIX : &'static str = "__nusion_core_hook";
    let ident = HookIdentifier{
-      module      : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_module",       uuid,
-      ),
-      trampoline  : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_trampoline",   uuid,
-      ),
-      closure     : quote::format_ident!(
-         "{IDENT_PREFIX}_{:X}_closure",      uuid,
-      ),
+      module      : quote::format_ident!("{base}_module"),
+      trampoline  : quote::format_ident!("{base}_trampoline"),
+      closure     : quote::format_ident!("{base}_closure"),
+      enter       : quote::format_ident!("{base}_enter"),
+      end         : quote::format_ident!("{base}_end"),
    };
 
-   // Parse the assembly template
-   let asm_template = input.parse_asm_template(&ident);
+   // Parse the assembly template once for
+   // each calling convention, since argument
+   // registers differ between them
+   let (
+      asm_template_windows,
+      uses_closure,
+   ) = input.parse_asm_template(&ident, HookAbi::Windows, context);
+   let (
+      asm_template_system_v,
+      _,
+   ) = input.parse_asm_template(&ident, HookAbi::SystemV, context);
   
    // Unpack various variables for use in the quote invocation
    let module_ident        = &ident.module;
@@ -34,6 +81,117 @@ pub fn hook(
    let closure_input       = &input.closure.inputs;
    let closure_output      = &input.closure.output;
    let closure_body        = &input.closure.body;
+   let symbol_origin       = &symbol.origin;
+
+   // Forward to the bound function
+   // instead of the closure body while
+   // one is bound, if a binding was given
+   let closure_binding = match &input.binding {
+      Some(binding)  => {
+         let arguments = input.argument_names();
+         quote::quote!{
+            if let Some(bound) = (#binding).get() {
+               return bound(#(#arguments),*);
+            }
+         }
+      },
+      None           => quote::quote!{},
+   };
+
+   // Refer to the closure through a symbol
+   // operand instead of by name, so it keeps
+   // its mangled name and isn't exported.
+   // Unused operands are rejected, so only
+   // pass it when the template calls it.
+   let closure_operand = match uses_closure {
+      true  => quote::quote!{, target = sym #closure_ident},
+      false => quote::quote!{},
+   };
+
+   // Skip the closure body while the
+   // enable switch is off, if one was given
+   let closure_guard = match &input.enabled {
+      Some(enabled)  => quote::quote!{
+         if (#enabled).is_enabled() == false {
+            return ::core::default::Default::default();
+         }
+      },
+      None           => quote::quote!{},
+   };
+
+   // Skip the closure body on threads the
+   // thread filter doesn't allow, if one was given
+   let closure_thread_guard = match &input.threads {
+      Some(threads)  => quote::quote!{
+         if (#threads).allows_current() == false {
+            return ::core::default::Default::default();
+         }
+      },
+      None           => quote::quote!{},
+   };
+
+   // A context hook's template is copied for
+   // each context, and the copy passes the
+   // context's closure to the function in the
+   // argument register after the closure's own
+   if context == true {
+      let end_ident        = &ident.end;
+      let closure_inputs   = input.closure.inputs.iter();
+      let arguments        = input.argument_names();
+      let argument_types   = input.argument_types();
+      let closure          = &input.closure;
+      let closure_return   = match closure_output {
+         syn::ReturnType::Default   => quote::quote!{()},
+         syn::ReturnType::Type(_, ty)  => quote::quote!{#ty},
+      };
+
+      return proc_macro::TokenStream::from(quote::quote!{
+         {
+            #[doc = #symbol_origin]
+            mod #module_ident {
+               use super::*;
+
+               #[cfg(target_os = "windows")]
+               core::arch::global_asm!(#asm_template_windows);
+               #[cfg(not(target_os = "windows"))]
+               core::arch::global_asm!(#asm_template_system_v);
+
+               #[allow(non_snake_case)]
+               extern "C" {
+                  pub fn #asm_template_ident();
+                  pub fn #end_ident();
+               }
+
+               #[allow(non_snake_case)]
+               #[allow(dead_code)]
+               pub extern "C" fn #closure_ident(
+                  #(#closure_inputs,)*
+                  __nusion_context : * const ::core::ffi::c_void,
+               ) #closure_output {
+                  #closure_guard
+                  #closure_thread_guard
+                  #closure_binding
+
+                  // The copy keeps the context alive
+                  // until every call has returned
+                  let closure = unsafe{&*(__nusion_context as * const ::std::boxed::Box<
+                     dyn Fn(#(#argument_types),*) -> #closure_return + Send + Sync
+                  >)};
+                  return closure(#(#arguments),*);
+               }
+            }
+
+            unsafe{nusion_core::hook::HookContext::install(
+               ::std::boxed::Box::new(#closure) as ::std::boxed::Box<
+                  dyn Fn(#(#argument_types),*) -> #closure_return + Send + Sync
+               >,
+               #module_ident::#asm_template_ident,
+               #module_ident::#end_ident,
+               #module_ident::#closure_ident as usize,
+            )}
+         }
+      });
+   }
 
    // Finally, generate the Rust code for the hook
    return proc_macro::TokenStream::from(quote::quote!{
@@ -43,12 +201,16 @@ pub fn hook(
          // functions in.  This is an easy way
          // to fudge our way around issues with
          // global_asm!() being used in a statement
+         #[doc = #symbol_origin]
          mod #module_ident {
             // Import items from environment
             use super::*;
 
             // Assembly trampoline code gen
-            core::arch::global_asm!(#asm_template);
+            #[cfg(target_os = "windows")]
+            core::arch::global_asm!(#asm_template_windows #closure_operand);
+            #[cfg(not(target_os = "windows"))]
+            core::arch::global_asm!(#asm_template_system_v #closure_operand);
    
             // Declaration of the assembly function
             #[allow(non_snake_case)]
@@ -57,11 +219,14 @@ pub fn hook(
             }
 
             // Construct a function from the closure
-            #[no_mangle]
             #[allow(non_snake_case)]
+            #[allow(dead_code)]
             pub extern "C" fn #closure_ident(
                #closure_input
             ) #closure_output {
+               #closure_guard
+               #closure_thread_guard
+               #closure_binding
                #closure_body
             }
          }
@@ -76,50 +241,75 @@ struct HookIdentifier {
    pub module     : syn::Ident,
    pub trampoline : syn::Ident,
    pub closure    : syn::Ident,
+   pub enter      : syn::Ident,
+   pub end        : syn::Ident,
 }
 
 struct HookInput {
    pub asm_template  : syn::LitStr,
    pub closure       : syn::ExprClosure,
+   pub enabled       : Option<syn::Expr>,
+   pub binding       : Option<syn::Expr>,
+   pub threads       : Option<syn::Expr>,
 }
 
 impl HookInput {
-   pub fn generate_uuid(
-      & self
-   ) -> u64 {
-      use core::hash::{Hash, Hasher};
-
-      let mut uuid_hasher = hashers::fnv::FNV1aHasher64::default();
-
-      // In order to have the lowest possible
-      // chance of generating duplicate hashes,
-      // we take into account the ASM string
-      // literal, closure content, file position
-      // of literal, and file position of closure.
-      self.asm_template                      .hash(& mut uuid_hasher);
-      self.closure                           .hash(& mut uuid_hasher);
-      self.asm_template.span().start()       .hash(& mut uuid_hasher);
-      self.asm_template.span().end()         .hash(& mut uuid_hasher);
-      self.closure.or1_token.spans[0].start().hash(& mut uuid_hasher);
-      self.closure.or2_token.spans[0].start().hash(& mut uuid_hasher);
-
-      return uuid_hasher.finish();
+   pub fn argument_names(
+      & self,
+   ) -> Vec<syn::Ident> {
+      // Already verified every argument is
+      // a typed identifier while parsing
+      return self.closure.inputs.iter().filter_map(|pat| match pat {
+         syn::Pat::Type(ty) => match &*ty.pat {
+            syn::Pat::Ident(id)  => Some(id.ident.clone()),
+            _                    => None,
+         },
+         _                  => None,
+      }).collect();
+   }
+
+   pub fn argument_types(
+      & self,
+   ) -> Vec<syn::Type> {
+      return self.closure.inputs.iter().filter_map(|pat| match pat {
+         syn::Pat::Type(ty) => Some((*ty.ty).clone()),
+         _                  => None,
+      }).collect();
    }
 
    pub fn parse_asm_template(
       & self,
       identifiers : & HookIdentifier,
-   ) -> syn::LitStr {
+      abi         : HookAbi,
+      context     : bool,
+   ) -> (syn::LitStr, bool) {
       lazy_static::lazy_static!{
          static ref ARG_SEARCHER : regex::Regex = regex::Regex::new(
             r"\{[^\{\}]*?\}"
          ).expect("Failed to parse Regex! This is a bug in the macro!");
       };
 
+      // The context is passed after the
+      // closure's own arguments
+      let context_register = match context {
+         true  => {
+            let registers = abi.argument_registers();
+            match registers.get(self.closure.inputs.len()) {
+               Some(register) => Some(*register),
+               None           => proc_macro_error::abort!(self.asm_template.span(),
+                  "a context hook's closure takes at most {} arguments under the {} calling convention, since the context is passed after them",
+                  registers.len() - 1, abi.name(),
+               ),
+            }
+         },
+         false => None,
+      };
+
       // Substitute template arguments
-      let output = ARG_SEARCHER.replace(
+      let mut substitutor = HookSubstitutor::new(identifiers, abi, self.asm_template.span(), context);
+      let output = ARG_SEARCHER.replace_all(
          &self.asm_template.value(),
-         HookSubstitutor::new(identifiers, self.asm_template.span()),
+         regex::Replacer::by_ref(& mut substitutor),
       ).into_owned();
 
       // Create the fully-constructed assembly template
@@ -129,8 +319,43 @@ impl HookInput {
          {output}             // Previously parsed ASM
       ");
 
+      // A context hook's template is followed by
+      // the routine its target calls, which passes
+      // the context to the closure's function, and
+      // the slots the copy fills in.  Everything
+      // is addressed relative to the end label,
+      // so the whole block still works once it's
+      // copied.  Calls are counted so the context
+      // can wait for them before it's freed.
+      let output = match context_register {
+         Some(context_register)  => {
+            let label_enter   = &identifiers.enter;
+            let label_end     = &identifiers.end;
+            format!("
+               {output}
+               {label_enter}:
+               sub rsp,0x28
+               lock inc qword ptr [rip+{label_end}-8]
+               mov {context_register},qword ptr [rip+{label_end}-16]
+               call qword ptr [rip+{label_end}-24]
+               lock dec qword ptr [rip+{label_end}-8]
+               add rsp,0x28
+               ret
+               .p2align 3
+               .quad 0  // Closure function
+               .quad 0  // Context
+               .quad 0  // Running calls
+               {label_end}:
+            ")
+         },
+         None                    => output,
+      };
+
       // Re-construct LitStr and return
-      return syn::LitStr::new(&output, self.asm_template.span());
+      return (
+         syn::LitStr::new(&output, self.asm_template.span()),
+         substitutor.uses_closure,
+      );
    }
 }
 
@@ -147,8 +372,37 @@ impl syn::parse::Parse for HookInput {
       // Required - Closure which will be called
       let closure = input.parse::<syn::ExprClosure>()?;
 
-      // Optional - Trailing comma after the last argument
-      input.parse::<Option<syn::Token![,]>>()?;
+      // Optional - Comma-separated named arguments
+      // for the enable switch, function binding,
+      // and thread filter
+      let mut enabled = None;
+      let mut binding = None;
+      let mut threads = None;
+      while input.parse::<Option<syn::Token![,]>>()?.is_some() == true
+         && input.is_empty() == false
+      {
+         let name = input.parse::<syn::Ident>()?;
+         let slot = if name == "enabled" {
+            & mut enabled
+         } else if name == "bind" {
+            & mut binding
+         } else if name == "threads" {
+            & mut threads
+         } else {
+            proc_macro_error::abort!(name.span(),
+               "unknown hook argument \"{}\", expected \"enabled\", \"bind\", or \"threads\"", name,
+            );
+         };
+
+         if slot.is_some() == true {
+            proc_macro_error::abort!(name.span(),
+               "hook argument \"{}\" given more than once", name,
+            );
+         }
+
+         input.parse::<syn::Token![=]>()?;
+         *slot = Some(input.parse::<syn::Expr>()?);
+      }
 
       // Verify every argument for the closure
       // contains a concrete type
@@ -162,6 +416,17 @@ impl syn::parse::Parse for HookInput {
          }
 
          if let syn::Pat::Type(ty) = pat {
+            // Bound functions are passed the
+            // arguments by name
+            if binding.is_some() == true {
+               if let syn::Pat::Ident(_) = &*ty.pat {
+               } else {
+                  proc_macro_error::abort!(ty.colon_token.spans[0],
+                     "closure arguments must be plain names when the hook is bound",
+                  );
+               }
+            }
+
             let ty = &*ty.ty;
 
             if let syn::Type::Infer(ty) = ty {
@@ -186,14 +451,6 @@ impl syn::parse::Parse for HookInput {
          }
       }
 
-      // Verify there the move keyword wasn't used
-      if let Some(mv) = &closure.capture {
-         let span = mv.span;
-         proc_macro_error::abort!(span,
-            "closure may not take ownership of environment variables",
-         );
-      }
-
       // Verify the async keyword wasn't used
       if let Some(ay) = &closure.asyncness {
          let span = ay.span;
@@ -207,18 +464,79 @@ impl syn::parse::Parse for HookInput {
       return Ok(Self{
          asm_template   : asm_template,
          closure        : closure,
+         enabled        : enabled,
+         binding        : binding,
+         threads        : threads,
       });
    }
 }
 
+#[derive(Clone, Copy)]
+enum HookAbi {
+   Windows,
+   SystemV,
+}
+
+impl HookAbi {
+   pub fn name(
+      & self,
+   ) -> &'static str {
+      return match self {
+         Self::Windows  => "Windows x64",
+         Self::SystemV  => "System V",
+      };
+   }
+
+   pub fn argument_registers(
+      & self,
+   ) -> &'static [&'static str] {
+      return match self {
+         Self::Windows  => &["rcx", "rdx", "r8", "r9"],
+         Self::SystemV  => &["rdi", "rsi", "rdx", "rcx", "r8", "r9"],
+      };
+   }
+}
+
+/// Every general purpose register
+/// which may be passed as an argument.
+const GENERAL_REGISTERS : [&'static str; 16] = [
+   "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi",
+   "r8",  "r9",  "r10", "r11", "r12", "r13", "r14", "r15",
+];
+
+/// Registers which either calling
+/// convention lets a call overwrite,
+/// pushed in this order by save_volatile.
+const VOLATILE_REGISTERS : [&'static str; 9] = [
+   "rax", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11",
+];
+
+/// Every general purpose register except
+/// the stack pointer, pushed in this order
+/// by registers.  This must match the
+/// field order of hook::CpuContext, which
+/// is reversed since the stack grows down.
+const CONTEXT_REGISTERS : [&'static str; 15] = [
+   "rax", "rcx", "rdx", "rbx", "rbp", "rsi", "rdi",
+   "r8",  "r9",  "r10", "r11", "r12", "r13", "r14", "r15",
+];
+
 enum HookArgument {
    IdentifierTrampoline,
    IdentifierClosure,
+   SaveVolatile,
+   RestoreVolatile,
+   Argument{
+      index    : usize,
+      register : &'static str,
+   },
+   Registers,
 }
 
 enum HookArgumentError {
    UnknownArgument,
    UnexpectedParameter,
+   InvalidRegister,
 }
 
 impl std::str::FromStr for HookArgument {
@@ -233,9 +551,12 @@ impl std::str::FromStr for HookArgument {
             let mut map = HashMap::with_capacity(ARG_COUNT);
 
             // Add custom arguments here
-            const ARG_COUNT : usize = 2;
-            map.insert("self",   HookArgument::IdentifierTrampoline);
-            map.insert("target", HookArgument::IdentifierClosure);
+            const ARG_COUNT : usize = 5;
+            map.insert("self",               HookArgument::IdentifierTrampoline);
+            map.insert("target",             HookArgument::IdentifierClosure);
+            map.insert("save_volatile",      HookArgument::SaveVolatile);
+            map.insert("restore_volatile",   HookArgument::RestoreVolatile);
+            map.insert("registers",          HookArgument::Registers);
 
             map
          };
@@ -249,6 +570,18 @@ impl std::str::FromStr for HookArgument {
       let arg     = arg.trim();
       let param   = param.trim();
 
+      // Numbered arguments such as "arg0 rbx"
+      // aren't in the map
+      if let Some(index) = arg.strip_prefix("arg").and_then(|index| index.parse::<usize>().ok()) {
+         let register = GENERAL_REGISTERS.iter()
+            .find(|register| **register == param)
+            .ok_or(HookArgumentError::InvalidRegister)?;
+         return Ok(HookArgument::Argument{
+            index    : index,
+            register : register,
+         });
+      }
+
       // Parse into an argument enum
       let arg = ARG_MAP.get(arg).ok_or(HookArgumentError::UnknownArgument)?;
 
@@ -268,25 +601,170 @@ impl std::str::FromStr for HookArgument {
                Ok(HookArgument::IdentifierClosure)
             }
          },
+         HookArgument::SaveVolatile          => {
+            if param.is_empty() == false {
+               Err(HookArgumentError::UnexpectedParameter)
+            } else {
+               Ok(HookArgument::SaveVolatile)
+            }
+         },
+         HookArgument::RestoreVolatile       => {
+            if param.is_empty() == false {
+               Err(HookArgumentError::UnexpectedParameter)
+            } else {
+               Ok(HookArgument::RestoreVolatile)
+            }
+         },
+         HookArgument::Registers             => {
+            if param.is_empty() == false {
+               Err(HookArgumentError::UnexpectedParameter)
+            } else {
+               Ok(HookArgument::Registers)
+            }
+         },
+         // Never stored in the map
+         HookArgument::Argument{..}          => Err(HookArgumentError::UnknownArgument),
       };
    }
 }
 
 struct HookSubstitutor<'s> {
-   ident : &'s HookIdentifier,
-   span  : proc_macro2::Span,
+   ident          : &'s HookIdentifier,
+   abi            : HookAbi,
+   span           : proc_macro2::Span,
+   context        : bool,
+   uses_closure   : bool,
+   written        : Vec<&'static str>,
 }
 
 impl<'s> HookSubstitutor<'s> {
    pub fn new(
-      ident : &'s HookIdentifier,
-      span  : proc_macro2::Span,
+      ident    : &'s HookIdentifier,
+      abi      : HookAbi,
+      span     : proc_macro2::Span,
+      context  : bool,
    ) -> Self {
       return Self{
-         ident : ident,
-         span  : span,
+         ident          : ident,
+         abi            : abi,
+         span           : span,
+         context        : context,
+         uses_closure   : false,
+         written        : Vec::new(),
       };
    }
+
+   // A context hook calls the routine which
+   // passes the context, since its copied
+   // template can't reference the closure's
+   // function directly
+   fn target(
+      & mut self,
+   ) -> String {
+      if self.context == true {
+         return format!("{}", &self.ident.enter);
+      }
+
+      // Filled in by the symbol operand
+      self.uses_closure = true;
+      return String::from("{target}");
+   }
+
+   fn argument(
+      & mut self,
+      index    : usize,
+      register : &'static str,
+   ) -> String {
+      let destinations = self.abi.argument_registers();
+      let destination = match destinations.get(index) {
+         Some(destination) => *destination,
+         None              => proc_macro_error::abort!(self.span,
+            "argument {} is passed on the stack by the {} calling convention, only {} register arguments are supported",
+            index, self.abi.name(), destinations.len(),
+         ),
+      };
+
+      // Moves happen in the order written, so
+      // reading a register an earlier argument
+      // already replaced would pass the wrong value
+      if self.written.contains(&register) == true {
+         proc_macro_error::abort!(self.span,
+            "{} was already overwritten by an earlier argument under the {} calling convention, reorder the arguments",
+            register, self.abi.name(),
+         );
+      }
+      if destination == register {
+         return String::new();
+      }
+
+      self.written.push(destination);
+      return format!("mov {destination},{register}");
+   }
+
+   fn save_volatile(
+      & self,
+   ) -> String {
+      let mut output = String::from("pushfq\n");
+      for register in VOLATILE_REGISTERS {
+         output += &format!("push {register}\n");
+      }
+
+      // The stack is aligned for the call
+      // with rbp remembering where it was
+      output += "push rbp\nmov rbp,rsp\nsub rsp,0x100\nand rsp,-16\n";
+      for index in 0..16 {
+         output += &format!("movdqa [rsp+{:#X}],xmm{index}\n", index * 16);
+      }
+      output += "sub rsp,0x20\n";
+      return output;
+   }
+
+   fn restore_volatile(
+      & self,
+   ) -> String {
+      let mut output = String::from("lea rsp,[rbp-0x100]\nand rsp,-16\n");
+      for index in 0..16 {
+         output += &format!("movdqa xmm{index},[rsp+{:#X}]\n", index * 16);
+      }
+
+      output += "mov rsp,rbp\npop rbp\n";
+      for register in VOLATILE_REGISTERS.iter().rev() {
+         output += &format!("pop {register}\n");
+      }
+      output += "popfq\n";
+      return output;
+   }
+
+   fn registers(
+      & mut self,
+   ) -> String {
+      let target = self.target();
+
+      let mut output = String::from("pushfq\n");
+      for register in CONTEXT_REGISTERS {
+         output += &format!("push {register}\n");
+      }
+      output += "sub rsp,0x100\n";
+      for index in 0..16 {
+         output += &format!("movdqu [rsp+{:#X}],xmm{index}\n", index * 16);
+      }
+
+      // rbx is saved by the callee, so it
+      // holds the context across the call
+      let context = self.abi.argument_registers()[0];
+      output += &format!("mov {context},rsp\nmov rbx,rsp\nand rsp,-16\nsub rsp,0x20\n");
+      output += &format!("call {target}\nmov rsp,rbx\n");
+
+      for index in 0..16 {
+         output += &format!("movdqu xmm{index},[rsp+{:#X}]\n", index * 16);
+      }
+      output += "add rsp,0x100\n";
+      for register in CONTEXT_REGISTERS.iter().rev() {
+         output += &format!("pop {register}\n");
+      }
+      output += "popfq\n";
+      return output;
+   }
 }
 
 impl<'s> regex::Replacer for HookSubstitutor<'s> {
@@ -319,6 +797,10 @@ impl<'s> regex::Replacer for HookSubstitutor<'s> {
                   => proc_macro_error::abort!(self.span,
                      "assembly template argument \"{}\" has unexpected parameters", cap,
                   ),
+               HookArgumentError::InvalidRegister
+                  => proc_macro_error::abort!(self.span,
+                     "assembly template argument \"{}\" must name a 64-bit general purpose register", cap,
+                  ),
             }},
          };
 
@@ -327,7 +809,15 @@ impl<'s> regex::Replacer for HookSubstitutor<'s> {
             HookArgument::IdentifierTrampoline
                => format!("{}", &self.ident.trampoline),
             HookArgument::IdentifierClosure
-               => format!("{}", &self.ident.closure),
+               => self.target(),
+            HookArgument::SaveVolatile
+               => self.save_volatile(),
+            HookArgument::RestoreVolatile
+               => self.restore_volatile(),
+            HookArgument::Argument{index, register}
+               => self.argument(index, register),
+            HookArgument::Registers
+               => self.registers(),
          };
 
          // Append the generated text to the buffer
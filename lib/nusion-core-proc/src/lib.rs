@@ -11,8 +11,10 @@
 //////////////////////
 
 mod am_main;
+mod am_supported_versions;
 mod fm_hook;
 mod fm_asm_bytes;
+mod symbol;
 
 //////////////////////////////////
 // PROCEDURAL MACRO DEFINITIONS //
@@ -57,7 +59,60 @@ mod fm_asm_bytes;
 /// process in this list, it will exit
 /// before executing the main function.
 /// This process name list is a comma-separated
-/// list of string literals.
+/// list of string literals, which may also be
+/// written as <code>processes("a.exe", "b.exe")</code>.
+/// Names are compared ignoring case, and may
+/// contain <code>*</code> and <code>?</code>
+/// wildcards such as <code>"FSD-Win64-*.exe"</code>.
+///
+/// The following named options may be given
+/// alongside the process names:
+/// <ul>
+/// <li>
+/// <code>console = Window | Attach | None</code>
+/// decides where the mod's console comes
+/// from: a new window, the console of the
+/// process which started the game, or no
+/// console at all.  Any <code>bool</code>
+/// expression such as
+/// <code>cfg!(debug_assertions)</code> may
+/// also be given, where <code>true</code>
+/// creates a window and <code>false</code>
+/// creates no console.  Defaults to
+/// <code>Window</code>.
+/// </li>
+/// <li>
+/// <code>title = "..."</code>
+/// sets the title of the console window.
+/// </li>
+/// <li>
+/// <code>minidump = bool</code>
+/// decides whether a minidump of the game
+/// is written next to the panic report,
+/// so a crash can be opened in a debugger.
+/// This is only supported on Windows.
+/// Defaults to <code>false</code>.
+/// </li>
+/// <li>
+/// <code>manual_ready = bool</code>
+/// decides whether main calls
+/// <code>inject::signal_ready</code> itself
+/// to let a game started by
+/// <code>inject::inject_and_resume</code>
+/// run.  Set this when main has to patch
+/// the game before it initializes, as
+/// <code>game/drg</code> does.  Otherwise
+/// the game is resumed before main runs.
+/// Defaults to <code>false</code>.
+/// </li>
+/// </ul>
+///
+/// Only one instance of a mod may run
+/// in a process at a time, keyed by the
+/// package name in the mod's manifest.
+/// If the same library is injected twice,
+/// the second copy reports an error and
+/// exits before the environment is created.
 ///
 /// <h2 id=  main_example>
 /// <a href=#main_example>
@@ -113,6 +168,19 @@ mod fm_asm_bytes;
 ///    return Ok(());
 /// }
 /// ```
+///
+/// <h6 id=  main_examples_options>
+/// <a href=#main_examples_options>
+/// Console options
+/// </a></h6>
+///
+/// ```
+/// #[nusion_lib::main(processes("hl2.exe"), console = None)]
+/// fn main() {
+///    // Runs without a console window
+///    nusion_core::wait_for_unload(std::time::Duration::from_millis(250));
+/// }
+/// ```
 #[proc_macro_attribute]
 #[proc_macro_error::proc_macro_error]
 pub fn main(
@@ -122,6 +190,47 @@ pub fn main(
    return am_main::main(attr, item);
 }
 
+/// Disables a feature when the game's
+/// version doesn't match any of the
+/// listed version patterns.  The check
+/// happens the first time the function
+/// is called, and a disabled feature is
+/// reported once on standard output.
+/// See the <code>versions</code> module
+/// for the pattern syntax and how the
+/// game's version is found.
+///
+/// Instead of running, a disabled
+/// function returns a value given by
+/// the <code>versions::Unsupported</code>
+/// trait: nothing for <code>()</code>,
+/// <code>false</code>, <code>None</code>,
+/// or an <code>Err</code> for a
+/// <code>Result</code> whose error type
+/// converts from <code>VersionError</code>,
+/// such as <code>Box&lt;dyn Error&gt;</code>.
+///
+/// <h2 id=  supported_versions_examples>
+/// <a href=#supported_versions_examples>
+/// Examples
+/// </a></h2>
+///
+/// ```
+/// #[nusion_core::supported_versions("1.38.*", "1.39.0")]
+/// fn enable_god_mode(
+/// ) -> Result<nusion_core::process::ModuleSnapshotPatchContainer, Box<dyn std::error::Error>> {
+///    return Ok(unsafe{crate::game_mut!().patch_create(&GOD_MODE)}?);
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn supported_versions(
+   attr  : proc_macro::TokenStream,
+   item  : proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+   return am_supported_versions::supported_versions(attr, item);
+}
+
 /// Generates an ASM trampoline and
 /// Rust function pair, returning the
 /// function pointer to the ASM
@@ -160,7 +269,9 @@ pub fn main(
 /// the
 /// <code><a href=
 /// https://doc.rust-lang.org/std/ops/trait.Fn.html
-/// >Fn</a></code> trait.
+/// >Fn</a></code> trait.  To capture
+/// variables, use <code>hook_with_context!</code>
+/// instead.
 ///
 /// <h2 id=  hook_asm_template_arguments>
 /// <a href=#hook_asm_template_arguments>
@@ -191,8 +302,145 @@ pub fn main(
 /// label for the Rust closure.  Use this argument
 /// to call your closure from your ASM trampoline.
 /// </li>
+/// <li>
+/// <code>save_volatile</code> - Saves the flags,
+/// every register either calling convention lets
+/// a call overwrite, and all XMM registers, then
+/// aligns the stack for a call.  <code>rbp</code>
+/// is used to remember the stack pointer, so it
+/// holds a different value until the registers
+/// are restored.
+/// </li>
+/// <li>
+/// <code>restore_volatile</code> - Restores
+/// everything saved by <code>save_volatile</code>,
+/// including the stack pointer.  Anything the
+/// closure returned in <code>rax</code> is lost.
+/// </li>
+/// <li>
+/// <code>arg0 rbx</code>, <code>arg1 rsi</code>,
+/// etc. - Moves a 64-bit register into the given
+/// argument of the platform's calling convention,
+/// so the same template works on every platform.
+/// Arguments are moved in the order written, and
+/// reading a register an earlier argument already
+/// replaced is a compile error.
+/// </li>
+/// <li>
+/// <code>registers</code> - Saves every register
+/// and the flags into a <code>hook::CpuContext</code>,
+/// calls the closure with a mutable reference to
+/// it, and restores the registers from it, so the
+/// closure can read and change any of them.
+/// </li>
 /// </ul>
 ///
+/// ```
+/// nusion_lib::hook!("
+///    {save_volatile}
+///    {arg0 rbx}
+///    {arg1 rdi}
+///    call  {target}
+///    {restore_volatile}
+///    ret
+/// ", |player : * mut Player, weapon : * mut Weapon| {
+///    // ...
+/// })
+/// ```
+///
+/// <h2 id=  hook_enable_flag>
+/// <a href=#hook_enable_flag>
+/// Enable Flag
+/// </a></h2>
+/// An optional <code>enabled</code> argument
+/// may follow the closure, naming a static
+/// <code>hook::HookSwitch</code>.  The switch
+/// is checked before the closure runs, and
+/// the closure is skipped while the switch
+/// is disabled.  This allows a feature to
+/// be toggled every frame without writing
+/// to the game's code.  When skipped, the
+/// closure returns the default value of
+/// its return type, so the return type
+/// must implement <code>Default</code>.
+///
+/// ```
+/// static FLIGHT : nusion_lib::hook::HookSwitch
+///    = nusion_lib::hook::HookSwitch::new(false);
+///
+/// const HOOK_GRAVITY : nusion_lib::patch::writer::Hook = nusion_lib::patch::writer::Hook{
+///    memory_offset_range  : 0x7FFF2000..0x7FFF2005,
+///    checksum             : nusion_lib::patch::Checksum::from(0xDEADBEEF),
+///    hook                 : nusion_lib::hook!("
+///       push  rcx
+///       lea   rcx,[rcx+0x20]
+///       call  {target}
+///       pop   rcx
+///       ret
+///    ", |gravity : & mut f32| {
+///       *gravity = 0.0;
+///    }, enabled = FLIGHT),
+/// }
+///
+/// // Later, from the main loop
+/// FLIGHT.set(bindings.flight.is_active());
+/// ```
+///
+/// <h2 id=  hook_binding>
+/// <a href=#hook_binding>
+/// Binding
+/// </a></h2>
+/// An optional <code>bind</code> argument
+/// may also follow the closure, naming a
+/// static <code>hook::HookBinding</code>.
+/// While a function is bound, the closure
+/// forwards its arguments to the function
+/// and returns its result instead of running
+/// its own body, so the hook's behavior can
+/// be swapped without repatching the game's
+/// code.  The closure arguments must be plain
+/// names for this to work.
+///
+/// ```
+/// static GRAVITY : nusion_lib::hook::HookBinding<fn(& mut f32)>
+///    = nusion_lib::hook::HookBinding::new();
+///
+/// // Inside the hook! macro
+/// ", |gravity : & mut f32| {
+///    *gravity = 0.0;
+/// }, enabled = FLIGHT, bind = GRAVITY),
+///
+/// // After reloading the config
+/// GRAVITY.bind(moon_gravity);
+/// ```
+///
+/// <h2 id=  hook_thread_filter>
+/// <a href=#hook_thread_filter>
+/// Thread Filter
+/// </a></h2>
+/// An optional <code>threads</code> argument
+/// may also follow the closure, naming a
+/// static <code>hook::HookThreadFilter</code>.
+/// The closure is skipped when the hooked
+/// code runs on a thread the filter doesn't
+/// allow, such as any thread other than the
+/// render thread.  Like with the enable flag,
+/// a skipped closure returns the default value
+/// of its return type.
+///
+/// ```
+/// static RENDER_THREAD : nusion_lib::hook::HookThreadFilter
+///    = nusion_lib::hook::HookThreadFilter::new();
+///
+/// // Inside the hook! macro
+/// ", |gravity : & mut f32| {
+///    *gravity = 0.0;
+/// }, enabled = FLIGHT, threads = RENDER_THREAD),
+///
+/// // From a hook only called by the render thread
+/// RENDER_THREAD.only_current();
+/// ```
+///
 /// <h2 id=  hook_safety>
 /// <a href=#hook_safety>
 /// Safety
@@ -361,6 +609,64 @@ pub fn hook(
    return fm_hook::hook(item);
 }
 
+/// The same as <code>hook!</code>, except
+/// the closure may capture its environment,
+/// including by <code>move</code>.  Instead
+/// of a hook function pointer, this creates
+/// a <code>hook::HookContext</code> at runtime
+/// which owns the closure, and whose
+/// <code>hook</code> method gets the function
+/// pointer to use in a writer.  The closure's
+/// captured state is freed when the context
+/// is dropped.
+///
+/// The closure must implement <code>Fn</code>,
+/// <code>Send</code>, and <code>Sync</code>,
+/// since the hook may run on any thread at
+/// the same time, so state it changes needs
+/// an atomic or a lock.  Its arguments must
+/// be plain names, and there can be at most
+/// three of them, since the context is passed
+/// in the next argument register.
+///
+/// Every context runs its own copy of the
+/// assembly template, so the template may
+/// only refer to its own labels,
+/// <code>{self}</code>, and <code>{target}</code>.
+/// Otherwise, the assembly template and the
+/// optional arguments are the same as
+/// <code><a href=#hook_asm_template_arguments>hook!</a></code>.
+///
+/// ```
+/// fn enable_speed(multiplier : f32) -> nusion_lib::patch::Result<
+///    nusion_lib::hook::HookContext<dyn Fn(& mut f32) + Send + Sync>
+/// > {
+///    return nusion_lib::hook_with_context!("
+///       push  rcx
+///       lea   rcx,[rcx+0x88]
+///       call  {target}
+///       pop   rcx
+///       ret
+///    ", move |speed : & mut f32| {
+///       *speed *= multiplier;
+///    });
+/// }
+/// ```
+///
+/// <h2 id=  hook_with_context_safety>
+/// <a href=#hook_with_context_safety>
+/// Safety
+/// </a></h2>
+/// All safety concerns from <code><a href=
+/// #hook_safety>hook!</a></code> apply.
+#[proc_macro]
+#[proc_macro_error::proc_macro_error]
+pub fn hook_with_context(
+   item  : proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+   return fm_hook::hook_with_context(item);
+}
+
 /// Generates a static byte slice
 /// containing assembly instructions.
 /// The syntax is mostly the same
@@ -374,14 +680,24 @@ pub fn hook(
 /// Note
 /// </a></h2>
 ///
-/// Due to issues with constructing
-/// the byte slice, the macro output
-/// cannot be used to initialize a
-/// const variable.  This can be
-/// side-stepped by using the
-/// <a href=https://docs.rs/lazy_static/1.4.0/lazy_static/
-/// >lazy_static</a> crate to
-/// initialize at runtime.
+/// The assembly is assembled while the
+/// macro expands with the same assembler
+/// as <code>nusion_core::asm</code>, so the
+/// output is a plain byte slice which can
+/// initialize a const variable.  Only the
+/// instructions that assembler supports may
+/// be used, which are <code>nop</code>,
+/// <code>ret</code>, <code>int3</code>,
+/// <code>leave</code>, <code>push</code>,
+/// <code>pop</code>, <code>lea</code>,
+/// <code>mov</code>, <code>add</code>,
+/// <code>or</code>, <code>and</code>,
+/// <code>sub</code>, <code>xor</code>,
+/// <code>cmp</code>, <code>jmp</code>,
+/// <code>call</code> and conditional jumps.
+/// Memory operands without a register
+/// operand need an explicit size, such as
+/// <code>dword ptr</code>.
 ///
 /// <h2 id=  asm_bytes_safety>
 /// <a href=#asm_bytes_safety>
@@ -417,7 +733,7 @@ pub fn hook(
 /// </a></h6>
 ///
 /// ```
-/// let correct_usage_0 = nusion_lib::asm_bytes!("
+/// const CORRECT_USAGE_0 : &[u8] = nusion_lib::asm_bytes!("
 ///    xor   eax,eax  // We aren't accessing memory
 ///                   // in any way, so there's
 ///                   // absolutely nothing wrong
@@ -444,7 +760,11 @@ pub fn hook(
 /// <b>Incorrect</b> usages
 /// </a></h6>
 ///
-/// ```
+/// References to labels outside the
+/// assembly are caught while assembling
+/// and reported as a compile error.
+///
+/// ```compile_fail
 /// let incorrect_usage_0 = nusion_lib::asm_bytes!("
 ///    sub   ebx,1             // This is not allowed because
 ///    jnz   external_label    // we are jumping to some outside label
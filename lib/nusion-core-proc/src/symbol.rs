@@ -0,0 +1,80 @@
+// Deterministic symbol generation for
+// the function-like macros.  Symbols are
+// built from the crate name and version,
+// the file the macro was expanded in, and
+// a hash of where in the file it was
+// expanded and the macro's input, so
+// unrelated edits elsewhere never change
+// them.  Nothing is kept between expansions,
+// since the same item may be expanded many
+// times by one proc-macro server, such as
+// by rust-analyzer or an incremental build.
+//
+// Setting NUSION_DEBUG_SYMBOLS while building
+// prints every generated symbol along with
+// where it was expanded from.
+
+use std::hash::{Hash, Hasher};
+
+pub struct Symbol {
+   pub base    : String,
+   pub origin  : String,
+}
+
+// Generates the symbol base for the
+// expansion of an item at the macro
+// call site.  The item is the macro's
+// input, which tells apart expansions
+// sharing a call site, such as from
+// within a declarative macro.
+pub fn generate(
+   prefix   : & str,
+   item     : & str,
+) -> Symbol {
+   let call_site  = proc_macro::Span::call_site();
+   let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or(String::from("unknown"));
+   let version    = std::env::var("CARGO_PKG_VERSION").unwrap_or(String::new());
+   let file       = call_site.file();
+
+   // The file path is hashed too, since
+   // different files may sanitize to the
+   // same name
+   let mut hasher = std::collections::hash_map::DefaultHasher::new();
+   file.hash(&mut hasher);
+   call_site.line().hash(&mut hasher);
+   call_site.column().hash(&mut hasher);
+   item.hash(&mut hasher);
+   let hash = hasher.finish();
+
+   let base = format!(
+      "{prefix}_{}_{}_{}_{hash:016x}",
+      sanitize(&crate_name),
+      sanitize(&version),
+      sanitize(&file),
+   );
+   let origin = format!(
+      "{file}:{}:{}",
+      call_site.line(),
+      call_site.column(),
+   );
+
+   if std::env::var_os("NUSION_DEBUG_SYMBOLS").is_some() {
+      eprintln!("nusion-core-proc: {base} <- {origin}");
+   }
+
+   return Symbol{
+      base     : base,
+      origin   : origin,
+   };
+}
+
+// Replaces every character which can't
+// appear in an identifier with an underscore.
+fn sanitize(
+   text : & str,
+) -> String {
+   return text.chars().map(|c| match c.is_ascii_alphanumeric() {
+      true  => c,
+      false => '_',
+   }).collect();
+}
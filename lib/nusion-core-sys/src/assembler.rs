@@ -0,0 +1,1083 @@
+//! Machine code assembly functions.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to assembling
+/// instructions at runtime.
+#[derive(Debug)]
+pub enum AsmError {
+   InvalidOperands{
+      mnemonic : &'static str,
+   },
+   ImmediateOutOfRange{
+      mnemonic : &'static str,
+      value    : i64,
+   },
+   AddressOutOfRange{
+      address  : usize,
+   },
+   InvalidScale{
+      scale    : u8,
+   },
+   InvalidIndex{
+      index    : Register,
+   },
+   UnboundLabel{
+      label    : usize,
+   },
+   LabelAlreadyBound{
+      label    : usize,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>AsmError</code>.
+pub type Result<T> = std::result::Result<T, AsmError>;
+
+/// A general-purpose register.  The size
+/// of the register used by an instruction
+/// is chosen by the operand, and defaults
+/// to the full 64 bits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Register {
+   Rax,
+   Rcx,
+   Rdx,
+   Rbx,
+   Rsp,
+   Rbp,
+   Rsi,
+   Rdi,
+   R8,
+   R9,
+   R10,
+   R11,
+   R12,
+   R13,
+   R14,
+   R15,
+}
+
+/// The size of a register or
+/// memory operand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Size {
+   Byte,
+   Word,
+   Dword,
+   Qword,
+}
+
+/// A memory address used by an
+/// instruction.  <code>Absolute</code>
+/// addresses are encoded relative to the
+/// instruction pointer, so they must be
+/// within 2GiB of the assembled code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Address {
+   Base{
+      base           : Register,
+      displacement   : i32,
+   },
+   Indexed{
+      base           : Register,
+      index          : Register,
+      scale          : u8,
+      displacement   : i32,
+   },
+   Absolute{
+      address        : usize,
+   },
+}
+
+/// An instruction operand.  Registers,
+/// addresses and integers can be converted
+/// into operands directly, in which case
+/// registers and memory are 64 bits wide.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operand {
+   Register{
+      register : Register,
+      size     : Size,
+   },
+   Memory{
+      address  : Address,
+      size     : Size,
+   },
+   Immediate{
+      value    : i64,
+   },
+}
+
+/// The condition tested by a
+/// conditional jump.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Condition {
+   Overflow,
+   NotOverflow,
+   Below,
+   AboveEqual,
+   Equal,
+   NotEqual,
+   BelowEqual,
+   Above,
+   Sign,
+   NotSign,
+   Parity,
+   NotParity,
+   Less,
+   GreaterEqual,
+   LessEqual,
+   Greater,
+}
+
+/// A position within the assembled code
+/// which can be jumped to before it has
+/// been bound.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Label {
+   id : usize,
+}
+
+/// The destination of a jump or call,
+/// either an absolute address, a label
+/// within the assembled code, or an
+/// address stored in a register.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+   Address{
+      address  : usize,
+   },
+   Label{
+      label    : Label,
+   },
+   Register{
+      register : Register,
+   },
+}
+
+/// Assembles instructions one at a time
+/// into a byte buffer which will be placed
+/// at a known address.  Every method returns
+/// the assembler again so calls can be
+/// chained, and nothing is written for an
+/// instruction which can't be encoded.
+#[derive(Clone, Debug)]
+pub struct Assembler {
+   base_address   : usize,
+   bytes          : Vec<u8>,
+   labels         : Vec<Option<usize>>,
+   fixups         : Vec<LabelFixup>,
+}
+
+/// A 32-bit displacement to a label which
+/// is filled in once every label is bound.
+#[derive(Clone, Copy, Debug)]
+struct LabelFixup {
+   label             : Label,
+   displacement      : usize,
+   instruction_end   : usize,
+}
+
+/// The operand encoded by the ModRM
+/// r/m field.
+#[derive(Clone, Copy)]
+enum RegisterOrMemory {
+   Register(Register),
+   Memory(Address),
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - AsmError //
+//////////////////////////////////////
+
+impl std::fmt::Display for AsmError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::InvalidOperands      {mnemonic,        }
+            => write!(stream, "Invalid operands for {mnemonic}"),
+         Self::ImmediateOutOfRange  {mnemonic, value, }
+            => write!(stream, "Immediate {value:#X} is out of range for {mnemonic}"),
+         Self::AddressOutOfRange    {address,         }
+            => write!(stream, "Address {address:#X} is too far away to be encoded relative to the instruction pointer"),
+         Self::InvalidScale         {scale,           }
+            => write!(stream, "Invalid index scale {scale}, expected 1, 2, 4 or 8"),
+         Self::InvalidIndex         {index,           }
+            => write!(stream, "{index:?} can't be used as an index register"),
+         Self::UnboundLabel         {label,           }
+            => write!(stream, "Label {label} was never bound"),
+         Self::LabelAlreadyBound    {label,           }
+            => write!(stream, "Label {label} is already bound"),
+      };
+   }
+}
+
+impl std::error::Error for AsmError {
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Operand //
+/////////////////////////////////////
+
+impl From<Register> for Operand {
+   fn from(
+      value : Register,
+   ) -> Self {
+      return value.qword();
+   }
+}
+
+impl From<Address> for Operand {
+   fn from(
+      value : Address,
+   ) -> Self {
+      return value.qword();
+   }
+}
+
+impl From<i32> for Operand {
+   fn from(
+      value : i32,
+   ) -> Self {
+      return Self::Immediate{
+         value : value as i64,
+      };
+   }
+}
+
+impl From<i64> for Operand {
+   fn from(
+      value : i64,
+   ) -> Self {
+      return Self::Immediate{
+         value : value,
+      };
+   }
+}
+
+impl From<usize> for Operand {
+   fn from(
+      value : usize,
+   ) -> Self {
+      return Self::Immediate{
+         value : value as i64,
+      };
+   }
+}
+
+////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Target //
+////////////////////////////////////
+
+impl From<Label> for Target {
+   fn from(
+      value : Label,
+   ) -> Self {
+      return Self::Label{
+         label : value,
+      };
+   }
+}
+
+impl From<Register> for Target {
+   fn from(
+      value : Register,
+   ) -> Self {
+      return Self::Register{
+         register : value,
+      };
+   }
+}
+
+impl From<usize> for Target {
+   fn from(
+      value : usize,
+   ) -> Self {
+      return Self::Address{
+         address : value,
+      };
+   }
+}
+
+////////////////////////
+// METHODS - Register //
+////////////////////////
+
+impl Register {
+   /// Uses the low 8 bits of the register.
+   pub fn byte(
+      self,
+   ) -> Operand {
+      return Operand::Register{register : self, size : Size::Byte};
+   }
+
+   /// Uses the low 16 bits of the register.
+   pub fn word(
+      self,
+   ) -> Operand {
+      return Operand::Register{register : self, size : Size::Word};
+   }
+
+   /// Uses the low 32 bits of the register.
+   pub fn dword(
+      self,
+   ) -> Operand {
+      return Operand::Register{register : self, size : Size::Dword};
+   }
+
+   /// Uses the whole register.
+   pub fn qword(
+      self,
+   ) -> Operand {
+      return Operand::Register{register : self, size : Size::Qword};
+   }
+
+   fn number(
+      self,
+   ) -> u8 {
+      return self as u8;
+   }
+}
+
+///////////////////////
+// METHODS - Address //
+///////////////////////
+
+impl Address {
+   /// Accesses a single byte at the address.
+   pub fn byte(
+      self,
+   ) -> Operand {
+      return Operand::Memory{address : self, size : Size::Byte};
+   }
+
+   /// Accesses 16 bits at the address.
+   pub fn word(
+      self,
+   ) -> Operand {
+      return Operand::Memory{address : self, size : Size::Word};
+   }
+
+   /// Accesses 32 bits at the address.
+   pub fn dword(
+      self,
+   ) -> Operand {
+      return Operand::Memory{address : self, size : Size::Dword};
+   }
+
+   /// Accesses 64 bits at the address.
+   pub fn qword(
+      self,
+   ) -> Operand {
+      return Operand::Memory{address : self, size : Size::Qword};
+   }
+}
+
+/////////////////////////
+// METHODS - Condition //
+/////////////////////////
+
+impl Condition {
+   /// Gets the condition which is true
+   /// whenever this one is false.
+   pub fn inverse(
+      self,
+   ) -> Self {
+      return match self {
+         Self::Overflow       => Self::NotOverflow,
+         Self::NotOverflow    => Self::Overflow,
+         Self::Below          => Self::AboveEqual,
+         Self::AboveEqual     => Self::Below,
+         Self::Equal          => Self::NotEqual,
+         Self::NotEqual       => Self::Equal,
+         Self::BelowEqual     => Self::Above,
+         Self::Above          => Self::BelowEqual,
+         Self::Sign           => Self::NotSign,
+         Self::NotSign        => Self::Sign,
+         Self::Parity         => Self::NotParity,
+         Self::NotParity      => Self::Parity,
+         Self::Less           => Self::GreaterEqual,
+         Self::GreaterEqual   => Self::Less,
+         Self::LessEqual      => Self::Greater,
+         Self::Greater        => Self::LessEqual,
+      };
+   }
+
+   fn code(
+      self,
+   ) -> u8 {
+      return self as u8;
+   }
+}
+
+/////////////////////////
+// METHODS - Assembler //
+/////////////////////////
+
+impl Assembler {
+   /// Creates an empty assembler for code
+   /// which will be placed at an address.
+   /// The address is needed to encode jumps,
+   /// calls and absolute memory addresses
+   /// relative to the instruction pointer.
+   pub fn new(
+      base_address : usize,
+   ) -> Self {
+      return Self{
+         base_address   : base_address,
+         bytes          : Vec::new(),
+         labels         : Vec::new(),
+         fixups         : Vec::new(),
+      };
+   }
+
+   /// Gets the address the code will
+   /// be placed at.
+   pub fn base_address(
+      & self,
+   ) -> usize {
+      return self.base_address;
+   }
+
+   /// Gets the address the next
+   /// instruction will be placed at.
+   pub fn address(
+      & self,
+   ) -> usize {
+      return self.base_address.wrapping_add(self.bytes.len());
+   }
+
+   /// Gets the number of bytes
+   /// assembled so far.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.bytes.len();
+   }
+
+   /// Checks whether nothing has
+   /// been assembled yet.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.bytes.is_empty();
+   }
+
+   /// Creates a label which can be jumped
+   /// to, and bound to a position later
+   /// with <code>bind</code>.
+   pub fn new_label(
+      & mut self,
+   ) -> Label {
+      self.labels.push(None);
+      return Label{
+         id : self.labels.len() - 1,
+      };
+   }
+
+   /// Binds a label to the position of
+   /// the next instruction.
+   pub fn bind(
+      & mut self,
+      label : Label,
+   ) -> Result<& mut Self> {
+      let position = self.bytes.len();
+      return match self.labels.get_mut(label.id) {
+         Some(Some(_))
+            => Err(AsmError::LabelAlreadyBound{label : label.id}),
+         Some(slot)
+            => {*slot = Some(position); Ok(self)},
+         None
+            => Err(AsmError::UnboundLabel{label : label.id}),
+      };
+   }
+
+   /// Copies raw bytes into the code,
+   /// such as from <code>asm_bytes!</code>.
+   /// The bytes must not contain anything
+   /// which is relative to its own address.
+   pub fn bytes(
+      & mut self,
+      bytes : & [u8],
+   ) -> Result<& mut Self> {
+      self.bytes.extend_from_slice(bytes);
+      return Ok(self);
+   }
+
+   /// <code>nop</code>
+   pub fn nop(
+      & mut self,
+   ) -> Result<& mut Self> {
+      return self.bytes(&[0x90]);
+   }
+
+   /// <code>ret</code>
+   pub fn ret(
+      & mut self,
+   ) -> Result<& mut Self> {
+      return self.bytes(&[0xC3]);
+   }
+
+   /// <code>int3</code>
+   pub fn int3(
+      & mut self,
+   ) -> Result<& mut Self> {
+      return self.bytes(&[0xCC]);
+   }
+
+   /// <code>leave</code>
+   pub fn leave(
+      & mut self,
+   ) -> Result<& mut Self> {
+      return self.bytes(&[0xC9]);
+   }
+
+   /// <code>mov destination, source</code>
+   ///
+   /// 64-bit immediates are only allowed
+   /// when moving into a 64-bit register.
+   pub fn mov(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      const MNEMONIC : &str = "mov";
+
+      let encoding = match (destination.into(), source.into()) {
+         (Operand::Register{register : destination, size : size_destination},
+          Operand::Register{register : source,      size : size_source})
+            if size_destination == size_source
+            => self.encode_modrm(&[opcode_sized(0x88, size_source)], size_source, source.number(), true, RegisterOrMemory::Register(destination), 0)?,
+         (Operand::Register{register, size : size_register},
+          Operand::Memory{address,     size : size_memory})
+            if size_register == size_memory
+            => self.encode_modrm(&[opcode_sized(0x8A, size_register)], size_register, register.number(), true, RegisterOrMemory::Memory(address), 0)?,
+         (Operand::Memory{address,     size : size_memory},
+          Operand::Register{register, size : size_register})
+            if size_register == size_memory
+            => self.encode_modrm(&[opcode_sized(0x88, size_register)], size_register, register.number(), true, RegisterOrMemory::Memory(address), 0)?,
+         (Operand::Register{register, size : Size::Qword}, Operand::Immediate{value})
+            if i32::try_from(value).is_err() == true
+            => {
+               // Only the register form takes a
+               // full 64-bit immediate
+               let mut encoding = vec![crate::compiler::rex_prefix(true, 0, 0, register.number()), 0xB8 | (register.number() & 0x07)];
+               encoding.extend(value.to_le_bytes());
+               encoding
+            },
+         (Operand::Register{register, size}, Operand::Immediate{value})
+            => {
+               let immediate = immediate_bytes(MNEMONIC, value, size)?;
+               let mut encoding = self.encode_modrm(&[opcode_sized(0xC6, size)], size, 0, false, RegisterOrMemory::Register(register), immediate.len())?;
+               encoding.extend(immediate);
+               encoding
+            },
+         (Operand::Memory{address, size}, Operand::Immediate{value})
+            => {
+               let immediate = immediate_bytes(MNEMONIC, value, size)?;
+               let mut encoding = self.encode_modrm(&[opcode_sized(0xC6, size)], size, 0, false, RegisterOrMemory::Memory(address), immediate.len())?;
+               encoding.extend(immediate);
+               encoding
+            },
+         _  => return Err(AsmError::InvalidOperands{mnemonic : MNEMONIC}),
+      };
+
+      self.bytes.extend(encoding);
+      return Ok(self);
+   }
+
+   /// <code>lea register, [address]</code>
+   pub fn lea(
+      & mut self,
+      register : Register,
+      address  : Address,
+   ) -> Result<& mut Self> {
+      let encoding = self.encode_modrm(&[0x8D], Size::Qword, register.number(), true, RegisterOrMemory::Memory(address), 0)?;
+
+      self.bytes.extend(encoding);
+      return Ok(self);
+   }
+
+   /// <code>push operand</code>
+   ///
+   /// Registers and memory must be 64 bits,
+   /// and immediates are sign-extended from
+   /// 32 bits.
+   pub fn push(
+      & mut self,
+      operand : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      const MNEMONIC : &str = "push";
+
+      let encoding = match operand.into() {
+         Operand::Register{register, size : Size::Qword}
+            => opcode_plus_register(0x50, register),
+         Operand::Memory{address, size : Size::Qword}
+            => self.encode_modrm(&[0xFF], Size::Dword, 6, false, RegisterOrMemory::Memory(address), 0)?,
+         Operand::Immediate{value}
+            => match i8::try_from(value) {
+               Ok(value)   => vec![0x6A, value as u8],
+               Err(_)      => {
+                  let mut encoding = vec![0x68];
+                  encoding.extend(immediate_bytes(MNEMONIC, value, Size::Qword)?);
+                  encoding
+               },
+            },
+         _  => return Err(AsmError::InvalidOperands{mnemonic : MNEMONIC}),
+      };
+
+      self.bytes.extend(encoding);
+      return Ok(self);
+   }
+
+   /// <code>pop operand</code>
+   ///
+   /// Registers and memory must be 64 bits.
+   pub fn pop(
+      & mut self,
+      operand : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      let encoding = match operand.into() {
+         Operand::Register{register, size : Size::Qword}
+            => opcode_plus_register(0x58, register),
+         Operand::Memory{address, size : Size::Qword}
+            => self.encode_modrm(&[0x8F], Size::Dword, 0, false, RegisterOrMemory::Memory(address), 0)?,
+         _  => return Err(AsmError::InvalidOperands{mnemonic : "pop"}),
+      };
+
+      self.bytes.extend(encoding);
+      return Ok(self);
+   }
+
+   /// <code>add destination, source</code>
+   pub fn add(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("add", 0, destination.into(), source.into());
+   }
+
+   /// <code>or destination, source</code>
+   pub fn or(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("or", 1, destination.into(), source.into());
+   }
+
+   /// <code>and destination, source</code>
+   pub fn and(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("and", 4, destination.into(), source.into());
+   }
+
+   /// <code>sub destination, source</code>
+   pub fn sub(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("sub", 5, destination.into(), source.into());
+   }
+
+   /// <code>xor destination, source</code>
+   pub fn xor(
+      & mut self,
+      destination : impl Into<Operand>,
+      source      : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("xor", 6, destination.into(), source.into());
+   }
+
+   /// <code>cmp left, right</code>
+   pub fn cmp(
+      & mut self,
+      left  : impl Into<Operand>,
+      right : impl Into<Operand>,
+   ) -> Result<& mut Self> {
+      return self.arithmetic("cmp", 7, left.into(), right.into());
+   }
+
+   /// <code>jmp target</code>
+   ///
+   /// Addresses further than 2GiB away
+   /// use an indirect jump through an
+   /// address stored after the instruction.
+   pub fn jmp(
+      & mut self,
+      target : impl Into<Target>,
+   ) -> Result<& mut Self> {
+      match target.into() {
+         Target::Label{label} => {
+            self.bytes.push(0xE9);
+            self.push_label_fixup(label);
+         },
+         Target::Address{address} => match self.relative_displacement(address, 5) {
+            Some(displacement) => {
+               self.bytes.push(0xE9);
+               self.bytes.extend(displacement.to_le_bytes());
+            },
+            None => {
+               self.bytes.extend([0xFF, 0x25, 0x00, 0x00, 0x00, 0x00]);
+               self.bytes.extend((address as u64).to_le_bytes());
+            },
+         },
+         Target::Register{register} => {
+            let encoding = self.encode_modrm(&[0xFF], Size::Dword, 4, false, RegisterOrMemory::Register(register), 0)?;
+            self.bytes.extend(encoding);
+         },
+      }
+
+      return Ok(self);
+   }
+
+   /// <code>call target</code>
+   ///
+   /// Addresses further than 2GiB away
+   /// use an indirect call through an
+   /// address stored after the instruction.
+   pub fn call(
+      & mut self,
+      target : impl Into<Target>,
+   ) -> Result<& mut Self> {
+      match target.into() {
+         Target::Label{label} => {
+            self.bytes.push(0xE8);
+            self.push_label_fixup(label);
+         },
+         Target::Address{address} => match self.relative_displacement(address, 5) {
+            Some(displacement) => {
+               self.bytes.push(0xE8);
+               self.bytes.extend(displacement.to_le_bytes());
+            },
+            None => {
+               // Jump over the stored address
+               // when the call returns
+               self.bytes.extend([0xFF, 0x15, 0x02, 0x00, 0x00, 0x00, 0xEB, 0x08]);
+               self.bytes.extend((address as u64).to_le_bytes());
+            },
+         },
+         Target::Register{register} => {
+            let encoding = self.encode_modrm(&[0xFF], Size::Dword, 2, false, RegisterOrMemory::Register(register), 0)?;
+            self.bytes.extend(encoding);
+         },
+      }
+
+      return Ok(self);
+   }
+
+   /// <code>jcc target</code>
+   ///
+   /// Addresses further than 2GiB away use
+   /// the inverse condition to skip over an
+   /// indirect jump.  Registers can't be
+   /// jumped to conditionally.
+   pub fn jcc(
+      & mut self,
+      condition   : Condition,
+      target      : impl Into<Target>,
+   ) -> Result<& mut Self> {
+      match target.into() {
+         Target::Label{label} => {
+            self.bytes.extend([0x0F, 0x80 | condition.code()]);
+            self.push_label_fixup(label);
+         },
+         Target::Address{address} => match self.relative_displacement(address, 6) {
+            Some(displacement) => {
+               self.bytes.extend([0x0F, 0x80 | condition.code()]);
+               self.bytes.extend(displacement.to_le_bytes());
+            },
+            None => {
+               self.bytes.extend([0x70 | condition.inverse().code(), 0x0E]);
+               self.bytes.extend([0xFF, 0x25, 0x00, 0x00, 0x00, 0x00]);
+               self.bytes.extend((address as u64).to_le_bytes());
+            },
+         },
+         Target::Register{..}
+            => return Err(AsmError::InvalidOperands{mnemonic : "jcc"}),
+      }
+
+      return Ok(self);
+   }
+
+   /// Fills in jumps to labels and returns
+   /// the assembled bytes, or an error if
+   /// a label used by a jump was never bound.
+   pub fn finish(
+      mut self,
+   ) -> Result<Vec<u8>> {
+      for fixup in std::mem::take(&mut self.fixups) {
+         let target = match self.labels.get(fixup.label.id) {
+            Some(Some(target))   => *target,
+            _                    => return Err(AsmError::UnboundLabel{label : fixup.label.id}),
+         };
+
+         // Code can't be larger than 2GiB
+         // in practice, so this never wraps
+         let displacement = (target as i64 - fixup.instruction_end as i64) as i32;
+         if let Some(slot) = self.bytes.get_mut(fixup.displacement..fixup.instruction_end) {
+            slot.copy_from_slice(&displacement.to_le_bytes());
+         }
+      }
+
+      return Ok(self.bytes);
+   }
+
+   fn arithmetic(
+      & mut self,
+      mnemonic    : &'static str,
+      digit       : u8,
+      destination : Operand,
+      source      : Operand,
+   ) -> Result<& mut Self> {
+      // Each operation has the same set of
+      // encodings, offset by its digit
+      let base = digit << 3;
+
+      let encoding = match (destination, source) {
+         (Operand::Register{register : destination, size : size_destination},
+          Operand::Register{register : source,      size : size_source})
+            if size_destination == size_source
+            => self.encode_modrm(&[opcode_sized(base, size_source)], size_source, source.number(), true, RegisterOrMemory::Register(destination), 0)?,
+         (Operand::Memory{address,     size : size_memory},
+          Operand::Register{register, size : size_register})
+            if size_register == size_memory
+            => self.encode_modrm(&[opcode_sized(base, size_register)], size_register, register.number(), true, RegisterOrMemory::Memory(address), 0)?,
+         (Operand::Register{register, size : size_register},
+          Operand::Memory{address,     size : size_memory})
+            if size_register == size_memory
+            => self.encode_modrm(&[opcode_sized(base | 0x02, size_register)], size_register, register.number(), true, RegisterOrMemory::Memory(address), 0)?,
+         (Operand::Register{register, size}, Operand::Immediate{value})
+            => self.encode_arithmetic_immediate(mnemonic, digit, size, RegisterOrMemory::Register(register), value)?,
+         (Operand::Memory{address, size}, Operand::Immediate{value})
+            => self.encode_arithmetic_immediate(mnemonic, digit, size, RegisterOrMemory::Memory(address), value)?,
+         _  => return Err(AsmError::InvalidOperands{mnemonic : mnemonic}),
+      };
+
+      self.bytes.extend(encoding);
+      return Ok(self);
+   }
+
+   fn encode_arithmetic_immediate(
+      & self,
+      mnemonic    : &'static str,
+      digit       : u8,
+      size        : Size,
+      operand     : RegisterOrMemory,
+      value       : i64,
+   ) -> Result<Vec<u8>> {
+      // Prefer the sign-extended 8-bit form
+      let (opcode, immediate) = match (size, i8::try_from(value)) {
+         (Size::Byte, _)
+            => (0x80, immediate_bytes(mnemonic, value, size)?),
+         (_, Ok(value))
+            => (0x83, vec![value as u8]),
+         (_, Err(_))
+            => (0x81, immediate_bytes(mnemonic, value, size)?),
+      };
+
+      let mut encoding = self.encode_modrm(&[opcode], size, digit, false, operand, immediate.len())?;
+      encoding.extend(immediate);
+      return Ok(encoding);
+   }
+
+   // Encodes the prefixes, opcode, ModRM byte
+   // and any SIB byte and displacement of an
+   // instruction appended to the code, which
+   // is followed by an immediate of a length
+   fn encode_modrm(
+      & self,
+      opcode            : & [u8],
+      size              : Size,
+      reg               : u8,
+      reg_is_register   : bool,
+      operand           : RegisterOrMemory,
+      immediate_length  : usize,
+   ) -> Result<Vec<u8>> {
+      const MOD_INDIRECT      : u8 = 0b00;
+      const MOD_DISP8         : u8 = 0b01;
+      const MOD_DISP32        : u8 = 0b10;
+      const MOD_REGISTER      : u8 = 0b11;
+      const RM_SIB            : u8 = 0b100;
+      const RM_RBP            : u8 = 0b101;
+
+      let mut encoding = Vec::with_capacity(16);
+      if size == Size::Word {
+         encoding.push(0x66);
+      }
+
+      // Byte registers above bl are only
+      // addressable with a REX prefix,
+      // otherwise they mean ah through bh
+      let byte_register = |register : u8| size == Size::Byte && (4..8).contains(&register);
+      let (index, base) = match operand {
+         RegisterOrMemory::Register(register)
+            => (0, register.number()),
+         RegisterOrMemory::Memory(Address::Base{base, ..})
+            => (0, base.number()),
+         RegisterOrMemory::Memory(Address::Indexed{base, index, ..})
+            => (index.number(), base.number()),
+         RegisterOrMemory::Memory(Address::Absolute{..})
+            => (0, 0),
+      };
+      let force_rex = (reg_is_register == true && byte_register(reg) == true)
+         || matches!(operand, RegisterOrMemory::Register(register) if byte_register(register.number()) == true);
+
+      let rex = crate::compiler::rex_prefix(size == Size::Qword, reg, index, base);
+      if rex != crate::compiler::REX_PREFIX || force_rex == true {
+         encoding.push(rex);
+      }
+      encoding.extend_from_slice(opcode);
+
+      let (displacement, address) = match operand {
+         RegisterOrMemory::Register(register) => {
+            encoding.push(crate::compiler::modrm(MOD_REGISTER, reg, register.number()));
+            return Ok(encoding);
+         },
+         RegisterOrMemory::Memory(Address::Absolute{address}) => {
+            encoding.push(crate::compiler::modrm(MOD_INDIRECT, reg, RM_RBP));
+            let instruction_length = encoding.len() + 4 + immediate_length;
+            let displacement = self.relative_displacement(address, instruction_length)
+               .ok_or(AsmError::AddressOutOfRange{address : address})?;
+            encoding.extend(displacement.to_le_bytes());
+            return Ok(encoding);
+         },
+         RegisterOrMemory::Memory(Address::Base{base, displacement}) => {
+            (displacement, base)
+         },
+         RegisterOrMemory::Memory(Address::Indexed{base, index, scale, displacement}) => {
+            if matches!(scale, 1 | 2 | 4 | 8) == false {
+               return Err(AsmError::InvalidScale{scale : scale});
+            }
+            if index == Register::Rsp {
+               return Err(AsmError::InvalidIndex{index : index});
+            }
+
+            (displacement, base)
+         },
+      };
+
+      // rbp and r13 can't be used as a base
+      // without a displacement, since that
+      // encoding means something else
+      let base_number = address.number() & 0x07;
+      let mode = match i8::try_from(displacement) {
+         Ok(0) if base_number != RM_RBP   => MOD_INDIRECT,
+         Ok(_)                            => MOD_DISP8,
+         Err(_)                           => MOD_DISP32,
+      };
+
+      // rsp and r12 always need a SIB byte
+      match operand {
+         RegisterOrMemory::Memory(Address::Indexed{index, scale, ..}) => {
+            encoding.push(crate::compiler::modrm(mode, reg, RM_SIB));
+            encoding.push(crate::compiler::sib(scale, index.number(), base_number));
+         },
+         _ if base_number == RM_SIB => {
+            encoding.push(crate::compiler::modrm(mode, reg, RM_SIB));
+            encoding.push(0x24);
+         },
+         _ => {
+            encoding.push(crate::compiler::modrm(mode, reg, base_number));
+         },
+      }
+
+      match mode {
+         MOD_DISP8   => encoding.push(displacement as i8 as u8),
+         MOD_DISP32  => encoding.extend(displacement.to_le_bytes()),
+         _           => (),
+      }
+
+      return Ok(encoding);
+   }
+
+   // Gets the displacement from the end of an
+   // instruction appended to the code to an
+   // address, if it fits in 32 bits
+   fn relative_displacement(
+      & self,
+      address              : usize,
+      instruction_length   : usize,
+   ) -> Option<i32> {
+      let instruction_end = self.address().wrapping_add(instruction_length);
+      return i32::try_from(address.wrapping_sub(instruction_end) as isize).ok();
+   }
+
+   fn push_label_fixup(
+      & mut self,
+      label : Label,
+   ) {
+      let displacement = self.bytes.len();
+      self.bytes.extend([0x00; 4]);
+      self.fixups.push(LabelFixup{
+         label             : label,
+         displacement      : displacement,
+         instruction_end   : self.bytes.len(),
+      });
+      return;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+// Byte operations use the opcode below
+// the one for every other size
+fn opcode_sized(
+   opcode_byte : u8,
+   size        : Size,
+) -> u8 {
+   return match size {
+      Size::Byte  => opcode_byte,
+      _           => opcode_byte + 1,
+   };
+}
+
+fn opcode_plus_register(
+   opcode   : u8,
+   register : Register,
+) -> Vec<u8> {
+   let number = register.number();
+   return match number >> 3 {
+      0 => vec![opcode | number],
+      _ => vec![0x41, opcode | (number & 0x07)],
+   };
+}
+
+// Encodes an immediate for an operand size,
+// where 64-bit operands take a sign-extended
+// 32-bit immediate
+fn immediate_bytes(
+   mnemonic : &'static str,
+   value    : i64,
+   size     : Size,
+) -> Result<Vec<u8>> {
+   let out_of_range = || AsmError::ImmediateOutOfRange{
+      mnemonic : mnemonic,
+      value    : value,
+   };
+
+   // Sizes smaller than 64 bits accept
+   // both signed and unsigned values
+   return Ok(match size {
+      Size::Byte
+         => vec![i8::try_from(value).map(|value| value as u8).or(u8::try_from(value)).map_err(|_| out_of_range())?],
+      Size::Word
+         => i16::try_from(value).map(|value| value as u16).or(u16::try_from(value)).map_err(|_| out_of_range())?.to_le_bytes().to_vec(),
+      Size::Dword
+         => i32::try_from(value).map(|value| value as u32).or(u32::try_from(value)).map_err(|_| out_of_range())?.to_le_bytes().to_vec(),
+      Size::Qword
+         => i32::try_from(value).map_err(|_| out_of_range())?.to_le_bytes().to_vec(),
+   });
+}
@@ -0,0 +1,54 @@
+//! System clipboard access.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to the clipboard.
+#[derive(Debug)]
+pub enum ClipboardError {
+   InvalidTextCharacters,
+   Unsupported,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ClipboardError</code>.
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ClipboardError //
+////////////////////////////////////////////
+
+impl std::fmt::Display for ClipboardError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::InvalidTextCharacters
+            => "Text contains invalid characters",
+         Self::Unsupported
+            => "No clipboard is available",
+         Self::Unknown
+            => "Unknown",
+      });
+   }
+}
+
+impl std::error::Error for ClipboardError {
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Replaces the contents of the
+/// system clipboard with text.
+pub fn set_text(
+   text : & str,
+) -> Result<()> {
+   return crate::os::clipboard::set_text(text);
+}
+
@@ -1,5 +1,25 @@
 //! Code compilation functions.
 
+/// Length of a conditional jump with
+/// the shortest displacement.
+pub const BRANCH_SHORT_LENGTH : usize
+   = crate::cpu::compiler::BRANCH_SHORT_LENGTH;
+
+/// Length of a conditional jump with
+/// the longest displacement.
+pub const BRANCH_NEAR_LENGTH : usize
+   = crate::cpu::compiler::BRANCH_NEAR_LENGTH;
+
+/// Maximum number of hooks which can
+/// be linked into a single hook chain.
+pub const HOOK_CHAIN_CAPACITY : usize = 16;
+
+// Identifies a hook chain header to every
+// module, and changes whenever its layout
+// does so incompatible modules never
+// share a chain
+const HOOK_CHAIN_MAGIC : u64 = u64::from_le_bytes(*b"NUSHOOK1");
+
 //////////////////////
 // TYPE DEFINITIONS //
 //////////////////////
@@ -11,6 +31,15 @@ pub enum CompilationError {
       instruction_length   : usize,
       buffer_length        : usize,
    },
+   UnrelocatableInstruction{
+      offset               : usize,
+   },
+   AllocationFailed,
+   UnexpectedInstruction,
+   HookChainFull{
+      capacity             : usize,
+   },
+   HookChainRetired,
 }
 
 /// <code>Result</code> type with error
@@ -21,6 +50,73 @@ pub type Result<T> = std::result::Result<T, CompilationError>;
 /// for use in <code>hook_fill</code>.
 pub type HookTarget = unsafe extern "C" fn();
 
+/// A hook site shared by hooks from any
+/// number of modules.  The site jumps to
+/// a stub which calls every linked hook,
+/// newest first, and the original bytes
+/// are only restored once the last hook
+/// is unlinked, no matter which order the
+/// hooks are unlinked in.  The chain lives
+/// in memory which is never freed, and its
+/// layout is identified by a magic number,
+/// so separately built modules can find and
+/// link into each other's chains.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HookChain {
+   header_address : usize,
+}
+
+/// Stored at the start of a hook chain's
+/// stub.  This is shared between modules,
+/// so any change to it must also change
+/// <code>HOOK_CHAIN_MAGIC</code>.
+#[repr(C)]
+struct HookChainHeader {
+   magic          : u64,
+   lock           : std::sync::atomic::AtomicBool,
+   jmp_back       : bool,
+   site_address   : std::sync::atomic::AtomicUsize,
+   site_length    : usize,
+   original_bytes : usize,
+   empty_link     : usize,
+   links          : [std::sync::atomic::AtomicUsize; HOOK_CHAIN_CAPACITY],
+}
+
+/// Holds a hook chain's lock
+/// until it is dropped.
+struct HookChainLock<'h> {
+   header : &'h HookChainHeader,
+}
+
+/// A jump found at the start of a memory
+/// buffer, such as one written by another
+/// hooking library.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JumpInstruction {
+   pub target_address   : usize,
+   pub length           : usize,
+}
+
+/// The result of <code>hook_fill_inline</code>.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineHookFill {
+   pub stolen_length : usize,
+   pub emulated      : Vec<EmulatedInstruction>,
+}
+
+/// A stolen instruction which couldn't be
+/// relocated as-is, so it was rewritten to
+/// use the absolute address it references.
+/// The offset is relative to the start of
+/// the hooked memory buffer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmulatedInstruction {
+   pub offset           : usize,
+   pub length           : usize,
+   pub kind             : crate::decoder::ReferenceKind,
+   pub target_address   : usize,
+}
+
 //////////////////////////////////////////////
 // TRAIT IMPLEMENTATIONS - CompilationError //
 //////////////////////////////////////////////
@@ -35,6 +131,16 @@ impl std::fmt::Display for CompilationError {
             => write!(stream, "Impossible instruction encoding"),
          Self::BufferTooSmall {instruction_length, buffer_length}
             => write!(stream, "Buffer is too small for instruction encoding: Requires at least {instruction_length}, found {buffer_length}"),
+         Self::UnrelocatableInstruction {offset}
+            => write!(stream, "Instruction at offset {offset} cannot be relocated"),
+         Self::AllocationFailed
+            => write!(stream, "Failed to allocate executable memory"),
+         Self::UnexpectedInstruction
+            => write!(stream, "Memory buffer doesn't contain the expected instruction"),
+         Self::HookChainFull {capacity}
+            => write!(stream, "Hook chain already holds the maximum of {capacity} hooks"),
+         Self::HookChainRetired
+            => write!(stream, "Hook chain was removed while linking into it"),
       };
    }
 }
@@ -42,6 +148,164 @@ impl std::fmt::Display for CompilationError {
 impl std::error::Error for CompilationError {
 }
 
+/////////////////////////
+// METHODS - HookChain //
+/////////////////////////
+
+impl HookChain {
+   /// Gets the length of the hooked
+   /// memory buffer.
+   pub fn site_length(
+      & self,
+   ) -> usize {
+      return self.header().site_length;
+   }
+
+   /// Checks whether the original
+   /// instructions are executed after
+   /// the hooks are called.
+   pub fn jmp_back(
+      & self,
+   ) -> bool {
+      return self.header().jmp_back;
+   }
+
+   /// Copies the bytes which were in the
+   /// memory buffer before it was hooked.
+   pub fn original_bytes(
+      & self,
+   ) -> Vec<u8> {
+      let header = self.header();
+      return unsafe{std::slice::from_raw_parts(
+         header.original_bytes as * const u8,
+         header.site_length,
+      )}.to_vec();
+   }
+
+   /// Links a hook into the chain, returning
+   /// the index of its link.  The hook is
+   /// called before every hook linked
+   /// before it.
+   pub fn link(
+      & self,
+      hook : HookTarget,
+   ) -> Result<usize> {
+      let header  = self.header();
+      let _lock   = HookChainLock::acquire(header);
+
+      if header.site_address.load(std::sync::atomic::Ordering::Acquire) == 0 {
+         return Err(CompilationError::HookChainRetired);
+      }
+
+      // Links are called from the highest index
+      // down, so take the slot above the newest
+      // hook to keep the order even after older
+      // hooks are unlinked
+      let index = header.links.iter()
+         .rposition(|link| link.load(std::sync::atomic::Ordering::Acquire) != header.empty_link)
+         .map(|index| index + 1)
+         .unwrap_or(0);
+
+      let link = header.links.get(index).ok_or(CompilationError::HookChainFull{
+         capacity : HOOK_CHAIN_CAPACITY,
+      })?;
+      link.store(hook as usize, std::sync::atomic::Ordering::Release);
+
+      return Ok(index);
+   }
+
+   /// Unlinks a hook linked with <code>link</code>.
+   /// Once no hooks are left, the original
+   /// bytes are restored to the memory buffer
+   /// and the chain can't be linked into again.
+   pub fn unlink(
+      & self,
+      index : usize,
+      hook  : HookTarget,
+   ) -> crate::memory::Result<()> {
+      let header  = self.header();
+      let _lock   = HookChainLock::acquire(header);
+
+      // Another hook may have taken the link if
+      // the chain was retired and recreated
+      if let Some(link) = header.links.get(index) {
+         let _ = link.compare_exchange(
+            hook as usize,
+            header.empty_link,
+            std::sync::atomic::Ordering::AcqRel,
+            std::sync::atomic::Ordering::Acquire,
+         );
+      }
+
+      let empty = header.links.iter()
+         .all(|link| link.load(std::sync::atomic::Ordering::Acquire) == header.empty_link);
+      let site_address = header.site_address.load(std::sync::atomic::Ordering::Acquire);
+      if empty == false || site_address == 0 {
+         return Ok(());
+      }
+
+      let site_range = site_address..site_address + header.site_length;
+      let mut editor = crate::memory::MemoryEditor::open_read_write(site_range)?;
+      let site = unsafe{editor.as_bytes_mut()};
+
+      // Don't clobber something else which
+      // overwrote the site in the meantime
+      if hook_chain_find(site, site_address) == Some(*self) {
+         site.copy_from_slice(unsafe{std::slice::from_raw_parts(
+            header.original_bytes as * const u8,
+            header.site_length,
+         )});
+      }
+      header.site_address.store(0, std::sync::atomic::Ordering::Release);
+
+      return Ok(());
+   }
+
+   fn header(
+      & self,
+   ) -> &'static HookChainHeader {
+      return unsafe{&*(self.header_address as * const HookChainHeader)};
+   }
+}
+
+/////////////////////////////
+// METHODS - HookChainLock //
+/////////////////////////////
+
+impl<'h> HookChainLock<'h> {
+   fn acquire(
+      header : &'h HookChainHeader,
+   ) -> Self {
+      // Other modules may hold the lock, so
+      // this can't be a mutex from this one
+      while header.lock.compare_exchange_weak(
+         false,
+         true,
+         std::sync::atomic::Ordering::Acquire,
+         std::sync::atomic::Ordering::Relaxed,
+      ).is_err() == true {
+         std::hint::spin_loop();
+      }
+
+      return Self{
+         header : header,
+      };
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookChainLock //
+///////////////////////////////////////////
+
+impl<'h> std::ops::Drop for HookChainLock<'h> {
+   fn drop(
+      & mut self,
+   ) {
+      self.header.lock.store(false, std::sync::atomic::Ordering::Release);
+      return;
+   }
+}
+
 ///////////////
 // FUNCTIONS //
 ///////////////
@@ -62,25 +326,477 @@ pub fn nop_fill(
 /// rest of the buffer is filled
 /// with architecture-dependent
 /// no-operation (NOP) instructions.
+/// Hooks too far away for a short call
+/// are called through a trampoline
+/// allocated near the address.
 ///
 /// <h2 id=  hook_fill_note>
 /// <a href=#hook_fill_note>
 /// Note
 /// </a></h2>
-/// The compiled code expects to
-/// never be moved to a new memory
-/// location.  Copying the memory
-/// buffer slice to a new region
-/// will lead to invalid code.
-/// If you want to clone a compiled,
-/// hook, it must be re-compiled
-/// in the new memory buffer.
+/// The compiled code only works at
+/// the given address, which is where
+/// the memory buffer will be executed.
+/// The memory buffer may be a separate
+/// copy as long as it is then copied to
+/// the address unchanged.  If you want
+/// to clone a compiled hook, it must
+/// be re-compiled for the new address.
 pub fn hook_fill(
    memory_buffer  : & mut [u8],
+   address        : usize,
    hook           : HookTarget,
 ) -> Result<()> {
+   // Route calls to far away hooks through a
+   // trampoline so the call itself stays short,
+   // falling back to a long call if none fits
+   let hook = match crate::memory::is_near(
+      address, &(hook as usize..hook as usize),
+   ) {
+      true  => hook,
+      false => allocate_trampoline(address, hook as usize)
+         .unwrap_or(hook),
+   };
+
    return crate::cpu::compiler::hook_fill(
-      memory_buffer, hook,
+      memory_buffer, address, hook,
+   );
+}
+
+
+/// Compiles a jump to a newly allocated
+/// stub which calls a function, executes
+/// the instructions originally stored in
+/// the memory buffer, then jumps back to
+/// the end of the memory buffer.  The
+/// rest of the buffer is filled with
+/// architecture-dependent no-operation
+/// (NOP) instructions.  Unlike <code>hook_fill</code>,
+/// the function only needs to return
+/// normally and doesn't need to execute
+/// the overwritten instructions itself.
+///
+/// The overwritten instructions must be
+/// whole and must not depend on their
+/// own address, otherwise an error is
+/// returned.  The stub is never freed,
+/// since a thread may still be executing
+/// it after the memory buffer is restored.
+///
+/// <h2 id=  hook_fill_jmp_back_note>
+/// <a href=#hook_fill_jmp_back_note>
+/// Note
+/// </a></h2>
+///
+/// All notes from <code><a href=
+/// #hook_fill_note>hook_fill</a></code>
+/// apply.
+pub fn hook_fill_jmp_back(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   hook           : HookTarget,
+) -> Result<()> {
+   crate::cpu::compiler::verify_relocatable(memory_buffer, address)?;
+
+   let stub_range = allocate_stub(
+      address,
+      crate::cpu::compiler::hook_stub_length(memory_buffer.len()),
+   )?;
+
+   let stub = unsafe{std::slice::from_raw_parts_mut(
+      stub_range.start as * mut u8,
+      stub_range.end - stub_range.start,
+   )};
+
+   return crate::cpu::compiler::hook_fill_jmp_back(
+      memory_buffer, address, stub, hook,
    );
 }
 
+/// Compiles a jump to a newly allocated
+/// stub which calls a function, executes
+/// relocated copies of the instructions
+/// overwritten by the jump, then jumps back
+/// to the first instruction after them.
+/// Returns the number of bytes overwritten
+/// at the start of the memory buffer, which
+/// is only as many whole instructions as the
+/// jump needs.  The rest of the buffer is
+/// left untouched.
+///
+/// Unlike <code>hook_fill_jmp_back</code>,
+/// the overwritten instructions may depend
+/// on their own address.  Relative branches
+/// are rewritten to reach their original
+/// targets, and instruction pointer-relative
+/// memory operands are adjusted as long as
+/// the stub is within reach of the target.
+/// If it isn't and <code>emulate</code> is
+/// set, a <code>lea</code> or <code>mov</code>
+/// with such an operand is rewritten to use
+/// the absolute address instead, and is listed
+/// in the returned <code>InlineHookFill</code>.
+/// Anything the stub pushes goes below the
+/// red zone on System V targets, so the
+/// interrupted function's locals are left
+/// alone.  The stub is never freed, since a thread
+/// may still be executing it after the
+/// memory buffer is restored.
+///
+/// <h2 id=  hook_fill_inline_note>
+/// <a href=#hook_fill_inline_note>
+/// Note
+/// </a></h2>
+///
+/// All notes from <code><a href=
+/// #hook_fill_note>hook_fill</a></code>
+/// apply.
+pub fn hook_fill_inline(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   hook           : HookTarget,
+   emulate        : bool,
+) -> Result<InlineHookFill> {
+   let stub_range = allocate_stub(
+      address,
+      crate::cpu::compiler::inline_hook_stub_length(memory_buffer)?,
+   )?;
+
+   let stub = unsafe{std::slice::from_raw_parts_mut(
+      stub_range.start as * mut u8,
+      stub_range.end - stub_range.start,
+   )};
+
+   return crate::cpu::compiler::hook_fill_inline(
+      memory_buffer, address, stub, hook, emulate,
+   );
+}
+
+/// Decodes an unconditional jump at the
+/// start of the memory buffer, including
+/// the absolute jumps hooking libraries
+/// write when their stub is out of reach.
+/// Relative jumps are resolved as if the
+/// memory buffer were stored at the address.
+pub fn jump_instruction(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<JumpInstruction> {
+   let (target_address, length) = crate::cpu::compiler::jump_instruction(memory_buffer, address)?;
+
+   return Some(JumpInstruction{
+      target_address : target_address,
+      length         : length,
+   });
+}
+
+/// Compiles an inline hook which runs after
+/// the hook another hooking library wrote
+/// at the start of the memory buffer.  Their
+/// jump is left in place, and the inline hook
+/// is written where their hook continues,
+/// which is after the jump and any padding
+/// following it.  Offsets in the returned
+/// <code>InlineHookFill</code> are relative
+/// to the start of the memory buffer, and
+/// the stolen length doesn't include their
+/// jump.
+///
+/// <h2 id=  hook_fill_foreign_note>
+/// <a href=#hook_fill_foreign_note>
+/// Note
+/// </a></h2>
+///
+/// All notes from <code><a href=
+/// #hook_fill_inline_note>hook_fill_inline</a></code>
+/// apply.  The memory buffer must start
+/// with a jump recognized by
+/// <code>jump_instruction</code>, and their
+/// hook must continue at the first byte which
+/// isn't a <code>nop</code> or <code>int3</code>
+/// after it.
+pub fn hook_fill_foreign(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   hook           : HookTarget,
+   emulate        : bool,
+) -> Result<InlineHookFill> {
+   let continuation = crate::cpu::compiler::foreign_hook_continuation(memory_buffer, address)
+      .ok_or(CompilationError::UnexpectedInstruction)?;
+
+   let mut fill = hook_fill_inline(
+      &mut memory_buffer[continuation..],
+      address + continuation,
+      hook,
+      emulate,
+   )?;
+
+   for instruction in fill.emulated.iter_mut() {
+      instruction.offset += continuation;
+   }
+
+   return Ok(fill);
+}
+
+/// Finds the hook chain the memory buffer
+/// jumps to, if the memory at the address
+/// was hooked with <code>hook_chain_fill</code>
+/// by any module.  The memory buffer holds
+/// the bytes stored at the address.
+pub fn hook_chain_find(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<HookChain> {
+   let entry         = crate::cpu::compiler::jump_target(memory_buffer, address)?;
+   let header_address = entry.checked_sub(1 + std::mem::size_of::<HookChainHeader>())?;
+
+   // The jump may lead anywhere, so only
+   // trust it once the magic number and
+   // site address match
+   let mut magic = [0u8; std::mem::size_of::<u64>()];
+   crate::memory::read_checked(header_address, &mut magic).ok()?;
+   if u64::from_le_bytes(magic) != HOOK_CHAIN_MAGIC {
+      return None;
+   }
+
+   let chain = HookChain{
+      header_address : header_address,
+   };
+   let site_address = chain.header().site_address.load(std::sync::atomic::Ordering::Acquire);
+   if site_address != address {
+      return None;
+   }
+
+   return Some(chain);
+}
+
+/// Compiles a jump to a new hook chain with
+/// no hooks linked, which executes the
+/// overwritten instructions afterwards if
+/// <code>jmp_back</code> is true, or skips
+/// them otherwise.  Link hooks into the
+/// returned chain with <code>HookChain::link</code>.
+///
+/// <h2 id=  hook_chain_fill_note>
+/// <a href=#hook_chain_fill_note>
+/// Note
+/// </a></h2>
+///
+/// All notes from <code><a href=
+/// #hook_fill_jmp_back_note>hook_fill_jmp_back</a></code>
+/// apply when <code>jmp_back</code> is true.
+pub fn hook_chain_fill(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   jmp_back       : bool,
+) -> Result<HookChain> {
+   if jmp_back == true {
+      crate::cpu::compiler::verify_relocatable(memory_buffer, address)?;
+   }
+
+   let header_length = std::mem::size_of::<HookChainHeader>();
+   let code_length   = crate::cpu::compiler::hook_chain_stub_length(
+      memory_buffer.len(), HOOK_CHAIN_CAPACITY,
+   );
+   let stub_range = allocate_stub(
+      address,
+      header_length + code_length + memory_buffer.len(),
+   )?;
+
+   let header_address   = stub_range.start;
+   let code_address     = header_address + header_length;
+   let original_address = code_address + code_length;
+
+   // The empty link is the ret at the
+   // start of the code, so every link can
+   // be called before any hook is linked
+   let original = unsafe{std::slice::from_raw_parts_mut(
+      original_address as * mut u8,
+      memory_buffer.len(),
+   )};
+   original.copy_from_slice(memory_buffer);
+
+   unsafe{std::ptr::write(header_address as * mut HookChainHeader, HookChainHeader{
+      magic          : HOOK_CHAIN_MAGIC,
+      lock           : std::sync::atomic::AtomicBool::new(false),
+      jmp_back       : jmp_back,
+      site_address   : std::sync::atomic::AtomicUsize::new(address),
+      site_length    : memory_buffer.len(),
+      original_bytes : original_address,
+      empty_link     : code_address,
+      links          : std::array::from_fn(|_| std::sync::atomic::AtomicUsize::new(code_address)),
+   })};
+
+   let code = unsafe{std::slice::from_raw_parts_mut(
+      code_address as * mut u8,
+      code_length,
+   )};
+   let links_address = header_address + std::mem::offset_of!(HookChainHeader, links);
+   crate::cpu::compiler::hook_chain_fill(
+      memory_buffer, address, code, links_address, HOOK_CHAIN_CAPACITY, jmp_back,
+   )?;
+
+   return Ok(HookChain{
+      header_address : header_address,
+   });
+}
+
+/// Replaces the call instruction filling
+/// the memory buffer with no-operation
+/// (NOP) instructions.  The call's return
+/// value is left as whatever was in the
+/// return register.  If the buffer doesn't
+/// hold exactly one call, an error is
+/// returned and nothing is written.
+pub fn call_nop_fill(
+   memory_buffer  : & mut [u8],
+) -> Result<()> {
+   return crate::cpu::compiler::call_nop_fill(
+      memory_buffer,
+   );
+}
+
+/// Replaces the conditional jump filling
+/// the memory buffer with a jump to the
+/// same target if it should always be
+/// taken, or no-operation (NOP) instructions
+/// if it should never be taken.  If the
+/// buffer doesn't hold exactly one conditional
+/// jump, an error is returned and nothing
+/// is written.  The jump's target is resolved
+/// as if the memory buffer were stored at
+/// the address.
+pub fn branch_fill(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   taken          : bool,
+) -> Result<()> {
+   return crate::cpu::compiler::branch_fill(
+      memory_buffer, address, taken,
+   );
+}
+
+/// Inverts the condition of the conditional
+/// jump filling the memory buffer, keeping
+/// its target.  If the buffer doesn't hold
+/// exactly one conditional jump with an
+/// invertible condition, an error is returned
+/// and nothing is written.
+pub fn branch_invert_fill(
+   memory_buffer  : & mut [u8],
+) -> Result<()> {
+   return crate::cpu::compiler::branch_invert_fill(
+      memory_buffer,
+   );
+}
+
+/// Gets the length of the instructions
+/// compiled by <code>return_fill</code>
+/// for a return value.
+pub fn return_length(
+   value : Option<u64>,
+) -> usize {
+   return crate::cpu::compiler::return_length(
+      value,
+   );
+}
+
+/// Compiles a return from the current
+/// function inside a memory buffer,
+/// first setting the return register
+/// to a value if there is one.  The rest
+/// of the buffer is filled with no-operation
+/// (NOP) instructions.
+pub fn return_fill(
+   memory_buffer  : & mut [u8],
+   value          : Option<u64>,
+) -> Result<()> {
+   return crate::cpu::compiler::return_fill(
+      memory_buffer, value,
+   );
+}
+
+/// The REX prefix with none of its
+/// bits set, which can be left out
+/// unless a byte register needs it.
+pub const REX_PREFIX : u8
+   = crate::cpu::compiler::REX_PREFIX;
+
+/// Encodes the REX prefix for an
+/// instruction's operand size and the
+/// register numbers in its ModRM and
+/// SIB bytes.
+pub fn rex_prefix(
+   wide  : bool,
+   reg   : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return crate::cpu::compiler::rex_prefix(
+      wide, reg, index, base,
+   );
+}
+
+/// Encodes a ModRM byte.  Only the low
+/// three bits of each register number
+/// are used, the rest are encoded by
+/// <code>rex_prefix</code>.
+pub fn modrm(
+   mode  : u8,
+   reg   : u8,
+   rm    : u8,
+) -> u8 {
+   return crate::cpu::compiler::modrm(
+      mode, reg, rm,
+   );
+}
+
+/// Encodes a SIB byte for a scale of
+/// 1, 2, 4 or 8.  Only the low three
+/// bits of each register number are
+/// used, the rest are encoded by
+/// <code>rex_prefix</code>.
+pub fn sib(
+   scale : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return crate::cpu::compiler::sib(
+      scale, index, base,
+   );
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn allocate_stub(
+   address  : usize,
+   size     : usize,
+) -> Result<std::ops::Range<usize>> {
+   // Prefer memory within reach of a short jump,
+   // but a far stub still works with a long one
+   if let Ok(range) = crate::memory::allocate_executable_near(address, size) {
+      return Ok(range);
+   }
+
+   return crate::os::memory::allocate_executable(size)
+      .map_err(|_| CompilationError::AllocationFailed);
+}
+
+fn allocate_trampoline(
+   address  : usize,
+   target   : usize,
+) -> Result<HookTarget> {
+   let range = crate::memory::allocate_executable_near(
+      address, crate::cpu::compiler::TRAMPOLINE_LENGTH,
+   ).map_err(|_| CompilationError::AllocationFailed)?;
+
+   let trampoline = unsafe{std::slice::from_raw_parts_mut(
+      range.start as * mut u8,
+      range.end - range.start,
+   )};
+   crate::cpu::compiler::trampoline_fill(trampoline, target)?;
+
+   return Ok(unsafe{std::mem::transmute::<usize, HookTarget>(range.start)});
+}
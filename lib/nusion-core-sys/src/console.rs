@@ -9,6 +9,8 @@
 #[derive(Debug)]
 pub enum ConsoleError {
    InvalidTitleCharacters,
+   NoParentConsole,
+   Unsupported,
    Unknown,
 }
 
@@ -24,6 +26,44 @@ pub struct Console {
    console : crate::os::console::Console,
 }
 
+/// Captures everything the process writes
+/// to its standard output and error, copying
+/// each to where it originally pointed and
+/// both to an optional file.  The original
+/// streams are restored when this is dropped.
+pub struct OutputRedirect {
+   redirect : crate::os::console::OutputRedirect,
+   threads  : Vec<std::thread::JoinHandle<()>>,
+}
+
+/// A key read from the console's input
+/// by a <code>KeyReader</code>.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConsoleKey {
+   Character(char),
+   Enter,
+   Tab,
+   Backspace,
+   Delete,
+   Escape,
+   Left,
+   Right,
+   Up,
+   Down,
+   Home,
+   End,
+   EndOfInput,
+}
+
+/// Reads individual key presses from
+/// the console's input instead of whole
+/// lines, without echoing them.  The
+/// input's previous mode is restored
+/// when this is dropped.
+pub struct KeyReader {
+   reader : crate::os::console::KeyReader,
+}
+
 //////////////////////////////////////////
 // TRAIT IMPLEMENTATIONS - ConsoleError //
 //////////////////////////////////////////
@@ -36,6 +76,10 @@ impl std::fmt::Display for ConsoleError {
       return write!(stream, "{}", match self {
          Self::InvalidTitleCharacters
             => "Title contains invalid characters",
+         Self::NoParentConsole
+            => "The parent process has no console to attach to",
+         Self::Unsupported
+            => "Unsupported on this platform",
          Self::Unknown
             => "Unknown",
       });
@@ -61,6 +105,17 @@ impl Console {
       });
    }
 
+   /// Attaches to the console of the process
+   /// which started the game, such as the
+   /// terminal it was launched from, instead
+   /// of creating a window.  The console's
+   /// title is left as-is.
+   pub fn attach() -> Result<Self> {
+      return Ok(Self{
+         console : crate::os::console::Console::attach()?,
+      });
+   }
+
    /// Copies the window title of the
    /// console into an owned String.
    pub fn get_title(
@@ -77,6 +132,165 @@ impl Console {
       self.console.set_title(new_title)?;
       return Ok(());
    }
+
+   /// Checks whether the user tried to
+   /// close the console, such as by pressing
+   /// Ctrl+C.  Instead of terminating the
+   /// whole process, the request is recorded
+   /// so the mod can unload itself.
+   pub fn is_close_requested(
+      & self,
+   ) -> bool {
+      return self.console.is_close_requested();
+   }
+
+   /// Reads up to the last <code>count</code>
+   /// lines of text shown in the console,
+   /// oldest first, with trailing whitespace
+   /// removed.
+   pub fn read_lines(
+      & self,
+      count : usize,
+   ) -> Result<Vec<String>> {
+      return self.console.read_lines(count);
+   }
+}
+
+/////////////////////////
+// METHODS - KeyReader //
+/////////////////////////
+
+impl KeyReader {
+   /// Switches the console's input to
+   /// reading individual keys.
+   pub fn new(
+   ) -> Result<Self> {
+      return Ok(Self{
+         reader : crate::os::console::KeyReader::new()?,
+      });
+   }
+
+   /// Blocks until a key is pressed.  Keys
+   /// which aren't represented are skipped.
+   pub fn read_key(
+      & mut self,
+   ) -> Result<ConsoleKey> {
+      return self.reader.read_key();
+   }
+
+   /// Waits until a key can be read without
+   /// blocking or the timeout passes, returning
+   /// whether a key is ready.  This allows a
+   /// thread reading keys to check whether it
+   /// should stop in between.
+   pub fn wait_for_key(
+      & mut self,
+      timeout : std::time::Duration,
+   ) -> Result<bool> {
+      return self.reader.wait_for_key(timeout);
+   }
+}
+
+//////////////////////////////
+// METHODS - OutputRedirect //
+//////////////////////////////
+
+impl OutputRedirect {
+   /// Starts capturing the process' standard
+   /// output and error.  On Windows, this
+   /// replaces the process' standard handles
+   /// and the descriptors of the C runtimes
+   /// the game loaded as libraries.  Output
+   /// written through a runtime linked into
+   /// the game itself isn't captured.  On
+   /// Linux, the file descriptors themselves
+   /// are replaced.
+   pub fn new(
+      output_file : Option<std::fs::File>,
+   ) -> Result<Self> {
+      use std::io::{Read, Write};
+
+      // Anything still buffered belongs
+      // to the original output
+      let _ = std::io::stdout().flush();
+      let _ = std::io::stderr().flush();
+
+      let (mut redirect, streams) = crate::os::console::OutputRedirect::capture()?;
+
+      // Both streams go to the same file
+      let output_file = std::sync::Arc::new(std::sync::Mutex::new(output_file));
+
+      let mut threads = Vec::with_capacity(streams.len());
+      for (mut reader, mut original) in streams {
+         let output_file = output_file.clone();
+         let thread = std::thread::Builder::new()
+            .name(String::from("nusion-output-redirect"))
+            .spawn(move || {
+               // Ends once every write end of
+               // the pipe has been closed
+               let mut buffer = [0u8; 4096];
+               loop {
+                  let count = match reader.read(& mut buffer) {
+                     Ok(0) | Err(_) => return,
+                     Ok(count)      => count,
+                  };
+
+                  if let Some(original) = original.as_mut() {
+                     let _ = original.write_all(&buffer[..count]);
+                  }
+
+                  let mut output_file = output_file.lock().unwrap_or_else(|poison| poison.into_inner());
+                  if let Some(output_file) = output_file.as_mut() {
+                     let _ = output_file.write_all(&buffer[..count]);
+                     let _ = output_file.flush();
+                  }
+               }
+            });
+
+         match thread {
+            Ok(thread)  => threads.push(thread),
+            Err(_)      => {
+               // Restoring ends the threads
+               // which were already started
+               redirect.restore();
+               for thread in threads {
+                  let _ = thread.join();
+               }
+               return Err(ConsoleError::Unknown);
+            },
+         }
+      }
+
+      return Ok(Self{
+         redirect : redirect,
+         threads  : threads,
+      });
+   }
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - OutputRedirect //
+////////////////////////////////////////////
+
+impl Drop for OutputRedirect {
+   fn drop(
+      & mut self,
+   ) {
+      use std::io::Write;
+
+      let _ = std::io::stdout().flush();
+      let _ = std::io::stderr().flush();
+
+      // Restoring the streams closes the
+      // last write ends of the pipes, which
+      // lets the threads copy what's left
+      self.redirect.restore();
+      for thread in self.threads.drain(..) {
+         let _ = thread.join();
+      }
+
+      return;
+   }
 }
 
 /////////////////////////////////////
@@ -30,6 +30,43 @@ fn build_instruction_encoding(
    return Ok(instruction_length);
 }
 
+//////////////////////
+// OPERAND ENCODING //
+//////////////////////
+
+pub const REX : u8 = 0x40;
+
+pub fn rex_prefix(
+   wide  : bool,
+   reg   : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return REX
+      | if wide == true {0x08} else {0}
+      | if reg   >= 8   {0x04} else {0}
+      | if index >= 8   {0x02} else {0}
+      | if base  >= 8   {0x01} else {0};
+}
+
+// The high bit of each register number
+// goes in the REX prefix instead
+pub fn modrm(
+   mode  : u8,
+   reg   : u8,
+   rm    : u8,
+) -> u8 {
+   return (mode << 6) | ((reg & 0x07) << 3) | (rm & 0x07);
+}
+
+pub fn sib(
+   scale : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return ((scale.trailing_zeros() as u8) << 6) | ((index & 0x07) << 3) | (base & 0x07);
+}
+
 //////////////////////////
 // INSTRUCTION BUILDERS //
 //////////////////////////
@@ -187,20 +224,25 @@ pub fn jmp_abs64(
    );
 }
 
+// The address is where the memory buffer
+// will be executed, which may differ from
+// the memory buffer's own address when it
+// is built in a separate buffer first
 pub fn jmp(
    memory_buffer  : & mut [u8],
+   address        : usize,
    target         : * const core::ffi::c_void,
 ) -> crate::compiler::Result<usize> {
-   let target  = target as * const u8;
-   let current = memory_buffer.as_ptr();
+   const JMP_REL8_LENGTH   : isize = 2;
+   const JMP_REL32_LENGTH  : isize = 5;
 
-   let offset = unsafe{target.offset_from(current)};
+   let offset = (target as usize).wrapping_sub(address) as isize;
 
-   if let Ok(offset) = i8  ::try_from(offset) {
-      return jmp_rel8   (memory_buffer, offset);
+   if i8  ::try_from(offset.wrapping_sub(JMP_REL8_LENGTH)).is_ok() == true {
+      return jmp_rel8   (memory_buffer, offset as i8);
    }
-   if let Ok(offset) = i32 ::try_from(offset) {
-      return jmp_rel32  (memory_buffer, offset);
+   if i32 ::try_from(offset.wrapping_sub(JMP_REL32_LENGTH)).is_ok() == true {
+      return jmp_rel32  (memory_buffer, offset as i32);
    }
 
    return jmp_abs64(memory_buffer, target as u64);
@@ -228,19 +270,180 @@ pub fn call_abs64(
    );
 }
 
+// The address is where the memory buffer
+// will be executed, like with jmp
 pub fn call(
    memory_buffer  : & mut [u8],
+   address        : usize,
    target         : * const core::ffi::c_void,
 ) -> crate::compiler::Result<usize> {
-   let target  = target as * const u8;
-   let current = memory_buffer.as_ptr();
+   const CALL_REL32_LENGTH : isize = 5;
 
-   let offset = unsafe{target.offset_from(current)};
+   let offset = (target as usize).wrapping_sub(address) as isize;
 
-   if let Ok(offset) = i32 ::try_from(offset) {
-      return call_rel32 (memory_buffer, offset);
+   if i32 ::try_from(offset.wrapping_sub(CALL_REL32_LENGTH)).is_ok() == true {
+      return call_rel32 (memory_buffer, offset as i32);
    }
 
    return call_abs64(memory_buffer, target as u64);
 }
 
+pub fn call_indirect_rel32(
+   memory_buffer  : & mut [u8],
+   rel32          : i32,
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0xFF, 0x15],
+      &rel32.to_le_bytes(),
+   );
+}
+
+pub fn ret(
+   memory_buffer  : & mut [u8],
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0xC3],
+      &[],
+   );
+}
+
+pub fn xor_eax_eax(
+   memory_buffer  : & mut [u8],
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0x31, 0xC0],
+      &[],
+   );
+}
+
+pub fn mov_eax_imm32(
+   memory_buffer  : & mut [u8],
+   imm32          : u32,
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0xB8],
+      &imm32.to_le_bytes(),
+   );
+}
+
+pub fn mov_rax_imm64(
+   memory_buffer  : & mut [u8],
+   imm64          : u64,
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0x48, 0xB8],
+      &imm64.to_le_bytes(),
+   );
+}
+
+pub fn mov_r64_imm64(
+   memory_buffer  : & mut [u8],
+   register       : u8,
+   imm64          : u64,
+) -> crate::compiler::Result<usize> {
+   return build_instruction_encoding(
+      memory_buffer,
+      &[0x48 | (register >> 3), 0xB8 | (register & 0x07)],
+      &imm64.to_le_bytes(),
+   );
+}
+
+pub fn mov_r32_imm32(
+   memory_buffer  : & mut [u8],
+   register       : u8,
+   imm32          : u32,
+) -> crate::compiler::Result<usize> {
+   return match register >> 3 {
+      0 => build_instruction_encoding(
+         memory_buffer,
+         &[0xB8 | (register & 0x07)],
+         &imm32.to_le_bytes(),
+      ),
+      _ => build_instruction_encoding(
+         memory_buffer,
+         &[0x41, 0xB8 | (register & 0x07)],
+         &imm32.to_le_bytes(),
+      ),
+   };
+}
+
+pub fn push_r64(
+   memory_buffer  : & mut [u8],
+   register       : u8,
+) -> crate::compiler::Result<usize> {
+   return match register >> 3 {
+      0 => build_instruction_encoding(memory_buffer, &[0x50 | register], &[]),
+      _ => build_instruction_encoding(memory_buffer, &[0x41, 0x50 | (register & 0x07)], &[]),
+   };
+}
+
+pub fn pop_r64(
+   memory_buffer  : & mut [u8],
+   register       : u8,
+) -> crate::compiler::Result<usize> {
+   return match register >> 3 {
+      0 => build_instruction_encoding(memory_buffer, &[0x58 | register], &[]),
+      _ => build_instruction_encoding(memory_buffer, &[0x41, 0x58 | (register & 0x07)], &[]),
+   };
+}
+
+// lea rsp, [rsp+displacement], which moves
+// the stack pointer without touching flags
+pub fn lea_rsp(
+   memory_buffer  : & mut [u8],
+   displacement   : i32,
+) -> crate::compiler::Result<usize> {
+   return match i8::try_from(displacement) {
+      Ok(displacement)  => build_instruction_encoding(
+         memory_buffer,
+         &[0x48, 0x8D, 0x64, 0x24],
+         &displacement.to_le_bytes(),
+      ),
+      Err(_)            => build_instruction_encoding(
+         memory_buffer,
+         &[0x48, 0x8D, 0xA4, 0x24],
+         &displacement.to_le_bytes(),
+      ),
+   };
+}
+
+pub fn mov_indirect(
+   memory_buffer  : & mut [u8],
+   opcode         : u8,
+   wide           : bool,
+   register       : u8,
+   base           : u8,
+) -> crate::compiler::Result<usize> {
+   const MOD_INDIRECT   : u8 = 0b00;
+   const MOD_DISP8      : u8 = 0b01;
+   const RM_SIB         : u8 = 0b100;
+   const RM_RBP         : u8 = 0b101;
+
+   let rex = rex_prefix(wide, register, 0, base);
+
+   let mut encoding = Vec::with_capacity(5);
+   if rex != REX {
+      encoding.push(rex);
+   }
+   encoding.push(opcode);
+
+   // [rsp] and [r12] need a SIB byte, and
+   // [rbp] and [r13] can only be encoded
+   // with a zero displacement
+   match base & 0x07 {
+      RM_SIB   => encoding.extend([modrm(MOD_INDIRECT, register, RM_SIB), 0x24]),
+      RM_RBP   => encoding.extend([modrm(MOD_DISP8, register, RM_RBP), 0x00]),
+      _        => encoding.push(modrm(MOD_INDIRECT, register, base)),
+   }
+
+   return build_instruction_encoding(
+      memory_buffer,
+      &encoding,
+      &[],
+   );
+}
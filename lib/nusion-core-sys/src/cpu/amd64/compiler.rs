@@ -1,5 +1,15 @@
 //! crate::cpu::compiler implementation for AMD64.
 
+// This runs while patching from inside game
+// threads, so running out of buffer must be
+// an error instead of a panic
+#![deny(
+   clippy::indexing_slicing,
+   clippy::panic,
+   clippy::unwrap_used,
+   clippy::expect_used,
+)]
+
 pub fn nop_fill(
    memory_buffer : & mut [u8],
 ) -> crate::compiler::Result<()> {
@@ -21,7 +31,7 @@ pub fn nop_fill(
          _  => super::assembler::nop11(memory_buffer_view)?,
       };
 
-      memory_buffer_view = & mut memory_buffer_view[instruction_length..];
+      memory_buffer_view = after(memory_buffer_view, instruction_length)?;
    }
 
    return Ok(());
@@ -29,19 +39,22 @@ pub fn nop_fill(
 
 pub fn hook_fill(
    memory_buffer  : & mut [u8],
+   address        : usize,
    hook           : crate::compiler::HookTarget,
 ) -> crate::compiler::Result<()> {
    const NOP_BYTES_TO_COMPILE_JMP : usize
       = 22; // At most 2 consecutive 11-byte nops
 
+   let end_address            = address.wrapping_add(memory_buffer.len());
    let mut memory_buffer_view = & mut memory_buffer[..];
 
    // Required instruction - Call to the hook
    let instruction_length = super::assembler::call(
       memory_buffer_view,
+      address,
       hook as * const core::ffi::c_void,
    )?;
-   memory_buffer_view = & mut memory_buffer_view[instruction_length..];
+   memory_buffer_view = after(memory_buffer_view, instruction_length)?;
 
    // If the remaining bytes are small, don't
    // compile a jmp and ud2, this is a speed
@@ -56,9 +69,10 @@ pub fn hook_fill(
    // memory region
    let instruction_length = super::assembler::jmp(
       memory_buffer_view,
-      memory_buffer_view.as_ptr_range().end as * const core::ffi::c_void,
+      end_address.wrapping_sub(memory_buffer_view.len()),
+      end_address as * const core::ffi::c_void,
    )?;
-   memory_buffer_view = & mut memory_buffer_view[instruction_length..];
+   memory_buffer_view = after(memory_buffer_view, instruction_length)?;
 
    // Compile a ud2 instruction after the
    // jmp in case something goes catastrophically
@@ -66,7 +80,7 @@ pub fn hook_fill(
    let instruction_bytes = super::assembler::ud2(
       memory_buffer_view,
    )?;
-   memory_buffer_view = & mut memory_buffer_view[instruction_bytes..];
+   memory_buffer_view = after(memory_buffer_view, instruction_bytes)?;
 
    // Fill the rest of the memory
    // with nop instructions
@@ -76,3 +90,839 @@ pub fn hook_fill(
    return Ok(());
 }
 
+pub const TRAMPOLINE_LENGTH : usize = 14;
+
+pub fn trampoline_fill(
+   memory_buffer  : & mut [u8],
+   target         : usize,
+) -> crate::compiler::Result<()> {
+   // Always absolute, since the trampoline
+   // only exists because the target is far
+   let instruction_length = super::assembler::jmp_abs64(
+      memory_buffer,
+      target as u64,
+   )?;
+
+   return nop_fill(after(memory_buffer, instruction_length)?);
+}
+
+pub fn hook_stub_length(
+   stolen_length : usize,
+) -> usize {
+   const CALL_ABS64_LENGTH : usize = 16;
+   const JMP_ABS64_LENGTH  : usize = 14;
+
+   return CALL_ABS64_LENGTH + stolen_length + JMP_ABS64_LENGTH;
+}
+
+pub fn verify_relocatable(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> crate::compiler::Result<()> {
+   let mut offset = 0;
+   while offset < memory_buffer.len() {
+      let bytes = memory_buffer.get(offset..).unwrap_or_default();
+
+      let instruction = super::decoder::decode_instruction(bytes).ok_or(
+         crate::compiler::CompilationError::UnrelocatableInstruction{
+            offset : offset,
+         },
+      )?;
+      if instruction.control_flow != crate::decoder::ControlFlow::Sequential ||
+         super::decoder::decode_reference(bytes, address.wrapping_add(offset)).is_some()
+      {
+         return Err(crate::compiler::CompilationError::UnrelocatableInstruction{
+            offset : offset,
+         });
+      }
+
+      offset += instruction.length;
+   }
+
+   return Ok(());
+}
+
+pub fn hook_fill_jmp_back(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   stub           : & mut [u8],
+   hook           : crate::compiler::HookTarget,
+) -> crate::compiler::Result<()> {
+   let mut stub_view = & mut stub[..];
+
+   // Required instruction - Call to the hook
+   let instruction_length = super::assembler::call(
+      stub_view,
+      stub_view.as_ptr() as usize,
+      hook as * const core::ffi::c_void,
+   )?;
+   stub_view = after(stub_view, instruction_length)?;
+
+   // Copy the instructions we're about to overwrite
+   copy_instruction(stub_view, memory_buffer)?;
+   stub_view = after(stub_view, memory_buffer.len())?;
+
+   // Required instruction - Jump back to
+   // the end of the memory region
+   let instruction_length = super::assembler::jmp(
+      stub_view,
+      stub_view.as_ptr() as usize,
+      address.wrapping_add(memory_buffer.len()) as * const core::ffi::c_void,
+   )?;
+   stub_view = after(stub_view, instruction_length)?;
+   nop_fill(stub_view)?;
+
+   // Replace the memory region with a
+   // jump to the stub
+   let instruction_length = super::assembler::jmp(
+      memory_buffer,
+      address,
+      stub.as_ptr() as * const core::ffi::c_void,
+   )?;
+   nop_fill(after(memory_buffer, instruction_length)?)?;
+
+   return Ok(());
+}
+
+pub fn hook_chain_stub_length(
+   stolen_length  : usize,
+   capacity       : usize,
+) -> usize {
+   const RET_LENGTH           : usize = 1;
+   const CALL_INDIRECT_LENGTH : usize = 6;
+   const JMP_ABS64_LENGTH     : usize = 14;
+
+   return RET_LENGTH + CALL_INDIRECT_LENGTH * capacity + stolen_length + JMP_ABS64_LENGTH;
+}
+
+pub fn hook_chain_fill(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   stub           : & mut [u8],
+   links_address  : usize,
+   capacity       : usize,
+   jmp_back       : bool,
+) -> crate::compiler::Result<()> {
+   let mut stub_view = & mut stub[..];
+
+   // Required instruction - Empty links point
+   // here so every link can always be called
+   let instruction_length = super::assembler::ret(stub_view)?;
+   stub_view = after(stub_view, instruction_length)?;
+   let entry = stub_view.as_ptr();
+
+   // Required instructions - Call through every
+   // link, starting with the newest
+   for index in (0..capacity).rev() {
+      const CALL_INDIRECT_LENGTH : usize = 6;
+
+      let link = links_address + index * std::mem::size_of::<usize>();
+      let next = stub_view.as_ptr() as usize + CALL_INDIRECT_LENGTH;
+      let rel32 = i32::try_from((link as isize).wrapping_sub(next as isize))
+         .map_err(|_| crate::compiler::CompilationError::ImpossibleEncoding)?;
+
+      let instruction_length = super::assembler::call_indirect_rel32(stub_view, rel32)?;
+      stub_view = after(stub_view, instruction_length)?;
+   }
+
+   // Copy the instructions we're about to overwrite
+   if jmp_back == true {
+      copy_instruction(stub_view, memory_buffer)?;
+      stub_view = after(stub_view, memory_buffer.len())?;
+   }
+
+   // Required instruction - Jump back to
+   // the end of the memory region
+   let instruction_length = super::assembler::jmp(
+      stub_view,
+      stub_view.as_ptr() as usize,
+      address.wrapping_add(memory_buffer.len()) as * const core::ffi::c_void,
+   )?;
+   stub_view = after(stub_view, instruction_length)?;
+   nop_fill(stub_view)?;
+
+   // Replace the memory region with a
+   // jump to the first call
+   let instruction_length = super::assembler::jmp(
+      memory_buffer,
+      address,
+      entry as * const core::ffi::c_void,
+   )?;
+   nop_fill(after(memory_buffer, instruction_length)?)?;
+
+   return Ok(());
+}
+
+pub fn jump_target(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<usize> {
+   return jump_instruction(memory_buffer, address).map(|(target, _)| target);
+}
+
+pub fn jump_instruction(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<(usize, usize)> {
+   return match memory_buffer {
+      [0xEB, ..]
+         => Some((address.wrapping_add(2).wrapping_add_signed(read_displacement(memory_buffer, 1, 1)?), 2)),
+      [0xE9, ..]
+         => Some((address.wrapping_add(5).wrapping_add_signed(read_displacement(memory_buffer, 1, 4)?), 5)),
+      [0xFF, 0x25, 0x00, 0x00, 0x00, 0x00, ..]
+         => Some((u64::from_le_bytes(memory_buffer.get(6..14)?.try_into().ok()?) as usize, 14)),
+      // mov rax, imm64 followed by jmp rax
+      [0x48, 0xB8, _, _, _, _, _, _, _, _, 0xFF, 0xE0, ..]
+         => Some((u64::from_le_bytes(memory_buffer.get(2..10)?.try_into().ok()?) as usize, 12)),
+      _  => None,
+   };
+}
+
+pub fn foreign_hook_continuation(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<usize> {
+   let (_, jump_length) = jump_instruction(memory_buffer, address)?;
+
+   // Hooking libraries pad the rest of the
+   // instructions they overwrote, and their
+   // hook continues after the padding
+   let padding = memory_buffer.get(jump_length..)?
+      .iter()
+      .take_while(|byte| **byte == 0x90 || **byte == 0xCC)
+      .count();
+
+   return Some(jump_length + padding);
+}
+
+// Longest encoding of a single relocated
+// instruction, which is a store emulated
+// through a scratch register saved below
+// the red zone
+const RELOCATED_LENGTH_MAX : usize = 32;
+
+// Bytes below the stack pointer which the
+// System V ABI lets a function use without
+// moving the stack pointer, so the stub has
+// to skip them before pushing anything.
+// Windows has no red zone.
+#[cfg(target_os = "windows")]
+const RED_ZONE_LENGTH : i32 = 0;
+#[cfg(not(target_os = "windows"))]
+const RED_ZONE_LENGTH : i32 = 128;
+
+// Longest lea rsp, [rsp+disp32]
+const LEA_RSP_LENGTH_MAX : usize = 8;
+
+fn jmp_length(
+   source         : usize,
+   destination    : usize,
+) -> usize {
+   const JMP_REL8_LENGTH   : usize = 2;
+   const JMP_REL32_LENGTH  : usize = 5;
+   const JMP_ABS64_LENGTH  : usize = 14;
+
+   let offset = (destination as isize).wrapping_sub(source as isize);
+   if i8::try_from(offset).is_ok() == true {
+      return JMP_REL8_LENGTH;
+   }
+   if i32::try_from(offset).is_ok() == true {
+      return JMP_REL32_LENGTH;
+   }
+   return JMP_ABS64_LENGTH;
+}
+
+fn stolen_length(
+   memory_buffer  : & [u8],
+   minimum_length : usize,
+) -> crate::compiler::Result<usize> {
+   let mut offset = 0;
+   while offset < minimum_length {
+      if offset >= memory_buffer.len() {
+         return Err(crate::compiler::CompilationError::BufferTooSmall{
+            instruction_length   : minimum_length,
+            buffer_length        : memory_buffer.len(),
+         });
+      }
+
+      let instruction = super::decoder::decode_instruction(
+         memory_buffer.get(offset..).unwrap_or_default(),
+      ).ok_or(crate::compiler::CompilationError::UnrelocatableInstruction{
+         offset : offset,
+      })?;
+
+      offset += instruction.length;
+   }
+
+   return Ok(offset);
+}
+
+fn relocate_instruction(
+   instruction    : & [u8],
+   source_address : usize,
+   stolen_range   : & std::ops::Range<usize>,
+   destination    : & mut [u8],
+   offset         : usize,
+   emulated       : Option<& mut Vec<crate::compiler::EmulatedInstruction>>,
+) -> crate::compiler::Result<usize> {
+   let unrelocatable = crate::compiler::CompilationError::UnrelocatableInstruction{
+      offset : offset,
+   };
+
+   let decoded = super::decoder::decode_instruction(instruction).ok_or(
+      crate::compiler::CompilationError::UnrelocatableInstruction{
+         offset : offset,
+      },
+   )?;
+   if decoded.control_flow == crate::decoder::ControlFlow::Trap {
+      return Err(unrelocatable);
+   }
+
+   // Position-independent instructions
+   // are copied as-is
+   let operand = match decoded.relative_operand {
+      Some(operand)  => operand,
+      None           => {
+         copy_instruction(destination, instruction)?;
+         return Ok(instruction.len());
+      },
+   };
+
+   let displacement = read_displacement(
+      instruction,
+      operand.offset,
+      operand.size,
+   ).ok_or(crate::compiler::CompilationError::UnrelocatableInstruction{
+      offset : offset,
+   })?;
+   let target = source_address
+      .wrapping_add(instruction.len())
+      .wrapping_add_signed(displacement);
+
+   // Branching into the middle of the stolen
+   // instructions would land inside the jump
+   // to the stub
+   if target > stolen_range.start && target < stolen_range.end {
+      return Err(unrelocatable);
+   }
+
+   let target_pointer = target as * const core::ffi::c_void;
+   return match decoded.control_flow {
+      crate::decoder::ControlFlow::Call
+         => super::assembler::call(destination, destination.as_ptr() as usize, target_pointer),
+      crate::decoder::ControlFlow::Jump
+         => super::assembler::jmp(destination, destination.as_ptr() as usize, target_pointer),
+      crate::decoder::ControlFlow::ConditionalJump
+         => {
+            // Condition code from either jcc rel8
+            // or jcc rel32, everything else such
+            // as loop and jrcxz has no long form
+            let condition = match instruction {
+               [opcode @ 0x70..=0x7F, ..]       => opcode & 0x0F,
+               [0x0F, opcode @ 0x80..=0x8F, ..] => opcode & 0x0F,
+               _                                => return Err(unrelocatable),
+            };
+
+            // Jump over an absolute jump to the
+            // target using the inverted condition
+            let destination_length = destination.len();
+            let [opcode, displacement, jmp_destination @ ..] = destination else {
+               return Err(crate::compiler::CompilationError::BufferTooSmall{
+                  instruction_length   : RELOCATED_LENGTH_MAX,
+                  buffer_length        : destination_length,
+               });
+            };
+            let jmp_length = super::assembler::jmp_abs64(
+               jmp_destination,
+               target as u64,
+            )?;
+            *opcode        = 0x70 | (condition ^ 0x01);
+            *displacement  = jmp_length as u8;
+
+            Ok(2 + jmp_length)
+         },
+      _
+         => {
+            // Instruction pointer-relative memory
+            // operand, which only needs a new
+            // displacement if it can reach
+            let destination_end = (destination.as_ptr() as usize).wrapping_add(instruction.len());
+            let displacement = match i32::try_from(
+               (target as isize).wrapping_sub(destination_end as isize),
+            ) {
+               Ok(displacement)  => displacement,
+               Err(_)            => {
+                  // Out of reach, so use the absolute
+                  // address instead if allowed
+                  let emulated = emulated.ok_or(unrelocatable)?;
+                  let (kind, length) = emulate_instruction(
+                     instruction, target, destination,
+                  ).ok_or(crate::compiler::CompilationError::UnrelocatableInstruction{
+                     offset : offset,
+                  })??;
+                  emulated.push(crate::compiler::EmulatedInstruction{
+                     offset         : offset,
+                     length         : instruction.len(),
+                     kind           : kind,
+                     target_address : target,
+                  });
+                  return Ok(length);
+               },
+            };
+            if operand.size != 4 {
+               return Err(unrelocatable);
+            }
+
+            copy_instruction(destination, instruction)?;
+            match destination.get_mut(operand.offset..operand.offset + 4) {
+               Some(operand_bytes)  => operand_bytes.copy_from_slice(&displacement.to_le_bytes()),
+               None                 => return Err(unrelocatable),
+            }
+            Ok(instruction.len())
+         },
+   };
+}
+
+// Rewrites an instruction pointer-relative
+// lea or mov to use an absolute address,
+// returning None if it isn't one of them.
+// Storing the stack pointer isn't supported
+// since the store pushes a scratch register.
+fn emulate_instruction(
+   instruction    : & [u8],
+   target         : usize,
+   destination    : & mut [u8],
+) -> Option<crate::compiler::Result<(crate::decoder::ReferenceKind, usize)>> {
+   const REX_FIRST   : u8 = 0x40;
+   const REX_LAST    : u8 = 0x4F;
+   const REX_W       : u8 = 0x08;
+   const REX_R       : u8 = 0x04;
+   const DISP32      : usize = 4;
+   const RSP         : u8 = 4;
+
+   // Optional - REX prefix, which can only
+   // extend the register operand since the
+   // memory operand is [rip+disp32]
+   let (rex, body) = match instruction {
+      [rex @ REX_FIRST..=REX_LAST, body @ ..]   => (*rex, body),
+      _                                         => (0x00, instruction),
+   };
+   let wide       = rex & REX_W != 0;
+   let register   = ((body.get(1)? >> 3) & 0x07) | if rex & REX_R != 0 {0x08} else {0x00};
+
+   // Required - Opcode, ModR/M and displacement
+   // with nothing else after them
+   if body.len() != 2 + DISP32 {
+      return None;
+   }
+
+   let result = match body.first()? {
+      0x8D => emulate_lea(destination, wide, register, target),
+      0x8B => emulate_load(destination, wide, register, target),
+      0x89 => match register {
+         RSP   => return None,
+         _     => emulate_store(destination, wide, register, target),
+      },
+      _    => return None,
+   };
+
+   return Some(result);
+}
+
+// lea reg, [rip+disp32]
+fn emulate_lea(
+   destination    : & mut [u8],
+   wide           : bool,
+   register       : u8,
+   target         : usize,
+) -> crate::compiler::Result<(crate::decoder::ReferenceKind, usize)> {
+   let length = match wide {
+      true  => super::assembler::mov_r64_imm64(destination, register, target as u64)?,
+      false => super::assembler::mov_r32_imm32(destination, register, target as u32)?,
+   };
+
+   return Ok((crate::decoder::ReferenceKind::LoadEffectiveAddress, length));
+}
+
+// mov reg, [rip+disp32], loading through
+// the register which is overwritten anyway
+fn emulate_load(
+   destination    : & mut [u8],
+   wide           : bool,
+   register       : u8,
+   target         : usize,
+) -> crate::compiler::Result<(crate::decoder::ReferenceKind, usize)> {
+   let mut length = 0;
+   length += super::assembler::mov_r64_imm64(after(destination, length)?, register, target as u64)?;
+   length += super::assembler::mov_indirect(after(destination, length)?, 0x8B, wide, register, register)?;
+
+   return Ok((crate::decoder::ReferenceKind::Move, length));
+}
+
+// mov [rip+disp32], reg, storing through
+// a scratch register saved on the stack
+fn emulate_store(
+   destination    : & mut [u8],
+   wide           : bool,
+   register       : u8,
+   target         : usize,
+) -> crate::compiler::Result<(crate::decoder::ReferenceKind, usize)> {
+   const RAX : u8 = 0;
+   const RCX : u8 = 1;
+
+   let scratch = if register == RAX {RCX} else {RAX};
+
+   let mut length = 0;
+   length += skip_red_zone(after(destination, length)?, -RED_ZONE_LENGTH)?;
+   length += super::assembler::push_r64(after(destination, length)?, scratch)?;
+   length += super::assembler::mov_r64_imm64(after(destination, length)?, scratch, target as u64)?;
+   length += super::assembler::mov_indirect(after(destination, length)?, 0x89, wide, register, scratch)?;
+   length += super::assembler::pop_r64(after(destination, length)?, scratch)?;
+   length += skip_red_zone(after(destination, length)?, RED_ZONE_LENGTH)?;
+
+   return Ok((crate::decoder::ReferenceKind::Move, length));
+}
+
+pub fn inline_hook_stub_length(
+   memory_buffer  : & [u8],
+) -> crate::compiler::Result<usize> {
+   const CALL_ABS64_LENGTH : usize = 16;
+   const JMP_ABS64_LENGTH  : usize = 14;
+
+   // Assume the longest jump to the stub,
+   // which steals the most instructions
+   let stolen = stolen_length(memory_buffer, JMP_ABS64_LENGTH)?;
+
+   let mut relocated = 0;
+   let mut offset    = 0;
+   while offset < stolen {
+      let instruction = super::decoder::decode_instruction(
+         memory_buffer.get(offset..).unwrap_or_default(),
+      ).ok_or(crate::compiler::CompilationError::UnrelocatableInstruction{
+         offset : offset,
+      })?;
+
+      relocated += std::cmp::max(instruction.length, RELOCATED_LENGTH_MAX);
+      offset    += instruction.length;
+   }
+
+   return Ok(LEA_RSP_LENGTH_MAX + CALL_ABS64_LENGTH + LEA_RSP_LENGTH_MAX + relocated + JMP_ABS64_LENGTH);
+}
+
+pub fn hook_fill_inline(
+   memory_buffer  : & mut [u8],
+   site_address   : usize,
+   stub           : & mut [u8],
+   hook           : crate::compiler::HookTarget,
+   emulate        : bool,
+) -> crate::compiler::Result<crate::compiler::InlineHookFill> {
+
+   // Only steal as many instructions as
+   // the jump to the stub overwrites
+   let stolen = stolen_length(
+      memory_buffer,
+      jmp_length(site_address, stub.as_ptr() as usize),
+   )?;
+   let stolen_range = site_address..site_address + stolen;
+
+   let mut stub_view = & mut stub[..];
+
+   // Required instructions - Call to the hook
+   // with the return address pushed below the
+   // interrupted function's red zone
+   let instruction_length = skip_red_zone(stub_view, -RED_ZONE_LENGTH)?;
+   stub_view = after(stub_view, instruction_length)?;
+   let instruction_length = super::assembler::call(
+      stub_view,
+      stub_view.as_ptr() as usize,
+      hook as * const core::ffi::c_void,
+   )?;
+   stub_view = after(stub_view, instruction_length)?;
+   let instruction_length = skip_red_zone(stub_view, RED_ZONE_LENGTH)?;
+   stub_view = after(stub_view, instruction_length)?;
+
+   // Relocate the instructions we're about
+   // to overwrite into the stub
+   let mut emulated  = Vec::new();
+   let mut offset    = 0;
+   while offset < stolen {
+      let length = super::decoder::decode_instruction(
+         memory_buffer.get(offset..).unwrap_or_default(),
+      ).ok_or(crate::compiler::CompilationError::UnrelocatableInstruction{
+         offset : offset,
+      })?.length;
+      let instruction = memory_buffer.get(offset..offset + length).ok_or(
+         crate::compiler::CompilationError::UnrelocatableInstruction{
+            offset : offset,
+         },
+      )?;
+
+      let instruction_length = relocate_instruction(
+         instruction,
+         site_address + offset,
+         &stolen_range,
+         stub_view,
+         offset,
+         if emulate == true {Some(& mut emulated)} else {None},
+      )?;
+      stub_view = after(stub_view, instruction_length)?;
+
+      offset += length;
+   }
+
+   // Required instruction - Jump back to
+   // the end of the stolen instructions
+   let instruction_length = super::assembler::jmp(
+      stub_view,
+      stub_view.as_ptr() as usize,
+      stolen_range.end as * const core::ffi::c_void,
+   )?;
+   stub_view = after(stub_view, instruction_length)?;
+   nop_fill(stub_view)?;
+
+   // Replace the stolen instructions with
+   // a jump to the stub, leaving the rest
+   // of the memory buffer untouched
+   let buffer_length = memory_buffer.len();
+   let stolen_buffer = memory_buffer.get_mut(..stolen).ok_or(
+      crate::compiler::CompilationError::BufferTooSmall{
+         instruction_length   : stolen,
+         buffer_length        : buffer_length,
+      },
+   )?;
+   let instruction_length = super::assembler::jmp(
+      stolen_buffer,
+      site_address,
+      stub.as_ptr() as * const core::ffi::c_void,
+   )?;
+   nop_fill(after(stolen_buffer, instruction_length)?)?;
+
+   return Ok(crate::compiler::InlineHookFill{
+      stolen_length  : stolen,
+      emulated       : emulated,
+   });
+}
+
+pub const BRANCH_SHORT_LENGTH : usize = 2;
+pub const BRANCH_NEAR_LENGTH  : usize = 6;
+
+pub const REX_PREFIX : u8 = super::assembler::REX;
+
+pub fn rex_prefix(
+   wide  : bool,
+   reg   : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return super::assembler::rex_prefix(wide, reg, index, base);
+}
+
+pub fn modrm(
+   mode  : u8,
+   reg   : u8,
+   rm    : u8,
+) -> u8 {
+   return super::assembler::modrm(mode, reg, rm);
+}
+
+pub fn sib(
+   scale : u8,
+   index : u8,
+   base  : u8,
+) -> u8 {
+   return super::assembler::sib(scale, index, base);
+}
+
+fn expect_instruction(
+   memory_buffer  : & [u8],
+   control_flow   : crate::decoder::ControlFlow,
+) -> crate::compiler::Result<crate::decoder::Instruction> {
+   // The instruction must fill the entire
+   // buffer so nothing after it is changed
+   return super::decoder::decode_instruction(memory_buffer)
+      .filter(|instruction| {
+         instruction.control_flow == control_flow &&
+         instruction.length       == memory_buffer.len()
+      })
+      .ok_or(crate::compiler::CompilationError::UnexpectedInstruction);
+}
+
+pub fn call_nop_fill(
+   memory_buffer  : & mut [u8],
+) -> crate::compiler::Result<()> {
+   expect_instruction(memory_buffer, crate::decoder::ControlFlow::Call)?;
+   return nop_fill(memory_buffer);
+}
+
+pub fn branch_fill(
+   memory_buffer  : & mut [u8],
+   address        : usize,
+   taken          : bool,
+) -> crate::compiler::Result<()> {
+   let instruction = expect_instruction(
+      memory_buffer,
+      crate::decoder::ControlFlow::ConditionalJump,
+   )?;
+
+   if taken == false {
+      return nop_fill(memory_buffer);
+   }
+
+   // Read the displacement, which is relative
+   // to the end of the conditional jump
+   let operand = instruction.relative_operand.ok_or(
+      crate::compiler::CompilationError::UnexpectedInstruction,
+   )?;
+   let displacement = read_displacement(
+      memory_buffer,
+      operand.offset,
+      operand.size,
+   ).ok_or(crate::compiler::CompilationError::UnexpectedInstruction)?;
+   let target = address.wrapping_add(memory_buffer.len()).wrapping_add_signed(displacement);
+
+   // An unconditional jump to the same target
+   // is never longer than the conditional one
+   let instruction_length = super::assembler::jmp(
+      memory_buffer,
+      address,
+      target as * const core::ffi::c_void,
+   )?;
+   return nop_fill(after(memory_buffer, instruction_length)?);
+}
+
+pub fn branch_invert_fill(
+   memory_buffer  : & mut [u8],
+) -> crate::compiler::Result<()> {
+   expect_instruction(
+      memory_buffer,
+      crate::decoder::ControlFlow::ConditionalJump,
+   )?;
+
+   // Condition codes come in pairs which
+   // only differ by the lowest bit, such
+   // as je (0x74) and jne (0x75)
+   let condition = match memory_buffer {
+      [condition @ 0x70..=0x7F, ..]       => condition,
+      [0x0F, condition @ 0x80..=0x8F, ..] => condition,
+      _                                   => return Err(crate::compiler::CompilationError::UnexpectedInstruction),
+   };
+   *condition ^= 0x01;
+
+   return Ok(());
+}
+
+pub fn return_length(
+   value : Option<u64>,
+) -> usize {
+   const RET_LENGTH           : usize = 1;
+   const XOR_EAX_EAX_LENGTH   : usize = 2;
+   const MOV_EAX_IMM32_LENGTH : usize = 5;
+   const MOV_RAX_IMM64_LENGTH : usize = 10;
+
+   return RET_LENGTH + match value {
+      None                                   => 0,
+      Some(0)                                => XOR_EAX_EAX_LENGTH,
+      Some(value) if value <= u32::MAX as u64 => MOV_EAX_IMM32_LENGTH,
+      Some(_)                                => MOV_RAX_IMM64_LENGTH,
+   };
+}
+
+pub fn return_fill(
+   memory_buffer  : & mut [u8],
+   value          : Option<u64>,
+) -> crate::compiler::Result<()> {
+   let mut memory_buffer_view = & mut memory_buffer[..];
+
+   // Writing to eax zero-extends into rax,
+   // so the shortest encoding is used
+   let instruction_length = match value {
+      None                                   => 0,
+      Some(0)                                => super::assembler::xor_eax_eax(
+         memory_buffer_view,
+      )?,
+      Some(value) if value <= u32::MAX as u64 => super::assembler::mov_eax_imm32(
+         memory_buffer_view,
+         value as u32,
+      )?,
+      Some(value)                            => super::assembler::mov_rax_imm64(
+         memory_buffer_view,
+         value,
+      )?,
+   };
+   memory_buffer_view = after(memory_buffer_view, instruction_length)?;
+
+   // Required instruction - Return to the caller
+   let instruction_length = super::assembler::ret(
+      memory_buffer_view,
+   )?;
+   memory_buffer_view = after(memory_buffer_view, instruction_length)?;
+
+   // Fill the rest of the memory
+   // with nop instructions
+   return nop_fill(memory_buffer_view);
+}
+
+// Gets the rest of a buffer after an offset,
+// returning an error instead of panicking
+// if the offset is past the end
+fn after(
+   memory_buffer  : & mut [u8],
+   offset         : usize,
+) -> crate::compiler::Result<& mut [u8]> {
+   let buffer_length = memory_buffer.len();
+   return memory_buffer.get_mut(offset..).ok_or(crate::compiler::CompilationError::BufferTooSmall{
+      instruction_length   : offset,
+      buffer_length        : buffer_length,
+   });
+}
+
+// Moves the stack pointer past the red zone
+// and back, using lea instead of sub and add
+// so the flags the stolen instructions may
+// depend on are left alone.  Nothing is
+// written when there's no red zone.
+fn skip_red_zone(
+   memory_buffer  : & mut [u8],
+   displacement   : i32,
+) -> crate::compiler::Result<usize> {
+   if displacement == 0 {
+      return Ok(0);
+   }
+
+   return super::assembler::lea_rsp(memory_buffer, displacement);
+}
+
+// Copies an instruction as-is to the
+// start of a buffer
+fn copy_instruction(
+   destination    : & mut [u8],
+   instruction    : & [u8],
+) -> crate::compiler::Result<()> {
+   let buffer_length = destination.len();
+   return match destination.get_mut(..instruction.len()) {
+      Some(destination) => {
+         destination.copy_from_slice(instruction);
+         Ok(())
+      },
+      None              => Err(crate::compiler::CompilationError::BufferTooSmall{
+         instruction_length   : instruction.len(),
+         buffer_length        : buffer_length,
+      }),
+   };
+}
+
+// Reads a signed 8 or 32-bit relative
+// displacement from an instruction
+fn read_displacement(
+   instruction    : & [u8],
+   offset         : usize,
+   size           : usize,
+) -> Option<isize> {
+   let bytes = instruction.get(offset..offset.checked_add(size)?)?;
+   return match size {
+      1  => Some(i8 ::from_le_bytes(bytes.try_into().ok()?) as isize),
+      4  => Some(i32::from_le_bytes(bytes.try_into().ok()?) as isize),
+      _  => None,
+   };
+}
+
@@ -0,0 +1,438 @@
+//! crate::cpu::decoder implementation for AMD64.
+
+use crate::decoder::{ControlFlow, Reference, ReferenceKind};
+
+// REX prefixes take up an entire row
+// of single-byte opcodes
+const REX_FIRST   : u8 = 0x40;
+const REX_LAST    : u8 = 0x4F;
+
+// ModR/M encoding for [rip+disp32]
+const MODRM_MASK_RIP_RELATIVE : u8 = 0b11_000_111;
+const MODRM_RIP_RELATIVE      : u8 = 0b00_000_101;
+
+fn decode_reference_relative(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<Reference> {
+   // Required - Opcode followed by a rel32 operand
+   let (kind, opcode_length) = match memory_buffer.first()? {
+      0xE8  => (ReferenceKind::Call, 1),
+      0xE9  => (ReferenceKind::Jump, 1),
+      0x0F  => match memory_buffer.get(1)? {
+         0x80..=0x8F => (ReferenceKind::ConditionalJump, 2),
+         _           => return None,
+      },
+      _     => return None,
+   };
+
+   // Required - 32-bit signed displacement
+   let displacement = i32::from_le_bytes(
+      memory_buffer.get(opcode_length..opcode_length+4)?.try_into().ok()?,
+   );
+
+   // Displacement is relative to the next instruction
+   let instruction_length  = opcode_length + 4;
+   let target_address      = address
+      .wrapping_add(instruction_length)
+      .wrapping_add_signed(displacement as isize);
+
+   return Some(Reference{
+      kind                 : kind,
+      instruction_length   : instruction_length,
+      target_address       : target_address,
+   });
+}
+
+fn decode_reference_rip_relative(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<Reference> {
+   // Optional - REX prefix
+   let prefix_length = match memory_buffer.first()? {
+      REX_FIRST..=REX_LAST => 1,
+      _                    => 0,
+   };
+   let memory_buffer = memory_buffer.get(prefix_length..)?;
+
+   // Required - Opcode with a ModR/M operand
+   let kind = match memory_buffer.first()? {
+      0x8D        => ReferenceKind::LoadEffectiveAddress,
+      0x89 | 0x8B => ReferenceKind::Move,
+      _           => return None,
+   };
+
+   // Required - ModR/M byte encoding [rip+disp32]
+   if memory_buffer.get(1)? & MODRM_MASK_RIP_RELATIVE != MODRM_RIP_RELATIVE {
+      return None;
+   }
+
+   // Required - 32-bit signed displacement
+   let displacement = i32::from_le_bytes(
+      memory_buffer.get(2..6)?.try_into().ok()?,
+   );
+
+   // Displacement is relative to the next instruction
+   let instruction_length  = prefix_length + 6;
+   let target_address      = address
+      .wrapping_add(instruction_length)
+      .wrapping_add_signed(displacement as isize);
+
+   return Some(Reference{
+      kind                 : kind,
+      instruction_length   : instruction_length,
+      target_address       : target_address,
+   });
+}
+
+pub fn decode_reference(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<Reference> {
+   return decode_reference_relative(memory_buffer, address).or_else(|| {
+      decode_reference_rip_relative(memory_buffer, address)
+   });
+}
+
+//////////////////////////
+// INSTRUCTION DECODING //
+//////////////////////////
+
+// The longest possible instruction encoding
+const MAX_INSTRUCTION_LENGTH : usize = 15;
+
+// Encoded size of an immediate or
+// relative displacement operand
+#[derive(Clone, Copy)]
+enum Immediate {
+   None,
+   Byte,
+   Word,
+   WordByte,
+   Full,
+   FullOrQuad,
+   Offset,
+   Relative,
+   Relative8,
+}
+
+// Whether an opcode is followed by
+// a ModR/M byte, and if so whether
+// the mod field is honored
+#[derive(Clone, Copy, PartialEq)]
+enum ModRM {
+   None,
+   Memory,
+   RegisterOnly,
+}
+
+type OpcodeInfo = (ModRM, Immediate, ControlFlow);
+
+fn opcode_info_one_byte(
+   opcode : u8,
+) -> Option<OpcodeInfo> {
+   use ControlFlow::*;
+
+   return Some(match opcode {
+      // ALU operations, the remaining
+      // columns are invalid in 64-bit mode
+      0x00..=0x3F => match opcode & 0x07 {
+         0..=3 => (ModRM::Memory,  Immediate::None,  Sequential),
+         4     => (ModRM::None,    Immediate::Byte,  Sequential),
+         5     => (ModRM::None,    Immediate::Full,  Sequential),
+         _     => return None,
+      },
+      0x50..=0x5F => (ModRM::None,     Immediate::None,        Sequential),
+      0x63        => (ModRM::Memory,   Immediate::None,        Sequential),
+      0x68        => (ModRM::None,     Immediate::Full,        Sequential),
+      0x69        => (ModRM::Memory,   Immediate::Full,        Sequential),
+      0x6A        => (ModRM::None,     Immediate::Byte,        Sequential),
+      0x6B        => (ModRM::Memory,   Immediate::Byte,        Sequential),
+      0x6C..=0x6F => (ModRM::None,     Immediate::None,        Sequential),
+      0x70..=0x7F => (ModRM::None,     Immediate::Relative8,   ConditionalJump),
+      0x80        => (ModRM::Memory,   Immediate::Byte,        Sequential),
+      0x81        => (ModRM::Memory,   Immediate::Full,        Sequential),
+      0x83        => (ModRM::Memory,   Immediate::Byte,        Sequential),
+      0x84..=0x8F => (ModRM::Memory,   Immediate::None,        Sequential),
+      0x90..=0x99 => (ModRM::None,     Immediate::None,        Sequential),
+      0x9B..=0x9F => (ModRM::None,     Immediate::None,        Sequential),
+      0xA0..=0xA3 => (ModRM::None,     Immediate::Offset,      Sequential),
+      0xA4..=0xA7 => (ModRM::None,     Immediate::None,        Sequential),
+      0xA8        => (ModRM::None,     Immediate::Byte,        Sequential),
+      0xA9        => (ModRM::None,     Immediate::Full,        Sequential),
+      0xAA..=0xAF => (ModRM::None,     Immediate::None,        Sequential),
+      0xB0..=0xB7 => (ModRM::None,     Immediate::Byte,        Sequential),
+      0xB8..=0xBF => (ModRM::None,     Immediate::FullOrQuad,  Sequential),
+      0xC0 | 0xC1 => (ModRM::Memory,   Immediate::Byte,        Sequential),
+      0xC2        => (ModRM::None,     Immediate::Word,        Return),
+      0xC3        => (ModRM::None,     Immediate::None,        Return),
+      0xC6        => (ModRM::Memory,   Immediate::Byte,        Sequential),
+      0xC7        => (ModRM::Memory,   Immediate::Full,        Sequential),
+      0xC8        => (ModRM::None,     Immediate::WordByte,    Sequential),
+      0xC9        => (ModRM::None,     Immediate::None,        Sequential),
+      0xCA        => (ModRM::None,     Immediate::Word,        Return),
+      0xCB        => (ModRM::None,     Immediate::None,        Return),
+      0xCC        => (ModRM::None,     Immediate::None,        Trap),
+      0xCD        => (ModRM::None,     Immediate::Byte,        Trap),
+      0xCF        => (ModRM::None,     Immediate::None,        Return),
+      0xD0..=0xD3 => (ModRM::Memory,   Immediate::None,        Sequential),
+      0xD7        => (ModRM::None,     Immediate::None,        Sequential),
+      0xD8..=0xDF => (ModRM::Memory,   Immediate::None,        Sequential),
+      0xE0..=0xE3 => (ModRM::None,     Immediate::Relative8,   ConditionalJump),
+      0xE4..=0xE7 => (ModRM::None,     Immediate::Byte,        Sequential),
+      0xE8        => (ModRM::None,     Immediate::Relative,    Call),
+      0xE9        => (ModRM::None,     Immediate::Relative,    Jump),
+      0xEB        => (ModRM::None,     Immediate::Relative8,   Jump),
+      0xEC..=0xEF => (ModRM::None,     Immediate::None,        Sequential),
+      0xF1        => (ModRM::None,     Immediate::None,        Trap),
+      0xF4        => (ModRM::None,     Immediate::None,        Trap),
+      0xF5        => (ModRM::None,     Immediate::None,        Sequential),
+      0xF6 | 0xF7 => (ModRM::Memory,   Immediate::None,        Sequential),
+      0xF8..=0xFD => (ModRM::None,     Immediate::None,        Sequential),
+      0xFE | 0xFF => (ModRM::Memory,   Immediate::None,        Sequential),
+
+      // Prefixes are consumed beforehand, so
+      // anything left is invalid in 64-bit mode
+      _           => return None,
+   });
+}
+
+fn opcode_info_two_byte(
+   opcode : u8,
+) -> Option<OpcodeInfo> {
+   use ControlFlow::*;
+
+   return Some(match opcode {
+      0x00..=0x03 => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x05..=0x09 => (ModRM::None,           Immediate::None,     Sequential),
+      0x0B        => (ModRM::None,           Immediate::None,     Trap),
+      0x0D        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x0E        => (ModRM::None,           Immediate::None,     Sequential),
+      0x0F        => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0x10..=0x1F => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x20..=0x23 => (ModRM::RegisterOnly,   Immediate::None,     Sequential),
+      0x28..=0x2F => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x30..=0x37 => (ModRM::None,           Immediate::None,     Sequential),
+      0x40..=0x6F => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x70..=0x73 => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0x74..=0x76 => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x77        => (ModRM::None,           Immediate::None,     Sequential),
+      0x78..=0x7F => (ModRM::Memory,         Immediate::None,     Sequential),
+      0x80..=0x8F => (ModRM::None,           Immediate::Relative, ConditionalJump),
+      0x90..=0x9F => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xA0..=0xA2 => (ModRM::None,           Immediate::None,     Sequential),
+      0xA3        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xA4        => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0xA5        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xA8..=0xAA => (ModRM::None,           Immediate::None,     Sequential),
+      0xAB        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xAC        => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0xAD..=0xB8 => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xB9        => (ModRM::Memory,         Immediate::None,     Trap),
+      0xBA        => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0xBB..=0xC1 => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xC2        => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0xC3        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xC4..=0xC6 => (ModRM::Memory,         Immediate::Byte,     Sequential),
+      0xC7        => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xC8..=0xCF => (ModRM::None,           Immediate::None,     Sequential),
+      0xD0..=0xFE => (ModRM::Memory,         Immediate::None,     Sequential),
+      0xFF        => (ModRM::Memory,         Immediate::None,     Trap),
+      _           => return None,
+   });
+}
+
+fn opcode_info_three_byte(
+   opcode_map  : u8,
+) -> Option<OpcodeInfo> {
+   return Some(match opcode_map {
+      0x38  => (ModRM::Memory, Immediate::None, ControlFlow::Sequential),
+      0x3A  => (ModRM::Memory, Immediate::Byte, ControlFlow::Sequential),
+      _     => return None,
+   });
+}
+
+// Byte length of the addressing bytes
+// following a ModR/M byte, including
+// the SIB byte and displacement
+fn modrm_operand_length(
+   memory_buffer  : & [u8],
+) -> Option<usize> {
+   let modrm   = *memory_buffer.first()?;
+   let mode    = modrm >> 6;
+   let rm      = modrm & 0x07;
+
+   if mode == 0b11 {
+      return Some(0);
+   }
+
+   let mut length = 0;
+   let mut base   = rm;
+   if rm == 0b100 {
+      base   = memory_buffer.get(1)? & 0x07;
+      length += 1;
+   }
+
+   length += match mode {
+      0b00 if rm == 0b101 || base == 0b101 => 4,
+      0b00                                 => 0,
+      0b01                                 => 1,
+      _                                    => 4,
+   };
+
+   return Some(length);
+}
+
+pub fn decode_instruction(
+   memory_buffer  : & [u8],
+) -> Option<crate::decoder::Instruction> {
+   let mut index                    = 0;
+   let mut operand_size_override    = false;
+   let mut address_size_override    = false;
+   let mut operand_size_quad        = false;
+
+   // Optional - Legacy prefixes
+   loop {
+      match *memory_buffer.get(index)? {
+         0x66                 => operand_size_override = true,
+         0x67                 => address_size_override = true,
+         0xF0 | 0xF2 | 0xF3   => (),
+         0x26 | 0x2E | 0x36   => (),
+         0x3E | 0x64 | 0x65   => (),
+         _                    => break,
+      }
+      index += 1;
+   }
+
+   // Optional - REX prefix
+   if let rex @ REX_FIRST..=REX_LAST = *memory_buffer.get(index)? {
+      operand_size_quad = rex & 0x08 != 0;
+      index += 1;
+   }
+
+   // Required - Opcode, possibly escaped
+   // into a larger opcode map
+   let opcode = *memory_buffer.get(index)?;
+   index += 1;
+
+   let (mut modrm, mut immediate, mut control_flow) = match opcode {
+      // VEX and EVEX encode the opcode map
+      // in their payload bytes
+      0xC4 | 0xC5 | 0x62 => {
+         let (payload_length, opcode_map) = match opcode {
+            0xC5  => (1, 0x01),
+            0xC4  => (2, memory_buffer.get(index)? & 0x1F),
+            _     => (3, memory_buffer.get(index)? & 0x07),
+         };
+         index += payload_length;
+
+         let opcode = *memory_buffer.get(index)?;
+         index += 1;
+
+         match (opcode_map, opcode) {
+            (0x01, 0x77)   => (ModRM::None,   Immediate::None, ControlFlow::Sequential),
+            (0x01, _)      => {
+               let (_, immediate, _) = opcode_info_two_byte(opcode)?;
+               (ModRM::Memory, immediate, ControlFlow::Sequential)
+            },
+            (0x02, _)      => opcode_info_three_byte(0x38)?,
+            (0x03, _)      => opcode_info_three_byte(0x3A)?,
+            _              => (ModRM::Memory, Immediate::None, ControlFlow::Sequential),
+         }
+      },
+
+      0x0F => {
+         let opcode = *memory_buffer.get(index)?;
+         index += 1;
+
+         match opcode {
+            0x38 | 0x3A => {
+               index += 1;
+               opcode_info_three_byte(opcode)?
+            },
+            _           => opcode_info_two_byte(opcode)?,
+         }
+      },
+
+      _ => opcode_info_one_byte(opcode)?,
+   };
+
+   // Optional - ModR/M operand, whose reg
+   // field selects the operation for a
+   // handful of opcodes
+   if modrm != ModRM::None {
+      let reg = (memory_buffer.get(index)? >> 3) & 0x07;
+
+      match (opcode, reg) {
+         (0xF6, 0 | 1)  => immediate    = Immediate::Byte,
+         (0xF7, 0 | 1)  => immediate    = Immediate::Full,
+         (0xFF, 2 | 3)  => control_flow = ControlFlow::Call,
+         (0xFF, 4 | 5)  => control_flow = ControlFlow::Jump,
+         _              => (),
+      }
+
+      if modrm == ModRM::RegisterOnly {
+         modrm = ModRM::None;
+         index += 1;
+      }
+   }
+   let mut relative_operand = None;
+   if modrm == ModRM::Memory {
+      // [rip+disp32] is the only ModR/M form
+      // which depends on the instruction's
+      // own address
+      if *memory_buffer.get(index)? & MODRM_MASK_RIP_RELATIVE == MODRM_RIP_RELATIVE {
+         relative_operand = Some(crate::decoder::RelativeOperand{
+            offset   : index + 1,
+            size     : 4,
+         });
+      }
+
+      index += 1 + modrm_operand_length(memory_buffer.get(index..)?)?;
+   }
+
+   // Optional - Immediate operand
+   match immediate {
+      Immediate::Relative     => relative_operand = Some(crate::decoder::RelativeOperand{
+         offset   : index,
+         size     : 4,
+      }),
+      Immediate::Relative8    => relative_operand = Some(crate::decoder::RelativeOperand{
+         offset   : index,
+         size     : 1,
+      }),
+      _                       => (),
+   }
+
+   let operand_size_full = match operand_size_override {
+      true  => 2,
+      false => 4,
+   };
+   index += match immediate {
+      Immediate::None         => 0,
+      Immediate::Byte         => 1,
+      Immediate::Word         => 2,
+      Immediate::WordByte     => 3,
+      Immediate::Full         => operand_size_full,
+      Immediate::FullOrQuad   => match operand_size_quad {
+         true  => 8,
+         false => operand_size_full,
+      },
+      Immediate::Offset       => match address_size_override {
+         true  => 4,
+         false => 8,
+      },
+      Immediate::Relative     => 4,
+      Immediate::Relative8    => 1,
+   };
+
+   if index > MAX_INSTRUCTION_LENGTH || index > memory_buffer.len() {
+      return None;
+   }
+
+   return Some(crate::decoder::Instruction{
+      length            : index,
+      control_flow      : control_flow,
+      relative_operand  : relative_operand,
+   });
+}
@@ -0,0 +1,173 @@
+//! crate::exception implementation for AMD64.
+
+// Every routine between the begin and end
+// symbols may fault on the memory it's
+// given.  The exception handler sends a
+// fault in one of them to the fixup, which
+// returns 1 after the fault is written to
+// the pointer kept in r11.  None of them
+// touch the stack, so the return address
+// is still on top when the fixup runs.
+// The fault pointer is never dereferenced
+// here, so it's passed as an opaque pointer.
+std::arch::global_asm!(
+   ".p2align 4",
+   ".globl nusion_core_sys_guarded_begin",
+   "nusion_core_sys_guarded_begin:",
+
+   ".globl nusion_core_sys_guarded_copy",
+   "nusion_core_sys_guarded_copy:",
+   "mov r11, rcx",
+   "mov rcx, rdx",
+   "rep movsb",
+   "xor eax, eax",
+   "ret",
+
+   ".globl nusion_core_sys_guarded_merge_8",
+   "nusion_core_sys_guarded_merge_8:",
+   "mov r11, rcx",
+   "and rdx, rsi",
+   "not rsi",
+   "movzx eax, byte ptr [rdi]",
+   "2:",
+   "mov ecx, eax",
+   "and rcx, rsi",
+   "or rcx, rdx",
+   "lock cmpxchg byte ptr [rdi], cl",
+   "jne 2b",
+   "xor eax, eax",
+   "ret",
+
+   ".globl nusion_core_sys_guarded_merge_16",
+   "nusion_core_sys_guarded_merge_16:",
+   "mov r11, rcx",
+   "and rdx, rsi",
+   "not rsi",
+   "movzx eax, word ptr [rdi]",
+   "2:",
+   "mov ecx, eax",
+   "and rcx, rsi",
+   "or rcx, rdx",
+   "lock cmpxchg word ptr [rdi], cx",
+   "jne 2b",
+   "xor eax, eax",
+   "ret",
+
+   ".globl nusion_core_sys_guarded_merge_32",
+   "nusion_core_sys_guarded_merge_32:",
+   "mov r11, rcx",
+   "and rdx, rsi",
+   "not rsi",
+   "mov eax, dword ptr [rdi]",
+   "2:",
+   "mov ecx, eax",
+   "and rcx, rsi",
+   "or rcx, rdx",
+   "lock cmpxchg dword ptr [rdi], ecx",
+   "jne 2b",
+   "xor eax, eax",
+   "ret",
+
+   ".globl nusion_core_sys_guarded_merge_64",
+   "nusion_core_sys_guarded_merge_64:",
+   "mov r11, rcx",
+   "and rdx, rsi",
+   "not rsi",
+   "mov rax, qword ptr [rdi]",
+   "2:",
+   "mov rcx, rax",
+   "and rcx, rsi",
+   "or rcx, rdx",
+   "lock cmpxchg qword ptr [rdi], rcx",
+   "jne 2b",
+   "xor eax, eax",
+   "ret",
+
+   ".globl nusion_core_sys_guarded_end",
+   "nusion_core_sys_guarded_end:",
+
+   ".globl nusion_core_sys_guarded_fixup",
+   "nusion_core_sys_guarded_fixup:",
+   "mov eax, 1",
+   "ret",
+);
+
+extern "sysv64" {
+   fn nusion_core_sys_guarded_begin();
+   fn nusion_core_sys_guarded_end();
+   fn nusion_core_sys_guarded_fixup();
+
+   fn nusion_core_sys_guarded_copy(
+      destination : * mut u8,
+      source      : * const u8,
+      length      : usize,
+      fault       : * mut std::ffi::c_void,
+   ) -> u32;
+
+   fn nusion_core_sys_guarded_merge_8(
+      destination : * mut u8,
+      bit_mask    : u64,
+      bits        : u64,
+      fault       : * mut std::ffi::c_void,
+   ) -> u32;
+
+   fn nusion_core_sys_guarded_merge_16(
+      destination : * mut u8,
+      bit_mask    : u64,
+      bits        : u64,
+      fault       : * mut std::ffi::c_void,
+   ) -> u32;
+
+   fn nusion_core_sys_guarded_merge_32(
+      destination : * mut u8,
+      bit_mask    : u64,
+      bits        : u64,
+      fault       : * mut std::ffi::c_void,
+   ) -> u32;
+
+   fn nusion_core_sys_guarded_merge_64(
+      destination : * mut u8,
+      bit_mask    : u64,
+      bits        : u64,
+      fault       : * mut std::ffi::c_void,
+   ) -> u32;
+}
+
+pub unsafe fn copy(
+   destination : * mut u8,
+   source      : * const u8,
+   length      : usize,
+   fault       : * mut crate::exception::FaultInfo,
+) -> bool {
+   return nusion_core_sys_guarded_copy(destination, source, length, fault.cast()) != 0;
+}
+
+pub unsafe fn merge(
+   destination : * mut u8,
+   length      : usize,
+   bit_mask    : u64,
+   bits        : u64,
+   fault       : * mut crate::exception::FaultInfo,
+) -> bool {
+   let merge = match length {
+      1 => nusion_core_sys_guarded_merge_8,
+      2 => nusion_core_sys_guarded_merge_16,
+      4 => nusion_core_sys_guarded_merge_32,
+      _ => nusion_core_sys_guarded_merge_64,
+   };
+
+   return merge(destination, bit_mask, bits, fault.cast()) != 0;
+}
+
+pub fn fixup_address(
+   instruction_pointer : usize,
+) -> Option<usize> {
+   let begin   = nusion_core_sys_guarded_begin as * const () as usize;
+   let end     = nusion_core_sys_guarded_end as * const () as usize;
+
+   if (begin..end).contains(&instruction_pointer) == false {
+      return None;
+   }
+
+   return Some(nusion_core_sys_guarded_fixup as * const () as usize);
+}
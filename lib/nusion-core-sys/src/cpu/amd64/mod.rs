@@ -5,4 +5,7 @@ mod assembler;
 
 // Public modules
 pub mod compiler;
+pub mod decoder;
+pub mod exception;
+pub mod processor;
 
@@ -0,0 +1,18 @@
+//! crate::processor implementation for AMD64.
+
+pub fn features() -> crate::processor::Features {
+   // This checks both CPUID and whether the
+   // operating system saves the extended
+   // register state, which AVX requires
+   return crate::processor::Features{
+      sse4_1   : std::arch::is_x86_feature_detected!("sse4.1"),
+      sse4_2   : std::arch::is_x86_feature_detected!("sse4.2"),
+      popcnt   : std::arch::is_x86_feature_detected!("popcnt"),
+      avx      : std::arch::is_x86_feature_detected!("avx"),
+      avx2     : std::arch::is_x86_feature_detected!("avx2"),
+      fma      : std::arch::is_x86_feature_detected!("fma"),
+      bmi1     : std::arch::is_x86_feature_detected!("bmi1"),
+      bmi2     : std::arch::is_x86_feature_detected!("bmi2"),
+      lzcnt    : std::arch::is_x86_feature_detected!("lzcnt"),
+   };
+}
@@ -0,0 +1,182 @@
+//! Hardware breakpoints set in the
+//! debug registers of every thread.
+
+/// Number of hardware breakpoints
+/// which can be set at once.
+pub const SLOT_COUNT : usize = 4;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to hardware breakpoints.
+#[derive(Debug)]
+pub enum DebugError {
+   Unsupported,
+   InvalidSlot{
+      slot     : usize,
+   },
+   InvalidLength{
+      length   : usize,
+   },
+   Misaligned{
+      address  : usize,
+      length   : usize,
+   },
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>DebugError</code>.
+pub type Result<T> = std::result::Result<T, DebugError>;
+
+/// The kind of access which
+/// triggers a hardware breakpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WatchKind {
+   Execute,
+   Write,
+   ReadWrite,
+}
+
+/// Information about a thread which
+/// triggered a hardware breakpoint.  For
+/// reads and writes, the instruction pointer
+/// is the instruction after the access.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WatchpointHit {
+   pub slot                   : usize,
+   pub thread_id              : u32,
+   pub instruction_pointer    : usize,
+}
+
+/// Function called from the exception
+/// handler whenever a hardware breakpoint
+/// is triggered.
+pub type HitHandler = fn(& WatchpointHit);
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - DebugError //
+////////////////////////////////////////
+
+impl std::fmt::Display for DebugError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::Unsupported
+            => write!(stream, "Unsupported on this platform"),
+         Self::InvalidSlot{slot}
+            => write!(stream, "Breakpoint slot {slot} doesn't exist"),
+         Self::InvalidLength{length}
+            => write!(stream, "Cannot watch {length} bytes, must be 1, 2, 4 or 8"),
+         Self::Misaligned{address, length}
+            => write!(stream, "Address {address:#X} isn't aligned to {length} bytes"),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+      };
+   }
+}
+
+impl std::error::Error for DebugError {
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Sets a hardware breakpoint in one of
+/// the slots for every thread which currently
+/// exists, replacing any breakpoint already
+/// in the slot.  Execute breakpoints must
+/// have a length of 1.  Threads created
+/// afterwards don't have the breakpoint.
+///
+/// <h2 id=  set_watchpoint_safety>
+/// <a href=#set_watchpoint_safety>
+/// Safety
+/// </a></h2>
+///
+/// Every other thread is briefly suspended,
+/// so all safety concerns from <code><a href=
+/// thread/struct.SuspendedThreads.html#suspended_threads_safety>SuspendedThreads</a></code>
+/// apply.  Debuggers and anti-cheat software
+/// may also use the debug registers.
+pub unsafe fn set_watchpoint(
+   slot     : usize,
+   address  : usize,
+   length   : usize,
+   kind     : WatchKind,
+) -> Result<()> {
+   if slot >= SLOT_COUNT {
+      return Err(DebugError::InvalidSlot{
+         slot     : slot,
+      });
+   }
+   let valid_length = match kind {
+      WatchKind::Execute   => length == 1,
+      _                    => matches!(length, 1 | 2 | 4 | 8),
+   };
+   if valid_length == false {
+      return Err(DebugError::InvalidLength{
+         length   : length,
+      });
+   }
+   if address % length != 0 {
+      return Err(DebugError::Misaligned{
+         address  : address,
+         length   : length,
+      });
+   }
+
+   return crate::os::debug::set_watchpoint(slot, address, length, kind);
+}
+
+/// Clears the hardware breakpoint in a
+/// slot for every thread which currently
+/// exists.
+///
+/// <h2 id=  clear_watchpoint_safety>
+/// <a href=#clear_watchpoint_safety>
+/// Safety
+/// </a></h2>
+///
+/// See the safety section for <code><a href=
+/// #set_watchpoint_safety>set_watchpoint</a></code>.
+pub unsafe fn clear_watchpoint(
+   slot : usize,
+) -> Result<()> {
+   if slot >= SLOT_COUNT {
+      return Err(DebugError::InvalidSlot{
+         slot     : slot,
+      });
+   }
+
+   return crate::os::debug::clear_watchpoint(slot);
+}
+
+/// Sets the function called whenever a
+/// hardware breakpoint is triggered,
+/// installing the exception handler if it
+/// isn't already.  The handler runs on the
+/// thread which triggered the breakpoint
+/// while it is stopped in the exception,
+/// so it should return quickly.
+pub fn set_hit_handler(
+   handler : HitHandler,
+) -> Result<()> {
+   return crate::os::debug::set_hit_handler(handler);
+}
+
+/// Removes the exception handler installed
+/// by <code>set_hit_handler</code>.  This
+/// must be done before the library is
+/// unloaded, since the handler would
+/// otherwise be left pointing at freed code.
+pub fn clear_hit_handler(
+) {
+   crate::os::debug::clear_hit_handler();
+   return;
+}
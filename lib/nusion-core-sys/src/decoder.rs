@@ -0,0 +1,96 @@
+//! Machine code decoding functions.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// The kind of instruction which
+/// references a memory address.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReferenceKind {
+   LoadEffectiveAddress,
+   Move,
+   Call,
+   Jump,
+   ConditionalJump,
+}
+
+/// How an instruction affects the
+/// flow of execution after it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ControlFlow {
+   Sequential,
+   ConditionalJump,
+   Jump,
+   Call,
+   Return,
+   Trap,
+}
+
+/// Basic information about a single
+/// decoded machine code instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Instruction {
+   pub length           : usize,
+   pub control_flow     : ControlFlow,
+   pub relative_operand : Option<RelativeOperand>,
+}
+
+/// The location of an operand within
+/// an instruction which stores a signed
+/// displacement relative to the end of
+/// the instruction, such as a branch
+/// target or an instruction pointer-relative
+/// memory operand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RelativeOperand {
+   pub offset  : usize,
+   pub size    : usize,
+}
+
+/// An instruction which references
+/// a memory address relative to its
+/// own location in memory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reference {
+   pub kind                : ReferenceKind,
+   pub instruction_length  : usize,
+   pub target_address      : usize,
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Attempts to decode the instruction
+/// at the start of a memory buffer.
+/// If the bytes don't encode a valid
+/// instruction or the buffer is too
+/// short to contain the entire
+/// instruction, <code>None</code>
+/// is returned.
+pub fn decode_instruction(
+   memory_buffer  : & [u8],
+) -> Option<Instruction> {
+   return crate::cpu::decoder::decode_instruction(
+      memory_buffer,
+   );
+}
+
+/// Attempts to decode an instruction
+/// at the start of a memory buffer
+/// which references a memory address
+/// relative to the instruction pointer
+/// / program counter.  The address is
+/// the location in memory the buffer
+/// will be executed from.  If the bytes
+/// don't encode a supported reference,
+/// <code>None</code> is returned.
+pub fn decode_reference(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<Reference> {
+   return crate::cpu::decoder::decode_reference(
+      memory_buffer, address,
+   );
+}
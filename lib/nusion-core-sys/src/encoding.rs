@@ -0,0 +1,186 @@
+//! Conversions between Rust strings and
+//! the narrow and wide null-terminated
+//! strings used by operating system APIs.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Error type for a string which
+/// can't be converted.
+#[derive(Debug)]
+pub enum EncodingError {
+   InvalidUtf8,
+   InvalidUtf16,
+   InteriorNul{
+      position : usize,
+   },
+   MissingNulTerminator,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>EncodingError</code>.
+pub type Result<T> = std::result::Result<T, EncodingError>;
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - EncodingError //
+///////////////////////////////////////////
+
+impl std::fmt::Display for EncodingError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::InvalidUtf8
+            => write!(stream, "String contains invalid UTF-8"),
+         Self::InvalidUtf16
+            => write!(stream, "String contains invalid UTF-16"),
+         Self::InteriorNul{position}
+            => write!(stream, "String contains a null character at position {position}"),
+         Self::MissingNulTerminator
+            => write!(stream, "String is missing a null terminator"),
+      };
+   }
+}
+
+impl std::error::Error for EncodingError {
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Encodes a string as null-terminated
+/// UTF-16 for wide operating system APIs.
+/// Fails if the string contains a null
+/// character, since the API would see
+/// the string end early.
+pub fn to_wide_nul(
+   string : & str,
+) -> Result<Vec<u16>> {
+   let mut wide = Vec::with_capacity(string.len() + 1);
+
+   for (position, unit) in string.encode_utf16().enumerate() {
+      if unit == 0 {
+         return Err(EncodingError::InteriorNul{
+            position : position,
+         });
+      }
+      wide.push(unit);
+   }
+
+   wide.push(0);
+   return Ok(wide);
+}
+
+/// Encodes a string as a null-terminated
+/// byte string for narrow operating system
+/// APIs.  Fails if the string contains a
+/// null character.
+pub fn to_narrow_nul(
+   string : & str,
+) -> Result<Vec<u8>> {
+   if let Some(position) = string.bytes().position(|byte| byte == 0) {
+      return Err(EncodingError::InteriorNul{
+         position : position,
+      });
+   }
+
+   let mut narrow = Vec::with_capacity(string.len() + 1);
+   narrow.extend_from_slice(string.as_bytes());
+   narrow.push(0);
+   return Ok(narrow);
+}
+
+/// Decodes UTF-16 into a string, failing
+/// on unpaired surrogates.
+pub fn from_wide(
+   wide : & [u16],
+) -> Result<String> {
+   return String::from_utf16(wide).map_err(|_| EncodingError::InvalidUtf16);
+}
+
+/// Decodes UTF-16 into a string, replacing
+/// unpaired surrogates with
+/// <code>U+FFFD</code>.
+pub fn from_wide_lossy(
+   wide : & [u16],
+) -> String {
+   return String::from_utf16_lossy(wide);
+}
+
+/// Decodes a null-terminated UTF-16 buffer
+/// into a string, ignoring everything after
+/// the first null character.  This is the
+/// form returned by most wide APIs which fill
+/// a fixed-size buffer.
+pub fn from_wide_nul(
+   wide : & [u16],
+) -> Result<String> {
+   let length = wide.iter().position(|unit| *unit == 0)
+      .ok_or(EncodingError::MissingNulTerminator)?;
+
+   return from_wide(&wide[..length]);
+}
+
+/// Decodes a null-terminated byte buffer
+/// as UTF-8, ignoring everything after the
+/// first null character.
+pub fn from_narrow_nul(
+   narrow : & [u8],
+) -> Result<String> {
+   let length = narrow.iter().position(|byte| *byte == 0)
+      .ok_or(EncodingError::MissingNulTerminator)?;
+
+   return String::from_utf8(narrow[..length].to_vec()).map_err(
+      |_| EncodingError::InvalidUtf8,
+   );
+}
+
+/// Creates a slice over a null-terminated
+/// wide string from a raw pointer, excluding
+/// the terminator.  At most <code>max_length</code>
+/// characters are searched for the terminator.
+///
+/// <h2 id=  encoding_read_wide_nul_safety>
+/// <a href=#encoding_read_wide_nul_safety>
+/// Safety
+/// </a></h2>
+///
+/// The pointer must be aligned and valid
+/// for reads up to and including the
+/// terminator or <code>max_length</code>
+/// characters, whichever comes first, and
+/// the memory must not change while the
+/// slice is alive.
+pub unsafe fn read_wide_nul<'l>(
+   pointer     : * const u16,
+   max_length  : usize,
+) -> Result<&'l [u16]> {
+   for length in 0..max_length {
+      if *pointer.add(length) == 0 {
+         return Ok(std::slice::from_raw_parts(pointer, length));
+      }
+   }
+
+   return Err(EncodingError::MissingNulTerminator);
+}
+
+/// Reads a null-terminated wide string
+/// from a raw pointer into an owned string.
+///
+/// <h2 id=  encoding_read_wide_nul_string_safety>
+/// <a href=#encoding_read_wide_nul_string_safety>
+/// Safety
+/// </a></h2>
+///
+/// All safety concerns from <code><a href=
+/// #encoding_read_wide_nul_safety>read_wide_nul</a></code>
+/// apply.
+pub unsafe fn read_wide_nul_string(
+   pointer     : * const u16,
+   max_length  : usize,
+) -> Result<String> {
+   return from_wide(read_wide_nul(pointer, max_length)?);
+}
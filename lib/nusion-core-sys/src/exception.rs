@@ -0,0 +1,398 @@
+//! Recovery from memory access faults
+//! using a process-wide exception handler.
+
+/// Maximum number of regions which can
+/// be registered with <code>add_guarded_region</code>
+/// at the same time.
+pub const MAX_GUARDED_REGIONS : usize = 64;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to exception handling.
+#[derive(Debug)]
+pub enum ExceptionError {
+   Fault{
+      fault    : FaultInfo,
+   },
+   TooManyRegions{
+      maximum  : usize,
+   },
+   BitFieldTooWide{
+      length   : usize,
+   },
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ExceptionError</code>.
+pub type Result<T> = std::result::Result<T, ExceptionError>;
+
+/// The kind of memory access
+/// which caused a fault.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FaultAccess {
+   Read,
+   Write,
+   Execute,
+   Unknown,
+}
+
+/// Information about a memory access
+/// fault.  The code is the exception code
+/// on Windows and the signal number on Linux.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FaultInfo {
+   pub code                   : u32,
+   pub access                 : FaultAccess,
+   pub fault_address          : usize,
+   pub instruction_pointer    : usize,
+}
+
+/// What to do with a fault after
+/// a region callback has seen it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FaultAction {
+   /// Runs the faulting instruction again,
+   /// after the callback has fixed whatever
+   /// caused the fault, such as by committing
+   /// the page.  The instruction faults again
+   /// otherwise.
+   Retry,
+   /// Passes the fault on to the next
+   /// handler, which usually crashes.
+   Continue,
+}
+
+/// Function called from the exception
+/// handler when a fault is caused by an
+/// access within a guarded region.
+pub type FaultCallback = fn(& FaultInfo) -> FaultAction;
+
+/// What the OS exception handler should
+/// do with a fault.
+pub(crate) enum FaultResponse {
+   /// Continues execution at the address.
+   Resume(usize),
+   /// Runs the faulting instruction again.
+   Retry,
+   /// Passes the fault on to the next handler.
+   Continue,
+}
+
+/// A slot which may hold a registered
+/// address range and the function called
+/// for faults inside it.  The exception
+/// handler reads these without locking,
+/// so the callback is stored last when
+/// a region is added and cleared first
+/// when it's removed.
+struct GuardedRegion {
+   id             : std::sync::atomic::AtomicUsize,
+   start          : std::sync::atomic::AtomicUsize,
+   end            : std::sync::atomic::AtomicUsize,
+   callback       : std::sync::atomic::AtomicUsize,
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ExceptionError //
+////////////////////////////////////////////
+
+impl std::fmt::Display for ExceptionError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::Fault{fault}
+            => write!(stream, "{fault}"),
+         Self::TooManyRegions{maximum}
+            => write!(stream, "No more than {maximum} guarded regions can be registered"),
+         Self::BitFieldTooWide{length}
+            => write!(stream, "Bit field of {length} bytes is not 1 to 8 bytes long"),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+      };
+   }
+}
+
+impl std::error::Error for ExceptionError {
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - FaultAccess //
+/////////////////////////////////////////
+
+impl std::fmt::Display for FaultAccess {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::Read     => write!(stream, "Read"),
+         Self::Write    => write!(stream, "Write"),
+         Self::Execute  => write!(stream, "Execute"),
+         Self::Unknown  => write!(stream, "Unknown"),
+      };
+   }
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - FaultInfo //
+///////////////////////////////////////
+
+impl std::fmt::Display for FaultInfo {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream,
+         "{} access to {:#X} faulted at instruction {:#X} with code {:#X}",
+         self.access,
+         self.fault_address,
+         self.instruction_pointer,
+         self.code,
+      );
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Installs the process-wide exception
+/// handler if it isn't already.  This is
+/// done automatically by the other functions
+/// in this module.
+pub fn install(
+) -> Result<()> {
+   if INSTALLED.load(std::sync::atomic::Ordering::Acquire) == true {
+      return Ok(());
+   }
+
+   crate::os::exception::install()?;
+   INSTALLED.store(true, std::sync::atomic::Ordering::Release);
+   return Ok(());
+}
+
+/// Removes the exception handler.  This
+/// must be done before the library is
+/// unloaded, since the handler would
+/// otherwise be left pointing at freed code.
+pub fn uninstall(
+) {
+   INSTALLED.store(false, std::sync::atomic::Ordering::Release);
+   crate::os::exception::uninstall();
+   return;
+}
+
+/// Registers a function which is called
+/// whenever any thread faults while
+/// accessing memory inside an address
+/// range, returning an ID which can be
+/// used to remove it again.  The callback
+/// runs inside the exception handler, so
+/// it must not allocate, lock or log, and
+/// must not access the region itself.
+pub fn add_guarded_region(
+   address_range  : std::ops::Range<usize>,
+   callback       : FaultCallback,
+) -> Result<usize> {
+   use std::sync::atomic::Ordering;
+
+   install()?;
+
+   let id = NEXT_REGION_ID.fetch_add(1, Ordering::Relaxed);
+   for region in REGIONS.iter() {
+      if region.id.compare_exchange(0, id, Ordering::AcqRel, Ordering::Relaxed).is_err() {
+         continue;
+      }
+
+      region.start.store(address_range.start, Ordering::Relaxed);
+      region.end.store(address_range.end, Ordering::Relaxed);
+      region.callback.store(callback as usize, Ordering::Release);
+      return Ok(id);
+   }
+
+   return Err(ExceptionError::TooManyRegions{
+      maximum : MAX_GUARDED_REGIONS,
+   });
+}
+
+/// Removes a region registered with
+/// <code>add_guarded_region</code>.
+pub fn remove_guarded_region(
+   id : usize,
+) {
+   use std::sync::atomic::Ordering;
+
+   if let Some(region) = REGIONS.iter().find(|region| region.id.load(Ordering::Acquire) == id) {
+      region.callback.store(0, Ordering::Release);
+      region.id.store(0, Ordering::Release);
+   }
+
+   return;
+}
+
+/// Copies bytes between two addresses,
+/// returning the fault as an error instead
+/// of crashing if either of them can't be
+/// accessed.  The copy stops at the faulting
+/// byte, so the destination may be left
+/// partly written.
+///
+/// <h2 id=  guarded_copy_safety>
+/// <a href=#guarded_copy_safety>
+/// Safety
+/// </a></h2>
+///
+/// Whatever is mapped at the destination
+/// is overwritten, and nothing stops the
+/// source and destination from being
+/// accessed by other threads at the same
+/// time.
+pub unsafe fn guarded_copy(
+   destination : * mut u8,
+   source      : * const u8,
+   length      : usize,
+) -> Result<()> {
+   install()?;
+
+   let mut fault = std::mem::MaybeUninit::<FaultInfo>::uninit();
+   if crate::cpu::exception::copy(destination, source, length, fault.as_mut_ptr()) == true {
+      return Err(ExceptionError::Fault{
+         fault : fault.assume_init(),
+      });
+   }
+
+   return Ok(());
+}
+
+/// Replaces the masked bits of a little-endian
+/// bit field of 1 to 8 bytes, returning the
+/// fault as an error instead of crashing if
+/// the field can't be accessed.  This is a
+/// single atomic read-modify-write if the
+/// field is naturally aligned and the size
+/// of an integer type, otherwise one atomic
+/// read-modify-write for each byte.
+///
+/// <h2 id=  guarded_merge_safety>
+/// <a href=#guarded_merge_safety>
+/// Safety
+/// </a></h2>
+///
+/// The bits selected by the mask are
+/// overwritten in whatever is mapped
+/// at the destination.
+pub unsafe fn guarded_merge(
+   destination : * mut u8,
+   length      : usize,
+   bit_mask    : u64,
+   bits        : u64,
+) -> Result<()> {
+   if length == 0 || length > std::mem::size_of::<u64>() {
+      return Err(ExceptionError::BitFieldTooWide{
+         length : length,
+      });
+   }
+
+   install()?;
+
+   let mut fault = std::mem::MaybeUninit::<FaultInfo>::uninit();
+   let faulted = match (length, destination as usize % length) {
+      (1 | 2 | 4 | 8, 0)
+         => crate::cpu::exception::merge(destination, length, bit_mask, bits, fault.as_mut_ptr()),
+      _  => (0..length).any(|offset| {
+         let shift = offset as u32 * u8::BITS;
+         return crate::cpu::exception::merge(
+            destination.wrapping_add(offset),
+            1,
+            (bit_mask >> shift) & 0xFF,
+            (bits >> shift) & 0xFF,
+            fault.as_mut_ptr(),
+         );
+      }),
+   };
+
+   if faulted == true {
+      return Err(ExceptionError::Fault{
+         fault : fault.assume_init(),
+      });
+   }
+
+   return Ok(());
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Called by the OS exception handler for
+/// every memory access fault.  The fault
+/// register is the register which holds
+/// the fault pointer while a guarded copy
+/// or merge runs.  This runs inside the
+/// exception handler, so it must not
+/// allocate or lock.
+pub(crate) fn dispatch_fault(
+   fault          : & FaultInfo,
+   fault_register : usize,
+) -> FaultResponse {
+   use std::sync::atomic::Ordering;
+
+   // The faulting access was made by one
+   // of the guarded routines, which always
+   // passes a valid pointer to write it to
+   if let Some(fixup_address) = crate::cpu::exception::fixup_address(fault.instruction_pointer) {
+      unsafe{(fault_register as * mut FaultInfo).write(*fault)};
+      return FaultResponse::Resume(fixup_address);
+   }
+
+   for region in REGIONS.iter() {
+      let callback = region.callback.load(Ordering::Acquire);
+      if callback == 0 {
+         continue;
+      }
+
+      let start   = region.start.load(Ordering::Relaxed);
+      let end     = region.end.load(Ordering::Relaxed);
+      if (start..end).contains(&fault.fault_address) == false {
+         continue;
+      }
+
+      let callback = unsafe{std::mem::transmute::<usize, FaultCallback>(callback)};
+      return match callback(fault) {
+         FaultAction::Retry      => FaultResponse::Retry,
+         FaultAction::Continue   => FaultResponse::Continue,
+      };
+   }
+
+   return FaultResponse::Continue;
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static REGIONS
+   : [GuardedRegion; MAX_GUARDED_REGIONS]
+   = [const {GuardedRegion{
+      id             : std::sync::atomic::AtomicUsize::new(0),
+      start          : std::sync::atomic::AtomicUsize::new(0),
+      end            : std::sync::atomic::AtomicUsize::new(0),
+      callback       : std::sync::atomic::AtomicUsize::new(0),
+   }}; MAX_GUARDED_REGIONS];
+
+// Zero marks a free slot, so
+// IDs start counting at one
+static NEXT_REGION_ID
+   : std::sync::atomic::AtomicUsize
+   = std::sync::atomic::AtomicUsize::new(1);
+
+static INSTALLED
+   : std::sync::atomic::AtomicBool
+   = std::sync::atomic::AtomicBool::new(false);
+
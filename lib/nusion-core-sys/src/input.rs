@@ -0,0 +1,18 @@
+//! Polled keyboard and mouse button state.
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Checks whether a key is currently
+/// held down, identified by its Windows
+/// virtual-key code.  This reads the
+/// global key state, so it works even
+/// while the game has its own input
+/// handling.
+pub fn is_key_down(
+   virtual_key : u8,
+) -> bool {
+   return crate::os::input::is_key_down(virtual_key);
+}
+
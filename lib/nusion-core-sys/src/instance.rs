@@ -0,0 +1,86 @@
+//! Detecting multiple instances of the
+//! same mod within one process.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to acquiring an instance lock.
+#[derive(Debug)]
+pub enum InstanceError {
+   InvalidName,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>InstanceError</code>.
+pub type Result<T> = std::result::Result<T, InstanceError>;
+
+/// A named lock held for as long as
+/// a mod is running, released on drop.
+/// The name is scoped to the current
+/// process, so the same mod injected
+/// into two different processes does
+/// not conflict.
+pub struct InstanceLock {
+   lock  : crate::os::instance::InstanceLock,
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - InstanceError //
+///////////////////////////////////////////
+
+impl std::fmt::Display for InstanceError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::InvalidName
+            => "Instance name contains invalid characters",
+         Self::Unknown
+            => "Unknown",
+      });
+   }
+}
+
+impl std::error::Error for InstanceError {
+}
+
+////////////////////////////
+// METHODS - InstanceLock //
+////////////////////////////
+
+impl InstanceLock {
+   /// Attempts to acquire the lock with
+   /// the given name, returning <code>None</code>
+   /// if another instance in this process
+   /// already holds it.
+   pub fn acquire(
+      name : & str,
+   ) -> Result<Option<Self>> {
+      if name.is_empty() == true || name.contains(['\\', '/', '\0']) == true {
+         return Err(InstanceError::InvalidName);
+      }
+
+      let name = format!("nusion-{name}-{}", std::process::id());
+      let lock = crate::os::instance::InstanceLock::acquire(&name)?;
+
+      return Ok(lock.map(|lock| Self{lock: lock}));
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - InstanceLock //
+//////////////////////////////////////////
+
+impl std::fmt::Debug for InstanceLock {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "InstanceLock {{ {:?} }}", self.lock.name());
+   }
+}
+
@@ -14,9 +14,23 @@ mod cpu;
 pub use os::osapi as __osapi;
 
 // Public modules
+pub mod assembler;
+pub mod clipboard;
 pub mod console;
 pub mod compiler;
+pub mod debug;
+pub mod decoder;
+pub mod encoding;
 pub mod environment;
+pub mod exception;
+pub mod input;
+pub mod instance;
 pub mod memory;
+#[cfg(feature = "overlay")]
+pub mod overlay;
 pub mod process;
+pub mod processor;
+pub mod screenshot;
+pub mod thread;
+pub mod window;
 
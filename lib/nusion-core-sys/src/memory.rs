@@ -41,6 +41,15 @@ pub struct MemoryEditor {
    old_permissions   : crate::os::memory::MemoryPermissions,
 }
 
+/// Information about a contiguous range
+/// of pages which all share the same
+/// state and access permissions.  This
+/// is only a snapshot, so the region may
+/// change at any point after it is queried.
+pub struct MemoryRegion {
+   region : crate::os::memory::MemoryRegion,
+}
+
 ///////////////////////////
 // METHODS - MemoryError //
 ///////////////////////////
@@ -119,6 +128,99 @@ impl std::fmt::Display for MemoryErrorKind {
    }
 }
 
+////////////////////////////
+// METHODS - MemoryRegion //
+////////////////////////////
+
+impl MemoryRegion {
+   /// Queries the region of pages
+   /// containing a memory address.
+   pub fn query(
+      address : usize,
+   ) -> Result<Self> {
+      return Ok(Self{
+         region : crate::os::memory::MemoryRegion::query(address)?,
+      });
+   }
+
+   /// Queries every region of pages
+   /// which overlaps an address range.
+   /// The first and last regions may
+   /// extend past the ends of the range.
+   pub fn all_within(
+      address_range : std::ops::Range<usize>,
+   ) -> Result<Vec<Self>> {
+      if address_range.end < address_range.start {
+         return Err(MemoryError::new(
+            MemoryErrorKind::InvalidAddressRange,
+            address_range,
+         ));
+      }
+
+      let mut list    = Vec::new();
+      let mut address = address_range.start;
+      while address < address_range.end {
+         let region = Self::query(address)?;
+
+         // Zero-length regions would loop forever
+         if region.address_range().end <= address {
+            return Err(MemoryError::new(
+               MemoryErrorKind::Unknown,
+               address..address_range.end,
+            ));
+         }
+
+         address = region.address_range().end;
+         list.push(region);
+      }
+
+      return Ok(list);
+   }
+
+   /// Gets the address range spanned
+   /// by the region.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.region.address_range;
+   }
+
+   /// Whether the pages in the region
+   /// are backed by committed memory.
+   pub fn is_committed(
+      & self,
+   ) -> bool {
+      return self.region.committed;
+   }
+
+   /// Whether the pages in the region
+   /// can currently be read from without
+   /// changing their permissions.
+   pub fn is_readable(
+      & self,
+   ) -> bool {
+      return self.region.readable;
+   }
+
+   /// Whether the pages in the region
+   /// can currently be written to without
+   /// changing their permissions.
+   pub fn is_writable(
+      & self,
+   ) -> bool {
+      return self.region.writable;
+   }
+
+   /// Whether the pages in the region
+   /// can currently be executed without
+   /// changing their permissions.
+   pub fn is_executable(
+      & self,
+   ) -> bool {
+      return self.region.executable;
+   }
+}
+
 /////////////////////////////////////
 // INTERNAL HELPERS - MemoryEditor //
 /////////////////////////////////////
@@ -208,6 +310,14 @@ impl MemoryEditor {
       );
    }
 
+   /// Gets the address range opened
+   /// by the editor.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.address_range;
+   }
+
    /// Creates a slice type referencing
    /// the data in the stored memory location.
    ///
@@ -335,6 +445,21 @@ impl MemoryEditor {
    ) -> &'l mut [u8] {
       return self.as_slice_mut::<u8>();
    }
+
+   /// Restores the memory's original
+   /// permissions now, returning an error
+   /// if they can't be restored instead of
+   /// panicking like dropping the editor.
+   pub fn close(
+      self,
+   ) -> Result<()> {
+      let editor = std::mem::ManuallyDrop::new(self);
+      crate::os::memory::MemoryPermissions::set(
+         &editor.address_range,
+         &editor.old_permissions,
+      )?;
+      return Ok(());
+   }
 }
 
 //////////////////////////////////////////
@@ -355,3 +480,116 @@ impl Drop for MemoryEditor {
    }
 }
 
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Allocates executable memory which can
+/// be reached from an address with a 32-bit
+/// relative jump or call, which is anywhere
+/// within about 2GB of the address.  Small
+/// allocations share pages with each other,
+/// so this is suitable for many tiny trampolines
+/// and hook stubs.
+///
+/// The memory is never freed, since a thread
+/// may still be executing it long after the
+/// code which jumps to it is restored.
+pub fn allocate_executable_near(
+   address  : usize,
+   size     : usize,
+) -> Result<std::ops::Range<usize>> {
+   let mut pool = NEAR_POOL.lock().unwrap_or_else(|e| e.into_inner());
+
+   // Reuse free space from an existing block
+   for block in pool.iter_mut() {
+      let start = align_up(block.used, NEAR_ALIGNMENT);
+      let end   = match start.checked_add(size) {
+         Some(end)   => end,
+         None        => continue,
+      };
+
+      if end > block.address_range.end
+      || is_near(address, &(start..end)) == false {
+         continue;
+      }
+
+      block.used = end;
+      return Ok(start..end);
+   }
+
+   // Allocate a new block, rounded up so the
+   // rest of it can be shared with others
+   let block_size    = align_up(size, NEAR_BLOCK_SIZE);
+   let address_range = crate::os::memory::allocate_executable_near(
+      address, block_size, NEAR_REACH,
+   )?;
+
+   let start = address_range.start;
+   pool.push(NearBlock{
+      address_range  : address_range,
+      used           : start + size,
+   });
+
+   return Ok(start..start + size);
+}
+
+/// Copies memory starting at an address
+/// into a buffer.  Unlike reading through
+/// a pointer, an error is returned instead
+/// of crashing if any of the memory is
+/// unmapped or can't be read, such as when
+/// another thread frees it during the copy.
+pub fn read_checked(
+   address  : usize,
+   buffer   : & mut [u8],
+) -> Result<()> {
+   return crate::os::memory::read_checked(
+      address, buffer,
+   );
+}
+
+/// Gets the size of a page of memory,
+/// which is the smallest unit memory
+/// permissions can be changed in.
+pub fn page_size(
+) -> usize {
+   return crate::os::memory::page_size();
+}
+
+/// Whether an address range can be reached
+/// from an address with a 32-bit relative
+/// jump or call.
+pub fn is_near(
+   address        : usize,
+   address_range  : & std::ops::Range<usize>,
+) -> bool {
+   return address.abs_diff(address_range.start) < NEAR_REACH
+      && address.abs_diff(address_range.end)    < NEAR_REACH;
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+// Leaves some slack below 2GB for the
+// length of the instruction doing the jump
+const NEAR_REACH        : usize = 0x7FF0_0000;
+const NEAR_BLOCK_SIZE   : usize = 0x1000;
+const NEAR_ALIGNMENT    : usize = 16;
+
+struct NearBlock {
+   address_range  : std::ops::Range<usize>,
+   used           : usize,
+}
+
+static NEAR_POOL : std::sync::Mutex<Vec<NearBlock>>
+   = std::sync::Mutex::new(Vec::new());
+
+fn align_up(
+   value       : usize,
+   alignment   : usize,
+) -> usize {
+   return value.div_ceil(alignment) * alignment;
+}
@@ -0,0 +1,52 @@
+//! crate::os::clipboard implementation for Linux.
+//! There is no clipboard API without linking
+//! against a display server, so the text is
+//! piped to the first clipboard tool found.
+
+use std::io::Write;
+
+// Commands which read clipboard text
+// from stdin, in order of preference
+const CLIPBOARD_COMMANDS : &[&[&str]] = &[
+   &["wl-copy"],
+   &["xclip", "-selection", "clipboard"],
+   &["xsel", "--clipboard", "--input"],
+];
+
+pub fn set_text(
+   text : & str,
+) -> crate::clipboard::Result<()> {
+   for command in CLIPBOARD_COMMANDS {
+      let child = std::process::Command::new(command[0])
+         .args(&command[1..])
+         .stdin(std::process::Stdio::piped())
+         .stdout(std::process::Stdio::null())
+         .stderr(std::process::Stdio::null())
+         .spawn();
+
+      // Try the next tool if this one
+      // isn't installed
+      let mut child = match child {
+         Ok(child)   => child,
+         Err(_)      => continue,
+      };
+
+      let written = match child.stdin.take() {
+         Some(mut stdin)   => stdin.write_all(text.as_bytes()).is_ok(),
+         None              => false,
+      };
+
+      let status = child.wait().map_err(
+         |_| crate::clipboard::ClipboardError::Unknown,
+      )?;
+
+      if written == false || status.success() == false {
+         return Err(crate::clipboard::ClipboardError::Unknown);
+      }
+
+      return Ok(());
+   }
+
+   return Err(crate::clipboard::ClipboardError::Unsupported);
+}
+
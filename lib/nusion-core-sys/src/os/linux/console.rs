@@ -0,0 +1,382 @@
+//! crate::os::console implementation for Linux.
+//! There is no console window to allocate,
+//! so output goes to the terminal the game
+//! was started from, if any.
+
+use libc::{
+   close,
+   dup,
+   dup2,
+   dup3,
+   isatty,
+   pipe2,
+   poll,
+   pollfd,
+   read,
+   tcgetattr,
+   tcsetattr,
+   termios,
+   ECHO,
+   ICANON,
+   O_CLOEXEC,
+   POLLIN,
+   STDERR_FILENO,
+   STDIN_FILENO,
+   STDOUT_FILENO,
+   TCSANOW,
+   VMIN,
+   VTIME,
+};
+
+pub struct Console {
+   title : String,
+}
+
+// Copies of the original output and error
+// descriptors, which are put back when
+// restoring, and the write ends of
+// their pipes
+pub struct OutputRedirect {
+   output   : i32,
+   error    : i32,
+   writes   : [i32; 2],
+}
+
+// Reads bytes from standard input with
+// the terminal in non-canonical mode so
+// keys arrive as they're pressed
+pub struct KeyReader {
+   original : Option<termios>,
+}
+
+impl Console {
+   pub fn allocate(
+   ) -> crate::console::Result<Self> {
+      return Ok(Self{
+         title : String::new(),
+      });
+   }
+
+   pub fn attach(
+   ) -> crate::console::Result<Self> {
+      return Self::allocate();
+   }
+
+   pub fn free(
+      & mut self,
+   ) -> crate::console::Result<()> {
+      return Ok(());
+   }
+
+   pub fn get_title(
+      & self,
+   ) -> crate::console::Result<String> {
+      return Ok(self.title.clone());
+   }
+
+   pub fn set_title(
+      & mut self,
+      new_title : & str,
+   ) -> crate::console::Result<()> {
+      // Control characters would end the
+      // escape sequence early
+      if new_title.chars().any(char::is_control) == true {
+         return Err(crate::console::ConsoleError::InvalidTitleCharacters);
+      }
+
+      // Set the terminal's title with an
+      // xterm escape sequence, which most
+      // terminal emulators understand
+      if unsafe{isatty(STDOUT_FILENO)} == 1 {
+         use std::io::Write;
+
+         let mut stdout = std::io::stdout();
+         if write!(stdout, "\x1B]0;{new_title}\x07").is_err() == true ||
+            stdout.flush().is_err() == true
+         {
+            return Err(crate::console::ConsoleError::Unknown);
+         }
+      }
+
+      self.title = String::from(new_title);
+      return Ok(());
+   }
+
+   // The terminal belongs to the game,
+   // so its signals are left alone
+   pub fn is_close_requested(
+      & self,
+   ) -> bool {
+      return false;
+   }
+
+   // Output goes straight to the terminal,
+   // which can't be read back
+   pub fn read_lines(
+      & self,
+      _count : usize,
+   ) -> crate::console::Result<Vec<String>> {
+      return Err(crate::console::ConsoleError::Unsupported);
+   }
+}
+
+impl OutputRedirect {
+   pub fn capture(
+   ) -> crate::console::Result<(Self, [(std::fs::File, Option<std::fs::File>); 2])> {
+      use std::os::unix::io::FromRawFd;
+
+      let mut output_pipe  = [-1i32; 2];
+      let mut error_pipe   = [-1i32; 2];
+      let pipes_created = unsafe{pipe2(output_pipe.as_mut_ptr(), O_CLOEXEC)} == 0
+         && unsafe{pipe2(error_pipe.as_mut_ptr(), O_CLOEXEC)} == 0;
+      let [output_read, output_write]  = output_pipe;
+      let [error_read, error_write]    = error_pipe;
+
+      let output        = unsafe{dup(STDOUT_FILENO)};
+      let error         = unsafe{dup(STDERR_FILENO)};
+      let output_copy   = unsafe{dup(STDOUT_FILENO)};
+      let error_copy    = unsafe{dup(STDERR_FILENO)};
+
+      // dup2 would clear close-on-exec, so
+      // child processes would inherit the
+      // pipes and keep them open after the
+      // output is restored
+      if pipes_created == false
+         || output < 0 || error < 0 || output_copy < 0 || error_copy < 0
+         || unsafe{dup3(output_write, STDOUT_FILENO, O_CLOEXEC)} < 0
+         || unsafe{dup3(error_write, STDERR_FILENO, O_CLOEXEC)} < 0
+      {
+         if output >= 0 {
+            unsafe{dup2(output, STDOUT_FILENO)};
+         }
+         if error >= 0 {
+            unsafe{dup2(error, STDERR_FILENO)};
+         }
+         for descriptor in [output_read, output_write, error_read, error_write, output, error, output_copy, error_copy] {
+            if descriptor >= 0 {
+               unsafe{close(descriptor)};
+            }
+         }
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      return Ok((
+         Self{
+            output   : output,
+            error    : error,
+            writes   : [output_write, error_write],
+         },
+         [
+            (unsafe{std::fs::File::from_raw_fd(output_read)}, Some(unsafe{std::fs::File::from_raw_fd(output_copy)})),
+            (unsafe{std::fs::File::from_raw_fd(error_read)}, Some(unsafe{std::fs::File::from_raw_fd(error_copy)})),
+         ],
+      ));
+   }
+
+   pub fn restore(
+      & mut self,
+   ) {
+      if self.writes.iter().any(|write| *write < 0) {
+         return;
+      }
+
+      unsafe{dup2(self.output, STDOUT_FILENO)};
+      unsafe{dup2(self.error, STDERR_FILENO)};
+      for descriptor in [self.output, self.error, self.writes[0], self.writes[1]] {
+         unsafe{close(descriptor)};
+      }
+
+      self.output = -1;
+      self.error  = -1;
+      self.writes = [-1, -1];
+      return;
+   }
+}
+
+impl KeyReader {
+   pub fn new(
+   ) -> crate::console::Result<Self> {
+      // Piped input has no terminal mode
+      // and is read as-is
+      if unsafe{isatty(STDIN_FILENO)} != 1 {
+         return Ok(Self{
+            original : None,
+         });
+      }
+
+      let mut original = unsafe{std::mem::zeroed::<termios>()};
+      if unsafe{tcgetattr(STDIN_FILENO, & mut original)} != 0 {
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      let mut raw = original;
+      raw.c_lflag &= !(ICANON | ECHO);
+      raw.c_cc[VMIN]    = 1;
+      raw.c_cc[VTIME]   = 0;
+      if unsafe{tcsetattr(STDIN_FILENO, TCSANOW, &raw)} != 0 {
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      return Ok(Self{
+         original : Some(original),
+      });
+   }
+
+   pub fn read_key(
+      & mut self,
+   ) -> crate::console::Result<crate::console::ConsoleKey> {
+      use crate::console::ConsoleKey;
+
+      loop {
+         let byte = match self.read_byte()? {
+            Some(byte)  => byte,
+            None        => return Ok(ConsoleKey::EndOfInput),
+         };
+
+         let key = match byte {
+            b'\r' | b'\n'   => ConsoleKey::Enter,
+            b'\t'           => ConsoleKey::Tab,
+            0x7F | 0x08     => ConsoleKey::Backspace,
+            0x04            => ConsoleKey::EndOfInput,
+            0x1B            => match self.read_escape()? {
+               Some(key)   => key,
+               None        => continue,
+            },
+            0x00..=0x1F     => continue,
+            _               => match self.read_character(byte)? {
+               Some(character)   => ConsoleKey::Character(character),
+               None              => continue,
+            },
+         };
+
+         return Ok(key);
+      }
+   }
+
+   pub fn wait_for_key(
+      & mut self,
+      timeout : std::time::Duration,
+   ) -> crate::console::Result<bool> {
+      let mut descriptor = pollfd{
+         fd       : STDIN_FILENO,
+         events   : POLLIN,
+         revents  : 0,
+      };
+
+      // The end of input also wakes the poll,
+      // which read_key reports as a key
+      let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+      return match unsafe{poll(& mut descriptor, 1, timeout)} {
+         0  => Ok(false),
+         1  => Ok(true),
+         _  => {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+               return Ok(false);
+            }
+            Err(crate::console::ConsoleError::Unknown)
+         },
+      };
+   }
+
+   fn read_byte(
+      & mut self,
+   ) -> crate::console::Result<Option<u8>> {
+      let mut byte = 0u8;
+      loop {
+         let count = unsafe{read(STDIN_FILENO, & mut byte as * mut u8 as * mut _, 1)};
+         match count {
+            1  => return Ok(Some(byte)),
+            0  => return Ok(None),
+            _  => {
+               if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                  continue;
+               }
+               return Err(crate::console::ConsoleError::Unknown);
+            },
+         }
+      }
+   }
+
+   // Decodes the arrow, home, end, and
+   // delete sequences sent by xterm-like
+   // terminals, skipping anything else
+   fn read_escape(
+      & mut self,
+   ) -> crate::console::Result<Option<crate::console::ConsoleKey>> {
+      use crate::console::ConsoleKey;
+
+      let introducer = match self.read_byte()? {
+         Some(byte)  => byte,
+         None        => return Ok(Some(ConsoleKey::Escape)),
+      };
+      if introducer != b'[' && introducer != b'O' {
+         return Ok(Some(ConsoleKey::Escape));
+      }
+
+      let mut parameter = 0u32;
+      loop {
+         let byte = match self.read_byte()? {
+            Some(byte)  => byte,
+            None        => return Ok(None),
+         };
+
+         let key = match byte {
+            b'0'..=b'9' => {
+               parameter = parameter.saturating_mul(10).saturating_add((byte - b'0') as u32);
+               continue;
+            },
+            b';'        => continue,
+            b'A'        => Some(ConsoleKey::Up),
+            b'B'        => Some(ConsoleKey::Down),
+            b'C'        => Some(ConsoleKey::Right),
+            b'D'        => Some(ConsoleKey::Left),
+            b'H'        => Some(ConsoleKey::Home),
+            b'F'        => Some(ConsoleKey::End),
+            b'~'        => match parameter {
+               1 | 7 => Some(ConsoleKey::Home),
+               4 | 8 => Some(ConsoleKey::End),
+               3     => Some(ConsoleKey::Delete),
+               _     => None,
+            },
+            _           => None,
+         };
+
+         return Ok(key);
+      }
+   }
+
+   fn read_character(
+      & mut self,
+      first : u8,
+   ) -> crate::console::Result<Option<char>> {
+      let length = match first {
+         0x00..=0x7F => 1,
+         0xC0..=0xDF => 2,
+         0xE0..=0xEF => 3,
+         0xF0..=0xF7 => 4,
+         _           => return Ok(None),
+      };
+
+      let mut bytes = vec![first];
+      while bytes.len() < length {
+         match self.read_byte()? {
+            Some(byte)  => bytes.push(byte),
+            None        => return Ok(None),
+         }
+      }
+
+      return Ok(std::str::from_utf8(&bytes).ok().and_then(|string| string.chars().next()));
+   }
+}
+
+impl Drop for KeyReader {
+   fn drop(
+      & mut self,
+   ) {
+      if let Some(original) = self.original.as_ref() {
+         unsafe{tcsetattr(STDIN_FILENO, TCSANOW, original)};
+      }
+
+      return;
+   }
+}
@@ -0,0 +1,29 @@
+//! crate::os::debug implementation for Linux.
+//! The debug registers can only be set by
+//! a tracing process, so this is unsupported.
+
+pub unsafe fn set_watchpoint(
+   _slot    : usize,
+   _address : usize,
+   _length  : usize,
+   _kind    : crate::debug::WatchKind,
+) -> crate::debug::Result<()> {
+   return Err(crate::debug::DebugError::Unsupported);
+}
+
+pub unsafe fn clear_watchpoint(
+   _slot : usize,
+) -> crate::debug::Result<()> {
+   return Err(crate::debug::DebugError::Unsupported);
+}
+
+pub fn set_hit_handler(
+   _handler : crate::debug::HitHandler,
+) -> crate::debug::Result<()> {
+   return Err(crate::debug::DebugError::Unsupported);
+}
+
+pub fn clear_hit_handler(
+) {
+   return;
+}
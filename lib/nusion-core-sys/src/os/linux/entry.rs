@@ -0,0 +1,32 @@
+//! crate::entry OS implementations for Linux.
+
+// Shared libraries have no DllMain, so the
+// entrypoint is registered as a constructor
+// in .init_array, which the dynamic loader
+// runs when the library is loaded.  Main
+// runs on its own thread so the loader isn't
+// blocked.  Unloading the library from its
+// own thread isn't possible, so the library
+// stays loaded after main returns.
+#[macro_export]
+macro_rules! build_entry {
+   ($starter:path, $entry:ident, $name:expr, $options:expr, $osapi:path, $($proc:literal),*)  => {
+      #[used]
+      #[link_section = ".init_array"]
+      static __NUSION_SLIB_INIT : extern "C" fn() = __nusion_slib_init;
+
+      extern "C" fn __nusion_slib_init() {
+         // Create the main execution thread
+         if let Err(err) = std::thread::Builder::new()
+            .name(String::from("nusion main"))
+            .spawn(|| {
+               $starter($entry, $name, &[$($proc),*], &$options);
+            })
+         {
+            panic!("Failed to create main thread: {err}");
+         }
+
+         return;
+      }
+   };
+}
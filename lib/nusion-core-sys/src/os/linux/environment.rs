@@ -0,0 +1,18 @@
+//! crate::os::environment implementations
+//! for Linux.
+
+use libc::{
+   c_int,
+};
+
+pub struct OSReturn {
+   pub code : c_int,
+}
+
+impl OSReturn {
+   pub const SUCCESS : Self
+      = Self{code : 0};
+
+   pub const FAILURE : Self
+      = Self{code : 1};
+}
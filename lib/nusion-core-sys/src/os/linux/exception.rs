@@ -0,0 +1,157 @@
+//! crate::os::exception implementation for Linux.
+
+use libc::{
+   c_int,
+   c_void,
+   sigaction,
+   sigemptyset,
+   siginfo_t,
+   ucontext_t,
+   REG_ERR,
+   REG_R11,
+   REG_RIP,
+   SA_NODEFER,
+   SA_SIGINFO,
+   SIGBUS,
+   SIGSEGV,
+   SIG_DFL,
+   SIG_IGN,
+};
+
+// Page fault error code bits
+// stored by the kernel
+const FAULT_WRITE    : i64 = 0x02;
+const FAULT_EXECUTE  : i64 = 0x10;
+
+const SIGNALS : [c_int; 2] = [SIGSEGV, SIGBUS];
+
+// The actions which were installed before
+// ours, which faults are passed on to.
+// These are only written while our handler
+// isn't installed, so the handler can read
+// them without locking.
+struct PreviousActions(std::cell::UnsafeCell<[libc::sigaction; 2]>);
+
+unsafe impl Sync for PreviousActions {
+}
+
+static PREVIOUS_ACTIONS : PreviousActions
+   = PreviousActions(std::cell::UnsafeCell::new(unsafe{std::mem::zeroed()}));
+
+// Serializes installing and removing
+// the handler, and tracks whether it is
+static INSTALLED : std::sync::Mutex<bool>
+   = std::sync::Mutex::new(false);
+
+pub fn install(
+) -> crate::exception::Result<()> {
+   let mut installed = INSTALLED.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *installed == true {
+      return Ok(());
+   }
+
+   // Our handler runs on the faulting
+   // thread's own stack, since threads
+   // created by the game have no
+   // alternate signal stack to run on
+   let mut action = unsafe{std::mem::zeroed::<libc::sigaction>()};
+   action.sa_sigaction = signal_handler as extern "C" fn(c_int, * mut siginfo_t, * mut c_void) as usize;
+   action.sa_flags     = SA_SIGINFO | SA_NODEFER;
+   unsafe{sigemptyset(&mut action.sa_mask)};
+
+   let previous = unsafe{&mut *PREVIOUS_ACTIONS.0.get()};
+   for (index, (signal, previous)) in SIGNALS.iter().zip(previous.iter_mut()).enumerate() {
+      if unsafe{sigaction(*signal, &action, previous)} != 0 {
+         for (signal, previous) in SIGNALS.iter().zip(previous_actions()).take(index) {
+            unsafe{sigaction(*signal, previous, std::ptr::null_mut())};
+         }
+         return Err(crate::exception::ExceptionError::Unknown);
+      }
+   }
+   *installed = true;
+
+   return Ok(());
+}
+
+pub fn uninstall(
+) {
+   let mut installed = INSTALLED.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *installed == true {
+      for (signal, previous) in SIGNALS.iter().zip(previous_actions()) {
+         unsafe{sigaction(*signal, previous, std::ptr::null_mut())};
+      }
+      *installed = false;
+   }
+
+   return;
+}
+
+fn previous_actions(
+) -> &'static [libc::sigaction; 2] {
+   return unsafe{&*PREVIOUS_ACTIONS.0.get()};
+}
+
+extern "C" fn signal_handler(
+   signal   : c_int,
+   info     : * mut siginfo_t,
+   context  : * mut c_void,
+) {
+   let context = unsafe{&mut *(context as * mut ucontext_t)};
+   let registers = &mut context.uc_mcontext.gregs;
+
+   let error = registers[REG_ERR as usize];
+   let access = if error & FAULT_EXECUTE != 0 {
+      crate::exception::FaultAccess::Execute
+   } else if error & FAULT_WRITE != 0 {
+      crate::exception::FaultAccess::Write
+   } else {
+      crate::exception::FaultAccess::Read
+   };
+
+   let fault = crate::exception::FaultInfo{
+      code                 : signal as u32,
+      access               : access,
+      fault_address        : unsafe{(*info).si_addr()} as usize,
+      instruction_pointer  : registers[REG_RIP as usize] as usize,
+   };
+
+   match crate::exception::dispatch_fault(&fault, registers[REG_R11 as usize] as usize) {
+      crate::exception::FaultResponse::Resume(resume_address) => {
+         registers[REG_RIP as usize] = resume_address as i64;
+         return;
+      },
+      crate::exception::FaultResponse::Retry => {
+         return;
+      },
+      crate::exception::FaultResponse::Continue => {
+      },
+   }
+
+   // Pass the fault on to whatever
+   // handled it before we did
+   let index = SIGNALS.iter().position(|other| *other == signal).unwrap_or(0);
+   let previous = match previous_actions().get(index) {
+      Some(previous) => *previous,
+      None           => return,
+   };
+
+   if previous.sa_sigaction == SIG_DFL || previous.sa_sigaction == SIG_IGN {
+      // Returning re-runs the faulting
+      // instruction with the default
+      // action restored
+      unsafe{sigaction(signal, &previous, std::ptr::null_mut())};
+   } else if previous.sa_flags & SA_SIGINFO != 0 {
+      let handler = unsafe{std::mem::transmute::<
+         usize, extern "C" fn(c_int, * mut siginfo_t, * mut c_void)
+      >(previous.sa_sigaction)};
+      handler(signal, info, context as * mut ucontext_t as * mut c_void);
+   } else {
+      let handler = unsafe{std::mem::transmute::<
+         usize, extern "C" fn(c_int)
+      >(previous.sa_sigaction)};
+      handler(signal);
+   }
+
+   return;
+}
+
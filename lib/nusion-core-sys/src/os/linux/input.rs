@@ -0,0 +1,13 @@
+//! crate::os::input implementation for Linux.
+//! Reading the global key state requires
+//! either a display server connection or
+//! access to the raw input devices, neither
+//! of which a library can count on having,
+//! so every key reads as released.
+
+pub fn is_key_down(
+   _virtual_key : u8,
+) -> bool {
+   return false;
+}
+
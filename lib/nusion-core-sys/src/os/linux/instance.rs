@@ -0,0 +1,69 @@
+//! crate::os::instance implementation for Linux.
+//! There are no named mutexes, so the lock
+//! is a socket bound in the abstract
+//! namespace, which the kernel releases
+//! once the socket is closed.
+
+pub struct InstanceLock {
+   name  : String,
+   fd    : libc::c_int,
+}
+
+impl InstanceLock {
+   pub fn acquire(
+      name : & str,
+   ) -> crate::instance::Result<Option<Self>> {
+      let mut address = unsafe{std::mem::zeroed::<libc::sockaddr_un>()};
+      address.sun_family = libc::AF_UNIX as libc::sa_family_t;
+
+      // Abstract socket names start with a
+      // nul byte and aren't nul-terminated
+      let name_bytes = name.as_bytes();
+      if name_bytes.len() + 1 > address.sun_path.len() {
+         return Err(crate::instance::InstanceError::InvalidName);
+      }
+      for (dest, src) in address.sun_path[1..].iter_mut().zip(name_bytes.iter()) {
+         *dest = *src as libc::c_char;
+      }
+      let address_length = std::mem::size_of::<libc::sa_family_t>() + 1 + name_bytes.len();
+
+      let fd = unsafe{libc::socket(libc::AF_UNIX, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0)};
+      if fd < 0 {
+         return Err(crate::instance::InstanceError::Unknown);
+      }
+
+      if unsafe{libc::bind(
+         fd,
+         &address as * const libc::sockaddr_un as * const libc::sockaddr,
+         address_length as libc::socklen_t,
+      )} != 0 {
+         let err = std::io::Error::last_os_error();
+         unsafe{libc::close(fd)};
+         return match err.raw_os_error() {
+            Some(libc::EADDRINUSE)  => Ok(None),
+            _                       => Err(crate::instance::InstanceError::Unknown),
+         };
+      }
+
+      return Ok(Some(Self{
+         name  : name.to_owned(),
+         fd    : fd,
+      }));
+   }
+
+   pub fn name(
+      & self,
+   ) -> & str {
+      return &self.name;
+   }
+}
+
+impl Drop for InstanceLock {
+   fn drop(
+      & mut self,
+   ) {
+      unsafe{libc::close(self.fd)};
+      return;
+   }
+}
+
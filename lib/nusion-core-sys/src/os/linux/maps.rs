@@ -0,0 +1,82 @@
+//! Parsing of /proc/[pid]/maps, which
+//! lists every memory mapping within
+//! a process.
+
+pub struct Mapping {
+   pub address_range : std::ops::Range<usize>,
+   pub readable      : bool,
+   pub writable      : bool,
+   pub executable    : bool,
+   pub path          : Option<String>,
+}
+
+impl Mapping {
+   /// Reads every mapping within a process,
+   /// sorted by address.  <code>None</code>
+   /// reads the mappings of the local process.
+   pub fn all(
+      process_id : Option<libc::pid_t>,
+   ) -> Option<Vec<Self>> {
+      let path = match process_id {
+         Some(process_id)  => format!("/proc/{process_id}/maps"),
+         None              => String::from("/proc/self/maps"),
+      };
+
+      let maps = std::fs::read_to_string(path).ok()?;
+      return maps.lines().map(Self::parse).collect();
+   }
+
+   /// Finds the mapping containing an
+   /// address within the local process,
+   /// along with the mappings before and
+   /// after it so gaps can be measured.
+   pub fn query(
+      address : usize,
+   ) -> Option<(Option<Self>, Option<Self>, Option<Self>)> {
+      let mut before = None;
+      for mapping in Self::all(None)? {
+         if mapping.address_range.end <= address {
+            before = Some(mapping);
+            continue;
+         }
+
+         if mapping.address_range.start <= address {
+            return Some((before, Some(mapping), None));
+         }
+
+         return Some((before, None, Some(mapping)));
+      }
+
+      return Some((before, None, None));
+   }
+
+   // Parses a line of the form
+   // "start-end perms offset dev inode    path",
+   // where only the path is padded with spaces
+   fn parse(
+      line : & str,
+   ) -> Option<Self> {
+      let mut fields = line.splitn(6, ' ');
+
+      let (start, end)  = fields.next()?.split_once('-')?;
+      let start         = usize::from_str_radix(start, 16).ok()?;
+      let end           = usize::from_str_radix(end,   16).ok()?;
+      let permissions   = fields.next()?.as_bytes();
+
+      // Skip the offset, device, and inode
+      let path = fields.nth(3).map(str::trim).unwrap_or("");
+      let path = path.strip_suffix(" (deleted)").unwrap_or(path);
+      let path = match path.is_empty() {
+         true  => None,
+         false => Some(String::from(path)),
+      };
+
+      return Some(Self{
+         address_range  : start..end,
+         readable       : permissions.first() == Some(&b'r'),
+         writable       : permissions.get(1)  == Some(&b'w'),
+         executable     : permissions.get(2)  == Some(&b'x'),
+         path           : path,
+      });
+   }
+}
@@ -0,0 +1,245 @@
+//! crate::memory OS implementations
+//! for Linux.
+
+use libc::{
+   c_int,
+   c_void,
+   getpid,
+   iovec,
+   mmap,
+   mprotect,
+   munmap,
+   process_vm_readv,
+   sysconf,
+   EACCES,
+   EINVAL,
+   ENOMEM,
+   MAP_ANONYMOUS,
+   MAP_FAILED,
+   MAP_PRIVATE,
+   PROT_EXEC,
+   PROT_NONE,
+   PROT_READ,
+   PROT_WRITE,
+   _SC_PAGESIZE,
+};
+
+use super::maps::Mapping;
+
+pub struct MemoryPermissions {
+   permissions : c_int,
+}
+
+pub struct MemoryRegion {
+   pub address_range : std::ops::Range<usize>,
+   pub committed     : bool,
+   pub readable      : bool,
+   pub writable      : bool,
+   pub executable    : bool,
+}
+
+impl MemoryPermissions {
+   pub const READ                : Self
+      = Self{permissions : PROT_READ                           };
+
+   pub const READ_WRITE          : Self
+      = Self{permissions : PROT_READ | PROT_WRITE              };
+
+   pub const READ_EXECUTE        : Self
+      = Self{permissions : PROT_READ | PROT_EXEC               };
+
+   pub const READ_WRITE_EXECUTE  : Self
+      = Self{permissions : PROT_READ | PROT_WRITE | PROT_EXEC  };
+
+   pub const ALL : Self
+      = Self::READ_WRITE_EXECUTE;
+}
+
+impl MemoryPermissions {
+   pub fn set(
+      address_range  : & std::ops::Range<usize>,
+      permissions    : & Self,
+   ) -> crate::memory::Result<Self> {
+      // mprotect doesn't return the old permissions,
+      // so take them from the first page's mapping
+      // the same way VirtualProtect does
+      let region = MemoryRegion::query(address_range.start)?;
+      if region.committed == false {
+         return Err(crate::memory::MemoryError::new(
+            crate::memory::MemoryErrorKind::UnmappedAddress,
+            address_range.clone(),
+         ));
+      }
+
+      let mut old_permissions = PROT_NONE;
+      if region.readable   == true {old_permissions |= PROT_READ;}
+      if region.writable   == true {old_permissions |= PROT_WRITE;}
+      if region.executable == true {old_permissions |= PROT_EXEC;}
+
+      // mprotect only accepts whole pages
+      let page_size  = page_size();
+      let base       = address_range.start / page_size * page_size;
+      let end        = address_range.end.div_ceil(page_size) * page_size;
+
+      if unsafe{mprotect(
+         base as * mut c_void,
+         end - base,
+         permissions.permissions,
+      )} == 0 {
+         return Ok(Self{permissions : old_permissions});
+      }
+
+      // Parse error number into MemoryErrorKind
+      use crate::memory::MemoryErrorKind::*;
+      let errkind = match std::io::Error::last_os_error().raw_os_error() {
+         Some(EACCES)   => PermissionDenied,
+         Some(EINVAL)   => InvalidAddressRange,
+         Some(ENOMEM)   => UnmappedAddress,
+         _              => Unknown,
+      };
+
+      // Create the MemoryError and return
+      return Err(crate::memory::MemoryError::new(
+         errkind, address_range.clone(),
+      ));
+   }
+}
+
+impl MemoryRegion {
+   pub fn query(
+      address : usize,
+   ) -> crate::memory::Result<Self> {
+      let (before, mapping, after) = Mapping::query(address).ok_or(
+         crate::memory::MemoryError::new(
+            crate::memory::MemoryErrorKind::Unknown,
+            address..address,
+         ),
+      )?;
+
+      if let Some(mapping) = mapping {
+         return Ok(Self{
+            address_range  : mapping.address_range,
+            committed      : true,
+            readable       : mapping.readable,
+            writable       : mapping.writable,
+            executable     : mapping.executable,
+         });
+      }
+
+      // Report the gap between mappings as an
+      // uncommitted region, like a free region
+      // returned by VirtualQuery
+      let start   = before.map(|mapping| mapping.address_range.end).unwrap_or(0);
+      let end     = after.map(|mapping| mapping.address_range.start).unwrap_or(usize::MAX);
+      return Ok(Self{
+         address_range  : start..end,
+         committed      : false,
+         readable       : false,
+         writable       : false,
+         executable     : false,
+      });
+   }
+}
+
+pub fn read_checked(
+   address  : usize,
+   buffer   : & mut [u8],
+) -> crate::memory::Result<()> {
+   // Reading through the process fails
+   // with EFAULT instead of raising a
+   // signal when the memory is unmapped
+   let local = iovec{
+      iov_base : buffer.as_mut_ptr() as * mut c_void,
+      iov_len  : buffer.len(),
+   };
+   let remote = iovec{
+      iov_base : address as * mut c_void,
+      iov_len  : buffer.len(),
+   };
+   let bytes_read = unsafe{process_vm_readv(getpid(), &local, 1, &remote, 1, 0)};
+   if bytes_read < 0 || bytes_read as usize != buffer.len() {
+      return Err(crate::memory::MemoryError::new(
+         crate::memory::MemoryErrorKind::UnmappedAddress,
+         address..address.saturating_add(buffer.len()),
+      ));
+   }
+
+   return Ok(());
+}
+
+pub fn allocate_executable(
+   size : usize,
+) -> crate::memory::Result<std::ops::Range<usize>> {
+   let base = unsafe{mmap(
+      std::ptr::null_mut(),
+      size,
+      PROT_READ | PROT_WRITE | PROT_EXEC,
+      MAP_PRIVATE | MAP_ANONYMOUS,
+      -1,
+      0,
+   )};
+
+   if base == MAP_FAILED {
+      return Err(crate::memory::MemoryError::new(
+         crate::memory::MemoryErrorKind::Unknown,
+         0..size,
+      ));
+   }
+
+   let base = base as usize;
+   return Ok(base..base + size);
+}
+
+pub fn allocate_executable_near(
+   address  : usize,
+   size     : usize,
+   reach    : usize,
+) -> crate::memory::Result<std::ops::Range<usize>> {
+   // The kernel only uses the hint when it's free, so
+   // walk outwards from the address in coarse steps
+   const STEP : usize = 0x100000;
+
+   let mut distance = STEP;
+   while distance < reach {
+      for hint in [
+         address.checked_add(distance),
+         address.checked_sub(distance),
+      ] {
+         let hint = match hint {
+            Some(hint)  => hint / STEP * STEP,
+            None        => continue,
+         };
+
+         let base = unsafe{mmap(
+            hint as * mut c_void,
+            size,
+            PROT_READ | PROT_WRITE | PROT_EXEC,
+            MAP_PRIVATE | MAP_ANONYMOUS,
+            -1,
+            0,
+         )};
+         if base == MAP_FAILED {
+            continue;
+         }
+
+         let base = base as usize;
+         if address.abs_diff(base) < reach && address.abs_diff(base + size) < reach {
+            return Ok(base..base + size);
+         }
+
+         unsafe{munmap(base as * mut c_void, size)};
+      }
+
+      distance += STEP;
+   }
+
+   return Err(crate::memory::MemoryError::new(
+      crate::memory::MemoryErrorKind::Unknown,
+      address..address + size,
+   ));
+}
+
+pub fn page_size(
+) -> usize {
+   return usize::try_from(unsafe{sysconf(_SC_PAGESIZE)}).unwrap_or(4096);
+}
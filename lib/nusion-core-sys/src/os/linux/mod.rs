@@ -0,0 +1,24 @@
+//! OS Implementations for Linux.
+
+// OS API public re-export
+pub use libc as osapi;
+
+// Internal modules
+mod maps;
+
+// Public modules
+pub mod clipboard;
+pub mod console;
+pub mod debug;
+pub mod entry;
+pub mod environment;
+pub mod exception;
+pub mod input;
+pub mod instance;
+pub mod memory;
+#[cfg(feature = "overlay")]
+pub mod overlay;
+pub mod process;
+pub mod screenshot;
+pub mod thread;
+pub mod window;
@@ -0,0 +1,70 @@
+//! crate::os::overlay implementation for Linux.
+//! Games on Linux render through OpenGL or
+//! Vulkan, neither of which is supported yet.
+
+use crate::overlay::{
+   FontMetrics,
+   OverlayError,
+   Result,
+   Vertex,
+};
+
+pub struct Renderer {
+}
+
+impl Renderer {
+   pub unsafe fn new(
+      _swap_chain    : usize,
+      _command_queue : Option<usize>,
+   ) -> Result<Self> {
+      return Err(OverlayError::Unsupported);
+   }
+
+   pub fn font_metrics(
+      & self,
+   ) -> FontMetrics {
+      return FontMetrics{
+         glyph_width    : 0.0,
+         glyph_height   : 0.0,
+         atlas_width    : 0.0,
+         atlas_height   : 0.0,
+      };
+   }
+
+   pub unsafe fn screen_size(
+      & mut self,
+      _swap_chain : usize,
+   ) -> Result<(f32, f32)> {
+      return Err(OverlayError::Unsupported);
+   }
+
+   pub unsafe fn render(
+      & mut self,
+      _swap_chain : usize,
+      _vertices   : & [Vertex],
+   ) -> Result<()> {
+      return Err(OverlayError::Unsupported);
+   }
+
+   pub fn release_targets(
+      & mut self,
+   ) {
+      return;
+   }
+}
+
+pub fn swap_chain_vtable(
+) -> Result<usize> {
+   return Err(OverlayError::Unsupported);
+}
+
+pub fn command_queue_vtable(
+) -> Result<usize> {
+   return Err(OverlayError::Unsupported);
+}
+
+pub unsafe fn is_direct_command_queue(
+   _command_queue : usize,
+) -> bool {
+   return false;
+}
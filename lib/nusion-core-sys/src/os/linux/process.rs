@@ -0,0 +1,329 @@
+//! crate::process implementations for
+//! Linux.
+
+use crate::process::{ProcessError, Result, Section, UnwindFunction};
+
+use libc::{
+   c_void,
+   dladdr,
+   getpid,
+   pid_t,
+   Dl_info,
+};
+
+use super::maps::Mapping;
+
+pub struct ProcessSnapshot {
+   pub process_id       : pid_t,
+   pub executable_name  : String,
+}
+
+pub struct ModuleSnapshot {
+   pub address_range : std::ops::Range<usize>,
+   pub module_name   : String,
+}
+
+pub struct RemoteProcess {
+   memory : std::fs::File,
+}
+
+pub struct SuspendedProcess {
+   pub process_id : pid_t,
+}
+
+// Gets the file name from a path,
+// failing on invalid UTF-8
+fn path_file_name(
+   path : & std::path::Path,
+) -> Option<String> {
+   return path.file_name()?.to_str().map(String::from);
+}
+
+// Gets the executable name of a process,
+// falling back to the command name which
+// may be truncated if the executable link
+// can't be read
+fn process_executable_name(
+   process : & str,
+) -> Option<String> {
+   if let Ok(path) = std::fs::read_link(format!("/proc/{process}/exe")) {
+      return path_file_name(&path);
+   }
+
+   let comm = std::fs::read_to_string(format!("/proc/{process}/comm")).ok()?;
+   return Some(String::from(comm.trim_end()));
+}
+
+impl ProcessSnapshot {
+   pub fn local(
+   ) -> Result<Self> {
+      let process_id = unsafe{getpid()};
+
+      let executable_name = process_executable_name("self")
+         .ok_or(ProcessError::BadExecutableFileName)?;
+
+      return Ok(Self{
+         process_id        : process_id,
+         executable_name   : executable_name,
+      });
+   }
+
+   pub fn all(
+   ) -> Result<Vec<Self>> {
+      let directory = std::fs::read_dir("/proc").map_err(|_| ProcessError::Unknown)?;
+
+      // Every numeric directory in /proc is a process
+      let mut process_list = Vec::new();
+      for entry in directory.flatten() {
+         let name = entry.file_name();
+         let process_id = match name.to_str().and_then(|name| name.parse::<pid_t>().ok()) {
+            Some(process_id)  => process_id,
+            None              => continue,
+         };
+
+         // The process may have exited since
+         // the directory was read
+         let executable_name = match process_executable_name(&process_id.to_string()) {
+            Some(name)  => name,
+            None        => continue,
+         };
+
+         process_list.push(Self{
+            process_id        : process_id,
+            executable_name   : executable_name,
+         });
+      }
+
+      return Ok(process_list);
+   }
+}
+
+impl ModuleSnapshot {
+   pub fn all(
+      parent_process : & ProcessSnapshot,
+   ) -> Result<Vec<Self>> {
+      let process_id = match parent_process.process_id == unsafe{getpid()} {
+         true  => None,
+         false => Some(parent_process.process_id),
+      };
+      let mappings = Mapping::all(process_id).ok_or(ProcessError::Unknown)?;
+
+      // A module is mapped as several segments
+      // with different permissions, so merge
+      // every mapping of the same file
+      let mut module_list : Vec<(String, Self)> = Vec::new();
+      for mapping in mappings {
+         let path = match mapping.path {
+            Some(path) if path.starts_with('/') => path,
+            _                                   => continue,
+         };
+
+         if let Some((_, module)) = module_list.iter_mut().find(|(p, _)| *p == path) {
+            module.address_range.start = usize::min(module.address_range.start, mapping.address_range.start);
+            module.address_range.end   = usize::max(module.address_range.end,   mapping.address_range.end);
+            continue;
+         }
+
+         let module_name = match path_file_name(std::path::Path::new(&path)) {
+            Some(name)  => name,
+            None        => continue,
+         };
+
+         module_list.push((path, Self{
+            address_range  : mapping.address_range,
+            module_name    : module_name,
+         }));
+      }
+
+      return Ok(module_list.into_iter().map(|(_, module)| module).collect());
+   }
+}
+
+impl ModuleSnapshot {
+   // ELF images have no table of unwind
+   // information comparable to the PE
+   // exception directory
+   pub unsafe fn unwind_function(
+      & self,
+      _address : usize,
+   ) -> Result<Option<UnwindFunction>> {
+      return Ok(None);
+   }
+
+   // ELF images resolve imports through the
+   // GOT rather than an import address table
+   pub unsafe fn import_slot(
+      & self,
+      _library    : & str,
+      _function   : & str,
+   ) -> Result<Option<usize>> {
+      return Ok(None);
+   }
+
+   // ELF dynamic symbols aren't parsed yet
+   pub unsafe fn export_rva(
+      & self,
+      _function   : & str,
+   ) -> Result<Option<usize>> {
+      return Ok(None);
+   }
+
+   pub unsafe fn file_offset_to_rva(
+      & self,
+      _file_offset : usize,
+   ) -> Result<Option<usize>> {
+      return Ok(None);
+   }
+
+   // Sections aren't required to be present
+   // in a loaded ELF image, only segments
+   pub unsafe fn sections(
+      & self,
+   ) -> Result<Option<Vec<Section>>> {
+      return Ok(None);
+   }
+
+   // The GOT isn't located yet, the same
+   // as with import slots
+   pub unsafe fn import_table_ranges(
+      & self,
+   ) -> Result<Vec<std::ops::Range<usize>>> {
+      return Ok(Vec::new());
+   }
+
+   // ELF images have no version resource
+   pub unsafe fn file_version(
+      & self,
+   ) -> Result<Option<[u16; 4]>> {
+      return Ok(None);
+   }
+}
+
+impl RemoteProcess {
+   pub fn open(
+      process : & ProcessSnapshot,
+   ) -> Result<Self> {
+      // Writing through the memory file
+      // ignores page protections, which
+      // matches how patching code works
+      let memory = std::fs::OpenOptions::new()
+         .read(true)
+         .write(true)
+         .open(format!("/proc/{}/mem", process.process_id))
+         .map_err(|_| ProcessError::AccessDenied)?;
+
+      return Ok(Self{
+         memory : memory,
+      });
+   }
+
+   pub fn read(
+      & self,
+      address  : usize,
+      buffer   : & mut [u8],
+   ) -> Result<()> {
+      use std::os::unix::fs::FileExt;
+
+      return self.memory.read_exact_at(buffer, address as u64)
+         .map_err(|_| ProcessError::RemoteMemoryFailed);
+   }
+
+   pub fn write(
+      & self,
+      address  : usize,
+      bytes    : & [u8],
+   ) -> Result<()> {
+      use std::os::unix::fs::FileExt;
+
+      return self.memory.write_all_at(bytes, address as u64)
+         .map_err(|_| ProcessError::RemoteMemoryFailed);
+   }
+
+   // Loading a library into another process
+   // would require hijacking a thread through
+   // ptrace to call dlopen
+   pub fn load_library(
+      & self,
+      _library_path : & std::path::Path,
+   ) -> Result<()> {
+      return Err(ProcessError::Unsupported);
+   }
+}
+
+// Starting a process suspended would require
+// stopping it under ptrace before it executes,
+// and nothing could be injected into it anyway
+impl SuspendedProcess {
+   pub fn spawn(
+      _executable_path  : & std::path::Path,
+      _arguments        : & [std::ffi::OsString],
+   ) -> Result<Self> {
+      return Err(ProcessError::Unsupported);
+   }
+
+   pub fn load_library(
+      & self,
+      _library_path : & std::path::Path,
+   ) -> Result<()> {
+      return Err(ProcessError::Unsupported);
+   }
+
+   pub fn wait_ready(
+      & self,
+      _timeout : Option<std::time::Duration>,
+   ) -> Result<bool> {
+      return Err(ProcessError::Unsupported);
+   }
+
+   pub fn resume(
+      self,
+   ) -> Result<()> {
+      return Err(ProcessError::Unsupported);
+   }
+}
+
+// Section tables are never read
+// from ELF images
+pub fn section_is_executable(
+   _characteristics : u32,
+) -> bool {
+   return false;
+}
+
+pub fn section_is_readable(
+   _characteristics : u32,
+) -> bool {
+   return false;
+}
+
+pub fn section_is_writable(
+   _characteristics : u32,
+) -> bool {
+   return false;
+}
+
+pub fn module_file_path(
+   address : usize,
+) -> Result<std::path::PathBuf> {
+   let mut info = unsafe{std::mem::zeroed::<Dl_info>()};
+   if unsafe{dladdr(address as * const c_void, & mut info)} == 0 || info.dli_fname.is_null() == true {
+      return Err(ProcessError::Unknown);
+   }
+
+   use std::os::unix::ffi::OsStrExt;
+   let path = unsafe{std::ffi::CStr::from_ptr(info.dli_fname)};
+   return Ok(std::path::PathBuf::from(std::ffi::OsStr::from_bytes(path.to_bytes())));
+}
+
+pub fn write_minidump(
+   _file_path : & std::path::Path,
+) -> Result<()> {
+   return Err(ProcessError::Unsupported);
+}
+
+// Processes can't be started suspended,
+// so nothing is ever waiting
+pub fn signal_ready(
+) -> Result<()> {
+   return Ok(());
+}
@@ -0,0 +1,10 @@
+//! crate::os::screenshot implementation for Linux.
+//! Capturing a window requires a display
+//! server connection, which a library
+//! can't count on having.
+
+pub fn capture_process_window(
+) -> crate::screenshot::Result<crate::screenshot::Frame> {
+   return Err(crate::screenshot::ScreenshotError::Unsupported);
+}
+
@@ -0,0 +1,47 @@
+//! crate::os::thread implementation for Linux.
+//! Threads can't be suspended from within the
+//! same process without a signal handler in
+//! every thread, so this is unsupported.
+
+pub struct SuspendedThreads {
+   instruction_pointers : Vec<usize>,
+}
+
+impl SuspendedThreads {
+   pub unsafe fn suspend_others(
+   ) -> crate::thread::Result<Self> {
+      return Err(crate::thread::ThreadError::Unsupported);
+   }
+
+   pub unsafe fn suspend_others_except(
+      _excluded_thread_ids : & [u32],
+   ) -> crate::thread::Result<Self> {
+      return Err(crate::thread::ThreadError::Unsupported);
+   }
+
+   pub fn instruction_pointers<'l>(
+      &'l self,
+   ) -> &'l [usize] {
+      return &self.instruction_pointers;
+   }
+}
+
+pub fn sample_instruction_pointers(
+) -> crate::thread::Result<Vec<usize>> {
+   return Err(crate::thread::ThreadError::Unsupported);
+}
+
+pub fn current_thread_id(
+) -> u32 {
+   return unsafe{libc::gettid()} as u32;
+}
+
+pub fn flush_instruction_cache(
+   _address_range : std::ops::Range<usize>,
+) -> crate::thread::Result<()> {
+   // The instruction cache is coherent with
+   // data writes on x86, so there is nothing
+   // to flush
+   return Ok(());
+}
+
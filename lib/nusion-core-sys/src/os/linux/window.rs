@@ -0,0 +1,29 @@
+//! crate::window implementations for
+//! Linux.  Windows belong to the display
+//! server rather than the process, and
+//! there is no window procedure to replace.
+
+use crate::window::{WindowError, Result};
+
+pub fn find_process_window(
+) -> Option<usize> {
+   return None;
+}
+
+pub fn is_foreground(
+   _window : usize,
+) -> bool {
+   return false;
+}
+
+pub fn subclass(
+   _window : usize,
+) -> Result<()> {
+   return Err(WindowError::Unsupported);
+}
+
+pub fn unsubclass(
+   _window : usize,
+) {
+   return;
+}
@@ -3,6 +3,7 @@
 // Platform support check
 #[cfg(not(any(
    target_os = "windows",
+   target_os = "linux",
 )))] compile_error! (
    "Unsupported target operating system",
 );
@@ -11,7 +12,13 @@
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+#[cfg(target_os = "linux")]
+pub mod linux;
+
 // OS abstraction re-exports
 #[cfg(target_os = "windows")]
 pub use windows::*;
 
+#[cfg(target_os = "linux")]
+pub use linux::*;
+
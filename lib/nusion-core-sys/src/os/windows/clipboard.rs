@@ -0,0 +1,74 @@
+//! crate::os::clipboard implementation for Windows.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         FALSE,
+      },
+   },
+   um::{
+      winbase::{
+         GlobalAlloc,
+         GlobalFree,
+         GlobalLock,
+         GlobalUnlock,
+         GMEM_MOVEABLE,
+      },
+      winuser::{
+         CloseClipboard,
+         EmptyClipboard,
+         OpenClipboard,
+         SetClipboardData,
+         CF_UNICODETEXT,
+      },
+   },
+};
+
+pub fn set_text(
+   text : & str,
+) -> crate::clipboard::Result<()> {
+   let text = crate::encoding::to_wide_nul(text).map_err(
+      |_| crate::clipboard::ClipboardError::InvalidTextCharacters,
+   )?;
+
+   if unsafe{OpenClipboard(std::ptr::null_mut())} == FALSE {
+      return Err(crate::clipboard::ClipboardError::Unknown);
+   }
+
+   let result = unsafe{set_open_clipboard_text(&text)};
+
+   unsafe{CloseClipboard()};
+   return result;
+}
+
+unsafe fn set_open_clipboard_text(
+   text : & [u16],
+) -> crate::clipboard::Result<()> {
+   if EmptyClipboard() == FALSE {
+      return Err(crate::clipboard::ClipboardError::Unknown);
+   }
+
+   // The clipboard takes ownership of the
+   // memory, so it must come from GlobalAlloc
+   let memory = GlobalAlloc(GMEM_MOVEABLE, text.len() * std::mem::size_of::<u16>());
+   if memory.is_null() == true {
+      return Err(crate::clipboard::ClipboardError::Unknown);
+   }
+
+   let buffer = GlobalLock(memory) as * mut u16;
+   if buffer.is_null() == true {
+      GlobalFree(memory);
+      return Err(crate::clipboard::ClipboardError::Unknown);
+   }
+
+   std::ptr::copy_nonoverlapping(text.as_ptr(), buffer, text.len());
+   GlobalUnlock(memory);
+
+   if SetClipboardData(CF_UNICODETEXT, memory).is_null() == true {
+      GlobalFree(memory);
+      return Err(crate::clipboard::ClipboardError::Unknown);
+   }
+
+   return Ok(());
+}
+
@@ -1,25 +1,96 @@
 //! crate::os::console implementation for Windows.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use winapi::{
    shared::{
       minwindef::{
+         BOOL,
          DWORD,
          FALSE,
+         FARPROC,
+         TRUE,
       },
    },
    um::{
       consoleapi::{
          AllocConsole,
+         GetConsoleOutputCP,
+         ReadConsoleInputW,
+         SetConsoleCtrlHandler,
+      },
+      synchapi::{
+         WaitForSingleObject,
+      },
+      handleapi::{
+         CloseHandle,
+         DuplicateHandle,
+         INVALID_HANDLE_VALUE,
+      },
+      libloaderapi::{
+         GetModuleHandleA,
+         GetProcAddress,
+      },
+      namedpipeapi::{
+         CreatePipe,
+      },
+      processenv::{
+         GetStdHandle,
+         SetStdHandle,
+      },
+      processthreadsapi::{
+         GetCurrentProcess,
+      },
+      winbase::{
+         STD_ERROR_HANDLE,
+         STD_INPUT_HANDLE,
+         STD_OUTPUT_HANDLE,
+         WAIT_OBJECT_0,
       },
       wincon::{
+         AttachConsole,
          FreeConsole,
+         GetConsoleScreenBufferInfo,
          GetConsoleTitleA,
+         GetConsoleWindow,
+         PeekConsoleInputW,
+         ReadConsoleOutputCharacterW,
+         SetConsoleOutputCP,
          SetConsoleTitleA,
+         ATTACH_PARENT_PROCESS,
+         CONSOLE_SCREEN_BUFFER_INFO,
+         CTRL_BREAK_EVENT,
+         CTRL_C_EVENT,
+         CTRL_CLOSE_EVENT,
+      },
+      wincontypes::{
+         COORD,
+         INPUT_RECORD,
+         KEY_EVENT,
       },
       winnt::{
+         DUPLICATE_SAME_ACCESS,
+         HANDLE,
          LPSTR,
          LPCSTR,
       },
+      winuser::{
+         DeleteMenu,
+         GetSystemMenu,
+         MF_BYCOMMAND,
+         SC_CLOSE,
+         VK_BACK,
+         VK_DELETE,
+         VK_DOWN,
+         VK_END,
+         VK_ESCAPE,
+         VK_HOME,
+         VK_LEFT,
+         VK_RETURN,
+         VK_RIGHT,
+         VK_TAB,
+         VK_UP,
+      },
    },
 };
 
@@ -27,9 +98,69 @@ use winapi::{
 // set with SetConsoleTitleA.
 const MAX_TITLE_LENGTH : DWORD = 65535;
 
+// Set when the user tries to close the
+// console instead of killing the process
+static CLOSE_REQUESTED : AtomicBool = AtomicBool::new(false);
+
 pub struct Console {
 }
 
+// The original output and error handles,
+// which are put back when restoring, the
+// write ends of their pipes, the console's
+// original code page, and the C runtimes
+// whose descriptors were redirected
+pub struct OutputRedirect {
+   output      : HANDLE,
+   error       : HANDLE,
+   writes      : [HANDLE; 2],
+   code_page   : u32,
+   runtimes    : Vec<RuntimeRedirect>,
+}
+
+// The C runtime keeps its own descriptors
+// for standard output and error, bound to
+// the standard handles when it started, so
+// its streams have to be redirected through
+// the runtime itself.  The original
+// descriptors are kept as copies.
+struct RuntimeRedirect {
+   dup2     : unsafe extern "C" fn(i32, i32) -> i32,
+   close    : unsafe extern "C" fn(i32) -> i32,
+   fflush   : unsafe extern "C" fn(* mut std::ffi::c_void) -> i32,
+   saved    : [i32; 2],
+}
+
+// Standard handles may be set from any thread
+unsafe impl Send for OutputRedirect {}
+unsafe impl Sync for OutputRedirect {}
+
+// Console input events are read directly,
+// which bypasses line input and echoing
+// regardless of the input's mode
+pub struct KeyReader {
+   input             : HANDLE,
+   high_surrogate    : Option<u16>,
+}
+
+// Ctrl+C, Ctrl+Break, and closing the window
+// all terminate the whole game by default.
+// Ctrl+C and Ctrl+Break are swallowed, but the
+// process is always terminated once the handler
+// returns from a close event, so the best that
+// can be done is recording the request.
+unsafe extern "system" fn console_ctrl_handler(
+   ctrl_type : DWORD,
+) -> BOOL {
+   return match ctrl_type {
+      CTRL_C_EVENT | CTRL_BREAK_EVENT | CTRL_CLOSE_EVENT => {
+         CLOSE_REQUESTED.store(true, Ordering::Relaxed);
+         TRUE
+      },
+      _ => FALSE,
+   };
+}
+
 impl Console {
    pub fn allocate(
    ) -> crate::console::Result<Self> {
@@ -37,12 +168,50 @@ impl Console {
          return Err(crate::console::ConsoleError::Unknown);
       }
 
+      // Remove the close button so the window
+      // can't be closed by accident
+      let window = unsafe{GetConsoleWindow()};
+      if window.is_null() == false {
+         let menu = unsafe{GetSystemMenu(window, FALSE)};
+         if menu.is_null() == false {
+            unsafe{DeleteMenu(menu, SC_CLOSE as u32, MF_BYCOMMAND)};
+         }
+      }
+
+      CLOSE_REQUESTED.store(false, Ordering::Relaxed);
+      if unsafe{SetConsoleCtrlHandler(Some(console_ctrl_handler), TRUE)} == FALSE {
+         unsafe{FreeConsole()};
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      return Ok(Self{});
+   }
+
+   pub fn attach(
+   ) -> crate::console::Result<Self> {
+      if unsafe{AttachConsole(ATTACH_PARENT_PROCESS)} == FALSE {
+         return Err(crate::console::ConsoleError::NoParentConsole);
+      }
+
+      // The console belongs to the parent,
+      // so its close button is left alone,
+      // but Ctrl+C still shouldn't kill the
+      // game along with it
+      CLOSE_REQUESTED.store(false, Ordering::Relaxed);
+      if unsafe{SetConsoleCtrlHandler(Some(console_ctrl_handler), TRUE)} == FALSE {
+         unsafe{FreeConsole()};
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
       return Ok(Self{});
    }
 
    pub fn free(
       & mut self,
    ) -> crate::console::Result<()> {
+      // The handler can't outlive the library
+      unsafe{SetConsoleCtrlHandler(Some(console_ctrl_handler), FALSE)};
+
       if unsafe{FreeConsole()} == FALSE {
          return Err(crate::console::ConsoleError::Unknown);
       }
@@ -89,9 +258,9 @@ impl Console {
       new_title : & str,
    ) -> crate::console::Result<()> {
       // null-terminated C-string
-      let mut title  = String::from(new_title);
-      let title      = unsafe{title.as_mut_vec()};
-      title.push(0);
+      let title = crate::encoding::to_narrow_nul(new_title).map_err(
+         |_| crate::console::ConsoleError::InvalidTitleCharacters,
+      )?;
 
       if title.len() > MAX_TITLE_LENGTH as usize {
          return Err(crate::console::ConsoleError::Unknown);
@@ -103,5 +272,408 @@ impl Console {
 
       return Ok(());
    }
+
+   pub fn is_close_requested(
+      & self,
+   ) -> bool {
+      return CLOSE_REQUESTED.load(Ordering::Relaxed);
+   }
+
+   pub fn read_lines(
+      & self,
+      count : usize,
+   ) -> crate::console::Result<Vec<String>> {
+      use std::os::windows::io::AsRawHandle;
+
+      // Standard output may be redirected,
+      // so the screen buffer is opened by name
+      let screen = std::fs::OpenOptions::new()
+         .read(true)
+         .write(true)
+         .open("CONOUT$")
+         .map_err(|_| crate::console::ConsoleError::Unknown)?;
+      let output = screen.as_raw_handle() as HANDLE;
+
+      let mut info = unsafe{std::mem::zeroed::<CONSOLE_SCREEN_BUFFER_INFO>()};
+      if unsafe{GetConsoleScreenBufferInfo(output, & mut info)} == FALSE {
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      // Include the cursor's row only if
+      // something was written to it
+      let width   = info.dwSize.X.max(0) as usize;
+      let end     = info.dwCursorPosition.Y as usize + usize::from(info.dwCursorPosition.X > 0);
+      let start   = end.saturating_sub(count);
+
+      let mut lines  = Vec::with_capacity(end - start);
+      let mut row    = vec![0u16; width];
+      for y in start..end {
+         let mut read : DWORD = 0;
+         if unsafe{ReadConsoleOutputCharacterW(
+            output,
+            row.as_mut_ptr(),
+            width as DWORD,
+            COORD{X : 0, Y : y as i16},
+            & mut read,
+         )} == FALSE {
+            return Err(crate::console::ConsoleError::Unknown);
+         }
+
+         // Rows are padded with spaces to
+         // the width of the screen buffer
+         let line = crate::encoding::from_wide_lossy(&row[..read as usize]);
+         lines.push(String::from(line.trim_end()));
+      }
+
+      return Ok(lines);
+   }
 }
 
+// Keys read_key reports which don't
+// type a character
+fn is_edit_key(
+   virtual_key : i32,
+) -> bool {
+   return matches!(
+      virtual_key,
+      VK_RETURN | VK_TAB | VK_BACK | VK_DELETE | VK_ESCAPE
+         | VK_LEFT | VK_RIGHT | VK_UP | VK_DOWN | VK_HOME | VK_END
+   );
+}
+
+impl OutputRedirect {
+   pub fn capture(
+   ) -> crate::console::Result<(Self, [(std::fs::File, Option<std::fs::File>); 2])> {
+      // Code page of UTF-8, since the pipe
+      // carries whatever bytes were written
+      const CP_UTF8 : u32 = 65001;
+
+      // Runtimes the game may be linked against
+      // dynamically.  A statically linked runtime
+      // can't be found, so its streams aren't
+      // captured.
+      const RUNTIMES : [&[u8]; 2] = [b"ucrtbase.dll\0", b"msvcrt.dll\0"];
+
+      let output  = unsafe{GetStdHandle(STD_OUTPUT_HANDLE)};
+      let error   = unsafe{GetStdHandle(STD_ERROR_HANDLE)};
+
+      let (output_write, output_stream) = capture_stream(output)?;
+      let (error_write, error_stream) = match capture_stream(error) {
+         Ok(capture) => capture,
+         Err(e)      => {
+            unsafe{CloseHandle(output_write)};
+            return Err(e);
+         },
+      };
+
+      if unsafe{SetStdHandle(STD_OUTPUT_HANDLE, output_write)} == FALSE
+         || unsafe{SetStdHandle(STD_ERROR_HANDLE, error_write)} == FALSE
+      {
+         unsafe{SetStdHandle(STD_OUTPUT_HANDLE, output)};
+         unsafe{SetStdHandle(STD_ERROR_HANDLE, error)};
+         for handle in [output_write, error_write] {
+            unsafe{CloseHandle(handle)};
+         }
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      let runtimes = RUNTIMES.iter()
+         .filter_map(|name| RuntimeRedirect::capture(name, [output_write, error_write]))
+         .collect();
+
+      let code_page = unsafe{GetConsoleOutputCP()};
+      unsafe{SetConsoleOutputCP(CP_UTF8)};
+
+      return Ok((
+         Self{
+            output      : output,
+            error       : error,
+            writes      : [output_write, error_write],
+            code_page   : code_page,
+            runtimes    : runtimes,
+         },
+         [output_stream, error_stream],
+      ));
+   }
+
+   pub fn restore(
+      & mut self,
+   ) {
+      if self.writes.iter().any(|write| write.is_null() == true) {
+         return;
+      }
+
+      for runtime in self.runtimes.drain(..) {
+         runtime.restore();
+      }
+
+      unsafe{SetStdHandle(STD_OUTPUT_HANDLE, self.output)};
+      unsafe{SetStdHandle(STD_ERROR_HANDLE, self.error)};
+      for write in self.writes.iter_mut() {
+         unsafe{CloseHandle(*write)};
+         *write = std::ptr::null_mut();
+      }
+
+      // Zero means there was no console
+      if self.code_page != 0 {
+         unsafe{SetConsoleOutputCP(self.code_page)};
+      }
+
+      return;
+   }
+}
+
+impl RuntimeRedirect {
+   fn capture(
+      module_name : & [u8],
+      writes      : [HANDLE; 2],
+   ) -> Option<Self> {
+      type OpenOsfhandle = unsafe extern "C" fn(isize, i32) -> i32;
+      type Dup           = unsafe extern "C" fn(i32) -> i32;
+      type Dup2          = unsafe extern "C" fn(i32, i32) -> i32;
+      type Close         = unsafe extern "C" fn(i32) -> i32;
+      type Fflush        = unsafe extern "C" fn(* mut std::ffi::c_void) -> i32;
+
+      // _O_WRONLY | _O_BINARY, since the
+      // pipe already carries the exact
+      // bytes which were written
+      const OPEN_FLAGS : i32 = 0x0001 | 0x8000;
+
+      let module = unsafe{GetModuleHandleA(module_name.as_ptr() as LPCSTR)};
+      if module.is_null() == true {
+         return None;
+      }
+
+      let function = |name : & [u8]| {
+         let function = unsafe{GetProcAddress(module, name.as_ptr() as LPCSTR)};
+         return match function.is_null() {
+            true  => None,
+            false => Some(function),
+         };
+      };
+
+      let open_osfhandle   = unsafe{std::mem::transmute::<FARPROC, OpenOsfhandle>(function(b"_open_osfhandle\0")?)};
+      let dup              = unsafe{std::mem::transmute::<FARPROC, Dup>(function(b"_dup\0")?)};
+      let runtime = Self{
+         dup2     : unsafe{std::mem::transmute::<FARPROC, Dup2>(function(b"_dup2\0")?)},
+         close    : unsafe{std::mem::transmute::<FARPROC, Close>(function(b"_close\0")?)},
+         fflush   : unsafe{std::mem::transmute::<FARPROC, Fflush>(function(b"fflush\0")?)},
+         saved    : [unsafe{dup(1)}, unsafe{dup(2)}],
+      };
+
+      // Anything still buffered belongs
+      // to the original streams
+      unsafe{(runtime.fflush)(std::ptr::null_mut())};
+
+      // The runtime takes ownership of the
+      // handle it's given, so it gets its own
+      for (descriptor, write) in [1, 2].into_iter().zip(writes) {
+         let mut copy = std::ptr::null_mut();
+         if unsafe{DuplicateHandle(
+            GetCurrentProcess(),
+            write,
+            GetCurrentProcess(),
+            & mut copy,
+            0,
+            FALSE,
+            DUPLICATE_SAME_ACCESS,
+         )} == FALSE {
+            continue;
+         }
+
+         let opened = unsafe{open_osfhandle(copy as isize, OPEN_FLAGS)};
+         if opened < 0 {
+            unsafe{CloseHandle(copy)};
+            continue;
+         }
+
+         unsafe{(runtime.dup2)(opened, descriptor)};
+         unsafe{(runtime.close)(opened)};
+      }
+
+      return Some(runtime);
+   }
+
+   // Closing the runtime's copies of the
+   // pipe is needed for the pipe to end
+   fn restore(
+      self,
+   ) {
+      unsafe{(self.fflush)(std::ptr::null_mut())};
+      for (descriptor, saved) in [1, 2].into_iter().zip(self.saved) {
+         match saved < 0 {
+            true  => unsafe{(self.close)(descriptor)},
+            false => {
+               unsafe{(self.dup2)(saved, descriptor)};
+               unsafe{(self.close)(saved)}
+            },
+         };
+      }
+
+      return;
+   }
+}
+
+// Creates a pipe to replace a standard
+// handle with, returning its write end
+// and its read end along with a copy of
+// the original handle to pass the output
+// on to.  A GUI process may have nowhere
+// its output originally went, in which
+// case there is no copy.
+fn capture_stream(
+   original : HANDLE,
+) -> crate::console::Result<(HANDLE, (std::fs::File, Option<std::fs::File>))> {
+   use std::os::windows::io::FromRawHandle;
+
+   let mut read   = std::ptr::null_mut();
+   let mut write  = std::ptr::null_mut();
+   if unsafe{CreatePipe(& mut read, & mut write, std::ptr::null_mut(), 0)} == FALSE {
+      return Err(crate::console::ConsoleError::Unknown);
+   }
+
+   let mut copy = std::ptr::null_mut();
+   let copied = original.is_null() == false && original != INVALID_HANDLE_VALUE && unsafe{DuplicateHandle(
+      GetCurrentProcess(),
+      original,
+      GetCurrentProcess(),
+      & mut copy,
+      0,
+      FALSE,
+      DUPLICATE_SAME_ACCESS,
+   )} != FALSE;
+
+   return Ok((
+      write,
+      (
+         unsafe{std::fs::File::from_raw_handle(read as std::os::windows::io::RawHandle)},
+         match copied {
+            true  => Some(unsafe{std::fs::File::from_raw_handle(copy as std::os::windows::io::RawHandle)}),
+            false => None,
+         },
+      ),
+   ));
+}
+
+impl KeyReader {
+   pub fn new(
+   ) -> crate::console::Result<Self> {
+      let input = unsafe{GetStdHandle(STD_INPUT_HANDLE)};
+      if input.is_null() == true || input == INVALID_HANDLE_VALUE {
+         return Err(crate::console::ConsoleError::Unknown);
+      }
+
+      return Ok(Self{
+         input             : input,
+         high_surrogate    : None,
+      });
+   }
+
+   pub fn read_key(
+      & mut self,
+   ) -> crate::console::Result<crate::console::ConsoleKey> {
+      use crate::console::ConsoleKey;
+
+      loop {
+         let mut record = unsafe{std::mem::zeroed::<INPUT_RECORD>()};
+         let mut read : DWORD = 0;
+         if unsafe{ReadConsoleInputW(self.input, & mut record, 1, & mut read)} == FALSE {
+            return Err(crate::console::ConsoleError::Unknown);
+         }
+
+         if read == 0 || record.EventType != KEY_EVENT {
+            continue;
+         }
+
+         let event = unsafe{record.Event.KeyEvent()};
+         if event.bKeyDown == FALSE {
+            continue;
+         }
+
+         let key = match event.wVirtualKeyCode as i32 {
+            VK_RETURN   => ConsoleKey::Enter,
+            VK_TAB      => ConsoleKey::Tab,
+            VK_BACK     => ConsoleKey::Backspace,
+            VK_DELETE   => ConsoleKey::Delete,
+            VK_ESCAPE   => ConsoleKey::Escape,
+            VK_LEFT     => ConsoleKey::Left,
+            VK_RIGHT    => ConsoleKey::Right,
+            VK_UP       => ConsoleKey::Up,
+            VK_DOWN     => ConsoleKey::Down,
+            VK_HOME     => ConsoleKey::Home,
+            VK_END      => ConsoleKey::End,
+            _           => {
+               let unit = unsafe{*event.uChar.UnicodeChar()};
+               match self.decode(unit) {
+                  Some(character)   => ConsoleKey::Character(character),
+                  None              => continue,
+               }
+            },
+         };
+
+         return Ok(key);
+      }
+   }
+
+   pub fn wait_for_key(
+      & mut self,
+      timeout : std::time::Duration,
+   ) -> crate::console::Result<bool> {
+      let deadline = std::time::Instant::now() + timeout;
+      loop {
+         let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+         let remaining = remaining.as_millis().min(DWORD::MAX as u128 - 1) as DWORD;
+         if unsafe{WaitForSingleObject(self.input, remaining)} != WAIT_OBJECT_0 {
+            return Ok(false);
+         }
+
+         let mut record = unsafe{std::mem::zeroed::<INPUT_RECORD>()};
+         let mut read : DWORD = 0;
+         if unsafe{PeekConsoleInputW(self.input, & mut record, 1, & mut read)} == FALSE {
+            return Err(crate::console::ConsoleError::Unknown);
+         }
+         if read != 0 && record.EventType == KEY_EVENT {
+            let event = unsafe{record.Event.KeyEvent()};
+            if event.bKeyDown != FALSE && unsafe{*event.uChar.UnicodeChar()} != 0 {
+               return Ok(true);
+            }
+            if event.bKeyDown != FALSE && is_edit_key(event.wVirtualKeyCode as i32) == true {
+               return Ok(true);
+            }
+         }
+
+         // Events read_key would skip, such as
+         // focus changes and modifier keys, are
+         // discarded so it doesn't block on them
+         if read != 0 && unsafe{ReadConsoleInputW(self.input, & mut record, 1, & mut read)} == FALSE {
+            return Err(crate::console::ConsoleError::Unknown);
+         }
+      }
+   }
+
+   // Characters outside of the basic
+   // multilingual plane arrive as two
+   // separate key events
+   fn decode(
+      & mut self,
+      unit : u16,
+   ) -> Option<char> {
+      if unit == 0 {
+         return None;
+      }
+
+      if (0xD800..0xDC00).contains(&unit) == true {
+         self.high_surrogate = Some(unit);
+         return None;
+      }
+
+      let units = match self.high_surrogate.take() {
+         Some(high)  => vec![high, unit],
+         None        => vec![unit],
+      };
+
+      return char::decode_utf16(units).next().and_then(|character| character.ok()).filter(|character| {
+         character.is_control() == false
+      });
+   }
+}
@@ -0,0 +1,243 @@
+//! crate::os::debug implementation for Windows.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         FALSE,
+      },
+      ntdef::{
+         LONG,
+      },
+   },
+   um::{
+      errhandlingapi::{
+         AddVectoredExceptionHandler,
+         RemoveVectoredExceptionHandler,
+      },
+      handleapi::{
+         CloseHandle,
+      },
+      minwinbase::{
+         EXCEPTION_SINGLE_STEP,
+      },
+      processthreadsapi::{
+         GetCurrentThreadId,
+         GetThreadContext,
+         OpenThread,
+         ResumeThread,
+         SetThreadContext,
+         SuspendThread,
+      },
+      winnt::{
+         CONTEXT,
+         CONTEXT_DEBUG_REGISTERS,
+         EXCEPTION_POINTERS,
+         THREAD_GET_CONTEXT,
+         THREAD_SET_CONTEXT,
+         THREAD_SUSPEND_RESUME,
+      },
+   },
+   vc::{
+      excpt::{
+         EXCEPTION_CONTINUE_EXECUTION,
+         EXCEPTION_CONTINUE_SEARCH,
+      },
+   },
+};
+
+// Resume flag, which stops an execute
+// breakpoint from triggering again on
+// the instruction it returns to
+const EFLAGS_RF : u32 = 0x10000;
+
+static HIT_HANDLER : std::sync::atomic::AtomicUsize
+   = std::sync::atomic::AtomicUsize::new(0);
+
+static HANDLER_HANDLE : std::sync::Mutex<usize>
+   = std::sync::Mutex::new(0);
+
+pub unsafe fn set_watchpoint(
+   slot     : usize,
+   address  : usize,
+   length   : usize,
+   kind     : crate::debug::WatchKind,
+) -> crate::debug::Result<()> {
+   let access = match kind {
+      crate::debug::WatchKind::Execute    => 0b00,
+      crate::debug::WatchKind::Write      => 0b01,
+      crate::debug::WatchKind::ReadWrite  => 0b11,
+   };
+   let size = match length {
+      1 => 0b00,
+      2 => 0b01,
+      8 => 0b10,
+      _ => 0b11,
+   };
+
+   return update_all_threads(&|context| {
+      set_address_register(context, slot, address as u64);
+      context.Dr7 &= !control_mask(slot);
+      context.Dr7 |= (1 << (slot * 2)) | (access << (16 + slot * 4)) | (size << (18 + slot * 4));
+      return;
+   });
+}
+
+pub unsafe fn clear_watchpoint(
+   slot : usize,
+) -> crate::debug::Result<()> {
+   return update_all_threads(&|context| {
+      set_address_register(context, slot, 0);
+      context.Dr7 &= !control_mask(slot);
+      return;
+   });
+}
+
+pub fn set_hit_handler(
+   handler : crate::debug::HitHandler,
+) -> crate::debug::Result<()> {
+   HIT_HANDLER.store(handler as usize, std::sync::atomic::Ordering::SeqCst);
+
+   let mut handle = HANDLER_HANDLE.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *handle == 0 {
+      // Called first so the handler runs
+      // before any added by the game
+      let new_handle = unsafe{AddVectoredExceptionHandler(1, Some(exception_handler))};
+      if new_handle.is_null() == true {
+         return Err(crate::debug::DebugError::Unknown);
+      }
+      *handle = new_handle as usize;
+   }
+
+   return Ok(());
+}
+
+pub fn clear_hit_handler(
+) {
+   let mut handle = HANDLER_HANDLE.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *handle != 0 {
+      unsafe{RemoveVectoredExceptionHandler(*handle as _)};
+      *handle = 0;
+   }
+   HIT_HANDLER.store(0, std::sync::atomic::Ordering::SeqCst);
+
+   return;
+}
+
+fn control_mask(
+   slot : usize,
+) -> u64 {
+   return (0b11 << (slot * 2)) | (0b1111 << (16 + slot * 4));
+}
+
+fn set_address_register(
+   context  : & mut CONTEXT,
+   slot     : usize,
+   address  : u64,
+) {
+   match slot {
+      0 => context.Dr0 = address,
+      1 => context.Dr1 = address,
+      2 => context.Dr2 = address,
+      _ => context.Dr3 = address,
+   }
+   return;
+}
+
+unsafe fn update_all_threads(
+   update : &(dyn Fn(& mut CONTEXT) + Sync),
+) -> crate::debug::Result<()> {
+   let thread_ids = crate::os::thread::other_thread_ids()
+      .map_err(|_| crate::debug::DebugError::Unknown)?;
+
+   // Threads may exit between taking the
+   // snapshot and opening them, so failures
+   // for other threads are ignored
+   for thread_id in thread_ids {
+      let _ = update_thread(thread_id, update);
+   }
+
+   // A running thread can't set its own
+   // debug registers, so a helper thread
+   // does it while this one is suspended
+   let thread_id = GetCurrentThreadId();
+   return std::thread::scope(|scope| {
+      return scope.spawn(|| unsafe{update_thread(thread_id, update)})
+         .join()
+         .unwrap_or(Err(crate::debug::DebugError::Unknown));
+   });
+}
+
+unsafe fn update_thread(
+   thread_id   : u32,
+   update      : &(dyn Fn(& mut CONTEXT) + Sync),
+) -> crate::debug::Result<()> {
+   let handle = OpenThread(
+      THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT | THREAD_SET_CONTEXT,
+      FALSE,
+      thread_id,
+   );
+   if handle.is_null() == true {
+      return Err(crate::debug::DebugError::Unknown);
+   }
+
+   if SuspendThread(handle) == u32::MAX {
+      CloseHandle(handle);
+      return Err(crate::debug::DebugError::Unknown);
+   }
+
+   let mut context = std::mem::zeroed::<CONTEXT>();
+   context.ContextFlags = CONTEXT_DEBUG_REGISTERS;
+   let mut updated = GetThreadContext(handle, &mut context) != FALSE;
+   if updated == true {
+      update(&mut context);
+      updated = SetThreadContext(handle, &context) != FALSE;
+   }
+
+   ResumeThread(handle);
+   CloseHandle(handle);
+
+   if updated == false {
+      return Err(crate::debug::DebugError::Unknown);
+   }
+   return Ok(());
+}
+
+unsafe extern "system" fn exception_handler(
+   exception_info : * mut EXCEPTION_POINTERS,
+) -> LONG {
+   let record  = &*(*exception_info).ExceptionRecord;
+   let context = &mut *(*exception_info).ContextRecord;
+
+   if record.ExceptionCode != EXCEPTION_SINGLE_STEP {
+      return EXCEPTION_CONTINUE_SEARCH;
+   }
+
+   // Only breakpoints enabled in Dr7 are ours,
+   // anything else is left for a debugger
+   let triggered = (0..crate::debug::SLOT_COUNT).filter(|slot| {
+      return context.Dr6 & (1 << slot) != 0 && context.Dr7 & (1 << (slot * 2)) != 0;
+   });
+
+   let handler = HIT_HANDLER.load(std::sync::atomic::Ordering::SeqCst);
+   let mut handled = false;
+   for slot in triggered {
+      handled = true;
+      if handler != 0 {
+         let handler = std::mem::transmute::<usize, crate::debug::HitHandler>(handler);
+         handler(&crate::debug::WatchpointHit{
+            slot                 : slot,
+            thread_id            : GetCurrentThreadId(),
+            instruction_pointer  : context.Rip as usize,
+         });
+      }
+   }
+
+   if handled == false {
+      return EXCEPTION_CONTINUE_SEARCH;
+   }
+
+   context.Dr6 = 0;
+   context.EFlags |= EFLAGS_RF;
+   return EXCEPTION_CONTINUE_EXECUTION;
+}
+
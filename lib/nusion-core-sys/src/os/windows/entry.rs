@@ -8,7 +8,7 @@
 // double underscores.
 #[macro_export]
 macro_rules! build_entry {
-   ($starter:path, $entry:ident, $osapi:path, $($proc:literal),*)  => {
+   ($starter:path, $entry:ident, $name:expr, $options:expr, $osapi:path, $($proc:literal),*)  => {
       // Re-export because of weird issues expanding in-place
       use $osapi as __nusion_core_osapi;
 
@@ -59,7 +59,7 @@ macro_rules! build_entry {
          handle_dll : __nusion_core_osapi::shared::minwindef::LPVOID,
       ) -> __nusion_core_osapi::shared::minwindef::DWORD {
          // Execute main, storing the return code for the end
-         let return_code = $starter($entry, &[$($proc),*]).code;
+         let return_code = $starter($entry, $name, &[$($proc),*], &$options).code;
 
          // Attempt to unload the library
          unsafe{__nusion_core_osapi::um::libloaderapi::FreeLibraryAndExitThread(
@@ -0,0 +1,100 @@
+//! crate::os::exception implementation for Windows.
+
+use winapi::{
+   shared::{
+      ntdef::{
+         LONG,
+      },
+      ntstatus::{
+         STATUS_ACCESS_VIOLATION,
+         STATUS_IN_PAGE_ERROR,
+      },
+   },
+   um::{
+      errhandlingapi::{
+         AddVectoredExceptionHandler,
+         RemoveVectoredExceptionHandler,
+      },
+      winnt::{
+         EXCEPTION_POINTERS,
+      },
+   },
+   vc::{
+      excpt::{
+         EXCEPTION_CONTINUE_EXECUTION,
+         EXCEPTION_CONTINUE_SEARCH,
+      },
+   },
+};
+
+// Access kinds stored in the first
+// exception information parameter
+const ACCESS_READ    : usize = 0;
+const ACCESS_WRITE   : usize = 1;
+const ACCESS_EXECUTE : usize = 8;
+
+static HANDLER_HANDLE : std::sync::Mutex<usize>
+   = std::sync::Mutex::new(0);
+
+pub fn install(
+) -> crate::exception::Result<()> {
+   let mut handle = HANDLER_HANDLE.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *handle == 0 {
+      let new_handle = unsafe{AddVectoredExceptionHandler(1, Some(exception_handler))};
+      if new_handle.is_null() == true {
+         return Err(crate::exception::ExceptionError::Unknown);
+      }
+      *handle = new_handle as usize;
+   }
+
+   return Ok(());
+}
+
+pub fn uninstall(
+) {
+   let mut handle = HANDLER_HANDLE.lock().unwrap_or_else(|poison| poison.into_inner());
+   if *handle != 0 {
+      unsafe{RemoveVectoredExceptionHandler(*handle as _)};
+      *handle = 0;
+   }
+
+   return;
+}
+
+unsafe extern "system" fn exception_handler(
+   exception_info : * mut EXCEPTION_POINTERS,
+) -> LONG {
+   let record  = &*(*exception_info).ExceptionRecord;
+   let context = &mut *(*exception_info).ContextRecord;
+
+   let code = record.ExceptionCode as i32;
+   if code != STATUS_ACCESS_VIOLATION && code != STATUS_IN_PAGE_ERROR {
+      return EXCEPTION_CONTINUE_SEARCH;
+   }
+
+   let access = match record.ExceptionInformation[0] {
+      ACCESS_READ    => crate::exception::FaultAccess::Read,
+      ACCESS_WRITE   => crate::exception::FaultAccess::Write,
+      ACCESS_EXECUTE => crate::exception::FaultAccess::Execute,
+      _              => crate::exception::FaultAccess::Unknown,
+   };
+
+   let fault = crate::exception::FaultInfo{
+      code                 : record.ExceptionCode,
+      access               : access,
+      fault_address        : record.ExceptionInformation[1],
+      instruction_pointer  : context.Rip as usize,
+   };
+
+   return match crate::exception::dispatch_fault(&fault, context.R11 as usize) {
+      crate::exception::FaultResponse::Resume(resume_address) => {
+         context.Rip = resume_address as u64;
+         EXCEPTION_CONTINUE_EXECUTION
+      },
+      crate::exception::FaultResponse::Retry
+         => EXCEPTION_CONTINUE_EXECUTION,
+      crate::exception::FaultResponse::Continue
+         => EXCEPTION_CONTINUE_SEARCH,
+   };
+}
+
@@ -0,0 +1,157 @@
+//! Portable Executable (PE) image
+//! parsing for modules loaded into
+//! the local process.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         DWORD,
+      },
+   },
+   um::{
+      winnt::{
+         IMAGE_DATA_DIRECTORY,
+         IMAGE_DOS_HEADER,
+         IMAGE_DOS_SIGNATURE,
+         IMAGE_FILE_HEADER,
+         IMAGE_NT_HEADERS64,
+         IMAGE_NT_OPTIONAL_HDR64_MAGIC,
+         IMAGE_NT_SIGNATURE,
+         IMAGE_SECTION_HEADER,
+      },
+   },
+};
+
+pub struct Image {
+   pub address_range : std::ops::Range<usize>,
+   pub headers_rva   : usize,
+   pub headers       : IMAGE_NT_HEADERS64,
+}
+
+impl Image {
+   /// Parses the headers of an image mapped
+   /// into memory over the address range.
+   /// The memory must be readable for the
+   /// duration of the call.
+   pub unsafe fn parse(
+      address_range : & std::ops::Range<usize>,
+   ) -> Option<Self> {
+      let mut image = Self{
+         address_range  : address_range.clone(),
+         headers_rva    : 0,
+         headers        : std::mem::zeroed(),
+      };
+
+      let dos_header = image.read::<IMAGE_DOS_HEADER>(0)?;
+      if dos_header.e_magic != IMAGE_DOS_SIGNATURE {
+         return None;
+      }
+
+      let headers_rva   = usize::try_from(dos_header.e_lfanew).ok()?;
+      let headers       = image.read::<IMAGE_NT_HEADERS64>(headers_rva)?;
+      if headers.Signature             != IMAGE_NT_SIGNATURE            ||
+         headers.OptionalHeader.Magic  != IMAGE_NT_OPTIONAL_HDR64_MAGIC
+      {
+         return None;
+      }
+
+      image.headers_rva = headers_rva;
+      image.headers     = headers;
+      return Some(image);
+   }
+
+   /// Converts a relative virtual address
+   /// range into an absolute address range,
+   /// making sure it lies within the image.
+   pub fn rva_range_to_address_range(
+      & self,
+      rva   : usize,
+      size  : usize,
+   ) -> Option<std::ops::Range<usize>> {
+      let start   = self.address_range.start.checked_add(rva)?;
+      let end     = start.checked_add(size)?;
+
+      if end > self.address_range.end {
+         return None;
+      }
+
+      return Some(start..end);
+   }
+
+   /// Reads an item stored at a relative
+   /// virtual address within the image.
+   pub unsafe fn read<T: Copy>(
+      & self,
+      rva : usize,
+   ) -> Option<T> {
+      let address_range = self.rva_range_to_address_range(
+         rva, std::mem::size_of::<T>(),
+      )?;
+
+      return Some(std::ptr::read_unaligned(
+         address_range.start as * const T,
+      ));
+   }
+
+   /// Reads a null-terminated string stored
+   /// at a relative virtual address within
+   /// the image, excluding the terminator.
+   pub unsafe fn read_c_str<'l>(
+      &'l self,
+      rva : usize,
+   ) -> Option<&'l [u8]> {
+      let address_range = self.rva_range_to_address_range(
+         rva, self.address_range.end.checked_sub(self.address_range.start)?.checked_sub(rva)?,
+      )?;
+      let bytes = std::slice::from_raw_parts(
+         address_range.start as * const u8,
+         address_range.end - address_range.start,
+      );
+
+      let length = bytes.iter().position(|byte| *byte == 0)?;
+      return Some(&bytes[..length]);
+   }
+
+   /// Gets the absolute address range of
+   /// one of the image's data directories,
+   /// or <code>None</code> if it is absent.
+   pub fn data_directory(
+      & self,
+      index : DWORD,
+   ) -> Option<std::ops::Range<usize>> {
+      let optional_header = &self.headers.OptionalHeader;
+      if index >= optional_header.NumberOfRvaAndSizes {
+         return None;
+      }
+
+      let directory : & IMAGE_DATA_DIRECTORY = optional_header.DataDirectory.get(
+         index as usize,
+      )?;
+      if directory.VirtualAddress == 0 || directory.Size == 0 {
+         return None;
+      }
+
+      return self.rva_range_to_address_range(
+         directory.VirtualAddress   as usize,
+         directory.Size             as usize,
+      );
+   }
+
+   /// Reads the image's section headers.
+   pub unsafe fn sections(
+      & self,
+   ) -> Option<Vec<IMAGE_SECTION_HEADER>> {
+      // The section table follows the optional
+      // header, whose size is variable
+      let table_rva = self.headers_rva
+         + std::mem::size_of::<DWORD>()
+         + std::mem::size_of::<IMAGE_FILE_HEADER>()
+         + self.headers.FileHeader.SizeOfOptionalHeader as usize;
+
+      return (0..self.headers.FileHeader.NumberOfSections as usize).map(|index| {
+         self.read::<IMAGE_SECTION_HEADER>(
+            table_rva + index * std::mem::size_of::<IMAGE_SECTION_HEADER>(),
+         )
+      }).collect();
+   }
+}
@@ -0,0 +1,18 @@
+//! crate::os::input implementation for Windows.
+
+use winapi::{
+   um::{
+      winuser::{
+         GetAsyncKeyState,
+      },
+   },
+};
+
+pub fn is_key_down(
+   virtual_key : u8,
+) -> bool {
+   // The most significant bit is set
+   // while the key is held down
+   return unsafe{GetAsyncKeyState(virtual_key as i32)} as u16 & 0x8000 != 0;
+}
+
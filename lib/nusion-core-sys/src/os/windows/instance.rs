@@ -0,0 +1,81 @@
+//! crate::os::instance implementation for Windows.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         FALSE,
+      },
+      winerror::{
+         ERROR_ALREADY_EXISTS,
+      },
+   },
+   um::{
+      errhandlingapi::{
+         GetLastError,
+      },
+      handleapi::{
+         CloseHandle,
+      },
+      synchapi::{
+         CreateMutexW,
+      },
+      winnt::{
+         HANDLE,
+      },
+   },
+};
+
+pub struct InstanceLock {
+   name     : String,
+   handle   : HANDLE,
+}
+
+// The handle is only closed on drop
+unsafe impl Send for InstanceLock {}
+unsafe impl Sync for InstanceLock {}
+
+impl InstanceLock {
+   pub fn acquire(
+      name : & str,
+   ) -> crate::instance::Result<Option<Self>> {
+      // Named mutexes in the session-local
+      // namespace are shared between every
+      // module loaded into the process
+      let name_wide = crate::encoding::to_wide_nul(&format!("Local\\{name}")).map_err(
+         |_| crate::instance::InstanceError::InvalidName,
+      )?;
+
+      let handle = unsafe{CreateMutexW(std::ptr::null_mut(), FALSE, name_wide.as_ptr())};
+      if handle.is_null() == true {
+         return Err(crate::instance::InstanceError::Unknown);
+      }
+
+      // The handle is still valid when the
+      // mutex already exists, so close it
+      if unsafe{GetLastError()} == ERROR_ALREADY_EXISTS {
+         unsafe{CloseHandle(handle)};
+         return Ok(None);
+      }
+
+      return Ok(Some(Self{
+         name     : name.to_owned(),
+         handle   : handle,
+      }));
+   }
+
+   pub fn name(
+      & self,
+   ) -> & str {
+      return &self.name;
+   }
+}
+
+impl Drop for InstanceLock {
+   fn drop(
+      & mut self,
+   ) {
+      unsafe{CloseHandle(self.handle)};
+      return;
+   }
+}
+
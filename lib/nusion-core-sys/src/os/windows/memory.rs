@@ -16,14 +16,32 @@ use winapi::{
       errhandlingapi::{
          GetLastError,
       },
+      processthreadsapi::{
+         GetCurrentProcess,
+      },
+      sysinfoapi::{
+         GetSystemInfo,
+         SYSTEM_INFO,
+      },
       memoryapi::{
+         ReadProcessMemory,
+         VirtualAlloc,
          VirtualProtect,
+         VirtualQuery,
       },
       winnt::{
+         MEMORY_BASIC_INFORMATION,
+         MEM_COMMIT,
+         MEM_FREE,
+         MEM_RESERVE,
+         PAGE_GUARD,
+         PAGE_EXECUTE,
          PAGE_READONLY,
          PAGE_READWRITE,
+         PAGE_WRITECOPY,
          PAGE_EXECUTE_READ,
          PAGE_EXECUTE_READWRITE,
+         PAGE_EXECUTE_WRITECOPY,
       },
    },
 };
@@ -32,6 +50,14 @@ pub struct MemoryPermissions {
    permissions : DWORD
 }
 
+pub struct MemoryRegion {
+   pub address_range : std::ops::Range<usize>,
+   pub committed     : bool,
+   pub readable      : bool,
+   pub writable      : bool,
+   pub executable    : bool,
+}
+
 impl MemoryPermissions {
    pub const READ                : Self
       = Self{permissions : PAGE_READONLY           };
@@ -82,3 +108,221 @@ impl MemoryPermissions {
    }
 }
 
+
+impl MemoryRegion {
+   pub fn query(
+      address : usize,
+   ) -> crate::memory::Result<Self> {
+      // Query the region containing the address
+      let mut info = MEMORY_BASIC_INFORMATION{
+         BaseAddress       : 0 as LPVOID,
+         AllocationBase    : 0 as LPVOID,
+         AllocationProtect : 0,
+         RegionSize        : 0,
+         State             : 0,
+         Protect           : 0,
+         Type              : 0,
+      };
+      if unsafe{VirtualQuery(
+         address as LPVOID,
+         & mut info,
+         std::mem::size_of::<MEMORY_BASIC_INFORMATION>() as SIZE_T,
+      )} == 0 {
+         return Err(crate::memory::MemoryError::new(
+            crate::memory::MemoryErrorKind::UnmappedAddress,
+            address..address,
+         ));
+      }
+
+      // Guard pages fault on first access, so
+      // treat them as if they had no access
+      let committed  = info.State == MEM_COMMIT;
+      let protect    = match info.Protect & PAGE_GUARD {
+         0 => info.Protect,
+         _ => 0,
+      };
+
+      let readable   = committed && protect & (
+         PAGE_READONLY           |
+         PAGE_READWRITE          |
+         PAGE_WRITECOPY          |
+         PAGE_EXECUTE_READ       |
+         PAGE_EXECUTE_READWRITE  |
+         PAGE_EXECUTE_WRITECOPY
+      ) != 0;
+      let writable   = committed && protect & (
+         PAGE_READWRITE          |
+         PAGE_WRITECOPY          |
+         PAGE_EXECUTE_READWRITE  |
+         PAGE_EXECUTE_WRITECOPY
+      ) != 0;
+      let executable = committed && protect & (
+         PAGE_EXECUTE            |
+         PAGE_EXECUTE_READ       |
+         PAGE_EXECUTE_READWRITE  |
+         PAGE_EXECUTE_WRITECOPY
+      ) != 0;
+
+      let base = info.BaseAddress as usize;
+      return Ok(Self{
+         address_range  : base..base + info.RegionSize,
+         committed      : committed,
+         readable       : readable,
+         writable       : writable,
+         executable     : executable,
+      });
+   }
+}
+
+pub fn read_checked(
+   address  : usize,
+   buffer   : & mut [u8],
+) -> crate::memory::Result<()> {
+   // Reading through the process handle
+   // fails instead of faulting when the
+   // memory is unmapped or inaccessible
+   let mut bytes_read = 0;
+   if unsafe{ReadProcessMemory(
+      GetCurrentProcess(),
+      address as LPVOID,
+      buffer.as_mut_ptr() as LPVOID,
+      buffer.len() as SIZE_T,
+      & mut bytes_read,
+   )} == 0 || bytes_read != buffer.len() {
+      return Err(crate::memory::MemoryError::new(
+         crate::memory::MemoryErrorKind::UnmappedAddress,
+         address..address.saturating_add(buffer.len()),
+      ));
+   }
+
+   return Ok(());
+}
+
+pub fn page_size(
+) -> usize {
+   let mut info = unsafe{std::mem::zeroed::<SYSTEM_INFO>()};
+   unsafe{GetSystemInfo(& mut info)};
+
+   return match info.dwPageSize {
+      0     => 4096,
+      size  => size as usize,
+   };
+}
+
+pub fn allocate_executable(
+   size : usize,
+) -> crate::memory::Result<std::ops::Range<usize>> {
+   let base = unsafe{VirtualAlloc(
+      std::ptr::null_mut(),
+      size as SIZE_T,
+      MEM_COMMIT | MEM_RESERVE,
+      PAGE_EXECUTE_READWRITE,
+   )} as usize;
+
+   if base == 0 {
+      return Err(crate::memory::MemoryError::new(
+         crate::memory::MemoryErrorKind::Unknown,
+         0..size,
+      ));
+   }
+
+   return Ok(base..base + size);
+}
+
+pub fn allocate_executable_near(
+   address  : usize,
+   size     : usize,
+   reach    : usize,
+) -> crate::memory::Result<std::ops::Range<usize>> {
+   // Allocations always start on a 64KiB boundary
+   const GRANULARITY : usize = 0x10000;
+
+   let lowest  = address.saturating_sub(reach).next_multiple_of(GRANULARITY);
+   let highest = address.saturating_add(reach).saturating_sub(size);
+
+   // Search upwards from the address first, then downwards
+   let mut candidate = address.next_multiple_of(GRANULARITY);
+   while candidate <= highest {
+      let info = match query_basic_information(candidate) {
+         Some(info)  => info,
+         None        => break,
+      };
+      let region_end = info.BaseAddress as usize + info.RegionSize;
+
+      if info.State == MEM_FREE && region_end - candidate >= size {
+         if let Some(range) = try_allocate_at(candidate, size) {
+            return Ok(range);
+         }
+      }
+
+      candidate = region_end.next_multiple_of(GRANULARITY);
+   }
+
+   let mut candidate = address.saturating_sub(size) / GRANULARITY * GRANULARITY;
+   while candidate >= lowest && candidate != 0 {
+      let info = match query_basic_information(candidate) {
+         Some(info)  => info,
+         None        => break,
+      };
+      let region_start  = info.BaseAddress as usize;
+      let region_end    = region_start + info.RegionSize;
+
+      if info.State == MEM_FREE && region_end - candidate >= size {
+         if let Some(range) = try_allocate_at(candidate, size) {
+            return Ok(range);
+         }
+      }
+
+      // Step below the start of this region
+      candidate = match (region_start.min(candidate)).checked_sub(1) {
+         Some(below) => below / GRANULARITY * GRANULARITY,
+         None        => break,
+      };
+   }
+
+   return Err(crate::memory::MemoryError::new(
+      crate::memory::MemoryErrorKind::Unknown,
+      address..address + size,
+   ));
+}
+
+fn query_basic_information(
+   address : usize,
+) -> Option<MEMORY_BASIC_INFORMATION> {
+   let mut info = MEMORY_BASIC_INFORMATION{
+      BaseAddress       : 0 as LPVOID,
+      AllocationBase    : 0 as LPVOID,
+      AllocationProtect : 0,
+      RegionSize        : 0,
+      State             : 0,
+      Protect           : 0,
+      Type              : 0,
+   };
+   if unsafe{VirtualQuery(
+      address as LPVOID,
+      & mut info,
+      std::mem::size_of::<MEMORY_BASIC_INFORMATION>() as SIZE_T,
+   )} == 0 || info.RegionSize == 0 {
+      return None;
+   }
+
+   return Some(info);
+}
+
+fn try_allocate_at(
+   address  : usize,
+   size     : usize,
+) -> Option<std::ops::Range<usize>> {
+   let base = unsafe{VirtualAlloc(
+      address as LPVOID,
+      size as SIZE_T,
+      MEM_COMMIT | MEM_RESERVE,
+      PAGE_EXECUTE_READWRITE,
+   )} as usize;
+
+   if base == 0 {
+      return None;
+   }
+
+   return Some(base..base + size);
+}
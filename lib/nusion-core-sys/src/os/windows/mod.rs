@@ -3,10 +3,23 @@
 // OS API public re-export
 pub use winapi as osapi;
 
+// Internal modules
+mod image;
+
 // Public modules
+pub mod clipboard;
 pub mod console;
+pub mod debug;
 pub mod entry;
 pub mod environment;
+pub mod exception;
+pub mod input;
+pub mod instance;
 pub mod memory;
+#[cfg(feature = "overlay")]
+pub mod overlay;
 pub mod process;
+pub mod screenshot;
+pub mod thread;
+pub mod window;
 
@@ -0,0 +1,1225 @@
+//! crate::os::overlay implementation for Windows.
+
+use crate::overlay::{
+   FontMetrics,
+   OverlayError,
+   Result,
+   Vertex,
+   FONT_ATLAS_COLUMNS,
+   FONT_FIRST_CHARACTER,
+   FONT_LAST_CHARACTER,
+};
+
+use winapi::{
+   Interface,
+   ctypes::{
+      c_void,
+   },
+   shared::{
+      dxgi::{
+         IDXGISwapChain,
+         DXGI_SWAP_CHAIN_DESC,
+         DXGI_SWAP_EFFECT_DISCARD,
+      },
+      dxgi1_4::{
+         IDXGISwapChain3,
+      },
+      dxgiformat::{
+         DXGI_FORMAT_R8_UNORM,
+         DXGI_FORMAT_R8G8B8A8_UNORM,
+         DXGI_FORMAT_R32G32_FLOAT,
+      },
+      dxgitype::{
+         DXGI_USAGE_RENDER_TARGET_OUTPUT,
+      },
+      minwindef::{
+         FALSE,
+         FARPROC,
+         TRUE,
+         UINT,
+      },
+      windef::{
+         HWND,
+      },
+      winerror::{
+         FAILED,
+      },
+   },
+   um::{
+      d3d11::{
+         D3D11CreateDeviceAndSwapChain,
+         ID3D11BlendState,
+         ID3D11Buffer,
+         ID3D11ClassInstance,
+         ID3D11DepthStencilState,
+         ID3D11DepthStencilView,
+         ID3D11Device,
+         ID3D11DeviceContext,
+         ID3D11InputLayout,
+         ID3D11PixelShader,
+         ID3D11RasterizerState,
+         ID3D11RenderTargetView,
+         ID3D11Resource,
+         ID3D11SamplerState,
+         ID3D11ShaderResourceView,
+         ID3D11Texture2D,
+         ID3D11VertexShader,
+         D3D11_BIND_CONSTANT_BUFFER,
+         D3D11_BIND_RENDER_TARGET,
+         D3D11_BIND_SHADER_RESOURCE,
+         D3D11_BIND_VERTEX_BUFFER,
+         D3D11_BLEND_DESC,
+         D3D11_BLEND_INV_SRC_ALPHA,
+         D3D11_BLEND_ONE,
+         D3D11_BLEND_OP_ADD,
+         D3D11_BLEND_SRC_ALPHA,
+         D3D11_BUFFER_DESC,
+         D3D11_COLOR_WRITE_ENABLE_ALL,
+         D3D11_COMPARISON_ALWAYS,
+         D3D11_CPU_ACCESS_WRITE,
+         D3D11_CULL_NONE,
+         D3D11_FILL_SOLID,
+         D3D11_FILTER_MIN_MAG_MIP_POINT,
+         D3D11_FLOAT32_MAX,
+         D3D11_INPUT_ELEMENT_DESC,
+         D3D11_INPUT_PER_VERTEX_DATA,
+         D3D11_MAPPED_SUBRESOURCE,
+         D3D11_MAP_WRITE_DISCARD,
+         D3D11_RASTERIZER_DESC,
+         D3D11_SAMPLER_DESC,
+         D3D11_SDK_VERSION,
+         D3D11_SUBRESOURCE_DATA,
+         D3D11_TEXTURE2D_DESC,
+         D3D11_TEXTURE_ADDRESS_CLAMP,
+         D3D11_USAGE_DYNAMIC,
+         D3D11_USAGE_IMMUTABLE,
+         D3D11_VIEWPORT,
+         D3D11_VIEWPORT_AND_SCISSORRECT_OBJECT_COUNT_PER_PIPELINE,
+      },
+      d3d11on12::{
+         ID3D11On12Device,
+         D3D11_RESOURCE_FLAGS,
+         PFN_D3D11ON12_CREATE_DEVICE,
+      },
+      d3d12::{
+         ID3D12CommandQueue,
+         ID3D12Device,
+         ID3D12Resource,
+         D3D12_COMMAND_LIST_TYPE_DIRECT,
+         D3D12_COMMAND_QUEUE_DESC,
+         D3D12_COMMAND_QUEUE_FLAG_NONE,
+         D3D12_RESOURCE_STATE_PRESENT,
+         PFN_D3D12_CREATE_DEVICE,
+      },
+      d3dcommon::{
+         ID3DBlob,
+         D3D_DRIVER_TYPE_HARDWARE,
+         D3D_FEATURE_LEVEL_11_0,
+         D3D_PRIMITIVE_TOPOLOGY,
+         D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
+      },
+      d3dcompiler::{
+         D3DCompile,
+      },
+      libloaderapi::{
+         GetModuleHandleA,
+         GetProcAddress,
+         LoadLibraryA,
+      },
+      unknwnbase::{
+         IUnknown,
+      },
+      wingdi::{
+         CreateCompatibleDC,
+         CreateDIBSection,
+         CreateFontA,
+         DeleteDC,
+         DeleteObject,
+         GdiFlush,
+         GetTextMetricsA,
+         SelectObject,
+         SetBkColor,
+         SetBkMode,
+         SetTextColor,
+         TextOutA,
+         ANSI_CHARSET,
+         BITMAPINFO,
+         BITMAPINFOHEADER,
+         BI_RGB,
+         CLIP_DEFAULT_PRECIS,
+         DIB_RGB_COLORS,
+         FF_MODERN,
+         FIXED_PITCH,
+         FW_NORMAL,
+         NONANTIALIASED_QUALITY,
+         OPAQUE,
+         OUT_DEFAULT_PRECIS,
+         RGB,
+         TEXTMETRICA,
+      },
+      winnt::{
+         LPCSTR,
+      },
+      winuser::{
+         CreateWindowExA,
+         DestroyWindow,
+         WS_OVERLAPPEDWINDOW,
+      },
+   },
+};
+
+// Shaders which transform pixel positions
+// to clip space and tint the font atlas,
+// whose solid cell draws plain shapes
+const SHADER_SOURCE : &str = "
+cbuffer Screen : register(b0) {
+   float2 screen_size;
+   float2 padding;
+};
+
+struct VertexInput {
+   float2 position   : POSITION;
+   float2 uv         : TEXCOORD0;
+   float4 color      : COLOR0;
+};
+
+struct PixelInput {
+   float4 position   : SV_POSITION;
+   float2 uv         : TEXCOORD0;
+   float4 color      : COLOR0;
+};
+
+Texture2D      font           : register(t0);
+SamplerState   font_sampler   : register(s0);
+
+PixelInput vs_main(VertexInput input) {
+   PixelInput output;
+   output.position = float4(
+      input.position.x / screen_size.x * 2.0 - 1.0,
+      1.0 - input.position.y / screen_size.y * 2.0,
+      0.0,
+      1.0
+   );
+   output.uv      = input.uv;
+   output.color   = input.color;
+   return output;
+}
+
+float4 ps_main(PixelInput input) : SV_TARGET {
+   return input.color * float4(1.0, 1.0, 1.0, font.Sample(font_sampler, input.uv).r);
+}
+";
+
+// Height of the font in pixels
+const FONT_HEIGHT : i32
+   = 16;
+
+// Vertices allocated when the vertex
+// buffer is first created
+const VERTEX_CAPACITY_MINIMUM : usize
+   = 4096;
+
+// Size of the constant buffer, which
+// must be a multiple of 16 bytes
+const CONSTANT_BUFFER_SIZE : UINT
+   = 16;
+
+pub struct Renderer {
+   device            : * mut ID3D11Device,
+   context           : * mut ID3D11DeviceContext,
+   device_on_12      : * mut ID3D11On12Device,
+   vertex_shader     : * mut ID3D11VertexShader,
+   pixel_shader      : * mut ID3D11PixelShader,
+   input_layout      : * mut ID3D11InputLayout,
+   constant_buffer   : * mut ID3D11Buffer,
+   vertex_buffer     : * mut ID3D11Buffer,
+   vertex_capacity   : usize,
+   blend_state       : * mut ID3D11BlendState,
+   rasterizer_state  : * mut ID3D11RasterizerState,
+   sampler_state     : * mut ID3D11SamplerState,
+   font_view         : * mut ID3D11ShaderResourceView,
+   font_metrics      : FontMetrics,
+   render_targets    : Vec<RenderTarget>,
+   screen_size       : (f32, f32),
+}
+
+// A view of one of the swap chain's
+// buffers.  Direct3D 12 buffers are
+// wrapped as Direct3D 11 resources,
+// which are kept to be acquired before
+// every frame drawn to them.
+#[derive(Clone, Copy)]
+struct RenderTarget {
+   wrapped  : * mut ID3D11Resource,
+   view     : * mut ID3D11RenderTargetView,
+}
+
+// The renderer is only used from the
+// thread presenting frames while the
+// overlay's lock is held
+unsafe impl Send for Renderer {}
+
+// The game's pipeline state which is
+// overwritten while drawing the overlay
+struct StateBackup {
+   render_target     : * mut ID3D11RenderTargetView,
+   depth_stencil     : * mut ID3D11DepthStencilView,
+   viewport_count    : UINT,
+   viewports         : [D3D11_VIEWPORT; D3D11_VIEWPORT_AND_SCISSORRECT_OBJECT_COUNT_PER_PIPELINE as usize],
+   blend_state       : * mut ID3D11BlendState,
+   blend_factor      : [f32; 4],
+   sample_mask       : UINT,
+   depth_state       : * mut ID3D11DepthStencilState,
+   stencil_ref       : UINT,
+   rasterizer_state  : * mut ID3D11RasterizerState,
+   input_layout      : * mut ID3D11InputLayout,
+   topology          : D3D_PRIMITIVE_TOPOLOGY,
+   vertex_buffer     : * mut ID3D11Buffer,
+   vertex_stride     : UINT,
+   vertex_offset     : UINT,
+   vertex_shader     : * mut ID3D11VertexShader,
+   pixel_shader      : * mut ID3D11PixelShader,
+   constant_buffer   : * mut ID3D11Buffer,
+   shader_resource   : * mut ID3D11ShaderResourceView,
+   sampler_state     : * mut ID3D11SamplerState,
+}
+
+// Releases a COM object if it isn't null
+unsafe fn release<T>(
+   object : * mut T,
+) {
+   if object.is_null() == false {
+      (*(object as * mut IUnknown)).Release();
+   }
+
+   return;
+}
+
+impl Renderer {
+   pub unsafe fn new(
+      swap_chain     : usize,
+      command_queue  : Option<usize>,
+   ) -> Result<Self> {
+      let swap_chain = swap_chain as * mut IDXGISwapChain;
+
+      let mut renderer = Self{
+         device            : std::ptr::null_mut(),
+         context           : std::ptr::null_mut(),
+         device_on_12      : std::ptr::null_mut(),
+         vertex_shader     : std::ptr::null_mut(),
+         pixel_shader      : std::ptr::null_mut(),
+         input_layout      : std::ptr::null_mut(),
+         constant_buffer   : std::ptr::null_mut(),
+         vertex_buffer     : std::ptr::null_mut(),
+         vertex_capacity   : 0,
+         blend_state       : std::ptr::null_mut(),
+         rasterizer_state  : std::ptr::null_mut(),
+         sampler_state     : std::ptr::null_mut(),
+         font_view         : std::ptr::null_mut(),
+         font_metrics      : FontMetrics{
+            glyph_width    : 0.0,
+            glyph_height   : 0.0,
+            atlas_width    : 0.0,
+            atlas_height   : 0.0,
+         },
+         render_targets    : Vec::new(),
+         screen_size       : (0.0, 0.0),
+      };
+
+      // Swap chains presented with Direct3D 12
+      // aren't owned by a D3D11 device, so one
+      // is created on top of the game's queue
+      let mut device : * mut ID3D11Device = std::ptr::null_mut();
+      match FAILED((*swap_chain).GetDevice(
+         &ID3D11Device::uuidof(),
+         & mut device as * mut _ as * mut * mut c_void,
+      )) {
+         false => {
+            renderer.device = device;
+            (*device).GetImmediateContext(& mut renderer.context);
+         },
+         true  => create_device_on_12(& mut renderer, swap_chain, command_queue)?,
+      }
+
+      // Anything created before a failure
+      // is released when the renderer drops
+      renderer.create_shaders()?;
+      renderer.create_states()?;
+      renderer.create_font()?;
+
+      return Ok(renderer);
+   }
+
+   pub fn font_metrics(
+      & self,
+   ) -> FontMetrics {
+      return self.font_metrics;
+   }
+
+   pub unsafe fn screen_size(
+      & mut self,
+      swap_chain : usize,
+   ) -> Result<(f32, f32)> {
+      self.create_render_target(swap_chain)?;
+      return Ok(self.screen_size);
+   }
+
+   pub unsafe fn render(
+      & mut self,
+      swap_chain  : usize,
+      vertices    : & [Vertex],
+   ) -> Result<()> {
+      if vertices.is_empty() == true {
+         return Ok(());
+      }
+
+      let index  = self.create_render_target(swap_chain)?;
+      let target = self.render_targets[index];
+      self.reserve_vertices(vertices.len())?;
+
+      let context = &*self.context;
+
+      let mut mapped = std::mem::zeroed::<D3D11_MAPPED_SUBRESOURCE>();
+      if FAILED(context.Map(
+         self.vertex_buffer as * mut ID3D11Resource,
+         0,
+         D3D11_MAP_WRITE_DISCARD,
+         0,
+         & mut mapped,
+      )) == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+      std::ptr::copy_nonoverlapping(vertices.as_ptr(), mapped.pData as * mut Vertex, vertices.len());
+      context.Unmap(self.vertex_buffer as * mut ID3D11Resource, 0);
+
+      if FAILED(context.Map(
+         self.constant_buffer as * mut ID3D11Resource,
+         0,
+         D3D11_MAP_WRITE_DISCARD,
+         0,
+         & mut mapped,
+      )) == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+      std::ptr::copy_nonoverlapping(
+         [self.screen_size.0, self.screen_size.1, 0.0, 0.0].as_ptr(),
+         mapped.pData as * mut f32,
+         4,
+      );
+      context.Unmap(self.constant_buffer as * mut ID3D11Resource, 0);
+
+      let mut wrapped = target.wrapped;
+      if wrapped.is_null() == false {
+         (*self.device_on_12).AcquireWrappedResources(& mut wrapped, 1);
+      }
+
+      let backup = StateBackup::capture(context);
+
+      let viewport = D3D11_VIEWPORT{
+         TopLeftX : 0.0,
+         TopLeftY : 0.0,
+         Width    : self.screen_size.0,
+         Height   : self.screen_size.1,
+         MinDepth : 0.0,
+         MaxDepth : 1.0,
+      };
+      let stride = std::mem::size_of::<Vertex>() as UINT;
+      let offset = 0;
+
+      context.OMSetRenderTargets(1, &target.view, std::ptr::null_mut());
+      context.OMSetBlendState(self.blend_state, &[0.0; 4], 0xFFFFFFFF);
+      context.OMSetDepthStencilState(std::ptr::null_mut(), 0);
+      context.RSSetViewports(1, &viewport);
+      context.RSSetState(self.rasterizer_state);
+      context.IASetInputLayout(self.input_layout);
+      context.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
+      context.IASetVertexBuffers(0, 1, &self.vertex_buffer, &stride, &offset);
+      context.VSSetShader(self.vertex_shader, std::ptr::null(), 0);
+      context.VSSetConstantBuffers(0, 1, &self.constant_buffer);
+      context.PSSetShader(self.pixel_shader, std::ptr::null(), 0);
+      context.PSSetShaderResources(0, 1, &self.font_view);
+      context.PSSetSamplers(0, 1, &self.sampler_state);
+
+      context.Draw(vertices.len() as UINT, 0);
+
+      backup.restore(context);
+
+      // The drawing is submitted to the game's
+      // queue so it lands before the present
+      if wrapped.is_null() == false {
+         (*self.device_on_12).ReleaseWrappedResources(& mut wrapped, 1);
+         context.Flush();
+      }
+
+      return Ok(());
+   }
+
+   pub fn release_targets(
+      & mut self,
+   ) {
+      unsafe{
+         for target in self.render_targets.drain(..) {
+            release(target.view);
+            release(target.wrapped);
+         }
+
+         // Wrapped buffers are only let go of
+         // once the device's work is submitted
+         if self.device_on_12.is_null() == false {
+            (*self.context).Flush();
+         }
+      }
+
+      return;
+   }
+
+   unsafe fn create_shaders(
+      & mut self,
+   ) -> Result<()> {
+      let vertex_code = compile_shader(b"vs_main\0", b"vs_4_0\0")?;
+      let pixel_code  = compile_shader(b"ps_main\0", b"ps_4_0\0")?;
+
+      let vertex_bytes = ((*vertex_code).GetBufferPointer(), (*vertex_code).GetBufferSize());
+      let pixel_bytes  = ((*pixel_code).GetBufferPointer(), (*pixel_code).GetBufferSize());
+
+      let layout = [
+         input_element(b"POSITION\0", DXGI_FORMAT_R32G32_FLOAT,   0),
+         input_element(b"TEXCOORD\0", DXGI_FORMAT_R32G32_FLOAT,   8),
+         input_element(b"COLOR\0",    DXGI_FORMAT_R8G8B8A8_UNORM, 16),
+      ];
+
+      let device = &*self.device;
+      let failed =
+         FAILED(device.CreateVertexShader(
+            vertex_bytes.0, vertex_bytes.1, std::ptr::null_mut(), & mut self.vertex_shader,
+         )) == true ||
+         FAILED(device.CreatePixelShader(
+            pixel_bytes.0, pixel_bytes.1, std::ptr::null_mut(), & mut self.pixel_shader,
+         )) == true ||
+         FAILED(device.CreateInputLayout(
+            layout.as_ptr(), layout.len() as UINT, vertex_bytes.0, vertex_bytes.1, & mut self.input_layout,
+         )) == true;
+
+      release(vertex_code);
+      release(pixel_code);
+
+      if failed == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      return Ok(());
+   }
+
+   unsafe fn create_states(
+      & mut self,
+   ) -> Result<()> {
+      let device = &*self.device;
+
+      let constant_desc = D3D11_BUFFER_DESC{
+         ByteWidth            : CONSTANT_BUFFER_SIZE,
+         Usage                : D3D11_USAGE_DYNAMIC,
+         BindFlags            : D3D11_BIND_CONSTANT_BUFFER,
+         CPUAccessFlags       : D3D11_CPU_ACCESS_WRITE,
+         MiscFlags            : 0,
+         StructureByteStride  : 0,
+      };
+
+      let mut blend_desc = std::mem::zeroed::<D3D11_BLEND_DESC>();
+      blend_desc.RenderTarget[0].BlendEnable             = TRUE;
+      blend_desc.RenderTarget[0].SrcBlend                = D3D11_BLEND_SRC_ALPHA;
+      blend_desc.RenderTarget[0].DestBlend               = D3D11_BLEND_INV_SRC_ALPHA;
+      blend_desc.RenderTarget[0].BlendOp                 = D3D11_BLEND_OP_ADD;
+      blend_desc.RenderTarget[0].SrcBlendAlpha           = D3D11_BLEND_ONE;
+      blend_desc.RenderTarget[0].DestBlendAlpha          = D3D11_BLEND_INV_SRC_ALPHA;
+      blend_desc.RenderTarget[0].BlendOpAlpha            = D3D11_BLEND_OP_ADD;
+      blend_desc.RenderTarget[0].RenderTargetWriteMask   = D3D11_COLOR_WRITE_ENABLE_ALL as u8;
+
+      let rasterizer_desc = D3D11_RASTERIZER_DESC{
+         FillMode                : D3D11_FILL_SOLID,
+         CullMode                : D3D11_CULL_NONE,
+         FrontCounterClockwise   : FALSE,
+         DepthBias               : 0,
+         DepthBiasClamp          : 0.0,
+         SlopeScaledDepthBias    : 0.0,
+         DepthClipEnable         : TRUE,
+         ScissorEnable           : FALSE,
+         MultisampleEnable       : FALSE,
+         AntialiasedLineEnable   : FALSE,
+      };
+
+      let sampler_desc = D3D11_SAMPLER_DESC{
+         Filter         : D3D11_FILTER_MIN_MAG_MIP_POINT,
+         AddressU       : D3D11_TEXTURE_ADDRESS_CLAMP,
+         AddressV       : D3D11_TEXTURE_ADDRESS_CLAMP,
+         AddressW       : D3D11_TEXTURE_ADDRESS_CLAMP,
+         MipLODBias     : 0.0,
+         MaxAnisotropy  : 1,
+         ComparisonFunc : D3D11_COMPARISON_ALWAYS,
+         BorderColor    : [0.0; 4],
+         MinLOD         : 0.0,
+         MaxLOD         : D3D11_FLOAT32_MAX,
+      };
+
+      if FAILED(device.CreateBuffer(&constant_desc, std::ptr::null(), & mut self.constant_buffer)) == true
+         || FAILED(device.CreateBlendState(&blend_desc, & mut self.blend_state)) == true
+         || FAILED(device.CreateRasterizerState(&rasterizer_desc, & mut self.rasterizer_state)) == true
+         || FAILED(device.CreateSamplerState(&sampler_desc, & mut self.sampler_state)) == true
+      {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      return Ok(());
+   }
+
+   unsafe fn create_font(
+      & mut self,
+   ) -> Result<()> {
+      let (pixels, metrics) = render_font_atlas()?;
+
+      let texture_desc = D3D11_TEXTURE2D_DESC{
+         Width          : metrics.atlas_width as UINT,
+         Height         : metrics.atlas_height as UINT,
+         MipLevels      : 1,
+         ArraySize      : 1,
+         Format         : DXGI_FORMAT_R8_UNORM,
+         SampleDesc     : winapi::shared::dxgitype::DXGI_SAMPLE_DESC{
+            Count    : 1,
+            Quality  : 0,
+         },
+         Usage          : D3D11_USAGE_IMMUTABLE,
+         BindFlags      : D3D11_BIND_SHADER_RESOURCE,
+         CPUAccessFlags : 0,
+         MiscFlags      : 0,
+      };
+      let texture_data = D3D11_SUBRESOURCE_DATA{
+         pSysMem           : pixels.as_ptr() as * const c_void,
+         SysMemPitch       : metrics.atlas_width as UINT,
+         SysMemSlicePitch  : 0,
+      };
+
+      let device = &*self.device;
+
+      let mut texture : * mut ID3D11Texture2D = std::ptr::null_mut();
+      if FAILED(device.CreateTexture2D(&texture_desc, &texture_data, & mut texture)) == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      let failed = FAILED(device.CreateShaderResourceView(
+         texture as * mut ID3D11Resource,
+         std::ptr::null(),
+         & mut self.font_view,
+      ));
+      release(texture);
+
+      if failed == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      self.font_metrics = metrics;
+      return Ok(());
+   }
+
+   // Creates the view of the buffer being
+   // presented if it doesn't exist yet,
+   // returning its index
+   unsafe fn create_render_target(
+      & mut self,
+      swap_chain : usize,
+   ) -> Result<usize> {
+      let swap_chain = swap_chain as * mut IDXGISwapChain;
+
+      let index = self.current_buffer(swap_chain)?;
+      if let Some(target) = self.render_targets.get(index) {
+         if target.view.is_null() == false {
+            return Ok(index);
+         }
+      }
+
+      let back_buffer = match self.device_on_12.is_null() {
+         true  => get_buffer(swap_chain)?,
+         false => self.wrap_buffer(swap_chain, index)?,
+      };
+
+      let mut desc = std::mem::zeroed::<D3D11_TEXTURE2D_DESC>();
+      (*back_buffer).GetDesc(& mut desc);
+
+      let mut view : * mut ID3D11RenderTargetView = std::ptr::null_mut();
+      if FAILED((*self.device).CreateRenderTargetView(
+         back_buffer as * mut ID3D11Resource,
+         std::ptr::null(),
+         & mut view,
+      )) == true {
+         release(back_buffer);
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      let wrapped = match self.device_on_12.is_null() {
+         true  => {
+            release(back_buffer);
+            std::ptr::null_mut()
+         },
+         false => back_buffer as * mut ID3D11Resource,
+      };
+
+      if self.render_targets.len() <= index {
+         self.render_targets.resize(index + 1, RenderTarget{
+            wrapped  : std::ptr::null_mut(),
+            view     : std::ptr::null_mut(),
+         });
+      }
+      self.render_targets[index] = RenderTarget{
+         wrapped  : wrapped,
+         view     : view,
+      };
+
+      self.screen_size = (desc.Width as f32, desc.Height as f32);
+      return Ok(index);
+   }
+
+   // Direct3D 11 always draws to the first
+   // buffer, while Direct3D 12 rotates
+   // through all of them
+   unsafe fn current_buffer(
+      & self,
+      swap_chain : * mut IDXGISwapChain,
+   ) -> Result<usize> {
+      if self.device_on_12.is_null() == true {
+         return Ok(0);
+      }
+
+      let mut swap_chain_3 : * mut IDXGISwapChain3 = std::ptr::null_mut();
+      if FAILED((*swap_chain).QueryInterface(
+         &IDXGISwapChain3::uuidof(),
+         & mut swap_chain_3 as * mut _ as * mut * mut c_void,
+      )) == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      let index = (*swap_chain_3).GetCurrentBackBufferIndex();
+      release(swap_chain_3);
+
+      return Ok(index as usize);
+   }
+
+   // The game leaves the buffer ready to be
+   // presented, so it's drawn to and handed
+   // back in that state
+   unsafe fn wrap_buffer(
+      & self,
+      swap_chain  : * mut IDXGISwapChain,
+      index       : usize,
+   ) -> Result<* mut ID3D11Texture2D> {
+      let mut buffer : * mut ID3D12Resource = std::ptr::null_mut();
+      if FAILED((*swap_chain).GetBuffer(
+         index as UINT,
+         &ID3D12Resource::uuidof(),
+         & mut buffer as * mut _ as * mut * mut c_void,
+      )) == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      let flags = D3D11_RESOURCE_FLAGS{
+         BindFlags            : D3D11_BIND_RENDER_TARGET,
+         MiscFlags            : 0,
+         CPUAccessFlags       : 0,
+         StructureByteStride  : 0,
+      };
+
+      let mut wrapped : * mut ID3D11Texture2D = std::ptr::null_mut();
+      let failed = FAILED((*self.device_on_12).CreateWrappedResource(
+         buffer as * mut IUnknown,
+         &flags,
+         D3D12_RESOURCE_STATE_PRESENT,
+         D3D12_RESOURCE_STATE_PRESENT,
+         &ID3D11Texture2D::uuidof(),
+         & mut wrapped as * mut _ as * mut * mut c_void,
+      ));
+      release(buffer);
+
+      if failed == true {
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      return Ok(wrapped);
+   }
+
+   unsafe fn reserve_vertices(
+      & mut self,
+      count : usize,
+   ) -> Result<()> {
+      if count <= self.vertex_capacity {
+         return Ok(());
+      }
+
+      release(self.vertex_buffer);
+      self.vertex_buffer   = std::ptr::null_mut();
+      self.vertex_capacity = 0;
+
+      let capacity = usize::max(count.next_power_of_two(), VERTEX_CAPACITY_MINIMUM);
+      let desc = D3D11_BUFFER_DESC{
+         ByteWidth            : (capacity * std::mem::size_of::<Vertex>()) as UINT,
+         Usage                : D3D11_USAGE_DYNAMIC,
+         BindFlags            : D3D11_BIND_VERTEX_BUFFER,
+         CPUAccessFlags       : D3D11_CPU_ACCESS_WRITE,
+         MiscFlags            : 0,
+         StructureByteStride  : 0,
+      };
+
+      if FAILED((*self.device).CreateBuffer(&desc, std::ptr::null(), & mut self.vertex_buffer)) == true {
+         self.vertex_buffer = std::ptr::null_mut();
+         return Err(OverlayError::ResourceCreationFailed);
+      }
+
+      self.vertex_capacity = capacity;
+      return Ok(());
+   }
+}
+
+impl Drop for Renderer {
+   fn drop(
+      & mut self,
+   ) {
+      self.release_targets();
+
+      unsafe{
+         release(self.font_view);
+         release(self.sampler_state);
+         release(self.rasterizer_state);
+         release(self.blend_state);
+         release(self.vertex_buffer);
+         release(self.constant_buffer);
+         release(self.input_layout);
+         release(self.pixel_shader);
+         release(self.vertex_shader);
+         release(self.device_on_12);
+         release(self.context);
+         release(self.device);
+      }
+
+      return;
+   }
+}
+
+impl StateBackup {
+   unsafe fn capture(
+      context : & ID3D11DeviceContext,
+   ) -> Self {
+      let mut backup = std::mem::zeroed::<Self>();
+
+      backup.viewport_count = backup.viewports.len() as UINT;
+
+      context.OMGetRenderTargets(1, & mut backup.render_target, & mut backup.depth_stencil);
+      context.OMGetBlendState(& mut backup.blend_state, & mut backup.blend_factor, & mut backup.sample_mask);
+      context.OMGetDepthStencilState(& mut backup.depth_state, & mut backup.stencil_ref);
+      context.RSGetViewports(& mut backup.viewport_count, backup.viewports.as_mut_ptr());
+      context.RSGetState(& mut backup.rasterizer_state);
+      context.IAGetInputLayout(& mut backup.input_layout);
+      context.IAGetPrimitiveTopology(& mut backup.topology);
+      context.IAGetVertexBuffers(0, 1, & mut backup.vertex_buffer, & mut backup.vertex_stride, & mut backup.vertex_offset);
+      context.VSGetShader(& mut backup.vertex_shader, std::ptr::null_mut::<* mut ID3D11ClassInstance>(), std::ptr::null_mut());
+      context.VSGetConstantBuffers(0, 1, & mut backup.constant_buffer);
+      context.PSGetShader(& mut backup.pixel_shader, std::ptr::null_mut::<* mut ID3D11ClassInstance>(), std::ptr::null_mut());
+      context.PSGetShaderResources(0, 1, & mut backup.shader_resource);
+      context.PSGetSamplers(0, 1, & mut backup.sampler_state);
+
+      return backup;
+   }
+
+   unsafe fn restore(
+      self,
+      context : & ID3D11DeviceContext,
+   ) {
+      context.OMSetRenderTargets(1, &self.render_target, self.depth_stencil);
+      context.OMSetBlendState(self.blend_state, &self.blend_factor, self.sample_mask);
+      context.OMSetDepthStencilState(self.depth_state, self.stencil_ref);
+      context.RSSetViewports(self.viewport_count, self.viewports.as_ptr());
+      context.RSSetState(self.rasterizer_state);
+      context.IASetInputLayout(self.input_layout);
+      context.IASetPrimitiveTopology(self.topology);
+      context.IASetVertexBuffers(0, 1, &self.vertex_buffer, &self.vertex_stride, &self.vertex_offset);
+      context.VSSetShader(self.vertex_shader, std::ptr::null(), 0);
+      context.VSSetConstantBuffers(0, 1, &self.constant_buffer);
+      context.PSSetShader(self.pixel_shader, std::ptr::null(), 0);
+      context.PSSetShaderResources(0, 1, &self.shader_resource);
+      context.PSSetSamplers(0, 1, &self.sampler_state);
+
+      // Every getter above added a reference
+      release(self.render_target);
+      release(self.depth_stencil);
+      release(self.blend_state);
+      release(self.depth_state);
+      release(self.rasterizer_state);
+      release(self.input_layout);
+      release(self.vertex_buffer);
+      release(self.vertex_shader);
+      release(self.constant_buffer);
+      release(self.pixel_shader);
+      release(self.shader_resource);
+      release(self.sampler_state);
+
+      return;
+   }
+}
+
+unsafe fn get_buffer(
+   swap_chain : * mut IDXGISwapChain,
+) -> Result<* mut ID3D11Texture2D> {
+   let mut buffer : * mut ID3D11Texture2D = std::ptr::null_mut();
+   if FAILED((*swap_chain).GetBuffer(
+      0,
+      &ID3D11Texture2D::uuidof(),
+      & mut buffer as * mut _ as * mut * mut c_void,
+   )) == true {
+      return Err(OverlayError::ResourceCreationFailed);
+   }
+
+   return Ok(buffer);
+}
+
+// Creates the renderer's Direct3D 11 device
+// on top of the game's Direct3D 12 command
+// queue, which its work is submitted to
+unsafe fn create_device_on_12(
+   renderer       : & mut Renderer,
+   swap_chain     : * mut IDXGISwapChain,
+   command_queue  : Option<usize>,
+) -> Result<()> {
+   let mut device_12 : * mut ID3D12Device = std::ptr::null_mut();
+   if FAILED((*swap_chain).GetDevice(
+      &ID3D12Device::uuidof(),
+      & mut device_12 as * mut _ as * mut * mut c_void,
+   )) == true {
+      return Err(OverlayError::UnsupportedGraphicsApi);
+   }
+
+   // A queue from another device, such as
+   // one used for video playback, can't
+   // draw to the swap chain's buffers
+   let mut queue_device : * mut ID3D12Device = std::ptr::null_mut();
+   let command_queue = match command_queue {
+      Some(command_queue)  => command_queue as * mut ID3D12CommandQueue,
+      None                 => std::ptr::null_mut(),
+   };
+   let found = command_queue.is_null() == false && FAILED((*command_queue).GetDevice(
+      &ID3D12Device::uuidof(),
+      & mut queue_device as * mut _ as * mut * mut c_void,
+   )) == false && queue_device == device_12;
+   release(queue_device);
+
+   if found == false {
+      release(device_12);
+      return Err(OverlayError::CommandQueueNotFound);
+   }
+
+   // Looked up at runtime since versions of
+   // Windows without Direct3D 12 don't have it
+   let create = std::mem::transmute::<FARPROC, PFN_D3D11ON12_CREATE_DEVICE>(GetProcAddress(
+      GetModuleHandleA(b"d3d11.dll\0".as_ptr() as LPCSTR),
+      b"D3D11On12CreateDevice\0".as_ptr() as LPCSTR,
+   ));
+   let create = match create {
+      Some(create)   => create,
+      None           => {
+         release(device_12);
+         return Err(OverlayError::UnsupportedGraphicsApi);
+      },
+   };
+
+   // Anything created before a failure
+   // is released when the renderer drops
+   let mut queue = command_queue as * mut IUnknown;
+   let result = create(
+      device_12 as * mut IUnknown,
+      0,
+      std::ptr::null(),
+      0,
+      & mut queue,
+      1,
+      0,
+      & mut renderer.device,
+      & mut renderer.context,
+      std::ptr::null_mut(),
+   );
+   release(device_12);
+
+   if FAILED(result) == true || FAILED((*renderer.device).QueryInterface(
+      &ID3D11On12Device::uuidof(),
+      & mut renderer.device_on_12 as * mut _ as * mut * mut c_void,
+   )) == true {
+      return Err(OverlayError::DeviceCreationFailed);
+   }
+
+   return Ok(());
+}
+
+unsafe fn compile_shader(
+   entrypoint  : & [u8],
+   target      : & [u8],
+) -> Result<* mut ID3DBlob> {
+   let mut code   : * mut ID3DBlob = std::ptr::null_mut();
+   let mut errors : * mut ID3DBlob = std::ptr::null_mut();
+
+   let result = D3DCompile(
+      SHADER_SOURCE.as_ptr() as * const c_void,
+      SHADER_SOURCE.len(),
+      std::ptr::null(),
+      std::ptr::null(),
+      std::ptr::null_mut(),
+      entrypoint.as_ptr() as LPCSTR,
+      target.as_ptr() as LPCSTR,
+      0,
+      0,
+      & mut code,
+      & mut errors,
+   );
+   release(errors);
+
+   if FAILED(result) == true {
+      release(code);
+      return Err(OverlayError::ShaderCompilationFailed);
+   }
+
+   return Ok(code);
+}
+
+fn input_element(
+   semantic_name  : &'static [u8],
+   format         : winapi::shared::dxgiformat::DXGI_FORMAT,
+   offset         : UINT,
+) -> D3D11_INPUT_ELEMENT_DESC {
+   return D3D11_INPUT_ELEMENT_DESC{
+      SemanticName         : semantic_name.as_ptr() as LPCSTR,
+      SemanticIndex        : 0,
+      Format               : format,
+      InputSlot            : 0,
+      AlignedByteOffset    : offset,
+      InputSlotClass       : D3D11_INPUT_PER_VERTEX_DATA,
+      InstanceDataStepRate : 0,
+   };
+}
+
+// Draws every printable ASCII character into
+// a grid with GDI, followed by a solid cell,
+// and returns the grid as 8-bit coverage
+unsafe fn render_font_atlas(
+) -> Result<(Vec<u8>, FontMetrics)> {
+   let glyph_count   = FONT_LAST_CHARACTER as usize - FONT_FIRST_CHARACTER as usize + 1;
+   let cell_count    = glyph_count + 1;
+   let rows          = cell_count.div_ceil(FONT_ATLAS_COLUMNS);
+
+   let dc = CreateCompatibleDC(std::ptr::null_mut());
+   if dc.is_null() == true {
+      return Err(OverlayError::FontCreationFailed);
+   }
+
+   let font = CreateFontA(
+      FONT_HEIGHT,
+      0,
+      0,
+      0,
+      FW_NORMAL,
+      FALSE as u32,
+      FALSE as u32,
+      FALSE as u32,
+      ANSI_CHARSET,
+      OUT_DEFAULT_PRECIS,
+      CLIP_DEFAULT_PRECIS,
+      NONANTIALIASED_QUALITY,
+      FIXED_PITCH | FF_MODERN,
+      b"Consolas\0".as_ptr() as LPCSTR,
+   );
+   if font.is_null() == true {
+      DeleteDC(dc);
+      return Err(OverlayError::FontCreationFailed);
+   }
+   SelectObject(dc, font as * mut _);
+
+   let mut text_metrics = std::mem::zeroed::<TEXTMETRICA>();
+   GetTextMetricsA(dc, & mut text_metrics);
+   let glyph_width   = usize::max(text_metrics.tmAveCharWidth as usize, 1);
+   let glyph_height  = usize::max(text_metrics.tmHeight as usize, 1);
+   let atlas_width   = glyph_width  * FONT_ATLAS_COLUMNS;
+   let atlas_height  = glyph_height * rows;
+
+   let mut info = std::mem::zeroed::<BITMAPINFO>();
+   info.bmiHeader.biSize         = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+   info.bmiHeader.biWidth        = atlas_width as i32;
+   info.bmiHeader.biHeight       = -(atlas_height as i32);
+   info.bmiHeader.biPlanes       = 1;
+   info.bmiHeader.biBitCount     = 32;
+   info.bmiHeader.biCompression  = BI_RGB;
+
+   let mut bits : * mut c_void = std::ptr::null_mut();
+   let bitmap = CreateDIBSection(dc, &info, DIB_RGB_COLORS, & mut bits, std::ptr::null_mut(), 0);
+   if bitmap.is_null() == true || bits.is_null() == true {
+      DeleteObject(font as * mut _);
+      DeleteDC(dc);
+      return Err(OverlayError::FontCreationFailed);
+   }
+   SelectObject(dc, bitmap as * mut _);
+
+   SetTextColor(dc, RGB(255, 255, 255));
+   SetBkColor(dc, RGB(0, 0, 0));
+   SetBkMode(dc, OPAQUE as i32);
+
+   for index in 0..glyph_count {
+      let character = FONT_FIRST_CHARACTER as u8 + index as u8;
+      TextOutA(
+         dc,
+         ((index % FONT_ATLAS_COLUMNS) * glyph_width) as i32,
+         ((index / FONT_ATLAS_COLUMNS) * glyph_height) as i32,
+         &character as * const u8 as LPCSTR,
+         1,
+      );
+   }
+   GdiFlush();
+
+   // The text is white, so any channel of
+   // the BGRA pixels is the coverage
+   let bgra = std::slice::from_raw_parts(bits as * const u8, atlas_width * atlas_height * 4);
+   let mut pixels = bgra.chunks_exact(4).map(|pixel| pixel[1]).collect::<Vec<u8>>();
+
+   let solid_x = (glyph_count % FONT_ATLAS_COLUMNS) * glyph_width;
+   let solid_y = (glyph_count / FONT_ATLAS_COLUMNS) * glyph_height;
+   for y in solid_y..solid_y + glyph_height {
+      pixels[y * atlas_width + solid_x..y * atlas_width + solid_x + glyph_width].fill(u8::MAX);
+   }
+
+   DeleteObject(bitmap as * mut _);
+   DeleteObject(font as * mut _);
+   DeleteDC(dc);
+
+   return Ok((pixels, FontMetrics{
+      glyph_width    : glyph_width  as f32,
+      glyph_height   : glyph_height as f32,
+      atlas_width    : atlas_width  as f32,
+      atlas_height   : atlas_height as f32,
+   }));
+}
+
+pub fn swap_chain_vtable(
+) -> Result<usize> {
+   let window : HWND = unsafe{CreateWindowExA(
+      0,
+      b"STATIC\0".as_ptr() as LPCSTR,
+      b"\0".as_ptr() as LPCSTR,
+      WS_OVERLAPPEDWINDOW,
+      0,
+      0,
+      8,
+      8,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      GetModuleHandleA(std::ptr::null()),
+      std::ptr::null_mut(),
+   )};
+   if window.is_null() == true {
+      return Err(OverlayError::DeviceCreationFailed);
+   }
+
+   let mut desc = unsafe{std::mem::zeroed::<DXGI_SWAP_CHAIN_DESC>()};
+   desc.BufferDesc.Format  = DXGI_FORMAT_R8G8B8A8_UNORM;
+   desc.SampleDesc.Count   = 1;
+   desc.BufferUsage        = DXGI_USAGE_RENDER_TARGET_OUTPUT;
+   desc.BufferCount        = 1;
+   desc.OutputWindow       = window;
+   desc.Windowed           = TRUE;
+   desc.SwapEffect         = DXGI_SWAP_EFFECT_DISCARD;
+
+   let mut swap_chain   : * mut IDXGISwapChain        = std::ptr::null_mut();
+   let mut device       : * mut ID3D11Device          = std::ptr::null_mut();
+   let mut context      : * mut ID3D11DeviceContext   = std::ptr::null_mut();
+   let result = unsafe{D3D11CreateDeviceAndSwapChain(
+      std::ptr::null_mut(),
+      D3D_DRIVER_TYPE_HARDWARE,
+      std::ptr::null_mut(),
+      0,
+      std::ptr::null(),
+      0,
+      D3D11_SDK_VERSION,
+      &desc,
+      & mut swap_chain,
+      & mut device,
+      std::ptr::null_mut(),
+      & mut context,
+   )};
+
+   // The table belongs to the DXGI library,
+   // so it outlives the temporary swap chain
+   let vtable = match FAILED(result) == false && swap_chain.is_null() == false {
+      true  => Ok(unsafe{*(swap_chain as * const usize)}),
+      false => Err(OverlayError::DeviceCreationFailed),
+   };
+
+   unsafe{
+      release(swap_chain);
+      release(context);
+      release(device);
+      DestroyWindow(window);
+   }
+
+   return vtable;
+}
+
+pub fn command_queue_vtable(
+) -> Result<usize> {
+   // The library is loaded at runtime since
+   // older versions of Windows don't have it,
+   // and never freed since the table is in it
+   let library = unsafe{LoadLibraryA(b"d3d12.dll\0".as_ptr() as LPCSTR)};
+   if library.is_null() == true {
+      return Err(OverlayError::UnsupportedGraphicsApi);
+   }
+
+   let create = unsafe{std::mem::transmute::<FARPROC, PFN_D3D12_CREATE_DEVICE>(GetProcAddress(
+      library,
+      b"D3D12CreateDevice\0".as_ptr() as LPCSTR,
+   ))};
+   let create = match create {
+      Some(create)   => create,
+      None           => return Err(OverlayError::UnsupportedGraphicsApi),
+   };
+
+   let mut device : * mut ID3D12Device = std::ptr::null_mut();
+   if FAILED(unsafe{create(
+      std::ptr::null_mut(),
+      D3D_FEATURE_LEVEL_11_0,
+      &ID3D12Device::uuidof(),
+      & mut device as * mut _ as * mut * mut c_void,
+   )}) == true {
+      return Err(OverlayError::DeviceCreationFailed);
+   }
+
+   let desc = D3D12_COMMAND_QUEUE_DESC{
+      Type     : D3D12_COMMAND_LIST_TYPE_DIRECT,
+      Priority : 0,
+      Flags    : D3D12_COMMAND_QUEUE_FLAG_NONE,
+      NodeMask : 0,
+   };
+
+   let mut command_queue : * mut ID3D12CommandQueue = std::ptr::null_mut();
+   let result = unsafe{(*device).CreateCommandQueue(
+      &desc,
+      &ID3D12CommandQueue::uuidof(),
+      & mut command_queue as * mut _ as * mut * mut c_void,
+   )};
+
+   let vtable = match FAILED(result) == false && command_queue.is_null() == false {
+      true  => Ok(unsafe{*(command_queue as * const usize)}),
+      false => Err(OverlayError::DeviceCreationFailed),
+   };
+
+   unsafe{
+      release(command_queue);
+      release(device);
+   }
+
+   return vtable;
+}
+
+pub unsafe fn is_direct_command_queue(
+   command_queue : usize,
+) -> bool {
+   let desc = (*(command_queue as * mut ID3D12CommandQueue)).GetDesc();
+   return desc.Type == D3D12_COMMAND_LIST_TYPE_DIRECT;
+}
@@ -1,7 +1,7 @@
 //! crate::process implementations for
 //! Windows.
 
-use crate::process::{ProcessError, Result};
+use crate::process::{ProcessError, Result, Section, UnwindFunction};
 
 use winapi::{
    shared::{
@@ -9,13 +9,17 @@ use winapi::{
          ULONG_PTR,
       },
       minwindef::{
-         BYTE,
          DWORD,
+         FARPROC,
+         LPCVOID,
+         LPVOID,
          HMODULE,
          FALSE,
          MAX_PATH,
+         TRUE,
       },
       ntdef::{
+         LPCWSTR,
          LPSTR,
       },
       winerror::{
@@ -31,10 +35,44 @@ use winapi::{
          INVALID_HANDLE_VALUE,
       },
       libloaderapi::{
+         FreeLibrary,
          GetModuleFileNameA,
+         GetModuleFileNameW,
+         GetModuleHandleA,
+         GetModuleHandleExW,
+         GetProcAddress,
+         LoadLibraryA,
+         GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS,
+         GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+      },
+      memoryapi::{
+         ReadProcessMemory,
+         VirtualAllocEx,
+         VirtualFreeEx,
+         VirtualProtectEx,
+         WriteProcessMemory,
+      },
+      minwinbase::{
+         LPTHREAD_START_ROUTINE,
       },
       processthreadsapi::{
+         CreateProcessW,
+         CreateRemoteThread,
+         FlushInstructionCache,
+         GetCurrentProcess,
          GetCurrentProcessId,
+         GetExitCodeThread,
+         OpenProcess,
+         ResumeThread,
+         TerminateProcess,
+         PROCESS_INFORMATION,
+         STARTUPINFOW,
+      },
+      synchapi::{
+         CreateEventW,
+         OpenEventW,
+         SetEvent,
+         WaitForSingleObject,
       },
       tlhelp32::{
          CreateToolhelp32Snapshot,
@@ -48,9 +86,72 @@ use winapi::{
          TH32CS_SNAPMODULE,
          TH32CS_SNAPMODULE32,
       },
+      winbase::{
+         CREATE_SUSPENDED,
+         INFINITE,
+         WAIT_OBJECT_0,
+      },
+      winnt::{
+         EVENT_MODIFY_STATE,
+         HANDLE,
+         LPCSTR,
+         MEM_COMMIT,
+         MEM_RELEASE,
+         MEM_RESERVE,
+         PAGE_EXECUTE_READWRITE,
+         PAGE_READWRITE,
+         PROCESS_CREATE_THREAD,
+         PROCESS_QUERY_INFORMATION,
+         PROCESS_VM_OPERATION,
+         PROCESS_VM_READ,
+         PROCESS_VM_WRITE,
+         IMAGE_DIRECTORY_ENTRY_EXCEPTION,
+         IMAGE_DIRECTORY_ENTRY_EXPORT,
+         IMAGE_DIRECTORY_ENTRY_IAT,
+         IMAGE_DIRECTORY_ENTRY_IMPORT,
+         IMAGE_DIRECTORY_ENTRY_RESOURCE,
+         IMAGE_ORDINAL_FLAG64,
+         IMAGE_SCN_MEM_EXECUTE,
+         IMAGE_SCN_MEM_READ,
+         IMAGE_SCN_MEM_WRITE,
+      },
    },
 };
 
+// Size of a RUNTIME_FUNCTION entry, which
+// is three DWORD relative virtual addresses
+const RUNTIME_FUNCTION_SIZE : usize
+   = std::mem::size_of::<[DWORD; 3]>();
+
+// Size of an IMAGE_IMPORT_DESCRIPTOR, which
+// is five DWORD fields
+const IMPORT_DESCRIPTOR_SIZE : usize
+   = std::mem::size_of::<[DWORD; 5]>();
+
+// Size of an IMAGE_EXPORT_DIRECTORY, which
+// is ten DWORD-sized fields
+const EXPORT_DIRECTORY_SIZE : usize
+   = std::mem::size_of::<[DWORD; 10]>();
+
+// Size of an IMAGE_RESOURCE_DIRECTORY, which
+// is followed by its entries
+const RESOURCE_DIRECTORY_SIZE : usize
+   = std::mem::size_of::<[DWORD; 4]>();
+
+// Size of an IMAGE_RESOURCE_DIRECTORY_ENTRY
+const RESOURCE_ENTRY_SIZE : usize
+   = std::mem::size_of::<[DWORD; 2]>();
+
+// Resource type ID of RT_VERSION
+const RESOURCE_TYPE_VERSION : DWORD = 16;
+
+// Signature at the start of VS_FIXEDFILEINFO
+const FIXED_FILE_INFO_SIGNATURE : DWORD = 0xFEEF04BD;
+
+// Size of a 64-bit import thunk
+const IMPORT_THUNK_SIZE : usize
+   = std::mem::size_of::<u64>();
+
 const EXECUTABLE_FILE_PATH_MAX_LENGTH : DWORD
    = MAX_PATH as DWORD;
 
@@ -64,6 +165,33 @@ pub struct ModuleSnapshot {
    pub module_name   : String,
 }
 
+pub struct RemoteProcess {
+   handle : HANDLE,
+}
+
+pub struct SuspendedProcess {
+   pub process_id : DWORD,
+   remote         : RemoteProcess,
+   thread         : HANDLE,
+   ready          : HANDLE,
+   resumed        : bool,
+}
+
+// dbghelp.dll loaded for writing a minidump,
+// which is released again when dropped
+struct DbgHelp {
+   library  : HMODULE,
+   symbols  : bool,
+}
+
+// Process handles may be used from any thread
+unsafe impl Send for RemoteProcess {}
+unsafe impl Sync for RemoteProcess {}
+
+// The same goes for thread handles
+unsafe impl Send for SuspendedProcess {}
+unsafe impl Sync for SuspendedProcess {}
+
 macro_rules! try_close_handle {
    ($handle:ident, $msg:literal) => {
       if unsafe{CloseHandle($handle)} == FALSE {
@@ -72,6 +200,51 @@ macro_rules! try_close_handle {
    };
 }
 
+// Quotes an argument the way the C runtime
+// splits a command line, where backslashes
+// are only special right before a quote
+fn append_argument(
+   command_line   : & mut Vec<u16>,
+   argument       : & std::ffi::OsStr,
+) -> Result<()> {
+   use std::os::windows::ffi::OsStrExt;
+
+   const SPACE       : u16 = b' '  as u16;
+   const TAB         : u16 = b'\t' as u16;
+   const QUOTE       : u16 = b'"'  as u16;
+   const BACKSLASH   : u16 = b'\\' as u16;
+
+   let argument = argument.encode_wide().collect::<Vec<u16>>();
+   if argument.contains(&0) == true {
+      return Err(ProcessError::SpawnFailed);
+   }
+
+   let quoted = argument.is_empty() == true
+      || argument.iter().any(|unit| *unit == SPACE || *unit == TAB || *unit == QUOTE);
+   if quoted == false {
+      command_line.extend(argument);
+      return Ok(());
+   }
+
+   command_line.push(QUOTE);
+   let mut backslashes = 0;
+   for unit in argument {
+      match unit {
+         BACKSLASH   => backslashes += 1,
+         QUOTE       => {
+            command_line.extend(std::iter::repeat_n(BACKSLASH, backslashes + 1));
+            backslashes = 0;
+         },
+         _           => backslashes = 0,
+      }
+      command_line.push(unit);
+   }
+   command_line.extend(std::iter::repeat_n(BACKSLASH, backslashes));
+   command_line.push(QUOTE);
+
+   return Ok(());
+}
+
 fn cstr_to_owned_string(
    string : &[i8],
 ) -> Option<String> {
@@ -79,20 +252,317 @@ fn cstr_to_owned_string(
       string.as_ptr() as * const u8,
       string.len(),
    )};
-   
-   // Strips out null bytes if there are any
-   // This works with UTF-8, which luckily is
-   // all we care about
-   let idx_null   = string.iter().position(|e| *e == 0x00)?;
-   let string     = &string[..idx_null];
-
-   let string = string.to_vec();
-   let string = match String::from_utf8(string) {
-      Ok(s)    => s,
-      Err(_)   => return None,
-   };
 
-   return Some(string);
+   return crate::encoding::from_narrow_nul(string).ok();
+}
+
+impl RemoteProcess {
+   pub fn open(
+      process : & ProcessSnapshot,
+   ) -> Result<Self> {
+      let handle = unsafe{OpenProcess(
+         PROCESS_VM_READ | PROCESS_VM_WRITE | PROCESS_VM_OPERATION | PROCESS_QUERY_INFORMATION
+            | PROCESS_CREATE_THREAD,
+         FALSE,
+         process.process_id,
+      )};
+      if handle.is_null() == true {
+         return Err(ProcessError::AccessDenied);
+      }
+
+      return Ok(Self{
+         handle : handle,
+      });
+   }
+
+   pub fn read(
+      & self,
+      address  : usize,
+      buffer   : & mut [u8],
+   ) -> Result<()> {
+      let mut bytes_read = 0;
+      if unsafe{ReadProcessMemory(
+         self.handle,
+         address as LPCVOID,
+         buffer.as_mut_ptr() as LPVOID,
+         buffer.len(),
+         & mut bytes_read,
+      )} == FALSE || bytes_read != buffer.len() {
+         return Err(ProcessError::RemoteMemoryFailed);
+      }
+
+      return Ok(());
+   }
+
+   pub fn write(
+      & self,
+      address  : usize,
+      bytes    : & [u8],
+   ) -> Result<()> {
+      // Code pages are usually read-only, so
+      // open them up for the duration of the write
+      let mut old_protect = 0;
+      if unsafe{VirtualProtectEx(
+         self.handle,
+         address as LPVOID,
+         bytes.len(),
+         PAGE_EXECUTE_READWRITE,
+         & mut old_protect,
+      )} == FALSE {
+         return Err(ProcessError::RemoteMemoryFailed);
+      }
+
+      let mut bytes_written = 0;
+      let written = unsafe{WriteProcessMemory(
+         self.handle,
+         address as LPVOID,
+         bytes.as_ptr() as LPCVOID,
+         bytes.len(),
+         & mut bytes_written,
+      )} != FALSE && bytes_written == bytes.len();
+
+      let mut unused_protect = 0;
+      unsafe{VirtualProtectEx(
+         self.handle,
+         address as LPVOID,
+         bytes.len(),
+         old_protect,
+         & mut unused_protect,
+      )};
+      unsafe{FlushInstructionCache(
+         self.handle,
+         address as LPCVOID,
+         bytes.len(),
+      )};
+
+      if written == false {
+         return Err(ProcessError::RemoteMemoryFailed);
+      }
+
+      return Ok(());
+   }
+}
+
+impl RemoteProcess {
+   pub fn load_library(
+      & self,
+      library_path : & std::path::Path,
+   ) -> Result<()> {
+      use std::os::windows::ffi::OsStrExt;
+
+      let mut path = library_path.as_os_str().encode_wide().collect::<Vec<u16>>();
+      if path.contains(&0) == true {
+         return Err(ProcessError::InjectionFailed);
+      }
+      path.push(0);
+
+      let path_bytes = unsafe{std::slice::from_raw_parts(
+         path.as_ptr() as * const u8,
+         path.len() * std::mem::size_of::<u16>(),
+      )};
+
+      // kernel32 is mapped at the same address
+      // in every process, so the local address
+      // of LoadLibraryW is also valid remotely
+      let load_library = unsafe{GetProcAddress(
+         GetModuleHandleA(b"kernel32.dll\0".as_ptr() as LPCSTR),
+         b"LoadLibraryW\0".as_ptr() as LPCSTR,
+      )};
+      if load_library.is_null() == true {
+         return Err(ProcessError::InjectionFailed);
+      }
+
+      let remote_path = unsafe{VirtualAllocEx(
+         self.handle,
+         std::ptr::null_mut(),
+         path_bytes.len(),
+         MEM_COMMIT | MEM_RESERVE,
+         PAGE_READWRITE,
+      )};
+      if remote_path.is_null() == true {
+         return Err(ProcessError::RemoteMemoryFailed);
+      }
+
+      let result = self.write(remote_path as usize, path_bytes).and_then(|_| {
+         self.call_remote(
+            unsafe{std::mem::transmute::<FARPROC, LPTHREAD_START_ROUTINE>(load_library)},
+            remote_path,
+         )
+      });
+
+      unsafe{VirtualFreeEx(self.handle, remote_path, 0, MEM_RELEASE)};
+
+      // The exit code is the low 32 bits of the
+      // module handle, which is only zero when
+      // loading the library failed
+      return match result? {
+         0 => Err(ProcessError::InjectionFailed),
+         _ => Ok(()),
+      };
+   }
+
+   fn call_remote(
+      & self,
+      routine  : LPTHREAD_START_ROUTINE,
+      argument : LPVOID,
+   ) -> Result<DWORD> {
+      let thread = unsafe{CreateRemoteThread(
+         self.handle,
+         std::ptr::null_mut(),
+         0,
+         routine,
+         argument,
+         0,
+         std::ptr::null_mut(),
+      )};
+      if thread.is_null() == true {
+         return Err(ProcessError::InjectionFailed);
+      }
+
+      let mut exit_code = 0;
+      unsafe{WaitForSingleObject(thread, INFINITE)};
+      let exit_code_read = unsafe{GetExitCodeThread(thread, & mut exit_code)};
+      try_close_handle!(thread, "remote thread");
+
+      if exit_code_read == FALSE {
+         return Err(ProcessError::InjectionFailed);
+      }
+
+      return Ok(exit_code);
+   }
+}
+
+impl Drop for RemoteProcess {
+   fn drop(
+      & mut self,
+   ) {
+      let handle = self.handle;
+      try_close_handle!(handle, "remote process");
+      return;
+   }
+}
+
+impl SuspendedProcess {
+   pub fn spawn(
+      executable_path   : & std::path::Path,
+      arguments         : & [std::ffi::OsString],
+   ) -> Result<Self> {
+      use std::os::windows::ffi::OsStrExt;
+
+      let mut application = executable_path.as_os_str().encode_wide().collect::<Vec<u16>>();
+      if application.contains(&0) == true {
+         return Err(ProcessError::SpawnFailed);
+      }
+      application.push(0);
+
+      let mut command_line = Vec::new();
+      append_argument(& mut command_line, executable_path.as_os_str())?;
+      for argument in arguments {
+         command_line.push(b' ' as u16);
+         append_argument(& mut command_line, argument)?;
+      }
+      command_line.push(0);
+
+      // A bare file name has no directory, in
+      // which case the current one is inherited
+      let directory = executable_path.parent()
+         .filter(|directory| directory.as_os_str().is_empty() == false)
+         .map(|directory| {
+            let mut directory = directory.as_os_str().encode_wide().collect::<Vec<u16>>();
+            directory.push(0);
+            directory
+         });
+
+      let mut startup_info = unsafe{std::mem::zeroed::<STARTUPINFOW>()};
+      startup_info.cb = std::mem::size_of::<STARTUPINFOW>() as DWORD;
+      let mut process_info = unsafe{std::mem::zeroed::<PROCESS_INFORMATION>()};
+
+      if unsafe{CreateProcessW(
+         application.as_ptr(),
+         command_line.as_mut_ptr(),
+         std::ptr::null_mut(),
+         std::ptr::null_mut(),
+         FALSE,
+         CREATE_SUSPENDED,
+         std::ptr::null_mut(),
+         directory.as_ref().map_or(std::ptr::null(), |directory| directory.as_ptr()),
+         & mut startup_info,
+         & mut process_info,
+      )} == FALSE {
+         return Err(ProcessError::SpawnFailed);
+      }
+
+      let mut process = Self{
+         process_id  : process_info.dwProcessId,
+         remote      : RemoteProcess{
+            handle : process_info.hProcess,
+         },
+         thread      : process_info.hThread,
+         ready       : std::ptr::null_mut(),
+         resumed     : false,
+      };
+
+      // Nothing in the process can run yet,
+      // so the event can't be set before
+      // it's created
+      let name = ready_event_name(process.process_id)?;
+      process.ready = unsafe{CreateEventW(std::ptr::null_mut(), TRUE, FALSE, name.as_ptr())};
+      if process.ready.is_null() == true {
+         return Err(ProcessError::SpawnFailed);
+      }
+
+      return Ok(process);
+   }
+
+   pub fn load_library(
+      & self,
+      library_path : & std::path::Path,
+   ) -> Result<()> {
+      return self.remote.load_library(library_path);
+   }
+
+   pub fn wait_ready(
+      & self,
+      timeout : Option<std::time::Duration>,
+   ) -> Result<bool> {
+      let milliseconds = match timeout {
+         Some(timeout)  => DWORD::try_from(timeout.as_millis()).unwrap_or(INFINITE - 1),
+         None           => INFINITE,
+      };
+
+      return Ok(unsafe{WaitForSingleObject(self.ready, milliseconds)} == WAIT_OBJECT_0);
+   }
+
+   pub fn resume(
+      mut self,
+   ) -> Result<()> {
+      // The previous suspend count is returned,
+      // or -1 on failure
+      if unsafe{ResumeThread(self.thread)} == DWORD::MAX {
+         return Err(ProcessError::SpawnFailed);
+      }
+      self.resumed = true;
+
+      return Ok(());
+   }
+}
+
+impl Drop for SuspendedProcess {
+   fn drop(
+      & mut self,
+   ) {
+      if self.resumed == false {
+         unsafe{TerminateProcess(self.remote.handle, 1)};
+      }
+
+      let thread = self.thread;
+      try_close_handle!(thread, "main thread");
+      if self.ready.is_null() == false {
+         let ready = self.ready;
+         try_close_handle!(ready, "ready event");
+      }
+      return;
+   }
 }
 
 impl ProcessSnapshot {
@@ -235,9 +705,9 @@ impl ModuleSnapshot {
          th32ProcessID  : 0,
          GlblcntUsage   : 0,
          ProccntUsage   : 0,
-         modBaseAddr    : 0 as * mut BYTE,
+         modBaseAddr    : std::ptr::null_mut(),
          modBaseSize    : 0,
-         hModule        : 0 as HMODULE,
+         hModule        : std::ptr::null_mut(),
          szModule       : [0; 256],
          szExePath      : [0; 260],
       };
@@ -293,3 +763,580 @@ impl ModuleSnapshot {
    }
 }
 
+
+impl ModuleSnapshot {
+   pub unsafe fn unwind_function(
+      & self,
+      address : usize,
+   ) -> Result<Option<UnwindFunction>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      // Modules without an exception directory
+      // have no unwind information at all
+      let directory = match image.data_directory(
+         IMAGE_DIRECTORY_ENTRY_EXCEPTION as DWORD,
+      ) {
+         Some(directory)   => directory,
+         None              => return Ok(None),
+      };
+
+      // RUNTIME_FUNCTION entries are sorted by
+      // their start address, so binary search
+      // for the entry containing the address
+      let rva           = address.wrapping_sub(self.address_range.start);
+      let entry_count   = (directory.end - directory.start) / RUNTIME_FUNCTION_SIZE;
+      let entry_rva     = directory.start - self.address_range.start;
+      let read_entry    = |index : usize| -> Result<[DWORD; 3]> {
+         return image.read::<[DWORD; 3]>(
+            entry_rva + index * RUNTIME_FUNCTION_SIZE,
+         ).ok_or(ProcessError::InvalidImage);
+      };
+
+      let mut low    = 0;
+      let mut high   = entry_count;
+      while low < high {
+         let middle = low + (high - low) / 2;
+         let [begin, end, unwind_info] = read_entry(middle)?;
+
+         if rva < begin as usize {
+            high = middle;
+            continue;
+         }
+         if rva >= end as usize {
+            low = middle + 1;
+            continue;
+         }
+
+         // The second byte of UNWIND_INFO is the
+         // byte length of the function's prologue
+         let prologue_length = image.read::<u8>(
+            unwind_info as usize + 1,
+         ).ok_or(ProcessError::InvalidImage)?;
+
+         let address_range = image.rva_range_to_address_range(
+            begin as usize,
+            (end as usize).checked_sub(begin as usize).ok_or(ProcessError::InvalidImage)?,
+         ).ok_or(ProcessError::InvalidImage)?;
+
+         return Ok(Some(UnwindFunction{
+            address_range     : address_range,
+            prologue_length   : prologue_length as usize,
+         }));
+      }
+
+      return Ok(None);
+   }
+
+   pub unsafe fn import_slot(
+      & self,
+      library  : & str,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      let directory = match image.data_directory(
+         IMAGE_DIRECTORY_ENTRY_IMPORT as DWORD,
+      ) {
+         Some(directory)   => directory,
+         None              => return Ok(None),
+      };
+
+      let descriptor_rva = directory.start - self.address_range.start;
+      for index in 0.. {
+         let [
+            original_first_thunk,
+            _time_date_stamp,
+            _forwarder_chain,
+            name,
+            first_thunk,
+         ] = image.read::<[DWORD; 5]>(
+            descriptor_rva + index * IMPORT_DESCRIPTOR_SIZE,
+         ).ok_or(ProcessError::InvalidImage)?;
+
+         // The descriptor list ends with a zeroed entry
+         if name == 0 && first_thunk == 0 {
+            break;
+         }
+
+         let library_name = image.read_c_str(name as usize)
+            .ok_or(ProcessError::InvalidImage)?;
+         if library_name.eq_ignore_ascii_case(library.as_bytes()) == false {
+            continue;
+         }
+
+         // Without the lookup table the names are
+         // gone once the loader fills in the IAT
+         if original_first_thunk == 0 {
+            continue;
+         }
+
+         for thunk_index in 0.. {
+            let thunk = image.read::<u64>(
+               original_first_thunk as usize + thunk_index * IMPORT_THUNK_SIZE,
+            ).ok_or(ProcessError::InvalidImage)?;
+
+            if thunk == 0 {
+               break;
+            }
+            if thunk & IMAGE_ORDINAL_FLAG64 != 0 {
+               continue;
+            }
+
+            // Skip the two byte hint before the name
+            let function_name = image.read_c_str(thunk as usize + 2)
+               .ok_or(ProcessError::InvalidImage)?;
+            if function_name != function.as_bytes() {
+               continue;
+            }
+
+            let slot = image.rva_range_to_address_range(
+               first_thunk as usize + thunk_index * IMPORT_THUNK_SIZE,
+               IMPORT_THUNK_SIZE,
+            ).ok_or(ProcessError::InvalidImage)?;
+
+            return Ok(Some(slot.start));
+         }
+      }
+
+      return Ok(None);
+   }
+
+   pub unsafe fn export_rva(
+      & self,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      let directory = match image.data_directory(
+         IMAGE_DIRECTORY_ENTRY_EXPORT as DWORD,
+      ) {
+         Some(directory)   => directory,
+         None              => return Ok(None),
+      };
+
+      let directory_rva = directory.start - self.address_range.start;
+      if directory.end - directory.start < EXPORT_DIRECTORY_SIZE {
+         return Err(ProcessError::InvalidImage);
+      }
+
+      let [
+         _characteristics,
+         _time_date_stamp,
+         _version,
+         _name,
+         _base,
+         function_count,
+         name_count,
+         functions_rva,
+         names_rva,
+         ordinals_rva,
+      ] = image.read::<[DWORD; 10]>(directory_rva).ok_or(ProcessError::InvalidImage)?;
+
+      // The name table is sorted, but a linear
+      // search doesn't depend on the linker
+      // having gotten that right
+      for index in 0..name_count as usize {
+         let name_rva = image.read::<DWORD>(
+            names_rva as usize + index * std::mem::size_of::<DWORD>(),
+         ).ok_or(ProcessError::InvalidImage)?;
+
+         let name = image.read_c_str(name_rva as usize)
+            .ok_or(ProcessError::InvalidImage)?;
+         if name != function.as_bytes() {
+            continue;
+         }
+
+         let ordinal = image.read::<u16>(
+            ordinals_rva as usize + index * std::mem::size_of::<u16>(),
+         ).ok_or(ProcessError::InvalidImage)?;
+         if ordinal as DWORD >= function_count {
+            return Err(ProcessError::InvalidImage);
+         }
+
+         let function_rva = image.read::<DWORD>(
+            functions_rva as usize + ordinal as usize * std::mem::size_of::<DWORD>(),
+         ).ok_or(ProcessError::InvalidImage)? as usize;
+
+         // Forwarded exports point to a string
+         // naming the function in another module
+         if (directory_rva..directory_rva + (directory.end - directory.start)).contains(
+            &function_rva,
+         ) == true {
+            return Ok(None);
+         }
+
+         return Ok(Some(function_rva));
+      }
+
+      return Ok(None);
+   }
+
+   pub unsafe fn file_offset_to_rva(
+      & self,
+      file_offset : usize,
+   ) -> Result<Option<usize>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      // The headers are mapped at the same
+      // offsets as they are stored in the file
+      if file_offset < image.headers.OptionalHeader.SizeOfHeaders as usize {
+         return Ok(Some(file_offset));
+      }
+
+      for section in image.sections().ok_or(ProcessError::InvalidImage)? {
+         let raw_start  = section.PointerToRawData as usize;
+         let raw_size   = usize::min(
+            section.SizeOfRawData as usize,
+            *section.Misc.VirtualSize() as usize,
+         );
+
+         if (raw_start..raw_start + raw_size).contains(&file_offset) == true {
+            return Ok(Some(section.VirtualAddress as usize + (file_offset - raw_start)));
+         }
+      }
+
+      return Ok(None);
+   }
+
+   pub unsafe fn sections(
+      & self,
+   ) -> Result<Option<Vec<Section>>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      let alignment = usize::max(image.headers.OptionalHeader.SectionAlignment as usize, 1);
+
+      let mut sections = image.sections().ok_or(ProcessError::InvalidImage)?
+         .into_iter()
+         .map(|section| {
+            // Names shorter than 8 bytes are
+            // padded with nulls, otherwise
+            // there is no terminator
+            let name = section.Name.split(|byte| *byte == 0).next().unwrap_or(&[]);
+            let start = self.address_range.start + section.VirtualAddress as usize;
+            let size  = *section.Misc.VirtualSize() as usize;
+            let size  = (size + alignment - 1) / alignment * alignment;
+            Section{
+               name              : String::from_utf8_lossy(name).into_owned(),
+               address_range     : start..start + size,
+               characteristics   : section.Characteristics,
+            }
+         })
+         .collect::<Vec<_>>();
+
+      sections.sort_by_key(|section| section.address_range.start);
+      return Ok(Some(sections));
+   }
+
+   pub unsafe fn import_table_ranges(
+      & self,
+   ) -> Result<Vec<std::ops::Range<usize>>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      // The descriptors themselves and the
+      // address table the loader fills in
+      return Ok([IMAGE_DIRECTORY_ENTRY_IMPORT, IMAGE_DIRECTORY_ENTRY_IAT].into_iter()
+         .filter_map(|index| image.data_directory(index as DWORD))
+         .map(|range| range.start - self.address_range.start..range.end - self.address_range.start)
+         .collect());
+   }
+
+   pub unsafe fn file_version(
+      & self,
+   ) -> Result<Option<[u16; 4]>> {
+      let image = super::image::Image::parse(&self.address_range)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      let directory = match image.data_directory(
+         IMAGE_DIRECTORY_ENTRY_RESOURCE as DWORD,
+      ) {
+         Some(directory)   => directory,
+         None              => return Ok(None),
+      };
+      let root_rva = directory.start - self.address_range.start;
+
+      // Resources are stored in a tree of type,
+      // then name, then language.  The version
+      // is found under the first name and language.
+      let mut entry = match resource_entry(&image, root_rva, root_rva, Some(RESOURCE_TYPE_VERSION))? {
+         Some(entry) => entry,
+         None        => return Ok(None),
+      };
+      for _ in 0..2 {
+         entry = match entry {
+            ResourceEntry::Directory(rva) => match resource_entry(&image, root_rva, rva, None)? {
+               Some(entry) => entry,
+               None        => return Ok(None),
+            },
+            ResourceEntry::Data(_)        => return Err(ProcessError::InvalidImage),
+         };
+      }
+
+      let data_entry_rva = match entry {
+         ResourceEntry::Data(rva)      => rva,
+         ResourceEntry::Directory(_)   => return Err(ProcessError::InvalidImage),
+      };
+      let [data_rva, data_size] = image.read::<[DWORD; 2]>(data_entry_rva)
+         .ok_or(ProcessError::InvalidImage)?;
+
+      // VS_VERSIONINFO starts with variable-length
+      // fields, so search for the fixed info by its
+      // signature, which is always DWORD-aligned
+      let fields = std::mem::size_of::<DWORD>();
+      for offset in (0..data_size as usize).step_by(fields) {
+         let field = image.read::<DWORD>(data_rva as usize + offset)
+            .ok_or(ProcessError::InvalidImage)?;
+         if field != FIXED_FILE_INFO_SIGNATURE {
+            continue;
+         }
+
+         let [_signature, _struct_version, version_high, version_low] = image.read::<[DWORD; 4]>(
+            data_rva as usize + offset,
+         ).ok_or(ProcessError::InvalidImage)?;
+
+         return Ok(Some([
+            (version_high >> 16)    as u16,
+            (version_high & 0xFFFF) as u16,
+            (version_low >> 16)     as u16,
+            (version_low & 0xFFFF)  as u16,
+         ]));
+      }
+
+      return Ok(None);
+   }
+}
+
+enum ResourceEntry {
+   Directory(usize),
+   Data(usize),
+}
+
+// Finds an entry in a resource directory by
+// ID, or the first entry if no ID is given
+unsafe fn resource_entry(
+   image          : & super::image::Image,
+   root_rva       : usize,
+   directory_rva  : usize,
+   id             : Option<DWORD>,
+) -> Result<Option<ResourceEntry>> {
+   let [_characteristics, _time_date_stamp, _version, counts] = image.read::<[DWORD; 4]>(
+      directory_rva,
+   ).ok_or(ProcessError::InvalidImage)?;
+
+   // The named entry count is the low word
+   // and the ID entry count is the high word
+   let named_count   = (counts & 0xFFFF) as usize;
+   let id_count      = (counts >> 16) as usize;
+
+   for index in 0..named_count + id_count {
+      let [name, offset] = image.read::<[DWORD; 2]>(
+         directory_rva + RESOURCE_DIRECTORY_SIZE + index * RESOURCE_ENTRY_SIZE,
+      ).ok_or(ProcessError::InvalidImage)?;
+
+      if let Some(id) = id {
+         if index < named_count || name != id {
+            continue;
+         }
+      }
+
+      // The high bit marks a subdirectory, and
+      // offsets are relative to the root
+      let target = root_rva + (offset & 0x7FFFFFFF) as usize;
+      return Ok(Some(match offset & 0x80000000 != 0 {
+         true  => ResourceEntry::Directory(target),
+         false => ResourceEntry::Data(target),
+      }));
+   }
+
+   return Ok(None);
+}
+
+pub fn section_is_executable(
+   characteristics : u32,
+) -> bool {
+   return characteristics & IMAGE_SCN_MEM_EXECUTE != 0;
+}
+
+pub fn section_is_readable(
+   characteristics : u32,
+) -> bool {
+   return characteristics & IMAGE_SCN_MEM_READ != 0;
+}
+
+pub fn section_is_writable(
+   characteristics : u32,
+) -> bool {
+   return characteristics & IMAGE_SCN_MEM_WRITE != 0;
+}
+
+pub fn module_file_path(
+   address : usize,
+) -> Result<std::path::PathBuf> {
+   let mut module : HMODULE = std::ptr::null_mut();
+   if unsafe{GetModuleHandleExW(
+      GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+      address as LPCWSTR,
+      & mut module,
+   )} == FALSE {
+      return Err(ProcessError::Unknown);
+   }
+
+   // Paths may be longer than MAX_PATH, and
+   // truncation is only detectable by the
+   // buffer being completely filled
+   let mut buffer = vec![0u16; MAX_PATH];
+   loop {
+      let length = unsafe{GetModuleFileNameW(
+         module,
+         buffer.as_mut_ptr(),
+         buffer.len() as DWORD,
+      )} as usize;
+
+      if length == 0 {
+         return Err(ProcessError::Unknown);
+      }
+      if length < buffer.len() {
+         use std::os::windows::ffi::OsStringExt;
+         return Ok(std::path::PathBuf::from(std::ffi::OsString::from_wide(&buffer[..length])));
+      }
+
+      buffer.resize(buffer.len() * 2, 0);
+   }
+}
+
+pub fn write_minidump(
+   file_path : & std::path::Path,
+) -> Result<()> {
+   // MiniDumpWriteDump from dbghelp.dll, which
+   // is loaded on demand so mods don't link
+   // against it just in case they crash
+   type MiniDumpWriteDump = unsafe extern "system" fn(
+      HANDLE, DWORD, HANDLE, DWORD, LPVOID, LPVOID, LPVOID,
+   ) -> i32;
+
+   // MiniDumpWithDataSegs, MiniDumpWithIndirectlyReferencedMemory
+   // and MiniDumpWithThreadInfo, which captures
+   // enough to walk every stack without
+   // including the whole heap
+   const MINIDUMP_TYPE : DWORD = 0x0001 | 0x0040 | 0x1000;
+
+   let dbghelp = DbgHelp::load()?;
+   let function = dbghelp.function(b"MiniDumpWriteDump\0").ok_or(ProcessError::Unsupported)?;
+   let function = unsafe{std::mem::transmute::<LPVOID, MiniDumpWriteDump>(function)};
+
+   let file = std::fs::File::create(file_path).map_err(|_| ProcessError::AccessDenied)?;
+
+   use std::os::windows::io::AsRawHandle;
+   if unsafe{function(
+      GetCurrentProcess(),
+      GetCurrentProcessId(),
+      file.as_raw_handle() as HANDLE,
+      MINIDUMP_TYPE,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+   )} == FALSE {
+      return Err(ProcessError::Unknown);
+   }
+
+   return Ok(());
+}
+
+impl DbgHelp {
+   // The symbol handler is shared by
+   // everything in the process which uses
+   // dbghelp, such as backtraces
+   const SYM_INITIALIZE : & 'static [u8] = b"SymInitialize\0";
+   const SYM_CLEANUP    : & 'static [u8] = b"SymCleanup\0";
+
+   fn load(
+   ) -> Result<Self> {
+      type SymInitialize = unsafe extern "system" fn(HANDLE, LPCSTR, i32) -> i32;
+
+      let library = unsafe{LoadLibraryA(b"dbghelp.dll\0".as_ptr() as LPCSTR)};
+      if library.is_null() == true {
+         return Err(ProcessError::Unsupported);
+      }
+
+      let mut dbghelp = Self{
+         library  : library,
+         symbols  : false,
+      };
+
+      // The symbol handler is only cleaned up
+      // when dropped if it's initialized here,
+      // since SymInitialize fails if something
+      // else in the process already has it open
+      if let Some(function) = dbghelp.function(Self::SYM_INITIALIZE) {
+         let function = unsafe{std::mem::transmute::<LPVOID, SymInitialize>(function)};
+         dbghelp.symbols = unsafe{function(GetCurrentProcess(), std::ptr::null(), FALSE)} != FALSE;
+      }
+
+      return Ok(dbghelp);
+   }
+
+   fn function(
+      & self,
+      name : & [u8],
+   ) -> Option<LPVOID> {
+      let function = unsafe{GetProcAddress(self.library, name.as_ptr() as LPCSTR)};
+      if function.is_null() == true {
+         return None;
+      }
+
+      return Some(function as LPVOID);
+   }
+}
+
+impl Drop for DbgHelp {
+   fn drop(
+      & mut self,
+   ) {
+      type SymCleanup = unsafe extern "system" fn(HANDLE) -> i32;
+
+      if self.symbols == true {
+         if let Some(function) = self.function(Self::SYM_CLEANUP) {
+            let function = unsafe{std::mem::transmute::<LPVOID, SymCleanup>(function)};
+            unsafe{function(GetCurrentProcess())};
+         }
+      }
+
+      unsafe{FreeLibrary(self.library)};
+      return;
+   }
+}
+
+pub fn signal_ready(
+) -> Result<()> {
+   let name = ready_event_name(unsafe{GetCurrentProcessId()})?;
+
+   // The event only exists when a launcher
+   // started the process and is waiting
+   let event = unsafe{OpenEventW(EVENT_MODIFY_STATE, FALSE, name.as_ptr())};
+   if event.is_null() == true {
+      return Ok(());
+   }
+
+   let set = unsafe{SetEvent(event)};
+   try_close_handle!(event, "ready event");
+   if set == FALSE {
+      return Err(ProcessError::Unknown);
+   }
+
+   return Ok(());
+}
+
+// The name of the event a process started
+// by a launcher sets once the libraries
+// loaded into it are ready
+fn ready_event_name(
+   process_id : DWORD,
+) -> Result<Vec<u16>> {
+   return crate::encoding::to_wide_nul(&format!("Local\\nusion-ready-{process_id}"))
+      .map_err(|_| ProcessError::Unknown);
+}
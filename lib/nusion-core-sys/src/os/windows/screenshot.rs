@@ -0,0 +1,119 @@
+//! crate::os::screenshot implementation for Windows.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         FALSE,
+      },
+      windef::{
+         HWND,
+         RECT,
+      },
+   },
+   um::{
+      wingdi::{
+         BitBlt,
+         CreateCompatibleBitmap,
+         CreateCompatibleDC,
+         DeleteDC,
+         DeleteObject,
+         GetDIBits,
+         SelectObject,
+         BITMAPINFO,
+         BITMAPINFOHEADER,
+         BI_RGB,
+         DIB_RGB_COLORS,
+         SRCCOPY,
+      },
+      winuser::{
+         GetClientRect,
+         GetDC,
+         ReleaseDC,
+      },
+   },
+};
+
+pub fn capture_process_window(
+) -> crate::screenshot::Result<crate::screenshot::Frame> {
+   let window = crate::os::window::find_process_window()
+      .ok_or(crate::screenshot::ScreenshotError::WindowNotFound)?;
+
+   return unsafe{capture_window(window as HWND)};
+}
+
+unsafe fn capture_window(
+   window : HWND,
+) -> crate::screenshot::Result<crate::screenshot::Frame> {
+   let mut rect = std::mem::zeroed::<RECT>();
+   if GetClientRect(window, & mut rect) == FALSE {
+      return Err(crate::screenshot::ScreenshotError::Unknown);
+   }
+
+   let width   = (rect.right - rect.left) as i32;
+   let height  = (rect.bottom - rect.top) as i32;
+
+   let window_dc = GetDC(window);
+   if window_dc.is_null() == true {
+      return Err(crate::screenshot::ScreenshotError::Unknown);
+   }
+
+   let memory_dc  = CreateCompatibleDC(window_dc);
+   let bitmap     = CreateCompatibleBitmap(window_dc, width, height);
+   let mut result = Err(crate::screenshot::ScreenshotError::Unknown);
+
+   if memory_dc.is_null() == false && bitmap.is_null() == false {
+      let old_bitmap = SelectObject(memory_dc, bitmap as _);
+
+      if BitBlt(memory_dc, 0, 0, width, height, window_dc, 0, 0, SRCCOPY) != FALSE {
+         // A negative height requests the
+         // rows top row first
+         let mut info = std::mem::zeroed::<BITMAPINFO>();
+         info.bmiHeader.biSize         = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+         info.bmiHeader.biWidth        = width;
+         info.bmiHeader.biHeight       = -height;
+         info.bmiHeader.biPlanes       = 1;
+         info.bmiHeader.biBitCount     = 32;
+         info.bmiHeader.biCompression  = BI_RGB;
+
+         let mut pixels = vec![0u8; width as usize * height as usize * 4];
+
+         // The bitmap can't be selected into
+         // a device context while reading it
+         SelectObject(memory_dc, old_bitmap);
+         if GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height as u32,
+            pixels.as_mut_ptr() as _,
+            & mut info,
+            DIB_RGB_COLORS,
+         ) != 0 {
+            // Pixels are read as BGRX
+            for pixel in pixels.chunks_exact_mut(4) {
+               pixel.swap(0, 2);
+               pixel[3] = 0xFF;
+            }
+
+            result = Ok(crate::screenshot::Frame{
+               width    : width as usize,
+               height   : height as usize,
+               pixels   : pixels,
+            });
+         }
+      } else {
+         SelectObject(memory_dc, old_bitmap);
+      }
+   }
+
+   if bitmap.is_null() == false {
+      DeleteObject(bitmap as _);
+   }
+   if memory_dc.is_null() == false {
+      DeleteDC(memory_dc);
+   }
+   ReleaseDC(window, window_dc);
+
+   return result;
+}
+
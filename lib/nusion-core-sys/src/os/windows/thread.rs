@@ -0,0 +1,191 @@
+//! crate::os::thread implementation for Windows.
+
+use winapi::{
+   shared::{
+      minwindef::{
+         FALSE,
+      },
+   },
+   um::{
+      handleapi::{
+         CloseHandle,
+         INVALID_HANDLE_VALUE,
+      },
+      processthreadsapi::{
+         FlushInstructionCache,
+         GetCurrentProcess,
+         GetCurrentProcessId,
+         GetCurrentThreadId,
+         GetThreadContext,
+         OpenThread,
+         ResumeThread,
+         SuspendThread,
+      },
+      tlhelp32::{
+         CreateToolhelp32Snapshot,
+         Thread32First,
+         Thread32Next,
+         THREADENTRY32,
+         TH32CS_SNAPTHREAD,
+      },
+      winnt::{
+         CONTEXT,
+         CONTEXT_CONTROL,
+         HANDLE,
+         THREAD_GET_CONTEXT,
+         THREAD_SUSPEND_RESUME,
+      },
+   },
+};
+
+pub struct SuspendedThreads {
+   handles              : Vec<HANDLE>,
+   instruction_pointers : Vec<usize>,
+}
+
+impl SuspendedThreads {
+   pub unsafe fn suspend_others(
+   ) -> crate::thread::Result<Self> {
+      return Self::suspend_others_except(&[]);
+   }
+
+   pub unsafe fn suspend_others_except(
+      excluded_thread_ids : & [u32],
+   ) -> crate::thread::Result<Self> {
+      // Every allocation happens before any
+      // thread is suspended, since a suspended
+      // thread may be holding the heap lock
+      let mut thread_ids = other_thread_ids()?;
+      thread_ids.retain(|thread_id| excluded_thread_ids.contains(thread_id) == false);
+
+      let mut threads = Self{
+         handles              : Vec::with_capacity(thread_ids.len()),
+         instruction_pointers : Vec::with_capacity(thread_ids.len()),
+      };
+
+      for thread_id in thread_ids {
+         // Threads may exit between taking the
+         // snapshot and opening them
+         let handle = OpenThread(THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT, FALSE, thread_id);
+         if handle.is_null() == true {
+            continue;
+         }
+
+         if SuspendThread(handle) == u32::MAX {
+            CloseHandle(handle);
+            continue;
+         }
+         threads.handles.push(handle);
+
+         let mut context = std::mem::zeroed::<CONTEXT>();
+         context.ContextFlags = CONTEXT_CONTROL;
+         if GetThreadContext(handle, &mut context) == FALSE {
+            // Dropping resumes every thread
+            // suspended so far
+            return Err(crate::thread::ThreadError::Unknown);
+         }
+         threads.instruction_pointers.push(context.Rip as usize);
+      }
+
+      return Ok(threads);
+   }
+
+   pub fn instruction_pointers<'l>(
+      &'l self,
+   ) -> &'l [usize] {
+      return &self.instruction_pointers;
+   }
+}
+
+// Thread handles can be resumed and
+// closed from any thread
+unsafe impl Send for SuspendedThreads {}
+
+impl Drop for SuspendedThreads {
+   fn drop(
+      & mut self,
+   ) {
+      for handle in self.handles.iter() {
+         unsafe{ResumeThread(*handle)};
+         unsafe{CloseHandle(*handle)};
+      }
+
+      return;
+   }
+}
+
+pub fn sample_instruction_pointers(
+) -> crate::thread::Result<Vec<usize>> {
+   let thread_ids = other_thread_ids()?;
+   let mut instruction_pointers = Vec::with_capacity(thread_ids.len());
+
+   for thread_id in thread_ids {
+      let handle = unsafe{OpenThread(THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT, FALSE, thread_id)};
+      if handle.is_null() == true {
+         continue;
+      }
+
+      // The context is only consistent
+      // while the thread is suspended
+      if unsafe{SuspendThread(handle)} != u32::MAX {
+         let mut context = unsafe{std::mem::zeroed::<CONTEXT>()};
+         context.ContextFlags = CONTEXT_CONTROL;
+         let read = unsafe{GetThreadContext(handle, &mut context)} != FALSE;
+         unsafe{ResumeThread(handle)};
+
+         if read == true {
+            instruction_pointers.push(context.Rip as usize);
+         }
+      }
+
+      unsafe{CloseHandle(handle)};
+   }
+
+   return Ok(instruction_pointers);
+}
+
+pub fn current_thread_id(
+) -> u32 {
+   return unsafe{GetCurrentThreadId()};
+}
+
+pub fn flush_instruction_cache(
+   address_range : std::ops::Range<usize>,
+) -> crate::thread::Result<()> {
+   if unsafe{FlushInstructionCache(
+      GetCurrentProcess(),
+      address_range.start as * const _,
+      address_range.end - address_range.start,
+   )} == FALSE {
+      return Err(crate::thread::ThreadError::Unknown);
+   }
+
+   return Ok(());
+}
+
+pub fn other_thread_ids(
+) -> crate::thread::Result<Vec<u32>> {
+   let process_id = unsafe{GetCurrentProcessId()};
+   let thread_id  = unsafe{GetCurrentThreadId()};
+
+   let snapshot = unsafe{CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0)};
+   if snapshot == INVALID_HANDLE_VALUE {
+      return Err(crate::thread::ThreadError::Unknown);
+   }
+
+   let mut entry = unsafe{std::mem::zeroed::<THREADENTRY32>()};
+   entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+
+   let mut thread_ids = Vec::new();
+   let mut found = unsafe{Thread32First(snapshot, &mut entry)} != FALSE;
+   while found == true {
+      if entry.th32OwnerProcessID == process_id && entry.th32ThreadID != thread_id {
+         thread_ids.push(entry.th32ThreadID);
+      }
+      found = unsafe{Thread32Next(snapshot, &mut entry)} != FALSE;
+   }
+
+   unsafe{CloseHandle(snapshot)};
+   return Ok(thread_ids);
+}
+
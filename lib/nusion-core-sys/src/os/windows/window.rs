@@ -0,0 +1,266 @@
+//! crate::window implementations for
+//! Windows.
+
+use crate::window::{WindowError, WindowMessage, Result};
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+use winapi::{
+   shared::{
+      basetsd::{
+         LONG_PTR,
+      },
+      minwindef::{
+         BOOL,
+         DWORD,
+         FALSE,
+         HMODULE,
+         LPARAM,
+         LRESULT,
+         TRUE,
+         UINT,
+         WPARAM,
+      },
+      windef::{
+         HWND,
+         RECT,
+      },
+   },
+   um::{
+      errhandlingapi::{
+         GetLastError,
+         SetLastError,
+      },
+      libloaderapi::{
+         GetModuleHandleExW,
+         GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS,
+         GET_MODULE_HANDLE_EX_FLAG_PIN,
+      },
+      processthreadsapi::{
+         GetCurrentProcessId,
+      },
+      winuser::{
+         CallWindowProcW,
+         DefWindowProcW,
+         EnumWindows,
+         GetClientRect,
+         GetForegroundWindow,
+         GetWindowLongPtrW,
+         GetWindowThreadProcessId,
+         IsWindowVisible,
+         SetWindowLongPtrW,
+         GWLP_WNDPROC,
+         WNDPROC,
+      },
+   },
+};
+
+// Largest visible window found so far
+// while enumerating windows
+struct WindowSearch {
+   process_id  : DWORD,
+   window      : HWND,
+   area        : i64,
+}
+
+// Most games only have one window, and a
+// few more leaves room for tool windows
+const MAX_SUBCLASSED_WINDOWS : usize = 8;
+
+// A window whose procedure was replaced
+// and the procedure it had before.  The
+// window procedure reads these on every
+// message without locking, so the original
+// is stored before the window when one
+// is added, and the window is cleared
+// first when it's removed.
+struct SubclassedWindow {
+   window   : AtomicUsize,
+   original : AtomicIsize,
+}
+
+static SUBCLASSED_WINDOWS
+   : [SubclassedWindow; MAX_SUBCLASSED_WINDOWS]
+   = [const {SubclassedWindow{
+      window   : AtomicUsize::new(0),
+      original : AtomicIsize::new(0),
+   }}; MAX_SUBCLASSED_WINDOWS];
+
+// Only serializes subclassing and
+// unsubclassing with each other
+static SUBCLASS_LOCK
+   : Mutex<()>
+   = Mutex::new(());
+
+unsafe extern "system" fn enum_windows_callback(
+   window   : HWND,
+   search   : LPARAM,
+) -> BOOL {
+   let search = &mut *(search as * mut WindowSearch);
+
+   let mut process_id : DWORD = 0;
+   GetWindowThreadProcessId(window, & mut process_id);
+   if process_id != search.process_id || IsWindowVisible(window) == FALSE {
+      return TRUE;
+   }
+
+   // The game's window is almost always the
+   // biggest, which skips the debug console
+   let mut rect = std::mem::zeroed::<RECT>();
+   if GetClientRect(window, & mut rect) == FALSE {
+      return TRUE;
+   }
+
+   let area = (rect.right - rect.left) as i64 * (rect.bottom - rect.top) as i64;
+   if area > search.area {
+      search.window  = window;
+      search.area    = area;
+   }
+
+   return TRUE;
+}
+
+unsafe extern "system" fn window_procedure(
+   window   : HWND,
+   message  : UINT,
+   w_param  : WPARAM,
+   l_param  : LPARAM,
+) -> LRESULT {
+   let original = find_subclassed(window as usize)
+      .map(|subclassed| subclassed.original.load(Ordering::Relaxed));
+
+   if let Some(result) = crate::window::dispatch_message(window as usize, &WindowMessage{
+      message : message,
+      w_param : w_param,
+      l_param : l_param,
+   }) {
+      return result;
+   }
+
+   return match original {
+      Some(original) => CallWindowProcW(
+         std::mem::transmute::<LONG_PTR, WNDPROC>(original),
+         window,
+         message,
+         w_param,
+         l_param,
+      ),
+      None           => DefWindowProcW(window, message, w_param, l_param),
+   };
+}
+
+fn own_procedure(
+) -> LONG_PTR {
+   return window_procedure as unsafe extern "system" fn(HWND, UINT, WPARAM, LPARAM) -> LRESULT
+      as usize as LONG_PTR;
+}
+
+fn find_subclassed(
+   window : usize,
+) -> Option<&'static SubclassedWindow> {
+   return SUBCLASSED_WINDOWS.iter().find(|subclassed| subclassed.window.load(Ordering::Acquire) == window);
+}
+
+pub fn find_process_window(
+) -> Option<usize> {
+   let mut search = WindowSearch{
+      process_id  : unsafe{GetCurrentProcessId()},
+      window      : std::ptr::null_mut(),
+      area        : 0,
+   };
+
+   unsafe{EnumWindows(
+      Some(enum_windows_callback),
+      & mut search as * mut WindowSearch as LPARAM,
+   )};
+
+   if search.window.is_null() == true {
+      return None;
+   }
+
+   return Some(search.window as usize);
+}
+
+pub fn is_foreground(
+   window : usize,
+) -> bool {
+   return unsafe{GetForegroundWindow()} as usize == window;
+}
+
+pub fn subclass(
+   window : usize,
+) -> Result<()> {
+   let _lock = SUBCLASS_LOCK.lock().unwrap_or_else(|poison| poison.into_inner());
+   if find_subclassed(window).is_some() == true {
+      return Ok(());
+   }
+   let slot = SUBCLASSED_WINDOWS.iter()
+      .find(|subclassed| subclassed.window.load(Ordering::Acquire) == 0)
+      .ok_or(WindowError::TooManyWindows{
+         maximum : MAX_SUBCLASSED_WINDOWS,
+      })?;
+
+   // Messages can arrive as soon as the
+   // procedure is replaced, so the original
+   // has to be findable before then
+   slot.original.store(unsafe{GetWindowLongPtrW(window as HWND, GWLP_WNDPROC)}, Ordering::Relaxed);
+   slot.window.store(window, Ordering::Release);
+
+   // Zero is both a failure and a valid
+   // previous value, so the last error
+   // has to be cleared to tell them apart
+   let original = unsafe{
+      SetLastError(0);
+      SetWindowLongPtrW(
+         window as HWND,
+         GWLP_WNDPROC,
+         own_procedure(),
+      )
+   };
+   if original == 0 && unsafe{GetLastError()} != 0 {
+      slot.window.store(0, Ordering::Release);
+      return Err(WindowError::SubclassFailed);
+   }
+
+   slot.original.store(original, Ordering::Relaxed);
+   return Ok(());
+}
+
+pub fn unsubclass(
+   window : usize,
+) {
+   let _lock = SUBCLASS_LOCK.lock().unwrap_or_else(|poison| poison.into_inner());
+   let subclassed = match find_subclassed(window) {
+      Some(subclassed)  => subclassed,
+      None              => return,
+   };
+
+   // If something replaced the procedure
+   // after us, putting back the original
+   // would cut it out of the chain, so
+   // ours is left to forward everything.
+   // That keeps the window calling into
+   // this library, so it's pinned to stay
+   // loaded for the rest of the process.
+   let current = unsafe{GetWindowLongPtrW(window as HWND, GWLP_WNDPROC)};
+   if current != own_procedure() {
+      pin_library();
+      return;
+   }
+
+   unsafe{SetWindowLongPtrW(window as HWND, GWLP_WNDPROC, subclassed.original.load(Ordering::Relaxed))};
+   subclassed.window.store(0, Ordering::Release);
+   return;
+}
+
+fn pin_library(
+) {
+   let mut module : HMODULE = std::ptr::null_mut();
+   unsafe{GetModuleHandleExW(
+      GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_PIN,
+      own_procedure() as * const u16,
+      & mut module,
+   )};
+   return;
+}
@@ -0,0 +1,305 @@
+//! Rendering triangles on top of the
+//! game's frames from inside its present
+//! call.  Direct3D 11 is drawn to directly,
+//! and Direct3D 12 is drawn to through a
+//! Direct3D 11 device wrapping the game's
+//! command queue.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to rendering an overlay.
+#[derive(Debug)]
+pub enum OverlayError {
+   DeviceCreationFailed,
+   UnsupportedGraphicsApi,
+   ShaderCompilationFailed,
+   ResourceCreationFailed,
+   FontCreationFailed,
+   CommandQueueNotFound,
+   Unsupported,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>OverlayError</code>.
+pub type Result<T> = std::result::Result<T, OverlayError>;
+
+/// A single vertex of an overlay triangle.
+/// Positions are in pixels from the top
+/// left of the screen, texture coordinates
+/// are into the font atlas, and the color
+/// is 8-bit RGBA.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+   pub position   : [f32; 2],
+   pub uv         : [f32; 2],
+   pub color      : [u8; 4],
+}
+
+/// Size and texture coordinates of
+/// the glyphs in the font atlas.  Each
+/// glyph has the same size since the
+/// font is monospace.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontMetrics {
+   pub glyph_width   : f32,
+   pub glyph_height  : f32,
+   pub atlas_width   : f32,
+   pub atlas_height  : f32,
+}
+
+/// Renders overlay triangles into the
+/// back buffer of the game's swap chain.
+/// Resources are created from the game's
+/// own device on first use, so this must
+/// only be used from within the game's
+/// present call.
+pub struct Renderer {
+   renderer : crate::os::overlay::Renderer,
+}
+
+/// Index of <code>Present</code> in the
+/// swap chain's virtual method table.
+pub const PRESENT_METHOD_INDEX : usize
+   = 8;
+
+/// Index of <code>ResizeBuffers</code> in
+/// the swap chain's virtual method table.
+pub const RESIZE_BUFFERS_METHOD_INDEX : usize
+   = 13;
+
+/// Index of <code>ExecuteCommandLists</code>
+/// in the Direct3D 12 command queue's
+/// virtual method table.
+pub const EXECUTE_COMMAND_LISTS_METHOD_INDEX : usize
+   = 10;
+
+/// First character in the font atlas.
+pub const FONT_FIRST_CHARACTER : char
+   = ' ';
+
+/// Last character in the font atlas.
+pub const FONT_LAST_CHARACTER : char
+   = '~';
+
+/// Number of glyph cells in each row
+/// of the font atlas.
+pub const FONT_ATLAS_COLUMNS : usize
+   = 16;
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - OverlayError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for OverlayError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::DeviceCreationFailed
+            => "Failed to create a graphics device",
+         Self::UnsupportedGraphicsApi
+            => "The game doesn't render with a supported graphics API",
+         Self::ShaderCompilationFailed
+            => "Failed to compile overlay shaders",
+         Self::ResourceCreationFailed
+            => "Failed to create overlay graphics resources",
+         Self::FontCreationFailed
+            => "Failed to create the overlay font",
+         Self::CommandQueueNotFound
+            => "The game's Direct3D 12 command queue hasn't been found yet",
+         Self::Unsupported
+            => "Unsupported on this platform",
+      });
+   }
+}
+
+impl std::error::Error for OverlayError {
+}
+
+///////////////////////////
+// METHODS - FontMetrics //
+///////////////////////////
+
+impl FontMetrics {
+   /// Gets the texture coordinates of a
+   /// character's glyph as the top left
+   /// and bottom right corners.  Characters
+   /// outside of the atlas are drawn as
+   /// <code>?</code>.
+   pub fn glyph_uv(
+      & self,
+      character : char,
+   ) -> [f32; 4] {
+      let character = match character {
+         FONT_FIRST_CHARACTER..=FONT_LAST_CHARACTER  => character,
+         _                                          => '?',
+      };
+
+      let index   = character as usize - FONT_FIRST_CHARACTER as usize;
+      return self.cell_uv(index);
+   }
+
+   /// Gets texture coordinates which are
+   /// fully opaque, for drawing solid shapes.
+   pub fn solid_uv(
+      & self,
+   ) -> [f32; 2] {
+      // The cell after the last glyph is solid
+      let cell = self.cell_uv(FONT_LAST_CHARACTER as usize - FONT_FIRST_CHARACTER as usize + 1);
+      return [(cell[0] + cell[2]) / 2.0, (cell[1] + cell[3]) / 2.0];
+   }
+
+   fn cell_uv(
+      & self,
+      index : usize,
+   ) -> [f32; 4] {
+      let column  = (index % FONT_ATLAS_COLUMNS) as f32;
+      let row     = (index / FONT_ATLAS_COLUMNS) as f32;
+
+      return [
+         column         * self.glyph_width  / self.atlas_width,
+         row            * self.glyph_height / self.atlas_height,
+         (column + 1.0) * self.glyph_width  / self.atlas_width,
+         (row + 1.0)    * self.glyph_height / self.atlas_height,
+      ];
+   }
+}
+
+////////////////////////
+// METHODS - Renderer //
+////////////////////////
+
+impl Renderer {
+   /// Creates the renderer's resources
+   /// from the device which owns a swap
+   /// chain.  Direct3D 12 swap chains also
+   /// need the command queue they present
+   /// on, and <code>CommandQueueNotFound</code>
+   /// is returned if it's missing or belongs
+   /// to another device.
+   ///
+   /// <h2 id=  renderer_new_safety>
+   /// <a href=#renderer_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The address must be a valid swap
+   /// chain, such as the one passed to a
+   /// hooked present call.  The command
+   /// queue, if any, must be a valid
+   /// Direct3D 12 command queue.
+   pub unsafe fn new(
+      swap_chain     : usize,
+      command_queue  : Option<usize>,
+   ) -> Result<Self> {
+      return Ok(Self{
+         renderer : crate::os::overlay::Renderer::new(swap_chain, command_queue)?,
+      });
+   }
+
+   /// Gets the size and layout of the
+   /// font atlas.
+   pub fn font_metrics(
+      & self,
+   ) -> FontMetrics {
+      return self.renderer.font_metrics();
+   }
+
+   /// Gets the size of the swap chain's
+   /// back buffer in pixels.
+   ///
+   /// <h2 id=  renderer_screen_size_safety>
+   /// <a href=#renderer_screen_size_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The same safety concerns as
+   /// <code>Renderer::new</code> apply.
+   pub unsafe fn screen_size(
+      & mut self,
+      swap_chain : usize,
+   ) -> Result<(f32, f32)> {
+      return self.renderer.screen_size(swap_chain);
+   }
+
+   /// Draws a list of triangles into
+   /// the swap chain's back buffer.  The
+   /// game's rendering state is restored
+   /// afterwards.
+   ///
+   /// <h2 id=  renderer_render_safety>
+   /// <a href=#renderer_render_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The same safety concerns as
+   /// <code>Renderer::new</code> apply.
+   /// In addition, this must be called on
+   /// the thread which presents the frame.
+   pub unsafe fn render(
+      & mut self,
+      swap_chain  : usize,
+      vertices    : & [Vertex],
+   ) -> Result<()> {
+      return self.renderer.render(swap_chain, vertices);
+   }
+
+   /// Releases references to the swap
+   /// chain's back buffer, which must be
+   /// done before the game resizes it.
+   pub fn release_targets(
+      & mut self,
+   ) {
+      self.renderer.release_targets();
+      return;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Finds the virtual method table shared
+/// by every DXGI swap chain by creating
+/// and destroying a temporary swap chain
+/// on a hidden window.  Swap chains for
+/// Direct3D 11 and 12 share the table.
+pub fn swap_chain_vtable(
+) -> Result<usize> {
+   return crate::os::overlay::swap_chain_vtable();
+}
+
+/// Finds the virtual method table shared
+/// by every Direct3D 12 command queue by
+/// creating and destroying a temporary
+/// queue.  <code>UnsupportedGraphicsApi</code>
+/// is returned if Direct3D 12 isn't
+/// available.
+pub fn command_queue_vtable(
+) -> Result<usize> {
+   return crate::os::overlay::command_queue_vtable();
+}
+
+/// Checks whether a Direct3D 12 command
+/// queue takes direct command lists, which
+/// are the only ones a game renders with.
+///
+/// <h2 id=  is_direct_command_queue_safety>
+/// <a href=#is_direct_command_queue_safety>
+/// Safety
+/// </a></h2>
+///
+/// The address must be a valid Direct3D
+/// 12 command queue, such as the one passed
+/// to a hooked <code>ExecuteCommandLists</code>
+/// call.
+pub unsafe fn is_direct_command_queue(
+   command_queue : usize,
+) -> bool {
+   return crate::os::overlay::is_direct_command_queue(command_queue);
+}
@@ -14,6 +14,12 @@
 #[derive(Debug)]
 pub enum ProcessError {
    BadExecutableFileName,
+   InvalidImage,
+   AccessDenied,
+   RemoteMemoryFailed,
+   InjectionFailed,
+   SpawnFailed,
+   Unsupported,
    Unknown,
 }
 
@@ -33,6 +39,47 @@ pub struct ModuleSnapshot {
    snapshot : crate::os::process::ModuleSnapshot,
 }
 
+/// A handle to another process which
+/// allows reading and writing its memory
+/// from outside of it.  The handle is
+/// closed when this is dropped.
+pub struct RemoteProcess {
+   process : crate::os::process::RemoteProcess,
+}
+
+/// A process which was started with its
+/// main thread suspended, so libraries can
+/// be loaded into it before any of its own
+/// code runs.  If this is dropped without
+/// resuming the process, the process is
+/// terminated.
+pub struct SuspendedProcess {
+   process : crate::os::process::SuspendedProcess,
+}
+
+/// Information the operating system
+/// uses to unwind the stack through
+/// a function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnwindFunction {
+   pub address_range    : std::ops::Range<usize>,
+   pub prologue_length  : usize,
+}
+
+/// A section from a module's section
+/// table, such as <code>.text</code>
+/// or <code>.rdata</code>.  The address
+/// range ends at the section's aligned
+/// size, the same as it is mapped, and
+/// the characteristics are the raw flags
+/// from the section header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Section {
+   pub name             : String,
+   pub address_range    : std::ops::Range<usize>,
+   pub characteristics  : u32,
+}
+
 //////////////////////////////////////////////////
 // TRAIT IMPLEMENTATIONS - ProcessSnapshotError //
 //////////////////////////////////////////////////
@@ -45,6 +92,18 @@ impl std::fmt::Display for ProcessError {
       return write!(stream, "{}", match self {
          Self::BadExecutableFileName
             => "Associated executable file name contains invalid UTF-8",
+         Self::InvalidImage
+            => "Module image is malformed",
+         Self::AccessDenied
+            => "Access to the process was denied",
+         Self::RemoteMemoryFailed
+            => "Failed to access memory in another process",
+         Self::InjectionFailed
+            => "Failed to load a library into another process",
+         Self::SpawnFailed
+            => "Failed to start a new process",
+         Self::Unsupported
+            => "Unsupported on this platform",
          Self::Unknown
             => "Unknown error",
       });
@@ -102,6 +161,14 @@ impl ProcessSnapshot {
    ) -> &'l str {
       return &self.snapshot.executable_name;
    }
+
+   /// Retrieves the process ID assigned
+   /// by the operating system.
+   pub fn process_id(
+      & self,
+   ) -> u32 {
+      return self.snapshot.process_id as u32;
+   }
 }
 
 //////////////////////////////
@@ -146,5 +213,327 @@ impl ModuleSnapshot {
    ) -> &'l str {
       return &self.snapshot.module_name;
    }
+
+   /// Finds the unwind information for
+   /// the function containing an address
+   /// within the module.  If the address
+   /// doesn't belong to a function with
+   /// unwind information, such as a leaf
+   /// function, <code>None</code> is returned.
+   ///
+   /// <h2 id=  module_snapshot_unwind_function_safety>
+   /// <a href=#module_snapshot_unwind_function_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn unwind_function(
+      & self,
+      address : usize,
+   ) -> Result<Option<UnwindFunction>> {
+      return self.snapshot.unwind_function(address);
+   }
+
+   /// Finds the import address table slot
+   /// which holds the address of a function
+   /// imported by name from a library.  The
+   /// library name is compared without regard
+   /// to case.  If the module doesn't import
+   /// the function by name, <code>None</code>
+   /// is returned.
+   ///
+   /// <h2 id=  module_snapshot_import_slot_safety>
+   /// <a href=#module_snapshot_import_slot_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn import_slot(
+      & self,
+      library  : & str,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      return self.snapshot.import_slot(library, function);
+   }
+
+   /// Reads the module's section table,
+   /// sorted by start address.  If the image
+   /// format has no section table, <code>None</code>
+   /// is returned.
+   ///
+   /// <h2 id=  module_snapshot_sections_safety>
+   /// <a href=#module_snapshot_sections_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn sections(
+      & self,
+   ) -> Result<Option<Vec<Section>>> {
+      return self.snapshot.sections();
+   }
+
+
+   /// Gets the ranges of the module's import
+   /// tables relative to the start of the
+   /// module, which are the import descriptors
+   /// and the address table the loader fills
+   /// in.  Modules without imports, and images
+   /// whose import tables aren't located, such
+   /// as on Linux, return an empty list.
+   ///
+   /// <h2 id=  module_snapshot_import_table_ranges_safety>
+   /// <a href=#module_snapshot_import_table_ranges_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn import_table_ranges(
+      & self,
+   ) -> Result<Vec<std::ops::Range<usize>>> {
+      return self.snapshot.import_table_ranges();
+   }
+
+   /// Reads the module's file version from
+   /// its version resource as its major,
+   /// minor, build, and revision numbers.
+   /// If the module has no version resource,
+   /// <code>None</code> is returned.
+   ///
+   /// <h2 id=  module_snapshot_file_version_safety>
+   /// <a href=#module_snapshot_file_version_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn file_version(
+      & self,
+   ) -> Result<Option<[u16; 4]>> {
+      return self.snapshot.file_version();
+   }
+
+   /// Finds the offset of a function the
+   /// module exports by name, relative to
+   /// the start of the module.  If the
+   /// module doesn't export the function
+   /// or forwards it to another module,
+   /// <code>None</code> is returned.
+   ///
+   /// <h2 id=  module_snapshot_export_rva_safety>
+   /// <a href=#module_snapshot_export_rva_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn export_rva(
+      & self,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      return self.snapshot.export_rva(function);
+   }
+
+   /// Converts an offset within the module's
+   /// file on disk into an offset relative
+   /// to the start of the loaded module, or
+   /// <code>None</code> if the file offset
+   /// isn't loaded into memory.
+   ///
+   /// <h2 id=  module_snapshot_file_offset_to_rva_safety>
+   /// <a href=#module_snapshot_file_offset_to_rva_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn file_offset_to_rva(
+      & self,
+      file_offset : usize,
+   ) -> Result<Option<usize>> {
+      return self.snapshot.file_offset_to_rva(file_offset);
+   }
+}
+
+///////////////////////
+// METHODS - Section //
+///////////////////////
+
+impl Section {
+   /// Checks whether the section
+   /// contains executable code.
+   pub fn is_executable(
+      & self,
+   ) -> bool {
+      return crate::os::process::section_is_executable(self.characteristics);
+   }
+
+   /// Checks whether the section
+   /// is mapped readable.
+   pub fn is_readable(
+      & self,
+   ) -> bool {
+      return crate::os::process::section_is_readable(self.characteristics);
+   }
+
+   /// Checks whether the section
+   /// is mapped writable.
+   pub fn is_writable(
+      & self,
+   ) -> bool {
+      return crate::os::process::section_is_writable(self.characteristics);
+   }
+}
+
+/////////////////////////////
+// METHODS - RemoteProcess //
+/////////////////////////////
+
+impl RemoteProcess {
+   /// Opens a process for reading and
+   /// writing its memory.
+   pub fn open(
+      process : & ProcessSnapshot,
+   ) -> Result<Self> {
+      return Ok(Self{
+         process : crate::os::process::RemoteProcess::open(&process.snapshot)?,
+      });
+   }
+
+   /// Reads memory from the process
+   /// into a buffer, failing unless the
+   /// whole buffer could be filled.
+   pub fn read(
+      & self,
+      address  : usize,
+      buffer   : & mut [u8],
+   ) -> Result<()> {
+      return self.process.read(address, buffer);
+   }
+
+   /// Writes bytes into the memory of
+   /// the process.  The memory is made
+   /// writable while writing, so this
+   /// can also patch code.
+   pub fn write(
+      & self,
+      address  : usize,
+      bytes    : & [u8],
+   ) -> Result<()> {
+      return self.process.write(address, bytes);
+   }
+
+   /// Loads a library into the process by
+   /// running the system's library loader on
+   /// a new thread within it, then waits for
+   /// the library to finish loading.
+   pub fn load_library(
+      & self,
+      library_path : & std::path::Path,
+   ) -> Result<()> {
+      return self.process.load_library(library_path);
+   }
+}
+
+////////////////////////////////
+// METHODS - SuspendedProcess //
+////////////////////////////////
+
+impl SuspendedProcess {
+   /// Starts an executable with its main
+   /// thread suspended.  The working directory
+   /// is the executable's own directory, since
+   /// games usually find their data relative
+   /// to it.
+   pub fn spawn(
+      executable_path   : & std::path::Path,
+      arguments         : & [std::ffi::OsString],
+   ) -> Result<Self> {
+      return Ok(Self{
+         process : crate::os::process::SuspendedProcess::spawn(executable_path, arguments)?,
+      });
+   }
+
+   /// Gets the process ID of the process.
+   pub fn process_id(
+      & self,
+   ) -> u32 {
+      return self.process.process_id as u32;
+   }
+
+   /// Loads a library into the process
+   /// and waits for it to finish loading,
+   /// including running its entrypoint.
+   pub fn load_library(
+      & self,
+      library_path : & std::path::Path,
+   ) -> Result<()> {
+      return self.process.load_library(library_path);
+   }
+
+   /// Waits for a library loaded into the
+   /// process to call <code>signal_ready</code>,
+   /// returning false if the timeout passes
+   /// first.  Without a timeout, this waits
+   /// forever.
+   pub fn wait_ready(
+      & self,
+      timeout : Option<std::time::Duration>,
+   ) -> Result<bool> {
+      return self.process.wait_ready(timeout);
+   }
+
+   /// Resumes the process' main thread.
+   /// If this fails, the process is
+   /// terminated.
+   pub fn resume(
+      self,
+   ) -> Result<()> {
+      return self.process.resume();
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Gets the full file path of the module
+/// loaded in the local process which
+/// contains an address, such as the path
+/// of a mod's own library.
+pub fn module_file_path(
+   address : usize,
+) -> Result<std::path::PathBuf> {
+   return crate::os::process::module_file_path(address);
 }
 
+/// Tells the launcher which started the
+/// local process with <code>SuspendedProcess</code>
+/// that it can be resumed.  This does
+/// nothing if no launcher is waiting.
+pub fn signal_ready(
+) -> Result<()> {
+   return crate::os::process::signal_ready();
+}
+
+/// Writes a minidump of the local process
+/// to a file, which can be opened in a
+/// debugger to inspect every thread's stack
+/// and registers after a crash.  This is
+/// only supported on Windows.
+pub fn write_minidump(
+   file_path : & std::path::Path,
+) -> Result<()> {
+   return crate::os::process::write_minidump(file_path);
+}
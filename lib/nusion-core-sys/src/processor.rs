@@ -0,0 +1,31 @@
+//! Processor information functions.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Instruction set extensions supported
+/// by the processor and operating system.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Features {
+   pub sse4_1  : bool,
+   pub sse4_2  : bool,
+   pub popcnt  : bool,
+   pub avx     : bool,
+   pub avx2    : bool,
+   pub fma     : bool,
+   pub bmi1    : bool,
+   pub bmi2    : bool,
+   pub lzcnt   : bool,
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Queries which instruction set
+/// extensions can be used on the
+/// current machine.
+pub fn features() -> Features {
+   return crate::cpu::processor::features();
+}
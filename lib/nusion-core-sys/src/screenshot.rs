@@ -0,0 +1,64 @@
+//! Capturing the contents of the
+//! game's window.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to capturing a screenshot.
+#[derive(Debug)]
+pub enum ScreenshotError {
+   WindowNotFound,
+   Unsupported,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ScreenshotError</code>.
+pub type Result<T> = std::result::Result<T, ScreenshotError>;
+
+/// A captured image stored as rows of
+/// 8-bit RGBA pixels, top row first.
+#[derive(Clone, Debug)]
+pub struct Frame {
+   pub width   : usize,
+   pub height  : usize,
+   pub pixels  : Vec<u8>,
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ScreenshotError //
+/////////////////////////////////////////////
+
+impl std::fmt::Display for ScreenshotError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::WindowNotFound
+            => "The process has no visible window",
+         Self::Unsupported
+            => "Unsupported on this platform",
+         Self::Unknown
+            => "Unknown",
+      });
+   }
+}
+
+impl std::error::Error for ScreenshotError {
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Captures the client area of the
+/// largest visible window owned by
+/// the current process.
+pub fn capture_process_window(
+) -> Result<Frame> {
+   return crate::os::screenshot::capture_process_window();
+}
+
@@ -0,0 +1,142 @@
+//! Suspending the other threads in the
+//! current process while code is patched,
+//! and sampling where they're executing.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to suspending threads.
+#[derive(Debug)]
+pub enum ThreadError {
+   Unsupported,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ThreadError</code>.
+pub type Result<T> = std::result::Result<T, ThreadError>;
+
+/// Every thread in the process other
+/// than the calling thread, suspended
+/// until this is dropped.
+///
+/// <h2 id=  suspended_threads_safety>
+/// <a href=#suspended_threads_safety>
+/// Safety
+/// </a></h2>
+///
+/// A suspended thread may be holding a
+/// lock, such as the heap lock, so the
+/// calling thread must not allocate or
+/// take any lock which another thread
+/// could hold until this is dropped.
+pub struct SuspendedThreads {
+   threads : crate::os::thread::SuspendedThreads,
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ThreadError //
+/////////////////////////////////////////
+
+impl std::fmt::Display for ThreadError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::Unsupported
+            => "Unsupported on this platform",
+         Self::Unknown
+            => "Unknown",
+      });
+   }
+}
+
+impl std::error::Error for ThreadError {
+}
+
+////////////////////////////////
+// METHODS - SuspendedThreads //
+////////////////////////////////
+
+impl SuspendedThreads {
+   /// Suspends every thread in the current
+   /// process other than the calling thread.
+   ///
+   /// <h2 id=  suspended_threads_suspend_others_safety>
+   /// <a href=#suspended_threads_suspend_others_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// See the safety section for <code><a href=
+   /// #suspended_threads_safety>SuspendedThreads</a></code>.
+   pub unsafe fn suspend_others(
+   ) -> Result<Self> {
+      return Ok(Self{
+         threads : crate::os::thread::SuspendedThreads::suspend_others()?,
+      });
+   }
+
+   /// Suspends every thread in the current
+   /// process other than the calling thread
+   /// and the threads with the given IDs.
+   ///
+   /// <h2 id=  suspended_threads_suspend_others_except_safety>
+   /// <a href=#suspended_threads_suspend_others_except_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// See the safety section for <code><a href=
+   /// #suspended_threads_safety>SuspendedThreads</a></code>.
+   pub unsafe fn suspend_others_except(
+      excluded_thread_ids : & [u32],
+   ) -> Result<Self> {
+      return Ok(Self{
+         threads : crate::os::thread::SuspendedThreads::suspend_others_except(excluded_thread_ids)?,
+      });
+   }
+
+   /// Gets the instruction pointer of
+   /// every suspended thread.
+   pub fn instruction_pointers<'l>(
+      &'l self,
+   ) -> &'l [usize] {
+      return self.threads.instruction_pointers();
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Samples the instruction pointer of
+/// every thread in the current process
+/// other than the calling thread.  Each
+/// thread is suspended only while its
+/// context is read, so the process keeps
+/// running between samples.  Threads which
+/// exit while being sampled are skipped.
+pub fn sample_instruction_pointers(
+) -> Result<Vec<usize>> {
+   return crate::os::thread::sample_instruction_pointers();
+}
+
+/// Gets the operating system's ID for
+/// the calling thread, the same ID shown
+/// by debuggers and system tools.
+pub fn current_thread_id(
+) -> u32 {
+   return crate::os::thread::current_thread_id();
+}
+
+/// Flushes the instruction cache for
+/// an address range so newly written
+/// code is executed.
+pub fn flush_instruction_cache(
+   address_range : std::ops::Range<usize>,
+) -> Result<()> {
+   return crate::os::thread::flush_instruction_cache(address_range);
+}
+
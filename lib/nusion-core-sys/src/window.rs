@@ -0,0 +1,319 @@
+//! Finding the game's main window and
+//! intercepting the messages sent to it.
+
+use std::sync::{Arc, RwLock};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Contains error information relating
+/// to the game's window.
+#[derive(Debug)]
+pub enum WindowError {
+   WindowNotFound,
+   SubclassFailed,
+   TooManyWindows{
+      maximum  : usize,
+   },
+   Unsupported,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>WindowError</code>.
+pub type Result<T> = std::result::Result<T, WindowError>;
+
+/// A window owned by the current process,
+/// identified by its raw handle.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Window {
+   handle : usize,
+}
+
+/// A message sent to a window, with its
+/// parameters as the raw values passed to
+/// the window procedure.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WindowMessage {
+   pub message : u32,
+   pub w_param : usize,
+   pub l_param : isize,
+}
+
+/// What to do with a message after
+/// a message hook has seen it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageAction {
+   /// Passes the message on to the next
+   /// hook, then to the game's own window
+   /// procedure.
+   Forward,
+   /// Stops the message from reaching the
+   /// game, returning the given result from
+   /// the window procedure instead.
+   Handled(isize),
+}
+
+/// Function called from the window
+/// procedure for every message sent
+/// to a hooked window.
+pub type MessageCallback = dyn Fn(& WindowMessage) -> MessageAction + Send + Sync;
+
+/// A registered window and the function
+/// called for messages sent to it.  Only
+/// Windows has a window procedure to call
+/// it from.
+#[cfg_attr(not(windows), allow(dead_code))]
+struct MessageHook {
+   id       : usize,
+   window   : Window,
+   callback : Arc<MessageCallback>,
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - WindowError //
+/////////////////////////////////////////
+
+impl std::fmt::Display for WindowError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::WindowNotFound
+            => write!(stream, "The process has no visible window"),
+         Self::SubclassFailed
+            => write!(stream, "Failed to replace the window procedure"),
+         Self::TooManyWindows{maximum}
+            => write!(stream, "No more than {maximum} windows can be subclassed"),
+         Self::Unsupported
+            => write!(stream, "Unsupported on this platform"),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+      };
+   }
+}
+
+impl std::error::Error for WindowError {
+}
+
+//////////////////////
+// METHODS - Window //
+//////////////////////
+
+impl Window {
+   /// Finds the main window of the current
+   /// process, which is the largest visible
+   /// window it owns.  This fails if the game
+   /// hasn't created its window yet.
+   pub fn find_process_window(
+   ) -> Result<Self> {
+      return Ok(Self{
+         handle : crate::os::window::find_process_window()
+            .ok_or(WindowError::WindowNotFound)?,
+      });
+   }
+
+   /// Gets the raw handle of the window.
+   pub fn handle(
+      & self,
+   ) -> usize {
+      return self.handle;
+   }
+
+   /// Checks whether the window is the
+   /// foreground window, meaning it has
+   /// the user's input focus.
+   pub fn is_foreground(
+      & self,
+   ) -> bool {
+      return crate::os::window::is_foreground(self.handle);
+   }
+
+   /// Registers a function which is called
+   /// for every message sent to the window,
+   /// returning an ID which can be used to
+   /// remove it again.  The window procedure
+   /// is replaced when the first function is
+   /// registered for a window.  Functions
+   /// run in the order they were registered,
+   /// on the window's own thread, and the
+   /// first to handle a message stops it
+   /// from reaching the rest.
+   pub fn add_message_hook(
+      & self,
+      callback : Arc<MessageCallback>,
+   ) -> Result<usize> {
+      let mut hooks = lock_hooks_write();
+      if hooks.iter().any(|hook| hook.window == *self) == false {
+         crate::os::window::subclass(self.handle)?;
+      }
+
+      let id = NEXT_HOOK_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+      hooks.push(MessageHook{
+         id       : id,
+         window   : *self,
+         callback : callback,
+      });
+
+      return Ok(id);
+   }
+}
+
+/////////////////////////////
+// METHODS - WindowMessage //
+/////////////////////////////
+
+impl WindowMessage {
+   /// <code>WM_SETFOCUS</code>
+   pub const SET_FOCUS        : u32 = 0x0007;
+   /// <code>WM_KILLFOCUS</code>
+   pub const KILL_FOCUS       : u32 = 0x0008;
+   /// <code>WM_ACTIVATEAPP</code>
+   pub const ACTIVATE_APP     : u32 = 0x001C;
+   /// <code>WM_SETCURSOR</code>
+   pub const SET_CURSOR       : u32 = 0x0020;
+   /// <code>WM_INPUT</code>
+   pub const INPUT            : u32 = 0x00FF;
+   /// <code>WM_KEYDOWN</code>
+   pub const KEY_DOWN         : u32 = 0x0100;
+   /// <code>WM_KEYUP</code>
+   pub const KEY_UP           : u32 = 0x0101;
+   /// <code>WM_CHAR</code>
+   pub const CHAR             : u32 = 0x0102;
+   /// <code>WM_SYSKEYDOWN</code>
+   pub const SYS_KEY_DOWN     : u32 = 0x0104;
+   /// <code>WM_SYSKEYUP</code>
+   pub const SYS_KEY_UP       : u32 = 0x0105;
+   /// <code>WM_MOUSEMOVE</code>
+   pub const MOUSE_MOVE       : u32 = 0x0200;
+   /// <code>WM_LBUTTONDOWN</code>
+   pub const MOUSE_LEFT_DOWN  : u32 = 0x0201;
+   /// <code>WM_LBUTTONUP</code>
+   pub const MOUSE_LEFT_UP    : u32 = 0x0202;
+   /// <code>WM_RBUTTONDOWN</code>
+   pub const MOUSE_RIGHT_DOWN : u32 = 0x0204;
+   /// <code>WM_RBUTTONUP</code>
+   pub const MOUSE_RIGHT_UP   : u32 = 0x0205;
+   /// <code>WM_MOUSEWHEEL</code>
+   pub const MOUSE_WHEEL      : u32 = 0x020A;
+
+   /// Checks whether the message is
+   /// keyboard, mouse, or raw input, which
+   /// is what a menu blocks from reaching
+   /// the game while it is open.
+   pub fn is_input(
+      & self,
+   ) -> bool {
+      return self.message == Self::INPUT
+         || (Self::KEY_DOWN..=0x0109).contains(&self.message)
+         || (Self::MOUSE_MOVE..=0x020E).contains(&self.message);
+   }
+
+   /// Checks whether the message means the
+   /// window gained or lost focus.
+   pub fn is_focus_change(
+      & self,
+   ) -> bool {
+      return self.message == Self::SET_FOCUS
+         || self.message == Self::KILL_FOCUS
+         || self.message == Self::ACTIVATE_APP;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Removes a message hook registered with
+/// <code>Window::add_message_hook</code>.
+/// When the last hook for a window is
+/// removed, its window procedure is put
+/// back unless something else replaced
+/// it since.
+pub fn remove_message_hook(
+   id : usize,
+) {
+   let mut hooks = lock_hooks_write();
+   let window = match hooks.iter().find(|hook| hook.id == id) {
+      Some(hook)  => hook.window,
+      None        => return,
+   };
+
+   hooks.retain(|hook| hook.id != id);
+   if hooks.iter().any(|hook| hook.window == window) == false {
+      crate::os::window::unsubclass(window.handle);
+   }
+
+   return;
+}
+
+/// Removes every message hook and puts
+/// back every window procedure.  This
+/// must be done before the library is
+/// unloaded, since the windows would
+/// otherwise be left calling freed code.
+/// A procedure which something else has
+/// replaced since can't be put back, so
+/// the library is kept loaded instead.
+pub fn uninstall(
+) {
+   let mut hooks = lock_hooks_write();
+   for hook in hooks.iter() {
+      crate::os::window::unsubclass(hook.window.handle);
+   }
+
+   hooks.clear();
+   return;
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn lock_hooks_write(
+) -> std::sync::RwLockWriteGuard<'static, Vec<MessageHook>> {
+   return HOOKS.write().unwrap_or_else(|poison| poison.into_inner());
+}
+
+/// Called by the OS window procedure for
+/// every message sent to a hooked window,
+/// returning the result to return from the
+/// window procedure, or <code>None</code>
+/// to pass the message on to the game.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn dispatch_message(
+   window   : usize,
+   message  : & WindowMessage,
+) -> Option<isize> {
+   // The lock isn't held while the hooks
+   // run, so they can add and remove hooks
+   // and send messages of their own
+   let callbacks = HOOKS.read()
+      .unwrap_or_else(|poison| poison.into_inner())
+      .iter()
+      .filter(|hook| hook.window.handle == window)
+      .map(|hook| Arc::clone(&hook.callback))
+      .collect::<Vec<Arc<MessageCallback>>>();
+
+   for callback in callbacks {
+      if let MessageAction::Handled(result) = callback(message) {
+         return Some(result);
+      }
+   }
+
+   return None;
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static HOOKS
+   : RwLock<Vec<MessageHook>>
+   = RwLock::new(Vec::new());
+
+static NEXT_HOOK_ID
+   : std::sync::atomic::AtomicUsize
+   = std::sync::atomic::AtomicUsize::new(0);
@@ -0,0 +1,140 @@
+//! Build script for nusion-core, which
+//! records information about the build
+//! for <code>nusion_core::BUILD_INFO</code>.
+
+fn main() {
+   let manifest_directory = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+
+   // Source tarballs and vendored copies
+   // have no repository to ask
+   let git = |arguments : & [& str]| {
+      return std::process::Command::new("git")
+         .current_dir(&manifest_directory)
+         .args(arguments)
+         .output()
+         .ok()
+         .filter(|output| output.status.success() == true)
+         .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+   };
+
+   // Only this crate's own sources count
+   // towards the dirty marker, since those
+   // are the only ones which rerun this
+   let git_hash = match (git(&["rev-parse", "--short=12", "HEAD"]), git(&["status", "--porcelain", "--untracked-files=no", "--", "."])) {
+      (Some(hash), Some(status)) if status.is_empty() == false
+         => format!("{hash}-dirty"),
+      (Some(hash), _)
+         => hash,
+      (None, _)
+         => String::new(),
+   };
+
+   // Rebuild when the checked out commit
+   // changes or the sources are edited,
+   // instead of on every build.  Cargo
+   // treats a missing path as always
+   // changed, so only existing ones are
+   // watched.
+   let rerun_if_changed = |path : & std::path::Path| {
+      if path.exists() == true {
+         println!("cargo:rerun-if-changed={}", path.display());
+      }
+   };
+   if let Some(git_directory) = git(&["rev-parse", "--absolute-git-dir"]) {
+      let git_directory = std::path::Path::new(&git_directory);
+      rerun_if_changed(&git_directory.join("HEAD"));
+      rerun_if_changed(&git_directory.join("index"));
+      rerun_if_changed(&git_directory.join("packed-refs"));
+      if let Some(reference) = git(&["symbolic-ref", "-q", "HEAD"]) {
+         rerun_if_changed(&git_directory.join(reference));
+      }
+   }
+   rerun_if_changed(std::path::Path::new("src"));
+   rerun_if_changed(std::path::Path::new("Cargo.toml"));
+   rerun_if_changed(std::path::Path::new("build.rs"));
+   println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+   // The time stamp comes from the sources
+   // instead of the clock, so the same
+   // sources always build the same library
+   let timestamp = std::env::var("SOURCE_DATE_EPOCH").ok()
+      .and_then(|timestamp| timestamp.parse::<u64>().ok())
+      .or_else(|| git(&["log", "-1", "--format=%ct"]).and_then(|timestamp| timestamp.parse::<u64>().ok()))
+      .unwrap_or(0);
+
+   println!("cargo:rustc-env=NUSION_BUILD_GIT_HASH={git_hash}");
+   println!("cargo:rustc-env=NUSION_BUILD_TIMESTAMP={timestamp}");
+   println!("cargo:rustc-env=NUSION_BUILD_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+   println!("cargo:rustc-env=NUSION_BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+
+   if std::env::var_os("CARGO_FEATURE_DENY_NETWORK").is_some() {
+      audit_network();
+   }
+
+   return;
+}
+
+// Crates which exist to reach the network,
+// so depending on any of them means a mod
+// could phone home
+const NETWORK_CRATES : & [& str] = &[
+   "attohttpc",
+   "curl",
+   "h2",
+   "hickory-resolver",
+   "hyper",
+   "isahc",
+   "native-tls",
+   "quinn",
+   "reqwest",
+   "rustls",
+   "socket2",
+   "surf",
+   "trust-dns-resolver",
+   "tungstenite",
+   "ureq",
+   "websocket",
+];
+
+// Fails the build if the lock file of the
+// workspace being built contains any crate
+// which can reach the network.  The lock
+// file is found next to the target directory
+// or above this crate's manifest, and the
+// audit fails if it can't be found, since
+// nothing could be proven otherwise.
+fn audit_network() {
+   let directories = ["OUT_DIR", "CARGO_MANIFEST_DIR"].iter()
+      .filter_map(|variable| std::env::var_os(variable))
+      .map(std::path::PathBuf::from)
+      .collect::<Vec<std::path::PathBuf>>();
+
+   let lock_file = directories.iter()
+      .flat_map(|directory| directory.ancestors())
+      .map(|directory| directory.join("Cargo.lock"))
+      .find(|lock_file| lock_file.is_file() == true)
+      .unwrap_or_else(|| panic!("The \"deny-network\" feature is enabled, but no Cargo.lock was found to audit"));
+   println!("cargo:rerun-if-changed={}", lock_file.display());
+
+   let lock = std::fs::read_to_string(&lock_file)
+      .unwrap_or_else(|err| panic!("Failed to read {}: {err}", lock_file.display()));
+
+   let mut found = lock.lines()
+      .filter_map(|line| line.trim().strip_prefix("name = \""))
+      .filter_map(|name| name.strip_suffix('"'))
+      .filter(|name| NETWORK_CRATES.contains(name) == true)
+      .collect::<Vec<&str>>();
+   found.sort_unstable();
+   found.dedup();
+
+   if found.is_empty() == false {
+      panic!(
+         "The \"deny-network\" feature is enabled, but {} depends on networking crates: {}",
+         lock_file.display(),
+         found.join(", "),
+      );
+   }
+
+   return;
+}
+
@@ -0,0 +1,253 @@
+//! Memory usage tracking and limits
+//! for allocations made by the mod.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// A global allocator which tracks how
+/// much memory the mod has allocated and
+/// optionally refuses allocations past a
+/// cap.  This only sees allocations made
+/// by the mod itself and not by the game,
+/// and must be registered as the mod's
+/// global allocator to take effect.
+///
+/// ```
+/// #[global_allocator]
+/// static ALLOCATOR : nusion_core::allocator::GuardedAllocator
+///    = nusion_core::allocator::GuardedAllocator::new(
+///       256 * 1024 * 1024,
+///       nusion_core::allocator::CapPolicy::Report,
+///    );
+///
+/// // Somewhere in the main loop
+/// let statistics = ALLOCATOR.statistics();
+/// if statistics.cap_exceeded == true {
+///    println!("Mod memory usage is too high: {statistics}");
+/// }
+/// ```
+pub struct GuardedAllocator {
+   cap               : AtomicUsize,
+   deny              : AtomicBool,
+   current_bytes     : AtomicUsize,
+   peak_bytes        : AtomicUsize,
+   allocation_count  : AtomicUsize,
+   denied_count      : AtomicUsize,
+   cap_exceeded      : AtomicBool,
+}
+
+/// What happens when an allocation
+/// would exceed the cap.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CapPolicy {
+   Report,
+   Deny,
+}
+
+/// A snapshot of the allocator's
+/// statistics.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllocationStatistics {
+   pub cap              : usize,
+   pub current_bytes    : usize,
+   pub peak_bytes       : usize,
+   pub allocation_count : usize,
+   pub denied_count     : usize,
+   pub cap_exceeded     : bool,
+}
+
+////////////////////////////////
+// METHODS - GuardedAllocator //
+////////////////////////////////
+
+impl GuardedAllocator {
+   /// Creates a new allocator with a cap
+   /// in bytes and a policy for when it
+   /// is exceeded.  <code>CapPolicy::Deny</code>
+   /// makes the allocation fail, which will
+   /// usually abort the process, so it is
+   /// best used to catch runaway memory
+   /// usage during development.
+   pub const fn new(
+      cap      : usize,
+      policy   : CapPolicy,
+   ) -> Self {
+      return Self{
+         cap               : AtomicUsize::new(cap),
+         deny              : AtomicBool::new(match policy {
+            CapPolicy::Report => false,
+            CapPolicy::Deny   => true,
+         }),
+         current_bytes     : AtomicUsize::new(0),
+         peak_bytes        : AtomicUsize::new(0),
+         allocation_count  : AtomicUsize::new(0),
+         denied_count      : AtomicUsize::new(0),
+         cap_exceeded      : AtomicBool::new(false),
+      };
+   }
+
+   /// Creates a new allocator which
+   /// only tracks statistics.
+   pub const fn unlimited() -> Self {
+      return Self::new(usize::MAX, CapPolicy::Report);
+   }
+
+   /// Changes the cap in bytes and the
+   /// policy for when it is exceeded.
+   pub fn set_cap(
+      & self,
+      cap      : usize,
+      policy   : CapPolicy,
+   ) -> & Self {
+      self.cap.store(cap, Ordering::Relaxed);
+      self.deny.store(policy == CapPolicy::Deny, Ordering::Relaxed);
+      return self;
+   }
+
+   /// Takes a snapshot of the allocator's
+   /// statistics.
+   pub fn statistics(
+      & self,
+   ) -> AllocationStatistics {
+      return AllocationStatistics{
+         cap               : self.cap              .load(Ordering::Relaxed),
+         current_bytes     : self.current_bytes    .load(Ordering::Relaxed),
+         peak_bytes        : self.peak_bytes       .load(Ordering::Relaxed),
+         allocation_count  : self.allocation_count .load(Ordering::Relaxed),
+         denied_count      : self.denied_count     .load(Ordering::Relaxed),
+         cap_exceeded      : self.cap_exceeded     .load(Ordering::Relaxed),
+      };
+   }
+
+   /// Clears the flag which records that
+   /// the cap was exceeded, so it can be
+   /// reported again.
+   pub fn clear_cap_exceeded(
+      & self,
+   ) -> & Self {
+      self.cap_exceeded.store(false, Ordering::Relaxed);
+      return self;
+   }
+}
+
+/////////////////////////////////////////
+// INTERNAL METHODS - GuardedAllocator //
+/////////////////////////////////////////
+
+impl GuardedAllocator {
+   // Reserves bytes against the cap, returning
+   // false if the allocation should be denied
+   fn reserve(
+      & self,
+      size : usize,
+   ) -> bool {
+      let cap     = self.cap.load(Ordering::Relaxed);
+      let current = self.current_bytes.fetch_add(size, Ordering::Relaxed) + size;
+
+      if current > cap {
+         self.cap_exceeded.store(true, Ordering::Relaxed);
+
+         if self.deny.load(Ordering::Relaxed) == true {
+            self.current_bytes.fetch_sub(size, Ordering::Relaxed);
+            self.denied_count.fetch_add(1, Ordering::Relaxed);
+            return false;
+         }
+      }
+
+      self.peak_bytes.fetch_max(current, Ordering::Relaxed);
+      self.allocation_count.fetch_add(1, Ordering::Relaxed);
+      return true;
+   }
+
+   fn release(
+      & self,
+      size : usize,
+   ) {
+      self.current_bytes.fetch_sub(size, Ordering::Relaxed);
+      return;
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - GuardedAllocator //
+//////////////////////////////////////////////
+
+unsafe impl std::alloc::GlobalAlloc for GuardedAllocator {
+   unsafe fn alloc(
+      & self,
+      layout : std::alloc::Layout,
+   ) -> * mut u8 {
+      if self.reserve(layout.size()) == false {
+         return std::ptr::null_mut();
+      }
+
+      let pointer = std::alloc::System.alloc(layout);
+      if pointer.is_null() == true {
+         self.release(layout.size());
+      }
+
+      return pointer;
+   }
+
+   unsafe fn dealloc(
+      & self,
+      pointer  : * mut u8,
+      layout   : std::alloc::Layout,
+   ) {
+      std::alloc::System.dealloc(pointer, layout);
+      self.release(layout.size());
+      return;
+   }
+
+   unsafe fn realloc(
+      & self,
+      pointer  : * mut u8,
+      layout   : std::alloc::Layout,
+      new_size : usize,
+   ) -> * mut u8 {
+      // Only account for the difference so a
+      // shrinking reallocation is never denied
+      if new_size > layout.size() {
+         if self.reserve(new_size - layout.size()) == false {
+            return std::ptr::null_mut();
+         }
+      } else {
+         self.release(layout.size() - new_size);
+      }
+
+      let new_pointer = std::alloc::System.realloc(pointer, layout, new_size);
+      if new_pointer.is_null() == true {
+         // The old allocation is still alive
+         if new_size > layout.size() {
+            self.release(new_size - layout.size());
+         } else {
+            self.current_bytes.fetch_add(layout.size() - new_size, Ordering::Relaxed);
+         }
+      }
+
+      return new_pointer;
+   }
+}
+
+//////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - AllocationStatistics //
+//////////////////////////////////////////////////
+
+impl std::fmt::Display for AllocationStatistics {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream,
+         "{} bytes in use, {} bytes peak, {} bytes cap, {} allocations, {} denied",
+         self.current_bytes,
+         self.peak_bytes,
+         self.cap,
+         self.allocation_count,
+         self.denied_count,
+      );
+   }
+}
@@ -0,0 +1,198 @@
+//! Runtime assembly of a small subset of
+//! x86_64 instructions, for patches which
+//! depend on addresses or values that are
+//! only known once the game is running,
+//! and decoding of instruction boundaries.
+//!
+//! Code which is known ahead of time should
+//! use <code>asm_bytes!</code> instead, which
+//! assembles the same instructions while
+//! the mod is compiled.
+//!
+//! ```
+//! use nusion_core::asm::{Address, Assembler, Condition, Register};
+//!
+//! let mut asm = Assembler::new(code_address);
+//! let skip    = asm.new_label();
+//!
+//! asm.cmp(Address::Base{base : Register::Rcx, displacement : 0x10}.dword(), 0)?
+//!    .jcc(Condition::Equal, skip)?
+//!    .mov(Register::Rax, Address::Absolute{address : health_address})?
+//!    .call(damage_function)?
+//!    .bind(skip)?
+//!    .ret()?;
+//!
+//! let bytes = asm.finish()?;
+//! ```
+
+// Decoded instruction information is
+// provided by the system abstraction
+// layer as-is
+pub use crate::sys::decoder::{
+   ControlFlow,
+   Instruction,
+   RelativeOperand,
+};
+
+// The assembler is also used by
+// asm_bytes! while expanding, so it
+// lives in the system abstraction layer
+pub use crate::sys::assembler::{
+   Address,
+   AsmError,
+   Assembler,
+   Condition,
+   Label,
+   Operand,
+   Register,
+   Result,
+   Size,
+   Target,
+};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Iterator over the instructions in a
+/// byte buffer, created by <code>instructions</code>.
+#[derive(Clone, Debug)]
+pub struct Instructions<'b> {
+   bytes    : &'b [u8],
+   offset   : usize,
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Instructions //
+//////////////////////////////////////////
+
+impl<'b> Iterator for Instructions<'b> {
+   type Item = (usize, Instruction);
+
+   fn next(
+      & mut self,
+   ) -> Option<Self::Item> {
+      let instruction = decode(self.bytes.get(self.offset..)?)?;
+      let offset = self.offset;
+
+      self.offset += instruction.length;
+      return Some((offset, instruction));
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Decodes the instruction at the start
+/// of a byte buffer.  If the bytes don't
+/// encode a valid instruction or the
+/// buffer is too short to contain all of
+/// it, <code>None</code> is returned.
+pub fn decode(
+   bytes : & [u8],
+) -> Option<Instruction> {
+   return crate::sys::decoder::decode_instruction(bytes);
+}
+
+/// Gets the length of the instruction
+/// at the start of a byte buffer, or
+/// <code>None</code> if it can't be decoded.
+pub fn decode_len(
+   bytes : & [u8],
+) -> Option<usize> {
+   return decode(bytes).map(|instruction| instruction.length);
+}
+
+/// Iterates over the instructions in a
+/// byte buffer along with their offsets,
+/// stopping at the end of the buffer or
+/// the first instruction which can't be
+/// decoded.  The buffer should start on
+/// an instruction boundary, otherwise the
+/// instructions will be garbage.
+///
+/// ```
+/// for (offset, instruction) in nusion_core::asm::instructions(function_bytes) {
+///    println!("{offset:#X}: {} bytes", instruction.length);
+/// }
+/// ```
+pub fn instructions<'b>(
+   bytes : &'b [u8],
+) -> Instructions<'b> {
+   return Instructions{
+      bytes    : bytes,
+      offset   : 0,
+   };
+}
+
+///////////
+// TESTS //
+///////////
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn assemble(
+      build : impl FnOnce(& mut Assembler) -> Result<& mut Assembler>,
+   ) -> Vec<u8> {
+      let mut asm = Assembler::new(0x1000);
+      build(&mut asm).expect("instruction should assemble");
+      return asm.finish().expect("code should finish");
+   }
+
+   #[test]
+   fn mov_register_rex_b() {
+      assert_eq!(assemble(|asm| asm.mov(Register::R9, Register::Rax)), [0x49, 0x89, 0xC1]);
+      assert_eq!(assemble(|asm| asm.mov(Register::R8.dword(), Register::Rcx.dword())), [0x41, 0x89, 0xC8]);
+   }
+
+   #[test]
+   fn mov_register_rex_r() {
+      assert_eq!(assemble(|asm| asm.mov(Register::Rax, Register::R9)), [0x4C, 0x89, 0xC8]);
+   }
+
+   #[test]
+   fn mov_register_rex_r_and_b() {
+      assert_eq!(assemble(|asm| asm.mov(Register::R15, Register::R8)), [0x4D, 0x89, 0xC7]);
+   }
+
+   #[test]
+   fn mov_byte_register_forces_rex() {
+      assert_eq!(assemble(|asm| asm.mov(Register::Rsi.byte(), Register::Rdi.byte())), [0x40, 0x88, 0xFE]);
+   }
+
+   #[test]
+   fn arithmetic_immediate_rex_b() {
+      assert_eq!(assemble(|asm| asm.add(Register::R9, 1)), [0x49, 0x83, 0xC1, 0x01]);
+   }
+
+   #[test]
+   fn memory_base_rex_b() {
+      // r12 needs a SIB byte and r13
+      // needs a displacement, like rsp
+      // and rbp
+      assert_eq!(
+         assemble(|asm| asm.mov(Register::Rax, Address::Base{base : Register::R12, displacement : 0})),
+         [0x49, 0x8B, 0x04, 0x24],
+      );
+      assert_eq!(
+         assemble(|asm| asm.mov(Register::Rax, Address::Base{base : Register::R13, displacement : 0})),
+         [0x49, 0x8B, 0x45, 0x00],
+      );
+   }
+
+   #[test]
+   fn memory_indexed_rex_r_and_x() {
+      assert_eq!(
+         assemble(|asm| asm.mov(Register::R10, Address::Indexed{
+            base           : Register::Rcx,
+            index          : Register::R11,
+            scale          : 8,
+            displacement   : 0x10,
+         })),
+         [0x4E, 0x8B, 0x54, 0xD9, 0x10],
+      );
+   }
+}
@@ -0,0 +1,247 @@
+//! Key bindings for toggling features
+//! from the keyboard or the console.
+//! Binding tables are usually generated
+//! with the <code>bindings!</code> macro.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to a binding table.
+#[derive(Debug)]
+pub enum BindingError {
+   UnknownFeature{
+      name  : String,
+   },
+   UnknownKey{
+      name  : String,
+   },
+   InvalidLine{
+      line  : usize,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>BindingError</code>.
+pub type Result<T> = std::result::Result<T, BindingError>;
+
+/// How a binding's key changes the
+/// state of its feature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BindingMode {
+   Press,
+   Toggle,
+}
+
+/// A single feature bound to a key
+/// and a console command.
+#[derive(Clone, Debug)]
+pub struct Binding<K: BindingKey> {
+   feature  : &'static str,
+   command  : &'static str,
+   mode     : BindingMode,
+   key      : K,
+   active   : bool,
+   changed  : bool,
+   pressed  : bool,
+}
+
+///////////////////////
+// TRAIT DEFINITIONS //
+///////////////////////
+
+/// Trait for keys which can be used
+/// in a binding table.  This is
+/// implemented on <code>input::Key</code>,
+/// but can also be implemented on a
+/// wrapper around an input library's
+/// key type.
+pub trait BindingKey : Copy {
+   /// Checks whether the key is
+   /// currently held down.
+   fn is_pressed(
+      self,
+   ) -> bool;
+
+   /// Parses a key from its name in
+   /// a config file.
+   fn from_name(
+      name : & str,
+   ) -> Option<Self>;
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - BindingError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for BindingError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::UnknownFeature{name}
+            => write!(stream, "Unknown feature \"{name}\""),
+         Self::UnknownKey{name}
+            => write!(stream, "Unknown key \"{name}\""),
+         Self::InvalidLine{line}
+            => write!(stream, "Invalid binding on line {line}"),
+      };
+   }
+}
+
+impl std::error::Error for BindingError {
+}
+
+///////////////////////
+// METHODS - Binding //
+///////////////////////
+
+impl<K: BindingKey> Binding<K> {
+   /// Creates a new inactive binding.
+   pub fn new(
+      feature  : &'static str,
+      command  : &'static str,
+      mode     : BindingMode,
+      key      : K,
+   ) -> Self {
+      return Self{
+         feature  : feature,
+         command  : command,
+         mode     : mode,
+         key      : key,
+         active   : false,
+         changed  : false,
+         pressed  : false,
+      };
+   }
+
+   /// Gets the name of the feature.
+   pub fn feature(
+      & self,
+   ) -> &'static str {
+      return self.feature;
+   }
+
+   /// Gets the console command which
+   /// triggers the feature.
+   pub fn command(
+      & self,
+   ) -> &'static str {
+      return self.command;
+   }
+
+   /// Gets how the key changes the
+   /// feature's state.
+   pub fn mode(
+      & self,
+   ) -> BindingMode {
+      return self.mode;
+   }
+
+   /// Gets the key bound to the feature.
+   pub fn key(
+      & self,
+   ) -> K {
+      return self.key;
+   }
+
+   /// Binds the feature to a new key.
+   pub fn set_key(
+      & mut self,
+      key : K,
+   ) -> & mut Self {
+      self.key = key;
+      return self;
+   }
+
+   /// Checks whether the feature is
+   /// currently active.
+   pub fn is_active(
+      & self,
+   ) -> bool {
+      return self.active;
+   }
+
+   /// Checks whether the feature's state
+   /// changed during the last poll or
+   /// console command.
+   pub fn is_changed(
+      & self,
+   ) -> bool {
+      return self.changed;
+   }
+
+   /// Updates the feature's state from
+   /// its key.  Toggled features flip
+   /// each time the key goes down.
+   pub fn poll(
+      & mut self,
+   ) -> & mut Self {
+      let pressed = self.key.is_pressed();
+
+      let active = match self.mode {
+         BindingMode::Press
+            => pressed,
+         BindingMode::Toggle
+            => self.active ^ (pressed == true && self.pressed == false),
+      };
+
+      self.changed   = active != self.active;
+      self.active    = active;
+      self.pressed   = pressed;
+      return self;
+   }
+
+   /// Triggers the feature as if its
+   /// key was pressed, such as from a
+   /// console command.  Pressed features
+   /// are only active until the next poll.
+   pub fn trigger(
+      & mut self,
+   ) -> & mut Self {
+      self.active = match self.mode {
+         BindingMode::Press   => true,
+         BindingMode::Toggle  => self.active == false,
+      };
+      self.changed = true;
+      return self;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Parses a binding config into pairs
+/// of feature names and keys.  Each
+/// non-empty line has the form
+/// <code>feature = KeyName</code>, and
+/// anything after a <code>#</code> is
+/// a comment.
+pub fn parse_config<K: BindingKey>(
+   config : & str,
+) -> Result<Vec<(String, K)>> {
+   let mut list = Vec::new();
+
+   for (index, line) in config.lines().enumerate() {
+      let line = line.split('#').next().unwrap_or("").trim();
+      if line.is_empty() == true {
+         continue;
+      }
+
+      let (feature, key) = line.split_once('=').ok_or(BindingError::InvalidLine{
+         line : index + 1,
+      })?;
+      let feature = feature.trim();
+      let key     = key.trim();
+
+      let key = K::from_name(key).ok_or_else(|| BindingError::UnknownKey{
+         name : String::from(key),
+      })?;
+
+      list.push((String::from(feature), key));
+   }
+
+   return Ok(list);
+}
@@ -0,0 +1,116 @@
+//! Information about the build of the
+//! library, so logs and error reports
+//! identify exactly which build they
+//! came from.
+//!
+//! ```
+//! nusion_core::log::info!("Running {}", nusion_core::BUILD_INFO);
+//! ```
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// Information recorded when the library
+/// was built.  The git hash is the commit
+/// the library was built from, ending in
+/// <code>-dirty</code> if there were
+/// uncommitted changes, and is
+/// <code>None</code> when it wasn't built
+/// from a git repository.  The time stamp
+/// is <code>SOURCE_DATE_EPOCH</code> if it
+/// was set, otherwise the time of the
+/// commit, in seconds since the Unix epoch.
+/// It is zero when neither is known.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BuildInfo {
+   pub crate_version    : &'static str,
+   pub git_hash         : Option<&'static str>,
+   pub build_timestamp  : u64,
+   pub target           : &'static str,
+   pub profile          : &'static str,
+}
+
+/// Information about this build
+/// of the library.
+pub const BUILD_INFO : BuildInfo = BuildInfo{
+   crate_version     : env!("CARGO_PKG_VERSION"),
+   git_hash          : match env!("NUSION_BUILD_GIT_HASH").is_empty() {
+      true  => None,
+      false => Some(env!("NUSION_BUILD_GIT_HASH")),
+   },
+   build_timestamp   : parse_timestamp(env!("NUSION_BUILD_TIMESTAMP")),
+   target            : env!("NUSION_BUILD_TARGET"),
+   profile           : env!("NUSION_BUILD_PROFILE"),
+};
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - BuildInfo //
+///////////////////////////////////////
+
+impl std::fmt::Display for BuildInfo {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      let (year, month, day, hour, minute, second) = civil_time(self.build_timestamp);
+
+      return write!(stream,
+         "nusion-core {} ({}, {} {}, built {year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC)",
+         self.crate_version,
+         self.git_hash.unwrap_or("unknown commit"),
+         self.target,
+         self.profile,
+      );
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+// The time stamp is passed from the build
+// script as text, and parsing a string
+// isn't const in the standard library
+const fn parse_timestamp(
+   text : & str,
+) -> u64 {
+   let bytes = text.as_bytes();
+
+   let mut value = 0u64;
+   let mut index = 0;
+   while index < bytes.len() {
+      let digit = bytes[index];
+      if digit < b'0' || digit > b'9' {
+         return 0;
+      }
+
+      value = value * 10 + (digit - b'0') as u64;
+      index += 1;
+   }
+
+   return value;
+}
+
+// Converts seconds since the Unix epoch into
+// a UTC date and time, using the days-to-civil
+// algorithm from Howard Hinnant's date library
+fn civil_time(
+   timestamp : u64,
+) -> (u64, u64, u64, u64, u64, u64) {
+   let days    = timestamp / 86400;
+   let seconds = timestamp % 86400;
+
+   let days    = days + 719468;
+   let era     = days / 146097;
+   let doe     = days - era * 146097;
+   let yoe     = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+   let doy     = doe - (365 * yoe + yoe / 4 - yoe / 100);
+   let mp      = (5 * doy + 2) / 153;
+   let day     = doy - (153 * mp + 2) / 5 + 1;
+   let month   = if mp < 10 {mp + 3} else {mp - 9};
+   let year    = yoe + era * 400 + if month <= 2 {1} else {0};
+
+   return (year, month, day, seconds / 3600, seconds % 3600 / 60, seconds % 60);
+}
+
@@ -0,0 +1,369 @@
+//! Copy console output, memory dumps,
+//! and checksums to the system clipboard
+//! so they can be pasted into source code
+//! instead of transcribed by hand.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to copying
+/// to the clipboard.
+#[derive(Debug)]
+pub enum ClipboardError {
+   InvalidTextCharacters,
+   Unsupported,
+   Unknown,
+   ConsoleError{
+      err : crate::console::ConsoleError,
+   },
+   PatchError{
+      err : crate::patch::PatchError,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ClipboardError</code>.
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ClipboardError //
+////////////////////////////////////////////
+
+impl std::fmt::Display for ClipboardError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::InvalidTextCharacters
+            => write!(stream, "Text contains invalid characters"),
+         Self::Unsupported
+            => write!(stream, "No clipboard is available"),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+         Self::ConsoleError{err}
+            => write!(stream, "Console error: {err}"),
+         Self::PatchError{err}
+            => write!(stream, "Patch error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for ClipboardError {
+}
+
+impl From<crate::sys::clipboard::ClipboardError> for ClipboardError {
+   fn from(
+      item : crate::sys::clipboard::ClipboardError,
+   ) -> Self {
+      use crate::sys::clipboard::ClipboardError::*;
+      return match item {
+         InvalidTextCharacters
+            => Self::InvalidTextCharacters,
+         Unsupported
+            => Self::Unsupported,
+         Unknown
+            => Self::Unknown,
+      };
+   }
+}
+
+impl From<crate::console::ConsoleError> for ClipboardError {
+   fn from(
+      item : crate::console::ConsoleError,
+   ) -> Self {
+      return Self::ConsoleError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::patch::PatchError> for ClipboardError {
+   fn from(
+      item : crate::patch::PatchError,
+   ) -> Self {
+      return Self::PatchError{
+         err : item,
+      };
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Replaces the contents of the system
+/// clipboard with text.  On Linux, this
+/// requires one of <code>wl-copy</code>,
+/// <code>xclip</code>, or <code>xsel</code>
+/// to be installed.
+pub fn set_text(
+   text : & str,
+) -> Result<()> {
+   crate::sys::clipboard::set_text(text)?;
+   return Ok(());
+}
+
+/// Copies up to the last <code>count</code>
+/// lines shown in the console, returning
+/// the number of lines copied.
+pub fn copy_console_lines(
+   console  : & crate::console::Console,
+   count    : usize,
+) -> Result<usize> {
+   let lines = console.read_lines(count)?;
+
+   set_text(&lines.join("\n"))?;
+   return Ok(lines.len());
+}
+
+/// Formats bytes as a hexdump, 16 bytes
+/// per line, with each line labeled by
+/// its offset starting at <code>base_offset</code>.
+///
+/// ```
+/// // 00001000  48 8B 05 ...  |H..|
+/// let text = nusion_core::clipboard::hexdump(0x1000, &bytes);
+/// ```
+pub fn hexdump(
+   base_offset : usize,
+   bytes       : & [u8],
+) -> String {
+   const BYTES_PER_LINE : usize = 16;
+
+   let mut text = String::new();
+   for (index, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+      let hex = line.iter().map(|byte| format!("{byte:02X}")).collect::<Vec<String>>();
+
+      let ascii = line.iter().map(|byte| match byte.is_ascii_graphic() || *byte == b' ' {
+         true  => *byte as char,
+         false => '.',
+      }).collect::<String>();
+
+      if index != 0 {
+         text.push('\n');
+      }
+      text.push_str(&format!(
+         "{:08X}  {:<width$}  |{ascii}|",
+         base_offset + index * BYTES_PER_LINE,
+         hex.join(" "),
+         width = BYTES_PER_LINE * 3 - 1,
+      ));
+   }
+
+   return text;
+}
+
+/// Copies a hexdump of a module's memory
+/// to the clipboard, returning the copied
+/// text.
+///
+/// <h2 id=  clipboard_copy_hexdump_safety>
+/// <a href=#clipboard_copy_hexdump_safety>
+/// Safety
+/// </a></h2>
+///
+/// All safety concerns from
+/// <code>Patch::patch_read</code> apply.
+pub unsafe fn copy_hexdump(
+   module         : & crate::process::ModuleSnapshot,
+   offset_range   : std::ops::Range<usize>,
+) -> Result<String> {
+   let bytes = read_bytes(module, offset_range.clone())?;
+   let text  = hexdump(offset_range.start, &bytes);
+
+   set_text(&text)?;
+   return Ok(text);
+}
+
+/// Calculates the checksum of a module's
+/// memory and copies it to the clipboard
+/// as a <code>Checksum</code> expression
+/// bound to the range's length, ready to
+/// paste into a writer.
+///
+/// <h2 id=  clipboard_copy_checksum_safety>
+/// <a href=#clipboard_copy_checksum_safety>
+/// Safety
+/// </a></h2>
+///
+/// All safety concerns from
+/// <code>Patch::patch_read</code> apply.
+pub unsafe fn copy_checksum(
+   module         : & crate::process::ModuleSnapshot,
+   offset_range   : std::ops::Range<usize>,
+   algorithm      : crate::patch::ChecksumAlgorithm,
+) -> Result<crate::patch::Checksum> {
+   let checksum = crate::patch::Checksum::compute(
+      algorithm,
+      &read_bytes(module, offset_range)?,
+   );
+
+   set_text(&checksum_expression(&checksum))?;
+   return Ok(checksum);
+}
+
+/// Runs a console command, printing
+/// the result to standard output.
+/// The supported commands are
+/// <code>copy lines &lt;count&gt;</code>,
+/// <code>copy hex &lt;module&gt; &lt;start&gt;..&lt;end&gt;</code>,
+/// and <code>copy checksum &lt;module&gt; &lt;start&gt;..&lt;end&gt; [algorithm]</code>,
+/// where the algorithm is one of <code>crc32</code>
+/// (the default), <code>crc64</code>, <code>fnv1a</code>,
+/// or <code>sha256</code>.
+/// Numbers may be decimal or hexadecimal
+/// with a <code>0x</code> prefix.  Returns
+/// <code>false</code> if the command isn't
+/// recognized.  Copying lines fails if
+/// there is no console.
+///
+/// <h2 id=  clipboard_run_command_safety>
+/// <a href=#clipboard_run_command_safety>
+/// Safety
+/// </a></h2>
+///
+/// All safety concerns from
+/// <code>Patch::patch_read</code> apply.
+pub unsafe fn run_command(
+   console  : Option<& crate::console::Console>,
+   modules  : & crate::process::ModuleSnapshotList,
+   command  : & str,
+) -> Result<bool> {
+   let words = command.split_whitespace().collect::<Vec<&str>>();
+
+   match words.as_slice() {
+      ["copy", "lines", count]                        => {
+         let count = match parse_number(count) {
+            Some(count) => count,
+            None        => return Ok(false),
+         };
+
+         let console = console.ok_or(crate::console::ConsoleError::NotAllocated)?;
+         let copied  = copy_console_lines(console, count)?;
+         println!("Copied {copied} lines");
+      },
+      ["copy", kind, module_name, range, options @ ..] => {
+         let offset_range = match parse_range(range) {
+            Some(range) => range,
+            None        => return Ok(false),
+         };
+
+         let module = modules.get(
+            module_name,
+         ).ok_or_else(|| crate::patch::PatchError::ModuleNotFound{
+            module_name : String::from(*module_name),
+         })?;
+
+         match (*kind, options) {
+            ("hex", [])                => {
+               copy_hexdump(module, offset_range.clone())?;
+               println!("Copied hexdump of {module_name} {:#X}..{:#X}",
+                  offset_range.start,
+                  offset_range.end,
+               );
+            },
+            ("checksum", algorithm)    => {
+               let algorithm = match algorithm {
+                  []             => crate::patch::ChecksumAlgorithm::Crc32,
+                  [algorithm]    => match parse_algorithm(algorithm) {
+                     Some(algorithm)   => algorithm,
+                     None              => return Ok(false),
+                  },
+                  _              => return Ok(false),
+               };
+
+               let checksum = copy_checksum(module, offset_range, algorithm)?;
+               println!("Copied checksum {checksum}");
+            },
+            _                          => return Ok(false),
+         }
+      },
+      _                                               => return Ok(false),
+   }
+
+   return Ok(true);
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+unsafe fn read_bytes(
+   module         : & crate::process::ModuleSnapshot,
+   offset_range   : std::ops::Range<usize>,
+) -> Result<Vec<u8>> {
+   use crate::patch::Patch;
+
+   return Ok(module.patch_read(&crate::patch::reader::Slice::<std::ops::Range<usize>, u8>{
+      marker               : Default::default(),
+      element_count        : offset_range.len(),
+      memory_offset_range  : offset_range,
+   })?);
+}
+
+fn checksum_expression(
+   checksum : & crate::patch::Checksum,
+) -> String {
+   use crate::patch::ChecksumValue;
+
+   let value = match checksum.value() {
+      ChecksumValue::Crc32(value)
+         => format!("nusion_core::patch::Checksum::from({value:#010X})"),
+      ChecksumValue::Crc64(value)
+         => format!("nusion_core::patch::Checksum::from_value(\
+            nusion_core::patch::ChecksumValue::Crc64({value:#018X}))"),
+      ChecksumValue::Fnv1a(value)
+         => format!("nusion_core::patch::Checksum::from_value(\
+            nusion_core::patch::ChecksumValue::Fnv1a({value:#018X}))"),
+      ChecksumValue::Sha256(value)
+         => format!("nusion_core::patch::Checksum::from_value(\
+            nusion_core::patch::ChecksumValue::Sha256([{}]))",
+            value.iter().map(|byte| format!("{byte:#04X}")).collect::<Vec<String>>().join(", "),
+         ),
+   };
+
+   return match checksum.length() {
+      Some(length)   => format!("{value}.with_length({length})"),
+      None           => value,
+   };
+}
+
+fn parse_algorithm(
+   text : & str,
+) -> Option<crate::patch::ChecksumAlgorithm> {
+   use crate::patch::ChecksumAlgorithm;
+
+   return match text.to_ascii_lowercase().as_str() {
+      "crc32"  => Some(ChecksumAlgorithm::Crc32),
+      "crc64"  => Some(ChecksumAlgorithm::Crc64),
+      "fnv1a"  => Some(ChecksumAlgorithm::Fnv1a),
+      "sha256" => Some(ChecksumAlgorithm::Sha256),
+      _        => None,
+   };
+}
+
+fn parse_number(
+   text : & str,
+) -> Option<usize> {
+   return match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+      Some(hex)   => usize::from_str_radix(hex, 16).ok(),
+      None        => text.parse().ok(),
+   };
+}
+
+fn parse_range(
+   text : & str,
+) -> Option<std::ops::Range<usize>> {
+   let (start, end) = text.split_once("..")?;
+   let start   = parse_number(start)?;
+   let end     = parse_number(end)?;
+
+   if start >= end {
+      return None;
+   }
+
+   return Some(start..end);
+}
+
@@ -0,0 +1,675 @@
+//! Persistent settings for mods, stored
+//! in a file next to the mod's library
+//! rather than the game's working directory.
+//!
+//! Config files use a flat subset of TOML
+//! where each line is a key and a value,
+//! which is either a boolean, an integer,
+//! a float, or a quoted string.  Anything
+//! after a <code>#</code> outside of a
+//! string is a comment.
+//!
+//! ```
+//! # Settings for my mod
+//! infinite_ammo  = true
+//! damage_scale   = 1.5
+//! max_health     = 250
+//! greeting       = "Rock and stone!"
+//! ```
+//!
+//! Settings are read with a default so a
+//! missing or deleted file is never fatal.
+//!
+//! ```
+//! let mut config = nusion_core::config::Config::open("my_mod.toml")?;
+//!
+//! let scale = config.get_or("damage_scale", 1.0f64);
+//! config.set("max_health", 300u32);
+//! config.save()?;
+//! ```
+
+use std::collections::BTreeMap;
+
+/// File extension of config files
+/// named after the mod's library.
+const CONFIG_FILE_EXT : &'static str
+   = "toml";
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to loading,
+/// saving, or reading a config.
+#[derive(Debug)]
+pub enum ConfigError {
+   IoError{
+      err         : std::io::Error,
+   },
+   ProcessError{
+      err         : crate::process::ProcessError,
+   },
+   InvalidLine{
+      line        : usize,
+   },
+   DuplicateKey{
+      line        : usize,
+      key         : String,
+   },
+   KeyNotFound{
+      key         : String,
+   },
+   WrongType{
+      key         : String,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ConfigError</code>.
+pub type Result<T> = std::result::Result<T, ConfigError>;
+
+/// A single value stored in a config.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigValue {
+   Boolean(bool),
+   Integer(i64),
+   Float(f64),
+   String(String),
+}
+
+/// A set of settings loaded from a
+/// config file.  Changes are only written
+/// back to the file when saved.
+#[derive(Debug)]
+pub struct Config {
+   path     : std::path::PathBuf,
+   values   : BTreeMap<String, ConfigValue>,
+   modified : Option<std::time::SystemTime>,
+}
+
+///////////////////////
+// TRAIT DEFINITIONS //
+///////////////////////
+
+/// A type which can be stored in a
+/// config and read back out of it.
+pub trait ConfigType : Sized {
+   /// Converts the value for storage.
+   fn to_value(
+      & self,
+   ) -> ConfigValue;
+
+   /// Converts a stored value back,
+   /// returning <code>None</code> if
+   /// it has the wrong type or is
+   /// out of range.
+   fn from_value(
+      value : & ConfigValue,
+   ) -> Option<Self>;
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ConfigError //
+/////////////////////////////////////////
+
+impl std::fmt::Display for ConfigError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::IoError           {err,       }
+            => write!(stream, "I/O error: {err}"),
+         Self::ProcessError      {err,       }
+            => write!(stream, "Process error: {err}"),
+         Self::InvalidLine       {line,      }
+            => write!(stream, "Invalid config line {line}"),
+         Self::DuplicateKey      {line, key, }
+            => write!(stream, "Duplicate key \"{key}\" on line {line}"),
+         Self::KeyNotFound       {key,       }
+            => write!(stream, "Key \"{key}\" not found"),
+         Self::WrongType         {key,       }
+            => write!(stream, "Key \"{key}\" has the wrong type"),
+      };
+   }
+}
+
+impl std::error::Error for ConfigError {
+}
+
+impl From<std::io::Error> for ConfigError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::process::ProcessError> for ConfigError {
+   fn from(
+      item : crate::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : item,
+      };
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ConfigValue //
+/////////////////////////////////////////
+
+impl std::fmt::Display for ConfigValue {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::Boolean(value)
+            => write!(stream, "{value}"),
+         Self::Integer(value)
+            => write!(stream, "{value}"),
+         // Floats always keep a decimal point
+         // so they're read back as floats, and
+         // use TOML's names for special values
+         Self::Float(value) if value.is_nan() == true
+            => write!(stream, "nan"),
+         Self::Float(value) if value.is_infinite() == true
+            => write!(stream, "{}inf", if *value < 0.0 {"-"} else {""}),
+         Self::Float(value)
+            => write!(stream, "{value:?}"),
+         Self::String(value)
+            => {
+               write!(stream, "\"")?;
+               for character in value.chars() {
+                  match character {
+                     '"'   => write!(stream, "\\\"")?,
+                     '\\'  => write!(stream, "\\\\")?,
+                     '\n'  => write!(stream, "\\n")?,
+                     '\t'  => write!(stream, "\\t")?,
+                     _     => write!(stream, "{character}")?,
+                  }
+               }
+               write!(stream, "\"")
+            },
+      };
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ConfigType //
+////////////////////////////////////////
+
+impl ConfigType for bool {
+   fn to_value(
+      & self,
+   ) -> ConfigValue {
+      return ConfigValue::Boolean(*self);
+   }
+
+   fn from_value(
+      value : & ConfigValue,
+   ) -> Option<Self> {
+      return match value {
+         ConfigValue::Boolean(value)   => Some(*value),
+         _                             => None,
+      };
+   }
+}
+
+impl ConfigType for f64 {
+   fn to_value(
+      & self,
+   ) -> ConfigValue {
+      return ConfigValue::Float(*self);
+   }
+
+   // Integers are accepted so a user can
+   // write "2" instead of "2.0"
+   fn from_value(
+      value : & ConfigValue,
+   ) -> Option<Self> {
+      return match value {
+         ConfigValue::Float(value)     => Some(*value),
+         ConfigValue::Integer(value)   => Some(*value as f64),
+         _                             => None,
+      };
+   }
+}
+
+impl ConfigType for f32 {
+   fn to_value(
+      & self,
+   ) -> ConfigValue {
+      return ConfigValue::Float(*self as f64);
+   }
+
+   fn from_value(
+      value : & ConfigValue,
+   ) -> Option<Self> {
+      return f64::from_value(value).map(|value| value as f32);
+   }
+}
+
+impl ConfigType for String {
+   fn to_value(
+      & self,
+   ) -> ConfigValue {
+      return ConfigValue::String(self.clone());
+   }
+
+   fn from_value(
+      value : & ConfigValue,
+   ) -> Option<Self> {
+      return match value {
+         ConfigValue::String(value)    => Some(value.clone()),
+         _                             => None,
+      };
+   }
+}
+
+macro_rules! impl_config_type_integer {
+   ($($type:ty),*) => {$(
+      impl ConfigType for $type {
+         fn to_value(
+            & self,
+         ) -> ConfigValue {
+            return ConfigValue::Integer(*self as i64);
+         }
+
+         fn from_value(
+            value : & ConfigValue,
+         ) -> Option<Self> {
+            return match value {
+               ConfigValue::Integer(value)   => <$type>::try_from(*value).ok(),
+               _                             => None,
+            };
+         }
+      }
+   )*};
+}
+
+impl_config_type_integer!(i8, i16, i32, i64, isize, u8, u16, u32, usize);
+
+//////////////////////
+// METHODS - Config //
+//////////////////////
+
+impl Config {
+   /// Opens a config file stored in the
+   /// same directory as the mod's library.
+   /// If the file doesn't exist, the config
+   /// starts out empty and the file is
+   /// created when saved.
+   pub fn open<P>(
+      file_name : P,
+   ) -> Result<Self>
+   where P: AsRef<std::path::Path>,
+   {
+      let library_path  = Self::library_path()?;
+      let directory     = library_path.parent().unwrap_or(std::path::Path::new(""));
+      return Self::open_path(directory.join(file_name));
+   }
+
+   /// Opens the config file named after the
+   /// mod's library, such as <code>my_mod.toml</code>
+   /// for <code>my_mod.dll</code>.  This is the
+   /// config loaded by the environment.
+   pub fn open_default(
+   ) -> Result<Self> {
+      return Self::open_path(Self::default_path()?);
+   }
+
+   /// Creates an empty config for the file
+   /// <code>open_default</code> would open,
+   /// without reading it.  This is what the
+   /// environment falls back to when the file
+   /// can't be read or is malformed, so the
+   /// mod still starts with its defaults.
+   pub fn empty_default(
+   ) -> Self {
+      return Self{
+         path     : Self::default_path().unwrap_or_default(),
+         values   : BTreeMap::new(),
+         modified : None,
+      };
+   }
+
+   /// Opens a config file at a specific
+   /// path.  If the file doesn't exist,
+   /// the config starts out empty.
+   pub fn open_path<P>(
+      path : P,
+   ) -> Result<Self>
+   where P: AsRef<std::path::Path>,
+   {
+      let mut config = Self{
+         path     : path.as_ref().to_path_buf(),
+         values   : BTreeMap::new(),
+         modified : None,
+      };
+
+      config.reload()?;
+      return Ok(config);
+   }
+
+   /// Gets the path of the config file.
+   pub fn path<'l>(
+      &'l self,
+   ) -> &'l std::path::Path {
+      return &self.path;
+   }
+
+   /// Reads a value by its key.
+   pub fn get<T>(
+      & self,
+      key : & str,
+   ) -> Result<T>
+   where T: ConfigType,
+   {
+      let value = self.values.get(key).ok_or(ConfigError::KeyNotFound{
+         key : String::from(key),
+      })?;
+
+      return T::from_value(value).ok_or(ConfigError::WrongType{
+         key : String::from(key),
+      });
+   }
+
+   /// Reads a value by its key, returning
+   /// a default if the key is missing or
+   /// has the wrong type.
+   pub fn get_or<T>(
+      & self,
+      key      : & str,
+      default  : T,
+   ) -> T
+   where T: ConfigType,
+   {
+      return self.get(key).unwrap_or(default);
+   }
+
+   /// Stores a value under a key,
+   /// replacing any existing value.
+   pub fn set<T>(
+      & mut self,
+      key   : & str,
+      value : T,
+   ) -> & mut Self
+   where T: ConfigType,
+   {
+      self.values.insert(String::from(key), value.to_value());
+      return self;
+   }
+
+   /// Removes a value by its key.
+   pub fn remove(
+      & mut self,
+      key : & str,
+   ) -> Option<ConfigValue> {
+      return self.values.remove(key);
+   }
+
+   /// Checks whether a key has a value.
+   pub fn contains_key(
+      & self,
+      key : & str,
+   ) -> bool {
+      return self.values.contains_key(key);
+   }
+
+   /// Creates an iterator over every key
+   /// and value, sorted by key.
+   pub fn iter<'l>(
+      &'l self,
+   ) -> std::collections::btree_map::Iter<'l, String, ConfigValue> {
+      return self.values.iter();
+   }
+
+   /// Writes every value to the config
+   /// file, sorted by key.  Comments in
+   /// the existing file are not kept.
+   pub fn save(
+      & mut self,
+   ) -> Result<& mut Self> {
+      let mut text = String::new();
+      for (key, value) in self.values.iter() {
+         text += &format!("{key} = {value}\n");
+      }
+
+      std::fs::write(&self.path, text)?;
+      self.modified = Self::modified_time(&self.path);
+      return Ok(self);
+   }
+
+   /// Reads the config file again, replacing
+   /// every value.  If the file doesn't exist,
+   /// the config is emptied.
+   pub fn reload(
+      & mut self,
+   ) -> Result<& mut Self> {
+      let text = match std::fs::read_to_string(&self.path) {
+         Ok(text)
+            => text,
+         Err(err) if err.kind() == std::io::ErrorKind::NotFound
+            => String::new(),
+         Err(err)
+            => return Err(err.into()),
+      };
+
+      self.values    = Self::parse(&text)?;
+      self.modified  = Self::modified_time(&self.path);
+      return Ok(self);
+   }
+
+   /// Reloads the config if the file was
+   /// modified since it was last loaded or
+   /// saved, such as by the user editing it
+   /// while the game is running.  Returns
+   /// whether the config was reloaded, so
+   /// this can be polled from a mod's main
+   /// loop to apply changes immediately.
+   pub fn reload_if_changed(
+      & mut self,
+   ) -> Result<bool> {
+      if Self::modified_time(&self.path) == self.modified {
+         return Ok(false);
+      }
+
+      self.reload()?;
+      return Ok(true);
+   }
+}
+
+///////////////////////////////
+// INTERNAL METHODS - Config //
+///////////////////////////////
+
+impl Config {
+   fn library_path(
+   ) -> Result<std::path::PathBuf> {
+      // Any function compiled into the mod
+      // is within the mod's library
+      return crate::sys::process::module_file_path(
+         Self::library_path as fn() -> Result<std::path::PathBuf> as usize,
+      ).map_err(|err| crate::process::ProcessError::from(err).into());
+   }
+
+   fn default_path(
+   ) -> Result<std::path::PathBuf> {
+      return Ok(Self::library_path()?.with_extension(CONFIG_FILE_EXT));
+   }
+
+   fn modified_time(
+      path : & std::path::Path,
+   ) -> Option<std::time::SystemTime> {
+      return std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+   }
+
+   fn parse(
+      text : & str,
+   ) -> Result<BTreeMap<String, ConfigValue>> {
+      let mut values = BTreeMap::new();
+
+      for (index, line) in text.lines().enumerate() {
+         let line_number   = index + 1;
+         let line          = line.trim();
+         if line.is_empty() == true || line.starts_with('#') == true {
+            continue;
+         }
+
+         let (key, value) = line.split_once('=').ok_or(
+            ConfigError::InvalidLine{line : line_number},
+         )?;
+         let key = key.trim();
+         if key.is_empty() == true || key.chars().all(|character| {
+            character.is_ascii_alphanumeric() == true || "_-.".contains(character) == true
+         }) == false {
+            return Err(ConfigError::InvalidLine{line : line_number});
+         }
+
+         let value = parse_value(value.trim()).ok_or(
+            ConfigError::InvalidLine{line : line_number},
+         )?;
+
+         if values.insert(String::from(key), value).is_some() == true {
+            return Err(ConfigError::DuplicateKey{
+               line : line_number,
+               key  : String::from(key),
+            });
+         }
+      }
+
+      return Ok(values);
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn parse_value(
+   text : & str,
+) -> Option<ConfigValue> {
+   if let Some(text) = text.strip_prefix('"') {
+      let mut value  = String::new();
+      let mut chars  = text.chars();
+      loop {
+         match chars.next()? {
+            '"'   => break,
+            '\\'  => value.push(match chars.next()? {
+               '"'   => '"',
+               '\\'  => '\\',
+               'n'   => '\n',
+               't'   => '\t',
+               _     => return None,
+            }),
+            other => value.push(other),
+         }
+      }
+
+      // Only a comment may follow the string
+      let rest = chars.as_str().trim();
+      if rest.is_empty() == false && rest.starts_with('#') == false {
+         return None;
+      }
+
+      return Some(ConfigValue::String(value));
+   }
+
+   let text = text.split('#').next().unwrap_or("").trim();
+   return match text {
+      "true"   => Some(ConfigValue::Boolean(true)),
+      "false"  => Some(ConfigValue::Boolean(false)),
+      _        => {
+         // TOML allows underscores between digits
+         let number = text.replace('_', "");
+         if let Ok(value) = number.parse::<i64>() {
+            Some(ConfigValue::Integer(value))
+         } else if let Some(hex) = number.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16).ok().map(ConfigValue::Integer)
+         } else {
+            number.parse::<f64>().ok().map(ConfigValue::Float)
+         }
+      },
+   };
+}
+
+///////////
+// TESTS //
+///////////
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn parse_one(
+      text : & str,
+   ) -> Option<ConfigValue> {
+      return Config::parse(&format!("key = {text}")).ok()?.remove("key");
+   }
+
+   fn round_trip(
+      value : ConfigValue,
+   ) -> Option<ConfigValue> {
+      return parse_one(&value.to_string());
+   }
+
+   #[test]
+   fn parse_scalars() {
+      assert_eq!(parse_one("true"), Some(ConfigValue::Boolean(true)));
+      assert_eq!(parse_one("false"), Some(ConfigValue::Boolean(false)));
+      assert_eq!(parse_one("-250"), Some(ConfigValue::Integer(-250)));
+      assert_eq!(parse_one("1_000"), Some(ConfigValue::Integer(1000)));
+      assert_eq!(parse_one("0xFF"), Some(ConfigValue::Integer(0xFF)));
+      assert_eq!(parse_one("1.5"), Some(ConfigValue::Float(1.5)));
+   }
+
+   #[test]
+   fn parse_strings() {
+      assert_eq!(parse_one("\"Rock and stone!\""), Some(ConfigValue::String(String::from("Rock and stone!"))));
+      assert_eq!(parse_one("\"a\\\"b\\n\" # comment"), Some(ConfigValue::String(String::from("a\"b\n"))));
+      assert_eq!(parse_one("\"# not a comment\""), Some(ConfigValue::String(String::from("# not a comment"))));
+      assert_eq!(parse_one("\"unterminated"), None);
+      assert_eq!(parse_one("\"trailing\" junk"), None);
+      assert_eq!(parse_one("\"\\q\""), None);
+   }
+
+   #[test]
+   fn parse_comments_and_blank_lines() {
+      let values = Config::parse("# header\n\nscale = 2.0 # inline\n").expect("config should parse");
+      assert_eq!(values.len(), 1);
+      assert_eq!(values.get("scale"), Some(&ConfigValue::Float(2.0)));
+   }
+
+   #[test]
+   fn parse_errors() {
+      assert!(matches!(Config::parse("a = 1\nnot a setting"), Err(ConfigError::InvalidLine{line : 2})));
+      assert!(matches!(Config::parse("bad key = 1"), Err(ConfigError::InvalidLine{line : 1})));
+      assert!(matches!(Config::parse("key = maybe"), Err(ConfigError::InvalidLine{line : 1})));
+      assert!(matches!(Config::parse("a = 1\na = 2"), Err(ConfigError::DuplicateKey{line : 2, ..})));
+   }
+
+   #[test]
+   fn values_round_trip() {
+      for value in [
+         ConfigValue::Boolean(true),
+         ConfigValue::Integer(i64::MIN),
+         ConfigValue::Float(2.0),
+         ConfigValue::Float(-0.1),
+         ConfigValue::Float(f64::INFINITY),
+         ConfigValue::Float(f64::NEG_INFINITY),
+         ConfigValue::String(String::from("tab\there \"quoted\" \\ # hash")),
+      ] {
+         assert_eq!(round_trip(value.clone()), Some(value));
+      }
+   }
+
+   #[test]
+   fn nan_round_trips() {
+      assert_eq!(ConfigValue::Float(f64::NAN).to_string(), "nan");
+      assert!(matches!(round_trip(ConfigValue::Float(f64::NAN)), Some(ConfigValue::Float(value)) if value.is_nan() == true));
+   }
+}
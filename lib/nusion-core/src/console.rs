@@ -1,4 +1,52 @@
 //! Console window creation and management.
+//!
+//! A console can also run a shell on its
+//! own thread, so trainer-style mods can
+//! accept typed commands.  Each line is
+//! split into words, with double quotes
+//! grouping words containing spaces, and
+//! the first word picks the command.  The
+//! <code>help</code> command lists every
+//! registered command and
+//! <code>history</code> lists the lines
+//! entered so far.
+//!
+//! ```
+//! let env     = nusion_core::env!();
+//! let console = env.console().unwrap();
+//!
+//! console.register_command("godmode", |arguments| {
+//!    let enabled = match arguments {
+//!       ["on"]   => true,
+//!       ["off"]  => false,
+//!       _        => return Err("Expected \"on\" or \"off\"".into()),
+//!    };
+//!
+//!    GODMODE.store(enabled, std::sync::atomic::Ordering::Relaxed);
+//!    return Ok(());
+//! })?;
+//! console.describe_command("godmode", "Turns invincibility on or off")?;
+//!
+//! console.start_shell("> ")?;
+//! ```
+
+/// Number of lines remembered by a
+/// <code>LineEditor</code> unless changed
+/// by the user.
+const DEFAULT_HISTORY_CAPACITY : usize
+   = 100;
+
+/// How often the shell checks whether it
+/// should stop while waiting for a key.
+const SHELL_POLL_INTERVAL : std::time::Duration
+   = std::time::Duration::from_millis(100);
+
+/// Commands built into the shell, which
+/// can't be registered by the mod.
+const BUILTIN_COMMANDS : &[(&str, &str)] = &[
+   ("help",    "Lists every command"),
+   ("history", "Lists the lines entered so far"),
+];
 
 //////////////////////
 // TYPE DEFINITIONS //
@@ -9,17 +57,112 @@
 #[derive(Debug)]
 pub enum ConsoleError {
    InvalidTitleCharacters,
+   NoParentConsole,
+   NotAllocated,
+   InvalidCommandName,
+   DuplicateCommand,
+   UnknownCommand,
+   Unsupported,
    Unknown,
+   IoError{
+      err : std::io::Error,
+   },
 }
 
 /// <code>Result</code> type with error
 /// variant <code>ConsoleError</code>.
 pub type Result<T> = std::result::Result<T, ConsoleError>;
 
+/// <code>Result</code> type returned by
+/// console commands.  Any error can be
+/// returned with <code>?</code>, and is
+/// printed to the console by the shell.
+pub type CommandResult = std::result::Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Function called when a command is
+/// entered, with the words typed after
+/// the command's name.
+pub type CommandCallback = dyn Fn(&[&str]) -> CommandResult + Send + Sync;
+
+/// Options for creating a console.
+#[derive(Clone, Debug, Default)]
+pub struct ConsoleOptions {
+   /// Whether to attach to the console of
+   /// the process which started the game
+   /// instead of creating a console window.
+   pub attach           : bool,
+   /// Whether everything the process writes
+   /// to its standard output and error is
+   /// captured and shown in the console,
+   /// including output from the game and
+   /// from crates which print directly.
+   pub capture_output   : bool,
+   /// A file captured output is also
+   /// appended to, ignored unless output
+   /// is captured.
+   pub output_file      : Option<std::path::PathBuf>,
+}
+
 /// A console window for displaying
-/// standard I/O streams.
+/// standard I/O streams and reading
+/// commands typed into it.
 pub struct Console {
    console  : crate::sys::console::Console,
+   commands : CommandTable,
+   shell    : std::sync::Mutex<Option<Shell>>,
+   redirect : Option<crate::sys::console::OutputRedirect>,
+}
+
+/// Commands registered with a console
+/// by name, shared with its shell.
+type CommandTable = std::sync::Arc<std::sync::Mutex<std::collections::BTreeMap<String, Command>>>;
+
+/// A command registered with a console.
+struct Command {
+   description : String,
+   callback    : std::sync::Arc<CommandCallback>,
+}
+
+/// The thread reading commands
+/// typed into a console.
+pub(crate) struct Shell {
+   stop     : std::sync::Arc<std::sync::atomic::AtomicBool>,
+   thread   : std::thread::JoinHandle<()>,
+}
+
+/// Reads lines typed into the console
+/// with basic editing.  The arrow keys
+/// move the cursor and step through
+/// previously entered lines, home and end
+/// jump to either end of the line, escape
+/// clears it, and tab completes the first
+/// word from a list of command names.
+///
+/// ```
+/// let mut editor = nusion_core::console::LineEditor::new();
+/// editor.set_completions(["undo", "redo", "history", "copy"]);
+///
+/// while let Some(line) = editor.read_line("> ")? {
+///    let mut env = nusion_core::env_mut!();
+///    if unsafe{env.patch_command(&line)}? == false {
+///       println!("Unknown command \"{line}\"");
+///    }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineEditor {
+   history           : std::collections::VecDeque<String>,
+   history_capacity  : usize,
+   completions       : Vec<String>,
+}
+
+/// The line being edited by a
+/// <code>LineEditor</code>.
+struct EditState<'p> {
+   prompt      : &'p str,
+   text        : Vec<char>,
+   cursor      : usize,
+   drawn       : usize,
 }
 
 //////////////////////////////////////////
@@ -31,18 +174,42 @@ impl std::fmt::Display for ConsoleError {
       & self,
       stream : & mut std::fmt::Formatter<'_>,
    ) -> std::fmt::Result {
-      return write!(stream, "{}", match self {
+      return match self {
          Self::InvalidTitleCharacters
-            => "Title contains invalid characters",
+            => write!(stream, "Title contains invalid characters"),
+         Self::NoParentConsole
+            => write!(stream, "The parent process has no console to attach to"),
+         Self::NotAllocated
+            => write!(stream, "The mod was started without a console"),
+         Self::InvalidCommandName
+            => write!(stream, "Command names must be a single word"),
+         Self::DuplicateCommand
+            => write!(stream, "A command with the same name is already registered"),
+         Self::UnknownCommand
+            => write!(stream, "No command is registered with the name"),
+         Self::Unsupported
+            => write!(stream, "Unsupported on this platform"),
          Self::Unknown
-            => "Unknown",
-      });
+            => write!(stream, "Unknown"),
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+      };
    }
 }
 
 impl std::error::Error for ConsoleError {
 }
 
+impl From<std::io::Error> for ConsoleError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
 impl From<crate::sys::console::ConsoleError> for ConsoleError {
    fn from(
       item : crate::sys::console::ConsoleError,
@@ -51,6 +218,10 @@ impl From<crate::sys::console::ConsoleError> for ConsoleError {
       return match item {
          InvalidTitleCharacters
             => Self::InvalidTitleCharacters,
+         NoParentConsole
+            => Self::NoParentConsole,
+         Unsupported
+            => Self::Unsupported,
          Unknown
             => Self::Unknown,
       }
@@ -64,9 +235,32 @@ impl From<crate::sys::console::ConsoleError> for ConsoleError {
 impl Console {
    /// Creates a new console.
    pub fn new() -> Result<Self> {
-      return Ok(Self{
-         console : crate::sys::console::Console::new()?,
-      });
+      return Self::with_options(&ConsoleOptions::default());
+   }
+
+   /// Creates a new console or attaches to
+   /// the parent's, capturing the process'
+   /// output into it if the options ask
+   /// for it.
+   pub fn with_options(
+      options : & ConsoleOptions,
+   ) -> Result<Self> {
+      let mut console = Self{
+         console  : if options.attach == true {
+            crate::sys::console::Console::attach()?
+         } else {
+            crate::sys::console::Console::new()?
+         },
+         commands : CommandTable::default(),
+         shell    : std::sync::Mutex::new(None),
+         redirect : None,
+      };
+
+      if options.capture_output == true {
+         console.capture_output(options.output_file.as_deref())?;
+      }
+
+      return Ok(console);
    }
 
    /// Gets an owned string copy of
@@ -86,5 +280,678 @@ impl Console {
       self.console.set_title(title)?;
       return Ok(self);
    }
+
+   /// Checks whether the user tried to
+   /// close the console.  The console's
+   /// close button is removed, and Ctrl+C
+   /// and Ctrl+Break no longer terminate
+   /// the game, so this should be polled
+   /// to unload the mod gracefully instead.
+   pub fn is_close_requested(
+      & self,
+   ) -> bool {
+      return self.console.is_close_requested();
+   }
+
+   /// Reads up to the last <code>count</code>
+   /// lines of text shown in the console,
+   /// oldest first.  This is unsupported on
+   /// Linux, where output goes to the terminal
+   /// the game was started from.
+   pub fn read_lines(
+      & self,
+      count : usize,
+   ) -> Result<Vec<String>> {
+      return Ok(self.console.read_lines(count)?);
+   }
+
+   /// Captures everything the process writes
+   /// to its standard output and error, showing
+   /// it in the console and appending it to a
+   /// file if one is given.  Any previous
+   /// capture is released first.  On Windows,
+   /// output the game writes through a C
+   /// runtime linked into its executable
+   /// isn't captured, only through the
+   /// runtime libraries it loads.
+   pub fn capture_output(
+      & mut self,
+      output_file : Option<& std::path::Path>,
+   ) -> Result<& Self> {
+      self.release_output();
+
+      let output_file = match output_file {
+         Some(path)  => Some(std::fs::OpenOptions::new().create(true).append(true).open(path)?),
+         None        => None,
+      };
+
+      self.redirect = Some(crate::sys::console::OutputRedirect::new(output_file)?);
+      return Ok(self);
+   }
+
+   /// Puts back the process' original
+   /// standard output and error.  This is
+   /// done automatically when the console
+   /// is dropped.
+   pub fn release_output(
+      & mut self,
+   ) -> & Self {
+      // Dropping restores the streams
+      // and copies what's left
+      self.redirect = None;
+      return self;
+   }
+
+   /// Checks whether the process' output
+   /// is being captured.
+   pub fn is_capturing_output(
+      & self,
+   ) -> bool {
+      return self.redirect.is_some();
+   }
+
+   /// Registers a command which runs when
+   /// its name is entered into the shell.
+   /// The name must be a single word which
+   /// isn't already registered or built
+   /// into the shell.
+   pub fn register_command<F>(
+      & self,
+      name     : & str,
+      callback : F,
+   ) -> Result<& Self>
+   where F: Fn(&[&str]) -> CommandResult + Send + Sync + 'static,
+   {
+      if name.is_empty() == true
+         || name.contains(|character : char| character.is_whitespace() || character == '"') == true
+         || BUILTIN_COMMANDS.iter().any(|(builtin, _)| *builtin == name) == true
+      {
+         return Err(ConsoleError::InvalidCommandName);
+      }
+
+      let mut commands = lock_commands(&self.commands);
+      if commands.contains_key(name) == true {
+         return Err(ConsoleError::DuplicateCommand);
+      }
+
+      commands.insert(String::from(name), Command{
+         description : String::new(),
+         callback    : std::sync::Arc::new(callback),
+      });
+      return Ok(self);
+   }
+
+   /// Sets the description listed next
+   /// to a command by <code>help</code>.
+   pub fn describe_command(
+      & self,
+      name        : & str,
+      description : & str,
+   ) -> Result<& Self> {
+      lock_commands(&self.commands)
+         .get_mut(name)
+         .ok_or(ConsoleError::UnknownCommand)?
+         .description = String::from(description);
+      return Ok(self);
+   }
+
+   /// Removes a registered command,
+   /// returning whether it existed.
+   pub fn unregister_command(
+      & self,
+      name : & str,
+   ) -> bool {
+      return lock_commands(&self.commands).remove(name).is_some();
+   }
+
+   /// Gets the name of every registered
+   /// command in sorted order, not including
+   /// the ones built into the shell.
+   pub fn command_names(
+      & self,
+   ) -> Vec<String> {
+      return lock_commands(&self.commands).keys().cloned().collect();
+   }
+
+   /// Runs a line as if it was entered
+   /// into the shell.  Returns
+   /// <code>None</code> if the line is
+   /// empty or its command isn't registered.
+   /// The commands built into the shell
+   /// aren't available here.
+   pub fn run_command(
+      & self,
+      line : & str,
+   ) -> Option<CommandResult> {
+      return run_line(&self.commands, line);
+   }
+
+   /// Starts reading commands typed into
+   /// the console on a separate thread,
+   /// showing the prompt before each line.
+   /// Commands run on that thread.  This does
+   /// nothing if the shell is already running.
+   pub fn start_shell(
+      & self,
+      prompt : & str,
+   ) -> Result<& Self> {
+      let mut shell = self.shell.lock().unwrap_or_else(|poison| poison.into_inner());
+      if shell.as_ref().is_some_and(|shell| shell.thread.is_finished() == false) == true {
+         return Ok(self);
+      }
+
+      let stop       = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+      let prompt     = String::from(prompt);
+      let commands   = std::sync::Arc::clone(&self.commands);
+      let thread_stop = std::sync::Arc::clone(&stop);
+      let thread = std::thread::Builder::new()
+         .name(String::from("nusion-console-shell"))
+         .spawn(move || run_shell(&prompt, &commands, &thread_stop))
+         .map_err(|_| ConsoleError::Unknown)?;
+
+      *shell = Some(Shell{
+         stop     : stop,
+         thread   : thread,
+      });
+      return Ok(self);
+   }
+
+   /// Stops the shell and waits for its
+   /// thread to exit, which happens once
+   /// any command it is running returns.
+   /// This is done automatically when the
+   /// console is dropped.
+   pub fn stop_shell(
+      & self,
+   ) -> & Self {
+      if let Some(shell) = self.take_shell() {
+         shell.stop();
+      }
+
+      return self;
+   }
+
+   /// Checks whether the shell is
+   /// reading commands.
+   pub fn is_shell_running(
+      & self,
+   ) -> bool {
+      return self.shell.lock()
+         .unwrap_or_else(|poison| poison.into_inner())
+         .as_ref()
+         .is_some_and(|shell| shell.thread.is_finished() == false);
+   }
+}
+
+////////////////////////////////
+// INTERNAL METHODS - Console //
+////////////////////////////////
+
+impl Console {
+   /// Takes the shell out of the console
+   /// so it can be stopped without holding
+   /// any lock the shell's commands may be
+   /// waiting on.
+   pub(crate) fn take_shell(
+      & self,
+   ) -> Option<Shell> {
+      return self.shell.lock().unwrap_or_else(|poison| poison.into_inner()).take();
+   }
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Console //
+/////////////////////////////////////
+
+impl std::ops::Drop for Console {
+   fn drop(
+      & mut self,
+   ) {
+      // Output has to go back to its original
+      // streams before the console is freed
+      self.stop_shell();
+      self.release_output();
+      return;
+   }
 }
 
+//////////////////////////////
+// INTERNAL METHODS - Shell //
+//////////////////////////////
+
+impl Shell {
+   /// Tells the shell to stop and
+   /// waits for its thread to exit.
+   pub(crate) fn stop(
+      self,
+   ) {
+      self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+      let _ = self.thread.join();
+      return;
+   }
+}
+
+
+//////////////////////////
+// METHODS - LineEditor //
+//////////////////////////
+
+impl LineEditor {
+   /// Creates an editor with an empty
+   /// history and no completions.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         history           : std::collections::VecDeque::new(),
+         history_capacity  : DEFAULT_HISTORY_CAPACITY,
+         completions       : Vec::new(),
+      };
+   }
+
+   /// Replaces the command names which
+   /// tab completes.
+   pub fn set_completions<I, S>(
+      & mut self,
+      completions : I,
+   ) -> & mut Self
+   where I: IntoIterator<Item = S>,
+         S: Into<String>,
+   {
+      self.completions = completions.into_iter().map(Into::into).collect();
+      self.completions.sort();
+      self.completions.dedup();
+      return self;
+   }
+
+   /// Adds a command name which tab
+   /// completes.
+   pub fn add_completion(
+      & mut self,
+      completion : & str,
+   ) -> & mut Self {
+      if let Err(index) = self.completions.binary_search_by(|name| name.as_str().cmp(completion)) {
+         self.completions.insert(index, String::from(completion));
+      }
+
+      return self;
+   }
+
+   /// Sets how many of the most recently
+   /// entered lines are remembered.
+   pub fn set_history_capacity(
+      & mut self,
+      capacity : usize,
+   ) -> & mut Self {
+      self.history_capacity = capacity;
+      while self.history.len() > capacity {
+         self.history.pop_front();
+      }
+
+      return self;
+   }
+
+   /// Gets the remembered lines, from
+   /// oldest to newest.
+   pub fn history(
+      & self,
+   ) -> Vec<& str> {
+      return self.history.iter().map(String::as_str).collect();
+   }
+
+   /// Forgets every remembered line.
+   pub fn clear_history(
+      & mut self,
+   ) -> & mut Self {
+      self.history.clear();
+      return self;
+   }
+
+   /// Shows a prompt and blocks until a
+   /// line is entered, returning it without
+   /// the line ending.  Returns <code>None</code>
+   /// once the input is closed.  Non-empty
+   /// lines are added to the history.
+   pub fn read_line(
+      & mut self,
+      prompt : & str,
+   ) -> Result<Option<String>> {
+      return self.read_line_until(prompt, None);
+   }
+}
+
+///////////////////////////////////
+// INTERNAL METHODS - LineEditor //
+///////////////////////////////////
+
+impl LineEditor {
+   /// Reads a line like <code>read_line</code>,
+   /// returning <code>None</code> early once
+   /// the stop flag is set.
+   fn read_line_until(
+      & mut self,
+      prompt   : & str,
+      stop     : Option<& std::sync::atomic::AtomicBool>,
+   ) -> Result<Option<String>> {
+      use crate::sys::console::ConsoleKey;
+
+      let mut reader = crate::sys::console::KeyReader::new()?;
+      let mut state  = EditState{
+         prompt   : prompt,
+         text     : Vec::new(),
+         cursor   : 0,
+         drawn    : 0,
+      };
+
+      // Recalled lines are edited as copies,
+      // and the line being typed is kept so
+      // stepping back down restores it
+      let mut history_index   = self.history.len();
+      let mut draft           = Vec::new();
+
+      state.redraw();
+      loop {
+         if let Some(stop) = stop {
+            while reader.wait_for_key(SHELL_POLL_INTERVAL)? == false {
+               if stop.load(std::sync::atomic::Ordering::Relaxed) == true {
+                  println!();
+                  return Ok(None);
+               }
+            }
+         }
+
+         match reader.read_key()? {
+            ConsoleKey::Character(character) => {
+               state.text.insert(state.cursor, character);
+               state.cursor += 1;
+            },
+            ConsoleKey::Enter       => break,
+            ConsoleKey::EndOfInput  => {
+               if state.text.is_empty() == true {
+                  println!();
+                  return Ok(None);
+               }
+               break;
+            },
+            ConsoleKey::Tab         => self.complete(& mut state),
+            ConsoleKey::Backspace   => {
+               if state.cursor != 0 {
+                  state.cursor -= 1;
+                  state.text.remove(state.cursor);
+               }
+            },
+            ConsoleKey::Delete      => {
+               if state.cursor < state.text.len() {
+                  state.text.remove(state.cursor);
+               }
+            },
+            ConsoleKey::Escape      => {
+               state.text.clear();
+               state.cursor = 0;
+            },
+            ConsoleKey::Left        => state.cursor = state.cursor.saturating_sub(1),
+            ConsoleKey::Right       => state.cursor = usize::min(state.cursor + 1, state.text.len()),
+            ConsoleKey::Home        => state.cursor = 0,
+            ConsoleKey::End         => state.cursor = state.text.len(),
+            ConsoleKey::Up          => {
+               if history_index != 0 {
+                  if history_index == self.history.len() {
+                     draft = state.text.clone();
+                  }
+                  history_index -= 1;
+                  state.set_text(self.history[history_index].chars().collect());
+               }
+            },
+            ConsoleKey::Down        => {
+               if history_index < self.history.len() {
+                  history_index += 1;
+                  state.set_text(match self.history.get(history_index) {
+                     Some(line)  => line.chars().collect(),
+                     None        => std::mem::take(& mut draft),
+                  });
+               }
+            },
+         }
+
+         state.redraw();
+      }
+
+      println!();
+
+      let line = state.text.into_iter().collect::<String>();
+      if line.trim().is_empty() == false && self.history.back() != Some(&line) && self.history_capacity != 0 {
+         if self.history.len() >= self.history_capacity {
+            self.history.pop_front();
+         }
+         self.history.push_back(line.clone());
+      }
+
+      return Ok(Some(line));
+   }
+
+   /// Completes the command name before
+   /// the cursor.  A single match is filled
+   /// in, otherwise the shared prefix of
+   /// every match is filled in and the
+   /// matches are listed.
+   fn complete(
+      & self,
+      state : & mut EditState<'_>,
+   ) {
+      let typed = state.text[..state.cursor].iter().collect::<String>();
+      if typed.contains(char::is_whitespace) == true {
+         return;
+      }
+
+      let matches = self.completions.iter()
+         .filter(|name| name.starts_with(typed.as_str()) == true)
+         .collect::<Vec<&String>>();
+
+      let completion = match matches.as_slice() {
+         []       => return,
+         [name]   => format!("{name} "),
+         _        => {
+            let prefix = matches.iter().skip(1).fold(matches[0].as_str(), |prefix, name| {
+               let length = prefix.chars().zip(name.chars())
+                  .take_while(|(a, b)| a == b)
+                  .map(|(a, _)| a.len_utf8())
+                  .sum();
+               &prefix[..length]
+            });
+
+            if prefix.len() == typed.len() {
+               println!();
+               println!("{}", matches.iter().map(|name| name.as_str()).collect::<Vec<&str>>().join("  "));
+               state.drawn = 0;
+            }
+            String::from(prefix)
+         },
+      };
+
+      let inserted = completion.chars().skip(typed.chars().count()).collect::<Vec<char>>();
+      let count    = inserted.len();
+      state.text.splice(state.cursor..state.cursor, inserted);
+      state.cursor += count;
+      return;
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - LineEditor //
+////////////////////////////////////////
+
+impl Default for LineEditor {
+   fn default(
+   ) -> Self {
+      return Self::new();
+   }
+}
+
+//////////////////////////////////
+// INTERNAL METHODS - EditState //
+//////////////////////////////////
+
+impl<'p> EditState<'p> {
+   /// Replaces the line, moving the
+   /// cursor to its end.
+   fn set_text(
+      & mut self,
+      text : Vec<char>,
+   ) {
+      self.cursor = text.len();
+      self.text   = text;
+      return;
+   }
+
+   /// Redraws the prompt and line over
+   /// what was drawn before.  Only carriage
+   /// returns and backspaces are used, so
+   /// this works in any console without
+   /// escape sequence support.
+   fn redraw(
+      & mut self,
+   ) {
+      use std::io::Write;
+
+      let length  = self.prompt.chars().count() + self.text.len();
+      let padding = self.drawn.saturating_sub(length);
+      let back    = padding + self.text.len() - self.cursor;
+
+      let mut stdout = std::io::stdout().lock();
+      let _ = write!(
+         stdout,
+         "\r{}{}{}{}",
+         self.prompt,
+         self.text.iter().collect::<String>(),
+         " ".repeat(padding),
+         "\x08".repeat(back),
+      );
+      let _ = stdout.flush();
+
+      self.drawn = length;
+      return;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn lock_commands<'l>(
+   commands : &'l CommandTable,
+) -> std::sync::MutexGuard<'l, std::collections::BTreeMap<String, Command>> {
+   return commands.lock().unwrap_or_else(|poison| poison.into_inner());
+}
+
+/// Splits a line into words at whitespace,
+/// where double quotes group words together
+/// and an empty pair of quotes is an empty
+/// word.
+fn split_words(
+   line : & str,
+) -> Vec<String> {
+   let mut words  = Vec::new();
+   let mut word   = None::<String>;
+   let mut quoted = false;
+   for character in line.chars() {
+      match character {
+         '"'                                                => {
+            quoted = !quoted;
+            word.get_or_insert_with(String::new);
+         },
+         character if character.is_whitespace() == true && quoted == false => {
+            words.extend(word.take());
+         },
+         character                                          => {
+            word.get_or_insert_with(String::new).push(character);
+         },
+      }
+   }
+
+   words.extend(word);
+   return words;
+}
+
+/// Runs the registered command named by
+/// the first word of a line.  The table
+/// isn't locked while the command runs,
+/// so commands can register others.
+fn run_line(
+   commands : & CommandTable,
+   line     : & str,
+) -> Option<CommandResult> {
+   let words = split_words(line);
+   let (name, arguments) = words.split_first()?;
+   let arguments = arguments.iter().map(String::as_str).collect::<Vec<&str>>();
+
+   let callback = lock_commands(commands).get(name).map(|command| {
+      std::sync::Arc::clone(&command.callback)
+   })?;
+
+   return Some(callback(&arguments));
+}
+
+/// Prints every command and its
+/// description in aligned columns.
+fn print_help(
+   commands : & CommandTable,
+) {
+   let mut entries = BUILTIN_COMMANDS.iter()
+      .map(|(name, description)| (String::from(*name), String::from(*description)))
+      .collect::<Vec<(String, String)>>();
+   entries.extend(lock_commands(commands).iter().map(|(name, command)| {
+      (name.clone(), command.description.clone())
+   }));
+   entries.sort();
+
+   let width = entries.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+   for (name, description) in entries {
+      let line = format!("  {name:<width$}  {description}");
+      println!("{}", line.trim_end());
+   }
+
+   return;
+}
+
+/// Body of the shell's thread, which reads
+/// and runs lines until it is told to stop
+/// or the input is closed.
+fn run_shell(
+   prompt   : & str,
+   commands : & CommandTable,
+   stop     : & std::sync::atomic::AtomicBool,
+) {
+   let mut editor = LineEditor::new();
+   loop {
+      let mut completions = lock_commands(commands).keys().cloned().collect::<Vec<String>>();
+      completions.extend(BUILTIN_COMMANDS.iter().map(|(name, _)| String::from(*name)));
+      editor.set_completions(completions);
+
+      let line = match editor.read_line_until(prompt, Some(stop)) {
+         Ok(Some(line)) => line,
+         Ok(None)       => return,
+         Err(err)       => {
+            crate::log::error!("Console shell stopped: {err}");
+            return;
+         },
+      };
+
+      let name = match split_words(&line).into_iter().next() {
+         Some(name)  => name,
+         None        => continue,
+      };
+
+      match name.as_str() {
+         "help"      => print_help(commands),
+         "history"   => {
+            for (index, line) in editor.history().iter().enumerate() {
+               println!("{:>4}  {line}", index + 1);
+            }
+         },
+         _           => match run_line(commands, &line) {
+            Some(Ok(()))   => (),
+            Some(Err(err)) => println!("{name}: {err}"),
+            None           => println!("Unknown command \"{name}\", enter \"help\" for a list of commands"),
+         },
+      }
+   }
+}
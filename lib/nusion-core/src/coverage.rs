@@ -0,0 +1,427 @@
+//! Find the game's hot code by sampling
+//! where its threads are executing, as a
+//! starting point for choosing where to
+//! hook without an external profiler.
+//!
+//! ```
+//! let env = nusion_core::environment::Environment::get();
+//!
+//! let sampling = nusion_core::coverage::CoverageSampler::new(env.modules().iter())
+//!    .interval(std::time::Duration::from_millis(2))
+//!    .bucket_size(64)
+//!    .start(env.tasks())?;
+//!
+//! std::thread::sleep(std::time::Duration::from_secs(10));
+//!
+//! let report = sampling.stop();
+//! for hit in report.hottest(20) {
+//!    println!("{hit}");
+//! }
+//! ```
+//!
+//! Samples are taken by briefly suspending
+//! each thread and reading its instruction
+//! pointer, so code which runs often shows
+//! up in proportion to how much time is spent
+//! in it.  Sampling is only supported on
+//! Windows.
+
+/// Time between samples unless
+/// changed by the user.
+const DEFAULT_INTERVAL : std::time::Duration
+   = std::time::Duration::from_millis(5);
+
+/// Size of each counted region unless
+/// changed by the user.
+const DEFAULT_BUCKET_SIZE : usize
+   = 64;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to coverage
+/// sampling.
+#[derive(Debug)]
+pub enum CoverageError {
+   ThreadError{
+      sys_error   : crate::sys::thread::ThreadError,
+   },
+   TaskError{
+      err         : crate::task::TaskError,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>CoverageError</code>.
+pub type Result<T> = std::result::Result<T, CoverageError>;
+
+/// Settings for sampling a set of
+/// modules, started with <code>start</code>.
+#[derive(Clone, Debug)]
+pub struct CoverageSampler {
+   modules     : Vec<(String, std::ops::Range<usize>)>,
+   interval    : std::time::Duration,
+   bucket_size : usize,
+}
+
+/// A handle to sampling running in the
+/// background.  Dropping the handle stops
+/// sampling and waits for its task to
+/// return.
+pub struct CoverageSampling {
+   task     : crate::task::TaskHandle,
+   report   : std::sync::Arc<std::sync::Mutex<CoverageReport>>,
+}
+
+/// Hit counts collected by sampling.
+/// Every sampled instruction pointer is
+/// counted, including ones outside of the
+/// sampled modules.
+#[derive(Clone, Debug)]
+pub struct CoverageReport {
+   bucket_size       : usize,
+   sample_count      : u64,
+   unmatched_count   : u64,
+   modules           : Vec<ModuleCoverage>,
+}
+
+/// The number of samples which landed
+/// in a region of a module.  The offset
+/// range is relative to the start of
+/// the module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverageHit {
+   pub module_name   : String,
+   pub offset_range  : std::ops::Range<usize>,
+   pub count         : u64,
+   pub fraction      : f64,
+}
+
+/// Hit counts for a single module.
+#[derive(Clone, Debug)]
+struct ModuleCoverage {
+   name           : String,
+   address_range  : std::ops::Range<usize>,
+   count          : u64,
+   buckets        : std::collections::HashMap<usize, u64>,
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - CoverageError //
+///////////////////////////////////////////
+
+impl std::fmt::Display for CoverageError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ThreadError{sys_error}
+            => write!(stream, "Thread error: {sys_error}"),
+         Self::TaskError{err}
+            => write!(stream, "Task error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for CoverageError {
+}
+
+impl From<crate::sys::thread::ThreadError> for CoverageError {
+   fn from(
+      value : crate::sys::thread::ThreadError,
+   ) -> Self {
+      return Self::ThreadError{
+         sys_error : value,
+      };
+   }
+}
+
+impl From<crate::task::TaskError> for CoverageError {
+   fn from(
+      value : crate::task::TaskError,
+   ) -> Self {
+      return Self::TaskError{
+         err : value,
+      };
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - CoverageHit //
+/////////////////////////////////////////
+
+impl std::fmt::Display for CoverageHit {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(
+         stream,
+         "{}+{:#X}..{:#X}: {} samples ({:.2}%)",
+         self.module_name,
+         self.offset_range.start,
+         self.offset_range.end,
+         self.count,
+         self.fraction * 100.0,
+      );
+   }
+}
+
+///////////////////////////////
+// METHODS - CoverageSampler //
+///////////////////////////////
+
+impl CoverageSampler {
+   /// Creates settings for sampling a set
+   /// of modules, such as every module in
+   /// the environment's module list.
+   pub fn new<'m, I>(
+      modules : I,
+   ) -> Self
+   where I: IntoIterator<Item = &'m crate::process::ModuleSnapshot>,
+   {
+      return Self{
+         modules     : modules.into_iter().map(|module| (
+            String::from(module.executable_file_name()),
+            module.address_range().clone(),
+         )).collect(),
+         interval    : DEFAULT_INTERVAL,
+         bucket_size : DEFAULT_BUCKET_SIZE,
+      };
+   }
+
+   /// Sets the time between samples.
+   pub fn interval(
+      mut self,
+      interval : std::time::Duration,
+   ) -> Self {
+      self.interval = interval;
+      return self;
+   }
+
+   /// Sets the size in bytes of the regions
+   /// samples are counted in.  Smaller regions
+   /// are more precise but spread the same
+   /// number of samples thinner.
+   pub fn bucket_size(
+      mut self,
+      bucket_size : usize,
+   ) -> Self {
+      self.bucket_size = usize::max(bucket_size, 1);
+      return self;
+   }
+
+   /// Starts sampling from a task spawned
+   /// on a runner, such as the environment's.
+   /// One sample is taken first so an
+   /// unsupported platform is reported here.
+   pub fn start(
+      self,
+      tasks : & crate::task::TaskRunner,
+   ) -> Result<CoverageSampling> {
+      crate::sys::thread::sample_instruction_pointers()?;
+
+      let report = std::sync::Arc::new(std::sync::Mutex::new(CoverageReport{
+         bucket_size       : self.bucket_size,
+         sample_count      : 0,
+         unmatched_count   : 0,
+         modules           : self.modules.into_iter().map(|(name, address_range)| ModuleCoverage{
+            name           : name,
+            address_range  : address_range,
+            count          : 0,
+            buckets        : std::collections::HashMap::new(),
+         }).collect(),
+      }));
+
+      let task_report   = report.clone();
+      let interval      = self.interval;
+      let task = tasks.spawn("nusion-coverage", move |context| {
+         loop {
+            if let Ok(instruction_pointers) = crate::sys::thread::sample_instruction_pointers() {
+               let mut report = task_report.lock().unwrap_or_else(|poison| poison.into_inner());
+               for instruction_pointer in instruction_pointers {
+                  report.record(instruction_pointer);
+               }
+            }
+            if context.sleep(interval) == false {
+               break;
+            }
+         }
+      })?;
+
+      return Ok(CoverageSampling{
+         task     : task,
+         report   : report,
+      });
+   }
+}
+
+////////////////////////////////
+// METHODS - CoverageSampling //
+////////////////////////////////
+
+impl CoverageSampling {
+   /// Gets a copy of the hit counts
+   /// collected so far without stopping.
+   pub fn report(
+      & self,
+   ) -> CoverageReport {
+      return self.report.lock().unwrap_or_else(|poison| poison.into_inner()).clone();
+   }
+
+   /// Stops sampling, returning the
+   /// final hit counts.
+   pub fn stop(
+      mut self,
+   ) -> CoverageReport {
+      self.join();
+      return self.report();
+   }
+}
+
+/////////////////////////////////////////
+// INTERNAL METHODS - CoverageSampling //
+/////////////////////////////////////////
+
+impl CoverageSampling {
+   fn join(
+      & mut self,
+   ) {
+      self.task.cancel();
+      self.task.wait();
+
+      return;
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - CoverageSampling //
+//////////////////////////////////////////////
+
+impl std::ops::Drop for CoverageSampling {
+   fn drop(
+      & mut self,
+   ) {
+      self.join();
+      return;
+   }
+}
+
+//////////////////////////////
+// METHODS - CoverageReport //
+//////////////////////////////
+
+impl CoverageReport {
+   /// Gets the number of instruction
+   /// pointers sampled, including ones
+   /// outside of the sampled modules.
+   pub fn sample_count(
+      & self,
+   ) -> u64 {
+      return self.sample_count;
+   }
+
+   /// Gets the number of samples which
+   /// weren't in any sampled module, such
+   /// as in JIT-compiled code.
+   pub fn unmatched_count(
+      & self,
+   ) -> u64 {
+      return self.unmatched_count;
+   }
+
+   /// Gets the number of samples in
+   /// each module, from most to fewest.
+   pub fn module_counts(
+      & self,
+   ) -> Vec<(String, u64)> {
+      let mut counts = self.modules.iter()
+         .filter(|module| module.count != 0)
+         .map(|module| (module.name.clone(), module.count))
+         .collect::<Vec<(String, u64)>>();
+
+      counts.sort_by(|a, b| b.1.cmp(&a.1));
+      return counts;
+   }
+
+   /// Gets up to a number of the regions
+   /// with the most samples across every
+   /// module, from most to fewest.
+   pub fn hottest(
+      & self,
+      count : usize,
+   ) -> Vec<CoverageHit> {
+      return self.collect_hits(self.modules.iter(), count);
+   }
+
+   /// Gets up to a number of the regions
+   /// with the most samples within one
+   /// module, from most to fewest.
+   pub fn hottest_in(
+      & self,
+      module_name : & str,
+      count       : usize,
+   ) -> Vec<CoverageHit> {
+      return self.collect_hits(
+         self.modules.iter().filter(|module| module.name == module_name),
+         count,
+      );
+   }
+}
+
+///////////////////////////////////////
+// INTERNAL METHODS - CoverageReport //
+///////////////////////////////////////
+
+impl CoverageReport {
+   /// Counts a sampled instruction
+   /// pointer in the module and region
+   /// containing it.
+   fn record(
+      & mut self,
+      instruction_pointer : usize,
+   ) {
+      self.sample_count += 1;
+
+      let bucket_size = self.bucket_size;
+      match self.modules.iter_mut().find(|module| {
+         module.address_range.contains(&instruction_pointer)
+      }) {
+         Some(module)   => {
+            let offset = instruction_pointer - module.address_range.start;
+            module.count += 1;
+            *module.buckets.entry(offset - offset % bucket_size).or_insert(0) += 1;
+         },
+         None           => self.unmatched_count += 1,
+      }
+
+      return;
+   }
+
+   /// Sorts the regions of the given
+   /// modules by their number of samples.
+   fn collect_hits<'m, I>(
+      &'m self,
+      modules  : I,
+      count    : usize,
+   ) -> Vec<CoverageHit>
+   where I: Iterator<Item = &'m ModuleCoverage>,
+   {
+      let total = u64::max(self.sample_count, 1) as f64;
+
+      let mut hits = modules.flat_map(|module| {
+         module.buckets.iter().map(|(offset, hits)| CoverageHit{
+            module_name    : module.name.clone(),
+            offset_range   : *offset..offset + self.bucket_size,
+            count          : *hits,
+            fraction       : *hits as f64 / total,
+         })
+      }).collect::<Vec<CoverageHit>>();
+
+      hits.sort_by(|a, b| b.count.cmp(&a.count).then(a.offset_range.start.cmp(&b.offset_range.start)));
+      hits.truncate(count);
+      return hits;
+   }
+}
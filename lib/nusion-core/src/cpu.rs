@@ -0,0 +1,156 @@
+//! Processor feature detection for
+//! selecting instruction variants in
+//! assembly patches at runtime.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to the processor.
+#[derive(Debug)]
+pub enum CpuError {
+   MissingFeature{
+      feature  : Feature,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>CpuError</code>.
+pub type Result<T> = std::result::Result<T, CpuError>;
+
+/// An instruction set extension.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Feature {
+   Sse4_1,
+   Sse4_2,
+   Popcnt,
+   Avx,
+   Avx2,
+   Fma,
+   Bmi1,
+   Bmi2,
+   Lzcnt,
+}
+
+/// The instruction set extensions
+/// which can be used on the current
+/// machine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Features {
+   features : crate::sys::processor::Features,
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - CpuError //
+//////////////////////////////////////
+
+impl std::fmt::Display for CpuError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::MissingFeature{feature}
+            => write!(stream, "Processor does not support {feature}"),
+      };
+   }
+}
+
+impl std::error::Error for CpuError {
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Feature //
+/////////////////////////////////////
+
+impl std::fmt::Display for Feature {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::Sse4_1   => "SSE4.1",
+         Self::Sse4_2   => "SSE4.2",
+         Self::Popcnt   => "POPCNT",
+         Self::Avx      => "AVX",
+         Self::Avx2     => "AVX2",
+         Self::Fma      => "FMA",
+         Self::Bmi1     => "BMI1",
+         Self::Bmi2     => "BMI2",
+         Self::Lzcnt    => "LZCNT",
+      });
+   }
+}
+
+////////////////////////
+// METHODS - Features //
+////////////////////////
+
+impl Features {
+   /// Checks whether a single extension
+   /// is supported.
+   pub fn has(
+      & self,
+      feature : Feature,
+   ) -> bool {
+      let features = &self.features;
+      return match feature {
+         Feature::Sse4_1   => features.sse4_1,
+         Feature::Sse4_2   => features.sse4_2,
+         Feature::Popcnt   => features.popcnt,
+         Feature::Avx      => features.avx,
+         Feature::Avx2     => features.avx2,
+         Feature::Fma      => features.fma,
+         Feature::Bmi1     => features.bmi1,
+         Feature::Bmi2     => features.bmi2,
+         Feature::Lzcnt    => features.lzcnt,
+      };
+   }
+
+   /// Checks that every extension is
+   /// supported, returning an error for
+   /// the first one which isn't.
+   pub fn require(
+      & self,
+      features : & [Feature],
+   ) -> Result<()> {
+      for feature in features {
+         if self.has(*feature) == false {
+            return Err(CpuError::MissingFeature{
+               feature : *feature,
+            });
+         }
+      }
+
+      return Ok(());
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Queries which instruction set
+/// extensions can be used on the
+/// current machine.
+pub fn features() -> Features {
+   return Features{
+      features : crate::sys::processor::features(),
+   };
+}
+
+/// Shorthand for checking every extension
+/// is supported before applying a patch
+/// which uses them.
+///
+/// ```
+/// nusion_core::cpu::require(&[
+///    nusion_core::cpu::Feature::Avx2,
+///    nusion_core::cpu::Feature::Bmi2,
+/// ])?;
+/// ```
+pub fn require(
+   features : & [Feature],
+) -> Result<()> {
+   return self::features().require(features);
+}
@@ -0,0 +1,224 @@
+//! Hardware breakpoints for finding what
+//! reads, writes, or executes an address,
+//! set in the debug registers of every
+//! thread.  Only four can exist at once.
+//!
+//! ```
+//! use nusion_core::debug::{Watchpoint, WatchKind};
+//!
+//! let health = game_base + 0x1A2B30;
+//! let watchpoint = unsafe{Watchpoint::new(health, 4, WatchKind::Write, |hit| {
+//!    nusion_core::log::info!("Health written before {:#X}", hit.instruction_pointer);
+//! })}?;
+//! ```
+
+use std::sync::RwLock;
+
+// The access kinds and hit information
+// are provided by the system abstraction
+// layer as-is
+pub use crate::sys::debug::{
+   WatchKind,
+   WatchpointHit,
+};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to setting
+/// hardware breakpoints.
+#[derive(Debug)]
+pub enum DebugError {
+   Unsupported,
+   InvalidLength{
+      length   : usize,
+   },
+   Misaligned{
+      address  : usize,
+      length   : usize,
+   },
+   NoFreeSlot,
+   Unknown,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>DebugError</code>.
+pub type Result<T> = std::result::Result<T, DebugError>;
+
+/// A hardware breakpoint which calls a
+/// function whenever it is triggered.
+/// The breakpoint is removed when this
+/// is dropped.
+pub struct Watchpoint {
+   slot : usize,
+}
+
+/// Function called when a hardware
+/// breakpoint is triggered.
+type Callback = Box<dyn Fn(& WatchpointHit) + Send + Sync>;
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - DebugError //
+////////////////////////////////////////
+
+impl std::fmt::Display for DebugError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::Unsupported
+            => write!(stream, "Unsupported on this platform"),
+         Self::InvalidLength{length}
+            => write!(stream, "Cannot watch {length} bytes, must be 1, 2, 4 or 8"),
+         Self::Misaligned{address, length}
+            => write!(stream, "Address {address:#X} isn't aligned to {length} bytes"),
+         Self::NoFreeSlot
+            => write!(stream, "All {} hardware breakpoints are in use", crate::sys::debug::SLOT_COUNT),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+      };
+   }
+}
+
+impl std::error::Error for DebugError {
+}
+
+impl From<crate::sys::debug::DebugError> for DebugError {
+   fn from(
+      item : crate::sys::debug::DebugError,
+   ) -> Self {
+      use crate::sys::debug::DebugError::*;
+      return match item {
+         Unsupported
+            => Self::Unsupported,
+         InvalidLength{length}
+            => Self::InvalidLength{length : length},
+         Misaligned{address, length}
+            => Self::Misaligned{address : address, length : length},
+         InvalidSlot{..} | Unknown
+            => Self::Unknown,
+      };
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Watchpoint //
+////////////////////////////////////////
+
+impl Drop for Watchpoint {
+   fn drop(
+      & mut self,
+   ) {
+      if let Err(err) = unsafe{crate::sys::debug::clear_watchpoint(self.slot)} {
+         crate::log::warn!("Failed to clear hardware breakpoint {}: {err}", self.slot);
+      }
+
+      release_slot(self.slot);
+      return;
+   }
+}
+
+//////////////////////////
+// METHODS - Watchpoint //
+//////////////////////////
+
+impl Watchpoint {
+   /// Sets a hardware breakpoint on
+   /// <code>length</code> bytes at an address
+   /// in every thread which currently exists.
+   /// Reads and writes are reported after the
+   /// accessing instruction has run.  Execute
+   /// breakpoints must have a length of 1.
+   ///
+   /// The callback runs on the thread which
+   /// triggered the breakpoint, so it must not
+   /// access the watched address itself.
+   ///
+   /// <h2 id=  watchpoint_new_safety>
+   /// <a href=#watchpoint_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// Every other thread is briefly suspended,
+   /// so all safety concerns from <code><a href=
+   /// ../runtime/fn.pause_game.html#runtime_pause_game_safety>runtime::pause_game</a></code>
+   /// apply.  Debuggers and anti-cheat software
+   /// may also use the debug registers.
+   pub unsafe fn new<F>(
+      address  : usize,
+      length   : usize,
+      kind     : WatchKind,
+      callback : F,
+   ) -> Result<Self>
+   where F: Fn(& WatchpointHit) + Send + Sync + 'static,
+   {
+      let slot = {
+         let mut callbacks = lock_callbacks_write();
+         let slot = callbacks.iter().position(Option::is_none).ok_or(DebugError::NoFreeSlot)?;
+
+         crate::sys::debug::set_hit_handler(dispatch_hit)?;
+         callbacks[slot] = Some(Box::new(callback));
+         slot
+      };
+
+      if let Err(err) = crate::sys::debug::set_watchpoint(slot, address, length, kind) {
+         release_slot(slot);
+         return Err(err.into());
+      }
+
+      return Ok(Self{
+         slot : slot,
+      });
+   }
+
+   /// Gets the debug register slot
+   /// used by the breakpoint.
+   pub fn slot(
+      & self,
+   ) -> usize {
+      return self.slot;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn lock_callbacks_write(
+) -> std::sync::RwLockWriteGuard<'static, [Option<Callback>; crate::sys::debug::SLOT_COUNT]> {
+   return CALLBACKS.write().unwrap_or_else(|poison| poison.into_inner());
+}
+
+/// Frees a callback slot, removing the
+/// exception handler once no slots are used.
+fn release_slot(
+   slot : usize,
+) {
+   let mut callbacks = lock_callbacks_write();
+   callbacks[slot] = None;
+   if callbacks.iter().all(Option::is_none) == true {
+      crate::sys::debug::clear_hit_handler();
+   }
+   return;
+}
+
+fn dispatch_hit(
+   hit : & WatchpointHit,
+) {
+   let callbacks = CALLBACKS.read().unwrap_or_else(|poison| poison.into_inner());
+   if let Some(callback) = &callbacks[hit.slot] {
+      callback(hit);
+   }
+   return;
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static CALLBACKS
+   : RwLock<[Option<Callback>; crate::sys::debug::SLOT_COUNT]>
+   = RwLock::new([None, None, None, None]);
+
@@ -0,0 +1,26 @@
+//! Conversions between Rust strings and
+//! the narrow and wide null-terminated
+//! strings used by operating system APIs.
+//! These are useful when calling the
+//! operating system directly, such as
+//! reading window titles or file paths.
+//!
+//! ```
+//! let title = nusion_core::encoding::to_wide_nul("Modded!")?;
+//! unsafe{SetWindowTextW(window, title.as_ptr())};
+//! ```
+
+// Everything is provided by the
+// system abstraction layer as-is
+pub use crate::sys::encoding::{
+   EncodingError,
+   Result,
+   to_wide_nul,
+   to_narrow_nul,
+   from_wide,
+   from_wide_lossy,
+   from_wide_nul,
+   from_narrow_nul,
+   read_wide_nul,
+   read_wide_nul_string,
+};
@@ -24,12 +24,13 @@ macro_rules! debug_sleep {
 /////////////////////////////////
 
 /// Prints an error report to the
-/// console and writes it to disk
+/// console and writes it to disk,
+/// returning the path written to
 fn output_error_report(
    error_report   : & str,
    file_name      : & str,
    file_extension : & str,
-) {
+) -> std::path::PathBuf {
    // Get the time since the Unix Epoch Time
    // for creating a time stamp for the error
    // log file.
@@ -38,18 +39,21 @@ fn output_error_report(
       .unwrap_or(std::time::Duration::from_secs(0))
       .as_secs();
 
-   // Get the current working directory to
-   // start enumerating the full file path
-   // for the error log.  This is done instead
-   // of using a relative path because since
-   // we may be panicking from the injected
+   // Get the working directory from when the
+   // mod started to begin enumerating the full
+   // file path for the error log.  This is done
+   // instead of using a relative path because
+   // since we may be panicking from the injected
    // process, it will output the error log
    // to the game's executable folder, not
    // the injected library's folder.  This
-   // can lead to lots of confusion.
-   let mut file_path = std::env::current_dir().unwrap_or(
-      std::path::PathBuf::new(),
-   );
+   // can lead to lots of confusion.  Launchers
+   // may change the working directory mid-run,
+   // so the current one is only a fallback.
+   let mut file_path = crate::host::initial_working_directory()
+      .map(std::path::Path::to_path_buf)
+      .or_else(|| std::env::current_dir().ok())
+      .unwrap_or(std::path::PathBuf::new());
 
    // Append file name, time, and extension
    file_path.push(std::path::Path::new("temp.bin"));
@@ -73,19 +77,45 @@ fn output_error_report(
       eprintln!("Grumble...grumble...");
    });
 
+   return file_path;
+}
+
+/// Writes a minidump next to an error
+/// report, sharing its name and time stamp
+fn output_minidump(
+   report_path : & std::path::Path,
+) {
+   let file_path = report_path.with_extension("dmp");
+
+   println!(
+      "Writing minidump to \"{}\"...\n",
+      file_path.to_str().unwrap_or("(invalid text)"),
+   );
+
+   crate::sys::process::write_minidump(&file_path).unwrap_or_else(|e| {
+      eprintln!("Failed to write the minidump! {e}");
+   });
+
    return;
 }
 
 /// Panic handler hook for printing
 /// the call stack and source code
-/// unwrap location
-fn panic_handler(panic_info : & std::panic::PanicInfo<'_>) {
+/// unwrap location, optionally followed
+/// by a minidump of the whole process
+fn panic_handler(panic_info : & std::panic::PanicInfo<'_>, minidump : bool) {
    // Error log file output name and extension
    const ERROR_REPORT_FILE_NAME  : &'static str
       = "nusion-panic-report";
    const ERROR_REPORT_FILE_EXT   : &'static str
       = "txt";
-   
+
+   // The panic may still be caught, so the
+   // patches are only reverted once it
+   // reaches the main starter or the
+   // environment is freed
+   let active = crate::patch::active_count();
+
    // Error log formatting buffer
    let mut err_buffer = String::new();
 
@@ -94,10 +124,16 @@ fn panic_handler(panic_info : & std::panic::PanicInfo<'_>) {
    err_buffer += "!!!       NUSION PANICKED       !!!\n";
    err_buffer += "!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!\n\n";
 
+   // Which build of the library panicked
+   err_buffer += &format!("{}\n\n", crate::BUILD_INFO);
+
    // Use the default formatter to format
    // the panic info payload
    err_buffer += &format!("{panic_info}\n\n");
 
+   // How many patches were still applied
+   err_buffer += &format!("{active} patches were active\n\n");
+
    // Format the call stack from most to least recent function
    err_buffer += "----------- Call stack ------------\n";
    for frame in backtrace::Backtrace::new().frames().iter() {
@@ -169,12 +205,19 @@ fn panic_handler(panic_info : & std::panic::PanicInfo<'_>) {
    err_buffer += "-----------------------------------\n\n";
 
    // Output the error report
-   output_error_report(
+   let report_path = output_error_report(
       &err_buffer,
       ERROR_REPORT_FILE_NAME,
       ERROR_REPORT_FILE_EXT,
    );
 
+   // The dump is written from the panicking
+   // thread, so its stack shows exactly
+   // where the panic came from
+   if minidump == true {
+      output_minidump(&report_path);
+   }
+
    // Sleep in debug builds to give time to
    // analyze the panic
    debug_sleep!();
@@ -199,6 +242,9 @@ pub fn report_error(err : & str) {
    err_buffer += "!!!       NUSION ERRORED       !!!\n";
    err_buffer += "!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!\n\n";
 
+   // Which build of the library errored
+   err_buffer += &format!("{}\n\n", crate::BUILD_INFO);
+
    // Format the error string 
    err_buffer += &format!("{err}\n\n");
 
@@ -230,31 +276,122 @@ pub enum EnvironmentError {
    ProcessError{
       err : crate::process::ProcessError,
    },
+   PatchError{
+      err : crate::patch::PatchError,
+   },
+   ClipboardError{
+      err : crate::clipboard::ClipboardError,
+   },
+   ConfigError{
+      err : crate::config::ConfigError,
+   },
+   LogError{
+      err : crate::log::LogError,
+   },
+   TaskError{
+      err : crate::task::TaskError,
+   },
+   WindowError{
+      err : crate::window::WindowError,
+   },
 }
 
 /// <code>Result</code> type with error
 /// variant <code>EnvironmentError</code>
 pub type Result<T> = std::result::Result<T, EnvironmentError>;
 
+/// Where the mod's console comes from,
+/// set through the <code>console</code>
+/// attribute option of
+/// <code>nusion_core::main</code>.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConsoleMode {
+   /// No console is created or attached,
+   /// so the mod runs without ever showing
+   /// a console window.  Output written to
+   /// standard output is discarded unless
+   /// the game already has somewhere to
+   /// send it.
+   None,
+   /// A separate console window is created
+   /// for the mod.
+   #[default]
+   Window,
+   /// The console of the process which
+   /// started the game is used, such as
+   /// the terminal it was launched from.
+   /// No window is created, and the
+   /// environment fails to start if the
+   /// parent has no console.
+   Attach,
+}
+
+/// Options for creating the environment,
+/// set through the attribute options of
+/// <code>nusion_core::main</code>.
+#[derive(Clone, Debug)]
+pub struct EnvironmentOptions {
+   /// Where the mod's console comes from.
+   pub console       : ConsoleMode,
+   /// Title to give the console window,
+   /// ignored unless a window is created.
+   pub title         : Option<&'static str>,
+   /// Whether a minidump is written next
+   /// to the panic report.  Only supported
+   /// on Windows.
+   pub minidump      : bool,
+   /// Whether main tells a launcher waiting
+   /// in <code>inject::inject_and_resume</code>
+   /// that the mod is ready by calling
+   /// <code>inject::signal_ready</code> itself.
+   /// Otherwise it's signaled as soon as the
+   /// environment is created, before main runs.
+   pub manual_ready  : bool,
+}
+
 /// Struct for storing and managing
-/// environment information.  In
-/// debug builds, a separate console
-/// window is created for debugging
-/// purposes.  If <code>main</code>
+/// environment information.  Unless
+/// disabled in the attribute options
+/// of <code>main</code>, a separate
+/// console window is created for
+/// debugging purposes.  If <code>main</code>
 /// fails to start, <code>main </code>
 /// returns an error, or at any point
 /// the program panics, an error log
-/// is written inside the game executable's
-/// directory.  In addition, in debug builds
-/// the environment will wait for a brief
+/// is written inside the game's working
+/// directory from when the mod started.
+/// In addition, in debug builds the
+/// environment will wait for a brief
 /// period of time before exiting to
 /// give developers a chance to notice
 /// the error or panic and see the output
-/// file path.
+/// file path.  Any patches still applied
+/// when the program panics or the
+/// environment is freed are reverted
+/// first.
 pub struct Environment {
-   console  : crate::console::Console,
+   console  : Option<crate::console::Console>,
    process  : crate::process::ProcessSnapshot,
    modules  : crate::process::ModuleSnapshotList,
+   history  : crate::history::PatchHistory,
+   config   : crate::config::Config,
+   tasks    : std::sync::Arc<crate::task::TaskRunner>,
+   host     : crate::host::HostEnvironment,
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ConsoleMode //
+/////////////////////////////////////////
+
+impl From<bool> for ConsoleMode {
+   fn from(
+      item : bool,
+   ) -> Self {
+      return match item {
+         true  => Self::Window,
+         false => Self::None,
+      };
+   }
 }
 
 //////////////////////////////////////////////
@@ -273,6 +410,18 @@ impl std::fmt::Display for EnvironmentError {
             => write!(stream, "Console error: {err}"),
          Self::ProcessError{err}
             => write!(stream, "Process error: {err}"),
+         Self::PatchError{err}
+            => write!(stream, "Patch error: {err}"),
+         Self::ClipboardError{err}
+            => write!(stream, "Clipboard error: {err}"),
+         Self::ConfigError{err}
+            => write!(stream, "Config error: {err}"),
+         Self::LogError{err}
+            => write!(stream, "Log error: {err}"),
+         Self::TaskError{err}
+            => write!(stream, "Task error: {err}"),
+         Self::WindowError{err}
+            => write!(stream, "Window error: {err}"),
       };
    }
 }
@@ -308,6 +457,66 @@ impl From<crate::process::ProcessError> for EnvironmentError {
    }
 }
 
+impl From<crate::patch::PatchError> for EnvironmentError {
+   fn from(
+      item : crate::patch::PatchError,
+   ) -> Self {
+      return Self::PatchError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::clipboard::ClipboardError> for EnvironmentError {
+   fn from(
+      item : crate::clipboard::ClipboardError,
+   ) -> Self {
+      return Self::ClipboardError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::config::ConfigError> for EnvironmentError {
+   fn from(
+      item : crate::config::ConfigError,
+   ) -> Self {
+      return Self::ConfigError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::log::LogError> for EnvironmentError {
+   fn from(
+      item : crate::log::LogError,
+   ) -> Self {
+      return Self::LogError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::task::TaskError> for EnvironmentError {
+   fn from(
+      item : crate::task::TaskError,
+   ) -> Self {
+      return Self::TaskError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::window::WindowError> for EnvironmentError {
+   fn from(
+      item : crate::window::WindowError,
+   ) -> Self {
+      return Self::WindowError{
+         err : item,
+      };
+   }
+}
+
 ////////////////////////////////
 // GLOBAL STATE - Environment //
 ////////////////////////////////
@@ -316,6 +525,10 @@ static mut ENVIRONMENT_GLOBAL_STATE
    : Option<Environment>
    = None;
 
+static UNLOAD_REQUESTED
+   : std::sync::atomic::AtomicBool
+   = std::sync::atomic::AtomicBool::new(false);
+
 lazy_static::lazy_static!{
 static ref ENVIRONMENT_GLOBAL_STATE_LOCK
    : RwLock<&'static mut Environment>
@@ -361,6 +574,20 @@ impl Environment {
    }
 
    fn global_state_free() -> Result<Self> {
+      // Stop every task before anything else
+      // is torn down.  The lock is released
+      // first since tasks may still be waiting
+      // to access the environment.
+      let tasks = Self::global_state_lock()?.tasks.clone();
+      tasks.shutdown();
+
+      // The shell may also be running a command
+      // which is waiting to access the environment
+      let shell = Self::global_state_lock()?.console.as_ref().and_then(|console| console.take_shell());
+      if let Some(shell) = shell {
+         shell.stop();
+      }
+
       // Obtain the lock to ensure thread safety
       let _write_lock = Self::global_state_lock_mut()?;
 
@@ -379,13 +606,59 @@ impl Environment {
 impl Environment {
    /// Creates a new instance of an
    /// environment
-   fn new() -> Result<Self> {
+   fn new(
+      options : & EnvironmentOptions,
+   ) -> Result<Self> {
+      // Record the working directory before
+      // anything else, so error reports land
+      // in the same place even if creating
+      // the environment fails
+      let host = crate::host::HostEnvironment::new();
+
       // Register our panic hook before all
       // else so we get proper panic behavior
       // if any of the below panics.
-      std::panic::set_hook(Box::new(panic_handler));
+      let minidump = options.minidump;
+      std::panic::set_hook(Box::new(move |panic_info| {
+         panic_handler(panic_info, minidump);
+      }));
+
+      let console = match options.console {
+         ConsoleMode::None    => None,
+         ConsoleMode::Window  => {
+            let mut console = crate::console::Console::new()?;
+            if let Some(title) = options.title {
+               console.set_title(title)?;
+            }
+            Some(console)
+         },
+         ConsoleMode::Attach  => Some(crate::console::Console::with_options(
+            &crate::console::ConsoleOptions{
+               attach : true,
+               ..Default::default()
+            },
+         )?),
+      };
+
+      // Log output needs the console, so
+      // install the logger once it exists.
+      // Another logger may already be
+      // installed, which still works.
+      if let Err(err) = crate::log::init() {
+         crate::log::warn!("Failed to install the logger, using the existing one instead: {err}");
+      }
 
-      let console = crate::console::Console::new()?;
+      // Startup banner, so every log starts
+      // with which build produced it
+      crate::log::info!("{}", crate::BUILD_INFO);
+
+      // A malformed config shouldn't stop the
+      // mod from starting, so fall back to
+      // every setting's default
+      let config = crate::config::Config::open_default().unwrap_or_else(|err| {
+         crate::log::error!("Failed to load the config, using defaults instead: {err}");
+         return crate::config::Config::empty_default();
+      });
 
       let process = crate::process::ProcessSnapshot::local()?;
 
@@ -397,6 +670,10 @@ impl Environment {
          console  : console,
          process  : process,
          modules  : modules,
+         history  : crate::history::PatchHistory::new(),
+         config   : config,
+         tasks    : std::sync::Arc::new(crate::task::TaskRunner::new()),
+         host     : host,
       });
    }
 }
@@ -409,7 +686,24 @@ impl std::ops::Drop for Environment {
    fn drop(
       & mut self,
    ) {
+      // Pages held open for reading go back
+      // to their own permissions, and the
+      // cache's thread must exit before the
+      // library is unloaded
+      crate::patch::flush_editor_cache();
+
+      // Patches the mod never dropped would
+      // keep pointing into the library after
+      // it is unloaded
+      let reverted = crate::patch::revert_all();
+      if reverted != 0 {
+         crate::log::warn!("Reverted {reverted} patches which were still active when the environment was freed");
+      }
+
       let _ = std::panic::take_hook();
+      crate::sys::exception::uninstall();
+      crate::sys::window::uninstall();
+      crate::plugin::notify_host_unloading();
       return;
    }
 }
@@ -472,19 +766,21 @@ impl Environment {
    } 
 
    /// Gets a reference to the stored
-   /// console.
+   /// console, or <code>None</code> if
+   /// the mod was started without one.
    pub fn console<'l>(
       &'l self,
-   ) -> &'l crate::console::Console {
-      return &self.console;
+   ) -> Option<&'l crate::console::Console> {
+      return self.console.as_ref();
    }
 
    /// Gets a mutable reference to the
-   /// stored console.
+   /// stored console, or <code>None</code>
+   /// if the mod was started without one.
    pub fn console_mut<'l>(
       &'l mut self,
-   ) -> &'l mut crate::console::Console {
-      return & mut self.console;
+   ) -> Option<&'l mut crate::console::Console> {
+      return self.console.as_mut();
    }
 
    /// Gets a reference to the current
@@ -528,6 +824,272 @@ impl Environment {
       self.modules = modules;
       return Ok(self);
    }
+
+   /// Gets a reference to the mod's config,
+   /// which is loaded from the file named
+   /// after the mod's library when the
+   /// environment starts.
+   pub fn config<'l>(
+      &'l self,
+   ) -> &'l crate::config::Config {
+      return &self.config;
+   }
+
+   /// Gets a mutable reference to the
+   /// mod's config.
+   pub fn config_mut<'l>(
+      &'l mut self,
+   ) -> &'l mut crate::config::Config {
+      return & mut self.config;
+   }
+
+   /// Gets a reference to the host process's
+   /// environment variables and working
+   /// directory.
+   pub fn host<'l>(
+      &'l self,
+   ) -> &'l crate::host::HostEnvironment {
+      return &self.host;
+   }
+
+   /// Gets a mutable reference to the host
+   /// process's environment variables and
+   /// working directory, which is needed to
+   /// watch variables and poll for changes.
+   pub fn host_mut<'l>(
+      &'l mut self,
+   ) -> &'l mut crate::host::HostEnvironment {
+      return & mut self.host;
+   }
+
+   /// Finds the game's main window, such as
+   /// to hook the messages sent to it.  The
+   /// window is looked up on every call, since
+   /// the game may not have created it yet
+   /// when the mod starts.  Any message hooks
+   /// still registered when the environment
+   /// is freed are removed.
+   pub fn window(
+      & self,
+   ) -> Result<crate::window::Window> {
+      return Ok(crate::window::Window::find()?);
+   }
+
+   /// Spawns a background task which is
+   /// cancelled and joined when the environment
+   /// is freed, so it can't outlive the mod.
+   /// See <code>task::TaskRunner::spawn</code>.
+   pub fn spawn_task<F>(
+      & self,
+      name : & str,
+      task : F,
+   ) -> Result<crate::task::TaskHandle>
+   where F: FnOnce(& crate::task::TaskContext) + Send + 'static,
+   {
+      return Ok(self.tasks.spawn(name, task)?);
+   }
+
+   /// Gets the runner which owns every
+   /// task spawned through the environment.
+   pub fn tasks<'l>(
+      &'l self,
+   ) -> &'l crate::task::TaskRunner {
+      return &self.tasks;
+   }
+
+   /// Holds a value at the end of a pointer
+   /// chain within a module by its executable
+   /// file name until the returned handle is
+   /// dropped, or the environment is freed.
+   /// See <code>freeze::freeze_chain</code>.
+   ///
+   /// <h2 id=  environment_freeze_chain_safety>
+   /// <a href=#environment_freeze_chain_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>freeze::freeze_chain</code> apply.
+   pub unsafe fn freeze_chain<T>(
+      & self,
+      module_name : & str,
+      base_offset : usize,
+      offsets     : & [usize],
+      value       : T,
+   ) -> Result<crate::freeze::FrozenValue>
+   where T: Copy + Send + 'static,
+   {
+      let module = self.modules.get(module_name).ok_or(
+         crate::patch::PatchError::ModuleNotFound{
+            module_name : String::from(module_name),
+         },
+      )?;
+
+      return Ok(crate::freeze::freeze_chain(&self.tasks, module, base_offset, offsets, value)?);
+   }
+
+   /// Gets a reference to the history
+   /// of patches applied through the
+   /// environment.
+   pub fn patch_history<'l>(
+      &'l self,
+   ) -> &'l crate::history::PatchHistory {
+      return &self.history;
+   }
+
+   /// Applies a writer to a module by
+   /// its executable file name and records
+   /// it in the patch history so it can be
+   /// undone and redone later.
+   ///
+   /// <h2 id=  environment_patch_apply_safety>
+   /// <a href=#environment_patch_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>PatchHistory::apply</code> apply.
+   pub unsafe fn patch_apply<W, R>(
+      & mut self,
+      module_name : & str,
+      name        : & str,
+      writer      : W,
+   ) -> Result<& mut Self>
+   where W: crate::patch::Writer<R> + Send + Sync + 'static,
+         R: std::ops::RangeBounds<usize>,
+   {
+      self.history.apply(& mut self.modules, module_name, name, writer)?;
+      return Ok(self);
+   }
+
+   /// Reverts the most recently applied
+   /// patch in the patch history.
+   pub fn patch_undo<'l>(
+      &'l mut self,
+   ) -> Option<&'l str> {
+      return self.history.undo();
+   }
+
+   /// Re-applies the most recently
+   /// reverted patch in the patch history.
+   ///
+   /// <h2 id=  environment_patch_redo_safety>
+   /// <a href=#environment_patch_redo_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>PatchHistory::redo</code> apply.
+   pub unsafe fn patch_redo<'l>(
+      &'l mut self,
+   ) -> Result<Option<&'l str>> {
+      return Ok(self.history.redo(& mut self.modules)?);
+   }
+
+   /// Runs a patch history console
+   /// command such as <code>undo</code>
+   /// or <code>redo</code>.  Returns
+   /// <code>false</code> if the command
+   /// isn't recognized.
+   ///
+   /// <h2 id=  environment_patch_command_safety>
+   /// <a href=#environment_patch_command_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>PatchHistory::run_command</code> apply.
+   pub unsafe fn patch_command(
+      & mut self,
+      command  : & str,
+   ) -> Result<bool> {
+      return Ok(self.history.run_command(& mut self.modules, command)?);
+   }
+
+   /// Runs a clipboard console command
+   /// such as <code>copy lines 20</code>.
+   /// Returns <code>false</code> if the
+   /// command isn't recognized.
+   ///
+   /// <h2 id=  environment_clipboard_command_safety>
+   /// <a href=#environment_clipboard_command_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>clipboard::run_command</code> apply.
+   pub unsafe fn clipboard_command(
+      & self,
+      command  : & str,
+   ) -> Result<bool> {
+      return Ok(crate::clipboard::run_command(self.console.as_ref(), &self.modules, command)?);
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Requests the mod to unload itself.
+/// This only sets a flag, so the mod's
+/// main loop should check
+/// <code>is_unload_requested</code> and
+/// return from <code>main</code>.  Once
+/// <code>main</code> returns, the environment
+/// is freed, reverting every patch in the
+/// patch history, and the library is freed
+/// from the process on Windows.  Any other
+/// patch containers must be dropped before
+/// returning, otherwise the game will be
+/// left calling into unloaded code.
+///
+/// ```
+/// #[nusion_core::main("game.exe")]
+/// fn main() {
+///    let _hook = unsafe{crate::game_mut!().patch_create(&HOOK)}?;
+///
+///    // Unloads when any thread calls
+///    // request_unload or the console
+///    // is closed
+///    nusion_core::wait_for_unload(std::time::Duration::from_millis(250));
+/// }
+/// ```
+pub fn request_unload() {
+   UNLOAD_REQUESTED.store(true, std::sync::atomic::Ordering::Relaxed);
+   return;
+}
+
+/// Checks whether the mod should unload
+/// itself, either because <code>request_unload</code>
+/// was called or the user tried to close
+/// the console.
+pub fn is_unload_requested(
+) -> bool {
+   if UNLOAD_REQUESTED.load(std::sync::atomic::Ordering::Relaxed) == true {
+      return true;
+   }
+
+   // Don't block if the environment is
+   // currently locked for writing
+   return match ENVIRONMENT_GLOBAL_STATE_LOCK.try_read() {
+      Ok(env)  => match env.console() {
+         Some(console)  => console.is_close_requested(),
+         None           => false,
+      },
+      Err(_)   => false,
+   };
+}
+
+/// Blocks the thread until the mod should
+/// unload, checking once every poll interval.
+pub fn wait_for_unload(
+   poll_interval : std::time::Duration,
+) {
+   while is_unload_requested() == false {
+      std::thread::sleep(poll_interval);
+   }
+
+   return;
 }
 
 ////////////////////////////////
@@ -541,8 +1103,8 @@ impl Environment {
 /// In debug mode, it will sleep for a
 /// brief period of time before exiting.
 macro_rules! environment_init {
-   () => {
-      match Environment::new() {
+   ($options:ident) => {
+      match Environment::new($options) {
          Ok(env)  => env.global_state_init(),
          Err(e)   => {
             report_error(&format!("Failed to initialize environment: {e}"));
@@ -552,13 +1114,62 @@ macro_rules! environment_init {
    };
 }
 
+/// Acquires the instance lock for the
+/// mod, returning from the caller with
+/// OSReturn::FAILURE if the same mod was
+/// already injected into this process.
+/// This runs before the environment is
+/// created so the second instance never
+/// touches the console or any patches.
+macro_rules! instance_lock_acquire {
+   ($mod_name:ident) => {
+      match crate::sys::instance::InstanceLock::acquire($mod_name) {
+         Ok(Some(lock)) => lock,
+         Ok(None)       => {
+            report_error(&format!(
+               "\"{}\" is already running in this process, aborting duplicate initialization",
+               $mod_name,
+            ));
+            return crate::sys::environment::OSReturn::FAILURE;
+         },
+         Err(e)         => {
+            report_error(&format!("Failed to acquire instance lock for \"{}\": {e}", $mod_name));
+            return crate::sys::environment::OSReturn::FAILURE;
+         },
+      }
+   };
+}
+
+/// Tells a launcher waiting on the mod
+/// that it's ready, unless main does it
+/// itself.  This runs once the environment
+/// is created so a failure is reported
+/// like any other, and before main so the
+/// game isn't left suspended while main
+/// runs its loop.
+macro_rules! ready_signal {
+   ($options:ident) => {
+      if $options.manual_ready == false {
+         if let Err(e) = crate::inject::signal_ready() {
+            report_error(&format!("Failed to signal the launcher: {e}"));
+         }
+      }
+   };
+}
+
 /// Frees the global environment context
 /// and drops it, returning from the caller
 /// with OSReturn::FAILURE upon failure.
-/// In debug mode, it will sleep for a
-/// brief period of time before exiting.
+/// A launcher waiting on the mod is told
+/// it's ready first, since main won't do
+/// it anymore.  In debug mode, it will
+/// sleep for a brief period of time
+/// before exiting.
 macro_rules! environment_free {
    () => {
+      if let Err(e) = crate::inject::signal_ready() {
+         report_error(&format!("Failed to signal the launcher: {e}"));
+      }
       std::mem::drop(match Environment::global_state_free() {
          Ok(_)    => (),
          Err(e)   => {
@@ -570,9 +1181,9 @@ macro_rules! environment_free {
 }
 
 /// Checks the given process whitelist
-/// and makes sure the process name is
-/// contained within the whitelist assuming
-/// a non-empty whitelist.
+/// and makes sure the process name
+/// matches a pattern in the whitelist
+/// assuming a non-empty whitelist.
 macro_rules! check_whitelist {
    ($whitelist:ident) => {
       // Make sure there's items
@@ -588,11 +1199,11 @@ macro_rules! check_whitelist {
          };
          let proc = &proc.executable_file_name();
 
-         // Find the process name in the list,
-         // erroring if not found
-         if $whitelist.iter().find(|cur| {
-            cur.eq(&proc)
-         }).is_none() == true {
+         // Find a matching pattern in the
+         // list, erroring if not found
+         if $whitelist.iter().any(|pattern| {
+            crate::process::executable_file_name_matches(proc, pattern)
+         }) == false {
             report_error(&format!("Entrypoint does not allow binding to \"{proc}\""));
             environment_free!();
             return crate::sys::environment::OSReturn::FAILURE;
@@ -602,17 +1213,25 @@ macro_rules! check_whitelist {
 }
 
 /// Executes a main-like function
-/// which has no return type.
+/// which has no return type.  If it
+/// panics, the global environment
+/// context will be freed, reverting
+/// every patch, and the caller will
+/// return OSReturn::FAILURE.
 macro_rules! execute_main_void {
    ($identifier:ident) => {
-      $identifier();
+      if std::panic::catch_unwind(std::panic::AssertUnwindSafe($identifier)).is_err() == true {
+         report_error("Main panicked");
+         environment_free!();
+         return crate::sys::environment::OSReturn::FAILURE;
+      }
    };
 }
 
 /// Executes a main-like function
 /// which returns a Result value.
-/// If an Err is returned, the
-/// global environment context will
+/// If an Err is returned or it panics,
+/// the global environment context will
 /// be freed andthe caller will return
 /// OSReturn::FAILURE to the system.
 /// In debug mode, it will sleep
@@ -620,10 +1239,18 @@ macro_rules! execute_main_void {
 /// exiting.
 macro_rules! execute_main_result {
    ($identifier:ident) => {
-      if let Err(err) = $identifier() {
-         report_error(&format!("Main returned an error: {err}"));
-         environment_free!();
-         return crate::sys::environment::OSReturn::FAILURE;
+      match std::panic::catch_unwind(std::panic::AssertUnwindSafe($identifier)) {
+         Ok(Ok(()))     => (),
+         Ok(Err(err))   => {
+            report_error(&format!("Main returned an error: {err}"));
+            environment_free!();
+            return crate::sys::environment::OSReturn::FAILURE;
+         },
+         Err(_)         => {
+            report_error("Main panicked");
+            environment_free!();
+            return crate::sys::environment::OSReturn::FAILURE;
+         },
       }
    };
 }
@@ -638,12 +1265,18 @@ pub mod __start_main {
 
    pub fn void<F>(
       entrypoint        : F,
+      mod_name          : &str,
       process_whitelist : &[&str],
+      options           : &EnvironmentOptions,
    ) -> crate::sys::environment::OSReturn
    where F: FnOnce(),
    {
-      environment_init! ();
+      let _instance_lock = instance_lock_acquire!(mod_name);
+      let _main_thread   = crate::runtime::register_current_thread();
+
+      environment_init! (options);
       check_whitelist!  (process_whitelist);
+      ready_signal!     (options);
       execute_main_void!(entrypoint);
       environment_free! ();
 
@@ -652,13 +1285,19 @@ pub mod __start_main {
 
    pub fn result_static<F, E>(
       entrypoint        : F,
+      mod_name          : &str,
       process_whitelist : &[&str],
+      options           : &EnvironmentOptions,
    ) -> crate::sys::environment::OSReturn
    where F: FnOnce() -> std::result::Result<(), E>,
          E: std::error::Error,
    {
-      environment_init!    ();
+      let _instance_lock = instance_lock_acquire!(mod_name);
+      let _main_thread   = crate::runtime::register_current_thread();
+
+      environment_init!    (options);
       check_whitelist!     (process_whitelist);
+      ready_signal!        (options);
       execute_main_result! (entrypoint);
       environment_free!    ();
 
@@ -667,12 +1306,18 @@ pub mod __start_main {
 
    pub fn result_dynamic<F>(
       entrypoint        : F,
+      mod_name          : &str,
       process_whitelist : &[&str],
+      options           : &EnvironmentOptions,
    ) -> crate::sys::environment::OSReturn
    where F: FnOnce() -> std::result::Result<(), Box<dyn std::error::Error>>,
    {
-      environment_init!    ();
+      let _instance_lock = instance_lock_acquire!(mod_name);
+      let _main_thread   = crate::runtime::register_current_thread();
+
+      environment_init!    (options);
       check_whitelist!     (process_whitelist);
+      ready_signal!        (options);
       execute_main_result! (entrypoint);
       environment_free!    ();
 
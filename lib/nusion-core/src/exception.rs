@@ -0,0 +1,42 @@
+//! Recovery from memory access faults
+//! which would otherwise crash the game.
+//! Reads and writes made through the
+//! <code>Patch</code> trait are already
+//! guarded, returning
+//! <code>PatchError::MemoryFault</code>
+//! instead of crashing.  Other memory can
+//! be copied with <code>guarded_copy</code>,
+//! or watched by registering a region.
+//! Region callbacks run inside the exception
+//! handler, so they must not allocate, lock
+//! or log.
+//!
+//! ```
+//! static ENTITY_LIST_FAULTS : std::sync::atomic::AtomicUsize
+//!    = std::sync::atomic::AtomicUsize::new(0);
+//!
+//! fn on_fault(_fault : & nusion_core::exception::FaultInfo) -> nusion_core::exception::FaultAction {
+//!    ENTITY_LIST_FAULTS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+//!    return nusion_core::exception::FaultAction::Continue;
+//! }
+//!
+//! let region = nusion_core::exception::add_guarded_region(
+//!    entity_list..entity_list + 0x1000, on_fault,
+//! )?;
+//! ```
+
+// Everything is provided by the
+// system abstraction layer as-is
+pub use crate::sys::exception::{
+   ExceptionError,
+   Result,
+   FaultAccess,
+   FaultInfo,
+   FaultAction,
+   FaultCallback,
+   MAX_GUARDED_REGIONS,
+   add_guarded_region,
+   remove_guarded_region,
+   guarded_copy,
+   guarded_merge,
+};
@@ -0,0 +1,129 @@
+//! Hold a value in the game's memory
+//! constant by rewriting it on an interval
+//! from a background thread, such as for
+//! infinite health or ammo.
+//!
+//! ```
+//! let health = unsafe{nusion_core::environment::Environment::get().freeze_chain::<f32>(
+//!    "game.exe",
+//!    0x02A4_B1C0,
+//!    &[0x18, 0x250, 0x44],
+//!    100.0,
+//! )}?;
+//!
+//! // The value is held until the handle is dropped
+//! drop(health);
+//! ```
+
+/// Time between each write of a
+/// frozen value.
+const FREEZE_INTERVAL : std::time::Duration
+   = std::time::Duration::from_millis(10);
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// A handle to a value being held at
+/// the end of a pointer chain.  The chain
+/// is followed again before every write,
+/// so the value follows the object if the
+/// game moves it.  If the chain is broken,
+/// such as while the game is loading, that
+/// write is skipped.  Dropping the handle
+/// stops the freeze and waits for its
+/// task to return.
+pub struct FrozenValue {
+   task : crate::task::TaskHandle,
+}
+
+///////////////////////////
+// METHODS - FrozenValue //
+///////////////////////////
+
+impl FrozenValue {
+   /// Stops holding the value.  This
+   /// is equivalent to dropping the
+   /// handle.
+   pub fn unfreeze(
+      self,
+   ) {
+      drop(self);
+      return;
+   }
+
+   /// Checks whether the value is still
+   /// being held, which stops early if the
+   /// task runner was shut down.
+   pub fn is_frozen(
+      & self,
+   ) -> bool {
+      return self.task.is_finished() == false;
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - FrozenValue //
+/////////////////////////////////////////
+
+impl std::ops::Drop for FrozenValue {
+   fn drop(
+      & mut self,
+   ) {
+      self.task.cancel();
+      self.task.wait();
+
+      return;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Holds a value at the end of a pointer
+/// chain within a module until the returned
+/// handle is dropped, from a task spawned
+/// on a runner.  The base offset is the
+/// offset of the base pointer within the
+/// module, and the offsets are followed the
+/// same as <code>patch::reader::PointerChain</code>.
+///
+/// <h2 id=  freeze_chain_safety>
+/// <a href=#freeze_chain_safety>
+/// Safety
+/// </a></h2>
+///
+/// Every pointer in the chain is checked
+/// before it is followed, but the game may
+/// free or reuse the memory between the
+/// check and the write.  The value is also
+/// written without any synchronization with
+/// the game's own threads.
+pub unsafe fn freeze_chain<T>(
+   tasks          : & crate::task::TaskRunner,
+   module         : & crate::process::ModuleSnapshot,
+   base_offset    : usize,
+   offsets        : & [usize],
+   value          : T,
+) -> crate::patch::Result<FrozenValue>
+where T: Copy + Send + 'static,
+{
+   let base_address = module.offset_range_to_address_range(
+      &(base_offset..(crate::patch::Offset::new(base_offset) + std::mem::size_of::<usize>())?.get()),
+   )?.start;
+
+   let offsets = offsets.to_vec();
+   let task    = tasks.spawn("nusion-freeze", move |context| {
+      loop {
+         let _ = crate::patch::write_pointer_chain(base_address, &offsets, value);
+         if context.sleep(FREEZE_INTERVAL) == false {
+            break;
+         }
+      }
+   })?;
+
+   return Ok(FrozenValue{
+      task : task,
+   });
+}
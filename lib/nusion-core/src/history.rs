@@ -0,0 +1,236 @@
+//! Undo and redo history for patches
+//! applied during an interactive session.
+
+use std::ops::RangeBounds;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An ordered history of applied and
+/// reverted patches.  Undoing a patch
+/// restores its overwritten bytes, and
+/// redoing it applies the same writer
+/// again.  Applying a new patch clears
+/// everything which could be redone.
+pub struct PatchHistory {
+   applied  : Vec<PatchHistoryEntry>,
+   reverted : Vec<PatchHistoryEntry>,
+}
+
+/// Closure which applies a stored writer
+/// to a module.
+type PatchHistoryApply = Box<
+   dyn Fn(
+      & mut crate::process::ModuleSnapshot,
+   ) -> crate::patch::Result<crate::process::ModuleSnapshotPatchContainer>
+   + Send + Sync
+>;
+
+struct PatchHistoryEntry {
+   name        : String,
+   module_name : String,
+   apply       : PatchHistoryApply,
+   container   : Option<crate::process::ModuleSnapshotPatchContainer>,
+}
+
+////////////////////////////
+// METHODS - PatchHistory //
+////////////////////////////
+
+impl PatchHistory {
+   /// Creates an empty patch history.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         applied  : Vec::new(),
+         reverted : Vec::new(),
+      };
+   }
+
+   /// Applies a writer to a module and
+   /// records it in the history under
+   /// the given name.
+   ///
+   /// <h2 id=  patch_history_apply_safety>
+   /// <a href=#patch_history_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_create</code> apply,
+   /// including every time the patch is
+   /// redone.
+   pub unsafe fn apply<W, R>(
+      & mut self,
+      modules     : & mut crate::process::ModuleSnapshotList,
+      module_name : & str,
+      name        : & str,
+      writer      : W,
+   ) -> crate::patch::Result<& mut Self>
+   where W: crate::patch::Writer<R> + Send + Sync + 'static,
+         R: RangeBounds<usize>,
+   {
+      let mut entry = PatchHistoryEntry{
+         name        : String::from(name),
+         module_name : String::from(module_name),
+         apply       : Box::new(move |module| {
+            use crate::patch::Patch;
+            return module.patch_create(&writer);
+         }),
+         container   : None,
+      };
+
+      entry.container = Some(entry.execute(modules)?);
+
+      self.applied.push(entry);
+      self.reverted.clear();
+      return Ok(self);
+   }
+
+   /// Reverts the most recently applied
+   /// patch.  Returns the name of the
+   /// patch, or <code>None</code> if there
+   /// is nothing to undo.
+   pub fn undo<'l>(
+      &'l mut self,
+   ) -> Option<&'l str> {
+      let mut entry = self.applied.pop()?;
+
+      // Dropping the container restores the bytes
+      entry.container = None;
+
+      self.reverted.push(entry);
+      return self.reverted.last().map(|entry| entry.name.as_str());
+   }
+
+   /// Re-applies the most recently reverted
+   /// patch.  Returns the name of the patch,
+   /// or <code>None</code> if there is nothing
+   /// to redo.
+   ///
+   /// <h2 id=  patch_history_redo_safety>
+   /// <a href=#patch_history_redo_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_create</code> apply.
+   pub unsafe fn redo<'l>(
+      &'l mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> crate::patch::Result<Option<&'l str>> {
+      let mut entry = match self.reverted.pop() {
+         Some(entry) => entry,
+         None        => return Ok(None),
+      };
+
+      entry.container = match entry.execute(modules) {
+         Ok(container)  => Some(container),
+         Err(err)       => {
+            self.reverted.push(entry);
+            return Err(err);
+         },
+      };
+
+      self.applied.push(entry);
+      return Ok(self.applied.last().map(|entry| entry.name.as_str()));
+   }
+
+   /// Returns the names of the applied
+   /// patches, oldest first.
+   pub fn applied<'l>(
+      &'l self,
+   ) -> Vec<&'l str> {
+      return self.applied.iter().map(|entry| entry.name.as_str()).collect();
+   }
+
+   /// Returns the names of the reverted
+   /// patches which can be redone, with
+   /// the next one to be redone last.
+   pub fn reverted<'l>(
+      &'l self,
+   ) -> Vec<&'l str> {
+      return self.reverted.iter().map(|entry| entry.name.as_str()).collect();
+   }
+
+   /// Runs a console command, printing
+   /// the result to standard output.
+   /// The supported commands are <code>undo</code>,
+   /// <code>redo</code>, and <code>history</code>.
+   /// Returns <code>false</code> if the
+   /// command isn't recognized.
+   ///
+   /// ```
+   /// let mut line = String::new();
+   /// std::io::stdin().read_line(& mut line)?;
+   ///
+   /// let mut env = nusion_core::env_mut!();
+   /// unsafe{env.patch_command(&line)}?;
+   /// ```
+   ///
+   /// <h2 id=  patch_history_run_command_safety>
+   /// <a href=#patch_history_run_command_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_history_redo_safety>redo</a></code>
+   /// apply.
+   pub unsafe fn run_command(
+      & mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+      command  : & str,
+   ) -> crate::patch::Result<bool> {
+      match command.trim() {
+         "undo"      => match self.undo() {
+            Some(name)  => println!("Undid patch \"{name}\""),
+            None        => println!("Nothing to undo"),
+         },
+         "redo"      => match self.redo(modules)? {
+            Some(name)  => println!("Redid patch \"{name}\""),
+            None        => println!("Nothing to redo"),
+         },
+         "history"   => {
+            for name in self.applied() {
+               println!("   applied  : {name}");
+            }
+            for name in self.reverted().into_iter().rev() {
+               println!("   reverted : {name}");
+            }
+         },
+         _           => return Ok(false),
+      }
+
+      return Ok(true);
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatchHistory //
+//////////////////////////////////////////
+
+impl Default for PatchHistory {
+   fn default() -> Self {
+      return Self::new();
+   }
+}
+
+//////////////////////////////////////////
+// INTERNAL METHODS - PatchHistoryEntry //
+//////////////////////////////////////////
+
+impl PatchHistoryEntry {
+   unsafe fn execute(
+      & self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> crate::patch::Result<crate::process::ModuleSnapshotPatchContainer> {
+      let module = modules.get_mut(
+         &self.module_name,
+      ).ok_or_else(|| crate::patch::PatchError::ModuleNotFound{
+         module_name : self.module_name.clone(),
+      })?;
+
+      return (self.apply)(module);
+   }
+}
@@ -0,0 +1,790 @@
+//! Share a single hook site between
+//! several independent closures.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// What a closure in a hook chain
+/// wants to happen after it returns.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HookAction {
+   PassThrough,
+   Consume,
+}
+
+/// An ordered list of closures which
+/// all run from the same physical hook.
+/// The hook is installed when the first
+/// closure is registered and removed when
+/// the last one is unregistered, so two
+/// features can hook the same address
+/// without their checksums failing or
+/// corrupting each other.  To share a
+/// hook site with other mods, which have
+/// their own copy of this library, use
+/// <code>patch::writer::ChainHook</code>.
+///
+/// The chain is meant to be stored in a
+/// <code>static</code> and dispatched from
+/// the closure of the <code>hook!</code>
+/// macro used by the chain's writer.
+///
+/// ```
+/// static DAMAGE_CHAIN : nusion_core::hook::HookChain<i32>
+///    = nusion_core::hook::HookChain::new();
+///
+/// const HOOK_DAMAGE : nusion_core::patch::writer::Hook<std::ops::Range<usize>>
+///    = nusion_core::patch::writer::Hook{
+///       memory_offset_range  : 0x9FCD4000..0x9FCD4010,
+///       checksum             : nusion_core::patch::Checksum::from(0xFC204AFD),
+///       hook                 : nusion_core::hook!("
+///          push  rcx
+///          lea   rcx,[rcx+0x40]
+///          call  {target}
+///          pop   rcx
+///          ret
+///       ", |health_value : & mut i32| {
+///          DAMAGE_CHAIN.dispatch(health_value);
+///       }),
+///    };
+/// ```
+pub struct HookChain<T: 'static> {
+   state : std::sync::Mutex<HookChainState<T>>,
+}
+
+/// A closure registered in a hook
+/// chain.  The closure is unregistered
+/// when the handle is dropped.
+pub struct HookChainHandle<T: 'static> {
+   chain : &'static HookChain<T>,
+   id    : usize,
+}
+
+/// A flag which turns a hook's closure
+/// on and off without touching the
+/// patched code.  Name the switch with
+/// the <code>enabled</code> argument of
+/// the <code>hook!</code> macro, and the
+/// closure is skipped while the switch
+/// is disabled.
+///
+/// ```
+/// static FLIGHT : nusion_core::hook::HookSwitch
+///    = nusion_core::hook::HookSwitch::new(false);
+///
+/// // Inside the hook! macro
+/// ", |gravity : & mut f32| {
+///    *gravity = 0.0;
+/// }, enabled = FLIGHT),
+///
+/// // From the main loop
+/// FLIGHT.set(bindings.flight.is_active());
+/// ```
+pub struct HookSwitch {
+   enabled : std::sync::atomic::AtomicBool,
+}
+
+/// A function pointer slot which a hook's
+/// closure forwards to while it is bound.
+/// Name the binding with the <code>bind</code>
+/// argument of the <code>hook!</code> macro,
+/// and the hook's behavior can be swapped at
+/// any time, such as after reloading a config,
+/// without touching the patched code.  The
+/// closure itself runs while nothing is bound.
+///
+/// The type parameter must be a function
+/// pointer taking the closure's arguments
+/// and returning its return type.
+///
+/// ```
+/// static DAMAGE : nusion_core::hook::HookBinding<fn(& mut i32)>
+///    = nusion_core::hook::HookBinding::new();
+///
+/// // Inside the hook! macro
+/// ", |health : & mut i32| {
+///    *health -= 1;
+/// }, bind = DAMAGE),
+///
+/// // After reloading the config
+/// fn no_damage(_health : & mut i32) {
+/// }
+/// DAMAGE.bind(no_damage);
+/// ```
+pub struct HookBinding<F: Copy + 'static> {
+   function : std::sync::atomic::AtomicUsize,
+   marker   : std::marker::PhantomData<F>,
+}
+
+/// Limits a hook's closure to certain
+/// threads, for functions which are called
+/// from several threads when the mod's logic
+/// is only valid on one of them.  Name the
+/// filter with the <code>threads</code>
+/// argument of the <code>hook!</code> macro,
+/// and the closure is skipped on any thread
+/// the filter doesn't allow.  Every thread
+/// is allowed until the filter is changed.
+///
+/// ```
+/// static RENDER_THREAD : nusion_core::hook::HookThreadFilter
+///    = nusion_core::hook::HookThreadFilter::new();
+///
+/// // Inside the hook! macro for a function
+/// // only ever called by the render thread
+/// ", || {
+///    RENDER_THREAD.only_current();
+/// }),
+///
+/// // Inside the hook! macro for a function
+/// // called from every thread
+/// ", |position : & mut [f32; 3]| {
+///    position[2] += 1.0;
+/// }, threads = RENDER_THREAD),
+/// ```
+pub struct HookThreadFilter {
+   mode : std::sync::RwLock<HookThreadFilterMode>,
+}
+
+/// Every register at the hook site, saved
+/// by the <code>{registers}</code> template
+/// argument of <code>hook!</code> and passed
+/// to the closure.  Changes made by the
+/// closure are written back to the registers
+/// when it returns, except the stack pointer,
+/// which isn't included.
+///
+/// ```
+/// nusion_core::hook!("
+///    {registers}
+///    ret
+/// ", |context : & mut nusion_core::hook::CpuContext| {
+///    // The game keeps the player's ammo in rbx here
+///    context.rbx = 999;
+/// })
+/// ```
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct CpuContext {
+   /// Each XMM register as two 64-bit halves.
+   pub xmm     : [[u64; 2]; 16],
+   pub r15     : u64,
+   pub r14     : u64,
+   pub r13     : u64,
+   pub r12     : u64,
+   pub r11     : u64,
+   pub r10     : u64,
+   pub r9      : u64,
+   pub r8      : u64,
+   pub rdi     : u64,
+   pub rsi     : u64,
+   pub rbp     : u64,
+   pub rbx     : u64,
+   pub rdx     : u64,
+   pub rcx     : u64,
+   pub rax     : u64,
+   pub rflags  : u64,
+}
+
+/// A hook created by <code>hook_with_context!</code>,
+/// which owns the state captured by its
+/// closure.  Each context runs its own copy
+/// of the hook, so any number of them can
+/// be created from the same expansion of
+/// the macro.  The closure runs whenever
+/// the hook is executed while this is alive,
+/// and is freed once this is dropped and
+/// every call already running has returned.
+/// Drop the patch using the hook first, since
+/// the hook no longer calls the closure
+/// afterwards and whatever it returns is
+/// left unspecified.  The copy of the hook
+/// is never freed.
+///
+/// ```
+/// let kills = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+///
+/// let context = nusion_core::hook_with_context!("
+///    {save_volatile}
+///    {arg0 rbx}
+///    call  {target}
+///    {restore_volatile}
+///    ret
+/// ", move |enemy : * mut Enemy| {
+///    kills.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+/// })?;
+///
+/// let _patch = unsafe{game.patch_create(&nusion_core::patch::writer::Hook{
+///    memory_offset_range  : 0x4A1C20..0x4A1C2E,
+///    checksum             : nusion_core::patch::Checksum::from(0x1B2C3D4E),
+///    hook                 : context.hook(),
+/// })}?;
+/// ```
+pub struct HookContext<F: ?Sized + 'static> {
+   _closure : Box<Box<F>>,
+   hook     : unsafe extern "C" fn(),
+   end      : usize,
+}
+
+/// The slots at the end of a copy of a
+/// context hook, in the order laid out
+/// by <code>hook_with_context!</code>.
+struct HookContextSlots {
+   function : &'static std::sync::atomic::AtomicUsize,
+   context  : &'static std::sync::atomic::AtomicUsize,
+   running  : &'static std::sync::atomic::AtomicUsize,
+}
+
+/// Which threads a thread filter
+/// allows.
+enum HookThreadFilterMode {
+   All,
+   Only(Vec<u32>),
+   Skip(Vec<u32>),
+}
+
+/// Boxed closure stored in a hook chain.
+type HookChainCallback<T> = Box<dyn FnMut(& mut T) -> HookAction + Send>;
+
+struct HookChainEntry<T> {
+   id       : usize,
+   priority : i32,
+   callback : HookChainCallback<T>,
+}
+
+struct HookChainState<T> {
+   entries     : Vec<HookChainEntry<T>>,
+   next_id     : usize,
+   container   : Option<crate::process::ModuleSnapshotPatchContainer>,
+}
+
+/////////////////////////
+// METHODS - HookChain //
+/////////////////////////
+
+impl<T: 'static> HookChain<T> {
+   /// Creates a new hook chain with
+   /// no registered closures.
+   pub const fn new() -> Self {
+      return Self{
+         state : std::sync::Mutex::new(HookChainState{
+            entries     : Vec::new(),
+            next_id     : 0,
+            container   : None,
+         }),
+      };
+   }
+
+   /// Registers a closure to run when
+   /// the hook is executed.  Closures run
+   /// in ascending order of priority, and
+   /// closures with equal priority run in
+   /// the order they were registered.  If
+   /// this is the first closure, the writer
+   /// is applied to the module, otherwise
+   /// the already installed hook is reused
+   /// and the writer is ignored.
+   ///
+   /// <h2 id=  hook_chain_register_safety>
+   /// <a href=#hook_chain_register_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_create</code> apply.
+   pub unsafe fn register<R, F>(
+      &'static self,
+      module   : & mut crate::process::ModuleSnapshot,
+      writer   : & crate::patch::writer::Hook<R>,
+      priority : i32,
+      callback : F,
+   ) -> crate::patch::Result<HookChainHandle<T>>
+   where R: std::ops::RangeBounds<usize>,
+         F: FnMut(& mut T) -> HookAction + Send + 'static,
+   {
+      let mut state = self.lock();
+
+      if state.container.is_none() == true {
+         use crate::patch::Patch;
+         state.container = Some(module.patch_create(writer)?);
+      }
+
+      let id = state.next_id;
+      state.next_id += 1;
+
+      // Insert after every entry of equal or
+      // lower priority to keep the order stable
+      let index = state.entries.partition_point(|entry| {
+         entry.priority <= priority
+      });
+      state.entries.insert(index, HookChainEntry{
+         id       : id,
+         priority : priority,
+         callback : Box::new(callback),
+      });
+
+      return Ok(HookChainHandle{
+         chain : self,
+         id    : id,
+      });
+   }
+
+   /// Runs every registered closure in
+   /// order until one consumes the call.
+   /// Returns <code>HookAction::Consume</code>
+   /// if a closure consumed the call, which
+   /// the hook may use to skip the original
+   /// code.  Closures must not register or
+   /// unregister closures on the same chain,
+   /// otherwise a deadlock will occur.
+   pub fn dispatch(
+      & self,
+      argument : & mut T,
+   ) -> HookAction {
+      let mut state = self.lock();
+
+      for entry in state.entries.iter_mut() {
+         if (entry.callback)(argument) == HookAction::Consume {
+            return HookAction::Consume;
+         }
+      }
+
+      return HookAction::PassThrough;
+   }
+
+   /// Returns the number of registered
+   /// closures.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.lock().entries.len();
+   }
+
+   /// Returns true if no closures are
+   /// registered, meaning the hook is
+   /// not installed.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.lock().entries.is_empty();
+   }
+}
+
+//////////////////////////
+// METHODS - HookSwitch //
+//////////////////////////
+
+impl HookSwitch {
+   /// Creates a new switch which is
+   /// initially enabled or disabled.
+   pub const fn new(
+      enabled : bool,
+   ) -> Self {
+      return Self{
+         enabled : std::sync::atomic::AtomicBool::new(enabled),
+      };
+   }
+
+   /// Checks whether the hook's
+   /// closure should run.
+   pub fn is_enabled(
+      & self,
+   ) -> bool {
+      return self.enabled.load(std::sync::atomic::Ordering::Relaxed);
+   }
+
+   /// Enables or disables the
+   /// hook's closure.
+   pub fn set(
+      & self,
+      enabled : bool,
+   ) -> & Self {
+      self.enabled.store(enabled, std::sync::atomic::Ordering::Relaxed);
+      return self;
+   }
+
+   /// Enables the hook's closure.
+   pub fn enable(
+      & self,
+   ) -> & Self {
+      return self.set(true);
+   }
+
+   /// Disables the hook's closure.
+   pub fn disable(
+      & self,
+   ) -> & Self {
+      return self.set(false);
+   }
+
+   /// Flips the switch, returning
+   /// the new state.
+   pub fn toggle(
+      & self,
+   ) -> bool {
+      return self.enabled.fetch_xor(true, std::sync::atomic::Ordering::Relaxed) == false;
+   }
+}
+
+///////////////////////////
+// METHODS - HookBinding //
+///////////////////////////
+
+impl<F: Copy + 'static> HookBinding<F> {
+   /// Creates a new binding with
+   /// nothing bound.  This fails to
+   /// compile in a <code>static</code>
+   /// if <code>F</code> isn't the size
+   /// of a function pointer.
+   pub const fn new(
+   ) -> Self {
+      assert!(
+         std::mem::size_of::<F>() == std::mem::size_of::<usize>(),
+         "Hook binding type must be a function pointer",
+      );
+
+      return Self{
+         function : std::sync::atomic::AtomicUsize::new(0),
+         marker   : std::marker::PhantomData,
+      };
+   }
+
+   /// Atomically binds a new function,
+   /// returning the previously bound one.
+   pub fn bind(
+      & self,
+      function : F,
+   ) -> Option<F> {
+      let raw = unsafe{std::mem::transmute_copy::<F, usize>(&function)};
+      return Self::from_raw(self.function.swap(raw, std::sync::atomic::Ordering::AcqRel));
+   }
+
+   /// Atomically unbinds the function
+   /// so the closure runs again,
+   /// returning the previously bound one.
+   pub fn unbind(
+      & self,
+   ) -> Option<F> {
+      return Self::from_raw(self.function.swap(0, std::sync::atomic::Ordering::AcqRel));
+   }
+
+   /// Gets the bound function.
+   pub fn get(
+      & self,
+   ) -> Option<F> {
+      return Self::from_raw(self.function.load(std::sync::atomic::Ordering::Acquire));
+   }
+
+   /// Checks whether a function
+   /// is bound.
+   pub fn is_bound(
+      & self,
+   ) -> bool {
+      return self.function.load(std::sync::atomic::Ordering::Acquire) != 0;
+   }
+}
+
+////////////////////////////////
+// METHODS - HookThreadFilter //
+////////////////////////////////
+
+impl HookThreadFilter {
+   /// Creates a new filter which
+   /// allows every thread.
+   pub const fn new(
+   ) -> Self {
+      return Self{
+         mode : std::sync::RwLock::new(HookThreadFilterMode::All),
+      };
+   }
+
+   /// Checks whether the closure should
+   /// run on a thread.
+   pub fn allows(
+      & self,
+      thread_id : u32,
+   ) -> bool {
+      return match &*self.read() {
+         HookThreadFilterMode::All
+            => true,
+         HookThreadFilterMode::Only(threads)
+            => threads.contains(&thread_id) == true,
+         HookThreadFilterMode::Skip(threads)
+            => threads.contains(&thread_id) == false,
+      };
+   }
+
+   /// Checks whether the closure should
+   /// run on the calling thread.
+   pub fn allows_current(
+      & self,
+   ) -> bool {
+      return self.allows(current_thread_id());
+   }
+
+   /// Allows every thread again.
+   pub fn allow_all(
+      & self,
+   ) -> & Self {
+      *self.write() = HookThreadFilterMode::All;
+      return self;
+   }
+
+   /// Allows only the given threads.
+   pub fn only<I>(
+      & self,
+      thread_ids : I,
+   ) -> & Self
+   where I: IntoIterator<Item = u32>,
+   {
+      *self.write() = HookThreadFilterMode::Only(thread_ids.into_iter().collect());
+      return self;
+   }
+
+   /// Allows every thread other than
+   /// the given threads.
+   pub fn skip<I>(
+      & self,
+      thread_ids : I,
+   ) -> & Self
+   where I: IntoIterator<Item = u32>,
+   {
+      *self.write() = HookThreadFilterMode::Skip(thread_ids.into_iter().collect());
+      return self;
+   }
+
+   /// Allows only the calling thread,
+   /// such as from a hook which is only
+   /// ever called by the render thread.
+   pub fn only_current(
+      & self,
+   ) -> & Self {
+      return self.only([current_thread_id()]);
+   }
+
+   /// Allows every thread other than
+   /// the calling thread.
+   pub fn skip_current(
+      & self,
+   ) -> & Self {
+      return self.skip([current_thread_id()]);
+   }
+}
+
+///////////////////////////
+// METHODS - HookContext //
+///////////////////////////
+
+impl<F: ?Sized + 'static> HookContext<F> {
+   /// Copies the hook generated by one
+   /// <code>hook_with_context!</code> and
+   /// fills in the closure it passes to
+   /// the function.  This is called by the
+   /// macro and never needs to be used
+   /// directly.
+   ///
+   /// <h2 id=  hook_context_install_safety>
+   /// <a href=#hook_context_install_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The template must be followed by the
+   /// slots laid out by the macro up to the
+   /// end, and the function must take the
+   /// closure's arguments followed by a
+   /// pointer to the boxed closure.
+   #[doc(hidden)]
+   pub unsafe fn install(
+      closure  : Box<F>,
+      template : unsafe extern "C" fn(),
+      end      : unsafe extern "C" fn(),
+      function : usize,
+   ) -> crate::patch::Result<Self> {
+      let template   = template as usize;
+      let length     = (end as usize).saturating_sub(template);
+
+      let copy = crate::sys::memory::allocate_executable_near(template, length)?;
+      std::ptr::copy_nonoverlapping(template as * const u8, copy.start as * mut u8, length);
+
+      let closure = Box::new(closure);
+      let slots   = context_slots(copy.end);
+      slots.function.store(function, std::sync::atomic::Ordering::Release);
+      slots.context.store(
+         &*closure as * const Box<F> as usize,
+         std::sync::atomic::Ordering::Release,
+      );
+      crate::sys::thread::flush_instruction_cache(copy.clone())?;
+
+      return Ok(Self{
+         _closure : closure,
+         hook     : std::mem::transmute::<usize, unsafe extern "C" fn()>(copy.start),
+         end      : copy.end,
+      });
+   }
+
+   /// Gets the hook to use in a writer,
+   /// such as <code>patch::writer::Hook</code>.
+   pub fn hook(
+      & self,
+   ) -> unsafe extern "C" fn() {
+      return self.hook;
+   }
+}
+
+/////////////////////////////////////////
+// INTERNAL METHODS - HookThreadFilter //
+/////////////////////////////////////////
+
+impl HookThreadFilter {
+   // Poisoning is ignored for the same
+   // reason as with hook chains
+   fn read<'l>(
+      &'l self,
+   ) -> std::sync::RwLockReadGuard<'l, HookThreadFilterMode> {
+      return self.mode.read().unwrap_or_else(|err| err.into_inner());
+   }
+
+   fn write<'l>(
+      &'l self,
+   ) -> std::sync::RwLockWriteGuard<'l, HookThreadFilterMode> {
+      return self.mode.write().unwrap_or_else(|err| err.into_inner());
+   }
+}
+
+////////////////////////////////////
+// INTERNAL METHODS - HookBinding //
+////////////////////////////////////
+
+impl<F: Copy + 'static> HookBinding<F> {
+   // Function pointers are never null,
+   // so zero means nothing is bound
+   fn from_raw(
+      raw : usize,
+   ) -> Option<F> {
+      if raw == 0 {
+         return None;
+      }
+
+      return Some(unsafe{std::mem::transmute_copy::<usize, F>(&raw)});
+   }
+}
+
+//////////////////////////////////
+// INTERNAL METHODS - HookChain //
+//////////////////////////////////
+
+impl<T: 'static> HookChain<T> {
+   // A panicking closure shouldn't take
+   // down every other hook on the site,
+   // and panicking inside a hook isn't
+   // an option, so ignore poisoning
+   fn lock<'l>(
+      &'l self,
+   ) -> std::sync::MutexGuard<'l, HookChainState<T>> {
+      return self.state.lock().unwrap_or_else(|err| err.into_inner());
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookBinding //
+/////////////////////////////////////////
+
+impl<F: Copy + 'static> Default for HookBinding<F> {
+   fn default() -> Self {
+      return Self::new();
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookThreadFilter //
+//////////////////////////////////////////////
+
+impl Default for HookThreadFilter {
+   fn default() -> Self {
+      return Self::new();
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookChainHandle //
+/////////////////////////////////////////////
+
+impl<T: 'static> std::ops::Drop for HookChainHandle<T> {
+   fn drop(
+      & mut self,
+   ) {
+      let mut state = self.chain.lock();
+
+      state.entries.retain(|entry| entry.id != self.id);
+
+      // Restore the hooked bytes once
+      // nothing is left in the chain
+      if state.entries.is_empty() == true {
+         state.container = None;
+      }
+
+      return;
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookContext //
+/////////////////////////////////////////
+
+impl<F: ?Sized + 'static> std::ops::Drop for HookContext<F> {
+   fn drop(
+      & mut self,
+   ) {
+      use std::sync::atomic::Ordering;
+
+      // Every call counts itself before loading
+      // the function, so once it's replaced, a
+      // count of zero means nothing can still
+      // reach the closure
+      let slots = context_slots(self.end);
+      slots.function.swap(context_retired as extern "C" fn() as usize, Ordering::SeqCst);
+      while slots.running.load(Ordering::SeqCst) != 0 {
+         std::thread::yield_now();
+      }
+
+      return;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn context_slots(
+   end : usize,
+) -> HookContextSlots {
+   let slot = |offset : usize| unsafe{
+      std::sync::atomic::AtomicUsize::from_ptr((end - offset) as * mut usize)
+   };
+
+   return HookContextSlots{
+      function : slot(24),
+      context  : slot(16),
+      running  : slot(8),
+   };
+}
+
+// Called by a context hook instead of the
+// closure's function once it's dropped
+extern "C" fn context_retired(
+) {
+   return;
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Gets the operating system's ID for
+/// the calling thread, as used by thread
+/// filters.
+pub fn current_thread_id(
+) -> u32 {
+   return crate::sys::thread::current_thread_id();
+}
@@ -0,0 +1,222 @@
+//! Access the host process's environment
+//! variables and working directory, which
+//! belong to the game and may be changed
+//! by it or its launcher at any time.
+//!
+//! ```
+//! let mut env = nusion_core::env_mut!();
+//! env.host_mut().watch_var("STEAM_COMPAT_DATA_PATH");
+//!
+//! // From the main loop
+//! for change in env.host_mut().poll() {
+//!    nusion_core::log::info!("{change}");
+//! }
+//! ```
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// The host process's environment
+/// variables and working directory, as
+/// last seen by <code>poll</code>.  Only
+/// the working directory and the watched
+/// variables are compared when polling.
+pub struct HostEnvironment {
+   working_directory : Option<std::path::PathBuf>,
+   watched           : Vec<(String, Option<String>)>,
+}
+
+/// A change found by
+/// <code>HostEnvironment::poll</code>.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HostChange {
+   WorkingDirectory{
+      old   : Option<std::path::PathBuf>,
+      new   : Option<std::path::PathBuf>,
+   },
+   Variable{
+      name  : String,
+      old   : Option<String>,
+      new   : Option<String>,
+   },
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+// The working directory when the mod
+// started, before a launcher had the
+// chance to change it
+static INITIAL_WORKING_DIRECTORY
+   : std::sync::OnceLock<Option<std::path::PathBuf>>
+   = std::sync::OnceLock::new();
+
+///////////////////////////////
+// METHODS - HostEnvironment //
+///////////////////////////////
+
+impl HostEnvironment {
+   /// Takes a snapshot of the working
+   /// directory with no watched variables.
+   /// The first snapshot also records the
+   /// initial working directory.
+   pub fn new(
+   ) -> Self {
+      let working_directory = std::env::current_dir().ok();
+      INITIAL_WORKING_DIRECTORY.get_or_init(|| working_directory.clone());
+
+      return Self{
+         working_directory : working_directory,
+         watched           : Vec::new(),
+      };
+   }
+
+   /// Reads an environment variable, which
+   /// is <code>None</code> if it isn't set
+   /// or isn't valid unicode.
+   pub fn var(
+      & self,
+      name : & str,
+   ) -> Option<String> {
+      return std::env::var(name).ok();
+   }
+
+   /// Reads every environment variable
+   /// which is valid unicode.
+   pub fn vars(
+      & self,
+   ) -> Vec<(String, String)> {
+      return std::env::vars_os()
+         .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+         .collect();
+   }
+
+   /// Gets the current working directory,
+   /// which is <code>None</code> if it was
+   /// deleted or can't be accessed.
+   pub fn current_dir(
+      & self,
+   ) -> Option<std::path::PathBuf> {
+      return std::env::current_dir().ok();
+   }
+
+   /// Gets the working directory from when
+   /// the mod started.  Error reports are
+   /// written here, so they end up in the
+   /// same place no matter what the game
+   /// or its launcher did since.
+   pub fn initial_dir(
+      & self,
+   ) -> Option<&'static std::path::Path> {
+      return initial_working_directory();
+   }
+
+   /// Adds an environment variable to the
+   /// ones compared by <code>poll</code>,
+   /// starting from its current value.
+   pub fn watch_var(
+      & mut self,
+      name : & str,
+   ) -> & mut Self {
+      if self.watched.iter().any(|(watched, _)| watched == name) == false {
+         self.watched.push((String::from(name), self.var(name)));
+      }
+      return self;
+   }
+
+   /// Stops comparing an environment
+   /// variable in <code>poll</code>.
+   pub fn unwatch_var(
+      & mut self,
+      name : & str,
+   ) -> & mut Self {
+      self.watched.retain(|(watched, _)| watched != name);
+      return self;
+   }
+
+   /// Compares the working directory and
+   /// the watched variables against the last
+   /// poll, returning everything which changed.
+   /// This is cheap enough to call from a
+   /// mod's main loop.
+   pub fn poll(
+      & mut self,
+   ) -> Vec<HostChange> {
+      let mut changes = Vec::new();
+
+      let working_directory = self.current_dir();
+      if working_directory != self.working_directory {
+         changes.push(HostChange::WorkingDirectory{
+            old   : std::mem::replace(& mut self.working_directory, working_directory.clone()),
+            new   : working_directory,
+         });
+      }
+
+      for (name, value) in self.watched.iter_mut() {
+         let new = std::env::var(name.as_str()).ok();
+         if new != *value {
+            changes.push(HostChange::Variable{
+               name  : name.clone(),
+               old   : std::mem::replace(value, new.clone()),
+               new   : new,
+            });
+         }
+      }
+
+      return changes;
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HostEnvironment //
+/////////////////////////////////////////////
+
+impl Default for HostEnvironment {
+   fn default(
+   ) -> Self {
+      return Self::new();
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HostChange //
+////////////////////////////////////////
+
+impl std::fmt::Display for HostChange {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      let path = |path : & Option<std::path::PathBuf>| match path {
+         Some(path)  => format!("\"{}\"", path.display()),
+         None        => String::from("(none)"),
+      };
+      let value = |value : & Option<String>| match value {
+         Some(value) => format!("\"{value}\""),
+         None        => String::from("(unset)"),
+      };
+
+      return match self {
+         Self::WorkingDirectory  {old, new}
+            => write!(stream, "Working directory changed from {} to {}", path(old), path(new)),
+         Self::Variable          {name, old, new}
+            => write!(stream, "Environment variable {name} changed from {} to {}", value(old), value(new)),
+      };
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Gets the working directory from when
+/// the environment was created, without
+/// needing to lock the environment, such
+/// as from a panic handler.
+pub fn initial_working_directory(
+) -> Option<&'static std::path::Path> {
+   return INITIAL_WORKING_DIRECTORY.get()?.as_deref();
+}
+
@@ -0,0 +1,425 @@
+//! Hook imported functions by replacing
+//! their entries in a module's import
+//! address table (IAT).
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to an import
+/// address table hook.
+#[derive(Debug)]
+pub enum IatError {
+   ProcessError{
+      sys_error   : crate::sys::process::ProcessError,
+   },
+   MemoryError{
+      sys_error   : crate::sys::memory::MemoryError,
+   },
+   ImportNotFound{
+      library     : String,
+      function    : String,
+   },
+   AlreadyHooked{
+      library     : String,
+      function    : String,
+   },
+   NotHooked{
+      library     : String,
+      function    : String,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>IatError</code>.
+pub type Result<T> = std::result::Result<T, IatError>;
+
+/// A set of hooked import address table
+/// entries which remembers every original
+/// function pointer.  Every entry can be
+/// restored in one pass, which also happens
+/// when the set is dropped.  Before an entry
+/// is restored, it is checked to still hold
+/// the replacement pointer, so an entry the
+/// game rewrote while it was hooked is left
+/// alone and reported instead of clobbered.
+///
+/// ```
+/// let mut iat = nusion_core::iat::IatHookSet::new();
+///
+/// let original = unsafe{iat.hook(
+///    crate::game!(),
+///    "kernel32.dll",
+///    "Sleep",
+///    sleep_hook as usize,
+/// )}?;
+///
+/// // Later, when unloading
+/// for conflict in unsafe{iat.restore_all()}? {
+///    println!("Not restored: {conflict}");
+/// }
+/// ```
+pub struct IatHookSet {
+   entries : Vec<IatEntry>,
+}
+
+/// An entry which no longer held the
+/// replacement pointer when it was
+/// verified or restored, meaning
+/// something else rewrote it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IatConflict {
+   pub library       : String,
+   pub function      : String,
+   pub slot_address  : usize,
+   pub expected      : usize,
+   pub found         : usize,
+}
+
+struct IatEntry {
+   library        : String,
+   function       : String,
+   slot_address   : usize,
+   original       : usize,
+   replacement    : usize,
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - IatError //
+//////////////////////////////////////
+
+impl std::fmt::Display for IatError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ProcessError{sys_error}
+            => write!(stream, "Process error: {sys_error}"),
+         Self::MemoryError{sys_error}
+            => write!(stream, "Memory error: {sys_error}"),
+         Self::ImportNotFound{library, function}
+            => write!(stream, "No import of {library}!{function}"),
+         Self::AlreadyHooked{library, function}
+            => write!(stream, "Import {library}!{function} is already hooked"),
+         Self::NotHooked{library, function}
+            => write!(stream, "Import {library}!{function} is not hooked"),
+      };
+   }
+}
+
+impl std::error::Error for IatError {
+}
+
+impl From<crate::sys::process::ProcessError> for IatError {
+   fn from(
+      value : crate::sys::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         sys_error : value,
+      };
+   }
+}
+
+impl From<crate::sys::memory::MemoryError> for IatError {
+   fn from(
+      value : crate::sys::memory::MemoryError,
+   ) -> Self {
+      return Self::MemoryError{
+         sys_error : value,
+      };
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - IatConflict //
+/////////////////////////////////////////
+
+impl std::fmt::Display for IatConflict {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream,
+         "{}!{} at {:#X} expected {:#X} but found {:#X}",
+         self.library,
+         self.function,
+         self.slot_address,
+         self.expected,
+         self.found,
+      );
+   }
+}
+
+//////////////////////////
+// METHODS - IatHookSet //
+//////////////////////////
+
+impl IatHookSet {
+   /// Creates an empty set.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         entries : Vec::new(),
+      };
+   }
+
+   /// Replaces the address of a function
+   /// a module imports by name, returning
+   /// the original address so the hook can
+   /// call through to it.
+   ///
+   /// <h2 id=  iat_hook_set_hook_safety>
+   /// <a href=#iat_hook_set_hook_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The replacement must be a function
+   /// with the exact signature and calling
+   /// convention of the imported function.
+   /// The module must stay loaded while the
+   /// entry is hooked.
+   pub unsafe fn hook(
+      & mut self,
+      module      : & crate::process::ModuleSnapshot,
+      library     : & str,
+      function    : & str,
+      replacement : usize,
+   ) -> Result<usize> {
+      let slot_address = import_slot_address(module, library, function)?;
+
+      if self.entries.iter().any(|entry| entry.slot_address == slot_address) == true {
+         return Err(IatError::AlreadyHooked{
+            library  : String::from(library),
+            function : String::from(function),
+         });
+      }
+
+      let original = swap_slot(slot_address, replacement)?;
+
+      self.entries.push(IatEntry{
+         library        : String::from(library),
+         function       : String::from(function),
+         slot_address   : slot_address,
+         original       : original,
+         replacement    : replacement,
+      });
+      return Ok(original);
+   }
+
+   /// Gets the original address of a
+   /// hooked import.
+   pub fn original(
+      & self,
+      library  : & str,
+      function : & str,
+   ) -> Option<usize> {
+      return self.find(library, function).map(|index| self.entries[index].original);
+   }
+
+   /// Returns the number of hooked
+   /// entries.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.entries.len();
+   }
+
+   /// Returns true if no entries
+   /// are hooked.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.entries.is_empty();
+   }
+
+   /// Checks every hooked entry still
+   /// holds its replacement pointer,
+   /// returning the entries which don't.
+   ///
+   /// <h2 id=  iat_hook_set_verify_safety>
+   /// <a href=#iat_hook_set_verify_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The modules containing the hooked
+   /// entries must still be loaded.
+   pub unsafe fn verify(
+      & self,
+   ) -> Vec<IatConflict> {
+      return self.entries.iter().filter_map(|entry| entry.conflict()).collect();
+   }
+
+   /// Restores a single hooked entry.
+   /// If the entry was rewritten by
+   /// something else, it is left alone
+   /// and the conflict is returned.
+   ///
+   /// <h2 id=  iat_hook_set_restore_safety>
+   /// <a href=#iat_hook_set_restore_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The modules containing the hooked
+   /// entries must still be loaded, and
+   /// nothing may still be relying on the
+   /// replacement being called.
+   pub unsafe fn restore(
+      & mut self,
+      library  : & str,
+      function : & str,
+   ) -> Result<Option<IatConflict>> {
+      let index = self.find(library, function).ok_or_else(|| IatError::NotHooked{
+         library  : String::from(library),
+         function : String::from(function),
+      })?;
+
+      let entry = self.entries.remove(index);
+      return entry.restore();
+   }
+
+   /// Restores every hooked entry in one
+   /// pass, returning the entries which
+   /// were left alone because something
+   /// else rewrote them.  Entries which
+   /// fail to restore stay in the set.
+   ///
+   /// <h2 id=  iat_hook_set_restore_all_safety>
+   /// <a href=#iat_hook_set_restore_all_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #iat_hook_set_restore_safety>restore</a></code>
+   /// apply.
+   pub unsafe fn restore_all(
+      & mut self,
+   ) -> Result<Vec<IatConflict>> {
+      let mut conflicts = Vec::new();
+
+      // Restore in the reverse of the
+      // order the entries were hooked
+      while let Some(entry) = self.entries.pop() {
+         match entry.restore() {
+            Ok(Some(conflict))   => conflicts.push(conflict),
+            Ok(None)             => (),
+            Err(err)             => {
+               self.entries.push(entry);
+               return Err(err);
+            },
+         }
+      }
+
+      return Ok(conflicts);
+   }
+}
+
+///////////////////////////////////
+// INTERNAL METHODS - IatHookSet //
+///////////////////////////////////
+
+impl IatHookSet {
+   fn find(
+      & self,
+      library  : & str,
+      function : & str,
+   ) -> Option<usize> {
+      return self.entries.iter().position(|entry| {
+         entry.library.eq_ignore_ascii_case(library) == true && entry.function == function
+      });
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - IatHookSet //
+////////////////////////////////////////
+
+impl Default for IatHookSet {
+   fn default() -> Self {
+      return Self::new();
+   }
+}
+
+impl std::ops::Drop for IatHookSet {
+   fn drop(
+      & mut self,
+   ) {
+      // Nothing can be done about conflicts
+      // or failures while dropping
+      let _ = unsafe{self.restore_all()};
+      return;
+   }
+}
+
+/////////////////////////////////
+// INTERNAL METHODS - IatEntry //
+/////////////////////////////////
+
+impl IatEntry {
+   unsafe fn conflict(
+      & self,
+   ) -> Option<IatConflict> {
+      let found = std::ptr::read_volatile(self.slot_address as * const usize);
+      if found == self.replacement {
+         return None;
+      }
+
+      return Some(IatConflict{
+         library        : self.library.clone(),
+         function       : self.function.clone(),
+         slot_address   : self.slot_address,
+         expected       : self.replacement,
+         found          : found,
+      });
+   }
+
+   unsafe fn restore(
+      & self,
+   ) -> Result<Option<IatConflict>> {
+      if let Some(conflict) = self.conflict() {
+         return Ok(Some(conflict));
+      }
+
+      swap_slot(self.slot_address, self.original)?;
+      return Ok(None);
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Finds the address of the import
+/// address table slot holding a function
+/// a module imports by name.
+pub(crate) unsafe fn import_slot_address(
+   module      : & crate::process::ModuleSnapshot,
+   library     : & str,
+   function    : & str,
+) -> Result<usize> {
+   return module.import_slot(library, function)?.ok_or_else(|| IatError::ImportNotFound{
+      library  : String::from(library),
+      function : String::from(function),
+   });
+}
+
+/// Writes a new pointer to an import
+/// address table slot, returning the
+/// old pointer.
+pub(crate) unsafe fn swap_slot(
+   slot_address   : usize,
+   value          : usize,
+) -> Result<usize> {
+   let slot_range = slot_address..slot_address + std::mem::size_of::<usize>();
+   let _write     = crate::patch::editor_cache_begin_write(&slot_range);
+   let mut editor = crate::sys::memory::MemoryEditor::open_read_write(slot_range)?;
+
+   let slot = &mut editor.as_slice_mut::<usize>()[0];
+   let old  = *slot;
+   *slot    = value;
+
+   // Failing to restore the permissions is
+   // returned instead of panicking on drop
+   editor.close()?;
+   return Ok(old);
+}
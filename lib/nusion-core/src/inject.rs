@@ -0,0 +1,281 @@
+//! Load a mod's library into a running
+//! game from outside of it, so a launcher
+//! only needs to find the game and point
+//! it at the library.
+//!
+//! ```
+//! let game = nusion_core::inject::wait_for_process(
+//!    "FSD-Win64-Shipping.exe",
+//!    None,
+//! )?;
+//!
+//! nusion_core::inject::inject_library(game.process_id(), "nusion_drg.dll")?;
+//! ```
+//!
+//! A mod which has to patch the game before
+//! it initializes can start the game itself
+//! with its main thread suspended instead.
+//! The game is resumed as soon as the mod's
+//! environment is created, before its main
+//! function runs.  A mod which patches the
+//! game in main sets <code>manual_ready</code>
+//! and calls <code>signal_ready</code> once
+//! its patches are in place, as
+//! <code>game/drg</code> does.  If main
+//! returns without calling it, the game is
+//! resumed then.
+//!
+//! ```
+//! let game = nusion_core::process::spawn_suspended("FSD-Win64-Shipping.exe", ["-nosplash"])?;
+//! nusion_core::inject::inject_and_resume(game, "nusion_drg.dll", Some(std::time::Duration::from_secs(30)))?;
+//! ```
+//!
+//! ```
+//! #[nusion_core::main(processes("FSD-Win64-Shipping.exe"), manual_ready = true)]
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!    let _hook = unsafe{nusion_core::process::game_mut().patch_create(&main_loop_hook)}?;
+//!    nusion_core::inject::signal_ready()?;
+//!    // ...
+//! }
+//! ```
+//!
+//! The library is loaded by starting a
+//! thread in the game which calls the
+//! system's library loader, so the library
+//! shows up in the game's module list like
+//! any other.  Injection is only supported
+//! on Windows.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to loading a
+/// library into another process.
+#[derive(Debug)]
+pub enum InjectError {
+   ProcessError{
+      err                  : crate::process::ProcessError,
+   },
+   ProcessNotFound{
+      executable_file_name : String,
+   },
+   ProcessIdNotFound{
+      process_id           : u32,
+   },
+   LibraryNotFound{
+      library_path         : std::path::PathBuf,
+   },
+   TimedOut,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>InjectError</code>.
+pub type Result<T> = std::result::Result<T, InjectError>;
+
+/// The process to load a library into,
+/// either by the file name of its main
+/// executable or by its process ID.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InjectTarget<'s> {
+   ExecutableFileName(&'s str),
+   ProcessId(u32),
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - InjectError //
+/////////////////////////////////////////
+
+impl std::fmt::Display for InjectError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ProcessError      {err,                  }
+            => write!(stream, "Process error: {err}"),
+         Self::ProcessNotFound   {executable_file_name, }
+            => write!(stream, "No process running \"{executable_file_name}\""),
+         Self::ProcessIdNotFound {process_id,           }
+            => write!(stream, "No process with ID {process_id}"),
+         Self::LibraryNotFound   {library_path,         }
+            => write!(stream, "Library \"{}\" not found", library_path.display()),
+         Self::TimedOut
+            => write!(stream, "Timed out waiting for the process"),
+      };
+   }
+}
+
+impl std::error::Error for InjectError {
+}
+
+impl From<crate::process::ProcessError> for InjectError {
+   fn from(
+      item : crate::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : item,
+      };
+   }
+}
+
+impl From<crate::sys::process::ProcessError> for InjectError {
+   fn from(
+      item : crate::sys::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : crate::process::ProcessError::from(item),
+      };
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - InjectTarget //
+//////////////////////////////////////////
+
+impl<'s> From<&'s str> for InjectTarget<'s> {
+   fn from(
+      item : &'s str,
+   ) -> Self {
+      return Self::ExecutableFileName(item);
+   }
+}
+
+impl<'s> From<u32> for InjectTarget<'s> {
+   fn from(
+      item : u32,
+   ) -> Self {
+      return Self::ProcessId(item);
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Loads a library into another process
+/// and waits for it to finish loading,
+/// including running its entrypoint.  The
+/// library path is made absolute first,
+/// since the process resolves relative
+/// paths against its own directory.
+pub fn inject_library<'s, T, P>(
+   target         : T,
+   library_path   : P,
+) -> Result<()>
+where T: Into<InjectTarget<'s>>,
+      P: AsRef<std::path::Path>,
+{
+   let library_path = library_path.as_ref();
+   let library_path = std::fs::canonicalize(library_path).map_err(|_| {
+      InjectError::LibraryNotFound{
+         library_path : library_path.to_path_buf(),
+      }
+   })?;
+
+   let processes  = crate::process::ProcessSnapshotList::all()?;
+   let process    = match target.into() {
+      InjectTarget::ExecutableFileName(executable_file_name)
+         => processes.get(executable_file_name)
+            .ok_or(InjectError::ProcessNotFound{
+               executable_file_name : String::from(executable_file_name),
+            })?,
+      InjectTarget::ProcessId(process_id)
+         => processes.find_by_process_id(process_id)
+            .ok_or(InjectError::ProcessIdNotFound{
+               process_id : process_id,
+            })?,
+   };
+
+   let remote = process.open_remote()?;
+   remote.load_library(&library_path)?;
+
+   return Ok(());
+}
+
+/// Loads a library into a process which was
+/// started suspended, then resumes it once
+/// the mod signals that it's ready.  This
+/// happens before the mod's main runs, or
+/// once main calls <code>signal_ready</code>
+/// if the mod sets <code>manual_ready</code>,
+/// so its patches are in place before any of
+/// the game's code runs.  The library's
+/// entrypoint returns as soon as the mod's
+/// main thread is started, so that alone
+/// isn't enough.  The process ID is returned.
+/// If loading the library fails or the mod
+/// isn't ready before the timeout, the
+/// process is terminated.
+pub fn inject_and_resume<P>(
+   process        : crate::process::SuspendedProcess,
+   library_path   : P,
+   timeout        : Option<std::time::Duration>,
+) -> Result<u32>
+where P: AsRef<std::path::Path>,
+{
+   let library_path = library_path.as_ref();
+   let library_path = std::fs::canonicalize(library_path).map_err(|_| {
+      InjectError::LibraryNotFound{
+         library_path : library_path.to_path_buf(),
+      }
+   })?;
+
+   let process_id = process.process_id();
+   process.load_library(&library_path)?;
+   if process.wait_ready(timeout)? == false {
+      return Err(InjectError::TimedOut);
+   }
+   process.resume()?;
+
+   return Ok(process_id);
+}
+
+/// Tells the launcher which started the game
+/// with <code>inject_and_resume</code> that
+/// the mod is ready, so the game can be
+/// resumed.  A mod which sets
+/// <code>manual_ready</code> in its
+/// <code>main</code> attribute must call
+/// this once the patches which must be in
+/// place before the game initializes have
+/// been made, or the game stays suspended
+/// until the launcher gives up.  Otherwise
+/// it's called before main runs.  This does
+/// nothing if the game wasn't started that
+/// way, and is called again when the mod's
+/// main function returns.
+pub fn signal_ready(
+) -> Result<()> {
+   crate::sys::process::signal_ready()?;
+   return Ok(());
+}
+
+/// Waits for a process to start by polling
+/// the process list, such as after starting
+/// the game through a store launcher.  If a
+/// timeout is given and the process doesn't
+/// start in time, an error is returned.
+pub fn wait_for_process(
+   executable_file_name : & str,
+   timeout              : Option<std::time::Duration>,
+) -> Result<crate::process::ProcessSnapshot> {
+   const POLL_INTERVAL : std::time::Duration
+      = std::time::Duration::from_millis(100);
+
+   let start = std::time::Instant::now();
+   loop {
+      let mut processes = crate::process::ProcessSnapshotList::all()?;
+      if let Some(process) = processes.remove(executable_file_name) {
+         return Ok(process);
+      }
+
+      if let Some(timeout) = timeout {
+         if start.elapsed() >= timeout {
+            return Err(InjectError::TimedOut);
+         }
+      }
+
+      std::thread::sleep(POLL_INTERVAL);
+   }
+}
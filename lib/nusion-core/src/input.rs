@@ -0,0 +1,358 @@
+//! Polled keyboard and mouse button
+//! state for hotkeys.  Keys implement
+//! <code>bindings::BindingKey</code>, so
+//! they can be used directly in binding
+//! tables for rebindable toggles.
+//!
+//! Global key state is only available
+//! on Windows.  On Linux, every key reads
+//! as released.
+
+/////////////////////
+// INTERNAL MACROS //
+/////////////////////
+
+macro_rules! keys {
+   (
+      $(#[$meta:meta])*
+      pub enum $name:ident {
+         $($key:ident = $code:literal,)*
+      }
+   ) => {
+      $(#[$meta])*
+      #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+      #[repr(u8)]
+      pub enum $name {
+         $($key = $code,)*
+      }
+
+      impl $name {
+         /// Every key, in order of
+         /// virtual-key code.
+         pub const ALL : &'static [Self] = &[$(Self::$key,)*];
+
+         /// Gets the name of the key, which
+         /// is the same as its variant name.
+         pub fn name(
+            self,
+         ) -> &'static str {
+            return match self {
+               $(Self::$key => stringify!($key),)*
+            };
+         }
+      }
+   };
+}
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+keys!{
+   /// A keyboard key or mouse button,
+   /// represented by its Windows
+   /// virtual-key code.
+   pub enum Key {
+      MouseLeft   = 0x01,
+      MouseRight  = 0x02,
+      MouseMiddle = 0x04,
+      Backspace   = 0x08,
+      Tab         = 0x09,
+      Enter       = 0x0D,
+      Shift       = 0x10,
+      Control     = 0x11,
+      Alt         = 0x12,
+      Escape      = 0x1B,
+      Space       = 0x20,
+      PageUp      = 0x21,
+      PageDown    = 0x22,
+      End         = 0x23,
+      Home        = 0x24,
+      Left        = 0x25,
+      Up          = 0x26,
+      Right       = 0x27,
+      Down        = 0x28,
+      Insert      = 0x2D,
+      Delete      = 0x2E,
+      Digit0      = 0x30,
+      Digit1      = 0x31,
+      Digit2      = 0x32,
+      Digit3      = 0x33,
+      Digit4      = 0x34,
+      Digit5      = 0x35,
+      Digit6      = 0x36,
+      Digit7      = 0x37,
+      Digit8      = 0x38,
+      Digit9      = 0x39,
+      A           = 0x41,
+      B           = 0x42,
+      C           = 0x43,
+      D           = 0x44,
+      E           = 0x45,
+      F           = 0x46,
+      G           = 0x47,
+      H           = 0x48,
+      I           = 0x49,
+      J           = 0x4A,
+      K           = 0x4B,
+      L           = 0x4C,
+      M           = 0x4D,
+      N           = 0x4E,
+      O           = 0x4F,
+      P           = 0x50,
+      Q           = 0x51,
+      R           = 0x52,
+      S           = 0x53,
+      T           = 0x54,
+      U           = 0x55,
+      V           = 0x56,
+      W           = 0x57,
+      X           = 0x58,
+      Y           = 0x59,
+      Z           = 0x5A,
+      Numpad0     = 0x60,
+      Numpad1     = 0x61,
+      Numpad2     = 0x62,
+      Numpad3     = 0x63,
+      Numpad4     = 0x64,
+      Numpad5     = 0x65,
+      Numpad6     = 0x66,
+      Numpad7     = 0x67,
+      Numpad8     = 0x68,
+      Numpad9     = 0x69,
+      F1          = 0x70,
+      F2          = 0x71,
+      F3          = 0x72,
+      F4          = 0x73,
+      F5          = 0x74,
+      F6          = 0x75,
+      F7          = 0x76,
+      F8          = 0x77,
+      F9          = 0x78,
+      F10         = 0x79,
+      F11         = 0x7A,
+      F12         = 0x7B,
+   }
+}
+
+/// A change in a key's state seen
+/// while polling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyEvent {
+   pub key     : Key,
+   pub pressed : bool,
+}
+
+/// The state of every key as of the
+/// last poll.  Comparing against the
+/// poll before it detects presses and
+/// releases, so <code>poll</code> should
+/// be called once per iteration of the
+/// mod's main loop.
+///
+/// ```
+/// let mut input = nusion_core::input::InputState::new();
+///
+/// input.add_hook(|event| {
+///    println!("{:?} pressed: {}", event.key, event.pressed);
+/// });
+///
+/// loop {
+///    input.poll();
+///
+///    if input.was_pressed(nusion_core::input::Key::F1) == true {
+///       god_mode = god_mode == false;
+///    }
+/// }
+/// ```
+pub struct InputState {
+   down     : [bool; KEY_CODE_COUNT],
+   previous : [bool; KEY_CODE_COUNT],
+   hooks    : Vec<Box<dyn FnMut(KeyEvent) + Send>>,
+}
+
+////////////////////////////////////
+// GLOBAL VARIABLES AND CONSTANTS //
+////////////////////////////////////
+
+const KEY_CODE_COUNT : usize = 256;
+
+///////////////////
+// METHODS - Key //
+///////////////////
+
+impl Key {
+   /// Gets the Windows virtual-key code
+   /// for the key.
+   pub fn code(
+      self,
+   ) -> u8 {
+      return self as u8;
+   }
+
+   /// Parses a key from its name,
+   /// ignoring case.
+   pub fn from_name(
+      name : & str,
+   ) -> Option<Self> {
+      return Self::ALL.iter().copied().find(|key| {
+         key.name().eq_ignore_ascii_case(name.trim())
+      });
+   }
+
+   /// Checks whether the key is held
+   /// down right now, without any
+   /// polling.
+   pub fn is_down(
+      self,
+   ) -> bool {
+      return crate::sys::input::is_key_down(self.code());
+   }
+}
+
+/////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Key //
+/////////////////////////////////
+
+impl std::fmt::Display for Key {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", self.name());
+   }
+}
+
+impl std::str::FromStr for Key {
+   type Err = crate::bindings::BindingError;
+
+   fn from_str(
+      name : & str,
+   ) -> crate::bindings::Result<Self> {
+      return Self::from_name(name).ok_or_else(|| crate::bindings::BindingError::UnknownKey{
+         name : String::from(name),
+      });
+   }
+}
+
+impl crate::bindings::BindingKey for Key {
+   fn is_pressed(
+      self,
+   ) -> bool {
+      return self.is_down();
+   }
+
+   fn from_name(
+      name : & str,
+   ) -> Option<Self> {
+      return Key::from_name(name);
+   }
+}
+
+//////////////////////////
+// METHODS - InputState //
+//////////////////////////
+
+impl InputState {
+   /// Creates a new state with every
+   /// key released.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         down     : [false; KEY_CODE_COUNT],
+         previous : [false; KEY_CODE_COUNT],
+         hooks    : Vec::new(),
+      };
+   }
+
+   /// Reads the state of every key,
+   /// calling the hooks for each key
+   /// which was pressed or released
+   /// since the last poll.
+   pub fn poll(
+      & mut self,
+   ) -> & mut Self {
+      self.previous = self.down;
+
+      for key in Key::ALL.iter().copied() {
+         let index   = key.code() as usize;
+         let down    = key.is_down();
+
+         self.down[index] = down;
+         if down == self.previous[index] {
+            continue;
+         }
+
+         let event = KeyEvent{
+            key      : key,
+            pressed  : down,
+         };
+         for hook in self.hooks.iter_mut() {
+            hook(event);
+         }
+      }
+
+      return self;
+   }
+
+   /// Checks whether a key was held
+   /// down as of the last poll.
+   pub fn is_down(
+      & self,
+      key : Key,
+   ) -> bool {
+      return self.down[key.code() as usize];
+   }
+
+   /// Checks whether a key went down
+   /// during the last poll.
+   pub fn was_pressed(
+      & self,
+      key : Key,
+   ) -> bool {
+      let index = key.code() as usize;
+      return self.down[index] == true && self.previous[index] == false;
+   }
+
+   /// Checks whether a key was let go
+   /// during the last poll.
+   pub fn was_released(
+      & self,
+      key : Key,
+   ) -> bool {
+      let index = key.code() as usize;
+      return self.down[index] == false && self.previous[index] == true;
+   }
+
+   /// Adds a hook which is called from
+   /// <code>poll</code> for every key
+   /// which was pressed or released.
+   pub fn add_hook<F>(
+      & mut self,
+      hook : F,
+   ) -> & mut Self
+   where F: FnMut(KeyEvent) + Send + 'static,
+   {
+      self.hooks.push(Box::new(hook));
+      return self;
+   }
+
+   /// Removes every hook.
+   pub fn clear_hooks(
+      & mut self,
+   ) -> & mut Self {
+      self.hooks.clear();
+      return self;
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - InputState //
+////////////////////////////////////////
+
+impl Default for InputState {
+   fn default() -> Self {
+      return Self::new();
+   }
+}
+
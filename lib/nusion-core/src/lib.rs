@@ -41,6 +41,21 @@
 //! for Windows or target/\[profile\]/\[name\].so
 //! for Linux.
 //!
+//! Nothing in this library uses the network
+//! unless the <code>network</code> feature is
+//! enabled.  To prove a mod can't phone home,
+//! enable the <code>deny-network</code> feature,
+//! which fails the build if any crate in the
+//! dependency graph enables <code>network</code>
+//! or if the workspace's Cargo.lock contains a
+//! crate which reaches the network, such as
+//! <code>reqwest</code> or <code>hyper</code>.
+//!
+//! ```toml
+//! [dependencies]
+//! nusion-lib = { version = "*", features = ["deny-network"] }
+//! ```
+//!
 //! <h5 id=  nusion_core_guide_entrypoint>
 //! <a href=#nusion_core_guide_entrypoint>
 //! Declaring an entrypoint
@@ -94,10 +109,61 @@
 //! }
 //! ```
 //!
+//! Process names are compared ignoring case,
+//! and may contain wildcards for games which
+//! ship differently named executables, where
+//! <code>*</code> matches any number of
+//! characters and <code>?</code> matches
+//! any single character.
+//!
+//! ```
+//! #[nusion_core::main("FSD-Win64-*.exe")]
+//! fn main() {
+//!    println!("Hello, world");
+//! }
+//! ```
+//!
 //! You still must have only one entrypoint function,
 //! even if the excess definition has a different
 //! process whitelist.
 //!
+//! <h5 id=  nusion_core_guide_console_options>
+//! <a href=#nusion_core_guide_console_options>
+//! Configure the console window
+//! </a></h5>
+//!
+//! Named options can be given alongside the
+//! process whitelist to set the console's
+//! title, or to skip creating the console
+//! entirely.  The <code>console</code> option
+//! takes any <code>bool</code> expression, so
+//! release builds can go without one.
+//!
+//! ```
+//! #[nusion_core::main(
+//!    processes("hl2.exe"),
+//!    console  = cfg!(debug_assertions),
+//!    title    = "My Mod",
+//! )]
+//! fn main() {
+//!    println!("Hello, world!");
+//! }
+//! ```
+//!
+//! The console can also be named directly:
+//! <code>Window</code> creates a console
+//! window, <code>Attach</code> uses the
+//! terminal the game was launched from,
+//! and <code>None</code> never shows a
+//! console, which suits shipped trainers.
+//!
+//! ```
+//! #[nusion_core::main(processes("hl2.exe"), console = Attach)]
+//! fn main() {
+//!    println!("Printed to the launching terminal");
+//! }
+//! ```
+//!
 //! <h5 id=  nusion_core_guide_return_errors>
 //! <a href=#nusion_core_guide_return_errors>
 //! Return errors from your entrypoint
@@ -177,18 +243,13 @@
 //! an OS-independent way.
 //!
 //! ```
-//! #[nusion::main("hl2.exe")]
+//! #[nusion::main(processes("hl2.exe"), title = "Hello Modding World Console")]
 //! fn main() {
-//!    // Change the default console title
-//!    nusion_core::env_mut!().console_mut().set_title(
-//!       "Hello Modding World Console",
-//!    );
-//!
 //!    // Access the module for our target
 //!    // process "hl2.exe"
 //!    let mut game = nusion_core::env_mut!()
 //!      .modules_mut()
-//!      .find_by_executable_file_name("hl2.exe")
+//!      .get("hl2.exe")
 //!      .unwrap();  // You should properly handle this in real projects
 //! }
 //! ```
@@ -230,7 +291,7 @@
 //! than some threshold...so let's do it!
 //!
 //! ```
-//! #[nusion::main("hl2.exe")]
+//! #[nusion::main(processes("hl2.exe"), title = "Hello Modding World Console")]
 //! fn main() {
 //!    // Our Reader struct for reading the health value
 //!    const READER_HEALTH : nusion_core::patch::reader::Item<i32> = nusion_lib::patch::reader::Item<i32>{
@@ -245,16 +306,11 @@
 //!       item                : &100,
 //!    };
 //!
-//!    // Change the default console title
-//!    nusion_core::env_mut!().console_mut().set_title(
-//!       "Hello Modding World Console",
-//!    );
-//!
 //!    // Access the module for our target
 //!    // process "hl2.exe"
 //!    let mut game = nusion_core::env_mut!()
 //!      .modules_mut()
-//!      .find_by_executable_file_name("hl2.exe")
+//!      .get("hl2.exe")
 //!      .unwrap();  // You should properly handle this in real projects
 //!      
 //!    // Import the Patch trait so we can modify process memory
@@ -331,7 +387,7 @@
 //! <code>Hook</code> and <code>Asm</code>.
 //!
 //! ```
-//! #[nusion::main("hl2.exe")]
+//! #[nusion::main(processes("hl2.exe"), title = "Hello Modding World Console")]
 //! fn main() {
 //!    // Our Writer struct for hooking the damage function
 //!    const HOOK_DAMAGE : nusion_core::patch::writer::Hook = nusion_lib::patch::writer::Hook{ 
@@ -364,16 +420,11 @@
 //!       }),
 //!    };
 //!
-//!    // Change the default console title
-//!    nusion_core::env_mut!().console_mut().set_title(
-//!       "Hello Modding World Console",
-//!    );
-//!
 //!    // Access the module for our target
 //!    // process "hl2.exe"
 //!    let mut game = nusion_core::env_mut!()
 //!      .modules_mut()
-//!      .find_by_executable_file_name("hl2.exe")
+//!      .get("hl2.exe")
 //!      .unwrap();  // You should properly handle this in real projects
 //!      
 //!    // Import the Patch trait so we can modify process memory
@@ -435,29 +486,25 @@
 //! struct!
 //!
 //! ```
-//! #[nusion::main("hl2.exe")]
+//! #[nusion::main(processes("hl2.exe"), title = "Hello Modding World Console")]
 //! fn main() {
 //!    // Our Writer struct for patching the damage function
-//!    const ASM_DAMAGE : nusion_core::patch::writer::Asm = nusion_lib::patch::writer::Asm{ 
+//!    const ASM_DAMAGE : nusion_core::patch::writer::Asm<std::ops::Range<usize>> = nusion_core::patch::writer::Asm{
 //!       memory_offset_range  : 0x9FCD4000..0x9FCD4010,
 //!       checksum             : nusion_core::patch::Checksum::from(0xFC204AFD),
-//!       hook                 : nusion_core::asm_bytes!("
+//!       alignment            : nusion_core::patch::Alignment::Left,
+//!       asm_bytes            : nusion_core::asm_bytes!("
 //!          // Overwrite the damage code and replace
 //!          // with a direct move of 100hp
-//!          mov   [rcx+0x40],100
+//!          mov   dword ptr [rcx+0x40],100
 //!       "),
 //!    };
 //!
-//!    // Change the default console title
-//!    nusion_core::env_mut!().console_mut().set_title(
-//!       "Hello Modding World Console",
-//!    );
-//!
 //!    // Access the module for our target
 //!    // process "hl2.exe"
 //!    let mut game = nusion_core::env_mut!()
 //!      .modules_mut()
-//!      .find_by_executable_file_name("hl2.exe")
+//!      .get("hl2.exe")
 //!      .unwrap();  // You should properly handle this in real projects
 //!      
 //!    // Import the Patch trait so we can modify process memory
@@ -502,19 +549,69 @@
 //! can also do that.  It's all up to you.  What
 //! will you build?
 
+// Network audit check
+#[cfg(all(
+   feature = "deny-network",
+   feature = "network",
+))] compile_error! (
+   "The \"deny-network\" feature is enabled, but a dependency enabled the \"network\" feature",
+);
+
 // Internal crate re-exports
+use nusion_core_base as base;
 use nusion_core_proc as proc;
 use nusion_core_sys  as sys;
 
 // Public modules
+pub mod allocator;
+pub mod asm;
+pub mod bindings;
+pub mod build_info;
+pub mod clipboard;
+pub mod config;
 pub mod console;
+pub mod coverage;
+pub mod cpu;
+pub mod debug;
+pub mod encoding;
 pub mod environment;
+pub mod exception;
+pub mod freeze;
+pub mod history;
+pub mod hook;
+pub mod host;
+pub mod iat;
+pub mod inject;
+pub mod input;
+pub mod log;
 pub mod macros;
+pub mod memory;
+pub mod offsets;
+#[cfg(feature = "overlay")]
+pub mod overlay;
 pub mod patch;
+pub mod plugin;
 pub mod process;
+pub mod runtime;
+pub mod sandbox;
+pub mod scan;
+pub mod screenshot;
+pub mod task;
+pub mod trace;
+pub mod versions;
+pub mod window;
 
 // Public module re-exports
 pub use proc::*;
+pub use build_info::{
+   BuildInfo,
+   BUILD_INFO,
+};
+pub use environment::{
+   request_unload,
+   is_unload_requested,
+   wait_for_unload,
+};
 
 // Public-internal items
 pub mod __private {
@@ -0,0 +1,371 @@
+//! Leveled logging routed to the console,
+//! an in-memory history, and optionally a
+//! file.
+//!
+//! This is a backend for the <code>log</code>
+//! crate, so messages from dependencies show
+//! up alongside the mod's own.  The logger is
+//! installed when the environment starts, and
+//! the usual macros are re-exported here.
+//!
+//! ```
+//! nusion_core::log::info!("Hooked the main loop");
+//! nusion_core::log::warn!(target : "ammo", "Ammo pointer is null");
+//!
+//! nusion_core::log::set_file("my_mod.log")?;
+//! nusion_core::log::set_target_level("ammo", nusion_core::log::LevelFilter::Warn);
+//! ```
+//!
+//! Each entry records the time, level, and
+//! target, which defaults to the module path
+//! of the call site.  The most recent entries
+//! are kept in memory and can be printed to
+//! the console with <code>print_recent</code>,
+//! such as after the console scrolled away.
+
+pub use ::log::{
+   Level,
+   LevelFilter,
+   trace,
+   debug,
+   info,
+   warn,
+   error,
+};
+
+/// Number of entries kept in memory
+/// unless changed by the user.
+const DEFAULT_CAPACITY : usize
+   = 1024;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to the logger.
+#[derive(Debug)]
+pub enum LogError {
+   AlreadyInitialized,
+   IoError{
+      err : std::io::Error,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>LogError</code>.
+pub type Result<T> = std::result::Result<T, LogError>;
+
+/// A single logged message.
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+   pub timestamp  : std::time::SystemTime,
+   pub level      : Level,
+   pub target     : String,
+   pub message    : String,
+}
+
+/// The logger installed into the
+/// <code>log</code> crate.
+struct Logger;
+
+/// Mutable state shared by every
+/// thread which logs.
+struct LoggerState {
+   entries        : std::collections::VecDeque<LogEntry>,
+   capacity       : usize,
+   file           : Option<std::fs::File>,
+   target_levels  : Vec<(String, LevelFilter)>,
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static LOGGER : Logger = Logger;
+
+lazy_static::lazy_static!{
+static ref LOGGER_STATE
+   : std::sync::Mutex<LoggerState>
+   = std::sync::Mutex::new(LoggerState{
+      entries        : std::collections::VecDeque::new(),
+      capacity       : DEFAULT_CAPACITY,
+      file           : None,
+      target_levels  : Vec::new(),
+   });
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - LogError //
+//////////////////////////////////////
+
+impl std::fmt::Display for LogError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::AlreadyInitialized
+            => write!(stream, "A logger was already installed"),
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for LogError {
+}
+
+impl From<std::io::Error> for LogError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - LogEntry //
+//////////////////////////////////////
+
+impl std::fmt::Display for LogEntry {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      const SECONDS_PER_DAY : u64 = 24 * 60 * 60;
+
+      // Times are shown in UTC since the
+      // local time zone isn't known portably
+      let since_epoch = self.timestamp
+         .duration_since(std::time::UNIX_EPOCH)
+         .unwrap_or_default();
+      let seconds = since_epoch.as_secs() % SECONDS_PER_DAY;
+
+      return write!(
+         stream,
+         "[{:02}:{:02}:{:02}.{:03} {:<5} {}] {}",
+         seconds / 3600,
+         seconds / 60 % 60,
+         seconds % 60,
+         since_epoch.subsec_millis(),
+         self.level,
+         self.target,
+         self.message,
+      );
+   }
+}
+
+////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Logger //
+////////////////////////////////////
+
+impl ::log::Log for Logger {
+   fn enabled(
+      & self,
+      metadata : & ::log::Metadata<'_>,
+   ) -> bool {
+      let state = match LOGGER_STATE.lock() {
+         Ok(state)   => state,
+         Err(_)      => return false,
+      };
+
+      return metadata.level() <= state.target_level(metadata.target());
+   }
+
+   fn log(
+      & self,
+      record : & ::log::Record<'_>,
+   ) {
+      if self.enabled(record.metadata()) == false {
+         return;
+      }
+
+      let entry = LogEntry{
+         timestamp   : std::time::SystemTime::now(),
+         level       : record.level(),
+         target      : String::from(record.target()),
+         message     : record.args().to_string(),
+      };
+
+      // Errors are ignored rather than
+      // panicking like the print macros,
+      // since there may be no console
+      use std::io::Write;
+      let _ = match entry.level {
+         Level::Error | Level::Warn
+            => writeln!(std::io::stderr().lock(), "{entry}"),
+         _
+            => writeln!(std::io::stdout().lock(), "{entry}"),
+      };
+
+      let mut state = match LOGGER_STATE.lock() {
+         Ok(state)   => state,
+         Err(_)      => return,
+      };
+
+      if let Some(file) = & mut state.file {
+         let _ = writeln!(file, "{entry}");
+      }
+
+      while state.entries.len() >= state.capacity && state.entries.is_empty() == false {
+         state.entries.pop_front();
+      }
+      if state.capacity != 0 {
+         state.entries.push_back(entry);
+      }
+
+      return;
+   }
+
+   fn flush(
+      & self,
+   ) {
+      if let Ok(mut state) = LOGGER_STATE.lock() {
+         if let Some(file) = & mut state.file {
+            use std::io::Write;
+            let _ = file.flush();
+         }
+      }
+
+      return;
+   }
+}
+
+////////////////////////////////////
+// INTERNAL METHODS - LoggerState //
+////////////////////////////////////
+
+impl LoggerState {
+   /// Finds the level of the most specific
+   /// target prefix matching a target.
+   fn target_level(
+      & self,
+      target : & str,
+   ) -> LevelFilter {
+      return self.target_levels.iter()
+         .filter(|(prefix, _)| {
+            target == prefix || target.strip_prefix(prefix.as_str())
+               .map(|rest| rest.starts_with("::") == true)
+               .unwrap_or(false) == true
+         })
+         .max_by_key(|(prefix, _)| prefix.len())
+         .map(|(_, level)| *level)
+         .unwrap_or(::log::max_level());
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Installs the logger into the <code>log</code>
+/// crate.  This is called when the environment
+/// starts, so it's only needed when using the
+/// logger without one.
+pub fn init(
+) -> Result<()> {
+   ::log::set_logger(&LOGGER).map_err(|_| LogError::AlreadyInitialized)?;
+   ::log::set_max_level(LevelFilter::Info);
+   return Ok(());
+}
+
+/// Sets the most verbose level which is
+/// logged by default.
+pub fn set_level(
+   level : LevelFilter,
+) {
+   ::log::set_max_level(level);
+   return;
+}
+
+/// Sets the most verbose level which is
+/// logged for a target and every target
+/// nested within it, such as a module and
+/// its submodules.  This can't log anything
+/// more verbose than <code>set_level</code>
+/// allows.
+pub fn set_target_level(
+   target   : & str,
+   level    : LevelFilter,
+) {
+   if let Ok(mut state) = LOGGER_STATE.lock() {
+      state.target_levels.retain(|(prefix, _)| prefix != target);
+      state.target_levels.push((String::from(target), level));
+   }
+
+   return;
+}
+
+/// Appends every logged message to a
+/// file, creating it if it doesn't exist.
+/// Any previous log file is closed.
+pub fn set_file<P>(
+   path : P,
+) -> Result<()>
+where P: AsRef<std::path::Path>,
+{
+   let file = std::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)?;
+
+   if let Ok(mut state) = LOGGER_STATE.lock() {
+      state.file = Some(file);
+   }
+
+   return Ok(());
+}
+
+/// Stops logging to a file.
+pub fn clear_file(
+) {
+   if let Ok(mut state) = LOGGER_STATE.lock() {
+      state.file = None;
+   }
+
+   return;
+}
+
+/// Sets how many of the most recent
+/// entries are kept in memory.
+pub fn set_capacity(
+   capacity : usize,
+) {
+   if let Ok(mut state) = LOGGER_STATE.lock() {
+      state.capacity = capacity;
+      while state.entries.len() > capacity {
+         state.entries.pop_front();
+      }
+   }
+
+   return;
+}
+
+/// Gets a copy of the most recent
+/// entries kept in memory, from oldest
+/// to newest.
+pub fn entries(
+) -> Vec<LogEntry> {
+   return match LOGGER_STATE.lock() {
+      Ok(state)   => state.entries.iter().cloned().collect(),
+      Err(_)      => Vec::new(),
+   };
+}
+
+/// Prints up to a number of the most
+/// recent entries to the console.
+pub fn print_recent(
+   count : usize,
+) {
+   use std::io::Write;
+
+   let entries    = entries();
+   let mut stdout = std::io::stdout().lock();
+   for entry in entries.iter().skip(entries.len().saturating_sub(count)) {
+      let _ = writeln!(stdout, "{entry}");
+   }
+
+   return;
+}
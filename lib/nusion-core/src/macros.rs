@@ -3,26 +3,47 @@
 /// Internal macro, do not use this!
 #[macro_export]
 macro_rules! __build_entry {
-   ($entry:ident, void,             $($proc:literal),*)   => {
+   ($entry:ident, void,             $console:expr, $title:expr, $minidump:expr, $manual_ready:expr, $($proc:literal),*)   => {
       $crate::__private::sys_build_entry!(
          $crate::__private::start_main::void,
          $entry,
+         ::core::env!("CARGO_PKG_NAME"),
+         $crate::environment::EnvironmentOptions{
+            console        : $crate::environment::ConsoleMode::from($console),
+            title          : $title,
+            minidump       : $minidump,
+            manual_ready   : $manual_ready,
+         },
          $crate::__private::osapi,
          $($proc),*
       );
    };
-   ($entry:ident, result_static,    $($proc:literal),*)   => {
+   ($entry:ident, result_static,    $console:expr, $title:expr, $minidump:expr, $manual_ready:expr, $($proc:literal),*)   => {
       $crate::__private::sys_build_entry!(
          $crate::__private::start_main::result_static,
          $entry,
+         ::core::env!("CARGO_PKG_NAME"),
+         $crate::environment::EnvironmentOptions{
+            console        : $crate::environment::ConsoleMode::from($console),
+            title          : $title,
+            minidump       : $minidump,
+            manual_ready   : $manual_ready,
+         },
          $crate::__private::osapi,
          $($proc),*
       );
    };
-   ($entry:ident, result_dynamic,   $($proc:literal),*)   => {
+   ($entry:ident, result_dynamic,   $console:expr, $title:expr, $minidump:expr, $manual_ready:expr, $($proc:literal),*)   => {
       $crate::__private::sys_build_entry!(
          $crate::__private::start_main::result_dynamic,
          $entry,
+         ::core::env!("CARGO_PKG_NAME"),
+         $crate::environment::EnvironmentOptions{
+            console        : $crate::environment::ConsoleMode::from($console),
+            title          : $title,
+            minidump       : $minidump,
+            manual_ready   : $manual_ready,
+         },
          $crate::__private::osapi,
          $($proc),*
       );
@@ -61,3 +82,310 @@ macro_rules! try_env_mut {
    };
 }
 
+/// Exports this mod's <code>plugin::HostApi</code>
+/// so other mods can register with it as
+/// plugins.  This must be used at most once,
+/// at the root of the mod's crate.
+///
+/// ```
+/// nusion_core::plugin_host!();
+/// ```
+#[macro_export]
+macro_rules! plugin_host {
+   () => {
+      #[no_mangle]
+      pub extern "C" fn nusion_plugin_host(
+      ) -> *const $crate::plugin::HostApi {
+         return $crate::plugin::host_api();
+      }
+   };
+}
+
+/// Logs a message the first time the
+/// call site is reached and then once
+/// every <code>count</code> times after
+/// that.  This keeps logging inside hooks
+/// which fire every frame from flooding
+/// the console.  The counter is shared by
+/// every thread reaching the call site.
+///
+/// ```
+/// nusion_core::log_every!(100, "Damage taken: {damage}");
+/// ```
+#[macro_export]
+macro_rules! log_every {
+   ($count:expr, $($arg:tt)+) => {{
+      static CALLS : ::std::sync::atomic::AtomicUsize
+         = ::std::sync::atomic::AtomicUsize::new(0);
+
+      let count : usize = $count;
+      if CALLS.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % usize::max(count, 1) == 0 {
+         $crate::log::info!($($arg)+);
+      }
+   }};
+}
+
+/// Logs a message only the first time
+/// the call site is reached.
+///
+/// ```
+/// nusion_core::log_once!("Main loop hook is running");
+/// ```
+#[macro_export]
+macro_rules! log_once {
+   ($($arg:tt)+) => {{
+      static LOGGED : ::std::sync::atomic::AtomicBool
+         = ::std::sync::atomic::AtomicBool::new(false);
+
+      if LOGGED.swap(true, ::std::sync::atomic::Ordering::Relaxed) == false {
+         $crate::log::info!($($arg)+);
+      }
+   }};
+}
+
+/// Calculates a <code>patch::Checksum</code>
+/// of known bytes at build time, bound to
+/// their length.  The algorithm is a variant
+/// name of <code>patch::ChecksumAlgorithm</code>
+/// and the bytes are a byte string or a byte
+/// array, such as the original machine code
+/// a patch overwrites.
+///
+/// ```
+/// const CHECKSUM : nusion_core::patch::Checksum = nusion_core::checksum!(
+///    Sha256, b"\x8B\x79\x40\x2B\x79\x44\x89\x79\x40",
+/// );
+/// ```
+#[macro_export]
+macro_rules! checksum {
+   ($algorithm:ident, $bytes:expr $(,)?) => {
+      const {
+         $crate::patch::Checksum::compute(
+            $crate::patch::ChecksumAlgorithm::$algorithm,
+            $bytes,
+         )
+      }
+   };
+}
+
+/// Gets a function within a module by
+/// its offset as a function pointer, so
+/// game code can be called without
+/// transmuting addresses by hand.  The
+/// offset is checked to lie within the
+/// module's sections and in executable
+/// memory.  See <code>process::ModuleSnapshot::function</code>.
+///
+/// The function type is either a full
+/// function pointer type, or a plain
+/// <code>fn</code> type optionally prefixed
+/// by a calling convention, which is one of
+/// <code>win64</code>, <code>sysv64</code>,
+/// or <code>thiscall</code>.  Without a
+/// calling convention, the platform's C
+/// convention is used.  There's no separate
+/// thiscall convention on x86-64, so
+/// <code>thiscall</code> takes the object
+/// pointer as the first argument in the
+/// platform's C convention, the same as
+/// a member function compiled for it.
+///
+/// ```
+/// let damage = unsafe{nusion_core::game_fn!(
+///    crate::game!(), 0x4F22A0, extern "C" fn(* mut Entity, i32),
+/// )}?;
+/// let heal = unsafe{nusion_core::game_fn!(
+///    crate::game!(), 0x4F2410, thiscall fn(* mut Entity, f32) -> bool,
+/// )}?;
+///
+/// damage(player, 10);
+/// heal(player, 25.0);
+/// ```
+#[macro_export]
+macro_rules! game_fn {
+   ($module:expr, $offset:expr, fn($($argument:ty),* $(,)?) $(-> $return:ty)? $(,)?) => {
+      ($module).function::<extern "C" fn($($argument),*) $(-> $return)?>($offset)
+   };
+   ($module:expr, $offset:expr, $convention:ident fn($($argument:ty),* $(,)?) $(-> $return:ty)? $(,)?) => {
+      ($module).function::<$crate::__game_fn_convention!($convention, ($($argument),*) $(-> $return)?)>($offset)
+   };
+   ($module:expr, $offset:expr, $function:ty $(,)?) => {
+      ($module).function::<$function>($offset)
+   };
+}
+
+/// Internal macro, do not use this!
+#[macro_export]
+macro_rules! __game_fn_convention {
+   (win64,     ($($argument:ty),*) $(-> $return:ty)?) => {
+      extern "win64" fn($($argument),*) $(-> $return)?
+   };
+   (sysv64,    ($($argument:ty),*) $(-> $return:ty)?) => {
+      extern "sysv64" fn($($argument),*) $(-> $return)?
+   };
+   (thiscall,  ($($argument:ty),*) $(-> $return:ty)?) => {
+      extern "C" fn($($argument),*) $(-> $return)?
+   };
+   ($convention:ident, ($($argument:ty),*) $(-> $return:ty)?) => {
+      ::core::compile_error!(::core::concat!(
+         "Unknown calling convention \"", ::core::stringify!($convention),
+         "\", expected win64, sysv64, or thiscall",
+      ))
+   };
+}
+
+/// Generates a binding table which maps
+/// keys and console commands to features.
+/// Each entry is a feature name, either
+/// <code>press</code> or <code>toggle</code>,
+/// the default key, and the console command.
+/// The key type must implement
+/// <code>bindings::BindingKey</code>.
+///
+/// ```
+/// nusion_core::bindings!{
+///    pub struct Bindings : nusion_core::input::Key {
+///       exit     : press  (Key::Delete)  => "exit",
+///       flight   : toggle (Key::Numpad1) => "flight",
+///    }
+/// }
+///
+/// let mut bindings = Bindings::new();
+/// bindings.load_config("flight = Numpad5")?;
+/// bindings.poll();
+///
+/// if bindings.flight.is_changed() == true {
+///    println!("Flight: {}", bindings.flight.is_active());
+/// }
+/// ```
+///
+/// The generated struct contains a public
+/// <code>bindings::Binding</code> field for
+/// each feature along with the following
+/// methods:
+///
+/// <ul>
+/// <li><code>new</code> - Creates the table with the default keys.</li>
+/// <li><code>poll</code> - Updates every feature from its key.</li>
+/// <li><code>run_command</code> - Triggers the feature bound to a console command.</li>
+/// <li><code>rebind</code> - Binds a feature to a new key by name.</li>
+/// <li><code>load_config</code> - Rebinds features from a config string.</li>
+/// <li><code>iter</code> - Iterates over every binding.</li>
+/// </ul>
+#[macro_export]
+macro_rules! bindings {
+   (
+      $(#[$meta:meta])*
+      $vis:vis struct $name:ident : $key:ty {
+         $(
+            $feature:ident : $mode:ident ($default:expr) => $command:literal
+         ),* $(,)?
+      }
+   ) => {
+      $(#[$meta])*
+      $vis struct $name {
+         $(
+            pub $feature : $crate::bindings::Binding<$key>,
+         )*
+      }
+
+      #[allow(dead_code)]
+      impl $name {
+         /// Creates the binding table
+         /// with the default keys.
+         pub fn new(
+         ) -> Self {
+            return Self{
+               $(
+                  $feature : $crate::bindings::Binding::new(
+                     stringify!($feature),
+                     $command,
+                     $crate::__bindings_mode!($mode),
+                     $default,
+                  ),
+               )*
+            };
+         }
+
+         /// Updates every feature
+         /// from its key.
+         pub fn poll(
+            & mut self,
+         ) -> & mut Self {
+            $(
+               self.$feature.poll();
+            )*
+            return self;
+         }
+
+         /// Triggers the feature bound to a
+         /// console command, returning its
+         /// name or <code>None</code> if no
+         /// feature uses the command.
+         pub fn run_command(
+            & mut self,
+            command : & str,
+         ) -> Option<&'static str> {
+            let command = command.trim();
+            $(
+               if self.$feature.command() == command {
+                  self.$feature.trigger();
+                  return Some(self.$feature.feature());
+               }
+            )*
+            return None;
+         }
+
+         /// Binds a feature to a new key.
+         pub fn rebind(
+            & mut self,
+            feature  : & str,
+            key      : $key,
+         ) -> $crate::bindings::Result<& mut Self> {
+            match feature {
+               $(
+                  stringify!($feature) => {
+                     self.$feature.set_key(key);
+                  },
+               )*
+               _ => return Err($crate::bindings::BindingError::UnknownFeature{
+                  name : String::from(feature),
+               }),
+            }
+            return Ok(self);
+         }
+
+         /// Rebinds features from a config
+         /// string.  Features missing from
+         /// the config keep their keys.
+         pub fn load_config(
+            & mut self,
+            config : & str,
+         ) -> $crate::bindings::Result<& mut Self> {
+            for (feature, key) in $crate::bindings::parse_config::<$key>(config)? {
+               self.rebind(&feature, key)?;
+            }
+            return Ok(self);
+         }
+
+         /// Iterates over every binding in
+         /// the order they were declared.
+         pub fn iter<'l>(
+            &'l self,
+         ) -> impl Iterator<Item = &'l $crate::bindings::Binding<$key>> {
+            return [$(&self.$feature),*].into_iter();
+         }
+      }
+   };
+}
+
+/// Internal macro, do not use this!
+#[macro_export]
+macro_rules! __bindings_mode {
+   (press)  => {
+      $crate::bindings::BindingMode::Press
+   };
+   (toggle) => {
+      $crate::bindings::BindingMode::Toggle
+   };
+}
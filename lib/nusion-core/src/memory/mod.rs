@@ -0,0 +1,4 @@
+//! Search the whole address space of
+//! the game, rather than a single module.
+
+pub mod scanner;
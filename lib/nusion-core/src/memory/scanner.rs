@@ -0,0 +1,769 @@
+//! Search every committed region of the
+//! process for a value, then narrow the
+//! results down by rescanning as the value
+//! changes in game, like Cheat Engine.
+//!
+//! ```
+//! use nusion_core::memory::scanner::{MemoryScanner, ScanComparison};
+//!
+//! // Find every copy of the current ammo count
+//! let mut results = MemoryScanner::new().scan::<i32>(30)?;
+//!
+//! // Fire a shot, then keep the ones which went down
+//! results.rescan(ScanComparison::Decreased);
+//! results.rescan(ScanComparison::Exact(29));
+//!
+//! for found in results.matches() {
+//!    println!("{:#X} = {}", found.address, found.value);
+//! }
+//! ```
+//!
+//! When the value isn't known, such as a
+//! health bar without a number, start with
+//! <code>scan_unknown</code> and narrow it
+//! down with <code>Changed</code>,
+//! <code>Increased</code> and so on.
+//!
+//! Only writable memory is searched unless
+//! changed, since that's where values which
+//! change at runtime are stored.  Memory is
+//! copied before it is compared, so memory
+//! freed by the game during a scan is skipped
+//! instead of crashing.
+
+use std::ops::Range;
+
+/// Number of bytes copied at
+/// a time while scanning.
+const CHUNK_SIZE : usize
+   = 1024 * 1024;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to a memory scan.
+#[derive(Debug)]
+pub enum ScannerError {
+   MemoryError{
+      sys_error   : crate::sys::memory::MemoryError,
+   },
+   EmptyString,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ScannerError</code>.
+pub type Result<T> = std::result::Result<T, ScannerError>;
+
+/// A value type which can be
+/// searched for in memory.
+///
+/// <h2 id=  scan_value_safety>
+/// <a href=#scan_value_safety>
+/// Safety
+/// </a></h2>
+///
+/// Every bit pattern of the type's size
+/// must be a valid value of the type, and
+/// the type must not be zero-sized or contain
+/// padding, since values are read from
+/// arbitrary memory and compared by bytes.
+pub unsafe trait ScanValue : Copy + PartialOrd + std::fmt::Debug {
+}
+
+/// How a scan decides which
+/// addresses to keep.  Everything
+/// other than <code>Exact</code> and
+/// <code>Between</code> compares against
+/// the value from the previous scan.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScanComparison<T: ScanValue> {
+   Exact(T),
+   Between(T, T),
+   Changed,
+   Unchanged,
+   Increased,
+   Decreased,
+}
+
+/// Settings for scanning memory, started
+/// with one of the <code>scan</code> methods.
+/// By default, all writable memory in the
+/// process is searched with each value
+/// type's natural alignment.
+#[derive(Clone, Debug)]
+pub struct MemoryScanner {
+   address_range  : Range<usize>,
+   alignment      : Option<usize>,
+   read_only      : bool,
+}
+
+/// An address which matched a scan
+/// along with its value at the time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScanMatch<T: ScanValue> {
+   pub address : usize,
+   pub value   : T,
+}
+
+/// The addresses which matched a
+/// value scan, which can be narrowed
+/// down by rescanning.
+pub struct ScanResults<T: ScanValue> {
+   scanner  : MemoryScanner,
+   state    : ScanState<T>,
+}
+
+/// The addresses which matched a
+/// string scan, which can be narrowed
+/// down by rescanning.
+#[derive(Clone, Debug)]
+pub struct StringMatches {
+   bytes       : Vec<u8>,
+   addresses   : Vec<usize>,
+}
+
+/// The values remembered between scans.
+enum ScanState<T: ScanValue> {
+   Snapshot{
+      chunks   : Vec<ChunkSnapshot>,
+   },
+   Matches{
+      matches  : Vec<ScanMatch<T>>,
+   },
+}
+
+/// A copy of a chunk of memory taken by
+/// an unknown value scan.  Only values
+/// starting before the limit belong to
+/// the chunk, since the copy overlaps
+/// with the next one.
+struct ChunkSnapshot {
+   address  : usize,
+   limit    : usize,
+   bytes    : Vec<u8>,
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ScannerError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for ScannerError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::MemoryError{sys_error}
+            => write!(stream, "Memory error: {sys_error}"),
+         Self::EmptyString
+            => write!(stream, "Cannot scan for an empty string"),
+      };
+   }
+}
+
+impl std::error::Error for ScannerError {
+}
+
+impl From<crate::sys::memory::MemoryError> for ScannerError {
+   fn from(
+      value : crate::sys::memory::MemoryError,
+   ) -> Self {
+      return Self::MemoryError{
+         sys_error : value,
+      };
+   }
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ScanValue //
+///////////////////////////////////////
+
+unsafe impl ScanValue for u8  {}
+unsafe impl ScanValue for u16 {}
+unsafe impl ScanValue for u32 {}
+unsafe impl ScanValue for u64 {}
+unsafe impl ScanValue for i8  {}
+unsafe impl ScanValue for i16 {}
+unsafe impl ScanValue for i32 {}
+unsafe impl ScanValue for i64 {}
+unsafe impl ScanValue for f32 {}
+unsafe impl ScanValue for f64 {}
+
+///////////////////////////////////////
+// INTERNAL METHODS - ScanComparison //
+///////////////////////////////////////
+
+impl<T: ScanValue> ScanComparison<T> {
+   /// Whether a value should be kept
+   /// given its value from the previous
+   /// scan.  Changes are compared by bytes
+   /// so a NaN which stays the same isn't
+   /// counted as changed.
+   fn keeps(
+      & self,
+      previous : T,
+      current  : T,
+   ) -> bool {
+      return match self {
+         Self::Exact(value)
+            => current == *value,
+         Self::Between(low, high)
+            => *low <= current && current <= *high,
+         Self::Changed
+            => value_bytes(&previous) != value_bytes(&current),
+         Self::Unchanged
+            => value_bytes(&previous) == value_bytes(&current),
+         Self::Increased
+            => current > previous,
+         Self::Decreased
+            => current < previous,
+      };
+   }
+}
+
+/////////////////////////////
+// METHODS - MemoryScanner //
+/////////////////////////////
+
+impl MemoryScanner {
+   /// Creates settings for scanning
+   /// the whole address space.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         address_range  : 0..usize::MAX,
+         alignment      : None,
+         read_only      : false,
+      };
+   }
+
+   /// Limits scanning to an address
+   /// range, such as a single heap.
+   pub fn within(
+      mut self,
+      address_range : Range<usize>,
+   ) -> Self {
+      self.address_range = address_range;
+      return self;
+   }
+
+   /// Sets the distance in bytes between
+   /// the addresses which are checked.  By
+   /// default, this is the alignment of the
+   /// value type, or 1 for strings.  Packed
+   /// structs may need an alignment of 1.
+   pub fn alignment(
+      mut self,
+      alignment : usize,
+   ) -> Self {
+      self.alignment = Some(usize::max(alignment, 1));
+      return self;
+   }
+
+   /// Sets whether memory which can't be
+   /// written to is also searched, such as
+   /// constants in a module's data.
+   pub fn include_read_only(
+      mut self,
+      read_only : bool,
+   ) -> Self {
+      self.read_only = read_only;
+      return self;
+   }
+
+   /// Finds every address storing
+   /// exactly a value.
+   pub fn scan<T: ScanValue>(
+      & self,
+      value : T,
+   ) -> Result<ScanResults<T>> {
+      return self.scan_matching(ScanComparison::Exact(value));
+   }
+
+   /// Finds every address storing a value
+   /// between two values, inclusive.  This is
+   /// useful for floating-point values, which
+   /// are rarely exactly what's displayed.
+   pub fn scan_between<T: ScanValue>(
+      & self,
+      low   : T,
+      high  : T,
+   ) -> Result<ScanResults<T>> {
+      return self.scan_matching(ScanComparison::Between(low, high));
+   }
+
+   /// Remembers every value in memory
+   /// without filtering any out, to be
+   /// narrowed down by rescanning.  This
+   /// copies all scanned memory, so limiting
+   /// the scan with <code>within</code> can
+   /// save a lot of memory.
+   pub fn scan_unknown<T: ScanValue>(
+      & self,
+   ) -> Result<ScanResults<T>> {
+      let size = std::mem::size_of::<T>();
+
+      let mut chunks = Vec::new();
+      for span in self.spans()? {
+         for_each_chunk(&span, size - 1, |address, limit, bytes| {
+            chunks.push(ChunkSnapshot{
+               address  : address,
+               limit    : limit,
+               bytes    : bytes.to_vec(),
+            });
+         });
+      }
+
+      return Ok(ScanResults{
+         scanner  : self.clone(),
+         state    : ScanState::Snapshot{
+            chunks   : chunks,
+         },
+      });
+   }
+
+   /// Finds every address storing a
+   /// string in the given encoding.  The
+   /// string isn't expected to be followed
+   /// by a null terminator.
+   pub fn scan_string(
+      & self,
+      string   : & str,
+      encoding : crate::scan::StringEncoding,
+   ) -> Result<StringMatches> {
+      if string.is_empty() == true {
+         return Err(ScannerError::EmptyString);
+      }
+
+      let bytes = match encoding {
+         crate::scan::StringEncoding::Utf8
+            => string.as_bytes().to_vec(),
+         crate::scan::StringEncoding::Utf16
+            => string.encode_utf16().flat_map(|c| c.to_le_bytes()).collect(),
+      };
+      let stride = self.alignment.unwrap_or(1);
+
+      let mut addresses = Vec::new();
+      for span in self.spans()? {
+         for_each_chunk(&span, bytes.len() - 1, |address, limit, chunk| {
+            for position in positions(address, limit, chunk.len(), bytes.len(), stride) {
+               let offset = position - address;
+               if chunk[offset..offset + bytes.len()] == bytes[..] {
+                  addresses.push(position);
+               }
+            }
+         });
+      }
+
+      return Ok(StringMatches{
+         bytes       : bytes,
+         addresses   : addresses,
+      });
+   }
+}
+
+//////////////////////////////////////
+// INTERNAL METHODS - MemoryScanner //
+//////////////////////////////////////
+
+impl MemoryScanner {
+   /// Gets the distance between
+   /// addresses checked for a type.
+   fn stride<T: ScanValue>(
+      & self,
+   ) -> usize {
+      return self.alignment.unwrap_or(std::mem::align_of::<T>());
+   }
+
+   /// Finds every address range which
+   /// should be scanned.  Adjacent regions
+   /// are merged together.
+   fn spans(
+      & self,
+   ) -> Result<Vec<Range<usize>>> {
+      let mut spans : Vec<Range<usize>> = Vec::new();
+
+      let mut address = self.address_range.start;
+      while address < self.address_range.end {
+         // Querying fails past the highest
+         // address the process can map
+         let region = match crate::sys::memory::MemoryRegion::query(address) {
+            Ok(region)  => region,
+            Err(err)    => match address == self.address_range.start {
+               true  => return Err(err.into()),
+               false => break,
+            },
+         };
+         let region_range = region.address_range().clone();
+         if region_range.end <= address {
+            break;
+         }
+         address = region_range.end;
+
+         if region.is_committed()   == false
+         || region.is_readable()    == false
+         || (region.is_writable()   == false && self.read_only == false) {
+            continue;
+         }
+
+         // Clip the region to the scanned range
+         let start   = usize::max(region_range.start, self.address_range.start);
+         let end     = usize::min(region_range.end,   self.address_range.end);
+
+         // Merge with the previous span if they touch
+         if let Some(last) = spans.last_mut() {
+            if last.end == start {
+               last.end = end;
+               continue;
+            }
+         }
+
+         spans.push(start..end);
+      }
+
+      return Ok(spans);
+   }
+
+   /// Finds every aligned value in
+   /// memory which a comparison keeps.
+   fn scan_matching<T: ScanValue>(
+      & self,
+      comparison : ScanComparison<T>,
+   ) -> Result<ScanResults<T>> {
+      let size    = std::mem::size_of::<T>();
+      let stride  = self.stride::<T>();
+
+      let mut matches = Vec::new();
+      for span in self.spans()? {
+         for_each_chunk(&span, size - 1, |address, limit, bytes| {
+            for position in positions(address, limit, bytes.len(), size, stride) {
+               let value = read_value::<T>(&bytes[position - address..]);
+               if comparison.keeps(value, value) == true {
+                  matches.push(ScanMatch{
+                     address  : position,
+                     value    : value,
+                  });
+               }
+            }
+         });
+      }
+
+      return Ok(ScanResults{
+         scanner  : self.clone(),
+         state    : ScanState::Matches{
+            matches  : matches,
+         },
+      });
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - MemoryScanner //
+///////////////////////////////////////////
+
+impl Default for MemoryScanner {
+   fn default(
+   ) -> Self {
+      return Self::new();
+   }
+}
+
+///////////////////////////
+// METHODS - ScanResults //
+///////////////////////////
+
+impl<T: ScanValue> ScanResults<T> {
+   /// Gets the number of addresses which
+   /// are still candidates.  After an unknown
+   /// value scan, this is every address which
+   /// was remembered.
+   pub fn len(
+      & self,
+   ) -> usize {
+      let size    = std::mem::size_of::<T>();
+      let stride  = self.scanner.stride::<T>();
+
+      return match &self.state {
+         ScanState::Snapshot{chunks}
+            => chunks.iter().map(|chunk| {
+               positions(chunk.address, chunk.limit, chunk.bytes.len(), size, stride).count()
+            }).sum(),
+         ScanState::Matches{matches}
+            => matches.len(),
+      };
+   }
+
+   /// Checks whether no addresses
+   /// are left.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.len() == 0;
+   }
+
+   /// Gets the addresses which matched
+   /// the last scan along with the values
+   /// they had.  This is empty after an
+   /// unknown value scan until the results
+   /// are rescanned.
+   pub fn matches(
+      & self,
+   ) -> & [ScanMatch<T>] {
+      return match &self.state {
+         ScanState::Snapshot{..}
+            => &[],
+         ScanState::Matches{matches}
+            => matches,
+      };
+   }
+
+   /// Gets the addresses which
+   /// matched the last scan.
+   pub fn addresses(
+      & self,
+   ) -> Vec<usize> {
+      return self.matches().iter().map(|found| found.address).collect();
+   }
+
+   /// Consumes the results, returning
+   /// the matches from the last scan.
+   pub fn into_matches(
+      self,
+   ) -> Vec<ScanMatch<T>> {
+      return match self.state {
+         ScanState::Snapshot{..}
+            => Vec::new(),
+         ScanState::Matches{matches}
+            => matches,
+      };
+   }
+
+   /// Reads every remaining address again,
+   /// keeping only the ones the comparison
+   /// accepts, and returns how many are left.
+   /// Addresses which can no longer be read
+   /// are dropped.
+   pub fn rescan(
+      & mut self,
+      comparison : ScanComparison<T>,
+   ) -> usize {
+      let size    = std::mem::size_of::<T>();
+      let stride  = self.scanner.stride::<T>();
+
+      let matches = match std::mem::replace(
+         & mut self.state,
+         ScanState::Matches{matches : Vec::new()},
+      ) {
+         ScanState::Snapshot{chunks}
+            => rescan_snapshot(chunks, &comparison, size, stride),
+         ScanState::Matches{matches}
+            => rescan_matches(matches, &comparison, size),
+      };
+
+      let count = matches.len();
+      self.state = ScanState::Matches{
+         matches  : matches,
+      };
+      return count;
+   }
+}
+
+/////////////////////////////
+// METHODS - StringMatches //
+/////////////////////////////
+
+impl StringMatches {
+   /// Gets the number of addresses
+   /// which matched the last scan.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.addresses.len();
+   }
+
+   /// Checks whether no addresses
+   /// are left.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.addresses.is_empty();
+   }
+
+   /// Gets the addresses which
+   /// matched the last scan.
+   pub fn addresses(
+      & self,
+   ) -> & [usize] {
+      return &self.addresses;
+   }
+
+   /// Reads every remaining address again,
+   /// keeping only the ones which still store
+   /// the string, and returns how many are
+   /// left.
+   pub fn rescan(
+      & mut self,
+   ) -> usize {
+      let mut buffer = vec![0u8; self.bytes.len()];
+      let bytes      = &self.bytes;
+
+      self.addresses.retain(|address| {
+         crate::sys::memory::read_checked(*address, & mut buffer).is_ok() && buffer == *bytes
+      });
+
+      return self.addresses.len();
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Copies a span of memory a chunk at a
+/// time, passing each chunk's address, the
+/// address values must start before and the
+/// copied bytes to a callback.  Each copy
+/// extends past the limit by the overlap so
+/// values crossing chunks aren't missed.
+/// Chunks which can't be copied are skipped.
+fn for_each_chunk<F>(
+   span     : & Range<usize>,
+   overlap  : usize,
+   mut f    : F,
+)
+where F: FnMut(usize, usize, & [u8]),
+{
+   let mut buffer    = Vec::new();
+   let mut address   = span.start;
+   while address < span.end {
+      let limit   = usize::min(address.saturating_add(CHUNK_SIZE), span.end);
+      let end     = usize::min(limit.saturating_add(overlap), span.end);
+
+      buffer.resize(end - address, 0);
+      if crate::sys::memory::read_checked(address, & mut buffer).is_ok() {
+         f(address, limit, &buffer);
+      }
+
+      address = limit;
+   }
+
+   return;
+}
+
+/// Iterates over the aligned addresses
+/// in a copied chunk which start before
+/// the limit and fit within the copy.
+fn positions(
+   address  : usize,
+   limit    : usize,
+   length   : usize,
+   size     : usize,
+   stride   : usize,
+) -> impl Iterator<Item = usize> {
+   let end = address + length;
+   return (address.next_multiple_of(stride)..limit)
+      .step_by(stride)
+      .take_while(move |position| position + size <= end);
+}
+
+/// Reads a value from the start
+/// of a byte slice.
+fn read_value<T: ScanValue>(
+   bytes : & [u8],
+) -> T {
+   assert!(bytes.len() >= std::mem::size_of::<T>());
+   return unsafe{std::ptr::read_unaligned(bytes.as_ptr() as * const T)};
+}
+
+/// Gets the bytes storing a value.
+fn value_bytes<T: ScanValue>(
+   value : & T,
+) -> & [u8] {
+   return unsafe{std::slice::from_raw_parts(
+      value as * const T as * const u8,
+      std::mem::size_of::<T>(),
+   )};
+}
+
+/// Compares every value in a snapshot
+/// against its current value.
+fn rescan_snapshot<T: ScanValue>(
+   chunks      : Vec<ChunkSnapshot>,
+   comparison  : & ScanComparison<T>,
+   size        : usize,
+   stride      : usize,
+) -> Vec<ScanMatch<T>> {
+   let mut matches = Vec::new();
+   let mut current = Vec::new();
+   for chunk in chunks {
+      current.resize(chunk.bytes.len(), 0);
+      if crate::sys::memory::read_checked(chunk.address, & mut current).is_err() {
+         continue;
+      }
+
+      for position in positions(chunk.address, chunk.limit, chunk.bytes.len(), size, stride) {
+         let offset     = position - chunk.address;
+         let previous   = read_value::<T>(&chunk.bytes[offset..]);
+         let value      = read_value::<T>(&current[offset..]);
+         if comparison.keeps(previous, value) == true {
+            matches.push(ScanMatch{
+               address  : position,
+               value    : value,
+            });
+         }
+      }
+   }
+
+   return matches;
+}
+
+/// Compares every match against
+/// its current value.
+fn rescan_matches<T: ScanValue>(
+   matches     : Vec<ScanMatch<T>>,
+   comparison  : & ScanComparison<T>,
+   size        : usize,
+) -> Vec<ScanMatch<T>> {
+   let mut kept   = Vec::new();
+   let mut buffer = Vec::new();
+
+   // Matches close together are read in
+   // a single copy, falling back to one
+   // copy each if any of them are gone
+   let mut remaining = &matches[..];
+   while let Some(first) = remaining.first() {
+      let start = first.address;
+      let count = remaining.iter().take_while(|found| {
+         found.address - start + size <= CHUNK_SIZE
+      }).count();
+      let (group, rest) = remaining.split_at(count);
+      remaining = rest;
+
+      let end = group[group.len() - 1].address + size;
+      buffer.resize(end - start, 0);
+      let group_read = crate::sys::memory::read_checked(start, & mut buffer).is_ok();
+
+      for found in group {
+         let value = match group_read {
+            true  => read_value::<T>(&buffer[found.address - start..]),
+            false => {
+               let mut bytes = vec![0u8; size];
+               if crate::sys::memory::read_checked(found.address, & mut bytes).is_err() {
+                  continue;
+               }
+               read_value::<T>(&bytes)
+            },
+         };
+
+         if comparison.keeps(found.value, value) == true {
+            kept.push(ScanMatch{
+               address  : found.address,
+               value    : value,
+            });
+         }
+      }
+   }
+
+   return kept;
+}
@@ -0,0 +1,520 @@
+//! Offset databases which store the
+//! offsets and signatures a mod needs
+//! separately from the mod's logic.
+//!
+//! An offset database is a text file with
+//! a version line followed by a section for
+//! each module.  Each entry in a section is
+//! a plain offset, an offset range with an
+//! optional checksum, a byte signature which
+//! is searched for at runtime, or the name of
+//! a function the module exports.  Anything
+//! after a <code>#</code> is a comment.
+//!
+//! ```
+//! # Offsets for game version 1.38
+//! version = 1
+//!
+//! [game.exe]
+//! player_health  = 0x1A2B30
+//! main_loop      = 0x8241BC..0x8241CD checksum 0xF7946268
+//! damage_func    = signature 48 8B ?? ?? 89 46 ?0
+//!
+//! [d3d11.dll]
+//! create_device  = export D3D11CreateDevice
+//! ```
+
+use std::collections::hash_map::HashMap;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to an offset database.
+#[derive(Debug)]
+pub enum OffsetsError {
+   IoError{
+      err         : std::io::Error,
+   },
+   ScanError{
+      err         : crate::scan::ScanError,
+   },
+   ProcessError{
+      err         : crate::process::ProcessError,
+   },
+   MissingVersion,
+   UnsupportedVersion{
+      version     : u32,
+   },
+   InvalidLine{
+      line        : usize,
+   },
+   DuplicateEntry{
+      line        : usize,
+      name        : String,
+   },
+   ModuleNotFound{
+      module_name : String,
+   },
+   EntryNotFound{
+      name        : String,
+   },
+   WrongEntryKind{
+      name        : String,
+   },
+   MissingChecksum{
+      name        : String,
+   },
+   SignatureNotFound{
+      name        : String,
+   },
+   AmbiguousSignature{
+      name        : String,
+      count       : usize,
+   },
+   ExportNotFound{
+      name        : String,
+      function    : String,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>OffsetsError</code>.
+pub type Result<T> = std::result::Result<T, OffsetsError>;
+
+/// A parsed offset database containing
+/// the offsets for one or more modules.
+#[derive(Clone, Debug)]
+pub struct OffsetsDb {
+   version  : u32,
+   modules  : HashMap<String, ModuleOffsets>,
+}
+
+/// The named entries in an offset
+/// database for a single module.
+#[derive(Clone, Debug, Default)]
+pub struct ModuleOffsets {
+   entries  : HashMap<String, OffsetEntry>,
+}
+
+/// A single named entry in an
+/// offset database.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OffsetEntry {
+   Offset{
+      offset         : usize,
+   },
+   Range{
+      offset_range   : std::ops::Range<usize>,
+      checksum       : Option<crate::patch::Checksum>,
+   },
+   Signature{
+      pattern        : crate::scan::Pattern,
+   },
+   Export{
+      function       : String,
+   },
+}
+
+////////////////////////////////////
+// GLOBAL VARIABLES AND CONSTANTS //
+////////////////////////////////////
+
+/// The newest database format version
+/// which can be parsed.
+pub const OFFSETS_DB_VERSION : u32 = 1;
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - OffsetsError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for OffsetsError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+         Self::ScanError{err}
+            => write!(stream, "Scan error: {err}"),
+         Self::ProcessError{err}
+            => write!(stream, "Process error: {err}"),
+         Self::MissingVersion
+            => write!(stream, "Offset database has no version line"),
+         Self::UnsupportedVersion{version}
+            => write!(stream, "Unsupported offset database version {version}, newest supported is {OFFSETS_DB_VERSION}"),
+         Self::InvalidLine{line}
+            => write!(stream, "Invalid offset database entry on line {line}"),
+         Self::DuplicateEntry{line, name}
+            => write!(stream, "Duplicate entry \"{name}\" on line {line}"),
+         Self::ModuleNotFound{module_name}
+            => write!(stream, "No offsets for module \"{module_name}\""),
+         Self::EntryNotFound{name}
+            => write!(stream, "No offset entry named \"{name}\""),
+         Self::WrongEntryKind{name}
+            => write!(stream, "Offset entry \"{name}\" is the wrong kind"),
+         Self::MissingChecksum{name}
+            => write!(stream, "Offset entry \"{name}\" has no checksum"),
+         Self::SignatureNotFound{name}
+            => write!(stream, "Signature \"{name}\" was not found"),
+         Self::AmbiguousSignature{name, count}
+            => write!(stream, "Signature \"{name}\" matched {count} times"),
+         Self::ExportNotFound{name, function}
+            => write!(stream, "Export \"{function}\" for entry \"{name}\" was not found"),
+      };
+   }
+}
+
+impl std::error::Error for OffsetsError {
+}
+
+impl From<std::io::Error> for OffsetsError {
+   fn from(
+      value : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : value,
+      };
+   }
+}
+
+impl From<crate::scan::ScanError> for OffsetsError {
+   fn from(
+      value : crate::scan::ScanError,
+   ) -> Self {
+      return Self::ScanError{
+         err : value,
+      };
+   }
+}
+
+impl From<crate::process::ProcessError> for OffsetsError {
+   fn from(
+      value : crate::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : value,
+      };
+   }
+}
+
+/////////////////////////
+// METHODS - OffsetsDb //
+/////////////////////////
+
+impl OffsetsDb {
+   /// Parses an offset database from
+   /// its text form.  This is usually
+   /// used with <code>include_str!</code>
+   /// to embed a default database.
+   pub fn parse(
+      text : & str,
+   ) -> Result<Self> {
+      let mut version   = None;
+      let mut modules   = HashMap::new();
+      let mut current   : Option<String> = None;
+
+      for (index, line) in text.lines().enumerate() {
+         let line_number   = index + 1;
+         let line          = line.split('#').next().unwrap_or("").trim();
+         if line.is_empty() == true {
+            continue;
+         }
+
+         // Module section header
+         if let Some(module_name) = line.strip_prefix('[') {
+            let module_name = module_name.strip_suffix(']').ok_or(
+               OffsetsError::InvalidLine{line : line_number},
+            )?.trim();
+
+            modules.entry(String::from(module_name)).or_insert_with(ModuleOffsets::default);
+            current = Some(String::from(module_name));
+            continue;
+         }
+
+         let (name, value) = line.split_once('=').ok_or(
+            OffsetsError::InvalidLine{line : line_number},
+         )?;
+         let name    = name.trim();
+         let value   = value.trim();
+
+         // The version must come before any module
+         let module_name = match &current {
+            Some(module_name) => module_name,
+            None              => {
+               if name != "version" || version.is_some() == true {
+                  return Err(OffsetsError::InvalidLine{line : line_number});
+               }
+
+               let number = value.parse::<u32>().map_err(
+                  |_| OffsetsError::InvalidLine{line : line_number},
+               )?;
+               if number == 0 || number > OFFSETS_DB_VERSION {
+                  return Err(OffsetsError::UnsupportedVersion{version : number});
+               }
+
+               version = Some(number);
+               continue;
+            },
+         };
+
+         if version.is_none() == true {
+            return Err(OffsetsError::MissingVersion);
+         }
+
+         let entry = OffsetEntry::parse(value).ok_or(
+            OffsetsError::InvalidLine{line : line_number},
+         )?;
+
+         let module = modules.get_mut(module_name).expect(
+            "Module section should have been inserted, this is a bug!",
+         );
+         if module.entries.contains_key(name) == true {
+            return Err(OffsetsError::DuplicateEntry{
+               line : line_number,
+               name : String::from(name),
+            });
+         }
+         module.entries.insert(String::from(name), entry);
+      }
+
+      return Ok(Self{
+         version  : version.ok_or(OffsetsError::MissingVersion)?,
+         modules  : modules,
+      });
+   }
+
+   /// Loads and parses an offset
+   /// database from a file, allowing
+   /// offset packs to be swapped without
+   /// rebuilding the mod.
+   pub fn load<P>(
+      path : P,
+   ) -> Result<Self>
+   where P: AsRef<std::path::Path>,
+   {
+      let text = std::fs::read_to_string(path)?;
+      return Self::parse(&text);
+   }
+
+   /// Gets the format version the
+   /// database was written for.
+   pub fn version(
+      & self,
+   ) -> u32 {
+      return self.version;
+   }
+
+   /// Gets the offsets for a module
+   /// by its executable file name.
+   pub fn module<'l>(
+      &'l self,
+      module_name : & str,
+   ) -> Result<&'l ModuleOffsets> {
+      return self.modules.get(module_name).ok_or_else(|| OffsetsError::ModuleNotFound{
+         module_name : String::from(module_name),
+      });
+   }
+
+   /// Iterates over the names of every
+   /// module in the database.
+   pub fn module_names<'l>(
+      &'l self,
+   ) -> impl Iterator<Item = &'l str> {
+      return self.modules.keys().map(|name| name.as_str());
+   }
+}
+
+/////////////////////////////
+// METHODS - ModuleOffsets //
+/////////////////////////////
+
+impl ModuleOffsets {
+   /// Gets an entry by name.
+   pub fn get<'l>(
+      &'l self,
+      name : & str,
+   ) -> Result<&'l OffsetEntry> {
+      return self.entries.get(name).ok_or_else(|| OffsetsError::EntryNotFound{
+         name : String::from(name),
+      });
+   }
+
+   /// Gets the offset of an entry.  For
+   /// ranges this is the start of the range.
+   /// Signatures and exports have to be
+   /// looked up with <code>resolve</code>
+   /// instead.
+   pub fn offset(
+      & self,
+      name : & str,
+   ) -> Result<usize> {
+      return match self.get(name)? {
+         OffsetEntry::Offset{offset}
+            => Ok(*offset),
+         OffsetEntry::Range{offset_range, ..}
+            => Ok(offset_range.start),
+         OffsetEntry::Signature{..} | OffsetEntry::Export{..}
+            => Err(OffsetsError::WrongEntryKind{name : String::from(name)}),
+      };
+   }
+
+   /// Gets the offset range of a
+   /// range entry.
+   pub fn offset_range(
+      & self,
+      name : & str,
+   ) -> Result<std::ops::Range<usize>> {
+      return match self.get(name)? {
+         OffsetEntry::Range{offset_range, ..}
+            => Ok(offset_range.clone()),
+         _  => Err(OffsetsError::WrongEntryKind{name : String::from(name)}),
+      };
+   }
+
+   /// Gets the checksum of a range
+   /// entry.
+   pub fn checksum(
+      & self,
+      name : & str,
+   ) -> Result<crate::patch::Checksum> {
+      return match self.get(name)? {
+         OffsetEntry::Range{checksum : Some(checksum), ..}
+            => Ok(checksum.clone()),
+         OffsetEntry::Range{checksum : None, ..}
+            => Err(OffsetsError::MissingChecksum{name : String::from(name)}),
+         _  => Err(OffsetsError::WrongEntryKind{name : String::from(name)}),
+      };
+   }
+
+   /// Gets the offset of an entry within
+   /// a module, searching the module for
+   /// signatures and looking up exports.
+   /// A signature must match exactly once.
+   ///
+   /// <h2 id=  module_offsets_resolve_safety>
+   /// <a href=#module_offsets_resolve_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from the
+   /// functions in <code>scan</code> and
+   /// <code>ModuleSnapshot::export_offset</code>
+   /// apply.
+   pub unsafe fn resolve(
+      & self,
+      name     : & str,
+      module   : & crate::process::ModuleSnapshot,
+   ) -> Result<usize> {
+      let pattern = match self.get(name)? {
+         OffsetEntry::Signature{pattern}  => pattern,
+         OffsetEntry::Export{function}    => {
+            return module.export_offset(function)?.ok_or_else(|| OffsetsError::ExportNotFound{
+               name     : String::from(name),
+               function : function.clone(),
+            });
+         },
+         _                                => return self.offset(name),
+      };
+
+      let found = module.find_pattern_iter(pattern)?.collect::<Vec<usize>>();
+
+      return match found.len() {
+         0  => Err(OffsetsError::SignatureNotFound{name : String::from(name)}),
+         1  => Ok(found[0]),
+         _  => Err(OffsetsError::AmbiguousSignature{
+            name  : String::from(name),
+            count : found.len(),
+         }),
+      };
+   }
+
+   /// Iterates over every entry and
+   /// its name.
+   pub fn iter<'l>(
+      &'l self,
+   ) -> impl Iterator<Item = (&'l str, &'l OffsetEntry)> {
+      return self.entries.iter().map(|(name, entry)| (name.as_str(), entry));
+   }
+}
+
+////////////////////////////////////
+// INTERNAL METHODS - OffsetEntry //
+////////////////////////////////////
+
+impl OffsetEntry {
+   fn parse(
+      value : & str,
+   ) -> Option<Self> {
+      if let Some(pattern) = value.strip_prefix("signature") {
+         return Some(Self::Signature{
+            pattern : pattern.parse().ok()?,
+         });
+      }
+
+      if let Some(function) = value.strip_prefix("export") {
+         let function = function.trim();
+         if function.is_empty() == true || function.contains(char::is_whitespace) == true {
+            return None;
+         }
+
+         return Some(Self::Export{
+            function : String::from(function),
+         });
+      }
+
+      let mut words  = value.split_whitespace();
+      let location   = words.next()?;
+
+      let entry = match location.split_once("..") {
+         None                 => Self::Offset{
+            offset : parse_number(location)?,
+         },
+         Some((start, end))   => {
+            let start   = parse_number(start)?;
+            let end     = parse_number(end)?;
+            if start >= end {
+               return None;
+            }
+
+            let checksum = match words.next() {
+               None              => None,
+               Some("checksum")  => Some(crate::patch::Checksum::from(
+                  u32::try_from(parse_number(words.next()?)?).ok()?,
+               )),
+               Some(_)           => return None,
+            };
+
+            Self::Range{
+               offset_range   : start..end,
+               checksum       : checksum,
+            }
+         },
+      };
+
+      if words.next().is_some() == true {
+         return None;
+      }
+
+      return Some(entry);
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Parses a decimal number or a
+/// hexadecimal number starting
+/// with <code>0x</code>.
+fn parse_number(
+   text : & str,
+) -> Option<usize> {
+   let text = text.trim();
+
+   return match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+      Some(hex)   => usize::from_str_radix(hex, 16).ok(),
+      None        => text.parse().ok(),
+   };
+}
@@ -0,0 +1,804 @@
+//! Draw text, rectangles, and menus on
+//! top of the game's frames.
+//!
+//! The overlay hooks the game's swap chain
+//! and calls a drawing function right before
+//! every frame is presented.  Drawing is
+//! immediate-mode: everything is drawn again
+//! each frame from the mod's current state.
+//!
+//! ```
+//! let mut menu = nusion_core::overlay::Menu::new("My Mod");
+//! menu.add_item("God mode: off");
+//! menu.add_item("Infinite ammo: off");
+//!
+//! let mut input = nusion_core::input::InputState::new();
+//!
+//! let overlay = nusion_core::overlay::Overlay::install(move |draw| {
+//!    input.poll();
+//!    if input.was_pressed(nusion_core::input::Key::Insert) == true {
+//!       menu.toggle();
+//!    }
+//!    if let Some(index) = menu.handle_input(&input) {
+//!       println!("Selected item {index}");
+//!    }
+//!
+//!    draw.text(8.0, 8.0, "My Mod loaded", nusion_core::overlay::Color::WHITE);
+//!    menu.draw(draw, 8.0, 32.0);
+//! })?;
+//!
+//! // The hooks are removed when the overlay is dropped
+//! drop(overlay);
+//! ```
+//!
+//! This module is only available with the
+//! <code>overlay</code> feature, and only games
+//! rendering with Direct3D 11 or 12 on Windows
+//! are supported.  For Direct3D 12, the game's
+//! command queue is found by hooking it as
+//! well, so drawing starts once the game has
+//! submitted work to it.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// <code>DXGI_PRESENT_TEST</code>, which
+/// checks whether the swap chain can present
+/// without presenting anything.
+const PRESENT_FLAG_TEST : u32
+   = 0x00000001;
+
+/// Padding around each side of a menu's
+/// contents, in pixels.
+const MENU_PADDING : f32
+   = 6.0;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to the overlay.
+#[derive(Debug)]
+pub enum OverlayError {
+   RenderError{
+      sys_error   : crate::sys::overlay::OverlayError,
+   },
+   PatchError{
+      err         : crate::patch::PatchError,
+   },
+   AlreadyInstalled,
+}
+
+/// <code>Result</code> type with error
+/// variant <code>OverlayError</code>.
+pub type Result<T> = std::result::Result<T, OverlayError>;
+
+/// An 8-bit RGBA color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+   pub r : u8,
+   pub g : u8,
+   pub b : u8,
+   pub a : u8,
+}
+
+/// The shapes drawn during one frame.
+/// Positions are in pixels from the top
+/// left of the screen.
+pub struct DrawList {
+   vertices    : Vec<crate::sys::overlay::Vertex>,
+   screen_size : (f32, f32),
+   font        : crate::sys::overlay::FontMetrics,
+}
+
+/// A vertical list of selectable items
+/// which is navigated with the arrow keys
+/// and activated with enter.  The menu
+/// starts hidden.
+#[derive(Clone, Debug)]
+pub struct Menu {
+   title    : String,
+   items    : Vec<String>,
+   selected : usize,
+   visible  : bool,
+}
+
+/// A handle to the installed overlay.
+/// Only one overlay may be installed at
+/// a time, and dropping the handle removes
+/// the swap chain hooks.
+pub struct Overlay {
+   present_hook               : Option<crate::patch::VTableHookContainer>,
+   resize_buffers_hook        : Option<crate::patch::VTableHookContainer>,
+   execute_command_lists_hook : Option<crate::patch::VTableHookContainer>,
+}
+
+/// The drawing function and renderer
+/// used from within the present hook.
+struct OverlayState {
+   draw     : Box<dyn FnMut(& mut DrawList) + Send>,
+   renderer : Option<crate::sys::overlay::Renderer>,
+   failed   : bool,
+}
+
+/// <code>IDXGISwapChain::Present</code>
+type PresentFn = unsafe extern "system" fn(
+   usize,
+   u32,
+   u32,
+) -> i32;
+
+/// <code>IDXGISwapChain::ResizeBuffers</code>
+type ResizeBuffersFn = unsafe extern "system" fn(
+   usize,
+   u32,
+   u32,
+   u32,
+   u32,
+   u32,
+) -> i32;
+
+/// <code>ID3D12CommandQueue::ExecuteCommandLists</code>
+type ExecuteCommandListsFn = unsafe extern "system" fn(
+   usize,
+   u32,
+   * const usize,
+);
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static PRESENT_ORIGINAL                : AtomicUsize = AtomicUsize::new(0);
+static RESIZE_BUFFERS_ORIGINAL         : AtomicUsize = AtomicUsize::new(0);
+static EXECUTE_COMMAND_LISTS_ORIGINAL  : AtomicUsize = AtomicUsize::new(0);
+
+// The Direct3D 12 command queue the game
+// renders with, or zero until it's found
+static COMMAND_QUEUE                   : AtomicUsize = AtomicUsize::new(0);
+
+lazy_static::lazy_static!{
+static ref OVERLAY_STATE
+   : std::sync::Mutex<Option<OverlayState>>
+   = std::sync::Mutex::new(None);
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - OverlayError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for OverlayError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::RenderError{sys_error}
+            => write!(stream, "Render error: {sys_error}"),
+         Self::PatchError{err}
+            => write!(stream, "Patch error: {err}"),
+         Self::AlreadyInstalled
+            => write!(stream, "An overlay is already installed"),
+      };
+   }
+}
+
+impl std::error::Error for OverlayError {
+}
+
+impl From<crate::sys::overlay::OverlayError> for OverlayError {
+   fn from(
+      value : crate::sys::overlay::OverlayError,
+   ) -> Self {
+      return Self::RenderError{
+         sys_error : value,
+      };
+   }
+}
+
+impl From<crate::patch::PatchError> for OverlayError {
+   fn from(
+      value : crate::patch::PatchError,
+   ) -> Self {
+      return Self::PatchError{
+         err : value,
+      };
+   }
+}
+
+/////////////////////
+// METHODS - Color //
+/////////////////////
+
+impl Color {
+   pub const TRANSPARENT   : Self = Self::rgba(0,   0,   0,   0);
+   pub const BLACK         : Self = Self::rgb(0,    0,   0);
+   pub const WHITE         : Self = Self::rgb(255,  255, 255);
+   pub const GRAY          : Self = Self::rgb(128,  128, 128);
+   pub const RED           : Self = Self::rgb(255,  0,   0);
+   pub const GREEN         : Self = Self::rgb(0,    255, 0);
+   pub const BLUE          : Self = Self::rgb(0,    0,   255);
+   pub const YELLOW        : Self = Self::rgb(255,  255, 0);
+
+   /// Creates an opaque color.
+   pub const fn rgb(
+      r : u8,
+      g : u8,
+      b : u8,
+   ) -> Self {
+      return Self::rgba(r, g, b, u8::MAX);
+   }
+
+   /// Creates a color with transparency,
+   /// where an alpha of zero is invisible.
+   pub const fn rgba(
+      r : u8,
+      g : u8,
+      b : u8,
+      a : u8,
+   ) -> Self {
+      return Self{
+         r : r,
+         g : g,
+         b : b,
+         a : a,
+      };
+   }
+
+   /// Copies the color with a
+   /// different alpha.
+   pub const fn with_alpha(
+      self,
+      a : u8,
+   ) -> Self {
+      return Self::rgba(self.r, self.g, self.b, a);
+   }
+}
+
+////////////////////////
+// METHODS - DrawList //
+////////////////////////
+
+impl DrawList {
+   /// Gets the size of the screen
+   /// in pixels.
+   pub fn screen_size(
+      & self,
+   ) -> (f32, f32) {
+      return self.screen_size;
+   }
+
+   /// Gets the height of a line of
+   /// text in pixels.
+   pub fn line_height(
+      & self,
+   ) -> f32 {
+      return self.font.glyph_height;
+   }
+
+   /// Draws a filled rectangle.
+   pub fn rect(
+      & mut self,
+      x        : f32,
+      y        : f32,
+      width    : f32,
+      height   : f32,
+      color    : Color,
+   ) -> & mut Self {
+      let uv = self.font.solid_uv();
+      self.quad([x, y, x + width, y + height], [uv[0], uv[1], uv[0], uv[1]], color);
+      return self;
+   }
+
+   /// Draws the outline of a rectangle,
+   /// with the border inside the bounds.
+   pub fn rect_outline(
+      & mut self,
+      x           : f32,
+      y           : f32,
+      width       : f32,
+      height      : f32,
+      thickness   : f32,
+      color       : Color,
+   ) -> & mut Self {
+      let thickness = thickness.min(width / 2.0).min(height / 2.0);
+      let inner     = height - thickness * 2.0;
+
+      self.rect(x,                     y,                      width,      thickness, color);
+      self.rect(x,                     y + height - thickness, width,      thickness, color);
+      self.rect(x,                     y + thickness,          thickness,  inner,     color);
+      self.rect(x + width - thickness, y + thickness,          thickness,  inner,     color);
+      return self;
+   }
+
+   /// Draws text with its top left corner
+   /// at a position.  Newlines start a new
+   /// line, and characters which aren't
+   /// printable ASCII are drawn as
+   /// <code>?</code>.
+   pub fn text(
+      & mut self,
+      x        : f32,
+      y        : f32,
+      text     : & str,
+      color    : Color,
+   ) -> & mut Self {
+      let (glyph_width, glyph_height) = (self.font.glyph_width, self.font.glyph_height);
+
+      for (row, line) in text.lines().enumerate() {
+         let top = y + row as f32 * glyph_height;
+
+         for (column, character) in line.chars().enumerate() {
+            if character == ' ' {
+               continue;
+            }
+
+            let left = x + column as f32 * glyph_width;
+            let uv   = self.font.glyph_uv(character);
+            self.quad([left, top, left + glyph_width, top + glyph_height], uv, color);
+         }
+      }
+
+      return self;
+   }
+
+   /// Measures the width and height of
+   /// text as drawn by <code>text</code>.
+   pub fn text_size(
+      & self,
+      text : & str,
+   ) -> (f32, f32) {
+      let columns = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+      let rows    = text.lines().count();
+
+      return (
+         columns as f32 * self.font.glyph_width,
+         rows    as f32 * self.font.glyph_height,
+      );
+   }
+}
+
+/////////////////////////////////
+// INTERNAL METHODS - DrawList //
+/////////////////////////////////
+
+impl DrawList {
+   /// Adds two triangles covering a
+   /// rectangle given as its top left
+   /// and bottom right corners.
+   fn quad(
+      & mut self,
+      bounds   : [f32; 4],
+      uv       : [f32; 4],
+      color    : Color,
+   ) {
+      let color = [color.r, color.g, color.b, color.a];
+      let vertex = |x : usize, y : usize| crate::sys::overlay::Vertex{
+         position : [bounds[x], bounds[y]],
+         uv       : [uv[x],     uv[y]],
+         color    : color,
+      };
+
+      self.vertices.extend_from_slice(&[
+         vertex(0, 1), vertex(2, 1), vertex(0, 3),
+         vertex(2, 1), vertex(2, 3), vertex(0, 3),
+      ]);
+      return;
+   }
+}
+
+////////////////////
+// METHODS - Menu //
+////////////////////
+
+impl Menu {
+   /// Creates an empty, hidden menu.
+   pub fn new(
+      title : & str,
+   ) -> Self {
+      return Self{
+         title    : String::from(title),
+         items    : Vec::new(),
+         selected : 0,
+         visible  : false,
+      };
+   }
+
+   /// Adds an item to the end of the
+   /// menu, returning its index.
+   pub fn add_item(
+      & mut self,
+      label : & str,
+   ) -> usize {
+      self.items.push(String::from(label));
+      return self.items.len() - 1;
+   }
+
+   /// Changes the label of an item, such
+   /// as to show whether a toggle is on.
+   /// Indices past the end are ignored.
+   pub fn set_label(
+      & mut self,
+      index : usize,
+      label : & str,
+   ) -> & mut Self {
+      if let Some(item) = self.items.get_mut(index) {
+         *item = String::from(label);
+      }
+
+      return self;
+   }
+
+   /// Gets the index of the highlighted
+   /// item.
+   pub fn selected(
+      & self,
+   ) -> usize {
+      return self.selected;
+   }
+
+   /// Checks whether the menu is shown.
+   pub fn is_visible(
+      & self,
+   ) -> bool {
+      return self.visible;
+   }
+
+   /// Shows or hides the menu.
+   pub fn set_visible(
+      & mut self,
+      visible : bool,
+   ) -> & mut Self {
+      self.visible = visible;
+      return self;
+   }
+
+   /// Shows the menu if it's hidden
+   /// and hides it if it's shown.
+   pub fn toggle(
+      & mut self,
+   ) -> & mut Self {
+      self.visible = self.visible == false;
+      return self;
+   }
+
+   /// Moves the highlight with the up and
+   /// down arrow keys, wrapping around at
+   /// either end.  If enter was pressed,
+   /// the index of the highlighted item is
+   /// returned.  Hidden menus ignore input.
+   pub fn handle_input(
+      & mut self,
+      input : & crate::input::InputState,
+   ) -> Option<usize> {
+      use crate::input::Key;
+
+      if self.visible == false || self.items.is_empty() == true {
+         return None;
+      }
+
+      if input.was_pressed(Key::Up) == true {
+         self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len() - 1);
+      }
+      if input.was_pressed(Key::Down) == true {
+         self.selected = (self.selected + 1) % self.items.len();
+      }
+
+      return match input.was_pressed(Key::Enter) {
+         true  => Some(self.selected),
+         false => None,
+      };
+   }
+
+   /// Draws the menu with its top left
+   /// corner at a position.  Hidden menus
+   /// aren't drawn.
+   pub fn draw(
+      & self,
+      draw_list   : & mut DrawList,
+      x           : f32,
+      y           : f32,
+   ) {
+      if self.visible == false {
+         return;
+      }
+
+      let line_height = draw_list.line_height();
+      let width = std::iter::once(&self.title)
+         .chain(self.items.iter())
+         .map(|label| draw_list.text_size(label).0)
+         .fold(0.0, f32::max) + MENU_PADDING * 2.0;
+      let height = (self.items.len() + 1) as f32 * line_height + MENU_PADDING * 2.0;
+
+      draw_list
+         .rect(x, y, width, height, Color::BLACK.with_alpha(192))
+         .rect_outline(x, y, width, height, 1.0, Color::GRAY)
+         .text(x + MENU_PADDING, y + MENU_PADDING, &self.title, Color::YELLOW);
+
+      for (index, label) in self.items.iter().enumerate() {
+         let top = y + MENU_PADDING + (index + 1) as f32 * line_height;
+
+         let color = match index == self.selected {
+            true  => {
+               draw_list.rect(x + 1.0, top, width - 2.0, line_height, Color::GRAY.with_alpha(128));
+               Color::WHITE
+            },
+            false => Color::GRAY,
+         };
+         draw_list.text(x + MENU_PADDING, top, label, color);
+      }
+
+      return;
+   }
+}
+
+///////////////////////
+// METHODS - Overlay //
+///////////////////////
+
+impl Overlay {
+   /// Hooks the game's swap chain so the
+   /// drawing function is called right before
+   /// every frame is presented.  The function
+   /// runs on the game's render thread, so it
+   /// should return quickly.  If it panics,
+   /// the frame is presented without the
+   /// overlay.
+   ///
+   /// The renderer is created from the game's
+   /// device on the first frame.  Games using
+   /// Direct3D 12 are drawn to once the command
+   /// queue they present with is found.  If the
+   /// game renders with neither Direct3D 11 nor
+   /// 12, an error is logged and nothing is
+   /// drawn.
+   pub fn install<F>(
+      draw : F,
+   ) -> Result<Self>
+   where F: FnMut(& mut DrawList) + Send + 'static,
+   {
+      let mut state = OVERLAY_STATE.lock().unwrap_or_else(|poison| poison.into_inner());
+      if state.is_some() == true {
+         return Err(OverlayError::AlreadyInstalled);
+      }
+
+      // The hook writer reads the table from
+      // an object, so the table address is
+      // stored where an object would keep it
+      let vtable = crate::sys::overlay::swap_chain_vtable()?;
+      let object = &vtable as * const usize as usize;
+
+      let mut overlay = Self{
+         present_hook               : None,
+         resize_buffers_hook        : None,
+         execute_command_lists_hook : None,
+      };
+
+      // The state is set before the hooks so
+      // a frame presented between them finds it
+      *state = Some(OverlayState{
+         draw     : Box::new(draw),
+         renderer : None,
+         failed   : false,
+      });
+      drop(state);
+
+      let hooks = unsafe{Self::hook(&mut overlay, object)};
+      if let Err(err) = hooks {
+         drop(overlay);
+         return Err(err);
+      }
+
+      return Ok(overlay);
+   }
+}
+
+////////////////////////////////
+// INTERNAL METHODS - Overlay //
+////////////////////////////////
+
+impl Overlay {
+   /// Hooks both swap chain methods and,
+   /// if Direct3D 12 is available, the
+   /// command queue's submission method,
+   /// storing the containers in the overlay
+   /// as they're applied.
+   unsafe fn hook(
+      overlay  : & mut Self,
+      object   : usize,
+   ) -> Result<()> {
+      // Without Direct3D 12 the game can't be
+      // using it, so only its hook is skipped
+      if let Ok(vtable) = crate::sys::overlay::command_queue_vtable() {
+         let queue_object = &vtable as * const usize as usize;
+         let execute_command_lists_hook = crate::patch::writer::VTableHook{
+            object_address : queue_object,
+            method_index   : crate::sys::overlay::EXECUTE_COMMAND_LISTS_METHOD_INDEX,
+            replacement    : execute_command_lists_hook as ExecuteCommandListsFn as usize,
+         };
+
+         EXECUTE_COMMAND_LISTS_ORIGINAL.store(
+            std::ptr::read_unaligned(execute_command_lists_hook.slot_address()? as * const usize),
+            Ordering::SeqCst,
+         );
+         overlay.execute_command_lists_hook = Some(execute_command_lists_hook.apply()?);
+      }
+
+      let present_hook = crate::patch::writer::VTableHook{
+         object_address : object,
+         method_index   : crate::sys::overlay::PRESENT_METHOD_INDEX,
+         replacement    : present_hook as PresentFn as usize,
+      };
+      let resize_buffers_hook = crate::patch::writer::VTableHook{
+         object_address : object,
+         method_index   : crate::sys::overlay::RESIZE_BUFFERS_METHOD_INDEX,
+         replacement    : resize_buffers_hook as ResizeBuffersFn as usize,
+      };
+
+      // The originals must be known before
+      // the replacements can be called
+      PRESENT_ORIGINAL.store(
+         std::ptr::read_unaligned(present_hook.slot_address()? as * const usize),
+         Ordering::SeqCst,
+      );
+      RESIZE_BUFFERS_ORIGINAL.store(
+         std::ptr::read_unaligned(resize_buffers_hook.slot_address()? as * const usize),
+         Ordering::SeqCst,
+      );
+
+      overlay.resize_buffers_hook   = Some(resize_buffers_hook.apply()?);
+      overlay.present_hook          = Some(present_hook.apply()?);
+      return Ok(());
+   }
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Overlay //
+/////////////////////////////////////
+
+impl std::ops::Drop for Overlay {
+   fn drop(
+      & mut self,
+   ) {
+      // The originals are kept so a frame
+      // already inside a hook can finish
+      self.present_hook                = None;
+      self.resize_buffers_hook         = None;
+      self.execute_command_lists_hook  = None;
+
+      *OVERLAY_STATE.lock().unwrap_or_else(|poison| poison.into_inner()) = None;
+      COMMAND_QUEUE.store(0, Ordering::SeqCst);
+      return;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+unsafe extern "system" fn present_hook(
+   swap_chain     : usize,
+   sync_interval  : u32,
+   flags          : u32,
+) -> i32 {
+   if flags & PRESENT_FLAG_TEST == 0 {
+      draw_frame(swap_chain);
+   }
+
+   let original : PresentFn = std::mem::transmute(PRESENT_ORIGINAL.load(Ordering::SeqCst));
+   return original(swap_chain, sync_interval, flags);
+}
+
+unsafe extern "system" fn resize_buffers_hook(
+   swap_chain     : usize,
+   buffer_count   : u32,
+   width          : u32,
+   height         : u32,
+   format         : u32,
+   flags          : u32,
+) -> i32 {
+   // Resizing fails while anything still
+   // references the old back buffer.  The
+   // lock is only held elsewhere briefly while
+   // installing or removing the overlay, so it
+   // isn't waited on from the render thread
+   if let Ok(mut state) = OVERLAY_STATE.try_lock() {
+      if let Some(renderer) = state.as_mut().and_then(|state| state.renderer.as_mut()) {
+         renderer.release_targets();
+      }
+   }
+
+   let original : ResizeBuffersFn = std::mem::transmute(RESIZE_BUFFERS_ORIGINAL.load(Ordering::SeqCst));
+   return original(swap_chain, buffer_count, width, height, format, flags);
+}
+
+unsafe extern "system" fn execute_command_lists_hook(
+   command_queue  : usize,
+   count          : u32,
+   command_lists  : * const usize,
+) {
+   // Copy and compute queues can't present,
+   // so only a direct queue is remembered
+   if COMMAND_QUEUE.load(Ordering::SeqCst) == 0
+      && crate::sys::overlay::is_direct_command_queue(command_queue) == true
+   {
+      COMMAND_QUEUE.store(command_queue, Ordering::SeqCst);
+   }
+
+   let original : ExecuteCommandListsFn = std::mem::transmute(EXECUTE_COMMAND_LISTS_ORIGINAL.load(Ordering::SeqCst));
+   return original(command_queue, count, command_lists);
+}
+
+/// Builds and renders one frame of the
+/// overlay.  Any failure disables the
+/// overlay so it isn't reported again
+/// every frame.
+unsafe fn draw_frame(
+   swap_chain : usize,
+) {
+   // A frame is skipped rather than
+   // stalling the render thread
+   let mut state = match OVERLAY_STATE.try_lock() {
+      Ok(state)   => state,
+      Err(_)      => return,
+   };
+   let state = match state.as_mut() {
+      Some(state) => state,
+      None        => return,
+   };
+   if state.failed == true {
+      return;
+   }
+
+   if state.renderer.is_none() == true {
+      let command_queue = match COMMAND_QUEUE.load(Ordering::SeqCst) {
+         0              => None,
+         command_queue  => Some(command_queue),
+      };
+
+      match crate::sys::overlay::Renderer::new(swap_chain, command_queue) {
+         Ok(renderer)   => state.renderer = Some(renderer),
+         Err(crate::sys::overlay::OverlayError::CommandQueueNotFound) => {
+            // Another queue may be the right one,
+            // so the next one submitted to is tried
+            COMMAND_QUEUE.store(0, Ordering::SeqCst);
+            return;
+         },
+         Err(err)       => {
+            crate::log::error!("Failed to create the overlay renderer: {err}");
+            state.failed = true;
+            return;
+         },
+      }
+   }
+   let renderer = match state.renderer.as_mut() {
+      Some(renderer) => renderer,
+      None           => return,
+   };
+
+   let screen_size = match renderer.screen_size(swap_chain) {
+      Ok(screen_size)   => screen_size,
+      Err(err)          => {
+         crate::log::warn!("Skipped overlay frame: {err}");
+         return;
+      },
+   };
+
+   let mut draw_list = DrawList{
+      vertices    : Vec::new(),
+      screen_size : screen_size,
+      font        : renderer.font_metrics(),
+   };
+
+   let draw = & mut state.draw;
+   if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| draw(&mut draw_list))).is_err() == true {
+      return;
+   }
+
+   if let Err(err) = renderer.render(swap_chain, &draw_list.vertices) {
+      crate::log::error!("Failed to render the overlay: {err}");
+      state.renderer = None;
+      state.failed   = true;
+   }
+
+   return;
+}
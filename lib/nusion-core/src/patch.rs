@@ -1,8 +1,32 @@
 //! Memory patching traits and
 //! implementations.
+//!
+//! Patches are applied from whichever thread
+//! asks for them, which is usually a game
+//! thread running a hook, so nothing in here
+//! may panic.  The lints below keep indexing
+//! and unwrapping out of this module.
+
+#![deny(
+   clippy::indexing_slicing,
+   clippy::panic,
+   clippy::unwrap_used,
+   clippy::expect_used,
+)]
 
 use std::ops::RangeBounds;
 
+pub use crate::base::alignment::{
+   Alignment,
+   TailFill,
+};
+pub use crate::base::checksum::{
+   Checksum,
+   ChecksumAlgorithm,
+   ChecksumValue,
+};
+pub use crate::base::offset::Offset;
+
 //////////////////////
 // TYPE DEFINITIONS //
 //////////////////////
@@ -27,6 +51,12 @@ pub enum PatchError {
    CompilationError{
       sys_error   : crate::sys::compiler::CompilationError,
    },
+   ProcessError{
+      sys_error   : crate::sys::process::ProcessError,
+   },
+   ThreadError{
+      sys_error   : crate::sys::thread::ThreadError,
+   },
    ChecksumMismatch{
       found       : Checksum,
       expected    : Checksum,
@@ -37,39 +67,397 @@ pub enum PatchError {
    },
    EndOffsetBeforeStartOffset,
    ZeroLengthType,
+   NoHookSite,
+   NoWriters,
+   ModuleNotFound{
+      module_name : String,
+   },
+   UnmappedWithin{
+      offset      : usize,
+   },
+   OverlappingRanges{
+      first       : std::ops::Range<usize>,
+      second      : std::ops::Range<usize>,
+   },
+   ThreadInRange{
+      address     : usize,
+   },
+   InvalidPointer{
+      step        : usize,
+      address     : usize,
+   },
+   Misaligned{
+      address     : usize,
+      alignment   : usize,
+   },
+   UnknownDiscriminant{
+      found       : i128,
+      allowed     : Vec<i128>,
+   },
+   OutsideSections{
+      offset      : usize,
+   },
+   OutsideAddressRange{
+      address_range  : std::ops::Range<usize>,
+      provided       : std::ops::Range<usize>,
+   },
+   MemoryFault{
+      fault       : crate::sys::exception::FaultInfo,
+   },
+   ExceptionError{
+      sys_error   : crate::sys::exception::ExceptionError,
+   },
+   AsmError{
+      err         : crate::asm::AsmError,
+   },
+   TaskError{
+      err         : crate::task::TaskError,
+   },
+   IatError{
+      err         : crate::iat::IatError,
+   },
+   DebugError{
+      err         : crate::debug::DebugError,
+   },
+   SplitsInstruction{
+      offset         : usize,
+      length         : usize,
+      range_length   : usize,
+   },
+   UndecodableInstruction{
+      offset         : usize,
+   },
+   Cancelled{
+      bytes_written  : usize,
+   },
+   ChunkMismatch{
+      offset         : usize,
+   },
+   HookChainMismatch{
+      length         : usize,
+      jmp_back       : bool,
+   },
+   NotExecutable{
+      offset         : usize,
+   },
+   StringTooLong{
+      length         : usize,
+      capacity       : usize,
+   },
+   InteriorNul{
+      position       : usize,
+   },
+   BitFieldTooWide{
+      length         : usize,
+   },
+   BitMaskOutOfRange{
+      bit_mask       : u64,
+      length         : usize,
+   },
+   BitValueOutOfRange{
+      value          : u64,
+      bit_mask       : u64,
+   },
+   ValidationFailed{
+      type_name      : &'static str,
+   },
+   PositionDependent,
+   CoveredByNewerPatch{
+      address_range  : std::ops::Range<usize>,
+   },
 }
 
 /// <code>Result</code> type with error
 /// variant <code>PatchError</code>
 pub type Result<T> = std::result::Result<T, PatchError>;
 
-/// Enum for representing alignment
-/// of data within a section of memory.
-#[derive(Debug)]
-pub enum Alignment {
-   Left,
-   LeftOffset{
-      elements : usize,
-   },
-   LeftByteOffset{
-      bytes    : usize,
-   },
-   Right,
-   RightOffset{
-      elements : usize,
-   },
-   RightByteOffset{
-      bytes    : usize,
-   },
-   Center,
-   CenterByte,
+/// A slot within a patch template which
+/// is filled with a value when the patch
+/// is applied.  The slot offset is relative
+/// to the start of the template bytes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Placeholder {
+   pub slot_offset   : usize,
+   pub value         : PlaceholderValue,
 }
 
-/// Struct for storing and verifying
-/// stored byte data for a patch.
+/// The value written to a placeholder
+/// slot.  Integer values are written in
+/// native byte order.  <code>Relative</code>
+/// values are written as a 4-byte signed
+/// displacement from the address of the
+/// template's base offset to the target
+/// address, such as for a relative jump
+/// or call whose instruction ends at the
+/// base offset.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Checksum {
-   checksum : u32,
+pub enum PlaceholderValue {
+   Byte(u8),
+   Word(u16),
+   DoubleWord(u32),
+   QuadWord(u64),
+   Relative{
+      target_address : usize,
+      base_offset    : usize,
+   },
+}
+
+/// How <code>writer::FlipJcc</code>
+/// changes a conditional jump.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JccFlip {
+   Invert,
+   ForceTaken,
+   ForceSkip,
+}
+
+/// How far a patch applied with
+/// <code>patch_create_chunked</code>
+/// has gotten.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ChunkProgress {
+   pub bytes_written : usize,
+   pub bytes_total   : usize,
+}
+
+/// Whether to keep applying a patch
+/// after a chunk has been written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChunkAction {
+   Continue,
+   Cancel,
+}
+
+/// The container for storing patched
+/// bytes in memory for restoration when
+/// the instance is dropped.  This is the
+/// container used by every type which
+/// implements <code>PatchTarget</code>.
+/// Every live container is tracked so
+/// its patch can be reverted with
+/// <code>revert_all</code>, after which
+/// dropping it does nothing.
+///
+/// Patches are always restored newest
+/// first where they overlap, no matter
+/// which order their containers are
+/// dropped in.  A container dropped while
+/// a newer overlapping patch is still
+/// applied leaves its own patch applied
+/// until the newer one is restored, so
+/// its old bytes never overwrite the
+/// newer patch.  If restoring fails when
+/// a container is dropped, the error is
+/// logged and the patch stays registered,
+/// so restoring it is tried again whenever
+/// another container is dropped and by
+/// <code>revert_all</code>.
+pub struct PatchContainer {
+   restore  : std::sync::Arc<PatchRestore<RestoreBytes>>,
+}
+
+/// What a container restores, shared
+/// with the patch registry so it is
+/// restored exactly once by whichever
+/// gets to it first.
+struct PatchRestore<U> {
+   address_range  : std::ops::Range<usize>,
+   undo           : U,
+   active         : std::sync::atomic::AtomicBool,
+   poisoned       : std::sync::atomic::AtomicBool,
+}
+
+/// Overwritten bytes restored by a
+/// <code>PatchContainer</code>, along
+/// with which of the ranges have been
+/// restored, so a failed restore only
+/// retries the rest.
+struct RestoreBytes {
+   old_bytes      : Vec<u8>,
+   restore_ranges : Vec<std::ops::Range<usize>>,
+   restore_mask   : Option<Vec<u8>>,
+   restored       : Vec<std::sync::atomic::AtomicBool>,
+}
+
+/// A pointer slot restored by a
+/// <code>SlotHookContainer</code>.
+struct RestoreSlot {
+   original       : usize,
+   replacement    : usize,
+}
+
+/// A hook unlinked by a
+/// <code>HookChainContainer</code>.
+struct RestoreChain {
+   chain          : crate::sys::compiler::HookChain,
+   index          : usize,
+   hook           : HookTarget,
+}
+
+/// A container's place in the patch
+/// registry.  An entry is released once
+/// its container is dropped, and stays
+/// until every newer overlapping patch
+/// is restored so it can be restored
+/// in turn.
+struct RegistryEntry {
+   patch    : std::sync::Arc<dyn RegisteredPatch>,
+   released : bool,
+}
+
+/// The container for a hooked function
+/// pointer slot, such as a virtual method
+/// table or import address table entry,
+/// which restores the original pointer
+/// when dropped.  The slot is left alone
+/// if something else rewrote it while it
+/// was hooked.  Like <code>PatchContainer</code>,
+/// it is reverted by <code>revert_all</code>.
+pub struct SlotHookContainer {
+   restore  : std::sync::Arc<PatchRestore<RestoreSlot>>,
+}
+
+/// The container returned by
+/// <code>writer::VTableHook</code>.
+pub type VTableHookContainer = SlotHookContainer;
+
+/// The container returned by
+/// <code>writer::IatHook</code>.
+pub type IatHookContainer = SlotHookContainer;
+
+/// The container returned by
+/// <code>writer::ChainHook</code>, which
+/// unlinks the hook when dropped.  The
+/// original bytes are restored once every
+/// hook in the chain has been unlinked.
+/// Like <code>PatchContainer</code>, it
+/// is reverted by <code>revert_all</code>.
+pub struct HookChainContainer {
+   restore  : std::sync::Arc<PatchRestore<RestoreChain>>,
+}
+
+/// Memory in the current process opened
+/// for patching by a <code>PatchTarget</code>.
+/// The memory's original permissions are
+/// restored when this is dropped, unless
+/// it was opened for reading through the
+/// editor cache, in which case they are
+/// restored once the cache lets go of it.
+pub struct PatchMemory {
+   _session       : std::sync::Arc<crate::sys::memory::MemoryEditor>,
+   _write         : Option<EditorCacheWrite>,
+}
+
+/// Options for the cache of memory opened
+/// for reading, which lets repeated reads
+/// of the same few pages, such as reading
+/// the same structs every frame, skip
+/// changing memory permissions.  Cached
+/// pages are opened without taking away
+/// any access they already have, and their
+/// permissions are restored once they
+/// haven't been read for <code>time_to_live</code>.
+/// Opening pages for writing evicts them,
+/// and they aren't cached again until the
+/// writer is done.  A capacity of zero
+/// disables the cache.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EditorCacheOptions {
+   pub capacity      : usize,
+   pub time_to_live  : std::time::Duration,
+}
+
+// The longest an instruction can be
+const MAXIMUM_INSTRUCTION_LENGTH : usize = 15;
+
+/// The options the editor cache
+/// starts out with.
+pub const DEFAULT_EDITOR_CACHE_OPTIONS : EditorCacheOptions = EditorCacheOptions{
+   capacity       : 32,
+   time_to_live   : std::time::Duration::from_millis(500),
+};
+
+/// A named set of writers which are
+/// applied and reverted together, such
+/// as every patch making up one feature.
+/// If any member fails to apply, the
+/// members applied before it are reverted
+/// so the group is never left half-enabled.
+/// Dropping the group reverts all of
+/// its members.
+///
+/// ```
+/// let mut god_mode = nusion_core::patch::PatchGroup::new("god mode");
+/// god_mode
+///    .add("game.exe", DAMAGE_NOP)
+///    .add("game.exe", FALL_DAMAGE_NOP)
+///    .set_tamper_policy(nusion_core::patch::TamperPolicy::Reapply);
+/// unsafe{god_mode.set_tamper_watch(true)}?;
+///
+/// // From the main loop
+/// let mut env = nusion_core::env_mut!();
+/// if bindings.god_mode.is_changed() == true {
+///    unsafe{god_mode.set_enabled(env.modules_mut(), bindings.god_mode.is_active())}?;
+/// }
+/// unsafe{god_mode.check_tampering(env.modules_mut())}?;
+/// ```
+pub struct PatchGroup {
+   name        : String,
+   members     : Vec<PatchGroupMember>,
+   enabled     : bool,
+   policy      : TamperPolicy,
+   watch       : bool,
+   watchpoints : Vec<crate::debug::Watchpoint>,
+   written     : std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+/// What a patch group does when
+/// <code>PatchGroup::check_tampering</code>
+/// finds that the game or another tool
+/// has overwritten its patched bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TamperPolicy {
+   Ignore,
+   Report,
+   Reapply,
+   Disable,
+}
+
+/// What a string writer does when the
+/// string doesn't fit in the buffer
+/// along with its null terminator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StringTruncation {
+   /// Fails with <code>StringTooLong</code>
+   /// without writing anything.
+   Error,
+   /// Cuts the string at a character
+   /// boundary so it fits along with
+   /// its null terminator.
+   Truncate,
+   /// Cuts the string at a character
+   /// boundary so it fits, leaving out
+   /// the null terminator if the string
+   /// fills the whole buffer.  This is
+   /// for buffers whose length is known
+   /// without needing a terminator.
+   TruncateUnterminated,
+}
+
+/// Closure which applies a stored writer
+/// to a module.
+type PatchGroupApply = Box<
+   dyn Fn(
+      & mut crate::process::ModuleSnapshot,
+   ) -> Result<crate::process::ModuleSnapshotPatchContainer>
+   + Send + Sync
+>;
+
+struct PatchGroupMember {
+   module_name : String,
+   apply       : PatchGroupApply,
+   container   : Option<crate::process::ModuleSnapshotPatchContainer>,
+   patched     : Vec<u8>,
 }
 
 /// Type which stores a pointer to
@@ -98,6 +486,41 @@ pub mod reader {
       pub memory_offset_range : R,
    }
 
+   /// Reads a game enum stored as an integer
+   /// discriminant and converts it to a value
+   /// from a list of allowed variants.  An
+   /// unknown discriminant is an error rather
+   /// than being cast into a Rust enum, which
+   /// would be undefined behavior.
+   ///
+   /// ```
+   /// #[derive(Clone, Copy, Debug)]
+   /// enum Weapon {
+   ///    Pistol,
+   ///    Rifle,
+   ///    Shotgun,
+   /// }
+   ///
+   /// let weapon = unsafe{game.patch_read(&nusion_core::patch::reader::Enum{
+   ///    memory_offset_range : 0x0A10..0x0A14,
+   ///    variants            : &[
+   ///       (0u32, Weapon::Pistol),
+   ///       (1u32, Weapon::Rifle),
+   ///       (4u32, Weapon::Shotgun),
+   ///    ],
+   /// })}?;
+   /// ```
+   #[derive(Debug)]
+   pub struct Enum<
+      'v,
+      R: RangeBounds<usize>,
+      D: Discriminant,
+      T: Copy,
+   > {
+      pub memory_offset_range : R,
+      pub variants            : &'v [(D, T)],
+   }
+
    /// Reads a slice of items which
    /// implement the <code>Copy</code>
    /// trait.
@@ -110,6 +533,159 @@ pub mod reader {
       pub memory_offset_range : R,
       pub element_count       : usize,
    }
+
+   /// Reads a null-terminated string of bytes
+   /// from a fixed-capacity buffer, stopping
+   /// at the first null or the end of the
+   /// buffer.  Invalid UTF-8 is replaced with
+   /// the replacement character.
+   ///
+   /// ```
+   /// let name = unsafe{game.patch_read(&nusion_core::patch::reader::CStr{
+   ///    memory_offset_range : 0x0B20..0x0B40,
+   /// })}?;
+   /// ```
+   #[derive(Debug)]
+   pub struct CStr<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+   }
+
+   /// Reads a null-terminated UTF-16 string
+   /// from a fixed-capacity buffer like
+   /// <code>CStr</code>.  Invalid UTF-16 is
+   /// replaced with the replacement character.
+   #[derive(Debug)]
+   pub struct WideStr<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+   }
+
+   /// Reads the bits selected by a mask
+   /// from a little-endian bit field of
+   /// 1 to 8 bytes, shifted down so the
+   /// mask's lowest bit is bit 0.  Naturally
+   /// aligned fields of 1, 2, 4, or 8 bytes
+   /// are read in a single access.
+   ///
+   /// ```
+   /// let god_mode = unsafe{game.patch_read(&nusion_core::patch::reader::Bits{
+   ///    memory_offset_range : 0x2F40..0x2F44,
+   ///    bit_mask            : 0x0000_0400,
+   /// })}? != 0;
+   /// ```
+   #[derive(Debug)]
+   pub struct Bits<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub bit_mask            : u64,
+   }
+
+   /// Follows a multi-level pointer chain
+   /// and reads the value at the end.  The
+   /// memory offset range holds the base
+   /// pointer, and every offset but the last
+   /// is added to the current pointer before
+   /// it is dereferenced again.  The last
+   /// offset is added to find the address of
+   /// the value, so <code>base → +0x40 → +0x8</code>
+   /// is written as the base pointer's range
+   /// with offsets <code>&[0x40, 0x8]</code>.
+   /// Every pointer is checked to be readable
+   /// before it is followed.
+   ///
+   /// ```
+   /// let health = unsafe{game.patch_read(&nusion_core::patch::reader::PointerChain{
+   ///    marker              : std::marker::PhantomData::<f32>,
+   ///    memory_offset_range : 0x018C..0x0194,
+   ///    offsets             : &[0x40, 0x8],
+   /// })}?;
+   /// ```
+   #[derive(Debug)]
+   pub struct PointerChain<
+      'o,
+      R: RangeBounds<usize>,
+      T: Copy,
+   > {
+      pub marker              : std::marker::PhantomData<* const T>,
+      pub memory_offset_range : R,
+      pub offsets             : &'o [usize],
+   }
+
+   /// Follows a multi-level pointer chain
+   /// like <code>PointerChain</code>, but
+   /// returns the address at the end of the
+   /// chain instead of reading it.
+   #[derive(Debug)]
+   pub struct PointerChainAddress<
+      'o,
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub offsets             : &'o [usize],
+   }
+
+   /// Converts the item read by another
+   /// reader.  Created by <code>Reader::map</code>.
+   ///
+   /// ```
+   /// use nusion_core::patch::Reader;
+   ///
+   /// let health_percent = unsafe{game.patch_read(&nusion_core::patch::reader::Item::<_, f32>{
+   ///    marker               : std::marker::PhantomData,
+   ///    memory_offset_range  : 0x1A40..0x1A44,
+   /// }.map(|health| health / 150.0 * 100.0))}?;
+   /// ```
+   pub struct Map<Rd, F> {
+      pub(super) reader : Rd,
+      pub(super) map    : F,
+   }
+
+   /// Checks the item read by another
+   /// reader, failing with <code>ValidationFailed</code>
+   /// if the check returns false.  Created
+   /// by <code>Reader::validate</code>.
+   ///
+   /// ```
+   /// use nusion_core::patch::Reader;
+   ///
+   /// let speed = unsafe{game.patch_read(&nusion_core::patch::reader::Item::<_, f32>{
+   ///    marker               : std::marker::PhantomData,
+   ///    memory_offset_range  : 0x1A48..0x1A4C,
+   /// }.validate(|speed| speed.is_finite() && *speed >= 0.0))}?;
+   /// ```
+   pub struct Validate<Rd, F> {
+      pub(super) reader   : Rd,
+      pub(super) validate : F,
+   }
+
+   /// Reads several items at once, opening
+   /// the memory spanning every reader's
+   /// memory offset range a single time.
+   /// The ranges may overlap, but the memory
+   /// between them should share the same
+   /// memory permissions.  Tuples of up to
+   /// 8 readers are supported.
+   ///
+   /// ```
+   /// let (health, ammo) = unsafe{game.patch_read(&nusion_core::patch::reader::Tuple::new((
+   ///    nusion_core::patch::reader::Item::<_, f32>{
+   ///       marker               : std::marker::PhantomData,
+   ///       memory_offset_range  : 0x1A40..0x1A44,
+   ///    },
+   ///    nusion_core::patch::reader::Item::<_, u32>{
+   ///       marker               : std::marker::PhantomData,
+   ///       memory_offset_range  : 0x1A60..0x1A64,
+   ///    },
+   /// ))?)}?;
+   /// ```
+   pub struct Tuple<T> {
+      pub(super) memory_offset_range  : std::ops::Range<usize>,
+      pub(super) readers              : T,
+   }
 }
 
 /// Collection of provided structs
@@ -162,6 +738,28 @@ pub mod writer {
       pub padding             : &'s U,
    }
 
+   /// Positions and clones a single
+   /// element according to the alignment,
+   /// filling the bytes before it with one
+   /// padding value and the bytes after it
+   /// with another.
+   #[derive(Debug)]
+   pub struct ItemPaddedSplit<
+      's,
+      R: RangeBounds<usize>,
+      T: Clone,
+      U: Clone,
+      V: Clone,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub alignment           : Alignment,
+      pub tail                : TailFill,
+      pub item                : &'s T,
+      pub padding_left        : &'s U,
+      pub padding_right       : &'s V,
+   }
+
    /// Clones a single slice.
    #[derive(Debug)]
    pub struct Slice<
@@ -205,6 +803,106 @@ pub mod writer {
       pub padding             : &'s U,
    }
 
+   /// Positions and clones a single
+   /// slice according to the alignment,
+   /// filling the bytes before it with one
+   /// padding value and the bytes after it
+   /// with another.
+   #[derive(Debug)]
+   pub struct SlicePaddedSplit<
+      's,
+      R: RangeBounds<usize>,
+      T: Clone,
+      U: Clone,
+      V: Clone,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub alignment           : Alignment,
+      pub tail                : TailFill,
+      pub slice               : &'s [T],
+      pub padding_left        : &'s U,
+      pub padding_right       : &'s V,
+   }
+
+   /// Writes a string as null-terminated
+   /// UTF-8 into a fixed-capacity buffer,
+   /// filling the rest of the buffer with
+   /// nulls.  What happens when the string
+   /// doesn't fit is decided by the truncation
+   /// policy.
+   ///
+   /// ```
+   /// unsafe{game.patch_write(&nusion_core::patch::writer::CStr{
+   ///    memory_offset_range : 0x0C00..0x0C80,
+   ///    checksum            : nusion_core::patch::Checksum::from(0x9A3C51E7),
+   ///    string              : "gg everyone",
+   ///    truncation          : nusion_core::patch::StringTruncation::Truncate,
+   /// })}?;
+   /// ```
+   #[derive(Debug)]
+   pub struct CStr<
+      's,
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub string              : &'s str,
+      pub truncation          : StringTruncation,
+   }
+
+   /// Writes a string as null-terminated
+   /// UTF-16 into a fixed-capacity buffer
+   /// like <code>CStr</code>.  Surrogate
+   /// pairs are never split when truncating.
+   #[derive(Debug)]
+   pub struct WideStr<
+      's,
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub string              : &'s str,
+      pub truncation          : StringTruncation,
+   }
+
+   /// Writes the bits selected by a mask
+   /// in a little-endian bit field of 1 to
+   /// 8 bytes, leaving every other bit as
+   /// it is.  The value is shifted up so its
+   /// bit 0 lands on the mask's lowest bit.
+   /// Naturally aligned fields of 1, 2, 4,
+   /// or 8 bytes are updated with a single
+   /// atomic read-modify-write, otherwise
+   /// each byte is updated atomically.
+   ///
+   /// The checksum is computed over the field
+   /// with the bits outside the mask cleared,
+   /// so neighboring flags changing at runtime
+   /// doesn't cause a mismatch.  Only the
+   /// masked bits are restored when a patch
+   /// container is dropped.
+   ///
+   /// ```
+   /// // The checksum only sees the masked bit,
+   /// // which is clear before the patch
+   /// let _god_mode = unsafe{game.patch_create(&nusion_core::patch::writer::Bits{
+   ///    memory_offset_range : 0x2F40..0x2F44,
+   ///    checksum            : nusion_core::patch::Checksum::new(&[0x00, 0x00, 0x00, 0x00]),
+   ///    bit_mask            : 0x0000_0400,
+   ///    value               : 1,
+   /// })}?;
+   /// ```
+   #[derive(Debug)]
+   pub struct Bits<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub bit_mask            : u64,
+      pub value               : u64,
+   }
+
    /// Compiles a block of architecture-dependent
    /// no-operation (nop) machine-code
    /// instructions.
@@ -216,12 +914,32 @@ pub mod writer {
       pub checksum            : Checksum,
    }
 
+   /// Changes the conditional jump filling
+   /// the memory offset range, which may be
+   /// either the short or near form.  It can
+   /// be inverted, keeping its target, or
+   /// turned into an unconditional jump or
+   /// no-operation instructions.  The existing
+   /// instruction is read when patching, so
+   /// anything other than a conditional jump
+   /// is an error.
+   #[derive(Debug)]
+   pub struct FlipJcc<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub flip                : JccFlip,
+   }
+
    /// Compiles a call to a given assembly
    /// subroutine, filling the rest of the
    /// bytes with architecture-dependent
    /// no-operation (nop) instructions.
    /// It is recommended to use the <code>hook!</code>
-   /// macro to generate your hook.
+   /// macro to generate your hook.  An error
+   /// is returned if the memory offset range
+   /// ends in the middle of an instruction.
    #[derive(Debug)]
    pub struct Hook<
       R: RangeBounds<usize>,
@@ -231,6 +949,141 @@ pub mod writer {
       pub hook                : HookTarget,
    }
 
+   /// Compiles a jump to a stub which
+   /// calls a given assembly subroutine,
+   /// executes the overwritten instructions,
+   /// and then jumps back to the end of the
+   /// memory offset range.  The subroutine
+   /// only needs to <code>ret</code> and
+   /// shouldn't execute the overwritten
+   /// instructions itself.  The overwritten
+   /// instructions must not depend on their
+   /// own address, such as relative jumps,
+   /// and the memory offset range must not
+   /// end in the middle of an instruction.
+   #[derive(Debug)]
+   pub struct HookJmpBack<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub hook                : HookTarget,
+   }
+
+   /// Links a given assembly subroutine into
+   /// a hook chain at the memory offset range,
+   /// so several mods can hook the same code.
+   /// The first hook compiles a jump to a
+   /// shared stub, and later hooks, including
+   /// those from other mods, are linked into
+   /// it.  Hooks are called newest first, then
+   /// the overwritten instructions are executed
+   /// if <code>jmp_back</code> is true, and the
+   /// original bytes are only restored once
+   /// every hook has been unlinked, in any
+   /// order.  The checksum is always checked
+   /// against the original bytes.  Like
+   /// <code>VTableHook</code>, this is applied
+   /// with <code>apply</code> instead of
+   /// through <code>Patch</code>.
+   ///
+   /// Every hook on the same chain must use
+   /// the same memory offset range and the
+   /// same <code>jmp_back</code>, and the
+   /// same notes as <code>Hook</code> and
+   /// <code>HookJmpBack</code> apply.
+   #[derive(Debug)]
+   pub struct ChainHook<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub hook                : HookTarget,
+      pub jmp_back            : bool,
+   }
+
+   /// Compiles a jump to a stub which calls
+   /// a given assembly subroutine, executes
+   /// the overwritten instructions, and then
+   /// jumps back to the instruction after
+   /// them.  The memory offset range starts
+   /// at the hook site and only needs to be
+   /// long enough for the jump.  The writer
+   /// disassembles the start of the range and
+   /// only overwrites the whole instructions
+   /// the jump covers, relocating them into
+   /// the stub so instructions which depend
+   /// on their own address, such as relative
+   /// jumps and calls, still work.  The
+   /// subroutine only needs to <code>ret</code>
+   /// and shouldn't execute the overwritten
+   /// instructions itself.
+   ///
+   /// If <code>emulate</code> is set, an
+   /// overwritten <code>lea</code> or <code>mov</code>
+   /// whose instruction pointer-relative operand
+   /// can't be reached from the stub is rewritten
+   /// to use the absolute address instead of
+   /// failing.  Each one is logged as a warning.
+   ///
+   /// ```
+   /// const HOOK_MONEY : nusion_core::patch::writer::InlineHook<std::ops::Range<usize>>
+   ///    = nusion_core::patch::writer::InlineHook{
+   ///    memory_offset_range  : 0x7FFF1337..0x7FFF1357,
+   ///    checksum             : nusion_core::patch::Checksum::from(0xDEADBEEF),
+   ///    emulate              : false,
+   ///    hook                 : nusion_core::hook!("
+   ///       push  rcx
+   ///       lea   rdi,[rcx+0x104]
+   ///       call  {target}
+   ///       pop   rcx
+   ///       ret
+   ///    ", |money_counter : & mut i32| {
+   ///       *money_counter = i32::MAX;
+   ///    }),
+   /// };
+   /// ```
+   #[derive(Debug)]
+   pub struct InlineHook<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub hook                : HookTarget,
+      pub emulate             : bool,
+   }
+
+   /// The same as <code>InlineHook</code>,
+   /// except that if another hooking library
+   /// already hooked the memory offset range,
+   /// the hook is chained onto theirs instead
+   /// of failing the checksum.  Their hook is
+   /// recognized as a jump at the start of the
+   /// range which leads out of the module.  The
+   /// jump is left in place, and the inline hook
+   /// is written where their hook continues,
+   /// after the jump and any <code>nop</code> or
+   /// <code>int3</code> padding following it, so
+   /// their hook runs first, then the subroutine,
+   /// then the original instructions.  The range
+   /// must be long enough for the padding and
+   /// the inline hook's jump.
+   ///
+   /// The checksum is still checked when the
+   /// range hasn't been hooked.  Restoring
+   /// the patch puts their jump back, so
+   /// if they remove their hook first, this
+   /// patch must be restored before theirs.
+   #[derive(Debug)]
+   pub struct CoexistingHook<
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub hook                : HookTarget,
+      pub emulate             : bool,
+   }
+
    /// Copies a byte buffer containing
    /// assembly instructions into the
    /// memory offset range according
@@ -249,28 +1102,238 @@ pub mod writer {
       pub alignment           : Alignment,
       pub asm_bytes           : &'static [u8],
    }
-}
 
-///////////////////////
-// TRAIT DEFINITIONS //
-///////////////////////
+   /// Assembles instructions at runtime
+   /// into the start of the memory offset
+   /// range, filling any unused bytes with
+   /// no-operation (nop) instructions.  The
+   /// function is given an assembler for the
+   /// final address of the bytes, so jumps,
+   /// calls and absolute memory addresses
+   /// can refer to anything in the process.
+   pub struct Assembled<
+      's,
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub assemble            : &'s dyn Fn(& mut crate::asm::Assembler) -> crate::asm::Result<()>,
+   }
 
-/// Opens a region of memory for
-/// patching and applies a patch
-/// using some type implementing
-/// either the <code>Reader</code>
-/// or <code>Writer</code> trait,
-/// depending on whether a reader
-/// or writer function is used.
+   /// Copies a byte buffer containing
+   /// assembly instructions into the
+   /// memory offset range like <code>Asm</code>,
+   /// filling each placeholder slot with its
+   /// value once the final location of the
+   /// bytes is known.  This allows simple
+   /// substitutions such as addresses found
+   /// by a scan or numbers from a config
+   /// without assembling code at runtime.
+   #[derive(Debug)]
+   pub struct Template<
+      's,
+      R: RangeBounds<usize>,
+   > {
+      pub memory_offset_range : R,
+      pub checksum            : Checksum,
+      pub alignment           : Alignment,
+      pub template_bytes      : &'s [u8],
+      pub placeholders        : &'s [Placeholder],
+   }
+
+   /// Applies several writers to disjoint
+   /// memory offset ranges as a single
+   /// patch.  Every writer's checksum is
+   /// verified before any bytes are written,
+   /// and only the writers' ranges are
+   /// restored when the patch container
+   /// is dropped.  The memory between the
+   /// ranges should share the same memory
+   /// permissions, since it is opened as a
+   /// single block while patching.
+   pub struct Scatter<
+      's,
+   > {
+      pub(super) memory_offset_range  : std::ops::Range<usize>,
+      pub(super) checksum             : Checksum,
+      pub(super) writers              : &'s [&'s dyn Writer<std::ops::Range<usize>>],
+   }
+
+   /// Replaces a method pointer in an
+   /// object's virtual method table.  The
+   /// table slot is found by following the
+   /// table pointer stored at the start of
+   /// the object, so unlike other writers
+   /// this works on absolute addresses and
+   /// is applied with <code>apply</code>
+   /// instead of through <code>Patch</code>.
+   ///
+   /// Every object of the same class shares
+   /// its table, so the hook affects all of
+   /// them, not only the given object.
+   #[derive(Debug)]
+   pub struct VTableHook {
+      pub object_address   : usize,
+      pub method_index     : usize,
+      pub replacement      : usize,
+   }
+
+   /// Replaces the address of a function
+   /// a module imports by name in the
+   /// module's import address table.  Like
+   /// <code>VTableHook</code>, this is applied
+   /// with <code>apply</code> instead of
+   /// through <code>Patch</code>.  Only calls
+   /// made by the given module are hooked.
+   /// The entry is found and swapped the
+   /// same way as <code>iat::IatHookSet</code>,
+   /// which can hook several entries at once.
+   #[derive(Debug)]
+   pub struct IatHook<
+      's,
+   > {
+      pub library          : &'s str,
+      pub function         : &'s str,
+      pub replacement      : usize,
+   }
+}
+
+/// Writers for very common patches which
+/// are sized for the instruction they
+/// replace, so no assembly has to be
+/// written by hand.  Each one checks the
+/// code it replaces before writing anything:
+/// calls and branches must be the expected
+/// kind of instruction, and returns must
+/// overwrite only whole instructions.
 ///
-/// <h2 id=  patch_safety>
-/// <a href=#patch_safety>
-/// Safety
-/// </a></h2>
+/// ```
+/// use nusion_core::patch::{Checksum, recipes};
 ///
-/// This is by far the most unsafe
-/// part of this library.  To put
-/// into perspective, this is about
+/// // Skip a call to the damage function
+/// let no_damage = unsafe{recipes::NopCall::new(
+///    &game, 0x1A2B30, Checksum::from(0x1F2E3D4C),
+/// )}?;
+/// let _no_damage = unsafe{game.patch_create(&no_damage)}?;
+///
+/// // Always take the "has ammo" branch
+/// let _infinite_ammo = unsafe{game.patch_create(&recipes::ForceBranch::taken_short(
+///    0x1A2C08, Checksum::from(0x5A6B7C8D),
+/// ))}?;
+///
+/// // Make the anti-cheat check always
+/// // return true
+/// let _no_anti_cheat = unsafe{game.patch_create(&recipes::ReturnConstant::new(
+///    0x0F4410, 1, Checksum::from(0x12345678),
+/// ))}?;
+/// ```
+pub mod recipes {
+   use super::*;
+
+   /// Replaces a call instruction with
+   /// no-operation instructions.  Any
+   /// return value is left as whatever was
+   /// in the return register.
+   #[derive(Debug)]
+   pub struct NopCall {
+      pub(super) memory_offset_range  : std::ops::Range<usize>,
+      pub(super) checksum             : Checksum,
+   }
+
+   /// Forces a conditional jump to always
+   /// or never be taken, replacing it with
+   /// an unconditional jump to the same
+   /// target or no-operation instructions.
+   #[derive(Debug)]
+   pub struct ForceBranch {
+      pub(super) memory_offset_range  : std::ops::Range<usize>,
+      pub(super) checksum             : Checksum,
+      pub(super) taken                : bool,
+   }
+
+   /// Returns early from a function at
+   /// the memory offset, optionally with
+   /// a constant return value.  The checksum
+   /// covers the instructions which are
+   /// overwritten, so the function's start
+   /// is usually the offset to use.  The
+   /// offset must start an instruction, and
+   /// an error is returned if the return
+   /// would end partway through one.
+   #[derive(Debug)]
+   pub struct ReturnConstant {
+      pub(super) memory_offset_range  : std::ops::Range<usize>,
+      pub(super) checksum             : Checksum,
+      pub(super) value                : Option<u64>,
+   }
+}
+
+/// Typed views which map structs onto
+/// the memory of a <code>PatchTarget</code>.
+pub mod view {
+   use super::*;
+
+   /// A view of a <code>#[repr(C)]</code>
+   /// struct stored at an offset within a
+   /// patch target, such as a game object
+   /// inside a module.  Fields are accessed
+   /// by their byte offsets, which should
+   /// come from <code>std::mem::offset_of!</code>,
+   /// and every access is checked to be
+   /// in bounds and aligned.  If the view
+   /// has a checksum, the whole struct is
+   /// verified against it before every access.
+   ///
+   /// ```
+   /// #[repr(C)]
+   /// struct Player {
+   ///    health   : i32,
+   ///    armor    : i32,
+   ///    position : Vector3,
+   /// }
+   ///
+   /// use nusion_core::patch::view::StructView;
+   /// use std::mem::offset_of;
+   ///
+   /// let player = StructView::<_, Player>::new(&game, 0x1A2B30)?;
+   ///
+   /// let health = unsafe{player.read_field::<i32>(offset_of!(Player, health))}?;
+   /// unsafe{player.write_field(offset_of!(Player, armor), health)}?;
+   ///
+   /// let position = player.view::<Vector3>(offset_of!(Player, position))?;
+   /// ```
+   pub struct StructView<
+      't,
+      P: PatchTarget,
+      T,
+   > {
+      pub(super) target          : &'t P,
+      pub(super) memory_offset   : usize,
+      pub(super) checksum        : Option<Checksum>,
+      pub(super) marker          : std::marker::PhantomData<* const T>,
+   }
+}
+
+///////////////////////
+// TRAIT DEFINITIONS //
+///////////////////////
+
+/// Opens a region of memory for
+/// patching and applies a patch
+/// using some type implementing
+/// either the <code>Reader</code>
+/// or <code>Writer</code> trait,
+/// depending on whether a reader
+/// or writer function is used.
+///
+/// <h2 id=  patch_safety>
+/// <a href=#patch_safety>
+/// Safety
+/// </a></h2>
+///
+/// This is by far the most unsafe
+/// part of this library.  To put
+/// into perspective, this is about
 /// as unsafe as
 /// <code><a href=
 /// https://doc.rust-lang.org/std/mem/fn.transmute.html>std::mem::transmute
@@ -370,6 +1433,201 @@ pub trait Patch {
    ) -> Result<Self::Container>
    where Wt: Writer<Mr>,
          Mr: RangeBounds<usize>;
+
+   /// Creates a patch using a writer
+   /// while every other thread in the
+   /// process is suspended, failing if
+   /// any of them is executing inside
+   /// the overwritten range.  Use this
+   /// when patching code which may be
+   /// running, such as a hook on the
+   /// game's main loop.  Implementations
+   /// which aren't backed by live memory
+   /// may use the default, which is the
+   /// same as <code>patch_create</code>.
+   unsafe fn patch_create_atomic<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      return self.patch_create(writer);
+   }
+
+   /// Creates a patch using a writer, copying
+   /// it into memory a chunk at a time for
+   /// writers which cover a large range, such
+   /// as a whole section.  After each chunk is
+   /// written and verified, the progress function
+   /// is called and may cancel the patch, in
+   /// which case the written chunks are restored
+   /// and <code>PatchError::Cancelled</code>
+   /// is returned.  Writers with a restore
+   /// mask have their chunks extended to an
+   /// 8-byte boundary, so no bit field is
+   /// split between two chunks.
+   ///
+   /// The patch is built in a separate buffer
+   /// for the address it will be copied to,
+   /// so nothing is written if the writer
+   /// fails.  Implementations
+   /// which aren't backed by live memory may
+   /// use the default, which creates the patch
+   /// all at once without reporting progress.
+   unsafe fn patch_create_chunked<Wt, Mr, F>(
+      & mut self,
+      writer      : & Wt,
+      chunk_size  : usize,
+      progress    : F,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+         F:  FnMut(ChunkProgress) -> ChunkAction,
+   {
+      let _ = (chunk_size, progress);
+      return self.patch_create(writer);
+   }
+
+   /// Writes several patches without saving
+   /// the overwritten bytes.  Every writer's
+   /// checksum is verified, and the patches
+   /// already written are rolled back if any
+   /// of them fails.  Implementations backed by live
+   /// memory change the memory permissions
+   /// once for each group of writers which
+   /// share a memory region, which is much
+   /// faster than writing them one at a time.
+   /// The writers' ranges must not overlap.
+   unsafe fn patch_write_batch(
+      & mut self,
+      writers : & [& dyn Writer<std::ops::Range<usize>>],
+   ) -> Result<()> {
+      let mut old_bytes = Vec::with_capacity(writers.len());
+      for writer in writers {
+         let range = writer.memory_offset_range().clone();
+         let bytes = self.patch_read(&reader::Slice::<_, u8>{
+            marker               : std::marker::PhantomData,
+            element_count        : range.end.saturating_sub(range.start),
+            memory_offset_range  : range,
+         })?;
+         old_bytes.push(bytes);
+      }
+
+      // Only the target knows the address the
+      // bytes live at, so each checksum is
+      // verified as part of its write
+      for (written, writer) in writers.iter().enumerate() {
+         if let Err(err) = self.patch_write(writer) {
+            for (writer, old_bytes) in writers.iter().zip(old_bytes.iter()).take(written).rev() {
+               let _ = restore_written(self, *writer, old_bytes);
+            }
+            return Err(err);
+         }
+      }
+
+      return Ok(());
+   }
+
+   /// Creates several patches, returning a
+   /// container for each writer in the same
+   /// order.  Every writer's checksum is
+   /// verified before anything is written,
+   /// and the patches already created are
+   /// restored if any of them fails, like
+   /// <code>patch_write_batch</code>.
+   /// Implementations which aren't backed
+   /// by live memory may use the default,
+   /// which verifies the checksums with each
+   /// memory offset as the address and then
+   /// creates each patch in turn.
+   unsafe fn patch_create_batch(
+      & mut self,
+      writers : & [& dyn Writer<std::ops::Range<usize>>],
+   ) -> Result<Vec<Self::Container>> {
+      for writer in writers {
+         let range = writer.memory_offset_range().clone();
+         let bytes = self.patch_read(&reader::Slice::<_, u8>{
+            marker               : std::marker::PhantomData,
+            element_count        : range.end.saturating_sub(range.start),
+            memory_offset_range  : range.clone(),
+         })?;
+         writer.verify_checksum(&bytes, range.start)?;
+      }
+
+      let mut containers = Vec::with_capacity(writers.len());
+      for writer in writers {
+         match self.patch_create(writer) {
+            Ok(container)  => containers.push(container),
+            Err(err)       => {
+               while let Some(container) = containers.pop() {
+                  std::mem::drop(container);
+               }
+               return Err(err);
+            },
+         }
+      }
+
+      return Ok(containers);
+   }
+}
+
+/// Trait for types which map offsets
+/// onto memory in the current process.
+/// Every type implementing this trait
+/// also implements <code>Patch</code>,
+/// so a custom target only needs to
+/// translate offsets into addresses and
+/// open the memory, and gets the same
+/// checksum and container behavior as
+/// <code>ModuleSnapshot</code>.
+///
+/// ```
+/// struct HeapObject {
+///    address  : usize,
+///    size     : usize,
+/// }
+///
+/// impl nusion_core::patch::PatchTarget for HeapObject {
+///    fn resolve_range<R>(
+///       & self,
+///       offset_range : & R,
+///    ) -> nusion_core::patch::Result<std::ops::Range<usize>>
+///    where R: std::ops::RangeBounds<usize>,
+///    {
+///       let range = nusion_core::patch::resolve_offset_range(offset_range, self.size)?;
+///       return Ok(self.address + range.start..self.address + range.end);
+///    }
+/// }
+///
+/// use nusion_core::patch::Patch;
+/// let mut object = HeapObject{address : player_address, size : 0x200};
+/// unsafe{object.patch_write(&GOD_MODE)}?;
+/// ```
+pub trait PatchTarget {
+   /// Converts an offset range into the
+   /// absolute address range it covers,
+   /// checking that it is in bounds.
+   fn resolve_range<R>(
+      & self,
+      offset_range   : & R,
+   ) -> Result<std::ops::Range<usize>>
+   where R: RangeBounds<usize>;
+
+   /// Opens the memory in an address
+   /// range returned by <code>resolve_range</code>
+   /// for reading, and also for writing
+   /// if <code>writable</code> is true.
+   /// This only needs to be implemented
+   /// by targets which have to check the
+   /// memory before it is opened.
+   fn memory_editor(
+      & self,
+      address_range  : std::ops::Range<usize>,
+      writable       : bool,
+   ) -> Result<PatchMemory> {
+      return PatchMemory::open(address_range, writable);
+   }
 }
 
 /// Trait for reading byte data from
@@ -391,10 +1649,69 @@ pub trait Reader<R: RangeBounds<usize>> {
    /// Reads the byte slice and converts
    /// it to a valid value of type
    /// <code>Self::Container</code>.
+   /// This must never panic, no matter
+   /// how long the memory buffer is, and
+   /// should return a <code>PatchError</code>
+   /// instead.
    fn read_item(
       & self,
       memory_buffer  : & [u8],
    ) -> Result<Self::Item>;
+
+   /// Converts the item after it is read.
+   fn map<F, U>(
+      self,
+      map : F,
+   ) -> reader::Map<Self, F>
+   where Self: Sized,
+         F: Fn(Self::Item) -> U,
+   {
+      return reader::Map{
+         reader   : self,
+         map      : map,
+      };
+   }
+
+   /// Checks the item after it is read,
+   /// returning <code>ValidationFailed</code>
+   /// instead if the check returns false.
+   fn validate<F>(
+      self,
+      validate : F,
+   ) -> reader::Validate<Self, F>
+   where Self: Sized,
+         F: Fn(&Self::Item) -> bool,
+   {
+      return reader::Validate{
+         reader   : self,
+         validate : validate,
+      };
+   }
+}
+
+/// A tuple of readers which can be read
+/// together by <code>reader::Tuple</code>.
+/// This is implemented for tuples of up
+/// to 8 readers.
+pub trait ReaderTuple {
+   /// The tuple of items read by
+   /// each reader.
+   type Items;
+
+   /// Returns every reader's memory
+   /// offset range, in order.
+   fn memory_offset_ranges(
+      & self,
+   ) -> Vec<std::ops::Range<usize>>;
+
+   /// Reads every item from a memory
+   /// buffer which starts at the given
+   /// memory offset.
+   fn read_items(
+      & self,
+      memory_offset  : usize,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Items>;
 }
 
 /// Trait for storing patch metadata
@@ -415,17 +1732,183 @@ pub trait Writer<R: RangeBounds<usize>> {
    ) -> &'l Checksum;
 
    /// Builds the patch and writes it
-   /// to the memory buffer.  The input
-   /// memory buffer should be a slice
-   /// to the actual memory location.
-   /// Copying the slice can break
-   /// many patch implementations.
+   /// to the memory buffer.  The memory
+   /// buffer holds the bytes currently
+   /// stored at the address, and may be
+   /// a separate copy which is written
+   /// there afterwards, so anything which
+   /// depends on where the patch ends up
+   /// must use the address instead of the
+   /// memory buffer's own address.
+   ///
+   /// This must never panic, no matter
+   /// how long the memory buffer is, since
+   /// unwinding from here would run through
+   /// whichever game thread applied the patch.
+   /// Any problem should be returned as a
+   /// <code>PatchError</code> instead.
    fn build_patch(
       & self,
       memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()>;
+
+   /// The same as <code>build_patch</code>,
+   /// except that warnings about how the
+   /// patch was built are returned instead
+   /// of logged.  Patching functions log
+   /// them once the patch is written and
+   /// any suspended threads are resumed,
+   /// since a suspended thread could be
+   /// holding the logger's lock.  This only
+   /// needs to be implemented by writers
+   /// which warn.
+   fn build_patch_warnings(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<Vec<String>> {
+      self.build_patch(memory_buffer, address)?;
+      return Ok(Vec::new());
+   }
+
+   /// Verifies the bytes currently stored
+   /// at the address, copied into the memory
+   /// buffer, against the stored checksum.
+   /// This only needs to be implemented by
+   /// writers which store more than one
+   /// checksum.
+   fn verify_checksum(
+      & self,
+      memory_buffer  : & [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let patch_checksum = self.checksum();
+
+      if patch_checksum.matches(memory_buffer) == false {
+         return Err(PatchError::ChecksumMismatch{
+            found    : Checksum::compute(patch_checksum.algorithm(), memory_buffer),
+            expected : patch_checksum.clone(),
+         });
+      }
+
+      return Ok(());
+   }
+
+   /// Returns the ranges within a memory
+   /// buffer of the given length which
+   /// are overwritten by <code>build_patch</code>.
+   /// Only these ranges are restored when
+   /// a patch container is dropped.  This
+   /// only needs to be implemented by writers
+   /// which leave some bytes untouched.
+   fn overwritten_ranges(
+      & self,
+      memory_buffer_length : usize,
+   ) -> Vec<std::ops::Range<usize>> {
+      return vec![0..memory_buffer_length];
+   }
+
+   /// Returns a mask over a memory buffer
+   /// of the given length selecting which
+   /// bits in the overwritten ranges are
+   /// restored when a patch container is
+   /// dropped.  The other bits keep whatever
+   /// they hold at the time.  This only needs
+   /// to be implemented by writers which
+   /// patch individual bits.
+   fn restore_mask(
+      & self,
+      _memory_buffer_length : usize,
+   ) -> Option<Vec<u8>> {
+      return None;
+   }
+
+   /// Checks whether the bytes built by
+   /// <code>build_patch</code> are the same
+   /// no matter which address they're built
+   /// for, and don't depend on anything in
+   /// the current process, such as an
+   /// allocated stub.  Only these writers
+   /// can be applied to memory which isn't
+   /// in the current process, such as a
+   /// <code>RemoteProcess</code> or a
+   /// <code>MemoryImage</code>.  This is
+   /// false unless implemented.
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return false;
+   }
+}
+
+/// An integer type which stores the
+/// discriminant of a game enum, read by
+/// <code>reader::Enum</code>.
+///
+/// <h2 id=  discriminant_safety>
+/// <a href=#discriminant_safety>
+/// Safety
+/// </a></h2>
+///
+/// Every bit pattern of the type's size
+/// must be a valid value of the type,
+/// since discriminants are read from
+/// arbitrary memory.
+pub unsafe trait Discriminant : Copy + Eq + Into<i128> {
+}
+
+/// Undoes whatever a container changed,
+/// given the address range it changed.
+trait RestoreAction : Send + Sync {
+   fn undo(
+      & self,
+      address_range : & std::ops::Range<usize>,
+   ) -> Result<()>;
+
+   // Hooks linked into the same chain
+   // can be unlinked in any order
+   fn linked_chain(
+      & self,
+   ) -> Option<crate::sys::compiler::HookChain> {
+      return None;
+   }
+}
+
+/// A container's restore state as seen by
+/// the patch registry, whatever kind of
+/// container it belongs to.
+trait RegisteredPatch : Send + Sync {
+   fn address_range(
+      & self,
+   ) -> & std::ops::Range<usize>;
+
+   fn is_active(
+      & self,
+   ) -> bool;
+
+   fn linked_chain(
+      & self,
+   ) -> Option<crate::sys::compiler::HookChain>;
+
+   fn restore(
+      & self,
    ) -> Result<()>;
 }
 
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Discriminant //
+//////////////////////////////////////////
+
+unsafe impl Discriminant for u8  {}
+unsafe impl Discriminant for u16 {}
+unsafe impl Discriminant for u32 {}
+unsafe impl Discriminant for u64 {}
+unsafe impl Discriminant for i8  {}
+unsafe impl Discriminant for i16 {}
+unsafe impl Discriminant for i32 {}
+unsafe impl Discriminant for i64 {}
+
 ////////////////////////////////////////
 // TRAIT IMPLEMENTATIONS - PatchError //
 ////////////////////////////////////////
@@ -446,6 +1929,10 @@ impl std::fmt::Display for PatchError {
             => write!(stream, "Residual bytes: {left} on left, {right} on right"),
          Self::CompilationError           {sys_error,       }
             => write!(stream, "Compilation error: {sys_error}"),
+         Self::ProcessError               {sys_error,       }
+            => write!(stream, "Process error: {sys_error}"),
+         Self::ThreadError                {sys_error,       }
+            => write!(stream, "Thread error: {sys_error}"),
          Self::ChecksumMismatch           {found, expected, }
             => write!(stream, "Checksum mismatch: Found {found}, expected {expected}"),
          Self::OutOfRange                 {maximum, provided}
@@ -454,6 +1941,72 @@ impl std::fmt::Display for PatchError {
             => write!(stream, "End offset is before start offset"),
          Self::ZeroLengthType
             => write!(stream, "Type has zero length for non-zero range length"),
+         Self::NoHookSite
+            => write!(stream, "No suitable hook site found"),
+         Self::NoWriters
+            => write!(stream, "No writers were provided"),
+         Self::ModuleNotFound             {module_name,     }
+            => write!(stream, "Module \"{module_name}\" not found"),
+         Self::UnmappedWithin             {offset,          }
+            => write!(stream, "Memory is unmapped at offset {offset:#X}"),
+         Self::OverlappingRanges          {first, second,   }
+            => write!(stream, "Overlapping ranges: {first:?} and {second:?}"),
+         Self::ThreadInRange              {address,         }
+            => write!(stream, "A thread is executing inside the patch at {address:#X}"),
+         Self::InvalidPointer             {step, address,   }
+            => write!(stream, "Pointer chain step {step} points to unreadable address {address:#X}"),
+         Self::Misaligned                 {address, alignment}
+            => write!(stream, "Address {address:#X} is not aligned to {alignment} bytes"),
+         Self::UnknownDiscriminant        {found, allowed,  }
+            => write!(stream, "Unknown enum discriminant {found}, expected one of {allowed:?}"),
+         Self::OutsideSections            {offset,          }
+            => write!(stream, "Offset {offset:#X} is outside of the module's sections"),
+         Self::OutsideAddressRange        {address_range, provided}
+            => write!(stream, "Addresses {:#X}..{:#X} are outside of {:#X}..{:#X}",
+               provided.start, provided.end, address_range.start, address_range.end,
+            ),
+         Self::MemoryFault                {fault,           }
+            => write!(stream, "Memory fault: {fault}"),
+         Self::ExceptionError             {sys_error,       }
+            => write!(stream, "Exception error: {sys_error}"),
+         Self::AsmError                   {err,             }
+            => write!(stream, "Assembly error: {err}"),
+         Self::TaskError                  {err,             }
+            => write!(stream, "Task error: {err}"),
+         Self::IatError                   {err,             }
+            => write!(stream, "Import address table error: {err}"),
+         Self::DebugError                 {err,             }
+            => write!(stream, "Hardware breakpoint error: {err}"),
+         Self::SplitsInstruction          {offset, length, range_length}
+            => write!(stream, "Memory offset range of {range_length} bytes ends inside the {length}-byte instruction at offset {offset:#X}"),
+         Self::UndecodableInstruction     {offset,          }
+            => write!(stream, "Unable to decode the instruction at offset {offset:#X} to find where it ends"),
+         Self::Cancelled                  {bytes_written,   }
+            => write!(stream, "Cancelled after writing {bytes_written} bytes"),
+         Self::ChunkMismatch              {offset,          }
+            => write!(stream, "Chunk at offset {offset:#X} doesn't match the patch after writing it"),
+         Self::HookChainMismatch          {length, jmp_back,}
+            => write!(stream, "Existing hook chain covers {length} bytes with jmp_back {jmp_back}, which doesn't match the hook"),
+         Self::NotExecutable              {offset,          }
+            => write!(stream, "Offset {offset:#X} is not in executable memory"),
+         Self::StringTooLong              {length, capacity,}
+            => write!(stream, "String needs {length} bytes, but the buffer only holds {capacity}"),
+         Self::InteriorNul                {position,        }
+            => write!(stream, "String contains a null character at position {position}"),
+         Self::BitFieldTooWide            {length,          }
+            => write!(stream, "Bit field is {length} bytes, but must be between 1 and 8 bytes"),
+         Self::BitMaskOutOfRange          {bit_mask, length,}
+            => write!(stream, "Bit mask {bit_mask:#X} is empty or doesn't fit in {length} bytes"),
+         Self::BitValueOutOfRange         {value, bit_mask, }
+            => write!(stream, "Value {value:#X} doesn't fit in bit mask {bit_mask:#X}"),
+         Self::ValidationFailed           {type_name,       }
+            => write!(stream, "Read value of type {type_name} failed validation"),
+         Self::PositionDependent
+            => write!(stream, "Writer's bytes depend on the address they are written to"),
+         Self::CoveredByNewerPatch        {address_range,   }
+            => write!(stream, "Patch at {:#X}..{:#X} is covered by a newer patch which must be restored first",
+               address_range.start, address_range.end,
+            ),
 
       };
    }
@@ -482,753 +2035,5467 @@ impl From<crate::sys::compiler::CompilationError> for PatchError {
    }
 }
 
-/////////////////////////
-// METHODS - Alignment //
-/////////////////////////
-
-impl Alignment {
-   /// Returns the amount of left
-   /// and right padding to insert
-   /// given a buffer byte count
-   /// and insert data byte count.
-   /// The returned tuple is the
-   /// amount of <b>elements</b>
-   /// to be inserted before and
-   /// after the source respectively.
-   /// If there are an uneven number
-   /// of bytes on either side or
-   /// a byte offset count too large
-   /// is passed in, an error is
-   /// returned.  This will panic
-   /// if the size of <code>T</code>
-   /// is zero
-   pub fn padding_count<T>(
-      & self,
-      buffer_byte_count : usize,
-      insert_byte_count : usize,
-   ) -> Result<(usize, usize)> {
-      if buffer_byte_count < insert_byte_count {
-         return Err(PatchError::LengthMismatch{
-            found    : insert_byte_count,
-            expected : buffer_byte_count,
-         });
-      }
-
-      let element_byte_size   = std::mem::size_of::<T>();
-      let padding_byte_count  = buffer_byte_count - insert_byte_count;
-
-      if element_byte_size == 0 {
-         panic!("Element byte size is zero");
-      }
-
-      let mut bytes_from_left    : Option<usize> = None;
-      let mut bytes_from_right   : Option<usize> = None;
-      match self {
-         Self::Left
-            => bytes_from_left   = Some(0),
-         Self::LeftOffset     {elements}
-            => bytes_from_left   = Some(*elements * element_byte_size),
-         Self::LeftByteOffset {bytes}
-            => bytes_from_left   = Some(*bytes),
-         Self::Right
-            => bytes_from_right  = Some(0),
-         Self::RightOffset    {elements}
-            => bytes_from_right  = Some(*elements * element_byte_size),
-         Self::RightByteOffset{bytes}
-            => bytes_from_right  = Some(*bytes),
-         Self::Center
-            => bytes_from_left   = Some(0),
-         Self::CenterByte
-            => bytes_from_right  = Some(buffer_byte_count / 2),
-      }
-
-      let mut bytes_left   : usize = 0;
-      let mut bytes_right  : usize = 0;
-      if let Some(bytes_from_left)  = bytes_from_left {
-         if bytes_from_left > padding_byte_count {
-            return Err(PatchError::OutOfRange{
-               maximum  : padding_byte_count,
-               provided : bytes_from_left,
-            });
-         }
-         bytes_left  = bytes_from_left;
-         bytes_right = padding_byte_count - bytes_from_left;
-      }
-      if let Some(bytes_from_right) = bytes_from_right {
-         if bytes_from_right > padding_byte_count {
-            return Err(PatchError::OutOfRange{
-               maximum  : padding_byte_count,
-               provided : bytes_from_right,
-            });
-         }
-         bytes_left  = padding_byte_count - bytes_from_right;
-         bytes_right = bytes_from_right;
-      }
-
-      let residual_bytes_left    = bytes_left   % element_byte_size;
-      let residual_bytes_right   = bytes_right  % element_byte_size;
-      if residual_bytes_left != 0 || residual_bytes_right != 0 {
-         return Err(PatchError::ResidualBytesDouble{
-            left  : residual_bytes_left,
-            right : residual_bytes_right,
-         });
-      }
+impl From<crate::sys::process::ProcessError> for PatchError {
+   fn from(
+      value : crate::sys::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         sys_error : value,
+      };
+   }
+}
 
-      let elements_left    = bytes_left   / element_byte_size;
-      let elements_right   = bytes_right  / element_byte_size;
+impl From<crate::base::offset::OffsetError> for PatchError {
+   fn from(
+      value : crate::base::offset::OffsetError,
+   ) -> Self {
+      use crate::base::offset::OffsetError;
 
-      return Ok((elements_left, elements_right));
+      return match value {
+         OffsetError::OutOfRange             {maximum, provided}
+            => Self::OutOfRange{maximum : maximum, provided : provided},
+      };
    }
+}
 
-   /// Fills a byte array with an
-   /// item surrounded by padding
-   /// values using the given
-   /// alignment.
-   pub fn clone_from_item_with_padding<T, U>(
-      & self,
-      buffer   : & mut [u8],
-      item     : T,
-      value    : U,
-   ) -> Result<& Self>
-   where U: Clone,
-   {
-      let size_of_t = std::mem::size_of::<T>();
-      let size_of_u = std::mem::size_of::<U>();
-
-      let (
-         pad_count_left,
-         pad_count_right,
-      ) = self.padding_count::<U>(
-         buffer.len(),
-         size_of_t,
-      )?;
- 
-      let byte_end_left    = pad_count_left * size_of_u;
-      let byte_end_slice   = byte_end_left + size_of_t;
-
-      // Fill left padding
-      unsafe{std::slice::from_raw_parts_mut(
-         buffer[
-            ..byte_end_left
-         ].as_ptr() as * mut U,
-         pad_count_left,
-      )}.fill(value.clone());
-
-      // Copy item
-      let dest = buffer[
-         byte_end_left..byte_end_slice
-      ].as_ptr() as * mut T;
-
-      unsafe{*dest = item};
- 
-      // Fill right padding
-      unsafe{std::slice::from_raw_parts_mut(
-         buffer[
-            byte_end_slice..
-         ].as_ptr() as * mut U,
-         pad_count_right,
-      )}.fill(value.clone());
-
-      return Ok(self);
-   }
-
-   /// Fills a byte array with a
-   /// slice type surrounded by
-   /// padding values using the
-   /// given alignment.
-   pub fn clone_from_slice_with_padding<T, U>(
-      & self,
-      buffer   : & mut [u8],
-      slice    : & [T],
-      value    : U,
-   ) -> Result<& Self>
-   where T: Clone,
-         U: Clone,
-   {
-      let size_of_t = std::mem::size_of::<T>();
-      let size_of_u = std::mem::size_of::<U>();
-
-      let (
-         pad_count_left,
-         pad_count_right,
-      ) = self.padding_count::<U>(
-         buffer.len(),
-         slice.len() * size_of_t,
-      )?;
- 
-      let byte_end_left    = pad_count_left * size_of_u;
-      let byte_end_slice   = byte_end_left + (slice.len() * size_of_t);
-
-      // Fill left padding
-      unsafe{std::slice::from_raw_parts_mut(
-         buffer[
-            ..byte_end_left
-         ].as_ptr() as * mut U,
-         pad_count_left,
-      )}.fill(value.clone());
- 
-      // Copy slice
-      unsafe{std::slice::from_raw_parts_mut(
-         buffer[
-            byte_end_left..byte_end_slice
-         ].as_ptr() as * mut T,
-         slice.len(),
-      )}.clone_from_slice(slice);
-
-      // Fill right padding
-      unsafe{std::slice::from_raw_parts_mut(
-         buffer[
-            byte_end_slice..
-         ].as_ptr() as * mut U,
-         pad_count_right,
-      )}.fill(value.clone());
-
-      return Ok(self);
+impl From<crate::base::alignment::AlignmentError> for PatchError {
+   fn from(
+      value : crate::base::alignment::AlignmentError,
+   ) -> Self {
+      use crate::base::alignment::AlignmentError;
+
+      return match value {
+         AlignmentError::LengthMismatch      {found, expected, }
+            => Self::LengthMismatch{found : found, expected : expected},
+         AlignmentError::OutOfRange          {maximum, provided}
+            => Self::OutOfRange{maximum : maximum, provided : provided},
+         AlignmentError::ResidualBytesDouble {left, right,     }
+            => Self::ResidualBytesDouble{left : left, right : right},
+         AlignmentError::ZeroLengthType
+            => Self::ZeroLengthType,
+      };
    }
 }
 
-///////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - Alignment //
-///////////////////////////////////////
-
-impl Default for Alignment {
-   fn default() -> Self {
-      return Self::Center;
+impl From<crate::sys::thread::ThreadError> for PatchError {
+   fn from(
+      value : crate::sys::thread::ThreadError,
+   ) -> Self {
+      return Self::ThreadError{
+         sys_error : value,
+      };
    }
 }
 
-////////////////////////
-// METHODS - Checksum //
-////////////////////////
-
-impl Checksum {
-   /// Creates a new checksum from
-   /// the provided byte data.
-   pub fn new(
-      data  : & [u8],
+impl From<crate::sys::exception::ExceptionError> for PatchError {
+   fn from(
+      value : crate::sys::exception::ExceptionError,
    ) -> Self {
-      let checksum = crc::Crc::<u32>::new(
-         &crc::CRC_32_CKSUM,
-      ).checksum(data);
+      return match value {
+         crate::sys::exception::ExceptionError::Fault{fault}
+            => Self::MemoryFault{fault : fault},
+         _  => Self::ExceptionError{sys_error : value},
+      };
+   }
+}
 
-      return Self{
-         checksum : checksum,
+impl From<crate::asm::AsmError> for PatchError {
+   fn from(
+      value : crate::asm::AsmError,
+   ) -> Self {
+      return Self::AsmError{
+         err : value,
       };
    }
+}
 
-   /// Creates a checksum from an
-   /// existing checksum value.
-   pub const fn from(
-      checksum : u32,
+impl From<crate::task::TaskError> for PatchError {
+   fn from(
+      value : crate::task::TaskError,
    ) -> Self {
-      return Self{
-         checksum : checksum,
+      return Self::TaskError{
+         err : value,
       };
    }
 }
 
-//////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - Checksum //
-//////////////////////////////////////
+impl From<crate::iat::IatError> for PatchError {
+   fn from(
+      value : crate::iat::IatError,
+   ) -> Self {
+      return Self::IatError{
+         err : value,
+      };
+   }
+}
 
-impl std::fmt::Display for Checksum {
-   fn fmt(
-      & self,
-      stream : & mut std::fmt::Formatter<'_>,
-   ) -> std::fmt::Result {
-      return write!(stream,
-         "{}",
-         self.checksum,
-      );
+impl From<crate::debug::DebugError> for PatchError {
+   fn from(
+      value : crate::debug::DebugError,
+   ) -> Self {
+      return Self::DebugError{
+         err : value,
+      };
    }
 }
 
 //////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - reader::Item //
+// TRAIT IMPLEMENTATIONS - TamperPolicy //
 //////////////////////////////////////////
 
-impl<
-   R: RangeBounds<usize>,
-   T: Copy,
-> Reader<R> for reader::Item<R, T> {
-   type Item = T;
+impl Default for TamperPolicy {
+   fn default() -> Self {
+      return Self::Ignore;
+   }
+}
 
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - StringTruncation //
+//////////////////////////////////////////////
+
+impl Default for StringTruncation {
+   fn default(
+   ) -> Self {
+      return Self::Error;
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - EditorCacheOptions //
+////////////////////////////////////////////////
+
+impl Default for EditorCacheOptions {
+   fn default(
+   ) -> Self {
+      return DEFAULT_EDITOR_CACHE_OPTIONS;
    }
+}
 
-   fn read_item(
+////////////////////////////////
+// METHODS - PlaceholderValue //
+////////////////////////////////
+
+impl PlaceholderValue {
+   /// Encodes the value for a template
+   /// which starts at the given address.
+   fn to_bytes(
       & self,
-      memory_buffer  : & [u8],
-   ) -> Result<Self::Item> {
-      let item_size = std::mem::size_of::<T>();
+      template_address  : usize,
+   ) -> Result<Vec<u8>> {
+      return Ok(match self {
+         Self::Byte(value)
+            => value.to_ne_bytes().to_vec(),
+         Self::Word(value)
+            => value.to_ne_bytes().to_vec(),
+         Self::DoubleWord(value)
+            => value.to_ne_bytes().to_vec(),
+         Self::QuadWord(value)
+            => value.to_ne_bytes().to_vec(),
+         Self::Relative{target_address, base_offset} => {
+            let base = template_address.wrapping_add(*base_offset);
+            let displacement = target_address.wrapping_sub(base) as isize;
+
+            i32::try_from(displacement).map_err(|_| PatchError::OutOfRange{
+               maximum  : i32::MAX as usize,
+               provided : displacement.unsigned_abs(),
+            })?.to_ne_bytes().to_vec()
+         },
+      });
+   }
+}
 
-      if memory_buffer.len() != item_size {
-         return Err(PatchError::LengthMismatch{
-            found    : memory_buffer.len(),
-            expected : item_size,
-         })
+/////////////////////////////
+// METHODS - reader::Tuple //
+/////////////////////////////
+
+impl<T: ReaderTuple> reader::Tuple<T> {
+   /// Creates a tuple reader spanning
+   /// every reader's memory offset range.
+   pub fn new(
+      readers : T,
+   ) -> Result<Self> {
+      let ranges = readers.memory_offset_ranges();
+      if ranges.iter().any(|range| range.end < range.start) == true {
+         return Err(PatchError::EndOffsetBeforeStartOffset);
       }
 
-      // This looks sketchy, but since we have
-      // the Copy trait bound and checked the
-      // length with the above code, this will
-      // always be valid given the memory buffer
-      // is also valid.
-      let item_ptr   = memory_buffer.as_ptr() as * const T;
-      let item       = unsafe{*item_ptr};
+      let start   = ranges.iter().map(|range| range.start).min().unwrap_or(0);
+      let end     = ranges.iter().map(|range| range.end).max().unwrap_or(0);
 
-      return Ok(item);
+      return Ok(Self{
+         memory_offset_range  : start..end,
+         readers              : readers,
+      });
    }
 }
 
-///////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - reader::Slice //
-///////////////////////////////////////////
+///////////////////////////////
+// METHODS - writer::Scatter //
+///////////////////////////////
 
-impl<
-   R: RangeBounds<usize>,
-   T: Copy,
-> Reader<R> for reader::Slice<R, T> {
-   type Item = Vec<T>;
+impl<'s> writer::Scatter<'s> {
+   /// Creates a scatter writer from a
+   /// list of writers.  The writers'
+   /// memory offset ranges must not
+   /// overlap each other.
+   pub fn new(
+      writers  : &'s [&'s dyn Writer<std::ops::Range<usize>>],
+   ) -> Result<Self> {
+      let mut ranges = writers.iter().map(|writer| {
+         writer.memory_offset_range().clone()
+      }).collect::<Vec<std::ops::Range<usize>>>();
+      ranges.sort_by_key(|range| range.start);
+
+      for range in ranges.iter() {
+         if range.end < range.start {
+            return Err(PatchError::EndOffsetBeforeStartOffset);
+         }
+      }
+      for pair in ranges.windows(2) {
+         if let [first, second] = pair {
+            if first.end > second.start {
+               return Err(PatchError::OverlappingRanges{
+                  first    : first.clone(),
+                  second   : second.clone(),
+               });
+            }
+         }
+      }
 
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+      let start   = ranges.first().ok_or(PatchError::NoWriters)?.start;
+      let end     = ranges.last().ok_or(PatchError::NoWriters)?.end;
+
+      // Identify the set by its checksums
+      let checksums = writers.iter().flat_map(|writer| {
+         writer.checksum().value().to_le_bytes()
+      }).collect::<Vec<u8>>();
+
+      return Ok(Self{
+         memory_offset_range  : start..end,
+         checksum             : Checksum::new(&checksums),
+         writers              : writers,
+      });
    }
 
-   fn read_item(
+   /// Converts a writer's memory offset
+   /// range into a range within the
+   /// scatter writer's memory buffer.
+   fn buffer_range(
       & self,
-      memory_buffer  : & [u8],
-   ) -> Result<Self::Item> {
-      let item_size  = std::mem::size_of::<T>();
-      let byte_count = self.element_count * item_size;
-
-      if memory_buffer.len() < byte_count {
-         return Err(PatchError::LengthMismatch{
-            found    : memory_buffer.len(),
-            expected : byte_count,
-         });
-      }
+      writer : & dyn Writer<std::ops::Range<usize>>,
+   ) -> std::ops::Range<usize> {
+      let base    = self.memory_offset_range.start;
+      let range   = writer.memory_offset_range();
+      return range.start.saturating_sub(base)..range.end.saturating_sub(base);
+   }
+}
 
-      if item_size == 0 {
-         return Ok(Vec::new());
-      }
+//////////////////////////////////
+// METHODS - writer::VTableHook //
+//////////////////////////////////
+
+impl writer::VTableHook {
+   /// Finds the address of the hooked
+   /// method's slot in the object's
+   /// virtual method table.
+   ///
+   /// <h2 id=  vtable_hook_slot_address_safety>
+   /// <a href=#vtable_hook_slot_address_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The object address must point to a
+   /// live object with a virtual method
+   /// table, and the method index must be
+   /// within the table.
+   pub unsafe fn slot_address(
+      & self,
+   ) -> Result<usize> {
+      let pointer_size = std::mem::size_of::<usize>();
 
-      let bytes_residual = memory_buffer.len() % item_size;
-      if bytes_residual != 0 {
-         return Err(PatchError::ResidualBytes{
-            residual : bytes_residual,
-         });
-      }
+      verify_readable(self.object_address, pointer_size, 0)?;
+      let table_address = std::ptr::read_unaligned(self.object_address as * const usize);
 
-      // Again, looks sketchy but the above code
-      // verifies this is sound
-      let item_slice = unsafe{std::slice::from_raw_parts(
-         memory_buffer.as_ptr() as * const T,
-         self.element_count,
-      )};
-      let item_vec   = item_slice.to_vec();
+      let slot_address = self.method_index.checked_mul(pointer_size)
+         .and_then(|offset| table_address.checked_add(offset))
+         .ok_or(PatchError::InvalidPointer{
+            step     : 1,
+            address  : table_address,
+         })?;
+      verify_readable(slot_address, pointer_size, 1)?;
 
-      return Ok(item_vec);
+      return Ok(slot_address);
    }
-}
 
-//////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::Item //
-//////////////////////////////////////////
+   /// Replaces the method pointer, returning
+   /// a container which holds the original
+   /// pointer so the hook can call through
+   /// to it.
+   ///
+   /// <h2 id=  vtable_hook_apply_safety>
+   /// <a href=#vtable_hook_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #vtable_hook_slot_address_safety>slot_address</a></code>
+   /// apply.  The replacement must be a function
+   /// with the exact signature and calling
+   /// convention of the original method, and
+   /// the table must outlive the container.
+   pub unsafe fn apply(
+      & self,
+   ) -> Result<VTableHookContainer> {
+      let slot_address  = self.slot_address()?;
+      let original      = swap_pointer(slot_address, self.replacement)?;
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-> Writer<R> for writer::Item<'s, R, T> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+      return Ok(SlotHookContainer::new(slot_address, original, self.replacement));
    }
+}
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   } 
-
-   fn build_patch(
+///////////////////////////////
+// METHODS - writer::IatHook //
+///////////////////////////////
+
+impl<'s> writer::IatHook<'s> {
+   /// Replaces the import address table
+   /// entry in the module, returning a
+   /// container which holds the original
+   /// address so the hook can call through
+   /// to it.
+   ///
+   /// <h2 id=  iat_hook_apply_safety>
+   /// <a href=#iat_hook_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The replacement must be a function
+   /// with the exact signature and calling
+   /// convention of the imported function.
+   /// The module must stay loaded while the
+   /// container is alive.
+   pub unsafe fn apply(
       & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      let item_size = std::mem::size_of::<T>();
+      module   : & crate::process::ModuleSnapshot,
+   ) -> Result<IatHookContainer> {
+      let slot_address  = crate::iat::import_slot_address(module, self.library, self.function)?;
+      let original      = crate::iat::swap_slot(slot_address, self.replacement)?;
 
-      if memory_buffer.len() != item_size {
-         return Err(PatchError::LengthMismatch{
-            found    : memory_buffer.len(),
-            expected : item_size,
-         });
-      }
+      return Ok(SlotHookContainer::new(slot_address, original, self.replacement));
+   }
+}
 
-      let destination = memory_buffer.as_mut_ptr() as * mut T;
+/////////////////////////////////
+// METHODS - writer::ChainHook //
+/////////////////////////////////
+
+impl<R: RangeBounds<usize>> writer::ChainHook<R> {
+   /// Links the hook into the chain at the
+   /// memory offset range, creating the
+   /// chain if there isn't one yet.
+   ///
+   /// <h2 id=  chain_hook_apply_safety>
+   /// <a href=#chain_hook_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code>Patch::patch_create</code>
+   /// apply.  The chain's stub is never freed,
+   /// so the hook may still be running on
+   /// another thread when the container is
+   /// dropped, and must stay loaded until it
+   /// returns.
+   pub unsafe fn apply<P: PatchTarget>(
+      & self,
+      target   : & P,
+   ) -> Result<HookChainContainer> {
+      let address_range = target.resolve_range(&self.memory_offset_range)?;
+      let _memory       = target.memory_editor(address_range.clone(), true)?;
+      let old_bytes     = read_guarded(&address_range)?;
+
+      let chain = match crate::sys::compiler::hook_chain_find(&old_bytes, address_range.start) {
+         Some(chain) => {
+            if chain.site_length() != old_bytes.len() || chain.jmp_back() != self.jmp_back {
+               return Err(PatchError::HookChainMismatch{
+                  length   : chain.site_length(),
+                  jmp_back : chain.jmp_back(),
+               });
+            }
+
+            let original = chain.original_bytes();
+            if self.checksum.matches(&original) == false {
+               return Err(PatchError::ChecksumMismatch{
+                  found    : Checksum::compute(self.checksum.algorithm(), &original),
+                  expected : self.checksum.clone(),
+               });
+            }
+
+            chain
+         },
+         None        => {
+            if self.checksum.matches(&old_bytes) == false {
+               return Err(PatchError::ChecksumMismatch{
+                  found    : Checksum::compute(self.checksum.algorithm(), &old_bytes),
+                  expected : self.checksum.clone(),
+               });
+            }
+            verify_instruction_boundary(&old_bytes, address_range.start)?;
+
+            let mut patched = old_bytes.clone();
+            let chain = crate::sys::compiler::hook_chain_fill(&mut patched, address_range.start, self.jmp_back)?;
+            commit_patch(address_range.start, &old_bytes, &patched, &[0..patched.len()], None)?;
+            chain
+         },
+      };
 
-      unsafe{*destination = self.item.clone()};
+      let index = chain.link(self.hook)?;
 
-      return Ok(());
+      return Ok(HookChainContainer{
+         restore : register_restore(address_range, RestoreChain{
+            chain : chain,
+            index : index,
+            hook  : self.hook,
+         }),
+      });
    }
 }
 
-//////////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::ItemFill //
-//////////////////////////////////////////////
+////////////////////////////////
+// METHODS - recipes::NopCall //
+////////////////////////////////
+
+impl recipes::NopCall {
+   /// Creates a writer which replaces the
+   /// call at the memory offset.  The call
+   /// is decoded from the patch target to
+   /// find its length, so both direct and
+   /// indirect calls can be replaced, and
+   /// the checksum must be computed over
+   /// the whole instruction.
+   ///
+   /// <h2 id=  nop_call_new_safety>
+   /// <a href=#nop_call_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_safety>Patch</a></code> apply.
+   pub unsafe fn new<T: PatchTarget>(
+      target         : & T,
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Result<Self> {
+      // Read as much as the longest possible
+      // instruction, stopping at the end of
+      // the patch target
+      let available  = target.resolve_range(&(memory_offset..))?;
+      let length     = usize::min(available.len(), MAXIMUM_INSTRUCTION_LENGTH);
+      let bytes      = target.patch_read(&reader::Slice::<_, u8>{
+         marker               : std::marker::PhantomData,
+         memory_offset_range  : memory_offset..memory_offset + length,
+         element_count        : length,
+      })?;
+
+      let length = crate::asm::decode_len(&bytes).ok_or(PatchError::UndecodableInstruction{
+         offset : memory_offset,
+      })?;
+
+      return Ok(Self{
+         memory_offset_range  : memory_offset..memory_offset + length,
+         checksum             : checksum,
+      });
+   }
+}
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-> Writer<R> for writer::ItemFill<'s, R, T> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+////////////////////////////////////
+// METHODS - recipes::ForceBranch //
+////////////////////////////////////
+
+impl recipes::ForceBranch {
+   /// Creates a writer which always takes
+   /// the conditional jump with a short
+   /// displacement at the memory offset.
+   pub fn taken_short(
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::new(memory_offset, crate::sys::compiler::BRANCH_SHORT_LENGTH, checksum, true);
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   /// Creates a writer which always takes
+   /// the conditional jump with a near
+   /// displacement at the memory offset.
+   pub fn taken_near(
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::new(memory_offset, crate::sys::compiler::BRANCH_NEAR_LENGTH, checksum, true);
+   }
 
-   fn build_patch(
-      & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      let item_size = std::mem::size_of::<T>();
+   /// Creates a writer which never takes
+   /// the conditional jump with a short
+   /// displacement at the memory offset.
+   pub fn skipped_short(
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::new(memory_offset, crate::sys::compiler::BRANCH_SHORT_LENGTH, checksum, false);
+   }
 
-      if item_size == 0 {
-         panic!("Item byte length is zero");
-      }
+   /// Creates a writer which never takes
+   /// the conditional jump with a near
+   /// displacement at the memory offset.
+   pub fn skipped_near(
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::new(memory_offset, crate::sys::compiler::BRANCH_NEAR_LENGTH, checksum, false);
+   }
 
-      let residual = memory_buffer.len() % item_size;
+   fn new(
+      memory_offset  : usize,
+      length         : usize,
+      checksum       : Checksum,
+      taken          : bool,
+   ) -> Self {
+      return Self{
+         memory_offset_range  : memory_offset..memory_offset + length,
+         checksum             : checksum,
+         taken                : taken,
+      };
+   }
+}
 
-      if residual != 0 {
-         return Err(PatchError::ResidualBytes{
-            residual : residual,
-         });
-      }
+///////////////////////////////////////
+// METHODS - recipes::ReturnConstant //
+///////////////////////////////////////
 
-      let element_buffer = unsafe{std::slice::from_raw_parts_mut(
-         memory_buffer.as_mut_ptr() as * mut T,
-         memory_buffer.len() / std::mem::size_of::<T>(),
-      )};
+impl recipes::ReturnConstant {
+   /// Creates a writer which returns a
+   /// constant integer from the function.
+   /// Smaller values take fewer bytes, so
+   /// the checksum must be computed over the
+   /// writer's <code>memory_offset_range</code>.
+   /// Negative values can be converted with
+   /// <code>as u64</code>.
+   pub fn new(
+      memory_offset  : usize,
+      value          : u64,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::with_value(memory_offset, Some(value), checksum);
+   }
 
-      element_buffer.fill(self.item.clone());
+   /// Creates a writer which returns
+   /// from a function without a return
+   /// value.
+   pub fn void(
+      memory_offset  : usize,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self::with_value(memory_offset, None, checksum);
+   }
 
-      return Ok(());
+   fn with_value(
+      memory_offset  : usize,
+      value          : Option<u64>,
+      checksum       : Checksum,
+   ) -> Self {
+      return Self{
+         memory_offset_range  : memory_offset..memory_offset + crate::sys::compiler::return_length(value),
+         checksum             : checksum,
+         value                : value,
+      };
    }
 }
 
-////////////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::ItemPadded //
-////////////////////////////////////////////////
+/////////////////////////////////
+// METHODS - SlotHookContainer //
+/////////////////////////////////
+
+impl SlotHookContainer {
+   // Registers the container so its
+   // slot is restored by revert_all
+   fn new(
+      slot_address   : usize,
+      original       : usize,
+      replacement    : usize,
+   ) -> Self {
+      return Self{
+         restore : register_restore(
+            slot_address..slot_address + std::mem::size_of::<usize>(),
+            RestoreSlot{
+               original    : original,
+               replacement : replacement,
+            },
+         ),
+      };
+   }
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-   U: Clone,
-> Writer<R> for writer::ItemPadded<'s, R, T, U> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+   /// Gets the address of the hooked
+   /// pointer slot.
+   pub fn slot_address(
+      & self,
+   ) -> usize {
+      return self.restore.address_range.start;
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   /// Gets the original pointer.
+   pub fn original(
+      & self,
+   ) -> usize {
+      return self.restore.undo.original;
+   }
 
-   fn build_patch(
+   /// Checks whether the slot still
+   /// holds the replacement pointer.
+   pub fn is_intact(
       & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      self.alignment.clone_from_item_with_padding(
-         memory_buffer,
-         self.item.clone(),
-         self.padding.clone(),
-      )?;
+   ) -> bool {
+      return self.restore.undo.is_intact(self.slot_address());
+   }
+}
 
-      return Ok(());
+//////////////////////////////////
+// METHODS - HookChainContainer //
+//////////////////////////////////
+
+impl HookChainContainer {
+   /// Gets the hook chain the
+   /// hook is linked into.
+   pub fn chain(
+      & self,
+   ) -> crate::sys::compiler::HookChain {
+      return self.restore.undo.chain;
    }
 }
 
-///////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::Slice //
-///////////////////////////////////////////
+////////////////////////////////
+// METHODS - view::StructView //
+////////////////////////////////
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-> Writer<R> for writer::Slice<'s, R, T> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+impl<'t, P: PatchTarget, T> view::StructView<'t, P, T> {
+   /// Creates a view of a struct at a
+   /// memory offset within a patch target.
+   /// The whole struct must be within the
+   /// target and aligned for its type.
+   pub fn new(
+      target         : &'t P,
+      memory_offset  : usize,
+   ) -> Result<Self> {
+      let view = Self{
+         target         : target,
+         memory_offset  : memory_offset,
+         checksum       : None,
+         marker         : Default::default(),
+      };
+      view.resolve::<T>(0)?;
+
+      return Ok(view);
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   /// Binds a checksum which the bytes
+   /// of the whole struct are verified
+   /// against before every access.
+   pub fn with_checksum(
+      mut self,
+      checksum : Checksum,
+   ) -> Self {
+      self.checksum = Some(checksum);
+      return self;
+   }
 
-   fn build_patch(
+   /// Gets the memory offset of the
+   /// struct within the patch target.
+   pub fn memory_offset(
+      & self,
+   ) -> usize {
+      return self.memory_offset;
+   }
+
+   /// Verifies the bytes of the whole
+   /// struct against the bound checksum.
+   /// Views without a checksum always pass.
+   ///
+   /// <h2 id=  struct_view_verify_safety>
+   /// <a href=#struct_view_verify_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_safety>Patch</a></code> apply.
+   pub unsafe fn verify(
       & self,
-      memory_buffer : & mut [u8],
    ) -> Result<()> {
-      let bytes = unsafe{std::slice::from_raw_parts(
-         self.slice.as_ptr() as * const u8,
-         self.slice.len() * std::mem::size_of::<T>(),
-      )};
+      let checksum = match &self.checksum {
+         Some(checksum) => checksum,
+         None           => return Ok(()),
+      };
 
-      if memory_buffer.len() != bytes.len() {
-         return Err(PatchError::LengthMismatch{
-            found    : bytes.len(),
-            expected : memory_buffer.len(),
+      let address_range = self.resolve::<T>(0)?;
+      let _memory       = self.target.memory_editor(address_range.clone(), false)?;
+      let bytes         = read_guarded(&address_range)?;
+
+      if checksum.matches(&bytes) == false {
+         return Err(PatchError::ChecksumMismatch{
+            found    : Checksum::compute(checksum.algorithm(), &bytes),
+            expected : checksum.clone(),
          });
       }
 
-      memory_buffer.clone_from_slice(bytes);
-
       return Ok(());
    }
-}
-
-///////////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::SliceFill //
-///////////////////////////////////////////////
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-> Writer<R> for writer::SliceFill<'s, R, T> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+   /// Reads the whole struct.
+   ///
+   /// <h2 id=  struct_view_read_safety>
+   /// <a href=#struct_view_read_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_safety>Patch</a></code> apply.
+   /// The bytes must be a valid value of
+   /// type <code>T</code>.
+   pub unsafe fn read(
+      & self,
+   ) -> Result<T>
+   where T: Copy,
+   {
+      return self.read_field::<T>(0);
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
-
-   fn build_patch(
+   /// Reads a field at a byte offset
+   /// within the struct.
+   ///
+   /// <h2 id=  struct_view_read_field_safety>
+   /// <a href=#struct_view_read_field_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_safety>Patch</a></code> apply.
+   /// The bytes must be a valid value of
+   /// type <code>F</code>.
+   pub unsafe fn read_field<F>(
       & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      let item_size = std::mem::size_of::<T>();
+      field_offset   : usize,
+   ) -> Result<F>
+   where F: Copy,
+   {
+      self.verify()?;
 
-      if item_size == 0 {
-         panic!("Item byte length is zero");
-      }
+      let address_range = self.resolve::<F>(field_offset)?;
+      let _memory       = self.target.memory_editor(address_range.clone(), false)?;
 
-      if memory_buffer.len() == 0 {
-         return Ok(());
-      }
+      // Faults while reading, such as the
+      // object being freed, become errors
+      let bytes         = read_guarded(&address_range)?;
 
-      if self.slice.len() == 0 {
-         return Err(PatchError::ZeroLengthType);
+      if bytes.len() != std::mem::size_of::<F>() {
+         return Err(PatchError::LengthMismatch{
+            found    : bytes.len(),
+            expected : std::mem::size_of::<F>(),
+         });
       }
 
-      let slice_len_bytes = self.slice.len() * item_size;
+      return Ok(bytes.as_ptr().cast::<F>().read_unaligned());
+   }
 
-      if memory_buffer.len() % slice_len_bytes != 0 {
-         return Err(PatchError::ResidualBytes{
-            residual : memory_buffer.len() % slice_len_bytes,
+   /// Writes a field at a byte offset
+   /// within the struct.
+   ///
+   /// <h2 id=  struct_view_write_field_safety>
+   /// <a href=#struct_view_write_field_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_safety>Patch</a></code> apply.
+   /// If the view has a checksum, it will
+   /// no longer match after the write.
+   pub unsafe fn write_field<F>(
+      & self,
+      field_offset   : usize,
+      value          : F,
+   ) -> Result<()>
+   where F: Copy,
+   {
+      self.verify()?;
+
+      let address_range = self.resolve::<F>(field_offset)?;
+      let _memory       = self.target.memory_editor(address_range.clone(), true)?;
+
+      let bytes = std::slice::from_raw_parts(
+         &value as * const F as * const u8,
+         std::mem::size_of::<F>(),
+      );
+
+      if bytes.len() != address_range.len() {
+         return Err(PatchError::LengthMismatch{
+            found    : address_range.len(),
+            expected : bytes.len(),
          });
       }
 
-      // This is how the sausage is made
-      // Have to create Vec copies so we
-      // call clone() and can still access
-      // the raw bytes.  Before you ask,
-      // std::slice::clone_from_slice()
-      // doesn't work for this use case.
-      let mut memory_buffer_view = & mut memory_buffer[..];
-      while memory_buffer_view.len() != 0 {
-         // Clone slice elements and convert to byte slice
-         let slice_clone = self.slice.to_vec();
-         let slice_clone = unsafe{std::slice::from_raw_parts(
-            slice_clone.as_ptr() as * const u8,
-            slice_len_bytes,
-         )};
+      write_guarded(address_range.start, bytes, &[0..bytes.len()], None, 0..bytes.len())?;
+      return Ok(());
+   }
+
+   /// Creates a view of a nested struct
+   /// stored in a field at a byte offset
+   /// within the struct.  The nested view
+   /// has no checksum.
+   pub fn view<U>(
+      & self,
+      field_offset   : usize,
+   ) -> Result<view::StructView<'t, P, U>> {
+      self.resolve::<U>(field_offset)?;
+
+      return Ok(view::StructView{
+         target         : self.target,
+         memory_offset  : self.field_memory_offset(field_offset)?,
+         checksum       : None,
+         marker         : Default::default(),
+      });
+   }
+}
 
-         // Copy to the beginning of the buffer view
-         memory_buffer_view[..slice_clone.len()].copy_from_slice(slice_clone);
+/////////////////////////////////////////
+// INTERNAL METHODS - view::StructView //
+/////////////////////////////////////////
 
-         // Isolate the buffer view to cut off the written bytes
-         memory_buffer_view = & mut memory_buffer_view[slice_clone.len()..];
+impl<'t, P: PatchTarget, T> view::StructView<'t, P, T> {
+   /// Resolves the address range of a field
+   /// of type <code>F</code>, checking it lies
+   /// within the struct and is aligned.
+   fn resolve<F>(
+      & self,
+      field_offset   : usize,
+   ) -> Result<std::ops::Range<usize>> {
+      let field_size = std::mem::size_of::<F>();
+      let field_end  = Offset::new(field_offset).to_range(field_size)?.end;
+
+      if field_end > std::mem::size_of::<T>() {
+         return Err(PatchError::OutOfRange{
+            maximum  : std::mem::size_of::<T>(),
+            provided : field_end,
+         });
       }
 
-      return Ok(());
+      let offset_range  = Offset::new(self.field_memory_offset(field_offset)?).to_range(field_size)?;
+      let address_range = self.target.resolve_range(&offset_range)?;
+
+      let alignment = std::mem::align_of::<F>();
+      if address_range.start % alignment != 0 {
+         return Err(PatchError::Misaligned{
+            address     : address_range.start,
+            alignment   : alignment,
+         });
+      }
+
+      return Ok(address_range);
+   }
+
+   /// Gets the memory offset of a field
+   /// within the patch target, failing
+   /// instead of wrapping around.
+   fn field_memory_offset(
+      & self,
+      field_offset   : usize,
+   ) -> Result<usize> {
+      return Ok((Offset::new(self.memory_offset) + field_offset)?.get());
    }
 }
 
-/////////////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::SlicePadded //
-/////////////////////////////////////////////////
+///////////////////////////
+// METHODS - PatchMemory //
+///////////////////////////
+
+impl PatchMemory {
+   /// Opens the memory in an address
+   /// range for reading, and also for
+   /// writing if <code>writable</code>
+   /// is true.  Memory opened only for
+   /// reading goes through the editor cache.
+   pub fn open(
+      address_range  : std::ops::Range<usize>,
+      writable       : bool,
+   ) -> Result<Self> {
+      if writable == false {
+         if let Some(session) = editor_cache_open(&address_range)? {
+            return Ok(Self::cached(session));
+         }
+      }
 
-impl<
-   's,
-   R: RangeBounds<usize>,
-   T: Clone,
-   U: Clone,
-> Writer<R> for writer::SlicePadded<'s, R, T, U> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+      let write   = writable.then(|| editor_cache_begin_write(&address_range));
+      let editor  = match writable {
+         true  => crate::sys::memory::MemoryEditor::open_read_write(address_range.clone())?,
+         false => crate::sys::memory::MemoryEditor::open_read(address_range.clone())?,
+      };
+
+      return Ok(Self::owned(editor, write));
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   /// Opens memory which other code may be
+   /// using at the same time, such as the
+   /// heap, without taking away any access
+   /// its pages already have.  Opening a
+   /// writable page with <code>open</code>
+   /// for reading would make it read-only,
+   /// crashing any other thread which writes
+   /// to the same page in the meantime.
+   pub fn open_shared(
+      address_range  : std::ops::Range<usize>,
+      writable       : bool,
+   ) -> Result<Self> {
+      if writable == false {
+         if let Some(session) = editor_cache_open(&address_range)? {
+            return Ok(Self::cached(session));
+         }
+      }
 
-   fn build_patch(
-      & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      self.alignment.clone_from_slice_with_padding(
-         memory_buffer,
-         self.slice,
-         self.padding.clone(),
-      )?;
+      let write   = writable.then(|| editor_cache_begin_write(&address_range));
+      let editor  = open_shared_editor(address_range.clone(), writable)?;
+      return Ok(Self::owned(editor, write));
+   }
+}
 
-      return Ok(());
+////////////////////////////////////
+// INTERNAL METHODS - PatchMemory //
+////////////////////////////////////
+
+impl PatchMemory {
+   // The editor is declared before the write
+   // so it's restored before the cache can
+   // open the pages again
+   fn owned(
+      editor         : crate::sys::memory::MemoryEditor,
+      write          : Option<EditorCacheWrite>,
+   ) -> Self {
+      return Self{
+         _session       : std::sync::Arc::new(editor),
+         _write         : write,
+      };
+   }
+
+   fn cached(
+      session        : std::sync::Arc<crate::sys::memory::MemoryEditor>,
+   ) -> Self {
+      return Self{
+         _session       : session,
+         _write         : None,
+      };
    }
 }
 
 /////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::Nop //
+// TRAIT IMPLEMENTATIONS - PatchTarget //
 /////////////////////////////////////////
 
-impl<
-   R: RangeBounds<usize>,
-> Writer<R> for writer::Nop<R> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+impl<T: PatchTarget> Patch for T {
+   type Container = PatchContainer;
+
+   unsafe fn patch_read<Rd, Mr>(
+      & self,
+      reader : & Rd,
+   ) -> Result<Rd::Item>
+   where Rd: Reader<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         reader.memory_offset_range(),
+      )?;
+
+      let _memory = self.memory_editor(address_range.clone(), false)?;
+
+      // Faults while accessing the memory, such
+      // as another thread unmapping it, become
+      // errors instead of crashing the game
+      let bytes = read_guarded(&address_range)?;
+
+      return reader.read_item(&bytes);
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   unsafe fn patch_write<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<()>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
 
-   fn build_patch(
-      & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      crate::sys::compiler::nop_fill(
-         memory_buffer,
+      let _memory = self.memory_editor(address_range.clone(), true)?;
+
+      let old_bytes = read_guarded(&address_range)?;
+      writer.verify_checksum(&old_bytes, address_range.start)?;
+
+      let mut patched = old_bytes.clone();
+      let warnings = writer.build_patch_warnings(&mut patched, address_range.start)?;
+
+      commit_patch(
+         address_range.start,
+         &old_bytes,
+         &patched,
+         &writer.overwritten_ranges(patched.len()),
+         writer.restore_mask(patched.len()).as_deref(),
       )?;
+
+      log_warnings(warnings);
       return Ok(());
    }
-}
 
-//////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::Hook //
-//////////////////////////////////////////
+   unsafe fn patch_write_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<()>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
 
-impl<
-   R: RangeBounds<usize>,
-> Writer<R> for writer::Hook<R> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
-   }
+      let _memory = self.memory_editor(address_range.clone(), true)?;
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+      let old_bytes = read_guarded(&address_range)?;
 
-   fn build_patch(
-      & self,
-      memory_buffer : & mut [u8],
-   ) -> Result<()> {
-      crate::sys::compiler::hook_fill(
-         memory_buffer,
-         self.hook,
+      let mut patched = old_bytes.clone();
+      let warnings = writer.build_patch_warnings(&mut patched, address_range.start)?;
+
+      commit_patch(
+         address_range.start,
+         &old_bytes,
+         &patched,
+         &writer.overwritten_ranges(patched.len()),
+         writer.restore_mask(patched.len()).as_deref(),
       )?;
+
+      log_warnings(warnings);
       return Ok(());
    }
-}
 
-/////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - writer::Asm //
-/////////////////////////////////////////
+   unsafe fn patch_create<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
 
-impl<
-   R: RangeBounds<usize>,
-> Writer<R> for writer::Asm<R> {
-   fn memory_offset_range<'l>(
-      &'l self,
-   ) -> &'l R {
-      return & self.memory_offset_range;
+      let _memory = self.memory_editor(address_range.clone(), true)?;
+
+      let old_bytes = read_guarded(&address_range)?;
+      writer.verify_checksum(&old_bytes, address_range.start)?;
+
+      return create_patch(writer, address_range, old_bytes);
    }
 
-   fn checksum<'l>(
-      &'l self,
-   ) -> &'l Checksum {
-      return & self.checksum;
-   }  
+   unsafe fn patch_create_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
 
-   fn build_patch(
-      & self,
-      memory_buffer : & mut [u8],
+      let _memory = self.memory_editor(address_range.clone(), true)?;
+
+      let old_bytes = read_guarded(&address_range)?;
+
+      return create_patch(writer, address_range, old_bytes);
+   }
+
+   unsafe fn patch_create_atomic<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
+
+      let _memory = self.memory_editor(address_range.clone(), true)?;
+
+      // The whole patch is built before
+      // suspending, since a suspended thread
+      // may be holding a lock the writer
+      // needs, such as the heap lock for
+      // allocating a stub or the logger's
+      // lock.  Only the guarded copy runs
+      // while the threads are suspended.
+      let old_bytes = read_guarded(&address_range)?;
+      writer.verify_checksum(&old_bytes, address_range.start)?;
+
+      let mut patched = old_bytes.clone();
+      let warnings = writer.build_patch_warnings(&mut patched, address_range.start)?;
+
+      let restore_ranges   = writer.overwritten_ranges(old_bytes.len());
+      let restore_mask     = writer.restore_mask(old_bytes.len());
+
+      let threads = suspend_threads_outside(&address_range)?;
+
+      let result = commit_patch(
+         address_range.start,
+         &old_bytes,
+         &patched,
+         &restore_ranges,
+         restore_mask.as_deref(),
+      ).and_then(|_| {
+         return Ok(crate::sys::thread::flush_instruction_cache(address_range.clone())?);
+      });
+
+      std::mem::drop(threads);
+      result?;
+
+      log_warnings(warnings);
+      return Ok(PatchContainer::new(address_range, old_bytes, restore_ranges, restore_mask));
+   }
+
+   unsafe fn patch_write_batch(
+      & mut self,
+      writers : & [& dyn Writer<std::ops::Range<usize>>],
    ) -> Result<()> {
-      // Verify the ASM will fit into the buffer
-      if memory_buffer.len() < self.asm_bytes.len() {
-         return Err(PatchError::LengthMismatch{
-            found    : self.asm_bytes.len(),
-            expected : memory_buffer.len(),
+      patch_batch(self, writers)?;
+      return Ok(());
+   }
+
+   unsafe fn patch_create_batch(
+      & mut self,
+      writers : & [& dyn Writer<std::ops::Range<usize>>],
+   ) -> Result<Vec<Self::Container>> {
+      let patched = patch_batch(self, writers)?;
+
+      return Ok(writers.iter().zip(patched).map(|(writer, (address_range, old_bytes))| {
+         let overwritten_ranges  = writer.overwritten_ranges(old_bytes.len());
+         let restore_mask        = writer.restore_mask(old_bytes.len());
+         return PatchContainer::new(address_range, old_bytes, overwritten_ranges, restore_mask);
+      }).collect());
+   }
+
+   unsafe fn patch_create_chunked<Wt, Mr, F>(
+      & mut self,
+      writer      : & Wt,
+      chunk_size  : usize,
+      progress    : F,
+   ) -> Result<Self::Container>
+   where Wt: Writer<Mr>,
+         Mr: RangeBounds<usize>,
+         F:  FnMut(ChunkProgress) -> ChunkAction,
+   {
+      let mut progress = progress;
+
+      let address_range = self.resolve_range(
+         writer.memory_offset_range(),
+      )?;
+
+      let _memory = self.memory_editor(address_range.clone(), true)?;
+
+      // Build the whole patch before writing
+      // anything, so a failing writer leaves
+      // the memory untouched
+      let old_bytes = read_guarded(&address_range)?;
+      writer.verify_checksum(&old_bytes, address_range.start)?;
+
+      let mut patched = old_bytes.clone();
+      let warnings = writer.build_patch_warnings(&mut patched, address_range.start)?;
+
+      let restore_ranges   = writer.overwritten_ranges(old_bytes.len());
+      let restore_mask     = writer.restore_mask(old_bytes.len());
+
+      // A chunk size of zero writes
+      // everything in one chunk
+      let chunk_size = match chunk_size {
+         0 => usize::MAX,
+         _ => chunk_size,
+      };
+
+      // Masked bits are written a naturally
+      // aligned integer at a time, so chunks
+      // end on an 8-byte boundary to keep each
+      // integer within a single atomic write
+      let chunk_alignment = match restore_mask {
+         Some(_)  => std::mem::size_of::<u64>(),
+         None     => 1,
+      };
+
+      let mut readback        = vec![0u8; usize::min(chunk_size.saturating_add(chunk_alignment - 1), patched.len())];
+      for chunk_range in chunk_ranges(address_range.start, patched.len(), chunk_size, chunk_alignment) {
+         let bytes_written = chunk_range.end;
+
+         let result = write_guarded(
+            address_range.start,
+            &patched,
+            &restore_ranges,
+            restore_mask.as_deref(),
+            chunk_range.clone(),
+         ).and_then(|_| {
+            // Read the chunk back through the OS
+            // so the comparison can't be optimized
+            // into comparing the patch with itself
+            let readback = readback.get_mut(..chunk_range.len()).unwrap_or_default();
+            crate::sys::memory::read_checked(address_range.start + chunk_range.start, readback)?;
+            if chunk_matches(readback, &patched, &chunk_range, &restore_ranges, restore_mask.as_deref()) == false {
+               return Err(PatchError::ChunkMismatch{
+                  offset : chunk_range.start,
+               });
+            }
+
+            return Ok(());
          });
-      }
 
-      // Byte padding count
-      let padding_bytes_left = self.alignment.padding_count::<u8>(
-         memory_buffer.len(),
-         self.asm_bytes.len(),
-      )?.0;
+         let result = result.and_then(|_| {
+            return match progress(ChunkProgress{
+               bytes_written  : bytes_written,
+               bytes_total    : patched.len(),
+            }) {
+               ChunkAction::Continue   => Ok(()),
+               ChunkAction::Cancel     => Err(PatchError::Cancelled{
+                  bytes_written : bytes_written,
+               }),
+            };
+         });
 
-      // Copy the ASM bytes
-      memory_buffer[
-         padding_bytes_left..padding_bytes_left+self.asm_bytes.len()
-      ].copy_from_slice(self.asm_bytes);
+         if let Err(err) = result {
+            // Nothing after the written chunks
+            // has been touched yet
+            if let Err(restore_err) = write_guarded(
+               address_range.start,
+               &old_bytes,
+               &restore_ranges,
+               restore_mask.as_deref(),
+               0..bytes_written,
+            ) {
+               crate::log::error!("Failed to restore partially patched bytes at {:#X}: {restore_err}", address_range.start);
+            }
+
+            return Err(err);
+         }
+      }
 
-      // Build the padding instructions
-      crate::sys::compiler::nop_fill(& mut memory_buffer[
-         ..padding_bytes_left
-      ])?;
-      crate::sys::compiler::nop_fill(& mut memory_buffer[
-         padding_bytes_left+self.asm_bytes.len()..
-      ])?;
+      log_warnings(warnings);
 
-      return Ok(());
+      // Only create the container once the
+      // whole patch is written, so it never
+      // restores bytes a second time
+      return Ok(PatchContainer::new(address_range, old_bytes, restore_ranges, restore_mask));
    }
 }
 
+//////////////////////////////
+// METHODS - PatchContainer //
+//////////////////////////////
+
+impl PatchContainer {
+   // Registers the container so its
+   // patch is reverted by revert_all
+   fn new(
+      address_range  : std::ops::Range<usize>,
+      old_bytes      : Vec<u8>,
+      restore_ranges : Vec<std::ops::Range<usize>>,
+      restore_mask   : Option<Vec<u8>>,
+   ) -> Self {
+      return Self{
+         restore : register_restore(address_range, RestoreBytes{
+            restored       : restore_ranges.iter().map(|_| std::sync::atomic::AtomicBool::new(false)).collect(),
+            old_bytes      : old_bytes,
+            restore_ranges : restore_ranges,
+            restore_mask   : restore_mask,
+         }),
+      };
+   }
+
+   /// Gets the address range the patch
+   /// was written to.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.restore.address_range;
+   }
+
+   /// Checks whether the patch is still
+   /// applied, which is false once it has
+   /// been reverted by <code>revert_all</code>.
+   pub fn is_active(
+      & self,
+   ) -> bool {
+      return self.restore.active.load(std::sync::atomic::Ordering::Acquire);
+   }
+
+   /// Checks whether restoring the patch
+   /// has failed at least once, such as
+   /// when the memory permissions couldn't
+   /// be changed.  A poisoned patch stays
+   /// applied until restoring succeeds.
+   pub fn is_poisoned(
+      & self,
+   ) -> bool {
+      return self.restore.poisoned.load(std::sync::atomic::Ordering::Acquire);
+   }
+
+   /// Restores the overwritten bytes now
+   /// instead of when the container is
+   /// dropped, so a failure can be handled
+   /// by the caller.  If restoring fails,
+   /// the container is marked poisoned and
+   /// the patch stays applied, so this can
+   /// be tried again.  Restoring a patch
+   /// which is no longer active does nothing,
+   /// and restoring one which is covered by
+   /// a newer overlapping patch fails.
+   pub fn restore(
+      & self,
+   ) -> Result<()> {
+      return restore_registered(&self.restore);
+   }
+
+   /// Reads the bytes currently stored in
+   /// the ranges overwritten by the patch,
+   /// in order.  Comparing this against an
+   /// earlier read detects whether something
+   /// else has written over the patch.
+   pub fn read_overwritten(
+      & self,
+   ) -> Result<Vec<u8>> {
+      // The patch is usually live code, so
+      // it must stay executable while it's
+      // read from another thread
+      let _memory = PatchMemory::open_shared(
+         self.restore.address_range.clone(),
+         false,
+      )?;
+
+      let bytes = unsafe{read_guarded(&self.restore.address_range)}?;
+
+      let mut overwritten = Vec::new();
+      for range in self.restore.undo.restore_ranges.iter() {
+         overwritten.extend_from_slice(subslice(&bytes, range.clone())?);
+      }
+
+      return Ok(overwritten);
+   }
+}
+
+///////////////////////////////////////
+// INTERNAL METHODS - PatchContainer //
+///////////////////////////////////////
+
+impl PatchContainer {
+   // Gets the address ranges of the bytes
+   // the patch overwrote
+   fn overwritten_address_ranges(
+      & self,
+   ) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+      let start = self.restore.address_range.start;
+      return self.restore.undo.restore_ranges.iter().map(move |range| {
+         return start + range.start..start + range.end;
+      });
+   }
+}
+
+///////////////////////////
+// METHODS - RestoreSlot //
+///////////////////////////
+
+impl RestoreSlot {
+   // Checks whether the slot still
+   // holds the replacement pointer
+   fn is_intact(
+      & self,
+      slot_address : usize,
+   ) -> bool {
+      let found = unsafe{std::ptr::read_volatile(slot_address as * const usize)};
+      return found == self.replacement;
+   }
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatchContainer //
+////////////////////////////////////////////
+
+impl std::ops::Drop for PatchContainer {
+   fn drop(
+      & mut self,
+   ) {
+      release_restore(&self.restore);
+      return;
+   }
+}
+
+///////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - SlotHookContainer //
+///////////////////////////////////////////////
+
+impl std::ops::Drop for SlotHookContainer {
+   fn drop(
+      & mut self,
+   ) {
+      release_restore(&self.restore);
+      return;
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - HookChainContainer //
+////////////////////////////////////////////////
+
+impl std::ops::Drop for HookChainContainer {
+   fn drop(
+      & mut self,
+   ) {
+      release_restore(&self.restore);
+      return;
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatchRestore //
+//////////////////////////////////////////
+
+impl<U: RestoreAction> RegisteredPatch for PatchRestore<U> {
+   fn address_range(
+      & self,
+   ) -> & std::ops::Range<usize> {
+      return &self.address_range;
+   }
+
+   fn is_active(
+      & self,
+   ) -> bool {
+      return self.active.load(std::sync::atomic::Ordering::Acquire);
+   }
+
+   fn linked_chain(
+      & self,
+   ) -> Option<crate::sys::compiler::HookChain> {
+      return self.undo.linked_chain();
+   }
+
+   // Restores the container's changes unless
+   // this or another thread already did.  On
+   // failure the patch is left active and
+   // marked poisoned so it can be retried.
+   fn restore(
+      & self,
+   ) -> Result<()> {
+      if self.active.swap(false, std::sync::atomic::Ordering::AcqRel) == false {
+         return Ok(());
+      }
+
+      let result = self.undo.undo(&self.address_range);
+      if result.is_err() == true {
+         self.poisoned.store(true, std::sync::atomic::Ordering::Release);
+         self.active.store(true, std::sync::atomic::Ordering::Release);
+      }
+
+      return result;
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RestoreBytes //
+//////////////////////////////////////////
+
+impl RestoreAction for RestoreBytes {
+   // Panicking here would unwind through
+   // whichever thread dropped the patch,
+   // so every failure is returned instead,
+   // including failing to restore the
+   // memory permissions
+   fn undo(
+      & self,
+      address_range : & std::ops::Range<usize>,
+   ) -> Result<()> {
+      let _write = editor_cache_begin_write(address_range);
+      let editor = crate::sys::memory::MemoryEditor::open_read_write(
+         address_range.clone(),
+      )?;
+
+      let result : Result<()> = self.restore_ranges.iter().zip(self.restored.iter()).try_for_each(|(range, restored)| {
+         if restored.load(std::sync::atomic::Ordering::Acquire) == true {
+            return Ok(());
+         }
+
+         unsafe{write_guarded(
+            address_range.start,
+            &self.old_bytes,
+            &self.restore_ranges,
+            self.restore_mask.as_deref(),
+            range.clone(),
+         )}?;
+         restored.store(true, std::sync::atomic::Ordering::Release);
+         return Ok(());
+      });
+
+      let closed = editor.close();
+      result?;
+      closed?;
+      return Ok(());
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RestoreSlot //
+/////////////////////////////////////////
+
+impl RestoreAction for RestoreSlot {
+   fn undo(
+      & self,
+      address_range : & std::ops::Range<usize>,
+   ) -> Result<()> {
+      // Don't clobber something else's hook
+      if self.is_intact(address_range.start) == false {
+         return Ok(());
+      }
+
+      unsafe{swap_pointer(address_range.start, self.original)}?;
+      return Ok(());
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RestoreChain //
+//////////////////////////////////////////
+
+impl RestoreAction for RestoreChain {
+   fn undo(
+      & self,
+      _address_range : & std::ops::Range<usize>,
+   ) -> Result<()> {
+      self.chain.unlink(self.index, self.hook)?;
+      return Ok(());
+   }
+
+   fn linked_chain(
+      & self,
+   ) -> Option<crate::sys::compiler::HookChain> {
+      return Some(self.chain);
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - EditorCacheWrite //
+//////////////////////////////////////////////
+
+impl std::ops::Drop for EditorCacheWrite {
+   fn drop(
+      & mut self,
+   ) {
+      let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+      if let Some(index) = cache.writers.iter().position(|writer| *writer == self.page_range) {
+         cache.writers.swap_remove(index);
+      }
+      return;
+   }
+}
+
+//////////////////////////
+// METHODS - PatchGroup //
+//////////////////////////
+
+impl PatchGroup {
+   /// Creates an empty, disabled patch
+   /// group with the given name.
+   pub fn new(
+      name : & str,
+   ) -> Self {
+      return Self{
+         name        : String::from(name),
+         members     : Vec::new(),
+         enabled     : false,
+         policy      : TamperPolicy::Ignore,
+         watch       : false,
+         watchpoints : Vec::new(),
+         written     : std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+      };
+   }
+
+   /// Adds a writer for the given module
+   /// to the group.  The writer is not
+   /// applied until the group is next
+   /// enabled, even if the group is
+   /// currently enabled.
+   pub fn add<W, R>(
+      & mut self,
+      module_name : & str,
+      writer      : W,
+   ) -> & mut Self
+   where W: Writer<R> + Send + Sync + 'static,
+         R: RangeBounds<usize>,
+   {
+      self.members.push(PatchGroupMember{
+         module_name : String::from(module_name),
+         // Only ever called from enable, whose
+         // caller upholds the safety concerns
+         apply       : Box::new(move |module| {
+            return unsafe{module.patch_create(&writer)};
+         }),
+         container   : None,
+         patched     : Vec::new(),
+      });
+      return self;
+   }
+
+   /// Applies every member of the group
+   /// in the order they were added.  If
+   /// a member fails, the members already
+   /// applied are reverted in reverse order
+   /// and the error is returned.  Does
+   /// nothing if the group is already
+   /// enabled.
+   ///
+   /// <h2 id=  patch_group_enable_safety>
+   /// <a href=#patch_group_enable_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_create</code> apply
+   /// for every member of the group.
+   pub unsafe fn enable(
+      & mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> Result<()> {
+      if self.enabled == true {
+         return Ok(());
+      }
+
+      for member in self.members.iter_mut() {
+         let result = member.execute(modules).and_then(|container| {
+            // Remember what was written so it
+            // can be checked for tampering
+            member.patched = container.read_overwritten()?;
+            member.container = Some(container);
+            return Ok(());
+         });
+
+         if let Err(err) = result {
+            self.revert();
+            return Err(err);
+         }
+      }
+
+      self.enabled = true;
+
+      // Failing to watch the group leaves it
+      // enabled, polled like an unwatched one
+      if let Err(err) = self.arm_watch() {
+         crate::log::warn!("Failed to watch patch group \"{}\" for tampering: {err}", self.name);
+      }
+
+      return Ok(());
+   }
+
+   /// Reverts every member of the group
+   /// in reverse order.  Does nothing if
+   /// the group is already disabled.
+   pub fn disable(
+      & mut self,
+   ) {
+      self.revert();
+      self.enabled = false;
+      return;
+   }
+
+   /// Enables the group if it is disabled
+   /// and disables it otherwise, returning
+   /// whether the group is now enabled.
+   ///
+   /// <h2 id=  patch_group_toggle_safety>
+   /// <a href=#patch_group_toggle_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_group_enable_safety>enable</a></code>
+   /// apply.
+   pub unsafe fn toggle(
+      & mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> Result<bool> {
+      if self.enabled == true {
+         self.disable();
+      } else {
+         self.enable(modules)?;
+      }
+
+      return Ok(self.enabled);
+   }
+
+   /// Applies or reverts the group to
+   /// match the given state.
+   ///
+   /// <h2 id=  patch_group_set_enabled_safety>
+   /// <a href=#patch_group_set_enabled_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_group_enable_safety>enable</a></code>
+   /// apply.
+   pub unsafe fn set_enabled(
+      & mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+      enabled  : bool,
+   ) -> Result<()> {
+      if enabled == true {
+         return self.enable(modules);
+      }
+
+      self.disable();
+      return Ok(());
+   }
+
+   /// Sets what happens when
+   /// <code>check_tampering</code> finds
+   /// the group's patched bytes have been
+   /// overwritten.  The default is
+   /// <code>TamperPolicy::Ignore</code>.
+   pub fn set_tamper_policy(
+      & mut self,
+      policy   : TamperPolicy,
+   ) -> & mut Self {
+      self.policy = policy;
+      return self;
+   }
+
+   /// Gets the group's tamper policy.
+   pub fn tamper_policy(
+      & self,
+   ) -> TamperPolicy {
+      return self.policy;
+   }
+
+   /// Sets whether the group's patched bytes
+   /// are watched with hardware breakpoints
+   /// while it is enabled, so every write to
+   /// them is caught, even one which is undone
+   /// before <code>check_tampering</code> is
+   /// next called.  There are only four
+   /// breakpoints of up to 8 aligned bytes
+   /// each, shared with <code>debug::Watchpoint</code>,
+   /// so this fails if the patched bytes need
+   /// more than are free.  If the group can't
+   /// be watched when it is next enabled, a
+   /// warning is logged and it is compared
+   /// instead.
+   ///
+   /// <h2 id=  patch_group_set_tamper_watch_safety>
+   /// <a href=#patch_group_set_tamper_watch_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// ../debug/struct.Watchpoint.html#watchpoint_new_safety>debug::Watchpoint::new</a></code>
+   /// apply.
+   pub unsafe fn set_tamper_watch(
+      & mut self,
+      watch : bool,
+   ) -> Result<()> {
+      self.watch = watch;
+      return self.arm_watch();
+   }
+
+   /// Checks whether the group's patched
+   /// bytes are being watched with hardware
+   /// breakpoints.
+   pub fn is_tamper_watched(
+      & self,
+   ) -> bool {
+      return self.watchpoints.is_empty() == false;
+   }
+
+   /// Checks whether any of the bytes
+   /// written by the group have since been
+   /// overwritten by the game or another
+   /// tool, logging a warning and then
+   /// reapplying or disabling the group
+   /// according to its tamper policy.
+   /// Returns whether tampering was found.
+   /// If the group is watched, this reports
+   /// every write caught since the last call.
+   /// Otherwise it compares the patched bytes
+   /// against a copy taken when the group was
+   /// enabled, which misses writes undone in
+   /// between.  Either way it should be called
+   /// periodically, such as once per iteration
+   /// of the main loop.
+   ///
+   /// <h2 id=  patch_group_check_tampering_safety>
+   /// <a href=#patch_group_check_tampering_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #patch_group_enable_safety>enable</a></code>
+   /// apply if the policy is
+   /// <code>TamperPolicy::Reapply</code>.
+   pub unsafe fn check_tampering(
+      & mut self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> Result<bool> {
+      if self.enabled == false || self.policy == TamperPolicy::Ignore {
+         return Ok(false);
+      }
+
+      let mut tampered = match self.written.swap(0, std::sync::atomic::Ordering::AcqRel) {
+         0        => None,
+         address  => Some(address),
+      };
+
+      if self.is_tamper_watched() == false {
+         for member in self.members.iter_mut() {
+            let container = match &member.container {
+               Some(container)   => container,
+               None              => continue,
+            };
+
+            let current = container.read_overwritten()?;
+            if current != member.patched {
+               tampered = Some(container.address_range().start);
+
+               // Only report each change once
+               member.patched = current;
+            }
+         }
+      }
+
+      let address = match tampered {
+         Some(address)  => address,
+         None           => return Ok(false),
+      };
+
+      crate::log::warn!(
+         "Patch group \"{}\" was overwritten near {address:#X}, {}",
+         self.name,
+         match self.policy {
+            TamperPolicy::Ignore  | TamperPolicy::Report
+               => "leaving it as is",
+            TamperPolicy::Reapply
+               => "reapplying it",
+            TamperPolicy::Disable
+               => "disabling it",
+         },
+      );
+
+      match self.policy {
+         TamperPolicy::Ignore  | TamperPolicy::Report
+            => (),
+         TamperPolicy::Reapply
+            => {
+               self.disable();
+               self.enable(modules)?;
+            },
+         TamperPolicy::Disable
+            => self.disable(),
+      }
+
+      return Ok(true);
+   }
+
+   /// Returns the name of the group.
+   pub fn name<'l>(
+      &'l self,
+   ) -> &'l str {
+      return &self.name;
+   }
+
+   /// Checks whether the group is
+   /// currently applied.
+   pub fn is_enabled(
+      & self,
+   ) -> bool {
+      return self.enabled;
+   }
+
+   /// Returns the number of writers
+   /// in the group.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.members.len();
+   }
+
+   /// Checks whether the group has
+   /// no writers.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.members.is_empty();
+   }
+}
+
+///////////////////////////////////
+// INTERNAL METHODS - PatchGroup //
+///////////////////////////////////
+
+impl PatchGroup {
+   fn revert(
+      & mut self,
+   ) {
+      // Restoring writes to the watched
+      // bytes, which isn't tampering
+      self.watchpoints.clear();
+
+      // Restore in reverse order in case
+      // two members overlap
+      for member in self.members.iter_mut().rev() {
+         member.container = None;
+      }
+
+      return;
+   }
+
+   // Sets a write breakpoint on every
+   // naturally aligned piece of the bytes
+   // written by the group if it's enabled
+   // and should be watched, otherwise
+   // removes them
+   unsafe fn arm_watch(
+      & mut self,
+   ) -> Result<()> {
+      self.watchpoints.clear();
+      self.written.store(0, std::sync::atomic::Ordering::Release);
+      if self.enabled == false || self.watch == false {
+         return Ok(());
+      }
+
+      let mut watchpoints = Vec::new();
+      for container in self.members.iter().filter_map(|member| member.container.as_ref()) {
+         for range in container.overwritten_address_ranges() {
+            for (offset, length) in merge_pieces(range.start, range.len()) {
+               let address = range.start + offset;
+               let written = self.written.clone();
+               watchpoints.push(crate::debug::Watchpoint::new(
+                  address,
+                  length,
+                  crate::debug::WatchKind::Write,
+                  move |_| written.store(address, std::sync::atomic::Ordering::Release),
+               )?);
+            }
+         }
+      }
+
+      self.watchpoints = watchpoints;
+      return Ok(());
+   }
+}
+
+/////////////////////////////////////////
+// INTERNAL METHODS - PatchGroupMember //
+/////////////////////////////////////////
+
+impl PatchGroupMember {
+   unsafe fn execute(
+      & self,
+      modules  : & mut crate::process::ModuleSnapshotList,
+   ) -> Result<crate::process::ModuleSnapshotPatchContainer> {
+      let module = modules.get_mut(
+         &self.module_name,
+      ).ok_or_else(|| PatchError::ModuleNotFound{
+         module_name : self.module_name.clone(),
+      })?;
+
+      return (self.apply)(module);
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatchGroup //
+////////////////////////////////////////
+
+impl std::ops::Drop for PatchGroup {
+   fn drop(
+      & mut self,
+   ) {
+      self.revert();
+      return;
+   }
+}
+
+impl std::fmt::Debug for PatchGroup {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(
+         stream,
+         "PatchGroup {{ name: {:?}, members: {}, enabled: {} }}",
+         self.name,
+         self.members.len(),
+         self.enabled,
+      );
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Item //
+//////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+   T: Copy,
+> Reader<R> for reader::Item<R, T> {
+   type Item = T;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let item_size = std::mem::size_of::<T>();
+
+      if memory_buffer.len() != item_size {
+         return Err(PatchError::LengthMismatch{
+            found    : memory_buffer.len(),
+            expected : item_size,
+         })
+      }
+
+      // This looks sketchy, but since we have
+      // the Copy trait bound and checked the
+      // length with the above code, this will
+      // always be valid given the memory buffer
+      // is also valid.
+      let item_ptr   = memory_buffer.as_ptr() as * const T;
+      let item       = unsafe{std::ptr::read_unaligned(item_ptr)};
+
+      return Ok(item);
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Enum //
+//////////////////////////////////////////
+
+impl<
+   'v,
+   R: RangeBounds<usize>,
+   D: Discriminant,
+   T: Copy,
+> Reader<R> for reader::Enum<'v, R, D, T> {
+   type Item = T;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let discriminant_size = std::mem::size_of::<D>();
+
+      if memory_buffer.len() != discriminant_size {
+         return Err(PatchError::LengthMismatch{
+            found    : memory_buffer.len(),
+            expected : discriminant_size,
+         });
+      }
+
+      // Any bit pattern is a valid value of
+      // a discriminant by its safety contract
+      let discriminant = unsafe{std::ptr::read_unaligned(memory_buffer.as_ptr() as * const D)};
+
+      return self.variants.iter()
+         .find(|(variant, _)| *variant == discriminant)
+         .map(|(_, item)| *item)
+         .ok_or_else(|| PatchError::UnknownDiscriminant{
+            found    : discriminant.into(),
+            allowed  : self.variants.iter().map(|(variant, _)| (*variant).into()).collect(),
+         });
+   }
+}
+
+//////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::PointerChain //
+//////////////////////////////////////////////////
+
+impl<
+   'o,
+   R: RangeBounds<usize>,
+   T: Copy,
+> Reader<R> for reader::PointerChain<'o, R, T> {
+   type Item = T;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let address = resolve_pointer_chain(memory_buffer, self.offsets)?;
+
+      verify_readable(address, std::mem::size_of::<T>(), self.offsets.len().saturating_sub(1))?;
+
+      // The address was checked to be readable
+      // for the whole size of the type above
+      let item = unsafe{std::ptr::read_unaligned(address as * const T)};
+
+      return Ok(item);
+   }
+}
+
+/////////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::PointerChainAddress //
+/////////////////////////////////////////////////////////
+
+impl<
+   'o,
+   R: RangeBounds<usize>,
+> Reader<R> for reader::PointerChainAddress<'o, R> {
+   type Item = usize;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      return resolve_pointer_chain(memory_buffer, self.offsets);
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Slice //
+///////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+   T: Copy,
+> Reader<R> for reader::Slice<R, T> {
+   type Item = Vec<T>;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let item_size  = std::mem::size_of::<T>();
+      let byte_count = self.element_count.saturating_mul(item_size);
+
+      if memory_buffer.len() < byte_count {
+         return Err(PatchError::LengthMismatch{
+            found    : memory_buffer.len(),
+            expected : byte_count,
+         });
+      }
+
+      if item_size == 0 {
+         return Ok(Vec::new());
+      }
+
+      let bytes_residual = memory_buffer.len() % item_size;
+      if bytes_residual != 0 {
+         return Err(PatchError::ResidualBytes{
+            residual : bytes_residual,
+         });
+      }
+
+      // Again, looks sketchy but the above code
+      // verifies this is sound
+      let item_slice = unsafe{std::slice::from_raw_parts(
+         memory_buffer.as_ptr() as * const T,
+         self.element_count,
+      )};
+      let item_vec   = item_slice.to_vec();
+
+      return Ok(item_vec);
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::CStr //
+//////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Reader<R> for reader::CStr<R> {
+   type Item = String;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let bytes = memory_buffer.split(|byte| *byte == 0).next().unwrap_or(&[]);
+      return Ok(String::from_utf8_lossy(bytes).into_owned());
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::WideStr //
+/////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Reader<R> for reader::WideStr<R> {
+   type Item = String;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let units = wide_units(memory_buffer)?
+         .take_while(|unit| *unit != 0)
+         .collect::<Vec<u16>>();
+
+      return Ok(String::from_utf16_lossy(&units));
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Bits //
+//////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Reader<R> for reader::Bits<R> {
+   type Item = u64;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      verify_bit_mask(self.bit_mask, memory_buffer.len())?;
+
+      let bits = load_bits(memory_buffer);
+      return Ok((bits & self.bit_mask) >> self.bit_mask.trailing_zeros());
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Map //
+/////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+   Rd: Reader<R>,
+   F: Fn(Rd::Item) -> U,
+   U,
+> Reader<R> for reader::Map<Rd, F> {
+   type Item = U;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return self.reader.memory_offset_range();
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      return Ok((self.map)(self.reader.read_item(memory_buffer)?));
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Validate //
+//////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+   Rd: Reader<R>,
+   F: Fn(&Rd::Item) -> bool,
+> Reader<R> for reader::Validate<Rd, F> {
+   type Item = Rd::Item;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return self.reader.memory_offset_range();
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      let item = self.reader.read_item(memory_buffer)?;
+      if (self.validate)(&item) == false {
+         return Err(PatchError::ValidationFailed{
+            type_name : std::any::type_name::<Rd::Item>(),
+         });
+      }
+
+      return Ok(item);
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - reader::Tuple //
+///////////////////////////////////////////
+
+impl<T: ReaderTuple> Reader<std::ops::Range<usize>> for reader::Tuple<T> {
+   type Item = T::Items;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return & self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> Result<Self::Item> {
+      return self.readers.read_items(self.memory_offset_range.start, memory_buffer);
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ReaderTuple //
+/////////////////////////////////////////
+
+macro_rules! impl_reader_tuple {
+   ($($reader:ident : $index:tt),+) => {
+      impl<$($reader),+> ReaderTuple for ($($reader,)+)
+      where $($reader: Reader<std::ops::Range<usize>>,)+
+      {
+         type Items = ($(<$reader as Reader<std::ops::Range<usize>>>::Item,)+);
+
+         fn memory_offset_ranges(
+            & self,
+         ) -> Vec<std::ops::Range<usize>> {
+            return vec![$(self.$index.memory_offset_range().clone()),+];
+         }
+
+         fn read_items(
+            & self,
+            memory_offset  : usize,
+            memory_buffer  : & [u8],
+         ) -> Result<Self::Items> {
+            return Ok(($({
+               let range = self.$index.memory_offset_range();
+               self.$index.read_item(subslice(
+                  memory_buffer,
+                  range.start.saturating_sub(memory_offset)..range.end.saturating_sub(memory_offset),
+               )?)?
+            },)+));
+         }
+      }
+   };
+}
+
+impl_reader_tuple!(A : 0);
+impl_reader_tuple!(A : 0, B : 1);
+impl_reader_tuple!(A : 0, B : 1, C : 2);
+impl_reader_tuple!(A : 0, B : 1, C : 2, D : 3);
+impl_reader_tuple!(A : 0, B : 1, C : 2, D : 3, E : 4);
+impl_reader_tuple!(A : 0, B : 1, C : 2, D : 3, E : 4, F : 5);
+impl_reader_tuple!(A : 0, B : 1, C : 2, D : 3, E : 4, F : 5, G : 6);
+impl_reader_tuple!(A : 0, B : 1, C : 2, D : 3, E : 4, F : 5, G : 6, H : 7);
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - &Writer //
+/////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+   W: Writer<R> + ?Sized,
+> Writer<R> for & W {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return (**self).memory_offset_range();
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return (**self).checksum();
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      return (**self).build_patch(memory_buffer, address);
+   }
+
+   fn build_patch_warnings(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<Vec<String>> {
+      return (**self).build_patch_warnings(memory_buffer, address);
+   }
+
+   fn verify_checksum(
+      & self,
+      memory_buffer  : & [u8],
+      address        : usize,
+   ) -> Result<()> {
+      return (**self).verify_checksum(memory_buffer, address);
+   }
+
+   fn overwritten_ranges(
+      & self,
+      memory_buffer_length : usize,
+   ) -> Vec<std::ops::Range<usize>> {
+      return (**self).overwritten_ranges(memory_buffer_length);
+   }
+
+   fn restore_mask(
+      & self,
+      memory_buffer_length : usize,
+   ) -> Option<Vec<u8>> {
+      return (**self).restore_mask(memory_buffer_length);
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return (**self).position_independent();
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Item //
+//////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+> Writer<R> for writer::Item<'s, R, T> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   } 
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let item_size = std::mem::size_of::<T>();
+
+      if memory_buffer.len() != item_size {
+         return Err(PatchError::LengthMismatch{
+            found    : memory_buffer.len(),
+            expected : item_size,
+         });
+      }
+
+      let destination = memory_buffer.as_mut_ptr() as * mut T;
+
+      // The memory buffer may not be aligned
+      // and holds bytes rather than a value
+      // which could be dropped
+      unsafe{std::ptr::write_unaligned(destination, self.item.clone())};
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::ItemFill //
+//////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+> Writer<R> for writer::ItemFill<'s, R, T> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let item_size = std::mem::size_of::<T>();
+
+      if item_size == 0 {
+         return Err(PatchError::ZeroLengthType);
+      }
+
+      let residual = memory_buffer.len() % item_size;
+
+      if residual != 0 {
+         return Err(PatchError::ResidualBytes{
+            residual : residual,
+         });
+      }
+
+      let element_buffer = unsafe{std::slice::from_raw_parts_mut(
+         memory_buffer.as_mut_ptr() as * mut T,
+         memory_buffer.len() / std::mem::size_of::<T>(),
+      )};
+
+      element_buffer.fill(self.item.clone());
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::ItemPadded //
+////////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+   U: Clone,
+> Writer<R> for writer::ItemPadded<'s, R, T, U> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      self.alignment.clone_from_item_with_padding(
+         memory_buffer,
+         address,
+         self.item.clone(),
+         self.padding.clone(),
+      )?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+/////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::ItemPaddedSplit //
+/////////////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+   U: Clone,
+   V: Clone,
+> Writer<R> for writer::ItemPaddedSplit<'s, R, T, U, V> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      self.alignment.clone_from_item_with_split_padding(
+         memory_buffer,
+         address,
+         self.item.clone(),
+         self.padding_left.clone(),
+         self.padding_right.clone(),
+         self.tail,
+      )?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Slice //
+///////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+> Writer<R> for writer::Slice<'s, R, T> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let bytes = unsafe{std::slice::from_raw_parts(
+         self.slice.as_ptr() as * const u8,
+         self.slice.len() * std::mem::size_of::<T>(),
+      )};
+
+      if memory_buffer.len() != bytes.len() {
+         return Err(PatchError::LengthMismatch{
+            found    : bytes.len(),
+            expected : memory_buffer.len(),
+         });
+      }
+
+      memory_buffer.clone_from_slice(bytes);
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+///////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::SliceFill //
+///////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+> Writer<R> for writer::SliceFill<'s, R, T> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let item_size = std::mem::size_of::<T>();
+
+      if item_size == 0 {
+         return Err(PatchError::ZeroLengthType);
+      }
+
+      if memory_buffer.len() == 0 {
+         return Ok(());
+      }
+
+      if self.slice.len() == 0 {
+         return Err(PatchError::ZeroLengthType);
+      }
+
+      let slice_len_bytes = self.slice.len().saturating_mul(item_size);
+
+      if memory_buffer.len() % slice_len_bytes != 0 {
+         return Err(PatchError::ResidualBytes{
+            residual : memory_buffer.len() % slice_len_bytes,
+         });
+      }
+
+      // This is how the sausage is made
+      // Have to create Vec copies so we
+      // call clone() and can still access
+      // the raw bytes.  Before you ask,
+      // std::slice::clone_from_slice()
+      // doesn't work for this use case.
+      for memory_buffer_view in memory_buffer.chunks_exact_mut(slice_len_bytes) {
+         // Clone slice elements and convert to byte slice
+         let slice_clone = self.slice.to_vec();
+         let slice_clone = unsafe{std::slice::from_raw_parts(
+            slice_clone.as_ptr() as * const u8,
+            slice_len_bytes,
+         )};
+
+         // Copy over the whole chunk, which is
+         // exactly one slice long
+         memory_buffer_view.copy_from_slice(slice_clone);
+      }
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+/////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::SlicePadded //
+/////////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+   U: Clone,
+> Writer<R> for writer::SlicePadded<'s, R, T, U> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      self.alignment.clone_from_slice_with_padding(
+         memory_buffer,
+         address,
+         self.slice,
+         self.padding.clone(),
+      )?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+//////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::SlicePaddedSplit //
+//////////////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+   T: Clone,
+   U: Clone,
+   V: Clone,
+> Writer<R> for writer::SlicePaddedSplit<'s, R, T, U, V> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      self.alignment.clone_from_slice_with_split_padding(
+         memory_buffer,
+         address,
+         self.slice,
+         self.padding_left.clone(),
+         self.padding_right.clone(),
+         self.tail,
+      )?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::CStr //
+//////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+> Writer<R> for writer::CStr<'s, R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let bytes = self.string.as_bytes();
+      if let Some(position) = bytes.iter().position(|byte| *byte == 0) {
+         return Err(PatchError::InteriorNul{
+            position : position,
+         });
+      }
+
+      let mut length = string_fit_length(bytes.len(), memory_buffer.len(), self.truncation)?;
+      while self.string.is_char_boundary(length) == false {
+         length -= 1;
+      }
+
+      memory_buffer.fill(0);
+      for (byte, value) in memory_buffer.iter_mut().zip(bytes.iter().take(length)) {
+         *byte = *value;
+      }
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::WideStr //
+/////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+> Writer<R> for writer::WideStr<'s, R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      let units = self.string.encode_utf16().collect::<Vec<u16>>();
+      if let Some(position) = units.iter().position(|unit| *unit == 0) {
+         return Err(PatchError::InteriorNul{
+            position : position,
+         });
+      }
+
+      // Checks the buffer holds whole units
+      let capacity = wide_units(memory_buffer)?.len();
+
+      let mut length = string_fit_length(units.len(), capacity, self.truncation).map_err(|err| match err {
+         PatchError::StringTooLong{length, capacity} => PatchError::StringTooLong{
+            length   : length * 2,
+            capacity : capacity * 2,
+         },
+         err => err,
+      })?;
+      if length < units.len() && units.get(length).is_some_and(|unit| (0xDC00..=0xDFFF).contains(unit)) == true {
+         length -= 1;
+      }
+
+      memory_buffer.fill(0);
+      for (chunk, unit) in memory_buffer.chunks_exact_mut(2).zip(units.iter().take(length)) {
+         chunk.copy_from_slice(&unit.to_ne_bytes());
+      }
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Bits //
+//////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Bits<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      verify_bit_mask(self.bit_mask, memory_buffer.len())?;
+
+      let bits = self.value.checked_shl(self.bit_mask.trailing_zeros()).unwrap_or(0);
+      if bits & self.bit_mask != bits || bits >> self.bit_mask.trailing_zeros() != self.value {
+         return Err(PatchError::BitValueOutOfRange{
+            value    : self.value,
+            bit_mask : self.bit_mask,
+         });
+      }
+
+      store_bits(memory_buffer, self.bit_mask, bits);
+      return Ok(());
+   }
+
+   fn verify_checksum(
+      & self,
+      memory_buffer  : & [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      verify_bit_mask(self.bit_mask, memory_buffer.len())?;
+
+      let bits  = load_bits(memory_buffer) & self.bit_mask;
+      let bytes = bits.to_le_bytes();
+      let bytes = bytes.get(..memory_buffer.len()).unwrap_or(&[]);
+
+      if self.checksum.matches(bytes) == false {
+         return Err(PatchError::ChecksumMismatch{
+            found    : Checksum::compute(self.checksum.algorithm(), bytes),
+            expected : self.checksum.clone(),
+         });
+      }
+
+      return Ok(());
+   }
+
+   fn restore_mask(
+      & self,
+      memory_buffer_length : usize,
+   ) -> Option<Vec<u8>> {
+      let restore_mask = self.bit_mask.to_le_bytes();
+      return Some(restore_mask.get(..memory_buffer_length).unwrap_or(&restore_mask).to_vec());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Nop //
+/////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Nop<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      crate::sys::compiler::nop_fill(
+         memory_buffer,
+      )?;
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::FlipJcc //
+/////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::FlipJcc<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      match self.flip {
+         JccFlip::Invert
+            => crate::sys::compiler::branch_invert_fill(memory_buffer)?,
+         JccFlip::ForceTaken
+            => crate::sys::compiler::branch_fill(memory_buffer, address, true)?,
+         JccFlip::ForceSkip
+            => crate::sys::compiler::branch_fill(memory_buffer, address, false)?,
+      }
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return self.flip != JccFlip::ForceTaken;
+   }
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Hook //
+//////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Hook<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      verify_instruction_boundary(memory_buffer, address)?;
+      crate::sys::compiler::hook_fill(
+         memory_buffer,
+         address,
+         self.hook,
+      )?;
+      return Ok(());
+   }
+}
+
+/////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::HookJmpBack //
+/////////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::HookJmpBack<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      verify_instruction_boundary(memory_buffer, address)?;
+      crate::sys::compiler::hook_fill_jmp_back(
+         memory_buffer,
+         address,
+         self.hook,
+      )?;
+      return Ok(());
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::InlineHook //
+////////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::InlineHook<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      log_warnings(self.build_patch_warnings(memory_buffer, address)?);
+      return Ok(());
+   }
+
+   fn build_patch_warnings(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<Vec<String>> {
+      let fill = crate::sys::compiler::hook_fill_inline(
+         memory_buffer,
+         address,
+         self.hook,
+         self.emulate,
+      )?;
+
+      return Ok(fill.emulated.into_iter().map(|instruction| format!(
+         "Emulated {:?} instruction at {:#X} ({} bytes), since its target {:#X} is out of reach of the inline hook stub",
+         instruction.kind,
+         address + instruction.offset,
+         instruction.length,
+         instruction.target_address,
+      )).collect());
+   }
+}
+
+////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::CoexistingHook //
+////////////////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::CoexistingHook<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn verify_checksum(
+      & self,
+      memory_buffer  : & [u8],
+      address        : usize,
+   ) -> Result<()> {
+      if self.checksum.matches(memory_buffer) == true {
+         return Ok(());
+      }
+
+      // Someone else's hook is the only
+      // reason the bytes may not match
+      if foreign_jump(memory_buffer, address).is_some() == true {
+         return Ok(());
+      }
+
+      return Err(PatchError::ChecksumMismatch{
+         found    : Checksum::compute(self.checksum.algorithm(), memory_buffer),
+         expected : self.checksum.clone(),
+      });
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      log_warnings(self.build_patch_warnings(memory_buffer, address)?);
+      return Ok(());
+   }
+
+   fn build_patch_warnings(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<Vec<String>> {
+      // The checksum was already verified, so
+      // a jump where the bytes don't match is
+      // their hook, and the modules don't need
+      // to be looked up again
+      let hooked = self.checksum.matches(memory_buffer) == false
+         && crate::sys::compiler::jump_instruction(memory_buffer, address).is_some() == true;
+
+      if hooked == false {
+         return writer::InlineHook{
+            memory_offset_range  : ..,
+            checksum             : self.checksum.clone(),
+            hook                 : self.hook,
+            emulate              : self.emulate,
+         }.build_patch_warnings(memory_buffer, address);
+      }
+
+      let fill = crate::sys::compiler::hook_fill_foreign(
+         memory_buffer,
+         address,
+         self.hook,
+         self.emulate,
+      )?;
+
+      return Ok(fill.emulated.into_iter().map(|instruction| format!(
+         "Emulated {:?} instruction at {:#X} ({} bytes), since its target {:#X} is out of reach of the inline hook stub",
+         instruction.kind,
+         address + instruction.offset,
+         instruction.length,
+         instruction.target_address,
+      )).collect());
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Asm //
+/////////////////////////////////////////
+
+impl<
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Asm<R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }  
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      // Verify the ASM will fit into the buffer
+      if memory_buffer.len() < self.asm_bytes.len() {
+         return Err(PatchError::LengthMismatch{
+            found    : self.asm_bytes.len(),
+            expected : memory_buffer.len(),
+         });
+      }
+
+      // Byte padding count
+      let padding_bytes_left = self.alignment.padding_bytes::<u8>(
+         address,
+         memory_buffer.len(),
+         self.asm_bytes.len(),
+      )?.0;
+
+      let asm_end = padding_bytes_left + self.asm_bytes.len();
+
+      // Copy the ASM bytes
+      subslice_mut(memory_buffer, padding_bytes_left..asm_end)?
+         .copy_from_slice(self.asm_bytes);
+
+      // Build the padding instructions
+      let memory_buffer_length = memory_buffer.len();
+      crate::sys::compiler::nop_fill(subslice_mut(
+         memory_buffer, 0..padding_bytes_left,
+      )?)?;
+      crate::sys::compiler::nop_fill(subslice_mut(
+         memory_buffer, asm_end..memory_buffer_length,
+      )?)?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+
+///////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Assembled //
+///////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Assembled<'s, R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      // Assemble for the final address
+      // before touching memory
+      let mut assembler = crate::asm::Assembler::new(address);
+      (self.assemble)(& mut assembler)?;
+      let asm_bytes = assembler.finish()?;
+
+      // Verify the ASM will fit into the buffer
+      if memory_buffer.len() < asm_bytes.len() {
+         return Err(PatchError::LengthMismatch{
+            found    : asm_bytes.len(),
+            expected : memory_buffer.len(),
+         });
+      }
+
+      // Copy the ASM bytes
+      let memory_buffer_length = memory_buffer.len();
+      subslice_mut(memory_buffer, 0..asm_bytes.len())?
+         .copy_from_slice(&asm_bytes);
+
+      // Build the padding instructions
+      crate::sys::compiler::nop_fill(subslice_mut(
+         memory_buffer, asm_bytes.len()..memory_buffer_length,
+      )?)?;
+
+      return Ok(());
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Template //
+//////////////////////////////////////////////
+
+impl<
+   's,
+   R: RangeBounds<usize>,
+> Writer<R> for writer::Template<'s, R> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l R {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      // Verify the template will fit into the buffer
+      if memory_buffer.len() < self.template_bytes.len() {
+         return Err(PatchError::LengthMismatch{
+            found    : self.template_bytes.len(),
+            expected : memory_buffer.len(),
+         });
+      }
+
+      // Byte padding count
+      let padding_bytes_left = self.alignment.padding_bytes::<u8>(
+         address,
+         memory_buffer.len(),
+         self.template_bytes.len(),
+      )?.0;
+
+      // Fill in the placeholders before touching
+      // memory so a bad value doesn't leave
+      // a partially written patch
+      let template_address = address.wrapping_add(padding_bytes_left);
+      let mut bytes = self.template_bytes.to_vec();
+      for placeholder in self.placeholders {
+         let value   = placeholder.value.to_bytes(template_address)?;
+         let maximum = bytes.len().saturating_sub(value.len());
+         let slot    = placeholder.slot_offset.checked_add(value.len()).and_then(|end| {
+            bytes.get_mut(placeholder.slot_offset..end)
+         });
+
+         match slot {
+            Some(slot)
+               => slot.copy_from_slice(&value),
+            None
+               => return Err(PatchError::OutOfRange{
+                  maximum  : maximum,
+                  provided : placeholder.slot_offset,
+               }),
+         }
+      }
+
+      let template_end = padding_bytes_left + bytes.len();
+
+      // Copy the resolved template bytes
+      subslice_mut(memory_buffer, padding_bytes_left..template_end)?
+         .copy_from_slice(&bytes);
+
+      // Build the padding instructions
+      let memory_buffer_length = memory_buffer.len();
+      crate::sys::compiler::nop_fill(subslice_mut(
+         memory_buffer, 0..padding_bytes_left,
+      )?)?;
+      crate::sys::compiler::nop_fill(subslice_mut(
+         memory_buffer, template_end..memory_buffer_length,
+      )?)?;
+
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      let relative = self.placeholders.iter().any(|placeholder| {
+         return matches!(placeholder.value, PlaceholderValue::Relative{..});
+      });
+
+      return relative == false && matches!(self.alignment, Alignment::Stride{..}) == false;
+   }
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - writer::Scatter //
+/////////////////////////////////////////////
+
+impl<'s> Writer<std::ops::Range<usize>> for writer::Scatter<'s> {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      log_warnings(self.build_patch_warnings(memory_buffer, address)?);
+      return Ok(());
+   }
+
+   fn build_patch_warnings(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<Vec<String>> {
+      let expected = self.memory_offset_range.end.saturating_sub(self.memory_offset_range.start);
+      if memory_buffer.len() != expected {
+         return Err(PatchError::LengthMismatch{
+            found    : memory_buffer.len(),
+            expected : expected,
+         });
+      }
+
+      // Undo any partially applied writers
+      // so the patch is applied as a unit
+      let old_bytes     = memory_buffer.to_vec();
+      let mut warnings  = Vec::new();
+      for writer in self.writers {
+         let range = self.buffer_range(*writer);
+         let writer_address = address.wrapping_add(range.start);
+         let result = subslice_mut(memory_buffer, range).and_then(|memory_buffer| {
+            return writer.build_patch_warnings(memory_buffer, writer_address);
+         });
+         match result {
+            Ok(writer_warnings)  => warnings.extend(writer_warnings),
+            Err(error)           => {
+               memory_buffer.copy_from_slice(&old_bytes);
+               return Err(error);
+            },
+         }
+      }
+
+      return Ok(warnings);
+   }
+
+   fn verify_checksum(
+      & self,
+      memory_buffer  : & [u8],
+      address        : usize,
+   ) -> Result<()> {
+      for writer in self.writers {
+         let range = self.buffer_range(*writer);
+         let writer_address = address.wrapping_add(range.start);
+         writer.verify_checksum(subslice(memory_buffer, range)?, writer_address)?;
+      }
+
+      return Ok(());
+   }
+
+   fn overwritten_ranges(
+      & self,
+      _memory_buffer_length : usize,
+   ) -> Vec<std::ops::Range<usize>> {
+      return self.writers.iter().flat_map(|writer| {
+         let range = self.buffer_range(*writer);
+         writer.overwritten_ranges(range.end.saturating_sub(range.start)).into_iter().map(
+            move |sub_range| {
+            range.start + sub_range.start..range.start + sub_range.end
+         })
+      }).collect();
+   }
+
+   fn restore_mask(
+      & self,
+      memory_buffer_length : usize,
+   ) -> Option<Vec<u8>> {
+      let mut restore_mask = None;
+      for writer in self.writers {
+         let range = self.buffer_range(*writer);
+         let writer_mask = match writer.restore_mask(range.end.saturating_sub(range.start)) {
+            Some(writer_mask) => writer_mask,
+            None              => continue,
+         };
+
+         let restore_mask = restore_mask.get_or_insert_with(|| vec![0xFF; memory_buffer_length]);
+         if let Some(restore_mask) = restore_mask.get_mut(range) {
+            for (mask, writer_mask) in restore_mask.iter_mut().zip(writer_mask) {
+               *mask = writer_mask;
+            }
+         }
+      }
+
+      return restore_mask;
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return self.writers.iter().all(|writer| writer.position_independent() == true);
+   }
+}
+
+//////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - recipes::NopCall //
+//////////////////////////////////////////////
+
+impl Writer<std::ops::Range<usize>> for recipes::NopCall {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      _address       : usize,
+   ) -> Result<()> {
+      crate::sys::compiler::call_nop_fill(
+         memory_buffer,
+      )?;
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+//////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - recipes::ForceBranch //
+//////////////////////////////////////////////////
+
+impl Writer<std::ops::Range<usize>> for recipes::ForceBranch {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      crate::sys::compiler::branch_fill(
+         memory_buffer,
+         address,
+         self.taken,
+      )?;
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return self.taken == false;
+   }
+}
+
+/////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - recipes::ReturnConstant //
+/////////////////////////////////////////////////////
+
+impl Writer<std::ops::Range<usize>> for recipes::ReturnConstant {
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return & self.memory_offset_range;
+   }
+
+   fn checksum<'l>(
+      &'l self,
+   ) -> &'l Checksum {
+      return & self.checksum;
+   }
+
+   fn build_patch(
+      & self,
+      memory_buffer  : & mut [u8],
+      address        : usize,
+   ) -> Result<()> {
+      verify_instruction_boundary(memory_buffer, address)?;
+      crate::sys::compiler::return_fill(
+         memory_buffer,
+         self.value,
+      )?;
+      return Ok(());
+   }
+
+   fn position_independent(
+      & self,
+   ) -> bool {
+      return true;
+   }
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+// Every patch which is still applied in the
+// order they were created, so overlapping
+// patches are restored newest first and
+// everything can be reverted when the
+// library is unloaded
+static PATCH_REGISTRY
+   : std::sync::Mutex<Vec<RegistryEntry>>
+   = std::sync::Mutex::new(Vec::new());
+
+// Pages kept open for reading, least
+// recently used first, along with the
+// thread which restores them once idle
+static EDITOR_CACHE
+   : std::sync::Mutex<EditorCache>
+   = std::sync::Mutex::new(EditorCache{
+      options  : DEFAULT_EDITOR_CACHE_OPTIONS,
+      entries  : Vec::new(),
+      writers  : Vec::new(),
+      sweeper  : None,
+      sweeping : false,
+   });
+
+// Wakes the sweeper early when the
+// cache is flushed or its options change
+static EDITOR_CACHE_SWEEP
+   : std::sync::Condvar
+   = std::sync::Condvar::new();
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Reverts every patch whose container is
+/// still alive, newest first, and returns
+/// how many were reverted.  The containers
+/// stay valid, but no longer restore anything
+/// when dropped.  This is called when the
+/// environment is freed and when main
+/// panics, so the game's code never keeps
+/// jumping into a library which is about
+/// to be unloaded.
+pub fn revert_all(
+) -> usize {
+   // Hold the lock while restoring, so no
+   // container dropped in the meantime can
+   // restore out of order
+   let mut registry = PATCH_REGISTRY.lock().unwrap_or_else(|poison| poison.into_inner());
+
+   let mut reverted = 0;
+   for entry in registry.iter().rev() {
+      if entry.patch.is_active() == false {
+         continue;
+      }
+
+      // This runs while a panic is reported,
+      // so failures are only logged
+      match entry.patch.restore() {
+         Ok(())   => reverted += 1,
+         Err(err) => crate::log::error!("Failed to restore patched bytes at {:#X}: {err}", entry.patch.address_range().start),
+      }
+   }
+
+   // Patches which failed to restore stay
+   // registered so they're tried again
+   registry.retain(|entry| entry.patch.is_active() == true);
+   return reverted;
+}
+
+/// Counts the patches whose containers
+/// are alive and haven't been reverted.
+pub fn active_count(
+) -> usize {
+   return PATCH_REGISTRY.lock()
+      .unwrap_or_else(|poison| poison.into_inner())
+      .iter()
+      .filter(|entry| entry.patch.is_active() == true)
+      .count();
+}
+
+/// Changes the options for the editor
+/// cache.  Pages past the new capacity
+/// have their permissions restored right
+/// away.
+pub fn set_editor_cache_options(
+   options  : EditorCacheOptions,
+) {
+   let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+   cache.options = options;
+
+   let excess  = cache.entries.len().saturating_sub(options.capacity);
+   let evicted = cache.entries.drain(..excess).collect::<Vec<_>>();
+
+   EDITOR_CACHE_SWEEP.notify_all();
+   drop(cache);
+   drop(evicted);
+   return;
+}
+
+/// Gets the current options for
+/// the editor cache.
+pub fn editor_cache_options(
+) -> EditorCacheOptions {
+   return EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner()).options;
+}
+
+/// Restores the permissions of every page
+/// held open by the editor cache and waits
+/// for its background thread to exit.  This
+/// is called when the environment is freed.
+pub fn flush_editor_cache(
+) {
+   let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+   let evicted = std::mem::take(& mut cache.entries);
+   let sweeper = cache.sweeper.take();
+
+   EDITOR_CACHE_SWEEP.notify_all();
+   drop(cache);
+   drop(evicted);
+
+   if let Some(sweeper) = sweeper {
+      let _ = sweeper.join();
+   }
+
+   return;
+}
+
+/// Converts an offset range into a
+/// bounded range, where an unbounded end
+/// is the given length.  This is useful
+/// for implementing <code>PatchTarget::resolve_range</code>.
+pub fn resolve_offset_range<R>(
+   offset_range   : & R,
+   length         : usize,
+) -> Result<std::ops::Range<usize>>
+where R: RangeBounds<usize>,
+{
+   use std::ops::Bound;
+   let start = match offset_range.start_bound() {
+      Bound::Included(b)
+         => *b,
+      Bound::Excluded(b)
+         => (Offset::new(*b) + 1)?.get(),
+      Bound::Unbounded
+         => 0,
+   };
+   let end = match offset_range.end_bound() {
+      Bound::Included(b)
+         => (Offset::new(*b) + 1)?.get(),
+      Bound::Excluded(b)
+         => *b,
+      Bound::Unbounded
+         => length,
+   };
+
+   if end > length {
+      return Err(PatchError::OutOfRange{
+         maximum  : length,
+         provided : end,
+      });
+   }
+   if end < start {
+      return Err(PatchError::EndOffsetBeforeStartOffset);
+   }
+
+   return Ok(start..end);
+}
+
+/// Suggests where to place a hook within
+/// a function in a module.  The returned
+/// offset range is the earliest one at or
+/// after the function offset which is at
+/// least the minimum length, covers only
+/// whole instructions, and doesn't overlap
+/// the function's prologue if the operating
+/// system's unwinder depends on it.  The
+/// covered instructions can be relocated
+/// without fixups, so they are safe to
+/// execute from a hook's trampoline.
+///
+/// This only considers the function's
+/// machine code and not jumps into it,
+/// so it is still a suggestion rather
+/// than a guarantee.
+///
+/// <h2 id=  patch_suggest_hook_site_safety>
+/// <a href=#patch_suggest_hook_site_safety>
+/// Safety
+/// </a></h2>
+///
+/// The function's code is read without
+/// any synchronization, so all safety
+/// concerns from <code>Patch::patch_read</code>
+/// apply.  In addition, the module must
+/// be loaded in the local process.
+pub unsafe fn suggest_hook_site(
+   module            : & crate::process::ModuleSnapshot,
+   function_offset   : usize,
+   minimum_length    : usize,
+) -> Result<std::ops::Range<usize>> {
+   let base    = module.address_range().start;
+   let address = module.offset_range_to_address_range(
+      &(function_offset..function_offset),
+   )?.start;
+
+   // Decode from the start of the function so
+   // instruction boundaries are known, but skip
+   // any prologue the unwinder depends on
+   let unwind_function = module.unwind_function(address)?;
+   let (decode_start, search_start, mut end) = match &unwind_function {
+      Some(function) => (
+         function.address_range.start,
+         usize::max(address, function.address_range.start + function.prologue_length),
+         function.address_range.end,
+      ),
+      None => (
+         address,
+         address,
+         module.address_range().end,
+      ),
+   };
+
+   // Only read memory which is currently executable
+   let region = crate::sys::memory::MemoryRegion::query(decode_start)?;
+   if region.is_readable() == false || region.is_executable() == false {
+      return Err(PatchError::NoHookSite);
+   }
+   end = usize::min(end, region.address_range().end);
+
+   let mut cursor       = decode_start;
+   let mut site_start   = None;
+   while cursor < end {
+      let bytes = std::slice::from_raw_parts(
+         cursor as * const u8,
+         end - cursor,
+      );
+
+      let instruction = crate::sys::decoder::decode_instruction(bytes)
+         .ok_or(PatchError::NoHookSite)?;
+      let next = cursor + instruction.length;
+
+      if cursor >= search_start {
+         use crate::sys::decoder::ControlFlow;
+
+         // Instructions which are relative to
+         // their own address can't be moved
+         let relocatable =
+            instruction.control_flow == ControlFlow::Sequential &&
+            crate::sys::decoder::decode_reference(bytes, cursor).is_none();
+
+         if relocatable == true {
+            let start = *site_start.get_or_insert(cursor);
+            if next - start >= minimum_length {
+               return Ok(start - base..next - base);
+            }
+         } else {
+            site_start = None;
+         }
+
+         // Without unwind information the end of
+         // the function is unknown, so stop at the
+         // first instruction which leaves it
+         if unwind_function.is_none() == true && matches!(
+            instruction.control_flow,
+            ControlFlow::Jump | ControlFlow::Return | ControlFlow::Trap,
+         ) {
+            break;
+         }
+      }
+
+      cursor = next;
+   }
+
+   return Err(PatchError::NoHookSite);
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+// How many bytes or code units of a string
+// to write into a buffer so it fits along
+// with its terminator, before moving back
+// to a character boundary
+fn string_fit_length(
+   length      : usize,
+   capacity    : usize,
+   truncation  : StringTruncation,
+) -> Result<usize> {
+   let too_long = PatchError::StringTooLong{
+      length   : length + 1,
+      capacity : capacity,
+   };
+
+   return match truncation {
+      StringTruncation::Error
+         => if length < capacity {Ok(length)} else {Err(too_long)},
+      StringTruncation::Truncate
+         => if capacity > 0 {Ok(usize::min(length, capacity - 1))} else {Err(too_long)},
+      StringTruncation::TruncateUnterminated
+         => Ok(usize::min(length, capacity)),
+   };
+}
+
+// Splits a buffer into native-endian
+// UTF-16 code units
+fn wide_units(
+   memory_buffer : & [u8],
+) -> Result<impl ExactSizeIterator<Item = u16> + '_> {
+   let residual = memory_buffer.len() % 2;
+   if residual != 0 {
+      return Err(PatchError::ResidualBytes{
+         residual : residual,
+      });
+   }
+
+   return Ok(memory_buffer.chunks_exact(2).map(|chunk| {
+      return u16::from_ne_bytes([
+         chunk.first().copied().unwrap_or(0),
+         chunk.last().copied().unwrap_or(0),
+      ]);
+   }));
+}
+
+// Applies several writers to a patch target,
+// opening the memory once for each group of
+// writers within the same memory region so
+// its permissions only change once.  Every
+// checksum is verified before anything is
+// written, and everything is rolled back if
+// any writer fails.  Returns each writer's
+// address range and overwritten bytes in
+// the order the writers were given.
+unsafe fn patch_batch<T: PatchTarget>(
+   target   : & T,
+   writers  : & [& dyn Writer<std::ops::Range<usize>>],
+) -> Result<Vec<(std::ops::Range<usize>, Vec<u8>)>> {
+   let mut entries = Vec::with_capacity(writers.len());
+   for (index, writer) in writers.iter().enumerate() {
+      entries.push((index, target.resolve_range(writer.memory_offset_range())?, *writer));
+   }
+   entries.sort_by_key(|(_, address_range, _)| address_range.start);
+
+   for pair in entries.windows(2) {
+      if let [(_, first, _), (_, second, _)] = pair {
+         if first.end > second.start {
+            return Err(PatchError::OverlappingRanges{
+               first    : first.clone(),
+               second   : second.clone(),
+            });
+         }
+      }
+   }
+
+   // Each group is the region it falls in,
+   // the span of its writers, and the writers
+   let mut groups = Vec::<(std::ops::Range<usize>, std::ops::Range<usize>, Vec<_>)>::new();
+   for entry in entries {
+      let address_range = entry.1.clone();
+      match groups.last_mut() {
+         Some((region, span, members)) if region.start <= address_range.start && address_range.end <= region.end => {
+            span.end = usize::max(span.end, address_range.end);
+            members.push(entry);
+         },
+         _ => {
+            let region = crate::sys::memory::MemoryRegion::query(address_range.start)
+               .map(|region| region.address_range().clone())
+               .unwrap_or(address_range.clone());
+            groups.push((region, address_range, vec![entry]));
+         },
+      }
+   }
+
+   let mut memories = Vec::with_capacity(groups.len());
+   for (_, span, _) in groups.iter() {
+      memories.push(target.memory_editor(span.clone(), true)?);
+   }
+
+   // Build every patch before writing any
+   // of them, so a failing checksum or
+   // writer leaves the memory untouched
+   let mut snapshots = Vec::with_capacity(writers.len());
+   for (_, _, members) in groups.iter() {
+      for (_, address_range, writer) in members.iter() {
+         let old_bytes = read_guarded(address_range)?;
+         writer.verify_checksum(&old_bytes, address_range.start)?;
+         snapshots.push(old_bytes);
+      }
+   }
+
+   let mut built     = Vec::with_capacity(writers.len());
+   let mut warnings  = Vec::new();
+   for ((index, address_range, writer), old_bytes) in groups.iter().flat_map(|(_, _, members)| members.iter()).zip(snapshots) {
+      let mut bytes = old_bytes.clone();
+      warnings.extend(writer.build_patch_warnings(&mut bytes, address_range.start)?);
+
+      let restore_ranges   = writer.overwritten_ranges(bytes.len());
+      let restore_mask     = writer.restore_mask(bytes.len());
+      built.push((*index, address_range.clone(), old_bytes, bytes, restore_ranges, restore_mask));
+   }
+
+   for (written, (_, address_range, old_bytes, bytes, restore_ranges, restore_mask)) in built.iter().enumerate() {
+      let result = commit_patch(address_range.start, old_bytes, bytes, restore_ranges, restore_mask.as_deref());
+      if let Err(err) = result {
+         for (_, address_range, old_bytes, _, restore_ranges, restore_mask) in built.iter().take(written).rev() {
+            let _ = write_guarded(address_range.start, old_bytes, restore_ranges, restore_mask.as_deref(), 0..old_bytes.len());
+         }
+         return Err(err);
+      }
+   }
+
+   std::mem::drop(memories);
+   log_warnings(warnings);
+
+   let mut patched = built.into_iter()
+      .map(|(index, address_range, old_bytes, _, _, _)| (index, address_range, old_bytes))
+      .collect::<Vec<_>>();
+   patched.sort_by_key(|(index, _, _)| *index);
+   return Ok(patched.into_iter().map(|(_, address_range, old_bytes)| (address_range, old_bytes)).collect());
+}
+
+// Writes back the bytes a writer overwrote
+// through a patch's public methods, keeping
+// any bytes and bits outside its restore
+// ranges and mask as they are now
+unsafe fn restore_written<P: Patch + ?Sized>(
+   target      : & mut P,
+   writer      : & dyn Writer<std::ops::Range<usize>>,
+   old_bytes   : & [u8],
+) -> Result<()> {
+   let range = writer.memory_offset_range().clone();
+
+   let mut restored = old_bytes.to_vec();
+   if let Some(restore_mask) = writer.restore_mask(old_bytes.len()) {
+      let current = target.patch_read(&reader::Slice::<_, u8>{
+         marker               : std::marker::PhantomData,
+         element_count        : old_bytes.len(),
+         memory_offset_range  : range.clone(),
+      })?;
+
+      for ((byte, current), mask) in restored.iter_mut().zip(current).zip(restore_mask) {
+         *byte = (*byte & mask) | (current & !mask);
+      }
+   }
+
+   for piece in writer.overwritten_ranges(old_bytes.len()) {
+      target.patch_write_unchecked(&writer::Slice{
+         memory_offset_range  : range.start + piece.start..range.start + piece.end,
+         checksum             : writer.checksum().clone(),
+         slice                : subslice(&restored, piece)?,
+      })?;
+   }
+
+   return Ok(());
+}
+
+// Checks a bit mask is non-empty and fits
+// within a bit field of 1 to 8 bytes
+fn verify_bit_mask(
+   bit_mask : u64,
+   length   : usize,
+) -> Result<()> {
+   if length == 0 || length > std::mem::size_of::<u64>() {
+      return Err(PatchError::BitFieldTooWide{
+         length : length,
+      });
+   }
+
+   let field_mask = u64::MAX.checked_shr(u64::BITS - length as u32 * 8).unwrap_or(0);
+   if bit_mask == 0 || bit_mask & !field_mask != 0 {
+      return Err(PatchError::BitMaskOutOfRange{
+         bit_mask : bit_mask,
+         length   : length,
+      });
+   }
+
+   return Ok(());
+}
+
+// Reads a little-endian bit field of 1 to 8
+// bytes, in a single access if it's naturally
+// aligned and the size of an integer type
+fn load_bits(
+   memory_buffer : & [u8],
+) -> u64 {
+   use std::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, AtomicU64, Ordering};
+
+   let address = memory_buffer.as_ptr() as * mut u8;
+   let ordering = Ordering::SeqCst;
+
+   // SAFETY: The pointer is valid for the whole
+   // field and each integer type is only used
+   // when the field is aligned for it
+   return unsafe{match (memory_buffer.len(), address as usize % memory_buffer.len().max(1)) {
+      (1, _) => AtomicU8::from_ptr(address).load(ordering) as u64,
+      (2, 0) => u16::from_le(AtomicU16::from_ptr(address as * mut u16).load(ordering)) as u64,
+      (4, 0) => u32::from_le(AtomicU32::from_ptr(address as * mut u32).load(ordering)) as u64,
+      (8, 0) => u64::from_le(AtomicU64::from_ptr(address as * mut u64).load(ordering)),
+      _      => {
+         let mut bytes = [0u8; 8];
+         for (offset, byte) in bytes.iter_mut().take(memory_buffer.len()).enumerate() {
+            *byte = AtomicU8::from_ptr(address.add(offset)).load(ordering);
+         }
+         u64::from_le_bytes(bytes)
+      },
+   }};
+}
+
+// Replaces the masked bits of a little-endian
+// bit field of 1 to 8 bytes, in a single atomic
+// read-modify-write if it's naturally aligned
+// and the size of an integer type, otherwise
+// one byte at a time
+fn store_bits(
+   memory_buffer  : & mut [u8],
+   bit_mask       : u64,
+   bits           : u64,
+) {
+   use std::sync::atomic::{AtomicU8, AtomicU16, AtomicU32, AtomicU64, Ordering};
+
+   let address = memory_buffer.as_mut_ptr();
+   let ordering = Ordering::SeqCst;
+   let merge = |old : u64| (old & !bit_mask) | (bits & bit_mask);
+
+   // SAFETY: The pointer is valid for the whole
+   // field and each integer type is only used
+   // when the field is aligned for it.  The
+   // update closures always return Some, so
+   // fetch_update can't fail.
+   unsafe{match (memory_buffer.len(), address as usize % memory_buffer.len().max(1)) {
+      (1, _) => drop(AtomicU8::from_ptr(address).fetch_update(ordering, ordering, |old| {
+         Some(merge(old as u64) as u8)
+      })),
+      (2, 0) => drop(AtomicU16::from_ptr(address as * mut u16).fetch_update(ordering, ordering, |old| {
+         Some(u16::to_le(merge(u16::from_le(old) as u64) as u16))
+      })),
+      (4, 0) => drop(AtomicU32::from_ptr(address as * mut u32).fetch_update(ordering, ordering, |old| {
+         Some(u32::to_le(merge(u32::from_le(old) as u64) as u32))
+      })),
+      (8, 0) => drop(AtomicU64::from_ptr(address as * mut u64).fetch_update(ordering, ordering, |old| {
+         Some(u64::to_le(merge(u64::from_le(old))))
+      })),
+      _      => {
+         let bit_masks = bit_mask.to_le_bytes();
+         let bytes     = bits.to_le_bytes();
+         for (offset, (bit_mask, bits)) in bit_masks.iter().zip(bytes).take(memory_buffer.len()).enumerate() {
+            let _ = AtomicU8::from_ptr(address.add(offset)).fetch_update(ordering, ordering, |old| {
+               Some((old & !bit_mask) | (bits & bit_mask))
+            });
+         }
+      },
+   }};
+
+   return;
+}
+
+// Gets the address ranges of every patch
+// whose container is alive and hasn't
+// been reverted
+pub(crate) fn active_address_ranges(
+) -> Vec<std::ops::Range<usize>> {
+   return PATCH_REGISTRY.lock()
+      .unwrap_or_else(|poison| poison.into_inner())
+      .iter()
+      .filter(|entry| entry.patch.is_active() == true)
+      .map(|entry| entry.patch.address_range().clone())
+      .collect();
+}
+
+// A range of whole pages held open
+// for reading by the editor cache
+struct CachedEditor {
+   page_range  : std::ops::Range<usize>,
+   session     : std::sync::Arc<crate::sys::memory::MemoryEditor>,
+   last_used   : std::time::Instant,
+}
+
+// Pages are never cached while they're
+// open for writing, since the cached
+// session would record the writer's
+// permissions and restore them after
+// the writer is done
+struct EditorCache {
+   options  : EditorCacheOptions,
+   entries  : Vec<CachedEditor>,
+   writers  : Vec<std::ops::Range<usize>>,
+   sweeper  : Option<std::thread::JoinHandle<()>>,
+   sweeping : bool,
+}
+
+/// Pages open for writing, which the
+/// editor cache leaves alone until
+/// this is dropped.
+pub(crate) struct EditorCacheWrite {
+   page_range  : std::ops::Range<usize>,
+}
+
+// Opens memory without taking away any
+// access its pages already have, since
+// other threads may be using them
+fn open_shared_editor(
+   address_range  : std::ops::Range<usize>,
+   writable       : bool,
+) -> Result<crate::sys::memory::MemoryEditor> {
+   let regions    = crate::sys::memory::MemoryRegion::all_within(address_range.clone())?;
+   let writable   = writable || regions.iter().all(|region| region.is_writable());
+   let executable = regions.iter().any(|region| region.is_executable());
+
+   return Ok(match (writable, executable) {
+      (false, false) => crate::sys::memory::MemoryEditor::open_read(address_range)?,
+      (true,  false) => crate::sys::memory::MemoryEditor::open_read_write(address_range)?,
+      (false, true)  => crate::sys::memory::MemoryEditor::open_read_execute(address_range)?,
+      (true,  true)  => crate::sys::memory::MemoryEditor::open_read_write_execute(address_range)?,
+   });
+}
+
+// Gets a session covering the pages of an
+// address range from the editor cache,
+// opening and caching one if there isn't
+// one already.  This is None when the
+// cache is disabled.
+fn editor_cache_open(
+   address_range  : & std::ops::Range<usize>,
+) -> Result<Option<std::sync::Arc<crate::sys::memory::MemoryEditor>>> {
+   if address_range.is_empty() == true {
+      return Ok(None);
+   }
+
+   let page_range = page_range(address_range);
+
+   let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+   if cache.options.capacity == 0 {
+      return Ok(None);
+   }
+   if cache.writers.iter().any(|writer| ranges_overlap(writer, &page_range)) == true {
+      return Ok(None);
+   }
+
+   // Most recently used goes to the back
+   let now           = std::time::Instant::now();
+   let time_to_live  = cache.options.time_to_live;
+   if let Some(index) = cache.entries.iter().position(|entry| {
+      return entry.page_range.start <= page_range.start
+         && entry.page_range.end    >= page_range.end
+         && now.duration_since(entry.last_used) < time_to_live;
+   }) {
+      let mut entry = cache.entries.remove(index);
+      entry.last_used = now;
+
+      let session = entry.session.clone();
+      cache.entries.push(entry);
+      return Ok(Some(session));
+   }
+
+   let session = std::sync::Arc::new(open_shared_editor(page_range.clone(), false)?);
+
+   // Without the sweeper nothing would ever
+   // restore the pages, so don't cache them.
+   // A sweeper which has stopped sweeping has
+   // already let go of the lock, so it's
+   // only waited on to exit.
+   if cache.sweeping == false {
+      if let Some(sweeper) = cache.sweeper.take() {
+         let _ = sweeper.join();
+      }
+
+      match std::thread::Builder::new()
+         .name(String::from("nusion-editor-cache"))
+         .spawn(editor_cache_sweep)
+      {
+         Ok(sweeper) => {
+            cache.sweeper  = Some(sweeper);
+            cache.sweeping = true;
+         },
+         Err(_)      => return Ok(Some(session)),
+      }
+   }
+
+   cache.entries.push(CachedEditor{
+      page_range  : page_range,
+      session     : session.clone(),
+      last_used   : now,
+   });
+
+   let excess  = cache.entries.len().saturating_sub(cache.options.capacity);
+   let evicted = cache.entries.drain(..excess).collect::<Vec<_>>();
+
+   drop(cache);
+   drop(evicted);
+   return Ok(Some(session));
+}
+
+// Evicts cached sessions overlapping an
+// address range which is about to be opened
+// for writing, and keeps the cache from
+// opening it again until the returned
+// value is dropped
+pub(crate) fn editor_cache_begin_write(
+   address_range  : & std::ops::Range<usize>,
+) -> EditorCacheWrite {
+   let page_range = page_range(address_range);
+
+   let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+   let (evicted, kept) = std::mem::take(& mut cache.entries).into_iter().partition::<Vec<_>, _>(|entry| {
+      return ranges_overlap(&entry.page_range, &page_range);
+   });
+   cache.entries = kept;
+   cache.writers.push(page_range.clone());
+
+   drop(cache);
+   drop(evicted);
+   return EditorCacheWrite{
+      page_range : page_range,
+   };
+}
+
+// Rounds an address range out to
+// whole pages
+fn page_range(
+   address_range  : & std::ops::Range<usize>,
+) -> std::ops::Range<usize> {
+   let page_size = crate::sys::memory::page_size();
+   return address_range.start / page_size * page_size
+      ..address_range.end.div_ceil(page_size) * page_size;
+}
+
+fn ranges_overlap(
+   first    : & std::ops::Range<usize>,
+   second   : & std::ops::Range<usize>,
+) -> bool {
+   return first.start < second.end && second.start < first.end;
+}
+
+// Runs on the editor cache's background
+// thread, restoring pages once they've
+// been idle for the time to live, and
+// exits once nothing is left in the cache
+fn editor_cache_sweep(
+) {
+   let _registration = crate::runtime::register_current_thread();
+
+   let mut cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+   loop {
+      let now           = std::time::Instant::now();
+      let time_to_live  = cache.options.time_to_live;
+
+      let (expired, kept) = std::mem::take(& mut cache.entries).into_iter().partition::<Vec<_>, _>(|entry| {
+         return now.duration_since(entry.last_used) >= time_to_live;
+      });
+      cache.entries = kept;
+
+      // Restoring permissions is a system call,
+      // so don't hold up readers while doing it
+      if expired.is_empty() == false {
+         drop(cache);
+         drop(expired);
+         cache = EDITOR_CACHE.lock().unwrap_or_else(|poison| poison.into_inner());
+         continue;
+      }
+
+      // The handle is kept so this thread
+      // is joined before another is started
+      // or the library is unloaded
+      if cache.entries.is_empty() == true {
+         cache.sweeping = false;
+         return;
+      }
+
+      let next_expiry = cache.entries.iter()
+         .map(|entry| time_to_live.saturating_sub(now.duration_since(entry.last_used)))
+         .min()
+         .unwrap_or(time_to_live);
+
+      cache = EDITOR_CACHE_SWEEP.wait_timeout(cache, next_expiry)
+         .unwrap_or_else(|poison| poison.into_inner())
+         .0;
+   }
+}
+
+/// Finds a jump at the start of the memory
+/// buffer which leads out of the module
+/// containing it, which is how hooks from
+/// other libraries are told apart from the
+/// game's own jumps.
+fn foreign_jump(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Option<crate::sys::compiler::JumpInstruction> {
+   let jump    = crate::sys::compiler::jump_instruction(memory_buffer, address)?;
+   let site    = address;
+
+   let process = crate::process::ProcessSnapshot::local().ok()?;
+   let modules = crate::process::ModuleSnapshotList::all(process).ok()?;
+   let within  = modules.iter().any(|module| {
+      let range = module.address_range();
+      return range.contains(&site) == true && range.contains(&jump.target_address) == true;
+   });
+
+   if within == true {
+      return None;
+   }
+
+   return Some(jump);
+}
+
+/// Builds a writer's patch over the old
+/// bytes stored in an address range, writes
+/// it, and returns a container which
+/// restores the old bytes.
+unsafe fn create_patch<Wt, Mr>(
+   writer         : & Wt,
+   address_range  : std::ops::Range<usize>,
+   old_bytes      : Vec<u8>,
+) -> Result<PatchContainer>
+where Wt: Writer<Mr>,
+      Mr: RangeBounds<usize>,
+{
+   let mut patched = old_bytes.clone();
+   let warnings = writer.build_patch_warnings(&mut patched, address_range.start)?;
+
+   let restore_ranges   = writer.overwritten_ranges(old_bytes.len());
+   let restore_mask     = writer.restore_mask(old_bytes.len());
+   commit_patch(address_range.start, &old_bytes, &patched, &restore_ranges, restore_mask.as_deref())?;
+   log_warnings(warnings);
+
+   // Only create the container once the
+   // patch is written, so a failed write
+   // is never restored
+   return Ok(PatchContainer::new(address_range, old_bytes, restore_ranges, restore_mask));
+}
+
+/// Logs the warnings returned by a writer
+/// once its patch has been written.
+fn log_warnings(
+   warnings : Vec<String>,
+) {
+   for warning in warnings {
+      crate::log::warn!("{warning}");
+   }
+
+   return;
+}
+
+/// Copies the bytes stored in an address
+/// range into a new buffer, returning a
+/// fault as an error instead of crashing.
+unsafe fn read_guarded(
+   address_range  : & std::ops::Range<usize>,
+) -> Result<Vec<u8>> {
+   let mut bytes = vec![0u8; address_range.len()];
+   crate::sys::exception::guarded_copy(
+      bytes.as_mut_ptr(),
+      address_range.start as * const u8,
+      bytes.len(),
+   )?;
+   return Ok(bytes);
+}
+
+/// Writes a built patch to memory at the
+/// address.  If writing faults part of the
+/// way through, the old bytes are written
+/// back before the fault is returned.  This
+/// neither allocates nor locks, so it can
+/// run while other threads are suspended.
+unsafe fn commit_patch(
+   address        : usize,
+   old_bytes      : & [u8],
+   patched        : & [u8],
+   restore_ranges : & [std::ops::Range<usize>],
+   restore_mask   : Option<& [u8]>,
+) -> Result<()> {
+   let result = write_guarded(address, patched, restore_ranges, restore_mask, 0..patched.len());
+   if result.is_err() == true {
+      let _ = write_guarded(address, old_bytes, restore_ranges, restore_mask, 0..old_bytes.len());
+   }
+
+   return result;
+}
+
+/// Writes the parts of the restore ranges
+/// within a range of the memory buffer to
+/// memory at the address.  Where there is
+/// a restore mask, only the masked bits are
+/// written, a naturally aligned integer at
+/// a time, so a bit field written by the
+/// patch is still written atomically.  This
+/// neither allocates nor locks, so it can
+/// run while other threads are suspended.
+unsafe fn write_guarded(
+   address        : usize,
+   memory_buffer  : & [u8],
+   restore_ranges : & [std::ops::Range<usize>],
+   restore_mask   : Option<& [u8]>,
+   within         : std::ops::Range<usize>,
+) -> Result<()> {
+   for range in restore_ranges.iter() {
+      let range = usize::max(range.start, within.start)..usize::min(range.end, within.end);
+      if range.start >= range.end {
+         continue;
+      }
+
+      let bytes = subslice(memory_buffer, range.clone())?;
+      let restore_mask = match restore_mask {
+         Some(restore_mask)   => subslice(restore_mask, range.clone())?,
+         None                 => {
+            crate::sys::exception::guarded_copy(
+               address.wrapping_add(range.start) as * mut u8,
+               bytes.as_ptr(),
+               bytes.len(),
+            )?;
+            continue;
+         },
+      };
+
+      for (offset, length) in merge_pieces(address.wrapping_add(range.start), bytes.len()) {
+         let bit_mask   = pack_bits(subslice(restore_mask, offset..offset + length)?);
+         let bits       = pack_bits(subslice(bytes, offset..offset + length)?);
+         if bit_mask != 0 {
+            crate::sys::exception::guarded_merge(
+               address.wrapping_add(range.start + offset) as * mut u8,
+               length,
+               bit_mask,
+               bits,
+            )?;
+         }
+      }
+   }
+
+   return Ok(());
+}
+
+/// Splits a range of memory into the
+/// offsets and lengths of the largest
+/// naturally aligned integers which
+/// fill it, up to 8 bytes each.
+fn merge_pieces(
+   address  : usize,
+   length   : usize,
+) -> impl Iterator<Item = (usize, usize)> {
+   let mut offset = 0;
+   return std::iter::from_fn(move || {
+      if offset >= length {
+         return None;
+      }
+
+      let piece_address = address.wrapping_add(offset);
+      let piece_length  = [8, 4, 2, 1].into_iter()
+         .find(|size| piece_address % size == 0 && offset + size <= length)
+         .unwrap_or(1);
+
+      let piece = (offset, piece_length);
+      offset += piece_length;
+      return Some(piece);
+   });
+}
+
+/// Splits a range of memory into chunks of
+/// up to the chunk size, extending each
+/// chunk which isn't the last so it ends
+/// on an address aligned to the alignment.
+fn chunk_ranges(
+   address     : usize,
+   length      : usize,
+   chunk_size  : usize,
+   alignment   : usize,
+) -> impl Iterator<Item = std::ops::Range<usize>> {
+   let mut offset = 0;
+   return std::iter::from_fn(move || {
+      if offset >= length {
+         return None;
+      }
+
+      let mut end = usize::min(offset.saturating_add(chunk_size), length);
+      while end < length && address.wrapping_add(end) % alignment != 0 {
+         end += 1;
+      }
+
+      let chunk = offset..end;
+      offset = end;
+      return Some(chunk);
+   });
+}
+
+/// Packs up to 8 bytes into a
+/// little-endian integer.
+fn pack_bits(
+   bytes : & [u8],
+) -> u64 {
+   let mut packed = [0u8; 8];
+   packed.iter_mut().zip(bytes).for_each(|(dest, src)| *dest = *src);
+   return u64::from_le_bytes(packed);
+}
+
+/// Checks the bytes read back from a chunk
+/// of a patch hold what was written to the
+/// parts of the restore ranges within it.
+fn chunk_matches(
+   readback       : & [u8],
+   patched        : & [u8],
+   chunk_range    : & std::ops::Range<usize>,
+   restore_ranges : & [std::ops::Range<usize>],
+   restore_mask   : Option<& [u8]>,
+) -> bool {
+   return restore_ranges.iter()
+      .flat_map(|range| usize::max(range.start, chunk_range.start)..usize::min(range.end, chunk_range.end))
+      .all(|offset| {
+         let mask = match restore_mask {
+            Some(restore_mask)   => restore_mask.get(offset).copied().unwrap_or(0),
+            None                 => 0xFF,
+         };
+
+         return match (readback.get(offset - chunk_range.start), patched.get(offset)) {
+            (Some(read), Some(written))   => (read ^ written) & mask == 0,
+            _                             => false,
+         };
+      });
+}
+
+/// Gets a sub-slice of a memory buffer,
+/// returning an error instead of panicking
+/// if the range doesn't fit.
+fn subslice(
+   memory_buffer  : & [u8],
+   range          : std::ops::Range<usize>,
+) -> Result<& [u8]> {
+   let maximum = memory_buffer.len();
+   return memory_buffer.get(range.clone()).ok_or(PatchError::OutOfRange{
+      maximum  : maximum,
+      provided : range.end,
+   });
+}
+
+/// Gets a mutable sub-slice of a memory
+/// buffer, returning an error instead of
+/// panicking if the range doesn't fit.
+fn subslice_mut(
+   memory_buffer  : & mut [u8],
+   range          : std::ops::Range<usize>,
+) -> Result<& mut [u8]> {
+   let maximum = memory_buffer.len();
+   return memory_buffer.get_mut(range.clone()).ok_or(PatchError::OutOfRange{
+      maximum  : maximum,
+      provided : range.end,
+   });
+}
+
+/// Verifies the memory buffer doesn't end
+/// in the middle of an instruction, which
+/// would leave the rest of the instruction
+/// behind as garbage after overwriting it.
+/// The address is where the bytes are
+/// stored, used to read what follows them.
+/// Code which can't be decoded is an error,
+/// since it can't be known where it ends.
+fn verify_instruction_boundary(
+   memory_buffer  : & [u8],
+   address        : usize,
+) -> Result<()> {
+   let mut offset = 0;
+   for (instruction_offset, instruction) in crate::asm::instructions(memory_buffer) {
+      offset = instruction_offset + instruction.length;
+   }
+
+   if offset >= memory_buffer.len() {
+      return Ok(());
+   }
+
+   // The last instruction continues past
+   // the end of the buffer, so read enough
+   // of what follows to decode all of it
+   let remaining  = memory_buffer.get(offset..).unwrap_or(&[]);
+   let mut bytes  = [0u8; MAXIMUM_INSTRUCTION_LENGTH];
+   let mut length = remaining.len().min(MAXIMUM_INSTRUCTION_LENGTH);
+   let (start, end) = bytes.split_at_mut(length);
+   start.iter_mut().zip(remaining).for_each(|(dest, src)| *dest = *src);
+   if crate::sys::memory::read_checked(address.wrapping_add(memory_buffer.len()), end).is_ok() {
+      length = MAXIMUM_INSTRUCTION_LENGTH;
+   }
+
+   return match crate::asm::decode_len(bytes.get(..length).unwrap_or(&[])) {
+      Some(length) if length > remaining.len()
+         => Err(PatchError::SplitsInstruction{
+            offset         : offset,
+            length         : length,
+            range_length   : memory_buffer.len(),
+         }),
+      Some(_)
+         => Ok(()),
+      None
+         => Err(PatchError::UndecodableInstruction{
+            offset         : offset,
+         }),
+   };
+}
+
+/// Tracks a container's restore state in
+/// the patch registry so it is reverted
+/// by <code>revert_all</code>.
+fn register_restore<U: RestoreAction + 'static>(
+   address_range  : std::ops::Range<usize>,
+   undo           : U,
+) -> std::sync::Arc<PatchRestore<U>> {
+   let restore = std::sync::Arc::new(PatchRestore{
+      address_range  : address_range,
+      undo           : undo,
+      active         : std::sync::atomic::AtomicBool::new(true),
+      poisoned       : std::sync::atomic::AtomicBool::new(false),
+   });
+
+   PATCH_REGISTRY.lock()
+      .unwrap_or_else(|poison| poison.into_inner())
+      .push(RegistryEntry{
+         patch    : restore.clone(),
+         released : false,
+      });
+
+   return restore;
+}
+
+/// Releases a container's entry in the
+/// patch registry when it's dropped, then
+/// restores every released patch which
+/// isn't covered by a newer one.
+fn release_restore<U: RestoreAction + 'static>(
+   restore : & std::sync::Arc<PatchRestore<U>>,
+) {
+   let failures = {
+      let mut registry = PATCH_REGISTRY.lock().unwrap_or_else(|poison| poison.into_inner());
+
+      let address = std::sync::Arc::as_ptr(restore);
+      if let Some(entry) = registry.iter_mut().find(|entry| {
+         return std::ptr::addr_eq(std::sync::Arc::as_ptr(&entry.patch), address);
+      }) {
+         entry.released = true;
+      }
+
+      restore_released(&mut registry)
+   };
+
+   // Drops can run while the mod unloads,
+   // where a panic would take down the game,
+   // and reporting the error may sleep
+   for (address, err) in failures {
+      crate::log::error!("Failed to restore patched bytes at {address:#X}: {err}");
+   }
+
+   return;
+}
+
+/// Restores a container's changes right
+/// away, unless a newer overlapping patch
+/// is still applied.
+fn restore_registered<U: RestoreAction + 'static>(
+   restore : & std::sync::Arc<PatchRestore<U>>,
+) -> Result<()> {
+   let registry = PATCH_REGISTRY.lock().unwrap_or_else(|poison| poison.into_inner());
+
+   let address = std::sync::Arc::as_ptr(restore);
+   if let Some(index) = registry.iter().position(|entry| {
+      return std::ptr::addr_eq(std::sync::Arc::as_ptr(&entry.patch), address);
+   }) {
+      if let Some(newer) = covering_patch(&registry, index) {
+         return Err(PatchError::CoveredByNewerPatch{
+            address_range : newer.address_range().clone(),
+         });
+      }
+   }
+
+   return restore.restore();
+}
+
+/// Restores and removes every released
+/// entry which no newer active patch
+/// overlaps, newest first, returning
+/// the address and error of each one
+/// which failed.  Restoring an entry
+/// may uncover older ones, which are
+/// only checked after it.  Entries which
+/// fail stay registered, still covering
+/// older ones, so they're tried again
+/// the next time any entry is released.
+fn restore_released(
+   registry : & mut Vec<RegistryEntry>,
+) -> Vec<(usize, PatchError)> {
+   let mut failures  = Vec::new();
+   let mut index     = registry.len();
+   while index > 0 {
+      index -= 1;
+
+      let Some(entry) = registry.get(index) else {
+         continue;
+      };
+      if entry.released == false || covering_patch(registry, index).is_some() == true {
+         continue;
+      }
+
+      if let Err(err) = entry.patch.restore() {
+         failures.push((entry.patch.address_range().start, err));
+         continue;
+      }
+      registry.remove(index);
+   }
+
+   return failures;
+}
+
+/// Finds a newer active patch overlapping
+/// the registry entry at the index, other
+/// than a hook linked into the same chain.
+fn covering_patch(
+   registry : & [RegistryEntry],
+   index    : usize,
+) -> Option<& dyn RegisteredPatch> {
+   let entry = registry.get(index)?;
+   let chain = entry.patch.linked_chain();
+
+   return registry.iter().skip(index + 1).map(|newer| newer.patch.as_ref()).find(|newer| {
+      return newer.is_active() == true
+         && ranges_overlap(entry.patch.address_range(), newer.address_range()) == true
+         && (chain.is_some() == true && newer.linked_chain() == chain) == false;
+   });
+}
+
+/// Writes a new pointer to a pointer-sized
+/// slot, returning the old pointer.
+unsafe fn swap_pointer(
+   slot_address   : usize,
+   value          : usize,
+) -> Result<usize> {
+   let slot_range = slot_address..slot_address + std::mem::size_of::<usize>();
+   let _write     = editor_cache_begin_write(&slot_range);
+   let mut editor = crate::sys::memory::MemoryEditor::open_read_write(slot_range)?;
+
+   let old = editor.as_slice_mut::<usize>().first_mut().map(|slot| {
+      return std::mem::replace(slot, value);
+   });
+
+   // Failing to restore the permissions is
+   // returned instead of panicking on drop
+   editor.close()?;
+   return old.ok_or(PatchError::LengthMismatch{
+      found    : 0,
+      expected : std::mem::size_of::<usize>(),
+   });
+}
+
+/// Suspends every other thread in the
+/// process, retrying for a short time
+/// while any of them is executing inside
+/// the address range.
+unsafe fn suspend_threads_outside(
+   address_range  : & std::ops::Range<usize>,
+) -> Result<crate::sys::thread::SuspendedThreads> {
+   const ATTEMPTS : usize = 64;
+
+   let mut address = address_range.start;
+   for _ in 0..ATTEMPTS {
+      let threads = crate::sys::thread::SuspendedThreads::suspend_others()?;
+
+      match threads.instruction_pointers().iter().find(|ip| {
+         address_range.contains(ip)
+      }) {
+         Some(ip) => address = *ip,
+         None     => return Ok(threads),
+      }
+
+      // Let the thread run out of the
+      // range before trying again
+      std::mem::drop(threads);
+      std::thread::sleep(std::time::Duration::from_millis(1));
+   }
+
+   return Err(PatchError::ThreadInRange{
+      address  : address,
+   });
+}
+
+/// Follows a pointer chain starting with
+/// the pointer stored in the memory buffer,
+/// returning the address at the end.
+fn resolve_pointer_chain(
+   memory_buffer  : & [u8],
+   offsets        : & [usize],
+) -> Result<usize> {
+   let pointer_size = std::mem::size_of::<usize>();
+
+   let mut address = usize::from_ne_bytes(
+      memory_buffer.try_into().map_err(|_| PatchError::LengthMismatch{
+         found    : memory_buffer.len(),
+         expected : pointer_size,
+      })?,
+   );
+
+   let (last, steps) = match offsets.split_last() {
+      Some(split) => split,
+      None        => return Ok(address),
+   };
+
+   for (step, offset) in steps.iter().enumerate() {
+      let pointer = address.wrapping_add(*offset);
+      verify_readable(pointer, pointer_size, step)?;
+
+      // Checked to be readable above
+      address = unsafe{std::ptr::read_unaligned(pointer as * const usize)};
+   }
+
+   return Ok(address.wrapping_add(*last));
+}
+
+/// Follows a pointer chain starting with
+/// the pointer stored at an address and
+/// writes a value at the end, checking
+/// every step is readable and the value's
+/// memory is writable.
+pub(crate) unsafe fn write_pointer_chain<T>(
+   base_address   : usize,
+   offsets        : & [usize],
+   value          : T,
+) -> Result<()>
+where T: Copy,
+{
+   let pointer_size = std::mem::size_of::<usize>();
+
+   verify_readable(base_address, pointer_size, 0)?;
+   let base_pointer = std::slice::from_raw_parts(base_address as * const u8, pointer_size);
+
+   let address = resolve_pointer_chain(base_pointer, offsets)?;
+   let length  = std::mem::size_of::<T>();
+   let invalid = PatchError::InvalidPointer{
+      step     : offsets.len().saturating_sub(1),
+      address  : address,
+   };
+
+   verify_readable(address, length, offsets.len().saturating_sub(1))?;
+   let regions = match crate::sys::memory::MemoryRegion::all_within(address..address + length) {
+      Ok(regions) => regions,
+      Err(_)      => return Err(invalid),
+   };
+   if regions.iter().all(|region| region.is_writable() == true) == false {
+      return Err(invalid);
+   }
+
+   std::ptr::write_unaligned(address as * mut T, value);
+   return Ok(());
+}
+
+/// Checks that every byte in a range
+/// starting at an address is mapped and
+/// readable, since a pointer chain may
+/// contain stale or null pointers.
+fn verify_readable(
+   address  : usize,
+   length   : usize,
+   step     : usize,
+) -> Result<()> {
+   let invalid = PatchError::InvalidPointer{
+      step     : step,
+      address  : address,
+   };
+
+   let end = match address.checked_add(length) {
+      Some(end)   => end,
+      None        => return Err(invalid),
+   };
+   if address == 0 {
+      return Err(invalid);
+   }
+
+   let regions = match crate::sys::memory::MemoryRegion::all_within(address..end) {
+      Ok(regions) => regions,
+      Err(_)      => return Err(invalid),
+   };
+   if regions.iter().all(|region| {
+      region.is_committed() == true && region.is_readable() == true
+   }) == false {
+      return Err(invalid);
+   }
+
+   return Ok(());
+}
+
+///////////
+// TESTS //
+///////////
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   // Keeps a test buffer aligned to 8
+   // bytes, so fields within it have a
+   // known alignment
+   #[repr(align(8))]
+   struct Aligned([u8; 16]);
+
+   #[test]
+   fn bits_restore_mask() {
+      let writer = writer::Bits{
+         memory_offset_range : 0x10..0x12,
+         checksum            : Checksum::new(&[0x00, 0x00]),
+         bit_mask            : 0x0400,
+         value               : 1,
+      };
+
+      assert_eq!(writer.restore_mask(2), Some(vec![0x00, 0x04]));
+   }
+
+   #[test]
+   fn scatter_restore_mask() {
+      let bits = writer::Bits{
+         memory_offset_range : 0x14..0x16,
+         checksum            : Checksum::new(&[0x00, 0x00]),
+         bit_mask            : 0x8001,
+         value               : 0x0101,
+      };
+      let item = writer::Item{
+         memory_offset_range : 0x10..0x12,
+         checksum            : Checksum::new(&[0x00, 0x00]),
+         item                : &0u16,
+      };
+
+      // Writers without a mask have every
+      // bit restored
+      let writers : [& dyn Writer<std::ops::Range<usize>>; 2] = [&item, &bits];
+      let scatter = writer::Scatter::new(&writers).unwrap();
+      assert_eq!(scatter.restore_mask(6), Some(vec![0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0x80]));
+
+      let writers : [& dyn Writer<std::ops::Range<usize>>; 1] = [&item];
+      let scatter = writer::Scatter::new(&writers).unwrap();
+      assert_eq!(scatter.restore_mask(2), None);
+   }
+
+   #[test]
+   fn verify_bit_mask_bounds() {
+      assert!(verify_bit_mask(0x01, 1).is_ok());
+      assert!(verify_bit_mask(0x8000_0000_0000_0000, 8).is_ok());
+      assert!(matches!(verify_bit_mask(0x01, 0), Err(PatchError::BitFieldTooWide{length : 0})));
+      assert!(matches!(verify_bit_mask(0x01, 9), Err(PatchError::BitFieldTooWide{length : 9})));
+      assert!(matches!(verify_bit_mask(0x00, 4), Err(PatchError::BitMaskOutOfRange{..})));
+      assert!(matches!(verify_bit_mask(0x0001_0000, 2), Err(PatchError::BitMaskOutOfRange{..})));
+   }
+
+   #[test]
+   fn store_and_load_bits_aligned() {
+      for length in [1, 2, 4, 8] {
+         let mut buffer = Aligned([0xAA; 16]);
+         let field = &mut buffer.0[8 - length..8];
+         store_bits(field, 0x0F, 0x05);
+         assert_eq!(load_bits(field) & 0xFF, 0xA5);
+         assert_eq!(field.first(), Some(&0xA5));
+         assert!(field.iter().skip(1).all(|byte| *byte == 0xAA));
+      }
+   }
+
+   #[test]
+   fn store_and_load_bits_unaligned() {
+      let mut buffer = Aligned([0x00; 16]);
+      let field = &mut buffer.0[1..4];
+      store_bits(field, 0x00FF_0F00, 0x0012_0300);
+      assert_eq!(field, [0x00, 0x03, 0x12]);
+      assert_eq!(load_bits(field), 0x0012_0300);
+
+      let field = &mut buffer.0[3..5];
+      store_bits(field, 0xFF00, 0xFFFF);
+      assert_eq!(field, [0x12, 0xFF]);
+   }
+
+   #[test]
+   fn merge_pieces_alignment() {
+      assert_eq!(merge_pieces(0x1000, 8).collect::<Vec<_>>(), [(0, 8)]);
+      assert_eq!(merge_pieces(0x1003, 13).collect::<Vec<_>>(), [(0, 1), (1, 4), (5, 8)]);
+      assert_eq!(merge_pieces(0x1002, 3).collect::<Vec<_>>(), [(0, 2), (2, 1)]);
+      assert_eq!(merge_pieces(0x1000, 0).count(), 0);
+   }
+
+   #[test]
+   fn pack_bits_little_endian() {
+      assert_eq!(pack_bits(&[]), 0);
+      assert_eq!(pack_bits(&[0x01, 0x02]), 0x0201);
+      assert_eq!(pack_bits(&[0xFF; 8]), u64::MAX);
+   }
+
+   #[test]
+   fn chunk_ranges_alignment() {
+      assert_eq!(chunk_ranges(0x1003, 10, 4, 1).collect::<Vec<_>>(), [0..4, 4..8, 8..10]);
+      assert_eq!(chunk_ranges(0x1003, 20, 4, 8).collect::<Vec<_>>(), [0..5, 5..13, 13..20]);
+      assert_eq!(chunk_ranges(0x1000, 16, usize::MAX, 8).collect::<Vec<_>>(), [0..16]);
+      assert_eq!(chunk_ranges(0x1000, 0, 4, 8).count(), 0);
+   }
+
+   #[test]
+   fn chunk_matches_masked_bits() {
+      let patched = [0x11, 0x22, 0x34];
+      let mask    = [0x00, 0xFF, 0x0F];
+
+      assert!(chunk_matches(&[0x99, 0x22, 0x04], &patched, &(0..3), &[0..3], Some(&mask)) == true);
+      assert!(chunk_matches(&[0x11, 0x23, 0x34], &patched, &(0..3), &[0..3], Some(&mask)) == false);
+      assert!(chunk_matches(&[0x99, 0x22], &patched, &(0..2), &[0..3], None) == false);
+      assert!(chunk_matches(&[0x22, 0x34], &patched, &(1..3), &[1..3], None) == true);
+   }
+
+   #[test]
+   fn string_fit_length_policies() {
+      assert_eq!(string_fit_length(3, 4, StringTruncation::Error).unwrap(), 3);
+      assert!(matches!(
+         string_fit_length(4, 4, StringTruncation::Error),
+         Err(PatchError::StringTooLong{length : 5, capacity : 4}),
+      ));
+
+      assert_eq!(string_fit_length(3, 4, StringTruncation::Truncate).unwrap(), 3);
+      assert_eq!(string_fit_length(8, 4, StringTruncation::Truncate).unwrap(), 3);
+      assert!(matches!(
+         string_fit_length(1, 0, StringTruncation::Truncate),
+         Err(PatchError::StringTooLong{length : 2, capacity : 0}),
+      ));
+
+      assert_eq!(string_fit_length(8, 4, StringTruncation::TruncateUnterminated).unwrap(), 4);
+      assert_eq!(string_fit_length(2, 4, StringTruncation::TruncateUnterminated).unwrap(), 2);
+      assert_eq!(string_fit_length(2, 0, StringTruncation::TruncateUnterminated).unwrap(), 0);
+   }
+
+   #[test]
+   fn cstr_truncates_at_char_boundary() {
+      let mut buffer = [0xFFu8; 4];
+      writer::CStr{
+         memory_offset_range : 0..4,
+         checksum            : Checksum::new(&[]),
+         string              : "ab\u{E9}",
+         truncation          : StringTruncation::Truncate,
+      }.build_patch(&mut buffer, 0).unwrap();
+
+      // The two-byte character doesn't fit
+      // before the terminator, so it's dropped
+      // whole instead of split
+      assert_eq!(buffer, [b'a', b'b', 0x00, 0x00]);
+      assert_eq!(reader::CStr{memory_offset_range : 0..4}.read_item(&buffer).unwrap(), "ab");
+   }
+
+   #[test]
+   fn wide_str_keeps_surrogate_pairs() {
+      let writer = |string, truncation, buffer : & mut [u8]| writer::WideStr{
+         memory_offset_range : 0..buffer.len(),
+         checksum            : Checksum::new(&[]),
+         string              : string,
+         truncation          : truncation,
+      }.build_patch(buffer, 0);
+
+      // A pair which fits is written whole
+      let mut buffer = [0xFFu8; 8];
+      writer("a\u{1F600}", StringTruncation::Truncate, &mut buffer).unwrap();
+      assert_eq!(reader::WideStr{memory_offset_range : 0..8}.read_item(&buffer).unwrap(), "a\u{1F600}");
+
+      // A pair cut in half is dropped whole
+      let mut buffer = [0xFFu8; 6];
+      writer("ab\u{1F600}", StringTruncation::TruncateUnterminated, &mut buffer).unwrap();
+      assert_eq!(buffer.chunks_exact(2).map(|chunk| u16::from_ne_bytes([chunk[0], chunk[1]])).collect::<Vec<u16>>(), [0x61, 0x62, 0x00]);
+
+      let mut buffer = [0xFFu8; 6];
+      assert!(matches!(
+         writer("ab\u{1F600}", StringTruncation::Error, &mut buffer),
+         Err(PatchError::StringTooLong{length : 10, capacity : 6}),
+      ));
+      assert!(matches!(
+         writer("a", StringTruncation::Error, &mut [0u8; 3]),
+         Err(PatchError::ResidualBytes{residual : 1}),
+      ));
+   }
+
+   #[test]
+   fn write_guarded_masked_bits() {
+      let mut buffer = Aligned([0xAA; 16]);
+      let patched = [0x00, 0x55, 0x55, 0x55, 0x55, 0x55];
+      let mask    = [0x00, 0x0F, 0xFF, 0xFF, 0xFF, 0xF0];
+
+      // Only the masked bits within both the
+      // restore ranges and the limit are written
+      unsafe{write_guarded(buffer.0.as_mut_ptr() as usize + 2, &patched, &[1..6], Some(&mask), 0..5)}.unwrap();
+      assert_eq!(buffer.0[..8], [0xAA, 0xAA, 0xAA, 0xA5, 0x55, 0x55, 0x55, 0xAA]);
+   }
+
+   #[test]
+   fn return_constant_instruction_boundary() {
+      let writer = recipes::ReturnConstant::void(0, Checksum::new(&[0x00]));
+
+      // mov rax, imm64 would be left without
+      // its first byte
+      let code = [0x48, 0xB8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xC3];
+      let mut buffer = [code[0]];
+      assert!(matches!(
+         writer.build_patch(&mut buffer, code.as_ptr() as usize),
+         Err(PatchError::SplitsInstruction{offset : 0, length : 10, range_length : 1}),
+      ));
+      assert_eq!(buffer, [0x48]);
+
+      // push rbp is replaced whole
+      let code = [0x55, 0x48, 0x89, 0xE5, 0xC3];
+      let mut buffer = [code[0]];
+      writer.build_patch(&mut buffer, code.as_ptr() as usize).unwrap();
+      assert_eq!(buffer, [0xC3]);
+   }
+
+   #[test]
+   fn struct_view_offset_overflow() {
+      // Maps offsets straight onto addresses
+      struct Everything;
+
+      impl PatchTarget for Everything {
+         fn resolve_range<R>(
+            & self,
+            offset_range : & R,
+         ) -> Result<std::ops::Range<usize>>
+         where R: RangeBounds<usize>,
+         {
+            return resolve_offset_range(offset_range, usize::MAX);
+         }
+      }
+
+      assert!(view::StructView::<_, u64>::new(&Everything, usize::MAX - 4).is_err());
+
+      let view = view::StructView::<_, [u64; 2]>::new(&Everything, usize::MAX - 23).unwrap();
+      assert!(view.view::<u64>(16).is_err());
+      assert_eq!(view.view::<u64>(8).unwrap().memory_offset(), usize::MAX - 15);
+   }
+
+   #[test]
+   fn create_batch_verifies_checksums_first() {
+      let mut image = crate::trace::MemoryImage::new(vec![0xAA; 8]);
+      let first = writer::Slice{
+         memory_offset_range  : 0..2,
+         checksum             : Checksum::new(&[0xAA, 0xAA]),
+         slice                : &[0x11u8, 0x22],
+      };
+      let second = writer::Slice{
+         memory_offset_range  : 4..6,
+         checksum             : Checksum::new(&[0x00, 0x00]),
+         slice                : &[0x33u8, 0x44],
+      };
+
+      // Containers of a memory image don't
+      // restore when dropped, so nothing may
+      // be written before the mismatch is found
+      assert!(matches!(
+         unsafe{image.patch_create_batch(&[&first, &second])},
+         Err(PatchError::ChecksumMismatch{..}),
+      ));
+      assert_eq!(image.as_bytes(), [0xAA; 8]);
+   }
+
+   #[test]
+   fn write_batch_rollback_keeps_unmasked_bits() {
+      let mut image = crate::trace::MemoryImage::new(vec![0xAA; 4]);
+      let bits = writer::Bits{
+         memory_offset_range  : 0..1,
+         checksum             : Checksum::new(&[0x0A]),
+         bit_mask             : 0x0F,
+         value                : 0x05,
+      };
+
+      unsafe{image.patch_write(&bits)}.unwrap();
+      unsafe{image.patch_write_unchecked(&writer::Slice{
+         memory_offset_range  : 0..1,
+         checksum             : Checksum::new(&[0x00]),
+         slice                : &[0x35u8],
+      })}.unwrap();
+
+      // Only the low bits belong to the patch,
+      // so the high bits changed since are kept
+      unsafe{restore_written(&mut image, &bits, &[0xAA])}.unwrap();
+      assert_eq!(image.as_bytes(), [0x3A, 0xAA, 0xAA, 0xAA]);
+   }
+}
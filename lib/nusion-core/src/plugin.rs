@@ -0,0 +1,493 @@
+//! A stable interface between a mod which
+//! hosts feature plugins and the plugins,
+//! which are separate mods loaded into the
+//! same game.  Each side may be built against
+//! a different version of this library, so
+//! everything crossing between them is a
+//! <code>repr(C)</code> table of plain values
+//! and function pointers with an ABI version.
+//!
+//! The host exports its table with
+//! <code>plugin_host!</code>:
+//!
+//! ```
+//! nusion_core::plugin_host!();
+//!
+//! #[nusion_core::main("FSD-Win64-Shipping.exe")]
+//! fn main() {
+//!    for plugin in nusion_core::plugin::registered_plugins() {
+//!       nusion_core::log::info!("Plugin loaded: {}", plugin.name);
+//!    }
+//! }
+//! ```
+//!
+//! Plugins then register by name, and are
+//! unregistered when the registration drops:
+//!
+//! ```
+//! let registration = unsafe{nusion_core::plugin::register("Extra HUD")}?;
+//! registration.log(nusion_core::log::Level::Info, "Hello from a plugin");
+//! ```
+//!
+//! Both sides check the other's ABI version.
+//! A plugin is rejected with an error instead
+//! of calling into a table it doesn't
+//! understand.  New entries are only ever
+//! appended to the tables, and the
+//! <code>size</code> field tells the reader
+//! which entries exist, so a newer host can
+//! still accept plugins built for an older
+//! ABI down to <code>MIN_ABI_VERSION</code>.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Version of the interface implemented
+/// by this copy of the library.  This is
+/// increased whenever entries are appended
+/// to <code>HostApi</code> or <code>PluginInfo</code>.
+pub const ABI_VERSION : u32 = 1;
+
+/// Oldest interface version this copy
+/// of the library can talk to, from
+/// either side.
+pub const MIN_ABI_VERSION : u32 = 1;
+
+/// Name of the function exported by
+/// <code>plugin_host!</code> which returns
+/// a pointer to the host's <code>HostApi</code>.
+pub const HOST_EXPORT : &'static str = "nusion_plugin_host";
+
+/// Size of the entries in <code>PluginInfo</code>
+/// which exist in every ABI version.
+const PLUGIN_INFO_MIN_SIZE : usize = std::mem::size_of::<PluginInfo>();
+
+/// Status returned by the host when
+/// the plugin was registered.
+pub const STATUS_REGISTERED         : u32 = 0;
+/// Status returned by the host when the
+/// plugin's ABI version isn't supported.
+pub const STATUS_INCOMPATIBLE_ABI   : u32 = 1;
+/// Status returned by the host when a
+/// plugin with the same name exists.
+pub const STATUS_DUPLICATE_NAME     : u32 = 2;
+/// Status returned by the host when
+/// the plugin information is malformed.
+pub const STATUS_INVALID_INFO       : u32 = 3;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to registering
+/// with a plugin host.
+#[derive(Debug)]
+pub enum PluginError {
+   ProcessError{
+      err                  : crate::process::ProcessError,
+   },
+   HostNotFound,
+   HostTooOld{
+      host_abi_version     : u32,
+      required             : u32,
+   },
+   Rejected{
+      status               : u32,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>PluginError</code>.
+pub type Result<T> = std::result::Result<T, PluginError>;
+
+/// Information a plugin passes to the host
+/// when registering.  It only needs to live
+/// for the duration of the call.
+#[repr(C)]
+pub struct PluginInfo {
+   /// Interface version the plugin was built for.
+   pub abi_version            : u32,
+   /// Oldest host interface the plugin accepts.
+   pub min_host_abi_version   : u32,
+   /// Size of this struct as the plugin knows it.
+   pub size                   : usize,
+   pub name                   : * const u8,
+   pub name_length            : usize,
+   /// Called when the host unloads, after
+   /// which the plugin must not call into
+   /// the host again.
+   pub host_unloading         : Option<extern "C" fn()>,
+}
+
+/// Table of functions the host provides
+/// to plugins.
+#[repr(C)]
+pub struct HostApi {
+   /// Interface version the host was built for.
+   pub abi_version      : u32,
+   /// Oldest plugin interface the host accepts.
+   pub min_abi_version  : u32,
+   /// Size of this struct as the host knows it.
+   pub size             : usize,
+   /// Registers a plugin, writing its ID and
+   /// returning one of the status constants.
+   pub register         : extern "C" fn(* const PluginInfo, * mut u64) -> u32,
+   pub unregister       : extern "C" fn(u64),
+   /// Logs a UTF-8 message through the host,
+   /// with a level from 1 (error) to 5 (trace).
+   pub log              : extern "C" fn(u64, u32, * const u8, usize),
+}
+
+/// A plugin registered with a host, which
+/// is unregistered when this is dropped.
+pub struct PluginRegistration {
+   host  : &'static HostApi,
+   id    : u64,
+}
+
+/// A plugin registered with this mod
+/// as the host.
+#[derive(Clone, Debug)]
+pub struct RegisteredPlugin {
+   pub id            : u64,
+   pub name          : String,
+   pub abi_version   : u32,
+}
+
+/// Host-side record of a plugin.
+struct HostedPlugin {
+   plugin         : RegisteredPlugin,
+   host_unloading : Option<extern "C" fn()>,
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PluginError //
+/////////////////////////////////////////
+
+impl std::fmt::Display for PluginError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ProcessError{err}
+            => write!(stream, "Process error: {err}"),
+         Self::HostNotFound
+            => write!(stream, "No loaded module exports \"{HOST_EXPORT}\""),
+         Self::HostTooOld{host_abi_version, required}
+            => write!(stream, "Host uses plugin ABI {host_abi_version}, at least {required} is required"),
+         Self::Rejected{status}
+            => match *status {
+               STATUS_INCOMPATIBLE_ABI
+                  => write!(stream, "Host doesn't support plugin ABI {ABI_VERSION}"),
+               STATUS_DUPLICATE_NAME
+                  => write!(stream, "Host already has a plugin with the same name"),
+               STATUS_INVALID_INFO
+                  => write!(stream, "Host rejected the plugin information as invalid"),
+               _  => write!(stream, "Host rejected the plugin with status {status}"),
+            },
+      };
+   }
+}
+
+impl std::error::Error for PluginError {
+}
+
+impl From<crate::process::ProcessError> for PluginError {
+   fn from(
+      item : crate::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : item,
+      };
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PluginRegistration //
+////////////////////////////////////////////////
+
+impl Drop for PluginRegistration {
+   fn drop(
+      & mut self,
+   ) {
+      if HOST_LOADED.load(Ordering::SeqCst) == true {
+         (self.host.unregister)(self.id);
+      }
+      return;
+   }
+}
+
+//////////////////////////////////
+// METHODS - PluginRegistration //
+//////////////////////////////////
+
+impl PluginRegistration {
+   /// Gets the ID the host assigned
+   /// to the plugin.
+   pub fn id(
+      & self,
+   ) -> u64 {
+      return self.id;
+   }
+
+   /// Gets the interface version
+   /// of the host.
+   pub fn host_abi_version(
+      & self,
+   ) -> u32 {
+      return self.host.abi_version;
+   }
+
+   /// Logs a message through the host,
+   /// targeted at the plugin's name.  This
+   /// does nothing once the host unloads.
+   pub fn log(
+      & self,
+      level    : crate::log::Level,
+      message  : & str,
+   ) {
+      if HOST_LOADED.load(Ordering::SeqCst) == true {
+         (self.host.log)(self.id, level as u32, message.as_ptr(), message.len());
+      }
+      return;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Gets the table this mod provides
+/// when acting as a plugin host.
+pub fn host_api(
+) -> &'static HostApi {
+   return &HOST_API;
+}
+
+/// Gets every plugin currently registered
+/// with this mod as the host.
+pub fn registered_plugins(
+) -> Vec<RegisteredPlugin> {
+   return lock_plugins().iter().map(|hosted| hosted.plugin.clone()).collect();
+}
+
+/// Tells every registered plugin that the
+/// host is unloading and forgets them.  This
+/// is called when the environment is freed.
+pub fn notify_host_unloading(
+) {
+   let plugins = std::mem::take(&mut *lock_plugins());
+   for hosted in plugins {
+      if let Some(host_unloading) = hosted.host_unloading {
+         host_unloading();
+      }
+   }
+   return;
+}
+
+/// Registers with the first loaded module
+/// which exports a plugin host.  Hosts are
+/// found through their exports, which are
+/// only read on Windows.
+///
+/// <h2 id=  register_safety>
+/// <a href=#register_safety>
+/// Safety
+/// </a></h2>
+///
+/// Any module exporting a function named
+/// <code>nusion_plugin_host</code> is assumed
+/// to have created it with <code>plugin_host!</code>.
+pub unsafe fn register(
+   name : & str,
+) -> Result<PluginRegistration> {
+   let modules = crate::process::ModuleSnapshotList::all(
+      crate::process::ProcessSnapshot::local()?,
+   )?;
+
+   for module in modules.iter() {
+      // Modules which can't be parsed
+      // can't be hosts either
+      if let Some(address) = module.export_address(HOST_EXPORT).ok().flatten() {
+         let host_export = std::mem::transmute::<
+            usize, extern "C" fn() -> * const HostApi
+         >(address);
+         let host = host_export();
+         if host.is_null() == false {
+            return register_with(&*host, name);
+         }
+      }
+   }
+
+   return Err(PluginError::HostNotFound);
+}
+
+/// Registers with a host given its
+/// table directly.
+///
+/// <h2 id=  register_with_safety>
+/// <a href=#register_with_safety>
+/// Safety
+/// </a></h2>
+///
+/// The table must be a <code>HostApi</code>
+/// from a loaded host, and stays valid
+/// until the host notifies its plugins
+/// that it is unloading.
+pub unsafe fn register_with(
+   host : &'static HostApi,
+   name : & str,
+) -> Result<PluginRegistration> {
+   // Nothing past the version fields can be
+   // trusted until the version is checked
+   if host.abi_version < MIN_ABI_VERSION {
+      return Err(PluginError::HostTooOld{
+         host_abi_version  : host.abi_version,
+         required          : MIN_ABI_VERSION,
+      });
+   }
+
+   let info = PluginInfo{
+      abi_version          : ABI_VERSION,
+      min_host_abi_version : MIN_ABI_VERSION,
+      size                 : std::mem::size_of::<PluginInfo>(),
+      name                 : name.as_ptr(),
+      name_length          : name.len(),
+      host_unloading       : Some(host_unloading),
+   };
+
+   let mut id = 0;
+   let status = (host.register)(&info, &mut id);
+   if status != STATUS_REGISTERED {
+      return Err(PluginError::Rejected{
+         status : status,
+      });
+   }
+
+   HOST_LOADED.store(true, Ordering::SeqCst);
+   return Ok(PluginRegistration{
+      host  : host,
+      id    : id,
+   });
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn lock_plugins(
+) -> std::sync::MutexGuard<'static, Vec<HostedPlugin>> {
+   return PLUGINS.lock().unwrap_or_else(|poison| poison.into_inner());
+}
+
+extern "C" fn host_register(
+   info  : * const PluginInfo,
+   id    : * mut u64,
+) -> u32 {
+   if info.is_null() == true || id.is_null() == true {
+      return STATUS_INVALID_INFO;
+   }
+   let info = unsafe{&*info};
+
+   if info.abi_version < MIN_ABI_VERSION || info.min_host_abi_version > ABI_VERSION {
+      return STATUS_INCOMPATIBLE_ABI;
+   }
+   if info.size < PLUGIN_INFO_MIN_SIZE || info.name.is_null() == true {
+      return STATUS_INVALID_INFO;
+   }
+
+   let name = unsafe{std::slice::from_raw_parts(info.name, info.name_length)};
+   let name = match std::str::from_utf8(name) {
+      Ok(name) => name.to_owned(),
+      Err(_)   => return STATUS_INVALID_INFO,
+   };
+
+   let mut plugins = lock_plugins();
+   if plugins.iter().any(|hosted| hosted.plugin.name == name) == true {
+      return STATUS_DUPLICATE_NAME;
+   }
+
+   let plugin_id = NEXT_PLUGIN_ID.fetch_add(1, Ordering::Relaxed);
+   plugins.push(HostedPlugin{
+      plugin         : RegisteredPlugin{
+         id             : plugin_id,
+         name           : name,
+         abi_version    : info.abi_version,
+      },
+      host_unloading : info.host_unloading,
+   });
+
+   unsafe{*id = plugin_id};
+   return STATUS_REGISTERED;
+}
+
+extern "C" fn host_unregister(
+   id : u64,
+) {
+   lock_plugins().retain(|hosted| hosted.plugin.id != id);
+   return;
+}
+
+extern "C" fn host_log(
+   id       : u64,
+   level    : u32,
+   message  : * const u8,
+   length   : usize,
+) {
+   if message.is_null() == true {
+      return;
+   }
+   let message = unsafe{std::slice::from_raw_parts(message, length)};
+   let message = String::from_utf8_lossy(message);
+
+   let level = match level {
+      1 => crate::log::Level::Error,
+      2 => crate::log::Level::Warn,
+      3 => crate::log::Level::Info,
+      4 => crate::log::Level::Debug,
+      _ => crate::log::Level::Trace,
+   };
+
+   let target = lock_plugins().iter()
+      .find(|hosted| hosted.plugin.id == id)
+      .map(|hosted| hosted.plugin.name.clone())
+      .unwrap_or_else(|| String::from("plugin"));
+
+   ::log::log!(target : &target, level, "{message}");
+   return;
+}
+
+extern "C" fn host_unloading(
+) {
+   HOST_LOADED.store(false, Ordering::SeqCst);
+   return;
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+static HOST_API : HostApi = HostApi{
+   abi_version      : ABI_VERSION,
+   min_abi_version  : MIN_ABI_VERSION,
+   size             : std::mem::size_of::<HostApi>(),
+   register         : host_register,
+   unregister       : host_unregister,
+   log              : host_log,
+};
+
+static PLUGINS
+   : Mutex<Vec<HostedPlugin>>
+   = Mutex::new(Vec::new());
+
+static NEXT_PLUGIN_ID
+   : AtomicU64
+   = AtomicU64::new(1);
+
+/// Whether the host this mod registered
+/// with as a plugin is still loaded.
+static HOST_LOADED
+   : AtomicBool
+   = AtomicBool::new(false);
+
@@ -4,6 +4,10 @@
 use std::collections::hash_map::HashMap;
 use std::ops::RangeBounds;
 
+// Sections are provided by the
+// system abstraction layer as-is
+pub use crate::sys::process::Section;
+
 //////////////////////
 // TYPE DEFINITIONS //
 //////////////////////
@@ -13,6 +17,12 @@ use std::ops::RangeBounds;
 #[derive(Debug)]
 pub enum ProcessError {
    BadExecutableFileName,
+   InvalidImage,
+   AccessDenied,
+   RemoteMemoryFailed,
+   InjectionFailed,
+   SpawnFailed,
+   Unsupported,
    Unknown,
 }
 
@@ -39,14 +49,92 @@ pub struct ProcessSnapshot {
 /// error.
 pub struct ModuleSnapshot {
    snapshot : crate::sys::process::ModuleSnapshot,
+   sections : std::sync::OnceLock<Vec<Section>>,
 }
 
 /// The container for storing patched
 /// bytes in a module for restoration
 /// when the instance is dropped.
-pub struct ModuleSnapshotPatchContainer {
+pub type ModuleSnapshotPatchContainer = crate::patch::PatchContainer;
+
+/// A module in another process which is
+/// patched from outside of it, such as
+/// from a launcher or external tool.  This
+/// implements <code>Patch</code> by copying
+/// memory in and out of the process, so
+/// the same readers and writers work both
+/// inside and outside of the process.
+/// Writers which compile code relative to
+/// their own address, such as hooks, can't
+/// be used since their memory buffer is a
+/// local copy.
+///
+/// ```
+/// let processes = nusion_core::process::ProcessSnapshotList::all()?;
+/// let process   = processes.get("hl2.exe").unwrap();
+/// let modules   = nusion_core::process::ModuleSnapshotList::all(process)?;
+/// let module    = modules.get("hl2.exe").unwrap();
+///
+/// let mut game = nusion_core::process::RemoteProcess::open(modules.parent_process(), module)?;
+///
+/// use nusion_core::patch::Patch;
+/// unsafe{game.patch_write(&WRITER_HEALTH)}?;
+/// ```
+pub struct RemoteProcess {
+   process        : std::sync::Arc<crate::sys::process::RemoteProcess>,
+   address_range  : std::ops::Range<usize>,
+}
+
+/// Memory in the current process which
+/// is addressed directly instead of by
+/// offsets into a module, such as heap
+/// objects found by following a pointer
+/// chain.  Offsets used by readers and
+/// writers are absolute addresses, and
+/// patches have the same checksum and
+/// container behavior as with a
+/// <code>ModuleSnapshot</code>.
+///
+/// ```
+/// use nusion_core::patch::Patch;
+/// let player = unsafe{game.patch_read(&nusion_core::patch::reader::PointerChainAddress{
+///    memory_offset_range : 0x02A4_B1C0..0x02A4_B1C8,
+///    offsets             : &[0x18, 0x250, 0x0],
+/// })}?;
+///
+/// let mut memory = nusion_core::process::ProcessMemory::new();
+/// unsafe{memory.patch_write(&nusion_core::patch::writer::Item{
+///    memory_offset_range  : player + 0x44..player + 0x48,
+///    checksum             : nusion_core::patch::Checksum::from(0),
+///    item                 : &100.0f32,
+/// })}?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProcessMemory {
+   address_range  : std::ops::Range<usize>,
+}
+
+/// The container for storing patched
+/// bytes in another process for restoration
+/// when the instance is dropped.
+pub struct RemoteProcessPatchContainer {
+   process        : std::sync::Arc<crate::sys::process::RemoteProcess>,
    address_range  : std::ops::Range<usize>,
    old_bytes      : Vec<u8>,
+   restore_ranges : Vec<std::ops::Range<usize>>,
+}
+
+/// A process which was started suspended,
+/// before any of its own code has run.
+/// Libraries can be loaded into it through
+/// <code>inject::inject_and_resume</code>
+/// or by passing its process ID to
+/// <code>inject::inject_library</code>
+/// before resuming it.  If this is dropped
+/// without resuming the process, the
+/// process is terminated.
+pub struct SuspendedProcess {
+   process : crate::sys::process::SuspendedProcess,
 }
 
 /// A list of process snapshots created
@@ -92,6 +180,18 @@ impl std::fmt::Display for ProcessError {
       return write!(stream, "{}", match self {
          Self::BadExecutableFileName
             => "Executable file name contains invalid characters",
+         Self::InvalidImage
+            => "Module image is malformed",
+         Self::AccessDenied
+            => "Access to the process was denied",
+         Self::RemoteMemoryFailed
+            => "Failed to access memory in another process",
+         Self::InjectionFailed
+            => "Failed to load a library into another process",
+         Self::SpawnFailed
+            => "Failed to start a new process",
+         Self::Unsupported
+            => "Unsupported on this platform",
          Self::Unknown
             => "Unknown",
       });
@@ -109,6 +209,18 @@ impl From<crate::sys::process::ProcessError> for ProcessError {
       return match item {
          BadExecutableFileName
             => Self::BadExecutableFileName,
+         InvalidImage
+            => Self::InvalidImage,
+         AccessDenied
+            => Self::AccessDenied,
+         RemoteMemoryFailed
+            => Self::RemoteMemoryFailed,
+         InjectionFailed
+            => Self::InjectionFailed,
+         SpawnFailed
+            => Self::SpawnFailed,
+         Unsupported
+            => Self::Unsupported,
          Unknown
             => Self::Unknown,
       };
@@ -140,6 +252,40 @@ impl ProcessSnapshot {
    ) -> &'l str {
       return self.snapshot.executable_file_name();
    }
+
+   /// Checks whether the executable's
+   /// file name matches a pattern.  See
+   /// <code>executable_file_name_matches</code>
+   /// for the pattern syntax.
+   pub fn executable_file_name_matches(
+      & self,
+      pattern : & str,
+   ) -> bool {
+      return executable_file_name_matches(self.executable_file_name(), pattern);
+   }
+
+   /// Gets the process ID assigned
+   /// by the operating system.
+   pub fn process_id(
+      & self,
+   ) -> u32 {
+      return self.snapshot.process_id();
+   }
+}
+
+////////////////////////////////////////
+// INTERNAL HELPERS - ProcessSnapshot //
+////////////////////////////////////////
+
+impl ProcessSnapshot {
+   pub(crate) fn open_remote(
+      & self,
+   ) -> std::result::Result<
+      crate::sys::process::RemoteProcess,
+      crate::sys::process::ProcessError,
+   > {
+      return crate::sys::process::RemoteProcess::open(&self.snapshot);
+   }
 }
 
 //////////////////////////////
@@ -166,6 +312,385 @@ impl ModuleSnapshot {
    ) -> &'l str {
       return self.snapshot.executable_file_name();
    }
+
+   /// Finds the address of a function the
+   /// module exports by name, such as
+   /// <code>CreateDevice</code> in a graphics
+   /// library.  Anchoring hooks on exports
+   /// keeps them working across updates which
+   /// shift raw offsets.  If the module doesn't
+   /// export the function or forwards it to
+   /// another module, <code>None</code> is
+   /// returned.  Exports are only read from
+   /// PE images, so this always returns
+   /// <code>None</code> on Linux.
+   ///
+   /// ```
+   /// let d3d11 = nusion_core::env!().modules().get("d3d11.dll").unwrap();
+   /// let create_device = unsafe{d3d11.export_address("D3D11CreateDevice")}?;
+   /// ```
+   ///
+   /// <h2 id=  module_snapshot_export_address_safety>
+   /// <a href=#module_snapshot_export_address_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn export_address(
+      & self,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      return Ok(self.export_offset(function)?.map(|offset| {
+         self.address_range().start + offset
+      }));
+   }
+
+   /// Finds the offset of a function the
+   /// module exports by name, for use in
+   /// a patch's memory offset range.
+   ///
+   /// <h2 id=  module_snapshot_export_offset_safety>
+   /// <a href=#module_snapshot_export_offset_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn export_offset(
+      & self,
+      function : & str,
+   ) -> Result<Option<usize>> {
+      return Ok(self.snapshot.export_rva(function)?);
+   }
+
+   /// Gets the function at an offset within
+   /// the module as a function pointer of
+   /// type <code>F</code>, checking that the
+   /// offset lies within one of the module's
+   /// sections and in executable memory.  The
+   /// <code>game_fn!</code> macro is shorthand
+   /// for this which can also pick the calling
+   /// convention.
+   ///
+   /// ```
+   /// let damage = unsafe{crate::game!().function::<extern "C" fn(* mut Entity, i32)>(0x4F22A0)}?;
+   /// damage(player, 10);
+   /// ```
+   ///
+   /// <h2 id=  module_snapshot_function_safety>
+   /// <a href=#module_snapshot_function_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// <code>F</code> must be a function pointer
+   /// type whose arguments, return type, and
+   /// calling convention match the function at
+   /// the offset, which can't be checked.  The
+   /// function pointer is only valid while the
+   /// module stays loaded.
+   pub unsafe fn function<F: Copy>(
+      & self,
+      offset : usize,
+   ) -> crate::patch::Result<F> {
+      const {
+         assert!(
+            std::mem::size_of::<F>() == std::mem::size_of::<usize>(),
+            "Game function type must be a function pointer",
+         );
+      }
+
+      let address_range = self.offset_range_to_address_range(&(offset..=offset))?;
+      self.verify_in_sections(&address_range)?;
+
+      let region = crate::sys::memory::MemoryRegion::query(address_range.start)?;
+      if region.is_committed() == false || region.is_executable() == false {
+         return Err(crate::patch::PatchError::NotExecutable{
+            offset : offset,
+         });
+      }
+
+      return Ok(std::mem::transmute_copy::<usize, F>(&address_range.start));
+   }
+
+   /// Reads the module's file version from
+   /// its version resource.  If the module
+   /// has no version resource, such as on
+   /// Linux, <code>None</code> is returned.
+   ///
+   /// <h2 id=  module_snapshot_file_version_safety>
+   /// <a href=#module_snapshot_file_version_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn file_version(
+      & self,
+   ) -> Result<Option<crate::versions::Version>> {
+      return Ok(self.snapshot.file_version()?.map(crate::versions::Version::from));
+   }
+
+   /// Gets the module's section table, such
+   /// as <code>.text</code> and <code>.rdata</code>,
+   /// sorted by start address.  The table is
+   /// read the first time this is called and
+   /// kept for the life of the snapshot.  Images
+   /// without a section table, such as on Linux,
+   /// have no sections.
+   ///
+   /// ```
+   /// for section in unsafe{crate::game!().sections()}? {
+   ///    nusion_core::log::info!("{} {:#X?}", section.name, section.address_range);
+   /// }
+   /// ```
+   ///
+   /// <h2 id=  module_snapshot_sections_safety>
+   /// <a href=#module_snapshot_sections_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn sections(
+      & self,
+   ) -> Result<&[Section]> {
+      return Ok(self.cached_sections()?);
+   }
+
+   /// Finds a section by its exact name,
+   /// such as <code>.rdata</code>.
+   ///
+   /// <h2 id=  module_snapshot_section_safety>
+   /// <a href=#module_snapshot_section_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn section(
+      & self,
+      name : & str,
+   ) -> Result<Option<&Section>> {
+      return Ok(self.sections()?.iter().find(|section| section.name == name));
+   }
+
+   /// Gets the address range of the module's
+   /// code, which is the <code>.text</code>
+   /// section, or the first executable section
+   /// if the linker named it differently.  If
+   /// the module has no section table, such as
+   /// on Linux, <code>None</code> is returned.
+   ///
+   /// ```
+   /// let game  = crate::game!();
+   /// let text  = unsafe{game.text_range()}?.unwrap();
+   /// let found = unsafe{game.find_pattern_in(&pattern, &text)}?;
+   /// ```
+   ///
+   /// <h2 id=  module_snapshot_text_range_safety>
+   /// <a href=#module_snapshot_text_range_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn text_range(
+      & self,
+   ) -> Result<Option<std::ops::Range<usize>>> {
+      let sections = self.sections()?;
+      return Ok(sections.iter()
+         .find(|section| section.name == ".text")
+         .or_else(|| sections.iter().find(|section| section.is_executable() == true))
+         .map(|section| section.address_range.clone()));
+   }
+
+   /// Converts a relative virtual address
+   /// (RVA), such as one shown by a PE viewer
+   /// or disassembler, into a module offset.
+   /// Module offsets are measured from the
+   /// start of the loaded module just like
+   /// RVAs, so this only checks the RVA lies
+   /// within the module.
+   pub fn rva_to_offset(
+      & self,
+      rva : usize,
+   ) -> Option<usize> {
+      let range = self.address_range();
+      if rva >= range.end - range.start {
+         return None;
+      }
+
+      return Some(rva);
+   }
+
+   /// Converts an offset within the module's
+   /// file on disk, such as one shown by a
+   /// hex editor, into a module offset.  If
+   /// the file offset isn't loaded into memory,
+   /// <code>None</code> is returned.  This is
+   /// only supported for PE images, so this
+   /// always returns <code>None</code> on Linux.
+   ///
+   /// <h2 id=  module_snapshot_file_offset_to_offset_safety>
+   /// <a href=#module_snapshot_file_offset_to_offset_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #module_snapshot_export_address_safety>export_address</a></code>
+   /// apply.
+   pub unsafe fn file_offset_to_offset(
+      & self,
+      file_offset : usize,
+   ) -> Result<Option<usize>> {
+      return Ok(self.snapshot.file_offset_to_rva(file_offset)?);
+   }
+
+   /// Finds the offset of the first match
+   /// of a byte pattern within the module's
+   /// readable memory, or <code>None</code>
+   /// if the pattern doesn't occur.  This
+   /// allows code to be located after a game
+   /// update shifts its offset.
+   ///
+   /// ```
+   /// let pattern = nusion_core::scan::Pattern::parse("48 8B ?? ?? 89 46 ?0")?;
+   /// let offset  = unsafe{crate::game!().find_pattern(&pattern)}?;
+   /// ```
+   ///
+   /// <h2 id=  module_snapshot_find_pattern_safety>
+   /// <a href=#module_snapshot_find_pattern_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>scan::find_string_refs</code>
+   /// apply.
+   pub unsafe fn find_pattern(
+      & self,
+      pattern : & crate::scan::Pattern,
+   ) -> crate::scan::Result<Option<usize>> {
+      return Ok(self.find_pattern_iter(pattern)?.next());
+   }
+
+   /// Finds the offset of the first match
+   /// of a byte pattern within an address
+   /// range of the module, such as one of
+   /// its sections, so scans for code don't
+   /// match data with the same bytes.  Only
+   /// the readable memory in the range is
+   /// searched.
+   ///
+   /// <h2 id=  module_snapshot_find_pattern_in_safety>
+   /// <a href=#module_snapshot_find_pattern_in_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>scan::find_string_refs</code>
+   /// apply.
+   pub unsafe fn find_pattern_in(
+      & self,
+      pattern        : & crate::scan::Pattern,
+      address_range  : & std::ops::Range<usize>,
+   ) -> crate::scan::Result<Option<usize>> {
+      return Ok(crate::scan::PatternMatches::within(self, pattern, address_range)?.next());
+   }
+
+   /// Iterates over the offset of every
+   /// match of a byte pattern within the
+   /// module's readable memory.  Matches
+   /// may overlap.
+   ///
+   /// <h2 id=  module_snapshot_find_pattern_iter_safety>
+   /// <a href=#module_snapshot_find_pattern_iter_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>scan::find_string_refs</code>
+   /// apply for as long as the iterator
+   /// is used.
+   pub unsafe fn find_pattern_iter<'p>(
+      & self,
+      pattern : &'p crate::scan::Pattern,
+   ) -> crate::scan::Result<crate::scan::PatternMatches<'p>> {
+      return crate::scan::PatternMatches::new(self, pattern);
+   }
+
+   /// Overwrites every occurrence of a
+   /// byte pattern in the module's readable
+   /// memory with the replacement bytes,
+   /// stopping after at most <code>limit</code>
+   /// occurrences.  Each occurrence gets its
+   /// own container, so sites can be restored
+   /// individually.  If any site fails to
+   /// patch, every site patched so far is
+   /// restored before returning the error.
+   ///
+   /// <h2 id=  module_snapshot_replace_all_safety>
+   /// <a href=#module_snapshot_replace_all_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_create</code> apply
+   /// to every occurrence.  Make sure the
+   /// pattern is specific enough that it
+   /// can't match anything unintended.
+   pub unsafe fn replace_all(
+      & mut self,
+      pattern     : & crate::scan::Pattern,
+      replacement : & [u8],
+      limit       : usize,
+   ) -> crate::scan::Result<Vec<ModuleSnapshotPatchContainer>> {
+      use crate::patch::Patch;
+
+      if replacement.len() != pattern.len() {
+         return Err(crate::patch::PatchError::LengthMismatch{
+            found    : replacement.len(),
+            expected : pattern.len(),
+         }.into());
+      }
+
+      // Find every site before patching so
+      // replacements can't create new matches
+      let spans = crate::scan::readable_spans(self, |_| true)?;
+      let sites = crate::scan::find_pattern_addresses(&spans, pattern);
+
+      // Skip matches overlapping a previous one,
+      // since their containers would restore
+      // each other's bytes
+      let mut site_end = 0;
+      let sites = sites.into_iter().filter(|address| {
+         if *address < site_end {
+            return false;
+         }
+         site_end = address + pattern.len();
+         return true;
+      });
+
+      let base = self.address_range().start;
+      let mut containers = Vec::new();
+      for address in sites.take(limit) {
+         let offset = address - base;
+
+         containers.push(self.patch_create_unchecked(&crate::patch::writer::Slice{
+            memory_offset_range  : offset..offset + replacement.len(),
+            checksum             : crate::patch::Checksum::from(0),
+            slice                : replacement,
+         })?);
+      }
+
+      return Ok(containers);
+   }
 }
 
 ///////////////////////////////////////
@@ -173,64 +698,165 @@ impl ModuleSnapshot {
 ///////////////////////////////////////
 
 impl ModuleSnapshot {
-   fn offset_range_to_address_range<R>(
+   pub(crate) fn offset_range_to_address_range<R>(
       & self,
       offset_range   : & R,
    ) -> crate::patch::Result<std::ops::Range<usize>>
    where R: RangeBounds<usize>,
    {
-      let address_start = self.address_range().start;
-      let address_end   = self.address_range().end;
-
-      use std::ops::Bound;
-      let offset_start = match offset_range.start_bound() {
-         Bound::Included(b)
-            => b.clone(),
-         Bound::Excluded(b)
-            => b.checked_add(1).ok_or(crate::patch::PatchError::OutOfRange{
-               maximum  : usize::MAX,
-               provided : b.clone(),
-            })?,
-         Bound::Unbounded
-            => 0,
-      };
-      let offset_end = match offset_range.end_bound() {
-         Bound::Included(b)
-            => b.checked_add(1).ok_or(crate::patch::PatchError::OutOfRange{
-               maximum  : usize::MAX,
-               provided : b.clone(),
-            })?,
-         Bound::Excluded(b)
-            => b.clone(),
-         Bound::Unbounded
-            => address_end - address_start, // Will always be valid
+      let address_start = crate::patch::Offset::new(self.address_range().start);
+      let offset_range  = crate::patch::resolve_offset_range(
+         offset_range,
+         self.address_range().end - self.address_range().start,
+      )?;
+
+      return Ok((address_start + offset_range.start)?.range_to(
+         (address_start + offset_range.end)?,
+      )?);
+   }
+
+   /// Verifies the address range lies fully
+   /// within the module's sections, so a
+   /// mistyped offset can't land in the image
+   /// headers or past the end of the image.
+   /// Ranges may span adjacent sections.  The
+   /// error contains the module offset of the
+   /// first byte outside of a section.  If
+   /// the image has no section table, any
+   /// range within the module is accepted.
+   fn verify_in_sections(
+      & self,
+      address_range  : & std::ops::Range<usize>,
+   ) -> crate::patch::Result<()> {
+      if address_range.start == address_range.end {
+         return Ok(());
+      }
+
+      let section_ranges = match unsafe{self.section_ranges()}? {
+         Some(ranges)   => ranges,
+         None           => return Ok(()),
       };
 
-      let address_target_start = address_start
-         .checked_add(offset_start)
-         .ok_or(crate::patch::PatchError::OutOfRange{
-            maximum  : usize::MAX - address_start,
-            provided : offset_start,
-         })?;
-
-      let address_target_end = address_start
-         .checked_add(offset_end)
-         .ok_or(crate::patch::PatchError::OutOfRange{
-            maximum  : usize::MAX - address_end,
-            provided : offset_end,
-         })?;
-
-      if address_target_end > address_end {
-         return Err(crate::patch::PatchError::OutOfRange{
-            maximum  : address_end - address_start,
-            provided : offset_end,
-         });
+      let base        = self.address_range().start;
+      let mut offset  = address_range.start - base;
+      let offset_end  = address_range.end - base;
+
+      while offset < offset_end {
+         offset = section_ranges.iter()
+            .find(|section| section.contains(&offset) == true)
+            .ok_or(crate::patch::PatchError::OutsideSections{
+               offset : offset,
+            })?
+            .end;
+      }
+
+      return Ok(());
+   }
+
+   /// Verifies every page in the address
+   /// range is mapped and committed, so
+   /// reading it won't fault.  The error
+   /// contains the module offset of the
+   /// first unmapped byte.
+   fn verify_mapped(
+      & self,
+      address_range  : & std::ops::Range<usize>,
+   ) -> crate::patch::Result<()> {
+      return verify_mapped(address_range, self.address_range().start);
+   }
+
+   pub(crate) unsafe fn unwind_function(
+      & self,
+      address : usize,
+   ) -> std::result::Result<
+      Option<crate::sys::process::UnwindFunction>,
+      crate::sys::process::ProcessError,
+   > {
+      return self.snapshot.unwind_function(address);
+   }
+
+   pub(crate) unsafe fn import_slot(
+      & self,
+      library  : & str,
+      function : & str,
+   ) -> std::result::Result<
+      Option<usize>,
+      crate::sys::process::ProcessError,
+   > {
+      return self.snapshot.import_slot(library, function);
+   }
+
+   pub(crate) unsafe fn import_table_ranges(
+      & self,
+   ) -> std::result::Result<
+      Vec<std::ops::Range<usize>>,
+      crate::sys::process::ProcessError,
+   > {
+      return self.snapshot.import_table_ranges();
+   }
+
+   /// Reads the section table the first
+   /// time it's needed, so every function
+   /// describing sections shares one parse.
+   unsafe fn cached_sections(
+      & self,
+   ) -> std::result::Result<
+      &[Section],
+      crate::sys::process::ProcessError,
+   > {
+      if let Some(sections) = self.sections.get() {
+         return Ok(sections);
       }
-      if address_target_end < address_target_start {
-         return Err(crate::patch::PatchError::EndOffsetBeforeStartOffset);
+
+      let sections = self.snapshot.sections()?.unwrap_or_default();
+      return Ok(self.sections.get_or_init(|| sections));
+   }
+
+   /// Gets the ranges of the module's sections
+   /// relative to the start of the module,
+   /// sorted by start offset.  If the module
+   /// has no section table, <code>None</code>
+   /// is returned.
+   pub(crate) unsafe fn section_ranges(
+      & self,
+   ) -> std::result::Result<
+      Option<Vec<std::ops::Range<usize>>>,
+      crate::sys::process::ProcessError,
+   > {
+      return self.section_offset_ranges(|_| true);
+   }
+
+   /// The same as <code>section_ranges</code>,
+   /// except only sections containing code
+   /// are included.
+   pub(crate) unsafe fn executable_section_ranges(
+      & self,
+   ) -> std::result::Result<
+      Option<Vec<std::ops::Range<usize>>>,
+      crate::sys::process::ProcessError,
+   > {
+      return self.section_offset_ranges(|section| section.is_executable() == true);
+   }
+
+   unsafe fn section_offset_ranges<F>(
+      & self,
+      filter : F,
+   ) -> std::result::Result<
+      Option<Vec<std::ops::Range<usize>>>,
+      crate::sys::process::ProcessError,
+   >
+   where F: Fn(&Section) -> bool,
+   {
+      let sections = self.cached_sections()?;
+      if sections.is_empty() == true {
+         return Ok(None);
       }
 
-      return Ok(address_target_start..address_target_end);
+      let base = self.address_range().start;
+      return Ok(Some(sections.iter()
+         .filter(|section| filter(section) == true)
+         .map(|section| section.address_range.start - base..section.address_range.end - base)
+         .collect()));
    }
 }
 
@@ -238,60 +864,245 @@ impl ModuleSnapshot {
 // TRAIT IMPLEMENTATIONS - ModuleSnapshot //
 ////////////////////////////////////////////
 
-impl crate::patch::Patch for ModuleSnapshot {
-   type Container = ModuleSnapshotPatchContainer;
+impl crate::patch::PatchTarget for ModuleSnapshot {
+   fn resolve_range<R>(
+      & self,
+      offset_range   : & R,
+   ) -> crate::patch::Result<std::ops::Range<usize>>
+   where R: RangeBounds<usize>,
+   {
+      let address_range = self.offset_range_to_address_range(offset_range)?;
+      self.verify_in_sections(&address_range)?;
+      return Ok(address_range);
+   }
 
-   unsafe fn patch_read<Rd, Mr>(
+   fn memory_editor(
       & self,
-      reader : & Rd,
-   ) -> crate::patch::Result<Rd::Item>
-   where Rd: crate::patch::Reader<Mr>,
-         Mr: RangeBounds<usize>,
+      address_range  : std::ops::Range<usize>,
+      writable       : bool,
+   ) -> crate::patch::Result<crate::patch::PatchMemory> {
+      // Reads shouldn't fault on gaps in
+      // the module's mapping
+      if writable == false {
+         self.verify_mapped(&address_range)?;
+      }
+
+      return crate::patch::PatchMemory::open(address_range, writable);
+   }
+}
+
+/////////////////////////////
+// METHODS - ProcessMemory //
+/////////////////////////////
+
+impl ProcessMemory {
+   /// Creates a view of the whole
+   /// address space of the current
+   /// process.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         address_range  : 0..usize::MAX,
+      };
+   }
+
+   /// Creates a view of only part of
+   /// the current process' address space.
+   /// Addresses outside of the range are
+   /// rejected, and an unbounded end in a
+   /// reader or writer's range stops at the
+   /// end of this range.
+   pub fn within(
+      address_range  : std::ops::Range<usize>,
+   ) -> Self {
+      return Self{
+         address_range  : address_range,
+      };
+   }
+
+   /// Gets the address range which
+   /// can be patched.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.address_range;
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ProcessMemory //
+///////////////////////////////////////////
+
+impl Default for ProcessMemory {
+   fn default(
+   ) -> Self {
+      return Self::new();
+   }
+}
+
+impl crate::patch::PatchTarget for ProcessMemory {
+   fn resolve_range<R>(
+      & self,
+      offset_range   : & R,
+   ) -> crate::patch::Result<std::ops::Range<usize>>
+   where R: RangeBounds<usize>,
    {
-      let address_range = self.offset_range_to_address_range(
-         reader.memory_offset_range(),
+      let address_range = crate::patch::resolve_offset_range(
+         offset_range,
+         self.address_range.end,
       )?;
 
-      let editor = crate::sys::memory::MemoryEditor::open_read(
-         address_range,
-      )?;
+      if address_range.start < self.address_range.start {
+         return Err(crate::patch::PatchError::OutsideAddressRange{
+            address_range  : self.address_range.clone(),
+            provided       : address_range,
+         });
+      }
 
-      let bytes = editor.as_bytes();
+      return Ok(address_range);
+   }
 
-      let item = reader.read_item(bytes)?;
+   fn memory_editor(
+      & self,
+      address_range  : std::ops::Range<usize>,
+      writable       : bool,
+   ) -> crate::patch::Result<crate::patch::PatchMemory> {
+      // Nothing is known about an arbitrary
+      // address, so always check it's mapped,
+      // and other threads may be using the
+      // same pages while they're open
+      verify_mapped(&address_range, 0)?;
+      return crate::patch::PatchMemory::open_shared(address_range, writable);
+   }
+}
 
-      return Ok(item);
+/////////////////////////////
+// METHODS - RemoteProcess //
+/////////////////////////////
+
+impl RemoteProcess {
+   /// Opens a module in another process
+   /// for patching.  Offsets used by readers
+   /// and writers are relative to the start
+   /// of the module.
+   pub fn open(
+      process  : & ProcessSnapshot,
+      module   : & ModuleSnapshot,
+   ) -> Result<Self> {
+      return Ok(Self{
+         process        : std::sync::Arc::new(
+            crate::sys::process::RemoteProcess::open(&process.snapshot)?,
+         ),
+         address_range  : module.address_range().clone(),
+      });
    }
 
-   unsafe fn patch_write<Wt, Mr>(
+   /// Gets the address range occupied
+   /// by the module within the process.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.address_range;
+   }
+}
+
+//////////////////////////////////////
+// INTERNAL METHODS - RemoteProcess //
+//////////////////////////////////////
+
+impl RemoteProcess {
+   fn resolve_range<R>(
+      & self,
+      offset_range   : & R,
+   ) -> crate::patch::Result<std::ops::Range<usize>>
+   where R: RangeBounds<usize>,
+   {
+      let range = crate::patch::resolve_offset_range(
+         offset_range,
+         self.address_range.end - self.address_range.start,
+      )?;
+
+      let address_start = crate::patch::Offset::new(self.address_range.start);
+      return Ok((address_start + range.start)?.range_to((address_start + range.end)?)?);
+   }
+
+   fn read_range(
+      & self,
+      address_range  : & std::ops::Range<usize>,
+   ) -> crate::patch::Result<Vec<u8>> {
+      let mut bytes = vec![0; address_range.end - address_range.start];
+      self.process.read(address_range.start, & mut bytes)?;
+      return Ok(bytes);
+   }
+
+   unsafe fn write_with<Wt, Mr>(
       & mut self,
-      writer : & Wt,
-   ) -> crate::patch::Result<()>
+      writer   : & Wt,
+      checked  : bool,
+      create   : bool,
+   ) -> crate::patch::Result<Option<RemoteProcessPatchContainer>>
    where Wt: crate::patch::Writer<Mr>,
          Mr: RangeBounds<usize>,
    {
-      let address_range = self.offset_range_to_address_range(
-         writer.memory_offset_range(),
-      )?;
+      // The bytes are built in this process,
+      // so they must not depend on where
+      // they will be written
+      if writer.position_independent() == false {
+         return Err(crate::patch::PatchError::PositionDependent);
+      }
 
-      let mut editor = crate::sys::memory::MemoryEditor::open_read_write(
-         address_range,
-      )?;
+      let address_range = self.resolve_range(writer.memory_offset_range())?;
+      let mut bytes     = self.read_range(&address_range)?;
+
+      if checked == true {
+         writer.verify_checksum(&bytes, address_range.start)?;
+      }
 
-      let bytes = editor.as_bytes_mut();
+      let container = match create {
+         true  => Some(RemoteProcessPatchContainer{
+            process        : self.process.clone(),
+            address_range  : address_range.clone(),
+            old_bytes      : bytes.clone(),
+            restore_ranges : writer.overwritten_ranges(bytes.len()),
+         }),
+         false => None,
+      };
 
-      let bytes_checksum = crate::patch::Checksum::new(bytes);
-      let patch_checksum = writer.checksum();
+      writer.build_patch(& mut bytes, address_range.start)?;
+      self.process.write(address_range.start, &bytes)?;
 
-      if &bytes_checksum != patch_checksum {
-         return Err(crate::patch::PatchError::ChecksumMismatch{
-            found    : bytes_checksum,
-            expected : patch_checksum.clone(),
-         });
-      }
+      return Ok(container);
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RemoteProcess //
+///////////////////////////////////////////
+
+impl crate::patch::Patch for RemoteProcess {
+   type Container = RemoteProcessPatchContainer;
+
+   unsafe fn patch_read<Rd, Mr>(
+      & self,
+      reader : & Rd,
+   ) -> crate::patch::Result<Rd::Item>
+   where Rd: crate::patch::Reader<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let address_range = self.resolve_range(reader.memory_offset_range())?;
+      let bytes         = self.read_range(&address_range)?;
 
-      writer.build_patch(bytes)?;
-      
+      return reader.read_item(&bytes);
+   }
+
+   unsafe fn patch_write<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<()>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      self.write_with(writer, true, false)?;
       return Ok(());
    }
 
@@ -302,18 +1113,7 @@ impl crate::patch::Patch for ModuleSnapshot {
    where Wt: crate::patch::Writer<Mr>,
          Mr: RangeBounds<usize>,
    {
-      let address_range = self.offset_range_to_address_range(
-         writer.memory_offset_range(),
-      )?;
-
-      let mut editor = crate::sys::memory::MemoryEditor::open_read_write(
-         address_range,
-      )?;
-
-      let bytes = editor.as_bytes_mut();
-
-      writer.build_patch(bytes)?;
-
+      self.write_with(writer, false, false)?;
       return Ok(());
    }
 
@@ -324,34 +1124,8 @@ impl crate::patch::Patch for ModuleSnapshot {
    where Wt: crate::patch::Writer<Mr>,
          Mr: RangeBounds<usize>,
    {
-      let address_range = self.offset_range_to_address_range(
-         writer.memory_offset_range(),
-      )?;
-
-      let mut editor = crate::sys::memory::MemoryEditor::open_read_write(
-         address_range.clone(),
-      )?;
-
-      let bytes = editor.as_bytes_mut();
-
-      let bytes_checksum = crate::patch::Checksum::new(bytes);
-      let patch_checksum = writer.checksum();
-
-      if &bytes_checksum != patch_checksum {
-         return Err(crate::patch::PatchError::ChecksumMismatch{
-            found    : bytes_checksum,
-            expected : patch_checksum.clone(),
-         });
-      }
-
-      let container = Self::Container{
-         address_range  : address_range,
-         old_bytes      : bytes.to_vec(),
-      };
-
-      writer.build_patch(bytes)?;
-
-      return Ok(container);
+      return Ok(self.write_with(writer, true, true)?
+         .expect("Remote patch container is missing, this is a bug!"));
    }
 
    unsafe fn patch_create_unchecked<Wt, Mr>(
@@ -361,45 +1135,91 @@ impl crate::patch::Patch for ModuleSnapshot {
    where Wt: crate::patch::Writer<Mr>,
          Mr: RangeBounds<usize>,
    {
-      let address_range = self.offset_range_to_address_range(
-         writer.memory_offset_range(),
-      )?;
-
-      let mut editor = crate::sys::memory::MemoryEditor::open_read_write(
-         address_range.clone(),
-      )?;
-
-      let bytes = editor.as_bytes_mut();
-
-      let container = Self::Container{
-         address_range  : address_range,
-         old_bytes      : bytes.to_vec(),
-      };
+      return Ok(self.write_with(writer, false, true)?
+         .expect("Remote patch container is missing, this is a bug!"));
+   }
+}
 
-      writer.build_patch(bytes)?;
+///////////////////////////////////////////
+// METHODS - RemoteProcessPatchContainer //
+///////////////////////////////////////////
 
-      return Ok(container);
+impl RemoteProcessPatchContainer {
+   /// Gets the address range within the
+   /// process the patch was written to.
+   pub fn address_range<'l>(
+      &'l self,
+   ) -> &'l std::ops::Range<usize> {
+      return &self.address_range;
    }
 }
 
-//////////////////////////////////////////////////////////
-// TRAIT IMPLEMENTATIONS - ModuleSnapshotPatchContainer //
-//////////////////////////////////////////////////////////
+/////////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RemoteProcessPatchContainer //
+/////////////////////////////////////////////////////////
 
-impl std::ops::Drop for ModuleSnapshotPatchContainer {
+impl std::ops::Drop for RemoteProcessPatchContainer {
    fn drop(
       & mut self,
    ) {
-      let mut editor = crate::sys::memory::MemoryEditor::open_read_write(
-         self.address_range.clone(),
-      ).expect("Failed to restore patched bytes");
+      // The process may have exited, in
+      // which case there's nothing to restore
+      let mut bytes = vec![0; self.old_bytes.len()];
+      if self.process.read(self.address_range.start, & mut bytes).is_err() == true {
+         return;
+      }
 
-      unsafe{editor.as_bytes_mut().copy_from_slice(&self.old_bytes)};
+      for range in self.restore_ranges.iter() {
+         bytes[range.clone()].copy_from_slice(&self.old_bytes[range.clone()]);
+      }
 
+      let _ = self.process.write(self.address_range.start, &bytes);
       return;
    }
 }
 
+////////////////////////////////
+// METHODS - SuspendedProcess //
+////////////////////////////////
+
+impl SuspendedProcess {
+   /// Gets the process ID of the process.
+   pub fn process_id(
+      & self,
+   ) -> u32 {
+      return self.process.process_id();
+   }
+
+   /// Resumes the process' main thread so
+   /// the game starts running.  If this
+   /// fails, the process is terminated.
+   pub fn resume(
+      self,
+   ) -> Result<()> {
+      return Ok(self.process.resume()?);
+   }
+}
+
+/////////////////////////////////////////
+// INTERNAL METHODS - SuspendedProcess //
+/////////////////////////////////////////
+
+impl SuspendedProcess {
+   pub(crate) fn load_library(
+      & self,
+      library_path : & std::path::Path,
+   ) -> std::result::Result<(), crate::sys::process::ProcessError> {
+      return self.process.load_library(library_path);
+   }
+
+   pub(crate) fn wait_ready(
+      & self,
+      timeout : Option<std::time::Duration>,
+   ) -> std::result::Result<bool, crate::sys::process::ProcessError> {
+      return self.process.wait_ready(timeout);
+   }
+}
+
 ///////////////////////////////////
 // METHODS - ProcessSnapshotList //
 ///////////////////////////////////
@@ -453,31 +1273,79 @@ impl ProcessSnapshotList {
    /// Tries to remove a process from
    /// the list by its executable file
    /// name.
-   pub fn remove_by_executable_file_name(
+   pub fn remove(
       & mut self,
       executable_file_name : & str,
    ) -> Option<ProcessSnapshot> {
       return self.processes.remove(executable_file_name);
    }
 
-   /// Tries to find a process by
-   /// its executable file name.
-   pub fn find_by_executable_file_name(
+   /// Gets a process by its exact
+   /// executable file name.
+   pub fn get(
       & self,
       executable_file_name : & str,
    ) -> Option<& ProcessSnapshot> {
       return self.processes.get(executable_file_name);
-   } 
+   }
 
-   /// Tries to find a mutable process
-   /// by its executable file name.
-   pub fn find_mut_by_executable_file_name(
+   /// Gets a mutable process by its
+   /// exact executable file name.
+   pub fn get_mut(
       & mut self,
       executable_file_name : & str,
    ) -> Option<& mut ProcessSnapshot> {
       return self.processes.get_mut(executable_file_name);
    }
 
+   /// Checks whether the list contains a
+   /// process with an exact executable
+   /// file name.
+   pub fn contains(
+      & self,
+      executable_file_name : & str,
+   ) -> bool {
+      return self.processes.contains_key(executable_file_name);
+   }
+
+   /// Old name for <code>remove</code>.
+   #[deprecated(note = "use `remove` instead")]
+   pub fn remove_by_executable_file_name(
+      & mut self,
+      executable_file_name : & str,
+   ) -> Option<ProcessSnapshot> {
+      return self.remove(executable_file_name);
+   }
+
+   /// Old name for <code>get</code>.
+   #[deprecated(note = "use `get` instead")]
+   pub fn find_by_executable_file_name(
+      & self,
+      executable_file_name : & str,
+   ) -> Option<& ProcessSnapshot> {
+      return self.get(executable_file_name);
+   }
+
+   /// Old name for <code>get_mut</code>.
+   #[deprecated(note = "use `get_mut` instead")]
+   pub fn find_mut_by_executable_file_name(
+      & mut self,
+      executable_file_name : & str,
+   ) -> Option<& mut ProcessSnapshot> {
+      return self.get_mut(executable_file_name);
+   }
+
+   /// Tries to find a process by
+   /// its process ID.
+   pub fn find_by_process_id(
+      & self,
+      process_id : u32,
+   ) -> Option<& ProcessSnapshot> {
+      return self.processes.values().find(|process| {
+         process.process_id() == process_id
+      });
+   }
+
    /// Creates an iterator over the
    /// processes in the list.
    pub fn iter<'l>(
@@ -530,6 +1398,7 @@ impl ModuleSnapshotList {
       for module in list {
          let module = ModuleSnapshot{
             snapshot : module,
+            sections : std::sync::OnceLock::new(),
          };
 
          hash.insert(
@@ -557,33 +1426,173 @@ impl ModuleSnapshotList {
       return self;
    }
 
-   /// Tries to removes a module from the
+   /// Tries to remove a module from the
    /// list by its executable file name.
-   pub fn remove_by_executable_file_name(
+   pub fn remove(
       & mut self,
       executable_file_name : & str,
    ) -> Option<ModuleSnapshot> {
       return self.modules.remove(executable_file_name);
    }
 
-   /// Tries to find a module snapshot
-   /// by its executable file name.
-   pub fn find_by_executable_file_name(
+   /// Gets a module snapshot by its
+   /// exact executable file name.
+   pub fn get(
       & self,
       executable_file_name : & str,
    ) -> Option<& ModuleSnapshot> {
       return self.modules.get(executable_file_name);
    }
 
-   /// Tries to find a mutable module
-   /// by its executable file name.
-   pub fn find_mut_by_executable_file_name(
+   /// Gets a mutable module by its
+   /// exact executable file name.
+   pub fn get_mut(
       & mut self,
       executable_file_name : & str,
    ) -> Option<& mut ModuleSnapshot> {
       return self.modules.get_mut(executable_file_name);
    }
 
+   /// Checks whether the list contains a
+   /// module with an exact executable
+   /// file name.
+   pub fn contains(
+      & self,
+      executable_file_name : & str,
+   ) -> bool {
+      return self.modules.contains_key(executable_file_name);
+   }
+
+   /// Old name for <code>remove</code>.
+   #[deprecated(note = "use `remove` instead")]
+   pub fn remove_by_executable_file_name(
+      & mut self,
+      executable_file_name : & str,
+   ) -> Option<ModuleSnapshot> {
+      return self.remove(executable_file_name);
+   }
+
+   /// Old name for <code>get</code>.
+   #[deprecated(note = "use `get` instead")]
+   pub fn find_by_executable_file_name(
+      & self,
+      executable_file_name : & str,
+   ) -> Option<& ModuleSnapshot> {
+      return self.get(executable_file_name);
+   }
+
+   /// Old name for <code>get_mut</code>.
+   #[deprecated(note = "use `get_mut` instead")]
+   pub fn find_mut_by_executable_file_name(
+      & mut self,
+      executable_file_name : & str,
+   ) -> Option<& mut ModuleSnapshot> {
+      return self.get_mut(executable_file_name);
+   }
+
+   /// Tries to find a module snapshot by
+   /// its executable file name, ignoring case
+   /// like file names on Windows.
+   pub fn find_by_executable_file_name_ignore_case(
+      & self,
+      executable_file_name : & str,
+   ) -> Option<& ModuleSnapshot> {
+      let key = self.find_key(|name| match name.eq_ignore_ascii_case(executable_file_name) {
+         true  => Some(0),
+         false => None,
+      })?;
+      return self.modules.get(key);
+   }
+
+   /// Tries to find a mutable module by
+   /// its executable file name, ignoring case
+   /// like file names on Windows.
+   pub fn find_mut_by_executable_file_name_ignore_case(
+      & mut self,
+      executable_file_name : & str,
+   ) -> Option<& mut ModuleSnapshot> {
+      let key = self.find_key(|name| match name.eq_ignore_ascii_case(executable_file_name) {
+         true  => Some(0),
+         false => None,
+      })?.clone();
+      return self.modules.get_mut(&key);
+   }
+
+   /// Tries to find a module snapshot whose
+   /// executable file name starts with a
+   /// prefix, ignoring case.  If several
+   /// modules match, the one with the
+   /// shortest name is returned.
+   pub fn find_by_prefix(
+      & self,
+      prefix : & str,
+   ) -> Option<& ModuleSnapshot> {
+      let key = self.find_key(|name| prefix_score(name, prefix))?;
+      return self.modules.get(key);
+   }
+
+   /// Tries to find a mutable module whose
+   /// executable file name starts with a
+   /// prefix, ignoring case.  If several
+   /// modules match, the one with the
+   /// shortest name is returned.
+   pub fn find_mut_by_prefix(
+      & mut self,
+      prefix : & str,
+   ) -> Option<& mut ModuleSnapshot> {
+      let key = self.find_key(|name| prefix_score(name, prefix))?.clone();
+      return self.modules.get_mut(&key);
+   }
+
+   /// Tries to find the module snapshot
+   /// whose executable file name best matches
+   /// a partial name, ignoring case.  An exact
+   /// name is preferred, then a name starting
+   /// with the query, then a name containing it,
+   /// and finally a name containing its characters
+   /// in order, so <code>"engine"</code> finds
+   /// <code>"Engine.dll"</code> and <code>"kbase"</code>
+   /// finds <code>"KernelBase.dll"</code>.
+   pub fn find_by_name_fuzzy(
+      & self,
+      query : & str,
+   ) -> Option<& ModuleSnapshot> {
+      let key = self.find_key(|name| fuzzy_score(name, query))?;
+      return self.modules.get(key);
+   }
+
+   /// Tries to find the mutable module whose
+   /// executable file name best matches a
+   /// partial name, ignoring case.  See
+   /// <code>find_by_name_fuzzy</code> for
+   /// how matches are ranked.
+   pub fn find_mut_by_name_fuzzy(
+      & mut self,
+      query : & str,
+   ) -> Option<& mut ModuleSnapshot> {
+      let key = self.find_key(|name| fuzzy_score(name, query))?.clone();
+      return self.modules.get_mut(&key);
+   }
+
+   /// Tries to find the module for the
+   /// process' own executable.
+   pub fn main_module(
+      & self,
+   ) -> Option<& ModuleSnapshot> {
+      return self.find_by_executable_file_name_ignore_case(
+         self.parent.executable_file_name(),
+      );
+   }
+
+   /// Tries to find the mutable module
+   /// for the process' own executable.
+   pub fn main_module_mut(
+      & mut self,
+   ) -> Option<& mut ModuleSnapshot> {
+      let executable_file_name = String::from(self.parent.executable_file_name());
+      return self.find_mut_by_executable_file_name_ignore_case(&executable_file_name);
+   }
+
    /// Returns a reference to the process
    /// snapshot which the module snapshot
    /// list belongs to.
@@ -614,6 +1623,30 @@ impl ModuleSnapshotList {
    }
 }
 
+///////////////////////////////////////////
+// INTERNAL HELPERS - ModuleSnapshotList //
+///////////////////////////////////////////
+
+impl ModuleSnapshotList {
+   /// Finds the key of the module with
+   /// the lowest score, skipping modules
+   /// scored as <code>None</code>.  Ties
+   /// go to the shorter name, then to the
+   /// name which sorts first, so the result
+   /// doesn't depend on hash order.
+   fn find_key<F>(
+      & self,
+      score : F,
+   ) -> Option<& String>
+   where F: Fn(& str) -> Option<u32>,
+   {
+      return self.modules.keys()
+         .filter_map(|name| score(name).map(|score| (score, name.len(), name)))
+         .min()
+         .map(|(_, _, name)| name);
+   }
+}
+
 /////////////////////////////////////////////////////////
 // TRAIT IMPLEMENTATIONS - ProcessSnapshotListIterator //
 /////////////////////////////////////////////////////////
@@ -672,3 +1705,235 @@ impl std::iter::IntoIterator for ModuleSnapshotListIntoIterator {
    }
 }
 
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Checks whether an executable file
+/// name matches a pattern, ignoring case.
+/// A <code>*</code> in the pattern matches
+/// any number of characters and a
+/// <code>?</code> matches any single
+/// character, so <code>"FSD-Win64-*.exe"</code>
+/// matches every build of a game regardless
+/// of which store it was bought from.  A
+/// backslash makes the character after it
+/// match literally, so <code>"\\*"</code>
+/// only matches a star.  A pattern without
+/// any of these is compared like a plain
+/// string.
+pub fn executable_file_name_matches(
+   file_name   : & str,
+   pattern     : & str,
+) -> bool {
+   let file_name  = file_name.chars().flat_map(char::to_lowercase).collect::<Vec<char>>();
+
+   // Each pattern character along with
+   // whether it may be a wildcard
+   let mut pattern_chars = pattern.chars();
+   let pattern = std::iter::from_fn(|| {
+      return match pattern_chars.next()? {
+         '\\'  => Some((pattern_chars.next().unwrap_or('\\'), false)),
+         c     => Some((c, true)),
+      };
+   }).flat_map(|(c, wildcard)| c.to_lowercase().map(move |c| (c, wildcard))).collect::<Vec<(char, bool)>>();
+
+   // Greedy matching which backtracks to
+   // the most recent star on a mismatch
+   let mut name_index      = 0;
+   let mut pattern_index   = 0;
+   let mut backtrack       = None;
+   while name_index < file_name.len() {
+      match pattern.get(pattern_index) {
+         Some(('*', true))                               => {
+            backtrack = Some((pattern_index, name_index));
+            pattern_index += 1;
+            continue;
+         },
+         Some((c, wildcard)) if (*c == '?' && *wildcard == true) || *c == file_name[name_index] => {
+            pattern_index  += 1;
+            name_index     += 1;
+            continue;
+         },
+         _                                               => (),
+      }
+
+      match backtrack {
+         Some((star_index, star_name_index)) => {
+            backtrack      = Some((star_index, star_name_index + 1));
+            pattern_index  = star_index + 1;
+            name_index     = star_name_index + 1;
+         },
+         None                                => return false,
+      }
+   }
+
+   // Any leftover stars match nothing
+   return pattern[pattern_index..].iter().all(|c| *c == ('*', true));
+}
+
+/// Starts an executable with its main
+/// thread suspended, so a mod's library
+/// can be loaded before the game runs any
+/// of its own code.  The working directory
+/// is the executable's own directory.  This
+/// is only supported on Windows.
+///
+/// ```
+/// let game = nusion_core::process::spawn_suspended(
+///    "FSD-Win64-Shipping.exe",
+///    ["-nosplash"],
+/// )?;
+///
+/// nusion_core::inject::inject_and_resume(game, "nusion_drg.dll", None)?;
+/// ```
+pub fn spawn_suspended<P, I, S>(
+   executable_path   : P,
+   arguments         : I,
+) -> Result<SuspendedProcess>
+where P: AsRef<std::path::Path>,
+      I: IntoIterator<Item = S>,
+      S: AsRef<std::ffi::OsStr>,
+{
+   let arguments = arguments.into_iter()
+      .map(|argument| argument.as_ref().to_os_string())
+      .collect::<Vec<std::ffi::OsString>>();
+
+   return Ok(SuspendedProcess{
+      process : crate::sys::process::SuspendedProcess::spawn(
+         executable_path.as_ref(),
+         &arguments,
+      )?,
+   });
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Scores a name for a prefix lookup.
+fn prefix_score(
+   name     : & str,
+   prefix   : & str,
+) -> Option<u32> {
+   let name    = name.to_lowercase();
+   let prefix  = prefix.to_lowercase();
+
+   return match name.starts_with(&prefix) {
+      true  => Some(0),
+      false => None,
+   };
+}
+
+/// Scores a name for a fuzzy lookup,
+/// where lower scores are better matches.
+fn fuzzy_score(
+   name  : & str,
+   query : & str,
+) -> Option<u32> {
+   let name    = name.to_lowercase();
+   let query   = query.to_lowercase();
+
+   if name == query {
+      return Some(0);
+   }
+   if name.starts_with(&query) == true {
+      return Some(1);
+   }
+   if name.contains(&query) == true {
+      return Some(2);
+   }
+
+   // Every character of the query
+   // appears in order
+   let mut name_chars = name.chars();
+   if query.chars().all(|c| name_chars.any(|n| n == c)) == true {
+      return Some(3);
+   }
+
+   return None;
+}
+
+/// Verifies every page in the address
+/// range is mapped and committed, so
+/// accessing it won't fault.  The error
+/// contains the offset from the base
+/// address of the first unmapped byte.
+fn verify_mapped(
+   address_range  : & std::ops::Range<usize>,
+   base           : usize,
+) -> crate::patch::Result<()> {
+   let mut address = address_range.start;
+
+   while address < address_range.end {
+      let region = match crate::sys::memory::MemoryRegion::query(address) {
+         Ok(region)  => region,
+         Err(_)      => return Err(crate::patch::PatchError::UnmappedWithin{
+            offset : address - base,
+         }),
+      };
+
+      // Zero-length regions would loop forever
+      if region.is_committed() == false || region.address_range().end <= address {
+         return Err(crate::patch::PatchError::UnmappedWithin{
+            offset : address - base,
+         });
+      }
+
+      address = region.address_range().end;
+   }
+
+   return Ok(());
+}
+
+///////////
+// TESTS //
+///////////
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn glob_plain() {
+      assert!(executable_file_name_matches("FSD-Win64-Shipping.exe", "fsd-win64-shipping.EXE") == true);
+      assert!(executable_file_name_matches("FSD-Win64-Shipping.exe", "FSD-Win64-Shipping") == false);
+      assert!(executable_file_name_matches("FSD.exe", "FSD.exe.bak") == false);
+   }
+
+   #[test]
+   fn glob_star() {
+      assert!(executable_file_name_matches("FSD-Win64-Shipping.exe", "FSD-Win64-*.exe") == true);
+      assert!(executable_file_name_matches("FSD-Win64-.exe", "FSD-Win64-*.exe") == true);
+      assert!(executable_file_name_matches("game.exe", "*") == true);
+      assert!(executable_file_name_matches("game.exe", "**.exe") == true);
+      assert!(executable_file_name_matches("game.exe.exe", "*.exe") == true);
+      assert!(executable_file_name_matches("game.exe", "*.dll") == false);
+      assert!(executable_file_name_matches("abcabd", "*ab?") == true);
+   }
+
+   #[test]
+   fn glob_question_mark() {
+      assert!(executable_file_name_matches("game1.exe", "game?.exe") == true);
+      assert!(executable_file_name_matches("game.exe", "game?.exe") == false);
+      assert!(executable_file_name_matches("game12.exe", "game?.exe") == false);
+   }
+
+   #[test]
+   fn glob_escaping() {
+      assert!(executable_file_name_matches("*.exe", "\\*.exe") == true);
+      assert!(executable_file_name_matches("game.exe", "\\*.exe") == false);
+      assert!(executable_file_name_matches("?", "\\?") == true);
+      assert!(executable_file_name_matches("a", "\\?") == false);
+      assert!(executable_file_name_matches("a\\b", "a\\\\b") == true);
+      assert!(executable_file_name_matches("a\\", "a\\") == true);
+   }
+
+   #[test]
+   fn glob_empty() {
+      assert!(executable_file_name_matches("", "") == true);
+      assert!(executable_file_name_matches("", "*") == true);
+      assert!(executable_file_name_matches("", "?") == false);
+      assert!(executable_file_name_matches("game.exe", "") == false);
+   }
+}
@@ -0,0 +1,351 @@
+//! Pause the whole game while memory
+//! is inspected.
+//!
+//! ```
+//! let pause = unsafe{nusion_core::runtime::pause_game()}?;
+//!
+//! // Every game thread is suspended, so
+//! // these values are read at the same
+//! // point in time
+//! let health  = unsafe{*(0x7FFF1340 as * const i32)};
+//! let ammo    = unsafe{*(0x7FFF1344 as * const i32)};
+//!
+//! nusion_core::runtime::resume_game(pause);
+//! ```
+//!
+//! Every thread in the process is suspended
+//! other than the pausing thread and the
+//! threads nusion runs itself, such as the
+//! thread <code>main</code> runs on and every
+//! task.  Pausing is only supported on Windows.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Time before a paused game is
+/// resumed unless changed by the user.
+pub const DEFAULT_PAUSE_TIMEOUT : std::time::Duration
+   = std::time::Duration::from_secs(5);
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to pausing
+/// the game.
+#[derive(Debug)]
+pub enum RuntimeError {
+   ThreadError{
+      sys_error   : crate::sys::thread::ThreadError,
+   },
+   IoError{
+      err : std::io::Error,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>RuntimeError</code>.
+pub type Result<T> = std::result::Result<T, RuntimeError>;
+
+/// A handle to the paused game.  The
+/// game is resumed when the handle is
+/// dropped, or once the pause's timeout
+/// runs out, whichever happens first.
+pub struct GamePause {
+   shared   : Arc<GamePauseShared>,
+   watchdog : Option<std::thread::JoinHandle<()>>,
+}
+
+/// Marks the calling thread as one of
+/// nusion's own until this is dropped, so
+/// pausing the game doesn't suspend it.
+/// Threads spawned through the environment
+/// are already registered, so this is only
+/// needed for threads spawned with
+/// <code>std::thread</code>.
+pub struct ThreadRegistration {
+   thread_id   : u32,
+   marker      : std::marker::PhantomData<* const ()>,
+}
+
+/// State shared between a pause
+/// and its watchdog thread.
+struct GamePauseShared {
+   state    : Mutex<GamePauseState>,
+   wake     : Condvar,
+}
+
+/// Mutable state of a pause.
+struct GamePauseState {
+   threads  : Option<crate::sys::thread::SuspendedThreads>,
+   resumed  : bool,
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RuntimeError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for RuntimeError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ThreadError{sys_error}
+            => write!(stream, "Thread error: {sys_error}"),
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for RuntimeError {
+}
+
+impl From<crate::sys::thread::ThreadError> for RuntimeError {
+   fn from(
+      value : crate::sys::thread::ThreadError,
+   ) -> Self {
+      return Self::ThreadError{
+         sys_error : value,
+      };
+   }
+}
+
+impl From<std::io::Error> for RuntimeError {
+   fn from(
+      value : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : value,
+      };
+   }
+}
+
+/////////////////////////
+// METHODS - GamePause //
+/////////////////////////
+
+impl GamePause {
+   /// Checks whether the game is still
+   /// paused, which is false once the
+   /// timeout has run out.
+   pub fn is_paused(
+      & self,
+   ) -> bool {
+      return self.shared.lock().threads.is_some();
+   }
+
+   /// Resumes the game.  This is the
+   /// same as dropping the handle.
+   pub fn resume(
+      self,
+   ) {
+      return;
+   }
+}
+
+////////////////////////////////////////
+// INTERNAL METHODS - GamePauseShared //
+////////////////////////////////////////
+
+impl GamePauseShared {
+   // Resuming must never fail, so
+   // poisoning is ignored
+   fn lock<'l>(
+      &'l self,
+   ) -> std::sync::MutexGuard<'l, GamePauseState> {
+      return self.state.lock().unwrap_or_else(|poison| poison.into_inner());
+   }
+
+   fn resume(
+      & self,
+   ) {
+      // Dropping the suspended threads
+      // resumes every one of them
+      let mut state = self.lock();
+      state.resumed = true;
+      std::mem::drop(state.threads.take());
+      std::mem::drop(state);
+
+      self.wake.notify_all();
+      return;
+   }
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - GamePause //
+///////////////////////////////////////
+
+impl std::ops::Drop for GamePause {
+   fn drop(
+      & mut self,
+   ) {
+      self.shared.resume();
+      if let Some(watchdog) = self.watchdog.take() {
+         let _ = watchdog.join();
+      }
+
+      return;
+   }
+}
+
+////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ThreadRegistration //
+////////////////////////////////////////////////
+
+impl std::ops::Drop for ThreadRegistration {
+   fn drop(
+      & mut self,
+   ) {
+      let mut threads = lock_own_threads();
+      if let Some(index) = threads.iter().position(|thread_id| *thread_id == self.thread_id) {
+         threads.swap_remove(index);
+      }
+
+      return;
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Pauses the game, resuming it after
+/// <code>DEFAULT_PAUSE_TIMEOUT</code>
+/// if it wasn't resumed before then.
+///
+/// <h2 id=  runtime_pause_game_safety>
+/// <a href=#runtime_pause_game_safety>
+/// Safety
+/// </a></h2>
+///
+/// See the safety section for <code><a href=
+/// #runtime_pause_game_for_safety>pause_game_for</a></code>.
+pub unsafe fn pause_game(
+) -> Result<GamePause> {
+   return pause_game_for(DEFAULT_PAUSE_TIMEOUT);
+}
+
+/// Pauses the game, resuming it after
+/// a timeout if it wasn't resumed before
+/// then.
+///
+/// <h2 id=  runtime_pause_game_for_safety>
+/// <a href=#runtime_pause_game_for_safety>
+/// Safety
+/// </a></h2>
+///
+/// A suspended game thread may be holding
+/// a lock, such as the heap lock, which
+/// blocks any thread still running until
+/// the game is resumed.  The timeout is
+/// what ends such a deadlock, so it should
+/// be kept short.  Code already waiting on
+/// the game, such as a hook's closure, will
+/// also stall until the game is resumed.
+pub unsafe fn pause_game_for(
+   timeout : std::time::Duration,
+) -> Result<GamePause> {
+   let shared = Arc::new(GamePauseShared{
+      state    : Mutex::new(GamePauseState{
+         threads  : None,
+         resumed  : false,
+      }),
+      wake     : Condvar::new(),
+   });
+
+   // The watchdog is started first so its
+   // thread ID is known when suspending
+   let (sender, receiver) = std::sync::mpsc::channel();
+   let watchdog_shared = shared.clone();
+   let watchdog = std::thread::Builder::new()
+      .name(String::from("nusion-pause-watchdog"))
+      .spawn(move || {
+         let _ = sender.send(crate::sys::thread::current_thread_id());
+
+         let state = watchdog_shared.lock();
+         let (state, _) = watchdog_shared.wake.wait_timeout_while(
+            state,
+            timeout,
+            |state| state.resumed == false,
+         ).unwrap_or_else(|poison| poison.into_inner());
+         std::mem::drop(state);
+
+         watchdog_shared.resume();
+      })?;
+
+   let mut excluded_thread_ids = own_thread_ids();
+   excluded_thread_ids.extend(receiver.recv().ok());
+
+   // The lock is held while suspending so
+   // the watchdog can't resume the game
+   // before the threads are stored.  If
+   // the timeout already ran out, the game
+   // isn't paused at all.
+   let mut state = shared.lock();
+   if state.resumed == false {
+      match crate::sys::thread::SuspendedThreads::suspend_others_except(&excluded_thread_ids) {
+         Ok(threads) => state.threads = Some(threads),
+         Err(err)    => {
+            state.resumed = true;
+            std::mem::drop(state);
+            shared.wake.notify_all();
+            let _ = watchdog.join();
+            return Err(err.into());
+         },
+      }
+   }
+   std::mem::drop(state);
+
+   return Ok(GamePause{
+      shared   : shared,
+      watchdog : Some(watchdog),
+   });
+}
+
+/// Resumes a paused game.  This is the
+/// same as dropping the pause.
+pub fn resume_game(
+   pause : GamePause,
+) {
+   pause.resume();
+   return;
+}
+
+/// Registers the calling thread as one
+/// of nusion's own threads, which aren't
+/// suspended when the game is paused.
+pub fn register_current_thread(
+) -> ThreadRegistration {
+   let thread_id = crate::sys::thread::current_thread_id();
+   lock_own_threads().push(thread_id);
+
+   return ThreadRegistration{
+      thread_id   : thread_id,
+      marker      : std::marker::PhantomData,
+   };
+}
+
+/// Gets the IDs of every thread
+/// registered as one of nusion's own.
+pub fn own_thread_ids(
+) -> Vec<u32> {
+   return lock_own_threads().clone();
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn lock_own_threads<'l>(
+) -> std::sync::MutexGuard<'l, Vec<u32>> {
+   return OWN_THREADS.lock().unwrap_or_else(|poison| poison.into_inner());
+}
+
+//////////////////
+// GLOBAL STATE //
+//////////////////
+
+/// IDs of the threads which aren't
+/// suspended when the game is paused.
+static OWN_THREADS : Mutex<Vec<u32>> = Mutex::new(Vec::new());
@@ -0,0 +1,413 @@
+//! Validate patch definitions which come
+//! from outside the mod, such as community
+//! patch packs loaded alongside an offset
+//! database, before any of them are applied.
+//!
+//! A sandbox is created for a single module
+//! with the capabilities the pack is allowed
+//! to use.  Every range is checked to lie
+//! within the module's sections, to stay out
+//! of the module's import tables and any
+//! patch nusion has already applied, and to
+//! only touch code if code patches are allowed.
+//!
+//! ```
+//! let pack = nusion_core::offsets::OffsetsDb::load("community.offsets")?;
+//!
+//! let mut env = nusion_core::env_mut!();
+//! let game    = env.modules_mut().get_mut("game.exe").unwrap();
+//!
+//! let sandbox = unsafe{nusion_core::sandbox::PatchSandbox::new(
+//!    game,
+//!    nusion_core::sandbox::SandboxCapabilities{
+//!       code : false,
+//!       data : true,
+//!    },
+//! )}?;
+//!
+//! // Reject the whole pack before applying anything
+//! sandbox.check_offsets(pack.module("game.exe")?)?;
+//!
+//! let _patch = unsafe{sandbox.apply(game, &writer)}?;
+//! ```
+
+use std::ops::RangeBounds;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to validating
+/// a patch definition.
+#[derive(Debug)]
+pub enum SandboxError {
+   PatchError{
+      err            : crate::patch::PatchError,
+   },
+   ProcessError{
+      err            : crate::sys::process::ProcessError,
+   },
+   EmptyRange{
+      offset         : usize,
+   },
+   OwnModule{
+      module_name    : String,
+   },
+   ModuleMismatch{
+      expected       : String,
+      provided       : String,
+   },
+   ImportTable{
+      offset_range   : std::ops::Range<usize>,
+   },
+   ActivePatch{
+      offset_range   : std::ops::Range<usize>,
+   },
+   CapabilityDenied{
+      kind           : PatchKind,
+      offset_range   : std::ops::Range<usize>,
+   },
+   EntryRejected{
+      name           : String,
+      err            : Box<SandboxError>,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>SandboxError</code>.
+pub type Result<T> = std::result::Result<T, SandboxError>;
+
+/// Whether a patch touches the module's
+/// code or only its data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PatchKind {
+   Code,
+   Data,
+}
+
+/// The kinds of patches a sandbox allows.
+/// Nothing is allowed by default, so every
+/// capability has to be granted explicitly.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SandboxCapabilities {
+   /// Whether patches may touch memory
+   /// which contains code.
+   pub code : bool,
+   /// Whether patches may touch memory
+   /// which only contains data.
+   pub data : bool,
+}
+
+/// Checks patch definitions against a single
+/// module before they are applied.  The
+/// module's sections and import tables are
+/// read once when the sandbox is created.
+pub struct PatchSandbox {
+   module_name    : String,
+   address_range  : std::ops::Range<usize>,
+   capabilities   : SandboxCapabilities,
+   section_ranges : Option<Vec<std::ops::Range<usize>>>,
+   import_ranges  : Vec<std::ops::Range<usize>>,
+   code_ranges    : Option<Vec<std::ops::Range<usize>>>,
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - SandboxError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for SandboxError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::PatchError{err}
+            => write!(stream, "Patch error: {err}"),
+         Self::ProcessError{err}
+            => write!(stream, "Process error: {err}"),
+         Self::EmptyRange{offset}
+            => write!(stream, "Offset range at {offset:#X} is empty"),
+         Self::OwnModule{module_name}
+            => write!(stream, "\"{module_name}\" contains nusion and can't be patched"),
+         Self::ModuleMismatch{expected, provided}
+            => write!(stream, "Sandbox is for \"{expected}\", not \"{provided}\""),
+         Self::ImportTable{offset_range}
+            => write!(stream, "Offsets {:#X}..{:#X} overlap an import table", offset_range.start, offset_range.end),
+         Self::ActivePatch{offset_range}
+            => write!(stream, "Offsets {:#X}..{:#X} overlap a patch which is already applied", offset_range.start, offset_range.end),
+         Self::CapabilityDenied{kind, offset_range}
+            => write!(stream, "{kind} patch at offsets {:#X}..{:#X} isn't allowed", offset_range.start, offset_range.end),
+         Self::EntryRejected{name, err}
+            => write!(stream, "Entry \"{name}\" rejected: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for SandboxError {
+}
+
+impl From<crate::patch::PatchError> for SandboxError {
+   fn from(
+      value : crate::patch::PatchError,
+   ) -> Self {
+      return Self::PatchError{
+         err : value,
+      };
+   }
+}
+
+impl From<crate::sys::process::ProcessError> for SandboxError {
+   fn from(
+      value : crate::sys::process::ProcessError,
+   ) -> Self {
+      return Self::ProcessError{
+         err : value,
+      };
+   }
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatchKind //
+///////////////////////////////////////
+
+impl std::fmt::Display for PatchKind {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::Code  => "Code",
+         Self::Data  => "Data",
+      });
+   }
+}
+
+////////////////////////////
+// METHODS - PatchSandbox //
+////////////////////////////
+
+impl PatchSandbox {
+   /// Creates a sandbox for a module with
+   /// the capabilities patches are allowed
+   /// to use.  The module containing nusion
+   /// itself is always rejected.
+   ///
+   /// <h2 id=  patch_sandbox_new_safety>
+   /// <a href=#patch_sandbox_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// The module must be loaded within
+   /// the local process, as its image
+   /// is read directly from memory.
+   pub unsafe fn new(
+      module         : & crate::process::ModuleSnapshot,
+      capabilities   : SandboxCapabilities,
+   ) -> Result<Self> {
+      if module.address_range().contains(&own_address()) == true {
+         return Err(SandboxError::OwnModule{
+            module_name : String::from(module.executable_file_name()),
+         });
+      }
+
+      return Ok(Self{
+         module_name    : String::from(module.executable_file_name()),
+         address_range  : module.address_range().clone(),
+         capabilities   : capabilities,
+         section_ranges : module.section_ranges()?,
+         import_ranges  : module.import_table_ranges()?,
+         code_ranges    : module.executable_section_ranges()?,
+      });
+   }
+
+   /// Gets the capabilities patches
+   /// are allowed to use.
+   pub fn capabilities(
+      & self,
+   ) -> SandboxCapabilities {
+      return self.capabilities;
+   }
+
+   /// Checks an offset range within the
+   /// module, returning whether it touches
+   /// code or only data.  This fails if the
+   /// range is empty, leaves the module's
+   /// sections, overlaps an import table or
+   /// a patch which is already applied, or
+   /// needs a capability the sandbox doesn't
+   /// allow.
+   pub fn check<R>(
+      & self,
+      offset_range : & R,
+   ) -> Result<PatchKind>
+   where R: RangeBounds<usize>,
+   {
+      let offset_range = crate::patch::resolve_offset_range(
+         offset_range,
+         self.address_range.len(),
+      )?;
+      if offset_range.is_empty() == true {
+         return Err(SandboxError::EmptyRange{
+            offset : offset_range.start,
+         });
+      }
+
+      let base          = self.address_range.start;
+      let address_range = base + offset_range.start..base + offset_range.end;
+
+      self.verify_in_sections(&offset_range)?;
+
+      if self.import_ranges.iter().any(|range| overlaps(range, &offset_range)) == true {
+         return Err(SandboxError::ImportTable{
+            offset_range : offset_range,
+         });
+      }
+
+      if crate::patch::active_address_ranges().iter().any(|range| overlaps(range, &address_range)) == true {
+         return Err(SandboxError::ActivePatch{
+            offset_range : offset_range,
+         });
+      }
+
+      let kind = self.kind(&offset_range, &address_range)?;
+      let allowed = match kind {
+         PatchKind::Code   => self.capabilities.code,
+         PatchKind::Data   => self.capabilities.data,
+      };
+      if allowed == false {
+         return Err(SandboxError::CapabilityDenied{
+            kind           : kind,
+            offset_range   : offset_range,
+         });
+      }
+
+      return Ok(kind);
+   }
+
+   /// Checks every range entry in an offset
+   /// database's section for the module, so
+   /// a whole pack can be rejected before
+   /// any of it is applied.  Plain offsets,
+   /// signatures, and exports don't declare
+   /// a range and are skipped.
+   pub fn check_offsets(
+      & self,
+      offsets : & crate::offsets::ModuleOffsets,
+   ) -> Result<()> {
+      for (name, entry) in offsets.iter() {
+         if let crate::offsets::OffsetEntry::Range{offset_range, ..} = entry {
+            self.check(offset_range).map_err(|err| SandboxError::EntryRejected{
+               name  : String::from(name),
+               err   : Box::new(err),
+            })?;
+         }
+      }
+
+      return Ok(());
+   }
+
+   /// Checks a writer's offset range and
+   /// only applies it if the check passes.
+   /// The module must be the one the sandbox
+   /// was created for.
+   ///
+   /// <h2 id=  patch_sandbox_apply_safety>
+   /// <a href=#patch_sandbox_apply_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>patch::Patch::patch_create</code>
+   /// apply.
+   pub unsafe fn apply<W, R>(
+      & self,
+      module   : & mut crate::process::ModuleSnapshot,
+      writer   : & W,
+   ) -> Result<crate::patch::PatchContainer>
+   where W: crate::patch::Writer<R>,
+         R: RangeBounds<usize>,
+   {
+      use crate::patch::Patch;
+
+      if *module.address_range() != self.address_range {
+         return Err(SandboxError::ModuleMismatch{
+            expected : self.module_name.clone(),
+            provided : String::from(module.executable_file_name()),
+         });
+      }
+
+      self.check(writer.memory_offset_range())?;
+      return Ok(module.patch_create(writer)?);
+   }
+}
+
+/////////////////////////////////////
+// INTERNAL METHODS - PatchSandbox //
+/////////////////////////////////////
+
+impl PatchSandbox {
+   // The same check as the module's own
+   // for patches, using the sections read
+   // when the sandbox was created
+   fn verify_in_sections(
+      & self,
+      offset_range : & std::ops::Range<usize>,
+   ) -> Result<()> {
+      let section_ranges = match &self.section_ranges {
+         Some(ranges)   => ranges,
+         None           => return Ok(()),
+      };
+
+      let mut offset = offset_range.start;
+      while offset < offset_range.end {
+         offset = section_ranges.iter()
+            .find(|section| section.contains(&offset) == true)
+            .ok_or(crate::patch::PatchError::OutsideSections{
+               offset : offset,
+            })?
+            .end;
+      }
+
+      return Ok(());
+   }
+
+   // Without a section table, such as on
+   // Linux, the pages' permissions decide
+   fn kind(
+      & self,
+      offset_range   : & std::ops::Range<usize>,
+      address_range  : & std::ops::Range<usize>,
+   ) -> Result<PatchKind> {
+      let code = match &self.code_ranges {
+         Some(code_ranges) => code_ranges.iter().any(|range| overlaps(range, offset_range)),
+         None              => crate::sys::memory::MemoryRegion::all_within(address_range.clone())
+            .map_err(crate::patch::PatchError::from)?
+            .iter()
+            .any(|region| region.is_executable()),
+      };
+
+      return Ok(match code {
+         true  => PatchKind::Code,
+         false => PatchKind::Data,
+      });
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn overlaps(
+   first    : & std::ops::Range<usize>,
+   second   : & std::ops::Range<usize>,
+) -> bool {
+   return first.start < second.end && second.start < first.end;
+}
+
+// Any function compiled into the mod
+// is within the mod's library
+fn own_address(
+) -> usize {
+   return own_address as fn() -> usize as usize;
+}
+
@@ -0,0 +1,908 @@
+//! Scan module memory for strings and
+//! the code which references them or
+//! any other address.
+
+use std::collections::hash_map::HashMap;
+
+/// Number of bytes searched by each
+/// tick of a <code>PatternScan</code>
+/// unless changed by the user.
+const DEFAULT_CHUNK_SIZE : usize
+   = 1024 * 1024;
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to a scan function.
+#[derive(Debug)]
+pub enum ScanError {
+   MemoryError{
+      sys_error   : crate::sys::memory::MemoryError,
+   },
+   PatchError{
+      err         : crate::patch::PatchError,
+   },
+   EmptyString,
+   EmptyPattern,
+   InvalidPattern{
+      position    : usize,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ScanError</code>.
+pub type Result<T> = std::result::Result<T, ScanError>;
+
+/// A byte pattern where any bit may
+/// be a wildcard, written as hexadecimal
+/// bytes separated by whitespace with
+/// <code>?</code> for a wildcard nibble,
+/// such as <code>"48 8B ?? ?? 89 46 ?0"</code>.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern {
+   bytes : Vec<(u8, u8)>,
+}
+
+/// The text encoding a string was
+/// found stored in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StringEncoding {
+   Utf8,
+   Utf16,
+}
+
+/// The kind of instruction which
+/// references an address.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReferenceKind {
+   LoadEffectiveAddress,
+   Move,
+   Call,
+   Jump,
+   ConditionalJump,
+}
+
+/// A location in a module's code
+/// which references an address
+/// within the same module.  All
+/// offsets are relative to the
+/// start of the module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reference {
+   pub kind                      : ReferenceKind,
+   pub instruction_offset_range  : std::ops::Range<usize>,
+   pub target_offset             : usize,
+}
+
+/// A null-terminated string found
+/// within a module along with an
+/// instruction which references it.
+/// All offsets are relative to the
+/// start of the module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StringReference {
+   pub encoding            : StringEncoding,
+   pub string_offset_range : std::ops::Range<usize>,
+   pub reference           : Reference,
+}
+
+/// Iterator over every match of a
+/// pattern within a module, yielding
+/// offsets relative to the start of
+/// the module.  Memory is searched
+/// lazily as the iterator advances.
+pub struct PatternMatches<'p> {
+   pattern  : &'p Pattern,
+   spans    : Vec<std::ops::Range<usize>>,
+   span     : usize,
+   position : usize,
+   base     : usize,
+}
+
+/// Progress of a <code>PatternScan</code>,
+/// passed to its progress callback after
+/// every tick.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ScanProgress {
+   pub scanned_bytes : usize,
+   pub total_bytes   : usize,
+   pub match_count   : usize,
+}
+
+/// A pattern scan which is run a bounded
+/// chunk at a time, so scanning a large
+/// module from a hook on the game's main
+/// or render thread doesn't stall a frame.
+/// Each call to <code>tick</code> searches
+/// at most one chunk of memory, then reports
+/// progress through the callback.  Once the
+/// whole module was searched, the completion
+/// callback receives every match.
+///
+/// ```
+/// let pattern = nusion_core::scan::Pattern::parse("48 8B 05 ?? ?? ?? ?? 48 85 C0")?;
+///
+/// let mut scan = unsafe{nusion_core::scan::PatternScan::new(crate::game!(), pattern)}?
+///    .chunk_size(256 * 1024)
+///    .on_progress(|progress| println!("{:.0}%", progress.fraction() * 100.0))
+///    .on_complete(|offsets| println!("Found {} matches", offsets.len()));
+///
+/// // Called once per frame from a game loop hook
+/// if unsafe{scan.tick()} == true {
+///    // Finished, the scan can be dropped
+/// }
+/// ```
+pub struct PatternScan {
+   pattern     : Pattern,
+   spans       : Vec<std::ops::Range<usize>>,
+   span        : usize,
+   position    : usize,
+   base        : usize,
+   chunk_size  : usize,
+   progress    : ScanProgress,
+   matches     : Vec<usize>,
+   on_progress : Option<Box<dyn FnMut(ScanProgress) + Send>>,
+   on_complete : Option<Box<dyn FnOnce(& [usize]) + Send>>,
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ScanError //
+///////////////////////////////////////
+
+impl std::fmt::Display for ScanError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::MemoryError{sys_error}
+            => write!(stream, "Memory error: {sys_error}"),
+         Self::PatchError{err}
+            => write!(stream, "Patch error: {err}"),
+         Self::EmptyString
+            => write!(stream, "Search string is empty"),
+         Self::EmptyPattern
+            => write!(stream, "Pattern is empty"),
+         Self::InvalidPattern{position}
+            => write!(stream, "Invalid pattern byte at position {position}"),
+      };
+   }
+}
+
+impl std::error::Error for ScanError {
+}
+
+impl From<crate::sys::memory::MemoryError> for ScanError {
+   fn from(
+      value : crate::sys::memory::MemoryError,
+   ) -> Self {
+      return Self::MemoryError{
+         sys_error : value,
+      };
+   }
+}
+
+impl From<crate::patch::PatchError> for ScanError {
+   fn from(
+      value : crate::patch::PatchError,
+   ) -> Self {
+      return Self::PatchError{
+         err : value,
+      };
+   }
+}
+
+///////////////////////
+// METHODS - Pattern //
+///////////////////////
+
+impl Pattern {
+   /// Parses a pattern from its text form.
+   /// The error contains the index of the
+   /// first invalid byte.
+   pub fn parse(
+      pattern : & str,
+   ) -> Result<Self> {
+      let mut bytes = Vec::new();
+
+      for (position, byte) in pattern.split_whitespace().enumerate() {
+         let nibbles = byte.chars().collect::<Vec<char>>();
+         if nibbles.len() != 2 {
+            return Err(ScanError::InvalidPattern{
+               position : position,
+            });
+         }
+
+         let mut value  = 0;
+         let mut mask   = 0;
+         for nibble in nibbles {
+            value <<= 4;
+            mask  <<= 4;
+
+            if nibble == '?' {
+               continue;
+            }
+
+            value |= nibble.to_digit(16).ok_or(ScanError::InvalidPattern{
+               position : position,
+            })? as u8;
+            mask  |= 0x0F;
+         }
+
+         bytes.push((value, mask));
+      }
+
+      if bytes.is_empty() == true {
+         return Err(ScanError::EmptyPattern);
+      }
+
+      return Ok(Self{
+         bytes : bytes,
+      });
+   }
+
+   /// Creates a pattern which exactly
+   /// matches a byte slice.
+   pub fn exact(
+      bytes : & [u8],
+   ) -> Result<Self> {
+      if bytes.is_empty() == true {
+         return Err(ScanError::EmptyPattern);
+      }
+
+      return Ok(Self{
+         bytes : bytes.iter().map(|byte| (*byte, 0xFF)).collect(),
+      });
+   }
+
+   /// Gets the length of the pattern
+   /// in bytes.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.bytes.len();
+   }
+
+   /// Checks whether the pattern is
+   /// empty, which is never the case
+   /// for a successfully parsed pattern.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.bytes.is_empty();
+   }
+
+   /// Checks whether a byte slice of
+   /// the same length matches the pattern.
+   pub fn matches(
+      & self,
+      bytes : & [u8],
+   ) -> bool {
+      if bytes.len() != self.bytes.len() {
+         return false;
+      }
+
+      return self.bytes.iter().zip(bytes).all(|((value, mask), byte)| {
+         byte & mask == *value
+      });
+   }
+}
+
+//////////////////////////////
+// METHODS - PatternMatches //
+//////////////////////////////
+
+impl<'p> PatternMatches<'p> {
+   /// Creates an iterator over every
+   /// match of a pattern in the module's
+   /// readable memory.
+   ///
+   /// <h2 id=  pattern_matches_new_safety>
+   /// <a href=#pattern_matches_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #scan_find_string_refs_safety>find_string_refs</a></code>
+   /// apply for as long as the iterator
+   /// is used.
+   pub unsafe fn new(
+      module   : & crate::process::ModuleSnapshot,
+      pattern  : &'p Pattern,
+   ) -> Result<Self> {
+      let spans = readable_spans(module, |_| true)?;
+      let start = spans.first().map(|span| span.start).unwrap_or(0);
+
+      return Ok(Self{
+         pattern  : pattern,
+         spans    : spans,
+         span     : 0,
+         position : start,
+         base     : module.address_range().start,
+      });
+   }
+
+   /// Creates an iterator over every match
+   /// of a pattern in the readable memory
+   /// within an address range of the module,
+   /// such as one of its sections.  Matches
+   /// must lie entirely within the range.
+   ///
+   /// <h2 id=  pattern_matches_within_safety>
+   /// <a href=#pattern_matches_within_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #pattern_matches_new_safety>new</a></code>
+   /// apply.
+   pub unsafe fn within(
+      module         : & crate::process::ModuleSnapshot,
+      pattern        : &'p Pattern,
+      address_range  : & std::ops::Range<usize>,
+   ) -> Result<Self> {
+      let spans = readable_spans(module, |_| true)?
+         .into_iter()
+         .map(|span| usize::max(span.start, address_range.start)..usize::min(span.end, address_range.end))
+         .filter(|span| span.start < span.end)
+         .collect::<Vec<_>>();
+      let start = spans.first().map(|span| span.start).unwrap_or(0);
+
+      return Ok(Self{
+         pattern  : pattern,
+         spans    : spans,
+         span     : 0,
+         position : start,
+         base     : module.address_range().start,
+      });
+   }
+}
+
+////////////////////////////
+// METHODS - ScanProgress //
+////////////////////////////
+
+impl ScanProgress {
+   /// Gets the fraction of the module
+   /// which was searched, from zero to one.
+   pub fn fraction(
+      & self,
+   ) -> f64 {
+      if self.total_bytes == 0 {
+         return 1.0;
+      }
+
+      return self.scanned_bytes as f64 / self.total_bytes as f64;
+   }
+
+   /// Checks whether the whole module
+   /// was searched.
+   pub fn is_complete(
+      & self,
+   ) -> bool {
+      return self.scanned_bytes == self.total_bytes;
+   }
+}
+
+///////////////////////////
+// METHODS - PatternScan //
+///////////////////////////
+
+impl PatternScan {
+   /// Prepares a scan of the module's
+   /// readable memory without searching
+   /// any of it yet.
+   ///
+   /// <h2 id=  pattern_scan_new_safety>
+   /// <a href=#pattern_scan_new_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #scan_find_string_refs_safety>find_string_refs</a></code>
+   /// apply for as long as the scan is
+   /// ticked.
+   pub unsafe fn new(
+      module   : & crate::process::ModuleSnapshot,
+      pattern  : Pattern,
+   ) -> Result<Self> {
+      let spans = readable_spans(module, |_| true)?;
+      let start = spans.first().map(|span| span.start).unwrap_or(0);
+      let total = spans.iter().map(|span| span.end - span.start).sum();
+
+      return Ok(Self{
+         pattern     : pattern,
+         spans       : spans,
+         span        : 0,
+         position    : start,
+         base        : module.address_range().start,
+         chunk_size  : DEFAULT_CHUNK_SIZE,
+         progress    : ScanProgress{
+            scanned_bytes  : 0,
+            total_bytes    : total,
+            match_count    : 0,
+         },
+         matches     : Vec::new(),
+         on_progress : None,
+         on_complete : None,
+      });
+   }
+
+   /// Sets the most bytes searched by
+   /// each tick.  Smaller chunks spread
+   /// the scan over more ticks.
+   pub fn chunk_size(
+      mut self,
+      chunk_size : usize,
+   ) -> Self {
+      self.chunk_size = usize::max(chunk_size, 1);
+      return self;
+   }
+
+   /// Sets the callback which receives
+   /// the progress after every tick.
+   pub fn on_progress<F>(
+      mut self,
+      callback : F,
+   ) -> Self
+   where F: FnMut(ScanProgress) + Send + 'static,
+   {
+      self.on_progress = Some(Box::new(callback));
+      return self;
+   }
+
+   /// Sets the callback which receives
+   /// the offset of every match, relative
+   /// to the start of the module, once the
+   /// scan finishes.
+   pub fn on_complete<F>(
+      mut self,
+      callback : F,
+   ) -> Self
+   where F: FnOnce(& [usize]) + Send + 'static,
+   {
+      self.on_complete = Some(Box::new(callback));
+      return self;
+   }
+
+   /// Searches the next chunk of memory,
+   /// returning <code>true</code> once the
+   /// whole module was searched.  Ticking
+   /// a finished scan does nothing.
+   ///
+   /// <h2 id=  pattern_scan_tick_safety>
+   /// <a href=#pattern_scan_tick_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from <code><a href=
+   /// #pattern_scan_new_safety>new</a></code>
+   /// apply.
+   pub unsafe fn tick(
+      & mut self,
+   ) -> bool {
+      if self.is_finished() == true {
+         return true;
+      }
+
+      let mut budget = self.chunk_size;
+      while budget != 0 {
+         let span = match self.spans.get(self.span) {
+            Some(span)  => span.clone(),
+            None        => break,
+         };
+
+         // Matches may start anywhere in the
+         // chunk but can extend past its end
+         let chunk_end  = usize::min(self.position.saturating_add(budget), span.end);
+         let bytes      = span_bytes(&(self.position..usize::min(
+            chunk_end.saturating_add(self.pattern.len() - 1),
+            span.end,
+         )));
+
+         for (index, window) in bytes.windows(self.pattern.len()).enumerate() {
+            if self.position + index >= chunk_end {
+               break;
+            }
+            if self.pattern.matches(window) == true {
+               self.matches.push(self.position + index - self.base);
+            }
+         }
+
+         budget                        -= chunk_end - self.position;
+         self.progress.scanned_bytes   += chunk_end - self.position;
+         self.position                 = chunk_end;
+
+         if self.position == span.end {
+            self.span += 1;
+            if let Some(span) = self.spans.get(self.span) {
+               self.position = span.start;
+            }
+         }
+      }
+
+      self.progress.match_count = self.matches.len();
+      if let Some(callback) = self.on_progress.as_mut() {
+         callback(self.progress);
+      }
+
+      if self.is_finished() == false {
+         return false;
+      }
+
+      if let Some(callback) = self.on_complete.take() {
+         callback(&self.matches);
+      }
+
+      return true;
+   }
+
+   /// Gets how much of the module was
+   /// searched so far.
+   pub fn progress(
+      & self,
+   ) -> ScanProgress {
+      return self.progress;
+   }
+
+   /// Checks whether the whole module
+   /// was searched.
+   pub fn is_finished(
+      & self,
+   ) -> bool {
+      return self.span >= self.spans.len();
+   }
+
+   /// Gets the offset of every match
+   /// found so far, relative to the
+   /// start of the module.
+   pub fn matches(
+      & self,
+   ) -> & [usize] {
+      return &self.matches;
+   }
+
+   /// Consumes the scan, returning the
+   /// offset of every match found so far.
+   pub fn into_matches(
+      self,
+   ) -> Vec<usize> {
+      return self.matches;
+   }
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Pattern //
+/////////////////////////////////////
+
+impl std::str::FromStr for Pattern {
+   type Err = ScanError;
+
+   fn from_str(
+      pattern : & str,
+   ) -> Result<Self> {
+      return Self::parse(pattern);
+   }
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - PatternMatches //
+////////////////////////////////////////////
+
+impl<'p> Iterator for PatternMatches<'p> {
+   type Item = usize;
+
+   fn next(
+      & mut self,
+   ) -> Option<Self::Item> {
+      while let Some(span) = self.spans.get(self.span) {
+         // Search the rest of the current span
+         // and move on to the next if nothing
+         // is left in it
+         let bytes = unsafe{span_bytes(&(self.position..span.end))};
+         let found = bytes.windows(self.pattern.len()).position(|window| {
+            self.pattern.matches(window)
+         });
+
+         if let Some(index) = found {
+            let address    = self.position + index;
+            self.position  = address + 1;
+            return Some(address - self.base);
+         }
+
+         self.span += 1;
+         if let Some(span) = self.spans.get(self.span) {
+            self.position = span.start;
+         }
+      }
+
+      return None;
+   }
+}
+
+///////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ReferenceKind //
+///////////////////////////////////////////
+
+impl From<crate::sys::decoder::ReferenceKind> for ReferenceKind {
+   fn from(
+      item : crate::sys::decoder::ReferenceKind,
+   ) -> Self {
+      use crate::sys::decoder::ReferenceKind::*;
+      return match item {
+         LoadEffectiveAddress
+            => Self::LoadEffectiveAddress,
+         Move
+            => Self::Move,
+         Call
+            => Self::Call,
+         Jump
+            => Self::Jump,
+         ConditionalJump
+            => Self::ConditionalJump,
+      };
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Finds every address range within
+/// the module which can be read without
+/// changing memory permissions and
+/// satisfies the region filter.  Adjacent
+/// matching regions are merged together.
+pub(crate) fn readable_spans<F>(
+   module : & crate::process::ModuleSnapshot,
+   filter : F,
+) -> Result<Vec<std::ops::Range<usize>>>
+where F: Fn(& crate::sys::memory::MemoryRegion) -> bool,
+{
+   let module_range = module.address_range().clone();
+
+   let mut spans : Vec<std::ops::Range<usize>> = Vec::new();
+   for region in crate::sys::memory::MemoryRegion::all_within(
+      module_range.clone(),
+   )? {
+      if region.is_readable() == false || filter(&region) == false {
+         continue;
+      }
+
+      // Clip the region to the module
+      let start   = usize::max(region.address_range().start, module_range.start);
+      let end     = usize::min(region.address_range().end,   module_range.end);
+
+      // Merge with the previous span if they touch
+      if let Some(last) = spans.last_mut() {
+         if last.end == start {
+            last.end = end;
+            continue;
+         }
+      }
+
+      spans.push(start..end);
+   }
+
+   return Ok(spans);
+}
+
+/// Creates a byte slice over a
+/// readable span of memory.
+unsafe fn span_bytes<'l>(
+   span : & std::ops::Range<usize>,
+) -> &'l [u8] {
+   return std::slice::from_raw_parts(
+      span.start as * const u8,
+      span.end - span.start,
+   );
+}
+
+/// Finds the address of every
+/// occurrence of a byte sequence
+/// within the spans.
+unsafe fn find_bytes(
+   spans    : & [std::ops::Range<usize>],
+   needle   : & [u8],
+) -> Vec<usize> {
+   let mut found = Vec::new();
+
+   for span in spans {
+      let bytes = span_bytes(span);
+
+      for (index, window) in bytes.windows(needle.len()).enumerate() {
+         if window == needle {
+            found.push(span.start + index);
+         }
+      }
+   }
+
+   return found;
+}
+
+/// Finds the address of every
+/// match of a pattern within the
+/// spans.
+pub(crate) unsafe fn find_pattern_addresses(
+   spans    : & [std::ops::Range<usize>],
+   pattern  : & Pattern,
+) -> Vec<usize> {
+   let mut found = Vec::new();
+
+   for span in spans {
+      let bytes = span_bytes(span);
+
+      for (index, window) in bytes.windows(pattern.len()).enumerate() {
+         if pattern.matches(window) == true {
+            found.push(span.start + index);
+         }
+      }
+   }
+
+   return found;
+}
+
+/// Finds every instruction within the
+/// spans which references one of the
+/// target addresses.  The returned
+/// tuples contain the instruction
+/// address and decoded reference.
+unsafe fn find_references<T>(
+   spans    : & [std::ops::Range<usize>],
+   targets  : & HashMap<usize, T>,
+) -> Vec<(usize, crate::sys::decoder::Reference)> {
+   let mut found = Vec::new();
+
+   for span in spans {
+      let bytes = span_bytes(span);
+
+      for index in 0..bytes.len() {
+         let address = span.start + index;
+
+         let reference = match crate::sys::decoder::decode_reference(
+            &bytes[index..], address,
+         ) {
+            Some(reference)   => reference,
+            None              => continue,
+         };
+
+         if targets.contains_key(&reference.target_address) {
+            found.push((address, reference));
+         }
+      }
+   }
+
+   return found;
+}
+
+/// Converts a decoded reference at
+/// an address into a module-relative
+/// reference.
+fn reference_from_decoded(
+   module      : & crate::process::ModuleSnapshot,
+   address     : usize,
+   reference   : crate::sys::decoder::Reference,
+) -> Reference {
+   let base                = module.address_range().start;
+   let instruction_offset  = address - base;
+
+   return Reference{
+      kind                       : reference.kind.into(),
+      instruction_offset_range   : instruction_offset..instruction_offset + reference.instruction_length,
+      target_offset              : reference.target_address.wrapping_sub(base),
+   };
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Finds every instruction in a module's
+/// executable memory which references a
+/// null-terminated copy of a string stored
+/// within the same module.  Both UTF-8 and
+/// UTF-16 encodings of the string are
+/// searched for.  The results are candidate code
+/// sites and may contain false positives
+/// since any executable byte sequence which
+/// decodes as a reference is considered.
+///
+/// <h2 id=  scan_find_string_refs_safety>
+/// <a href=#scan_find_string_refs_safety>
+/// Safety
+/// </a></h2>
+///
+/// The module's memory is read without
+/// any synchronization, so all safety
+/// concerns from <code>Patch::patch_read</code>
+/// apply.  In addition, the module must
+/// be loaded in the local process.
+pub unsafe fn find_string_refs(
+   module : & crate::process::ModuleSnapshot,
+   string : & str,
+) -> Result<Vec<StringReference>> {
+   if string.is_empty() == true {
+      return Err(ScanError::EmptyString);
+   }
+
+   // Null-terminated byte encodings of the string
+   let mut string_utf8 = string.as_bytes().to_vec();
+   string_utf8.push(0x00);
+
+   let string_utf16 = string.encode_utf16().chain([0x0000]).flat_map(|c| {
+      c.to_le_bytes()
+   }).collect::<Vec<u8>>();
+
+   // Find all copies of the string, mapping
+   // their addresses to their encoding
+   let spans_data = readable_spans(module, |_| true)?;
+   let spans_code = readable_spans(module, |region| region.is_executable())?;
+
+   let mut strings = HashMap::new();
+   for (encoding, bytes) in [
+      (StringEncoding::Utf8,  &string_utf8),
+      (StringEncoding::Utf16, &string_utf16),
+   ] {
+      for address in find_bytes(&spans_data, bytes) {
+         strings.insert(address, (encoding, bytes.len()));
+      }
+   }
+
+   if strings.is_empty() == true {
+      return Ok(Vec::new());
+   }
+
+   // Find all code referencing a copy of the string
+   let base = module.address_range().start;
+   let list = find_references(&spans_code, &strings).into_iter().map(
+      |(address, reference)| {
+      let (encoding, length) = strings[&reference.target_address];
+      let string_offset      = reference.target_address - base;
+
+      StringReference{
+         encoding             : encoding,
+         string_offset_range  : string_offset..string_offset + length,
+         reference            : reference_from_decoded(module, address, reference),
+      }
+   }).collect();
+
+   return Ok(list);
+}
+
+/// Finds every instruction in a module's
+/// executable memory which references
+/// an offset within the module, such as
+/// calls to a function or loads of a
+/// global variable.  This is useful for
+/// enumerating the callers of a function
+/// before deciding where to hook.  The
+/// results are candidate code sites and
+/// may contain false positives since any
+/// executable byte sequence which decodes
+/// as a reference is considered.
+///
+/// <h2 id=  scan_find_xrefs_safety>
+/// <a href=#scan_find_xrefs_safety>
+/// Safety
+/// </a></h2>
+///
+/// All safety concerns from <code><a href=
+/// #scan_find_string_refs_safety>find_string_refs</a></code>
+/// apply.
+pub unsafe fn find_xrefs(
+   module         : & crate::process::ModuleSnapshot,
+   target_offset  : usize,
+) -> Result<Vec<Reference>> {
+   let target = module.address_range().start.wrapping_add(target_offset);
+   let spans  = readable_spans(module, |region| region.is_executable())?;
+
+   let list = find_references(&spans, &HashMap::from([(target, ())]))
+      .into_iter()
+      .map(|(address, reference)| {
+         reference_from_decoded(module, address, reference)
+      })
+      .collect();
+
+   return Ok(list);
+}
@@ -0,0 +1,288 @@
+//! Capture the game's window to a PNG
+//! file, such as on a hotkey, to document
+//! what a visual mod does or attach to a
+//! bug report.
+//!
+//! The window is copied through GDI, so
+//! games running in exclusive fullscreen
+//! may capture as a black image.  Capturing
+//! is only supported on Windows.
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to capturing
+/// or saving a screenshot.
+#[derive(Debug)]
+pub enum ScreenshotError {
+   WindowNotFound,
+   Unsupported,
+   Unknown,
+   IoError{
+      err : std::io::Error,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>ScreenshotError</code>.
+pub type Result<T> = std::result::Result<T, ScreenshotError>;
+
+/// A captured image of the game's
+/// window.
+///
+/// ```
+/// let mut input = nusion_core::input::InputState::new();
+///
+/// loop {
+///    input.poll();
+///
+///    if let Some(path) = nusion_core::screenshot::capture_on_press(
+///       &input,
+///       nusion_core::input::Key::F12,
+///       "screenshots",
+///    )? {
+///       println!("Saved screenshot to {}", path.display());
+///    }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Screenshot {
+   frame : crate::sys::screenshot::Frame,
+}
+
+/////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - ScreenshotError //
+/////////////////////////////////////////////
+
+impl std::fmt::Display for ScreenshotError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::WindowNotFound
+            => write!(stream, "The process has no visible window"),
+         Self::Unsupported
+            => write!(stream, "Unsupported on this platform"),
+         Self::Unknown
+            => write!(stream, "Unknown"),
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for ScreenshotError {
+}
+
+impl From<crate::sys::screenshot::ScreenshotError> for ScreenshotError {
+   fn from(
+      item : crate::sys::screenshot::ScreenshotError,
+   ) -> Self {
+      use crate::sys::screenshot::ScreenshotError::*;
+      return match item {
+         WindowNotFound
+            => Self::WindowNotFound,
+         Unsupported
+            => Self::Unsupported,
+         Unknown
+            => Self::Unknown,
+      };
+   }
+}
+
+impl From<std::io::Error> for ScreenshotError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
+//////////////////////////
+// METHODS - Screenshot //
+//////////////////////////
+
+impl Screenshot {
+   /// Captures the client area of the
+   /// game's window, which is the largest
+   /// visible window owned by the process.
+   pub fn capture(
+   ) -> Result<Self> {
+      return Ok(Self{
+         frame : crate::sys::screenshot::capture_process_window()?,
+      });
+   }
+
+   /// Gets the width in pixels.
+   pub fn width(
+      & self,
+   ) -> usize {
+      return self.frame.width;
+   }
+
+   /// Gets the height in pixels.
+   pub fn height(
+      & self,
+   ) -> usize {
+      return self.frame.height;
+   }
+
+   /// Gets the pixels as rows of 8-bit
+   /// RGBA values, top row first.
+   pub fn pixels<'l>(
+      &'l self,
+   ) -> &'l [u8] {
+      return &self.frame.pixels;
+   }
+
+   /// Encodes the image as a PNG file.
+   /// The image data is stored without
+   /// compression, which keeps capturing
+   /// fast at the cost of file size.
+   pub fn to_png(
+      & self,
+   ) -> Vec<u8> {
+      const SIGNATURE : [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+      let mut header = Vec::with_capacity(13);
+      header.extend_from_slice(&(self.frame.width as u32).to_be_bytes());
+      header.extend_from_slice(&(self.frame.height as u32).to_be_bytes());
+
+      // 8-bit RGBA, default compression and
+      // filtering, no interlacing
+      header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+      // Each row starts with its filter type,
+      // which is always none
+      let row_length = self.frame.width * 4;
+      let mut scanlines = Vec::with_capacity((row_length + 1) * self.frame.height);
+      for row in self.frame.pixels.chunks_exact(row_length.max(1)) {
+         scanlines.push(0);
+         scanlines.extend_from_slice(row);
+      }
+
+      let mut png = SIGNATURE.to_vec();
+      write_chunk(& mut png, b"IHDR", &header);
+      write_chunk(& mut png, b"IDAT", &zlib_stored(&scanlines));
+      write_chunk(& mut png, b"IEND", &[]);
+      return png;
+   }
+
+   /// Saves the image to a PNG file.
+   pub fn save_png<P>(
+      & self,
+      path : P,
+   ) -> Result<()>
+   where P: AsRef<std::path::Path>,
+   {
+      std::fs::write(path, self.to_png())?;
+      return Ok(());
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Captures the game's window and saves it
+/// in a directory as <code>screenshot-&lt;time&gt;.png</code>,
+/// where the time is in milliseconds since
+/// the Unix epoch.  The directory is created
+/// if it doesn't exist.  Returns the path to
+/// the saved file.
+pub fn save_to_directory<P>(
+   directory : P,
+) -> Result<std::path::PathBuf>
+where P: AsRef<std::path::Path>,
+{
+   let screenshot = Screenshot::capture()?;
+
+   let time = std::time::SystemTime::now().duration_since(
+      std::time::UNIX_EPOCH,
+   ).map(|duration| duration.as_millis()).unwrap_or(0);
+
+   std::fs::create_dir_all(directory.as_ref())?;
+   let path = directory.as_ref().join(format!("screenshot-{time}.png"));
+
+   screenshot.save_png(&path)?;
+   return Ok(path);
+}
+
+/// Saves a screenshot to a directory if
+/// a key went down during the last input
+/// poll, returning the path to the saved
+/// file.
+pub fn capture_on_press<P>(
+   input       : & crate::input::InputState,
+   key         : crate::input::Key,
+   directory   : P,
+) -> Result<Option<std::path::PathBuf>>
+where P: AsRef<std::path::Path>,
+{
+   if input.was_pressed(key) == false {
+      return Ok(None);
+   }
+
+   return Ok(Some(save_to_directory(directory)?));
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn write_chunk(
+   png         : & mut Vec<u8>,
+   chunk_type  : & [u8; 4],
+   data        : & [u8],
+) {
+   png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+   // The CRC covers the type and data
+   // but not the length
+   let crc_start = png.len();
+   png.extend_from_slice(chunk_type);
+   png.extend_from_slice(data);
+
+   let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&png[crc_start..]);
+   png.extend_from_slice(&crc.to_be_bytes());
+   return;
+}
+
+/// Wraps data in a zlib stream made
+/// of uncompressed deflate blocks.
+fn zlib_stored(
+   data  : & [u8],
+) -> Vec<u8> {
+   const MAX_BLOCK_LENGTH : usize = 0xFFFF;
+
+   let mut stream = vec![0x78, 0x01];
+
+   let mut blocks = data.chunks(MAX_BLOCK_LENGTH).peekable();
+   if blocks.peek().is_none() == true {
+      stream.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+   }
+   while let Some(block) = blocks.next() {
+      let is_final   = blocks.peek().is_none();
+      let length     = block.len() as u16;
+
+      stream.push(u8::from(is_final));
+      stream.extend_from_slice(&length.to_le_bytes());
+      stream.extend_from_slice(&(!length).to_le_bytes());
+      stream.extend_from_slice(block);
+   }
+
+   // Adler-32 of the uncompressed data
+   let (mut a, mut b) = (1u32, 0u32);
+   for byte in data {
+      a = (a + *byte as u32) % 65521;
+      b = (b + a) % 65521;
+   }
+   stream.extend_from_slice(&((b << 16) | a).to_be_bytes());
+
+   return stream;
+}
+
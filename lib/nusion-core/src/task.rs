@@ -0,0 +1,392 @@
+//! Background tasks which are cancelled
+//! and joined before the mod unloads.
+//!
+//! A thread spawned with <code>std::thread</code>
+//! keeps running after the mod's library is
+//! freed, and crashes the game as soon as it
+//! executes code which no longer exists.  Tasks
+//! spawned through the environment are instead
+//! stopped when the environment is freed, before
+//! any of the mod's patches are reverted.
+//!
+//! ```
+//! let env = nusion_core::environment::Environment::get();
+//!
+//! env.spawn_task("ammo watcher", |context| {
+//!    // Sleeping wakes early once the
+//!    // task is cancelled
+//!    while context.sleep(std::time::Duration::from_millis(100)) == true {
+//!       // Poll something
+//!    }
+//! })?;
+//! ```
+//!
+//! Cancellation is cooperative, so a task
+//! which never checks its context will hang
+//! the unload instead of crashing the game.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to a task.
+#[derive(Debug)]
+pub enum TaskError {
+   ShuttingDown,
+   IoError{
+      err : std::io::Error,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>TaskError</code>.
+pub type Result<T> = std::result::Result<T, TaskError>;
+
+/// Passed to a running task to check
+/// whether it was cancelled.
+pub struct TaskContext {
+   shared : Arc<TaskShared>,
+}
+
+/// A handle to a spawned task.  Dropping
+/// the handle doesn't stop the task, since
+/// its runner still owns it.
+#[derive(Clone)]
+pub struct TaskHandle {
+   name     : Arc<str>,
+   shared   : Arc<TaskShared>,
+}
+
+/// Runs tasks on their own threads and
+/// stops every one of them on shutdown,
+/// which also happens when the runner is
+/// dropped.  The environment owns a runner
+/// which is shut down when it is freed.
+pub struct TaskRunner {
+   state : Mutex<TaskRunnerState>,
+}
+
+/// State shared between a task's
+/// thread and its handles.
+struct TaskShared {
+   cancelled   : Mutex<bool>,
+   wake        : Condvar,
+   finished    : AtomicBool,
+}
+
+/// A spawned task owned by a runner.
+struct Task {
+   handle   : TaskHandle,
+   thread   : std::thread::JoinHandle<()>,
+}
+
+/// Mutable state of a task runner.
+struct TaskRunnerState {
+   tasks          : Vec<Task>,
+   shutting_down  : bool,
+}
+
+///////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - TaskError //
+///////////////////////////////////////
+
+impl std::fmt::Display for TaskError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::ShuttingDown
+            => write!(stream, "The task runner is shutting down"),
+         Self::IoError{err}
+            => write!(stream, "I/O error: {err}"),
+      };
+   }
+}
+
+impl std::error::Error for TaskError {
+}
+
+impl From<std::io::Error> for TaskError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
+///////////////////////////////////
+// INTERNAL METHODS - TaskShared //
+///////////////////////////////////
+
+impl TaskShared {
+   fn cancel(
+      & self,
+   ) {
+      *self.cancelled.lock().unwrap_or_else(|poison| poison.into_inner()) = true;
+      self.wake.notify_all();
+      return;
+   }
+
+   fn is_cancelled(
+      & self,
+   ) -> bool {
+      return *self.cancelled.lock().unwrap_or_else(|poison| poison.into_inner());
+   }
+
+   // Marked under the lock so a thread
+   // about to wait can't miss the wake
+   fn finish(
+      & self,
+   ) {
+      let _cancelled = self.cancelled.lock().unwrap_or_else(|poison| poison.into_inner());
+      self.finished.store(true, Ordering::Release);
+      self.wake.notify_all();
+      return;
+   }
+}
+
+///////////////////////////
+// METHODS - TaskContext //
+///////////////////////////
+
+impl TaskContext {
+   /// Checks whether the task was
+   /// cancelled and should return.
+   pub fn is_cancelled(
+      & self,
+   ) -> bool {
+      return self.shared.is_cancelled();
+   }
+
+   /// Sleeps for a duration or until the
+   /// task is cancelled, whichever happens
+   /// first.  Returns <code>false</code> if
+   /// the task was cancelled, so it can be
+   /// used as a loop condition.
+   pub fn sleep(
+      & self,
+      duration : std::time::Duration,
+   ) -> bool {
+      let cancelled = self.shared.cancelled.lock().unwrap_or_else(|poison| poison.into_inner());
+      let (cancelled, _) = self.shared.wake.wait_timeout_while(
+         cancelled,
+         duration,
+         |cancelled| *cancelled == false,
+      ).unwrap_or_else(|poison| poison.into_inner());
+
+      return *cancelled == false;
+   }
+}
+
+//////////////////////////
+// METHODS - TaskHandle //
+//////////////////////////
+
+impl TaskHandle {
+   /// Gets the name the task was
+   /// spawned with.
+   pub fn name(
+      & self,
+   ) -> & str {
+      return &self.name;
+   }
+
+   /// Asks the task to stop.  This
+   /// doesn't wait for it to return.
+   pub fn cancel(
+      & self,
+   ) {
+      self.shared.cancel();
+      return;
+   }
+
+   /// Checks whether the task was
+   /// asked to stop.
+   pub fn is_cancelled(
+      & self,
+   ) -> bool {
+      return self.shared.is_cancelled();
+   }
+
+   /// Checks whether the task returned
+   /// or panicked.
+   pub fn is_finished(
+      & self,
+   ) -> bool {
+      return self.shared.finished.load(Ordering::Acquire);
+   }
+
+   /// Waits for the task to return or
+   /// panic.  Calling this from within the
+   /// task itself never returns.
+   pub fn wait(
+      & self,
+   ) {
+      let cancelled = self.shared.cancelled.lock().unwrap_or_else(|poison| poison.into_inner());
+      let _cancelled = self.shared.wake.wait_while(
+         cancelled,
+         |_| self.shared.finished.load(Ordering::Acquire) == false,
+      ).unwrap_or_else(|poison| poison.into_inner());
+
+      return;
+   }
+}
+
+//////////////////////////
+// METHODS - TaskRunner //
+//////////////////////////
+
+impl TaskRunner {
+   /// Creates a runner without
+   /// any tasks.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         state : Mutex::new(TaskRunnerState{
+            tasks          : Vec::new(),
+            shutting_down  : false,
+         }),
+      };
+   }
+
+   /// Spawns a task on its own thread
+   /// with a name, which is also used as
+   /// the thread's name.  Tasks which
+   /// already finished are cleaned up
+   /// whenever a new one is spawned.
+   pub fn spawn<F>(
+      & self,
+      name : & str,
+      task : F,
+   ) -> Result<TaskHandle>
+   where F: FnOnce(& TaskContext) + Send + 'static,
+   {
+      let mut state = self.state.lock().unwrap_or_else(|poison| poison.into_inner());
+      if state.shutting_down == true {
+         return Err(TaskError::ShuttingDown);
+      }
+
+      // Finished threads are still joined since
+      // they may not have fully exited yet
+      let (finished, running) = std::mem::take(& mut state.tasks)
+         .into_iter()
+         .partition(|task| task.handle.is_finished() == true);
+      state.tasks = running;
+      join_all(finished);
+
+      let shared = Arc::new(TaskShared{
+         cancelled   : Mutex::new(false),
+         wake        : Condvar::new(),
+         finished    : AtomicBool::new(false),
+      });
+      let context = TaskContext{
+         shared : shared.clone(),
+      };
+
+      let thread = std::thread::Builder::new()
+         .name(String::from(name))
+         .spawn(move || {
+            let _registration = crate::runtime::register_current_thread();
+
+            // A panic is passed on to the join
+            // once the task is marked finished
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task(&context)));
+            context.shared.finish();
+            if let Err(panic) = result {
+               std::panic::resume_unwind(panic);
+            }
+         })?;
+
+      let handle = TaskHandle{
+         name     : Arc::from(name),
+         shared   : shared,
+      };
+      state.tasks.push(Task{
+         handle   : handle.clone(),
+         thread   : thread,
+      });
+
+      return Ok(handle);
+   }
+
+   /// Gets a handle to every task which
+   /// is still running.
+   pub fn tasks(
+      & self,
+   ) -> Vec<TaskHandle> {
+      let state = self.state.lock().unwrap_or_else(|poison| poison.into_inner());
+      return state.tasks.iter()
+         .filter(|task| task.handle.is_finished() == false)
+         .map(|task| task.handle.clone())
+         .collect();
+   }
+
+   /// Cancels every task and waits for
+   /// all of them to return.  No tasks can
+   /// be spawned afterwards.  A task which
+   /// panicked is logged and otherwise
+   /// ignored.
+   pub fn shutdown(
+      & self,
+   ) {
+      // The lock is released before joining
+      // so finishing tasks can't deadlock
+      // on it by spawning or listing tasks
+      let tasks = {
+         let mut state = self.state.lock().unwrap_or_else(|poison| poison.into_inner());
+         state.shutting_down = true;
+         std::mem::take(& mut state.tasks)
+      };
+
+      for task in tasks.iter() {
+         task.handle.cancel();
+      }
+      join_all(tasks);
+
+      return;
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - TaskRunner //
+////////////////////////////////////////
+
+impl Default for TaskRunner {
+   fn default(
+   ) -> Self {
+      return Self::new();
+   }
+}
+
+impl std::ops::Drop for TaskRunner {
+   fn drop(
+      & mut self,
+   ) {
+      self.shutdown();
+      return;
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+/// Waits for every task's thread to
+/// exit, logging any which panicked.
+fn join_all(
+   tasks : Vec<Task>,
+) {
+   for task in tasks {
+      if task.thread.join().is_err() == true {
+         crate::log::error!("Task \"{}\" panicked", task.handle.name());
+      }
+   }
+
+   return;
+}
@@ -0,0 +1,880 @@
+//! Recording patch operations during a
+//! live session and replaying them offline
+//! against a dumped memory image.
+//!
+//! Wrapping a module in a <code>Recorder</code>
+//! logs the bytes before and after every
+//! read and write made through the
+//! <code>Patch</code> trait.  The module
+//! can also be dumped to a <code>MemoryImage</code>,
+//! which implements <code>Patch</code> itself,
+//! so the same patch logic can later be run
+//! against the image without launching the
+//! game and the two traces compared.
+//!
+//! ```
+//! use nusion_core::patch::Patch;
+//! use nusion_core::trace::{MemoryImage, Recorder, Trace};
+//!
+//! // During a live session
+//! let mut game = nusion_core::game_mut!();
+//! unsafe{MemoryImage::dump(&game)}?.save("game.bin")?;
+//!
+//! let mut recorder = Recorder::new(& mut *game);
+//! apply_patches(& mut recorder)?;
+//! recorder.trace().save("live.trace")?;
+//!
+//! // Later, offline
+//! let live      = Trace::load("live.trace")?;
+//! let mut image = MemoryImage::load("game.bin")?;
+//!
+//! let mut recorder = Recorder::new(& mut image);
+//! apply_patches(& mut recorder)?;
+//!
+//! if let Some(index) = live.first_divergence(&recorder.trace()) {
+//!    println!("Diverged at operation {index}");
+//! }
+//! ```
+
+use std::ops::{Bound, RangeBounds};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to saving, loading,
+/// or replaying a trace.
+#[derive(Debug)]
+pub enum TraceError {
+   IoError{
+      err   : std::io::Error,
+   },
+   InvalidFormat{
+      line  : usize,
+   },
+   OutOfRange{
+      index    : usize,
+      offset   : usize,
+   },
+   ReplayMismatch{
+      index    : usize,
+      offset   : usize,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>TraceError</code>.
+pub type Result<T> = std::result::Result<T, TraceError>;
+
+/// The kind of patch operation stored
+/// in a trace entry, one for each
+/// function of the <code>Patch</code>
+/// trait.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceOperation {
+   Read,
+   Write,
+   WriteUnchecked,
+   Create,
+   CreateUnchecked,
+   CreateAtomic,
+   CreateChunked,
+}
+
+/// A single recorded patch operation.
+/// The offset is the start of the range
+/// accessed by the reader or writer.
+/// For reads, the before and after bytes
+/// are the same.  If the operation failed,
+/// the error message is stored and the
+/// after bytes are unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+   pub operation  : TraceOperation,
+   pub offset     : usize,
+   pub before     : Vec<u8>,
+   pub after      : Vec<u8>,
+   pub error      : Option<String>,
+}
+
+/// An ordered list of recorded patch
+/// operations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+   entries  : Vec<TraceEntry>,
+}
+
+/// Wraps a type implementing <code>Patch</code>,
+/// forwarding every operation to it and
+/// recording the operation in a trace.
+pub struct Recorder<'p, P: crate::patch::Patch> {
+   inner : &'p mut P,
+   trace : std::cell::RefCell<Trace>,
+}
+
+/// A copy of a module's memory which
+/// can be patched offline.
+#[derive(Clone, Debug)]
+pub struct MemoryImage {
+   bytes : Vec<u8>,
+}
+
+/// Stores the bytes overwritten by a
+/// patch on a memory image.  Unlike
+/// module patch containers, the bytes
+/// aren't restored on drop and must be
+/// restored with <code>restore</code>.
+#[derive(Clone, Debug)]
+pub struct MemoryImagePatchContainer {
+   offset_range   : std::ops::Range<usize>,
+   old_bytes      : Vec<u8>,
+   restore_ranges : Vec<std::ops::Range<usize>>,
+}
+
+/// Reads every byte in a range.
+struct RawBytes {
+   memory_offset_range  : (Bound<usize>, Bound<usize>),
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - TraceError //
+////////////////////////////////////////
+
+impl std::fmt::Display for TraceError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::IoError        {err,             }
+            => write!(stream, "I/O error: {err}"),
+         Self::InvalidFormat  {line,            }
+            => write!(stream, "Invalid trace format on line {line}"),
+         Self::OutOfRange     {index, offset,   }
+            => write!(stream, "Operation {index} at offset {offset:#X} is outside the memory image"),
+         Self::ReplayMismatch {index, offset,   }
+            => write!(stream, "Operation {index} at offset {offset:#X} found different bytes than recorded"),
+      };
+   }
+}
+
+impl std::error::Error for TraceError {
+}
+
+impl From<std::io::Error> for TraceError {
+   fn from(
+      item : std::io::Error,
+   ) -> Self {
+      return Self::IoError{
+         err : item,
+      };
+   }
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - TraceOperation //
+////////////////////////////////////////////
+
+impl std::fmt::Display for TraceOperation {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream, "{}", match self {
+         Self::Read              => "read",
+         Self::Write             => "write",
+         Self::WriteUnchecked    => "write-unchecked",
+         Self::Create            => "create",
+         Self::CreateUnchecked   => "create-unchecked",
+         Self::CreateAtomic      => "create-atomic",
+         Self::CreateChunked     => "create-chunked",
+      });
+   }
+}
+
+impl std::str::FromStr for TraceOperation {
+   type Err = ();
+
+   fn from_str(
+      s : & str,
+   ) -> std::result::Result<Self, Self::Err> {
+      return match s {
+         "read"               => Ok(Self::Read),
+         "write"              => Ok(Self::Write),
+         "write-unchecked"    => Ok(Self::WriteUnchecked),
+         "create"             => Ok(Self::Create),
+         "create-unchecked"   => Ok(Self::CreateUnchecked),
+         "create-atomic"      => Ok(Self::CreateAtomic),
+         "create-chunked"     => Ok(Self::CreateChunked),
+         _                    => Err(()),
+      };
+   }
+}
+
+////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - TraceEntry //
+////////////////////////////////////////
+
+impl std::fmt::Display for TraceEntry {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      write!(
+         stream,
+         "{} {:#X} {} {}",
+         self.operation,
+         self.offset,
+         hex_encode(&self.before),
+         hex_encode(&self.after),
+      )?;
+
+      if let Some(error) = &self.error {
+         write!(stream, " ! {error}")?;
+      }
+
+      return Ok(());
+   }
+}
+
+/////////////////////
+// METHODS - Trace //
+/////////////////////
+
+impl Trace {
+   /// Creates an empty trace.
+   pub fn new(
+   ) -> Self {
+      return Self{
+         entries  : Vec::new(),
+      };
+   }
+
+   /// Gets the recorded operations,
+   /// oldest first.
+   pub fn entries<'l>(
+      &'l self,
+   ) -> &'l [TraceEntry] {
+      return &self.entries;
+   }
+
+   /// Returns the number of recorded
+   /// operations.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.entries.len();
+   }
+
+   /// Checks whether no operations
+   /// have been recorded.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.entries.is_empty();
+   }
+
+   /// Writes the trace to a text file
+   /// with one operation per line.
+   pub fn save<P>(
+      & self,
+      path  : P,
+   ) -> Result<()>
+   where P: AsRef<std::path::Path>,
+   {
+      let mut text = String::new();
+      for entry in self.entries.iter() {
+         text += &entry.to_string();
+         text += "\n";
+      }
+
+      std::fs::write(path, text)?;
+      return Ok(());
+   }
+
+   /// Reads a trace previously written
+   /// with <code>save</code>.
+   pub fn load<P>(
+      path  : P,
+   ) -> Result<Self>
+   where P: AsRef<std::path::Path>,
+   {
+      let text = std::fs::read_to_string(path)?;
+
+      let mut entries = Vec::new();
+      for (index, line) in text.lines().enumerate() {
+         if line.trim().is_empty() == true {
+            continue;
+         }
+
+         entries.push(parse_entry(line).ok_or(TraceError::InvalidFormat{
+            line  : index + 1,
+         })?);
+      }
+
+      return Ok(Self{
+         entries  : entries,
+      });
+   }
+
+   /// Applies the recorded writes to a
+   /// memory image in order, checking
+   /// that the image contains the recorded
+   /// before bytes at every step.  Failed
+   /// operations are checked but not
+   /// written.
+   pub fn replay(
+      & self,
+      image : & mut MemoryImage,
+   ) -> Result<()> {
+      for (index, entry) in self.entries.iter().enumerate() {
+         let bytes = entry.offset.checked_add(entry.before.len()).and_then(
+            |end| image.bytes.get_mut(entry.offset..end),
+         ).ok_or(TraceError::OutOfRange{
+            index    : index,
+            offset   : entry.offset,
+         })?;
+
+         if bytes != entry.before.as_slice() {
+            return Err(TraceError::ReplayMismatch{
+               index    : index,
+               offset   : entry.offset,
+            });
+         }
+
+         if entry.error.is_none() == true && entry.after.len() == bytes.len() {
+            bytes.copy_from_slice(&entry.after);
+         }
+      }
+
+      return Ok(());
+   }
+
+   /// Compares two traces and returns the
+   /// index of the first operation which
+   /// differs, or <code>None</code> if they
+   /// are identical.  If one trace is a
+   /// prefix of the other, the index is
+   /// the length of the shorter trace.
+   pub fn first_divergence(
+      & self,
+      other : & Self,
+   ) -> Option<usize> {
+      if let Some(index) = self.entries.iter().zip(other.entries.iter()).position(
+         |(left, right)| left != right,
+      ) {
+         return Some(index);
+      }
+
+      if self.entries.len() != other.entries.len() {
+         return Some(std::cmp::min(self.entries.len(), other.entries.len()));
+      }
+
+      return None;
+   }
+}
+
+////////////////////////
+// METHODS - Recorder //
+////////////////////////
+
+impl<'p, P: crate::patch::Patch> Recorder<'p, P> {
+   /// Wraps a patch target with an
+   /// empty trace.
+   pub fn new(
+      inner : &'p mut P,
+   ) -> Self {
+      return Self{
+         inner : inner,
+         trace : std::cell::RefCell::new(Trace::new()),
+      };
+   }
+
+   /// Gets the operations recorded
+   /// so far.
+   pub fn trace<'l>(
+      &'l self,
+   ) -> std::cell::Ref<'l, Trace> {
+      return self.trace.borrow();
+   }
+
+   /// Consumes the recorder, returning
+   /// the recorded trace.
+   pub fn into_trace(
+      self,
+   ) -> Trace {
+      return self.trace.into_inner();
+   }
+}
+
+/////////////////////////////////
+// INTERNAL METHODS - Recorder //
+/////////////////////////////////
+
+impl<'p, P: crate::patch::Patch> Recorder<'p, P> {
+   unsafe fn read_bytes(
+      & self,
+      range : & (Bound<usize>, Bound<usize>),
+   ) -> crate::patch::Result<Vec<u8>> {
+      return self.inner.patch_read(&RawBytes{
+         memory_offset_range  : range.clone(),
+      });
+   }
+
+   unsafe fn record_write<T, F>(
+      & mut self,
+      operation   : TraceOperation,
+      range       : (Bound<usize>, Bound<usize>),
+      write       : F,
+   ) -> crate::patch::Result<T>
+   where F: FnOnce(& mut P) -> crate::patch::Result<T>,
+   {
+      let before = self.read_bytes(&range).unwrap_or_default();
+
+      let result = write(self.inner);
+
+      let after = match result {
+         Ok(_)    => self.read_bytes(&range).unwrap_or_default(),
+         Err(_)   => before.clone(),
+      };
+
+      self.trace.borrow_mut().entries.push(TraceEntry{
+         operation   : operation,
+         offset      : bound_start(&range),
+         before      : before,
+         after       : after,
+         error       : result.as_ref().err().map(|err| err.to_string()),
+      });
+
+      return result;
+   }
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Recorder //
+//////////////////////////////////////
+
+impl<'p, P: crate::patch::Patch> crate::patch::Patch for Recorder<'p, P> {
+   type Container = P::Container;
+
+   unsafe fn patch_read<Rd, Mr>(
+      & self,
+      reader : & Rd,
+   ) -> crate::patch::Result<Rd::Item>
+   where Rd: crate::patch::Reader<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(reader.memory_offset_range());
+      let bytes = self.read_bytes(&range).unwrap_or_default();
+
+      let result = self.inner.patch_read(reader);
+
+      self.trace.borrow_mut().entries.push(TraceEntry{
+         operation   : TraceOperation::Read,
+         offset      : bound_start(&range),
+         before      : bytes.clone(),
+         after       : bytes,
+         error       : result.as_ref().err().map(|err| err.to_string()),
+      });
+
+      return result;
+   }
+
+   unsafe fn patch_write<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<()>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::Write, range, |inner| {
+         return inner.patch_write(writer);
+      });
+   }
+
+   unsafe fn patch_write_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<()>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::WriteUnchecked, range, |inner| {
+         return inner.patch_write_unchecked(writer);
+      });
+   }
+
+   unsafe fn patch_create<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::Create, range, |inner| {
+         return inner.patch_create(writer);
+      });
+   }
+
+   unsafe fn patch_create_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::CreateUnchecked, range, |inner| {
+         return inner.patch_create_unchecked(writer);
+      });
+   }
+
+   unsafe fn patch_create_atomic<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::CreateAtomic, range, |inner| {
+         return inner.patch_create_atomic(writer);
+      });
+   }
+
+   unsafe fn patch_create_chunked<Wt, Mr, F>(
+      & mut self,
+      writer      : & Wt,
+      chunk_size  : usize,
+      progress    : F,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+         F:  FnMut(crate::patch::ChunkProgress) -> crate::patch::ChunkAction,
+   {
+      let range = bounds_of(writer.memory_offset_range());
+      return self.record_write(TraceOperation::CreateChunked, range, |inner| {
+         return inner.patch_create_chunked(writer, chunk_size, progress);
+      });
+   }
+}
+
+///////////////////////////
+// METHODS - MemoryImage //
+///////////////////////////
+
+impl MemoryImage {
+   /// Creates a memory image from
+   /// raw bytes.
+   pub fn new(
+      bytes : Vec<u8>,
+   ) -> Self {
+      return Self{
+         bytes : bytes,
+      };
+   }
+
+   /// Copies the entire memory of a
+   /// module into a new image.
+   ///
+   /// <h2 id=  memory_image_dump_safety>
+   /// <a href=#memory_image_dump_safety>
+   /// Safety
+   /// </a></h2>
+   ///
+   /// All safety concerns from
+   /// <code>Patch::patch_read</code> apply.
+   pub unsafe fn dump(
+      module : & crate::process::ModuleSnapshot,
+   ) -> crate::patch::Result<Self> {
+      use crate::patch::Patch;
+
+      let bytes = module.patch_read(&RawBytes{
+         memory_offset_range  : (Bound::Unbounded, Bound::Unbounded),
+      })?;
+
+      return Ok(Self::new(bytes));
+   }
+
+   /// Reads a memory image previously
+   /// written with <code>save</code>.
+   pub fn load<P>(
+      path  : P,
+   ) -> Result<Self>
+   where P: AsRef<std::path::Path>,
+   {
+      return Ok(Self::new(std::fs::read(path)?));
+   }
+
+   /// Writes the raw bytes of the
+   /// image to a file.
+   pub fn save<P>(
+      & self,
+      path  : P,
+   ) -> Result<()>
+   where P: AsRef<std::path::Path>,
+   {
+      std::fs::write(path, &self.bytes)?;
+      return Ok(());
+   }
+
+   /// Gets the bytes stored in the
+   /// image.
+   pub fn as_bytes<'l>(
+      &'l self,
+   ) -> &'l [u8] {
+      return &self.bytes;
+   }
+
+   /// Returns the length of the image
+   /// in bytes.
+   pub fn len(
+      & self,
+   ) -> usize {
+      return self.bytes.len();
+   }
+
+   /// Checks whether the image has
+   /// no bytes.
+   pub fn is_empty(
+      & self,
+   ) -> bool {
+      return self.bytes.is_empty();
+   }
+}
+
+////////////////////////////////////
+// INTERNAL METHODS - MemoryImage //
+////////////////////////////////////
+
+impl MemoryImage {
+   fn offset_range_to_buffer_range<R>(
+      & self,
+      offset_range   : & R,
+   ) -> crate::patch::Result<std::ops::Range<usize>>
+   where R: RangeBounds<usize>,
+   {
+      return crate::patch::resolve_offset_range(offset_range, self.bytes.len());
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - MemoryImage //
+/////////////////////////////////////////
+
+impl crate::patch::Patch for MemoryImage {
+   type Container = MemoryImagePatchContainer;
+
+   unsafe fn patch_read<Rd, Mr>(
+      & self,
+      reader : & Rd,
+   ) -> crate::patch::Result<Rd::Item>
+   where Rd: crate::patch::Reader<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = self.offset_range_to_buffer_range(reader.memory_offset_range())?;
+
+      return reader.read_item(&self.bytes[range]);
+   }
+
+   unsafe fn patch_write<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<()>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = self.offset_range_to_buffer_range(writer.memory_offset_range())?;
+      let start = range.start;
+      let bytes = &mut self.bytes[range];
+
+      if writer.position_independent() == false {
+         return Err(crate::patch::PatchError::PositionDependent);
+      }
+
+      writer.verify_checksum(bytes, start)?;
+
+      return writer.build_patch(bytes, start);
+   }
+
+   unsafe fn patch_write_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<()>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = self.offset_range_to_buffer_range(writer.memory_offset_range())?;
+
+      if writer.position_independent() == false {
+         return Err(crate::patch::PatchError::PositionDependent);
+      }
+
+      return writer.build_patch(&mut self.bytes[range.clone()], range.start);
+   }
+
+   unsafe fn patch_create<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = self.offset_range_to_buffer_range(writer.memory_offset_range())?;
+
+      writer.verify_checksum(&self.bytes[range.clone()], range.start)?;
+
+      return self.patch_create_unchecked(writer);
+   }
+
+   unsafe fn patch_create_unchecked<Wt, Mr>(
+      & mut self,
+      writer : & Wt,
+   ) -> crate::patch::Result<Self::Container>
+   where Wt: crate::patch::Writer<Mr>,
+         Mr: RangeBounds<usize>,
+   {
+      let range = self.offset_range_to_buffer_range(writer.memory_offset_range())?;
+      let bytes = &mut self.bytes[range.clone()];
+
+      if writer.position_independent() == false {
+         return Err(crate::patch::PatchError::PositionDependent);
+      }
+
+      let container = Self::Container{
+         offset_range   : range.clone(),
+         old_bytes      : bytes.to_vec(),
+         restore_ranges : writer.overwritten_ranges(bytes.len()),
+      };
+
+      writer.build_patch(bytes, range.start)?;
+
+      return Ok(container);
+   }
+}
+
+/////////////////////////////////////////
+// METHODS - MemoryImagePatchContainer //
+/////////////////////////////////////////
+
+impl MemoryImagePatchContainer {
+   /// Restores the overwritten bytes in
+   /// the image the patch was applied to.
+   pub fn restore(
+      self,
+      image : & mut MemoryImage,
+   ) {
+      let bytes = &mut image.bytes[self.offset_range];
+      for range in self.restore_ranges.iter() {
+         bytes[range.clone()].copy_from_slice(&self.old_bytes[range.clone()]);
+      }
+
+      return;
+   }
+}
+
+//////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - RawBytes //
+//////////////////////////////////////
+
+impl crate::patch::Reader<(Bound<usize>, Bound<usize>)> for RawBytes {
+   type Item = Vec<u8>;
+
+   fn memory_offset_range<'l>(
+      &'l self,
+   ) -> &'l (Bound<usize>, Bound<usize>) {
+      return &self.memory_offset_range;
+   }
+
+   fn read_item(
+      & self,
+      memory_buffer  : & [u8],
+   ) -> crate::patch::Result<Self::Item> {
+      return Ok(memory_buffer.to_vec());
+   }
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn bounds_of<R>(
+   range : & R,
+) -> (Bound<usize>, Bound<usize>)
+where R: RangeBounds<usize>,
+{
+   return (range.start_bound().cloned(), range.end_bound().cloned());
+}
+
+fn bound_start(
+   range : & (Bound<usize>, Bound<usize>),
+) -> usize {
+   return match range.0 {
+      Bound::Included(b)   => b,
+      Bound::Excluded(b)   => b.saturating_add(1),
+      Bound::Unbounded     => 0,
+   };
+}
+
+fn hex_encode(
+   bytes : & [u8],
+) -> String {
+   // Empty byte strings are written as a
+   // dash so every line has the same number
+   // of fields
+   if bytes.is_empty() == true {
+      return String::from("-");
+   }
+
+   return bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+}
+
+fn hex_decode(
+   text : & str,
+) -> Option<Vec<u8>> {
+   if text == "-" {
+      return Some(Vec::new());
+   }
+   if text.len() % 2 != 0 || text.is_ascii() == false {
+      return None;
+   }
+
+   return (0..text.len()).step_by(2).map(|index| {
+      u8::from_str_radix(&text[index..index + 2], 16).ok()
+   }).collect();
+}
+
+fn parse_entry(
+   line : & str,
+) -> Option<TraceEntry> {
+   let (fields, error) = match line.split_once(" ! ") {
+      Some((fields, error))   => (fields, Some(String::from(error))),
+      None                    => (line, None),
+   };
+
+   let mut fields = fields.split_whitespace();
+   let operation  = fields.next()?.parse().ok()?;
+   let offset     = fields.next()?;
+   let before     = hex_decode(fields.next()?)?;
+   let after      = hex_decode(fields.next()?)?;
+   if fields.next().is_some() == true {
+      return None;
+   }
+
+   let offset = usize::from_str_radix(
+      offset.strip_prefix("0x").or(offset.strip_prefix("0X"))?,
+      16,
+   ).ok()?;
+
+   return Some(TraceEntry{
+      operation   : operation,
+      offset      : offset,
+      before      : before,
+      after       : after,
+      error       : error,
+   });
+}
+
@@ -0,0 +1,475 @@
+//! Gate features on the version of the
+//! game, so a mod loaded into a game build
+//! it wasn't written for disables the
+//! features which may not work instead of
+//! crashing on checksum errors.  Features
+//! are usually gated with the
+//! <code>supported_versions</code> attribute.
+//!
+//! The game's version is read from the
+//! version resource of the process's main
+//! executable.  If that isn't the version
+//! the game reports, such as for games built
+//! on third-party engines, or on Linux where
+//! executables have no version resource, set
+//! it with <code>set_game_version</code>
+//! before any gated feature is used.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, RwLock};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// An error relating to game versions.
+#[derive(Debug)]
+pub enum VersionError {
+   InvalidVersion{
+      text     : String,
+   },
+   InvalidPattern{
+      text     : String,
+   },
+   UnsupportedVersion{
+      feature  : &'static str,
+      version  : Option<Version>,
+   },
+}
+
+/// <code>Result</code> type with error
+/// variant <code>VersionError</code>.
+pub type Result<T> = std::result::Result<T, VersionError>;
+
+/// A four-part version number such as
+/// <code>1.38.2.1047</code>.  Missing parts
+/// are zero when parsed.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Version {
+   pub major      : u16,
+   pub minor      : u16,
+   pub build      : u16,
+   pub revision   : u16,
+}
+
+/// A pattern which matches a set of
+/// versions, such as <code>1.38.*</code>.
+/// Each part is a number or a
+/// <code>*</code> wildcard, which is only
+/// allowed as the last part and matches
+/// every remaining part.  Parts left out
+/// of a pattern without a wildcard must be
+/// zero, so <code>1.39</code> only matches
+/// <code>1.39.0.0</code>.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionPattern {
+   parts    : Vec<u16>,
+   wildcard : bool,
+}
+
+/// The version check for a single
+/// feature.  The check happens the
+/// first time the feature is used and
+/// is remembered afterwards.  Features
+/// which are disabled are reported once
+/// on standard output.
+pub struct VersionGate {
+   feature  : &'static str,
+   patterns : &'static [&'static str],
+   state    : AtomicU8,
+}
+
+///////////////////////
+// TRAIT DEFINITIONS //
+///////////////////////
+
+/// Trait for the return types of gated
+/// features, which gives the value returned
+/// in place of running a disabled feature.
+pub trait Unsupported {
+   /// Gets the value returned when a
+   /// feature is disabled.
+   fn unsupported(
+      gate : & VersionGate,
+   ) -> Self;
+}
+
+////////////////////////////////////
+// GLOBAL VARIABLES AND CONSTANTS //
+////////////////////////////////////
+
+const GATE_STATE_UNCHECKED    : u8 = 0;
+const GATE_STATE_SUPPORTED    : u8 = 1;
+const GATE_STATE_UNSUPPORTED  : u8 = 2;
+
+lazy_static::lazy_static!{
+   static ref GAME_VERSION          : RwLock<Option<Option<Version>>>
+      = RwLock::new(None);
+   static ref UNSUPPORTED_FEATURES  : Mutex<Vec<&'static str>>
+      = Mutex::new(Vec::new());
+}
+
+//////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - VersionError //
+//////////////////////////////////////////
+
+impl std::fmt::Display for VersionError {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return match self {
+         Self::InvalidVersion{text}
+            => write!(stream, "Invalid version \"{text}\""),
+         Self::InvalidPattern{text}
+            => write!(stream, "Invalid version pattern \"{text}\""),
+         Self::UnsupportedVersion{feature, version : Some(version)}
+            => write!(stream, "Feature \"{feature}\" doesn't support game version {version}"),
+         Self::UnsupportedVersion{feature, version : None}
+            => write!(stream, "Feature \"{feature}\" is disabled because the game version is unknown"),
+      };
+   }
+}
+
+impl std::error::Error for VersionError {
+}
+
+///////////////////////
+// METHODS - Version //
+///////////////////////
+
+impl Version {
+   /// Creates a version from its parts.
+   pub const fn new(
+      major    : u16,
+      minor    : u16,
+      build    : u16,
+      revision : u16,
+   ) -> Self {
+      return Self{
+         major    : major,
+         minor    : minor,
+         build    : build,
+         revision : revision,
+      };
+   }
+
+   /// Gets the parts of the version
+   /// from most to least significant.
+   pub const fn parts(
+      & self,
+   ) -> [u16; 4] {
+      return [self.major, self.minor, self.build, self.revision];
+   }
+}
+
+/////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Version //
+/////////////////////////////////////
+
+impl std::fmt::Display for Version {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      return write!(stream,
+         "{}.{}.{}.{}",
+         self.major,
+         self.minor,
+         self.build,
+         self.revision,
+      );
+   }
+}
+
+impl std::str::FromStr for Version {
+   type Err = VersionError;
+
+   fn from_str(
+      text : & str,
+   ) -> Result<Self> {
+      let error = || VersionError::InvalidVersion{
+         text : String::from(text),
+      };
+
+      let mut parts = [0u16; 4];
+      let mut count = 0;
+      for part in text.trim().split('.') {
+         *parts.get_mut(count).ok_or_else(error)? = part.parse().map_err(|_| error())?;
+         count += 1;
+      }
+
+      return Ok(Self::new(parts[0], parts[1], parts[2], parts[3]));
+   }
+}
+
+impl From<[u16; 4]> for Version {
+   fn from(
+      parts : [u16; 4],
+   ) -> Self {
+      return Self::new(parts[0], parts[1], parts[2], parts[3]);
+   }
+}
+
+//////////////////////////////
+// METHODS - VersionPattern //
+//////////////////////////////
+
+impl VersionPattern {
+   /// Checks whether a version matches
+   /// the pattern.
+   pub fn matches(
+      & self,
+      version : & Version,
+   ) -> bool {
+      let parts = version.parts();
+
+      for (index, part) in parts.iter().enumerate() {
+         match self.parts.get(index) {
+            Some(expected) => if expected != part {
+               return false;
+            },
+            None           => return self.wildcard == true || *part == 0,
+         }
+      }
+
+      return true;
+   }
+}
+
+////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - VersionPattern //
+////////////////////////////////////////////
+
+impl std::str::FromStr for VersionPattern {
+   type Err = VersionError;
+
+   fn from_str(
+      text : & str,
+   ) -> Result<Self> {
+      let error = || VersionError::InvalidPattern{
+         text : String::from(text),
+      };
+
+      let mut parts     = Vec::new();
+      let mut wildcard  = false;
+      for part in text.trim().split('.') {
+         if wildcard == true {
+            return Err(error());
+         }
+
+         match part {
+            "*"   => wildcard = true,
+            _     => parts.push(part.parse::<u16>().map_err(|_| error())?),
+         }
+      }
+
+      if parts.len() > 4 || (parts.len() == 4 && wildcard == true) {
+         return Err(error());
+      }
+
+      return Ok(Self{
+         parts    : parts,
+         wildcard : wildcard,
+      });
+   }
+}
+
+impl std::fmt::Display for VersionPattern {
+   fn fmt(
+      & self,
+      stream : & mut std::fmt::Formatter<'_>,
+   ) -> std::fmt::Result {
+      let mut parts = self.parts.iter().map(u16::to_string).collect::<Vec<String>>();
+      if self.wildcard == true {
+         parts.push(String::from("*"));
+      }
+
+      return write!(stream, "{}", parts.join("."));
+   }
+}
+
+///////////////////////////
+// METHODS - VersionGate //
+///////////////////////////
+
+impl VersionGate {
+   /// Creates a gate for a feature which
+   /// supports the game versions matching
+   /// any of the patterns.
+   pub const fn new(
+      feature  : &'static str,
+      patterns : &'static [&'static str],
+   ) -> Self {
+      return Self{
+         feature  : feature,
+         patterns : patterns,
+         state    : AtomicU8::new(GATE_STATE_UNCHECKED),
+      };
+   }
+
+   /// Gets the name of the feature.
+   pub fn feature(
+      & self,
+   ) -> &'static str {
+      return self.feature;
+   }
+
+   /// Checks whether the feature supports
+   /// the game's version, checking it the
+   /// first time this is called.  Invalid
+   /// patterns never match.
+   pub fn is_supported(
+      & self,
+   ) -> bool {
+      match self.state.load(Ordering::Acquire) {
+         GATE_STATE_SUPPORTED    => return true,
+         GATE_STATE_UNSUPPORTED  => return false,
+         _                       => (),
+      }
+
+      let supported = match game_version() {
+         Some(version)  => is_supported(&version, self.patterns),
+         None           => false,
+      };
+
+      // Only report the first time if more
+      // than one thread checks at once
+      let state = match supported {
+         true  => GATE_STATE_SUPPORTED,
+         false => GATE_STATE_UNSUPPORTED,
+      };
+      if self.state.swap(state, Ordering::AcqRel) == GATE_STATE_UNCHECKED && supported == false {
+         crate::log::warn!("{}", self.error());
+         if let Ok(mut features) = UNSUPPORTED_FEATURES.lock() {
+            features.push(self.feature);
+         }
+      }
+
+      return supported;
+   }
+
+   /// Creates the error describing why
+   /// the feature is disabled.
+   pub fn error(
+      & self,
+   ) -> VersionError {
+      return VersionError::UnsupportedVersion{
+         feature  : self.feature,
+         version  : game_version(),
+      };
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - Unsupported //
+/////////////////////////////////////////
+
+impl Unsupported for () {
+   fn unsupported(
+      _ : & VersionGate,
+   ) -> Self {
+      return ();
+   }
+}
+
+impl Unsupported for bool {
+   fn unsupported(
+      _ : & VersionGate,
+   ) -> Self {
+      return false;
+   }
+}
+
+impl<T> Unsupported for Option<T> {
+   fn unsupported(
+      _ : & VersionGate,
+   ) -> Self {
+      return None;
+   }
+}
+
+impl<T, E: From<VersionError>> Unsupported for std::result::Result<T, E> {
+   fn unsupported(
+      gate : & VersionGate,
+   ) -> Self {
+      return Err(E::from(gate.error()));
+   }
+}
+
+///////////////
+// FUNCTIONS //
+///////////////
+
+/// Gets the game's version, reading it
+/// from the process's main executable
+/// the first time unless it was set with
+/// <code>set_game_version</code>.
+pub fn game_version(
+) -> Option<Version> {
+   if let Ok(version) = GAME_VERSION.read() {
+      if let Some(version) = *version {
+         return version;
+      }
+   }
+
+   let detected = detect_game_version();
+   if let Ok(mut version) = GAME_VERSION.write() {
+      return *version.get_or_insert(detected);
+   }
+
+   return detected;
+}
+
+/// Overrides the game's version.  Features
+/// which were already checked keep their
+/// result, so this should be called at the
+/// start of <code>main</code>.
+pub fn set_game_version(
+   version : Version,
+) {
+   if let Ok(mut game_version) = GAME_VERSION.write() {
+      *game_version = Some(Some(version));
+   }
+   return;
+}
+
+/// Checks whether a version matches
+/// any of the patterns.  Invalid
+/// patterns never match.
+pub fn is_supported(
+   version  : & Version,
+   patterns : & [& str],
+) -> bool {
+   return patterns.iter().any(|pattern| {
+      pattern.parse::<VersionPattern>().map(|pattern| {
+         pattern.matches(version)
+      }).unwrap_or(false)
+   });
+}
+
+/// Gets the names of every feature which
+/// was disabled so far, in the order they
+/// were checked.
+pub fn unsupported_features(
+) -> Vec<&'static str> {
+   return match UNSUPPORTED_FEATURES.lock() {
+      Ok(features)   => features.clone(),
+      Err(_)         => Vec::new(),
+   };
+}
+
+//////////////////////
+// INTERNAL HELPERS //
+//////////////////////
+
+fn detect_game_version(
+) -> Option<Version> {
+   let process = crate::process::ProcessSnapshot::local().ok()?;
+   let name    = String::from(process.executable_file_name());
+   let modules = crate::process::ModuleSnapshotList::all(process).ok()?;
+
+   let module = modules.get(&name)?;
+   return unsafe{module.file_version()}.ok()?;
+}
+
@@ -0,0 +1,133 @@
+//! Find the game's main window and see
+//! the messages sent to it before the game
+//! does, such as to capture input, block
+//! the game's input while a menu is open,
+//! or respond to focus changes.
+//!
+//! ```
+//! static MENU_OPEN : std::sync::atomic::AtomicBool
+//!    = std::sync::atomic::AtomicBool::new(false);
+//!
+//! let window = nusion_core::env!().window()?;
+//!
+//! let _hook = window.add_message_hook(|message| {
+//!    use std::sync::atomic::Ordering;
+//!    use nusion_core::window::{MessageAction, WindowMessage};
+//!
+//!    if message.message == WindowMessage::KEY_DOWN && message.w_param == 0x2D {
+//!       MENU_OPEN.fetch_xor(true, Ordering::Relaxed);
+//!       return MessageAction::Handled(0);
+//!    }
+//!
+//!    if message.is_input() == true && MENU_OPEN.load(Ordering::Relaxed) == true {
+//!       return MessageAction::Handled(0);
+//!    }
+//!
+//!    return MessageAction::Forward;
+//! })?;
+//! ```
+//!
+//! Hooks run on the window's own thread
+//! from within its window procedure, so
+//! they should return quickly.  Games which
+//! read input through DirectInput or by
+//! polling key state don't see window
+//! messages for it, so blocking messages
+//! won't stop that input.  Hooking window
+//! messages is only supported on Windows.
+
+// Messages and errors are provided by
+// the system abstraction layer as-is
+pub use crate::sys::window::{
+   WindowError,
+   Result,
+   WindowMessage,
+   MessageAction,
+   MessageCallback,
+};
+
+//////////////////////
+// TYPE DEFINITIONS //
+//////////////////////
+
+/// The game's main window.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Window {
+   window : crate::sys::window::Window,
+}
+
+/// A function registered to see the
+/// messages sent to a window.  The
+/// function is removed when this is
+/// dropped, and the window procedure is
+/// put back once no hooks are left for
+/// the window.
+pub struct MessageHook {
+   id : usize,
+}
+
+//////////////////////
+// METHODS - Window //
+//////////////////////
+
+impl Window {
+   /// Finds the main window of the current
+   /// process, which is the largest visible
+   /// window it owns.  This fails if the game
+   /// hasn't created its window yet.
+   pub fn find(
+   ) -> Result<Self> {
+      return Ok(Self{
+         window : crate::sys::window::Window::find_process_window()?,
+      });
+   }
+
+   /// Gets the raw handle of the window,
+   /// which is an <code>HWND</code> on
+   /// Windows.
+   pub fn handle(
+      & self,
+   ) -> usize {
+      return self.window.handle();
+   }
+
+   /// Checks whether the window is the
+   /// foreground window, meaning it has
+   /// the user's input focus.
+   pub fn is_foreground(
+      & self,
+   ) -> bool {
+      return self.window.is_foreground();
+   }
+
+   /// Registers a function which is called
+   /// for every message sent to the window
+   /// until the returned hook is dropped.
+   /// Functions run in the order they were
+   /// registered, and the first to handle
+   /// a message stops it from reaching the
+   /// rest and the game.
+   pub fn add_message_hook<F>(
+      & self,
+      callback : F,
+   ) -> Result<MessageHook>
+   where F: Fn(& WindowMessage) -> MessageAction + Send + Sync + 'static,
+   {
+      return Ok(MessageHook{
+         id : self.window.add_message_hook(std::sync::Arc::new(callback))?,
+      });
+   }
+}
+
+/////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS - MessageHook //
+/////////////////////////////////////////
+
+impl std::ops::Drop for MessageHook {
+   fn drop(
+      & mut self,
+   ) {
+      crate::sys::window::remove_message_hook(self.id);
+      return;
+   }
+}